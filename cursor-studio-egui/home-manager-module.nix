@@ -25,6 +25,7 @@ let
     font_scale = cfg.ui.fontScale;
     message_spacing = cfg.ui.messageSpacing;
     status_bar_font_size = cfg.ui.statusBarFontSize;
+    min_text_size = cfg.ui.minTextSize;
 
     # Display Preferences
     display_prefs = map (p: {
@@ -39,6 +40,12 @@ let
       npm_scanning = cfg.security.npmScanning;
       sensitive_data_scan = cfg.security.sensitiveDataScan;
       blocklist_path = cfg.security.blocklistPath;
+      severity = {
+        password = cfg.security.severity.password;
+        cloud_key = cfg.security.severity.cloudKey;
+        api_key = cfg.security.severity.apiKey;
+        secret = cfg.security.severity.secret;
+      };
     };
 
     # Export Settings
@@ -58,6 +65,7 @@ let
 
     # Paths
     cursor_data_dir = cfg.cursorDataDir;
+    cache_dir = cfg.cacheDir;
   });
 
 in
@@ -82,6 +90,16 @@ in
       '';
     };
 
+    cacheDir = mkOption {
+      type = types.nullOr types.str;
+      default = null;
+      example = "/mnt/scratch/cursor-studio";
+      description = ''
+        Directory for downloaded Cursor AppImages, kept separate from the
+        data directory. Null uses the default cache location.
+      '';
+    };
+
     # UI Configuration
     ui = {
       theme = mkOption {
@@ -107,6 +125,12 @@ in
         default = 12.0;
         description = "Font size for the status bar.";
       };
+
+      minTextSize = mkOption {
+        type = types.float;
+        default = 8.0;
+        description = "Smallest size (8-12) that captions and other small labels are drawn at.";
+      };
     };
 
     # Display Preferences per content type
@@ -170,6 +194,32 @@ in
           If null, uses the embedded blocklist.
         '';
       };
+
+      severity = {
+        password = mkOption {
+          type = types.enum [ "high" "medium" "low" ];
+          default = "high";
+          description = "Severity of passwords found in chat history.";
+        };
+
+        cloudKey = mkOption {
+          type = types.enum [ "high" "medium" "low" ];
+          default = "high";
+          description = "Severity of cloud provider credentials (AWS, GitHub, Slack, ...).";
+        };
+
+        apiKey = mkOption {
+          type = types.enum [ "high" "medium" "low" ];
+          default = "medium";
+          description = "Severity of other API keys and tokens.";
+        };
+
+        secret = mkOption {
+          type = types.enum [ "high" "medium" "low" ];
+          default = "medium";
+          description = "Severity of generic secrets and access tokens.";
+        };
+      };
     };
 
     # Export Settings
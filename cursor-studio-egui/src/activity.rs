@@ -0,0 +1,180 @@
+//! Time bucketing for the dashboard activity chart
+
+use crate::database::DayActivity;
+use chrono::{Datelike, Duration, NaiveDate};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Bucket {
+    Day,
+    #[default]
+    Week,
+    Month,
+}
+
+impl Bucket {
+    pub const ALL: [Bucket; 3] = [Self::Day, Self::Week, Self::Month];
+
+    pub fn from_key(key: &str) -> Self {
+        match key {
+            "day" => Self::Day,
+            "month" => Self::Month,
+            _ => Self::Week,
+        }
+    }
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Day => "day",
+            Self::Week => "week",
+            Self::Month => "month",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Day => "Day",
+            Self::Week => "Week",
+            Self::Month => "Month",
+        }
+    }
+
+    /// How many buckets the chart shows
+    pub fn span(&self) -> usize {
+        match self {
+            Self::Day => 30,
+            Self::Week => 26,
+            Self::Month => 24,
+        }
+    }
+
+    /// First day of the bucket holding `date`; weeks start on Monday
+    fn start(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Day => date,
+            Self::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+            Self::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    fn previous(&self, start: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Day => start - Duration::days(1),
+            Self::Week => start - Duration::weeks(1),
+            Self::Month => self.start(start - Duration::days(1)),
+        }
+    }
+
+    /// Axis label for a bucket starting at `start`
+    pub fn format(&self, start: NaiveDate) -> String {
+        match self {
+            Self::Day | Self::Week => start.format("%b %-d").to_string(),
+            Self::Month => start.format("%b %Y").to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActivityBucket {
+    pub start: NaiveDate,
+    pub conversations: usize,
+    pub messages: usize,
+}
+
+/// The last `bucket.span()` buckets up to `today`, with empty ones filled in.
+///
+/// Returns nothing when no activity falls inside the window.
+pub fn bucket_activity(
+    days: &[DayActivity],
+    bucket: Bucket,
+    today: NaiveDate,
+) -> Vec<ActivityBucket> {
+    let mut starts = vec![bucket.start(today)];
+    while starts.len() < bucket.span() {
+        let prev = bucket.previous(starts[starts.len() - 1]);
+        starts.push(prev);
+    }
+    starts.reverse();
+
+    let mut buckets: Vec<ActivityBucket> = starts
+        .into_iter()
+        .map(|start| ActivityBucket {
+            start,
+            conversations: 0,
+            messages: 0,
+        })
+        .collect();
+
+    for day in days {
+        let Ok(date) = NaiveDate::parse_from_str(&day.day, "%Y-%m-%d") else {
+            continue;
+        };
+        let start = bucket.start(date);
+        if let Ok(i) = buckets.binary_search_by_key(&start, |b| b.start) {
+            buckets[i].conversations += day.conversations;
+            buckets[i].messages += day.messages;
+        }
+    }
+
+    if buckets
+        .iter()
+        .all(|b| b.conversations == 0 && b.messages == 0)
+    {
+        return Vec::new();
+    }
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(day: &str, conversations: usize, messages: usize) -> DayActivity {
+        DayActivity {
+            day: day.to_string(),
+            conversations,
+            messages,
+        }
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_weekly_buckets() {
+        // 2024-05-06 is a Monday
+        let days = [
+            day("2024-05-06", 1, 4),
+            day("2024-05-12", 2, 6),
+            day("2024-05-13", 1, 1),
+            day("not a date", 9, 9),
+        ];
+        let buckets = bucket_activity(&days, Bucket::Week, date("2024-05-15"));
+        assert_eq!(buckets.len(), 26);
+
+        let last = &buckets[25];
+        assert_eq!(
+            (last.start, last.conversations, last.messages),
+            (date("2024-05-13"), 1, 1)
+        );
+        let prev = &buckets[24];
+        assert_eq!(
+            (prev.start, prev.conversations, prev.messages),
+            (date("2024-05-06"), 3, 10)
+        );
+        assert_eq!(buckets[23].conversations, 0);
+    }
+
+    #[test]
+    fn test_monthly_buckets_and_empty_window() {
+        let days = [day("2024-01-31", 1, 2), day("2024-03-01", 1, 3)];
+        let buckets = bucket_activity(&days, Bucket::Month, date("2024-03-20"));
+        assert_eq!(buckets[23].start, date("2024-03-01"));
+        assert_eq!(buckets[22].start, date("2024-02-01"));
+        assert_eq!(buckets[21].messages, 2);
+
+        // Activity older than the window leaves nothing to chart
+        assert!(bucket_activity(&days, Bucket::Day, date("2024-06-01")).is_empty());
+        assert!(bucket_activity(&[], Bucket::Week, date("2024-06-01")).is_empty());
+    }
+}
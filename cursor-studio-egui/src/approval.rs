@@ -14,12 +14,24 @@ pub enum ApprovalOperation {
         version: String,
         size_estimate: Option<u64>,
     },
+    /// Download several Cursor versions in one batch
+    DownloadMultiple {
+        versions: Vec<String>,
+        size_estimate: Option<u64>,
+    },
     /// Install a version
     Install { version: String, path: String },
     /// Clear and reimport all data
     ClearAndReimport { conversation_count: usize },
     /// Delete a version
     DeleteVersion { version: String },
+    /// Delete conversations from the chat library
+    DeleteConversations { count: usize },
+    /// Rewrite message text with a search-and-replace
+    ReplaceInMessages {
+        messages: usize,
+        conversations: usize,
+    },
     /// Export data
     Export { format: String, path: String },
     /// Custom operation
@@ -33,6 +45,9 @@ impl ApprovalOperation {
             ApprovalOperation::Download { version, .. } => {
                 format!("Download Cursor v{}", version)
             }
+            ApprovalOperation::DownloadMultiple { versions, .. } => {
+                format!("Download {} Cursor Versions", versions.len())
+            }
             ApprovalOperation::Install { version, .. } => {
                 format!("Install Cursor v{}", version)
             }
@@ -40,6 +55,12 @@ impl ApprovalOperation {
             ApprovalOperation::DeleteVersion { version } => {
                 format!("Delete Cursor v{}", version)
             }
+            ApprovalOperation::DeleteConversations { count } => {
+                format!("Delete {} Conversations", count)
+            }
+            ApprovalOperation::ReplaceInMessages { messages, .. } => {
+                format!("Replace Text in {} Messages", messages)
+            }
             ApprovalOperation::Export { format, .. } => {
                 format!("Export to {}", format)
             }
@@ -62,6 +83,19 @@ impl ApprovalOperation {
                     version, size_str
                 )
             }
+            ApprovalOperation::DownloadMultiple {
+                versions,
+                size_estimate,
+            } => {
+                let size_str = size_estimate
+                    .map(|s| format!(" (~{} MB total)", s / 1024 / 1024))
+                    .unwrap_or_default();
+                format!(
+                    "Download Cursor IDE versions {}{}.\nThey will be downloaded one at a time into the cache directory.",
+                    versions.join(", "),
+                    size_str
+                )
+            }
             ApprovalOperation::Install { version, path } => {
                 format!(
                     "Install Cursor IDE version {} from:\n  {}\n\nThe AppImage will be made executable and registered.",
@@ -80,6 +114,21 @@ impl ApprovalOperation {
                     version
                 )
             }
+            ApprovalOperation::DeleteConversations { count } => {
+                format!(
+                    "Delete {} conversations, their messages and bookmarks from the library.\n\n⚠️ Deletion is permanent: later imports skip them. Only a full Reimport brings them back if they still exist in Cursor.",
+                    count
+                )
+            }
+            ApprovalOperation::ReplaceInMessages {
+                messages,
+                conversations,
+            } => {
+                format!(
+                    "Rewrite the text of {} messages in {} conversations.\n\nThe original text is backed up and can be restored with Undo.",
+                    messages, conversations
+                )
+            }
             ApprovalOperation::Export { format, path } => {
                 format!("Export data to {} format at:\n  {}", format, path)
             }
@@ -91,7 +140,10 @@ impl ApprovalOperation {
     pub fn is_destructive(&self) -> bool {
         matches!(
             self,
-            ApprovalOperation::ClearAndReimport { .. } | ApprovalOperation::DeleteVersion { .. }
+            ApprovalOperation::ClearAndReimport { .. }
+                | ApprovalOperation::DeleteVersion { .. }
+                | ApprovalOperation::DeleteConversations { .. }
+                | ApprovalOperation::ReplaceInMessages { .. }
         )
     }
 }
@@ -439,6 +491,13 @@ mod tests {
             size_estimate: Some(150_000_000),
         };
         assert!(op.title().contains("2.1.34"));
+
+        let op = ApprovalOperation::DownloadMultiple {
+            versions: vec!["2.1.34".into(), "2.1.32".into()],
+            size_estimate: Some(300 * 1024 * 1024),
+        };
+        assert_eq!(op.title(), "Download 2 Cursor Versions");
+        assert!(op.description().contains("~300 MB total"));
     }
 
     #[test]
@@ -449,6 +508,12 @@ mod tests {
             }
             .is_destructive()
         );
+        assert!(ApprovalOperation::DeleteConversations { count: 3 }.is_destructive());
+        assert!(ApprovalOperation::ReplaceInMessages {
+            messages: 4,
+            conversations: 2
+        }
+        .is_destructive());
         assert!(!ApprovalOperation::Download {
             version: "1.0".into(),
             size_estimate: None
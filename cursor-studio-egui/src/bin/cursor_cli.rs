@@ -16,6 +16,7 @@ use cursor_studio::version_registry::{
 };
 use cursor_studio::versions::{
     get_available_versions, get_cache_dir, get_version_info, install_version, is_version_installed,
+    set_cache_dir, validate_cache_dir,
 };
 
 static CHECK: Emoji<'_, '_> = Emoji("✓ ", "+ ");
@@ -59,6 +60,10 @@ pub struct Cli {
     /// Skip confirmation prompts (auto-approve)
     #[arg(short = 'y', long, global = true)]
     pub yes: bool,
+
+    /// Directory for downloaded AppImages (default: ~/.cache/cursor-studio/downloads)
+    #[arg(long, global = true, value_name = "DIR")]
+    pub cache_dir: Option<PathBuf>,
 }
 
 #[derive(Subcommand)]
@@ -96,6 +101,10 @@ pub enum Commands {
         /// Don't set as default after installing
         #[arg(long)]
         no_default: bool,
+
+        /// Also add a launcher entry to ~/.local/share/applications
+        #[arg(long)]
+        desktop_entry: bool,
     },
 
     /// Show information about a version
@@ -151,13 +160,13 @@ pub enum Commands {
         dry_run: bool,
     },
 
-    /// Import a manually downloaded file
+    /// Import a manually downloaded file: verify, cache and install it
     Import {
         /// Path to the downloaded file (AppImage or DMG)
         file: PathBuf,
 
-        /// Version number (e.g., "2.1.34")
-        #[arg(short, long)]
+        /// Version number (e.g., "2.1.34"); long-only since -v is --verbose
+        #[arg(long)]
         version: String,
 
         /// Platform (auto-detected from filename if not specified)
@@ -167,6 +176,10 @@ pub enum Commands {
         /// Update the hash registry after import
         #[arg(long)]
         update_registry: bool,
+
+        /// Import even if the file doesn't match the known hash
+        #[arg(long)]
+        skip_verify: bool,
     },
 
     /// Show download URLs for manual download
@@ -203,6 +216,11 @@ fn main() -> Result<()> {
 
     let cli = Cli::parse();
 
+    if let Some(dir) = &cli.cache_dir {
+        validate_cache_dir(dir)?;
+        set_cache_dir(Some(dir.clone()));
+    }
+
     // Set up approval mode based on --yes flag
     let approval_mode = if cli.yes {
         ApprovalMode::AutoApprove
@@ -221,7 +239,8 @@ fn main() -> Result<()> {
         Commands::Install {
             version,
             no_default,
-        } => cmd_install(&version, no_default, &mut approval),
+            desktop_entry,
+        } => cmd_install(&version, no_default, desktop_entry, &mut approval),
         Commands::Info { version } => cmd_info(&version),
         Commands::Clean {
             older_than,
@@ -240,7 +259,14 @@ fn main() -> Result<()> {
             version,
             platform,
             update_registry,
-        } => cmd_import(&file, &version, platform.as_deref(), update_registry),
+            skip_verify,
+        } => cmd_import(
+            &file,
+            &version,
+            platform.as_deref(),
+            update_registry,
+            skip_verify,
+        ),
         Commands::Urls { version, all } => cmd_urls(&version, all),
         Commands::ExportRegistry { output } => cmd_export_registry(output.as_ref()),
         Commands::ImportRegistry { file, merge } => cmd_import_registry(&file, merge),
@@ -489,7 +515,12 @@ fn cmd_download(
 }
 
 /// Install a version
-fn cmd_install(version: &str, no_default: bool, approval: &mut ApprovalManager) -> Result<()> {
+fn cmd_install(
+    version: &str,
+    no_default: bool,
+    desktop_entry: bool,
+    approval: &mut ApprovalManager,
+) -> Result<()> {
     // First download
     cmd_download(version, false, false, approval)?;
 
@@ -522,7 +553,7 @@ fn cmd_install(version: &str, no_default: bool, approval: &mut ApprovalManager)
 
     println!("{} Installing v{}...", ARROW, style(&version_str).cyan());
 
-    match install_version(&cached_path, &version_str) {
+    match install_version(&cached_path, &version_str, desktop_entry) {
         Ok(install_path) => {
             println!(
                 "{} Installed to {}",
@@ -1099,6 +1130,7 @@ fn cmd_import(
     version: &str,
     platform_str: Option<&str>,
     update_registry: bool,
+    skip_verify: bool,
 ) -> Result<()> {
     println!();
     println!("{}", style("Manual Import").bold().underlined());
@@ -1150,10 +1182,62 @@ fn cmd_import(
     // Perform import
     let (dest_path, hash) = ManualImport::import(file, version, platform)?;
 
+    // The known hash is for the Linux x64 AppImage
+    let expected = get_version_info(version)
+        .and_then(|info| info.sha256_hash)
+        .filter(|_| platform == Platform::LinuxX64);
+    match &expected {
+        Some(expected) if *expected != hash => {
+            if skip_verify {
+                println!(
+                    "  {} Hash mismatch ignored (--skip-verify)",
+                    style("⚠").yellow()
+                );
+            } else {
+                let _ = std::fs::remove_file(&dest_path);
+                println!("  {} HASH MISMATCH - file not imported", CROSS);
+                println!("  Expected: {}", style(expected).green());
+                println!("  Computed: {}", style(&hash).red());
+                println!("  The file is corrupted or not Cursor v{}.", version);
+                println!("  Use --skip-verify to import it anyway.");
+                anyhow::bail!("Hash mismatch for v{}", version);
+            }
+        }
+        Some(_) => println!("  {} Hash verified", CHECK),
+        None => println!(
+            "  {} No known hash for v{}, not verified",
+            style("⚠").yellow(),
+            version
+        ),
+    }
+
     println!("  {} Imported successfully!", CHECK);
-    println!("  Destination: {}", style(dest_path.display()).dim());
+    println!("  Cached:      {}", style(dest_path.display()).dim());
     println!("  SHA256:      {}", style(&hash).cyan());
 
+    // Only AppImages for this machine can be installed
+    if platform == Platform::current() && dest_path.extension().is_some_and(|e| e == "AppImage") {
+        match install_version(&dest_path, version, false) {
+            Ok(install_path) => {
+                println!(
+                    "  {} Installed to {}",
+                    CHECK,
+                    style(install_path.display()).green()
+                );
+            }
+            Err(e) => {
+                println!("  {} Installation failed: {}", CROSS, style(&e).red());
+                anyhow::bail!("{}", e);
+            }
+        }
+    } else {
+        println!(
+            "  {} Not installed: {} builds don't run on this machine",
+            INFO,
+            platform.display_name()
+        );
+    }
+
     // Update registry if requested
     if update_registry {
         println!();
@@ -0,0 +1,251 @@
+//! Branch structure of chats where a message was edited and regenerated
+//!
+//! Messages without a recorded parent follow the message before them, so a
+//! linear conversation has no branch points and is shown unchanged.
+
+use crate::database::Message;
+use std::collections::{BTreeMap, HashMap};
+
+/// Which branch is shown at each branch point, keyed by the parent message id
+pub type BranchChoices = HashMap<String, usize>;
+
+/// A message with more than one continuation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchPoint {
+    /// Index of the message the branches start from
+    pub parent: usize,
+    /// Index of the first message of each branch, in branch order
+    pub children: Vec<usize>,
+    /// Enclosing branch point and branch position, for nested edits
+    pub owner: Option<(usize, usize)>,
+}
+
+/// Parent links and branch points for one conversation's messages
+#[derive(Debug, Clone, Default)]
+pub struct ConversationTree {
+    parents: Vec<Option<usize>>,
+    pub points: Vec<BranchPoint>,
+}
+
+impl ConversationTree {
+    pub fn new(messages: &[Message]) -> Self {
+        let index: HashMap<&str, usize> = messages
+            .iter()
+            .enumerate()
+            .map(|(i, m)| (m.id.as_str(), i))
+            .collect();
+
+        // Only earlier messages can be parents, which keeps bad data acyclic
+        let parents: Vec<Option<usize>> = messages
+            .iter()
+            .enumerate()
+            .map(
+                |(i, m)| match m.parent_id.as_deref().and_then(|id| index.get(id)) {
+                    Some(&p) if p < i => Some(p),
+                    _ => i.checked_sub(1),
+                },
+            )
+            .collect();
+
+        let mut children: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for (i, parent) in parents.iter().enumerate() {
+            if let Some(p) = parent {
+                children.entry(*p).or_default().push(i);
+            }
+        }
+
+        let mut points: Vec<BranchPoint> = children
+            .into_iter()
+            .filter(|(_, kids)| kids.len() > 1)
+            .map(|(parent, mut kids)| {
+                kids.sort_by_key(|&c| (messages[c].branch, c));
+                BranchPoint {
+                    parent,
+                    children: kids,
+                    owner: None,
+                }
+            })
+            .collect();
+
+        let heads = branch_heads(&points);
+        for point in &mut points {
+            let mut node = Some(point.parent);
+            while let Some(n) = node {
+                if let Some(&owner) = heads.get(&n) {
+                    point.owner = Some(owner);
+                    break;
+                }
+                node = parents[n];
+            }
+        }
+
+        Self { parents, points }
+    }
+
+    /// True when no message has more than one continuation
+    pub fn is_linear(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Branch shown at `point`, defaulting to the newest
+    pub fn chosen(&self, messages: &[Message], point: usize, choices: &BranchChoices) -> usize {
+        let p = &self.points[point];
+        choices
+            .get(&messages[p.parent].id)
+            .copied()
+            .filter(|&pos| pos < p.children.len())
+            .unwrap_or(p.children.len() - 1)
+    }
+
+    /// Indices of the messages on the chosen path, in order
+    pub fn visible(&self, messages: &[Message], choices: &BranchChoices) -> Vec<usize> {
+        let mut hidden = vec![false; messages.len()];
+        for (i, p) in self.points.iter().enumerate() {
+            let chosen = self.chosen(messages, i, choices);
+            for (pos, &child) in p.children.iter().enumerate() {
+                hidden[child] = pos != chosen;
+            }
+        }
+        // Parents always come first, so one pass hides whole branches
+        for (i, parent) in self.parents.iter().enumerate() {
+            if let Some(p) = *parent {
+                hidden[i] |= hidden[p];
+            }
+        }
+        (0..messages.len()).filter(|&i| !hidden[i]).collect()
+    }
+
+    /// Choose the branches needed to show the message at `index`
+    pub fn reveal(&self, messages: &[Message], choices: &mut BranchChoices, index: usize) {
+        let heads = branch_heads(&self.points);
+        let mut node = Some(index);
+        while let Some(n) = node {
+            if let Some(&(point, pos)) = heads.get(&n) {
+                choices.insert(messages[self.points[point].parent].id.clone(), pos);
+            }
+            node = self.parents.get(n).copied().flatten();
+        }
+    }
+
+    /// Branch point and position for each message that starts a branch
+    pub fn heads(&self) -> HashMap<usize, (usize, usize)> {
+        branch_heads(&self.points)
+    }
+
+    /// Branch points in tree order, as (depth, point index)
+    pub fn outline(&self) -> Vec<(usize, usize)> {
+        let mut rows = Vec::new();
+        self.push_outline(None, 0, &mut rows);
+        rows
+    }
+
+    fn push_outline(&self, owner: Option<usize>, depth: usize, rows: &mut Vec<(usize, usize)>) {
+        let mut nested: Vec<usize> = (0..self.points.len())
+            .filter(|&i| self.points[i].owner.map(|(point, _)| point) == owner)
+            .collect();
+        nested.sort_by_key(|&i| (self.points[i].owner, self.points[i].parent));
+        for i in nested {
+            rows.push((depth, i));
+            self.push_outline(Some(i), depth + 1, rows);
+        }
+    }
+}
+
+fn branch_heads(points: &[BranchPoint]) -> HashMap<usize, (usize, usize)> {
+    points
+        .iter()
+        .enumerate()
+        .flat_map(|(point, p)| {
+            p.children
+                .iter()
+                .enumerate()
+                .map(move |(pos, &child)| (child, (point, pos)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{ContentType, MessageRole};
+
+    fn msg(id: &str, parent: Option<&str>, branch: usize) -> Message {
+        Message {
+            id: id.to_string(),
+            conversation_id: "conv".to_string(),
+            sequence: 0,
+            role: MessageRole::User,
+            content: id.to_string(),
+            tool_call: None,
+            thinking: None,
+            content_type: ContentType::Text,
+            has_code_blocks: false,
+            has_terminal_output: false,
+            files_edited: Vec::new(),
+            created_at: None,
+            parent_id: parent.map(str::to_string),
+            branch,
+            tool_call_id: None,
+        }
+    }
+
+    fn ids(messages: &[Message], indices: &[usize]) -> Vec<String> {
+        indices.iter().map(|&i| messages[i].id.clone()).collect()
+    }
+
+    #[test]
+    fn test_linear_conversation_is_unchanged() {
+        let messages = vec![msg("u1", None, 0), msg("a1", None, 0), msg("u2", None, 0)];
+        let tree = ConversationTree::new(&messages);
+        assert!(tree.is_linear());
+        assert_eq!(
+            tree.visible(&messages, &BranchChoices::new()),
+            vec![0, 1, 2]
+        );
+    }
+
+    #[test]
+    fn test_branch_selection() {
+        // u2 was edited into u2b; each edit got its own reply
+        let messages = vec![
+            msg("u1", None, 0),
+            msg("a1", None, 0),
+            msg("u2", Some("a1"), 0),
+            msg("a2", None, 0),
+            msg("u2b", Some("a1"), 1),
+            msg("a2b", None, 0),
+        ];
+        let tree = ConversationTree::new(&messages);
+        assert_eq!(tree.points.len(), 1);
+        assert_eq!(tree.points[0].children, vec![2, 4]);
+
+        let mut choices = BranchChoices::new();
+        let newest = tree.visible(&messages, &choices);
+        assert_eq!(ids(&messages, &newest), ["u1", "a1", "u2b", "a2b"]);
+
+        tree.reveal(&messages, &mut choices, 3);
+        let first = tree.visible(&messages, &choices);
+        assert_eq!(ids(&messages, &first), ["u1", "a1", "u2", "a2"]);
+    }
+
+    #[test]
+    fn test_nested_branches_outline() {
+        let messages = vec![
+            msg("u1", None, 0),
+            msg("a1", Some("u1"), 0),
+            msg("a1b", Some("u1"), 1),
+            msg("u2", None, 0),
+            msg("a2", Some("u2"), 0),
+            msg("a2b", Some("u2"), 1),
+        ];
+        let tree = ConversationTree::new(&messages);
+        assert_eq!(tree.points.len(), 2);
+        assert_eq!(tree.points[1].owner, Some((0, 1)));
+        assert_eq!(tree.outline(), vec![(0, 0), (1, 1)]);
+
+        let mut choices = BranchChoices::new();
+        tree.reveal(&messages, &mut choices, 4);
+        let shown = tree.visible(&messages, &choices);
+        assert_eq!(ids(&messages, &shown), ["u1", "a1b", "u2", "a2"]);
+    }
+}
@@ -0,0 +1,328 @@
+//! Conversation exports from other AI chat apps
+//!
+//! Both ChatGPT and Claude ship a `conversations.json` in their data export.
+//! ChatGPT stores each chat as a tree of edits and regenerations; only the path
+//! to the message the user last saw is kept, matching the linear library model.
+
+use crate::database::MessageRole;
+use anyhow::{bail, Result};
+use serde_json::Value;
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportSource {
+    ChatGpt,
+    Claude,
+}
+
+impl ExportSource {
+    /// Shown as the conversation's source version
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportSource::ChatGpt => "ChatGPT",
+            ExportSource::Claude => "Claude",
+        }
+    }
+
+    /// Tag added to every imported conversation
+    pub fn tag(self) -> &'static str {
+        match self {
+            ExportSource::ChatGpt => "chatgpt",
+            ExportSource::Claude => "claude",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportedMessage {
+    pub id: String,
+    pub role: MessageRole,
+    pub content: String,
+    /// RFC 3339 UTC, when the export carried a time
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportedConversation {
+    /// Source id prefixed with the source tag, so it can't clash with Cursor ids
+    pub id: String,
+    pub title: String,
+    pub messages: Vec<ExportedMessage>,
+}
+
+/// Parse a `conversations.json` from either app, detecting which one it is
+pub fn parse_export(text: &str) -> Result<(ExportSource, Vec<ExportedConversation>)> {
+    let data: Value = serde_json::from_str(text)?;
+    let Some(items) = data.as_array() else {
+        bail!("Expected a list of conversations");
+    };
+
+    let source = if items.iter().any(|c| c.get("mapping").is_some()) {
+        ExportSource::ChatGpt
+    } else if items.iter().any(|c| c.get("chat_messages").is_some()) {
+        ExportSource::Claude
+    } else {
+        bail!("Not a ChatGPT or Claude conversations export");
+    };
+
+    let conversations = items
+        .iter()
+        .filter_map(|c| match source {
+            ExportSource::ChatGpt => parse_chatgpt_conversation(c),
+            ExportSource::Claude => parse_claude_conversation(c),
+        })
+        .filter(|c| !c.messages.is_empty())
+        .collect();
+    Ok((source, conversations))
+}
+
+fn parse_chatgpt_conversation(conv: &Value) -> Option<ExportedConversation> {
+    let conv_id = str_field(conv, "conversation_id").or_else(|| str_field(conv, "id"))?;
+    let mapping = conv.get("mapping")?.as_object()?;
+
+    // Walk up from the current node, falling back to the newest leaf
+    let leaf = str_field(conv, "current_node")
+        .filter(|id| mapping.contains_key(*id))
+        .or_else(|| {
+            mapping
+                .iter()
+                .filter(|(_, node)| {
+                    let children = node.get("children").and_then(|c| c.as_array());
+                    !children.is_some_and(|c| !c.is_empty())
+                })
+                .max_by(|(_, a), (_, b)| node_time(a).total_cmp(&node_time(b)))
+                .map(|(id, _)| id.as_str())
+        })?;
+
+    let mut path = Vec::new();
+    let mut seen = HashSet::new();
+    let mut node_id = Some(leaf);
+    while let Some(id) = node_id {
+        // A malformed export could loop; stop at the first repeat
+        if !seen.insert(id) {
+            break;
+        }
+        let Some(node) = mapping.get(id) else {
+            break;
+        };
+        path.push(node);
+        node_id = str_field(node, "parent");
+    }
+    path.reverse();
+
+    let messages = path
+        .into_iter()
+        .filter_map(|node| {
+            let msg = node.get("message")?;
+            let role = match msg.pointer("/author/role")?.as_str()? {
+                "user" => MessageRole::User,
+                "assistant" => MessageRole::Assistant,
+                // System prompts and tool plumbing aren't part of the chat
+                _ => return None,
+            };
+            let content = chatgpt_text(msg.get("content")?);
+            if content.trim().is_empty() {
+                return None;
+            }
+            let id = str_field(msg, "id").or(str_field(node, "id"))?;
+            Some(ExportedMessage {
+                id: format!("chatgpt-{}", id),
+                role,
+                content,
+                created_at: msg.get("create_time").and_then(epoch_seconds),
+            })
+        })
+        .collect();
+
+    Some(ExportedConversation {
+        id: format!("chatgpt-{}", conv_id),
+        title: title_or_untitled(str_field(conv, "title")),
+        messages,
+    })
+}
+
+fn node_time(node: &Value) -> f64 {
+    let time = node
+        .pointer("/message/create_time")
+        .and_then(|t| t.as_f64());
+    time.unwrap_or(0.0)
+}
+
+/// Text of a ChatGPT message: string `parts` for chat, `text` for code
+fn chatgpt_text(content: &Value) -> String {
+    if let Some(parts) = content.get("parts").and_then(|p| p.as_array()) {
+        // Non-string parts are attachments such as images
+        let texts: Vec<&str> = parts.iter().filter_map(|p| p.as_str()).collect();
+        return texts.join("\n");
+    }
+    str_field(content, "text").unwrap_or_default().to_string()
+}
+
+fn parse_claude_conversation(conv: &Value) -> Option<ExportedConversation> {
+    let conv_id = str_field(conv, "uuid")?;
+    let messages = conv
+        .get("chat_messages")?
+        .as_array()?
+        .iter()
+        .enumerate()
+        .filter_map(|(i, msg)| {
+            let role = match str_field(msg, "sender")? {
+                "human" => MessageRole::User,
+                "assistant" => MessageRole::Assistant,
+                _ => return None,
+            };
+            let content = claude_text(msg);
+            if content.trim().is_empty() {
+                return None;
+            }
+            let id = match str_field(msg, "uuid") {
+                Some(id) => format!("claude-{}", id),
+                None => format!("claude-{}:{}", conv_id, i),
+            };
+            Some(ExportedMessage {
+                id,
+                role,
+                content,
+                created_at: str_field(msg, "created_at").and_then(rfc3339_utc),
+            })
+        })
+        .collect();
+
+    Some(ExportedConversation {
+        id: format!("claude-{}", conv_id),
+        title: title_or_untitled(str_field(conv, "name")),
+        messages,
+    })
+}
+
+/// Text of a Claude message: `text`, or the text blocks of `content`
+fn claude_text(msg: &Value) -> String {
+    if let Some(text) = str_field(msg, "text").filter(|t| !t.trim().is_empty()) {
+        return text.to_string();
+    }
+    let blocks = msg.get("content").and_then(|c| c.as_array());
+    let texts: Vec<&str> = blocks
+        .into_iter()
+        .flatten()
+        .filter(|b| str_field(b, "type") == Some("text"))
+        .filter_map(|b| str_field(b, "text"))
+        .collect();
+    texts.join("\n\n")
+}
+
+fn str_field<'a>(value: &'a Value, field: &str) -> Option<&'a str> {
+    value.get(field).and_then(|v| v.as_str())
+}
+
+fn title_or_untitled(title: Option<&str>) -> String {
+    match title.map(str::trim) {
+        Some(t) if !t.is_empty() => t.to_string(),
+        _ => "Untitled".to_string(),
+    }
+}
+
+fn epoch_seconds(value: &Value) -> Option<String> {
+    let secs = value.as_f64()?;
+    let dt = chrono::DateTime::from_timestamp_millis((secs * 1000.0) as i64)?;
+    Some(dt.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+}
+
+fn rfc3339_utc(text: &str) -> Option<String> {
+    let dt = chrono::DateTime::parse_from_rfc3339(text).ok()?;
+    Some(
+        dt.with_timezone(&chrono::Utc)
+            .to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chatgpt_export_follows_current_branch() {
+        // The user edited their first message; the edit is the current branch
+        let export = serde_json::json!([{
+            "id": "c1",
+            "title": "Rust help",
+            "current_node": "a2",
+            "mapping": {
+                "root": {"id": "root", "message": null, "parent": null, "children": ["sys"]},
+                "sys": {
+                    "id": "sys",
+                    "message": {"id": "sys", "author": {"role": "system"},
+                                "content": {"content_type": "text", "parts": [""]}},
+                    "parent": "root", "children": ["u1", "u2"]
+                },
+                "u1": {
+                    "id": "u1",
+                    "message": {"id": "u1", "author": {"role": "user"}, "create_time": 1714566600.0,
+                                "content": {"content_type": "text", "parts": ["old question"]}},
+                    "parent": "sys", "children": ["a1"]
+                },
+                "a1": {
+                    "id": "a1",
+                    "message": {"id": "a1", "author": {"role": "assistant"},
+                                "content": {"content_type": "text", "parts": ["old answer"]}},
+                    "parent": "u1", "children": []
+                },
+                "u2": {
+                    "id": "u2",
+                    "message": {"id": "u2", "author": {"role": "user"},
+                                "content": {"content_type": "text", "parts": ["new question"]}},
+                    "parent": "sys", "children": ["a2"]
+                },
+                "a2": {
+                    "id": "a2",
+                    "message": {"id": "a2", "author": {"role": "assistant"},
+                                "content": {"content_type": "code", "text": "fn main() {}"}},
+                    "parent": "u2", "children": []
+                }
+            }
+        }]);
+
+        let (source, convs) = parse_export(&export.to_string()).unwrap();
+        assert_eq!(source, ExportSource::ChatGpt);
+        assert_eq!(convs[0].id, "chatgpt-c1");
+        assert_eq!(convs[0].title, "Rust help");
+        let contents: Vec<_> = convs[0]
+            .messages
+            .iter()
+            .map(|m| m.content.as_str())
+            .collect();
+        assert_eq!(contents, ["new question", "fn main() {}"]);
+        assert_eq!(convs[0].messages[1].role, MessageRole::Assistant);
+    }
+
+    #[test]
+    fn test_claude_export() {
+        let export = serde_json::json!([{
+            "uuid": "k1",
+            "name": "",
+            "chat_messages": [
+                {"uuid": "m1", "sender": "human", "text": "hello",
+                 "created_at": "2024-05-01T14:30:00+02:00"},
+                {"uuid": "m2", "sender": "assistant", "text": "",
+                 "content": [{"type": "text", "text": "hi there"}, {"type": "tool_use"}]}
+            ]
+        }]);
+
+        let (source, convs) = parse_export(&export.to_string()).unwrap();
+        assert_eq!(source, ExportSource::Claude);
+        assert_eq!(convs[0].title, "Untitled");
+        let messages = &convs[0].messages;
+        assert_eq!(messages[0].role, MessageRole::User);
+        assert_eq!(
+            messages[0].created_at.as_deref(),
+            Some("2024-05-01T12:30:00Z")
+        );
+        assert_eq!(messages[1].content, "hi there");
+    }
+
+    #[test]
+    fn test_unknown_export_is_rejected() {
+        assert!(parse_export("{}").is_err());
+        assert!(parse_export("[{\"foo\": 1}]").is_err());
+        assert!(parse_export("not json").is_err());
+    }
+}
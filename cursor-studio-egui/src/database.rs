@@ -1,10 +1,15 @@
 //! Database module for Cursor versions and chat history
 
+use crate::chat_exports::{parse_export, ExportSource};
+use crate::languages::{detect_languages, language_tag, LANGUAGE_TAG_PREFIX};
 use anyhow::{Context, Result};
-use rusqlite::{params, Connection};
+use regex::Regex;
+use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::path::PathBuf;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
 /// Extract message content from various possible JSON structures in Cursor's database
@@ -129,6 +134,60 @@ fn extract_message_content(data: &Value) -> (String, Option<ToolCallInfo>, Optio
     (content, tool_call, thinking)
 }
 
+/// Extract the creation time of a bubble as an RFC 3339 UTC string.
+///
+/// Newer Cursor builds store `createdAt` (ISO string or epoch millis); older
+/// ones only carry `timingInfo.clientStartTime`.
+fn extract_created_at(data: &Value) -> Option<String> {
+    let timing = data.get("timingInfo");
+    let candidates = [
+        data.get("createdAt"),
+        timing.and_then(|t| t.get("clientStartTime")),
+        timing.and_then(|t| t.get("clientRpcSendTime")),
+    ];
+
+    for value in candidates.into_iter().flatten() {
+        let parsed = match value {
+            Value::String(s) => chrono::DateTime::parse_from_rfc3339(s)
+                .ok()
+                .map(|dt| dt.with_timezone(&chrono::Utc)),
+            // Ignore small values: some timing fields are relative, not epoch millis
+            Value::Number(n) => n
+                .as_f64()
+                .filter(|ms| *ms > 1_000_000_000_000.0)
+                .and_then(|ms| chrono::DateTime::from_timestamp_millis(ms as i64)),
+            _ => None,
+        };
+        if let Some(dt) = parsed {
+            return Some(dt.to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+        }
+    }
+
+    None
+}
+
+/// Extract the output of a tool call bubble (`toolFormerData.result`).
+fn extract_tool_result(data: &Value) -> Option<String> {
+    let result = data.get("toolFormerData")?.get("result")?;
+    let text = match result {
+        Value::String(s) => s.clone(),
+        Value::Null => return None,
+        other => serde_json::to_string_pretty(other).ok()?,
+    };
+    (!text.trim().is_empty()).then_some(text)
+}
+
+/// Extract the bubble an edited or regenerated message branches from.
+///
+/// Only present when the source recorded the edit; linear chats carry none.
+fn extract_parent_id(data: &Value) -> Option<String> {
+    ["parentBubbleId", "parentId"]
+        .into_iter()
+        .filter_map(|field| data.get(field).and_then(|v| v.as_str()))
+        .find(|id| !id.is_empty())
+        .map(str::to_string)
+}
+
 /// Extract plain text from Lexical editor JSON format
 fn extract_lexical_text(root: &Value) -> String {
     let mut parts = Vec::new();
@@ -245,7 +304,9 @@ CREATE TABLE IF NOT EXISTS conversations (
     message_count INTEGER DEFAULT 0,
     is_favorite INTEGER DEFAULT 0,
     is_archived INTEGER DEFAULT 0,
-    content_hash TEXT
+    content_hash TEXT,
+    notes TEXT,
+    languages_scanned INTEGER DEFAULT 0
 );
 
 CREATE TABLE IF NOT EXISTS messages (
@@ -262,10 +323,17 @@ CREATE TABLE IF NOT EXISTS messages (
     content_type TEXT DEFAULT 'text',
     has_code_blocks INTEGER DEFAULT 0,
     has_terminal_output INTEGER DEFAULT 0,
-    files_edited TEXT DEFAULT '[]'
+    files_edited TEXT DEFAULT '[]',
+    created_at TEXT,
+    parent_id TEXT,
+    branch INTEGER DEFAULT 0,
+    tool_call_id TEXT,
+    -- Words in content, counted whenever it is written (see count_words)
+    word_count INTEGER
 );
 
 CREATE INDEX IF NOT EXISTS idx_msg_conv ON messages(conversation_id);
+CREATE INDEX IF NOT EXISTS idx_conv_content_hash ON conversations(content_hash);
 
 -- Bookmarks table - persists across cache clears
 CREATE TABLE IF NOT EXISTS bookmarks (
@@ -280,6 +348,33 @@ CREATE TABLE IF NOT EXISTS bookmarks (
 );
 
 CREATE INDEX IF NOT EXISTS idx_bookmark_conv ON bookmarks(conversation_id);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_bookmark_message ON bookmarks(conversation_id, message_id);
+
+-- User-given conversation titles - keyed by conversation id so they survive reimports
+CREATE TABLE IF NOT EXISTS conversation_titles (
+    conversation_id TEXT PRIMARY KEY,
+    title TEXT NOT NULL,
+    renamed_at TEXT DEFAULT (datetime('now'))
+);
+
+-- Conversation tags - keyed by conversation id so, like bookmarks, they
+-- survive cache clears and come back on reimport
+CREATE TABLE IF NOT EXISTS tags (
+    conversation_id TEXT NOT NULL,
+    tag TEXT NOT NULL,
+    PRIMARY KEY (conversation_id, tag)
+);
+
+CREATE INDEX IF NOT EXISTS idx_tags_tag ON tags(tag);
+
+-- Message content as it was before a search-and-replace, for undo
+CREATE TABLE IF NOT EXISTS message_backups (
+    batch_id TEXT NOT NULL,
+    message_id TEXT NOT NULL,
+    content TEXT,
+    created_at TEXT DEFAULT (datetime('now')),
+    PRIMARY KEY (batch_id, message_id)
+);
 
 -- User request segments - groups of messages per user turn
 CREATE TABLE IF NOT EXISTS request_segments (
@@ -301,7 +396,9 @@ CREATE TABLE IF NOT EXISTS display_preferences (
     content_type TEXT PRIMARY KEY,
     alignment TEXT DEFAULT 'left',
     style TEXT DEFAULT 'default',
-    collapsed_by_default INTEGER DEFAULT 0
+    collapsed_by_default INTEGER DEFAULT 0,
+    fg_color TEXT,
+    bg_color TEXT
 );
 
 INSERT OR IGNORE INTO display_preferences (content_type, alignment, style) VALUES
@@ -312,6 +409,58 @@ INSERT OR IGNORE INTO display_preferences (content_type, alignment, style) VALUE
     ('tool_result', 'left', 'compact'),
     ('code_block', 'left', 'highlight'),
     ('terminal', 'left', 'monospace');
+
+-- Cursor installs the user confirmed from "Detect installations"
+CREATE TABLE IF NOT EXISTS registered_installs (
+    version TEXT PRIMARY KEY,
+    appimage_path TEXT,
+    data_dir TEXT NOT NULL,
+    registered_at TEXT DEFAULT (datetime('now'))
+);
+
+-- Newest cursorDiskKV rowid imported from each Cursor database, so the next
+-- import only reads what was written since
+CREATE TABLE IF NOT EXISTS import_state (
+    source TEXT PRIMARY KEY,
+    last_rowid INTEGER NOT NULL,
+    updated_at TEXT DEFAULT (datetime('now'))
+);
+
+-- Conversations an import couldn't read, tried again by id on the next one
+CREATE TABLE IF NOT EXISTS import_retries (
+    source TEXT NOT NULL,
+    conversation_id TEXT NOT NULL,
+    PRIMARY KEY (source, conversation_id)
+);
+
+-- Full-text index over message text, kept in step with messages by the
+-- triggers below. Rows share the message's rowid.
+CREATE VIRTUAL TABLE IF NOT EXISTS messages_fts USING fts5(
+    message_id UNINDEXED,
+    conversation_id UNINDEXED,
+    content,
+    thinking,
+    tool_text
+);
+
+CREATE TRIGGER IF NOT EXISTS messages_fts_insert AFTER INSERT ON messages BEGIN
+    INSERT INTO messages_fts (rowid, message_id, conversation_id, content, thinking, tool_text)
+    VALUES (new.rowid, new.id, new.conversation_id, new.content, new.thinking,
+            TRIM(COALESCE(new.tool_name, '') || ' ' || COALESCE(new.tool_args, '')));
+END;
+
+CREATE TRIGGER IF NOT EXISTS messages_fts_delete AFTER DELETE ON messages BEGIN
+    DELETE FROM messages_fts WHERE rowid = old.rowid;
+END;
+
+CREATE TRIGGER IF NOT EXISTS messages_fts_update
+AFTER UPDATE OF content, thinking, tool_name, tool_args ON messages BEGIN
+    UPDATE messages_fts
+    SET content = new.content,
+        thinking = new.thinking,
+        tool_text = TRIM(COALESCE(new.tool_name, '') || ' ' || COALESCE(new.tool_args, ''))
+    WHERE rowid = old.rowid;
+END;
 "#;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -322,6 +471,14 @@ pub struct CursorVersion {
     pub is_default: bool,
 }
 
+/// A Cursor install found outside the usual locations and registered by the user
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegisteredInstall {
+    pub version: String,
+    pub appimage: Option<PathBuf>,
+    pub data_dir: PathBuf,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Conversation {
     pub id: String,
@@ -330,7 +487,74 @@ pub struct Conversation {
     pub category: String,
     pub message_count: usize,
     pub is_favorite: bool,
+    pub is_archived: bool,
     pub user_tags: Vec<String>,
+    /// Time of the newest message, falling back to the import time
+    pub updated_at: Option<String>,
+    /// Freeform note the user attached to the whole conversation
+    pub notes: Option<String>,
+    /// Words in the user and assistant messages; tool calls and their output
+    /// aren't counted as reading
+    pub word_count: usize,
+}
+
+/// Marks around the matched terms in [`MessageHit::snippet`]
+pub const SNIPPET_MATCH_START: char = '\u{2}';
+pub const SNIPPET_MATCH_END: char = '\u{3}';
+
+/// A message matching a full-text search
+#[derive(Debug, Clone)]
+pub struct MessageHit {
+    pub conversation_id: String,
+    pub conversation_title: String,
+    pub message_id: String,
+    pub role: String,
+    /// Text around the match, with matched terms between
+    /// `SNIPPET_MATCH_START` and `SNIPPET_MATCH_END`
+    pub snippet: String,
+}
+
+/// Activity on one calendar day (`YYYY-MM-DD`, UTC)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayActivity {
+    pub day: String,
+    pub conversations: usize,
+    pub messages: usize,
+}
+
+impl DayActivity {
+    fn new(day: String) -> Self {
+        Self {
+            day,
+            conversations: 0,
+            messages: 0,
+        }
+    }
+}
+
+/// A conversation with all of its messages, as copied to the clipboard for sharing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationJson {
+    pub conversation: Conversation,
+    pub messages: Vec<Message>,
+}
+
+/// Messages a search-and-replace would change, shown before applying it
+#[derive(Debug, Clone, Default)]
+pub struct ReplacePreview {
+    pub messages: usize,
+    pub conversations: usize,
+    pub matches: usize,
+    /// A few excerpts around the first match in the first affected messages
+    pub samples: Vec<ReplaceSample>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReplaceSample {
+    pub conversation_id: String,
+    pub message_id: String,
+    pub before: String,
+    pub after: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -355,6 +579,23 @@ pub struct Message {
     pub has_code_blocks: bool,
     pub has_terminal_output: bool,
     pub files_edited: Vec<String>,
+    /// RFC 3339 creation time, when the source bubble carried one
+    pub created_at: Option<String>,
+    /// Message this one branches from, when the source recorded edits
+    pub parent_id: Option<String>,
+    /// Position among the messages sharing `parent_id` (0 for the first)
+    pub branch: usize,
+    /// Links a tool call to its result, when the source recorded an id
+    pub tool_call_id: Option<String>,
+}
+
+impl Message {
+    /// No text, thinking or tool call: import bookkeeping with nothing to show
+    pub fn is_empty(&self) -> bool {
+        self.content.trim().is_empty()
+            && self.tool_call.is_none()
+            && self.thinking.as_deref().unwrap_or("").trim().is_empty()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -389,6 +630,18 @@ impl std::str::FromStr for ContentType {
     }
 }
 
+impl MessageRole {
+    /// Value stored in the `messages.role` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MessageRole::User => "user",
+            MessageRole::Assistant => "assistant",
+            MessageRole::ToolCall => "tool_call",
+            MessageRole::ToolResult => "tool_result",
+        }
+    }
+}
+
 impl ContentType {
     /// Parse from string (convenience method)
     pub fn parse(s: &str) -> Self {
@@ -418,6 +671,32 @@ pub struct Bookmark {
     pub color: String,
 }
 
+/// One bookmark in the file written by `export_bookmarks`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BookmarkJson {
+    conversation_id: String,
+    message_id: String,
+    seq: usize,
+    label: Option<String>,
+    note: Option<String>,
+    color: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BookmarksFile {
+    bookmarks: Vec<BookmarkJson>,
+}
+
+/// Outcome of `import_bookmarks`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BookmarkImport {
+    /// Found their message, by id or by position in the conversation
+    pub attached: usize,
+    /// Their conversation isn't in the library (yet); they're kept, and attach
+    /// when it's imported like bookmarks kept through a cache clear
+    pub orphaned: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RequestSegment {
     pub id: String,
@@ -437,11 +716,26 @@ pub struct DisplayPreference {
     pub alignment: String, // "left", "right", "center"
     pub style: String,     // "default", "bubble", "compact", "collapsed", "highlight", "monospace"
     pub collapsed_by_default: bool,
+    /// Optional `#rrggbb` overrides applied over the theme defaults
+    #[serde(default)]
+    pub fg_color: Option<String>,
+    #[serde(default)]
+    pub bg_color: Option<String>,
 }
 
 /// Internal type for message parsing during import
-/// (msg_id, role, content, sequence, tool_call, thinking)
-type ParsedMessage = (String, String, String, usize, Option<ToolCallInfo>, Option<String>);
+/// (msg_id, role, content, sequence, tool_call, thinking, created_at, parent_id, tool_call_id)
+type ParsedMessage = (
+    String,
+    String,
+    String,
+    usize,
+    Option<ToolCallInfo>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
 
 /// Statistics about message types for analytics
 #[derive(Debug, Clone, Default)]
@@ -454,12 +748,873 @@ pub struct MessageStats {
     pub bookmarks: usize,
 }
 
+/// Outcome of importing a single Cursor database
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CursorImport {
+    pub imported: usize,
+    /// Ids of the conversations added, in import order
+    pub imported_ids: Vec<String>,
+    /// Already in the library
+    pub skipped: usize,
+    /// Same content as a conversation already in the library under another id,
+    /// e.g. the same chat seen in two version directories
+    pub duplicates: usize,
+    /// Conversations with nothing readable left
+    pub lost_conversations: usize,
+    /// Unreadable bubbles dropped, including those of lost conversations
+    pub lost_messages: usize,
+    /// Cursor kept the database locked, so only its last checkpoint was read
+    pub locked: bool,
+    /// Bubble rows written since the previous import that had to be scanned
+    pub rows_scanned: usize,
+}
+
+impl CursorImport {
+    pub fn is_damaged(&self) -> bool {
+        self.lost_conversations > 0 || self.lost_messages > 0
+    }
+}
+
+/// Retries of a Cursor database read while Cursor holds a lock on it
+const BUSY_RETRIES: u32 = 5;
+/// Wait before the first busy retry; doubles on each attempt
+const BUSY_RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(100);
+
+fn is_busy(err: &rusqlite::Error) -> bool {
+    matches!(
+        err.sqlite_error_code(),
+        Some(rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked)
+    )
+}
+
+/// Run a read, backing off and retrying while the database is locked
+fn retry_while_busy<T>(mut read: impl FnMut() -> rusqlite::Result<T>) -> rusqlite::Result<T> {
+    let mut delay = BUSY_RETRY_DELAY;
+    let mut attempt = 0;
+    loop {
+        match read() {
+            Err(e) if is_busy(&e) && attempt < BUSY_RETRIES => {
+                log::debug!("Cursor database is locked, retrying in {:?}", delay);
+                std::thread::sleep(delay);
+                delay *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Collect the readable rows and count the rest; a lock error aborts so it can be retried
+fn read_rows<T>(
+    rows: impl Iterator<Item = rusqlite::Result<T>>,
+) -> rusqlite::Result<(Vec<T>, usize)> {
+    let mut readable = Vec::new();
+    let mut unreadable = 0;
+    for row in rows {
+        match row {
+            Ok(value) => readable.push(value),
+            Err(e) if is_busy(&e) => return Err(e),
+            Err(e) => {
+                log::debug!("Skipping unreadable row: {}", e);
+                unreadable += 1;
+            }
+        }
+    }
+    Ok((readable, unreadable))
+}
+
+/// Open a Cursor database for reading.
+///
+/// While Cursor is running it can hold a lock for longer than the busy
+/// retries wait; the database is then reopened with `immutable=1`, which reads
+/// the last checkpointed state without taking locks. The flag reports that
+/// fallback, since chats still in Cursor's write-ahead log are missed.
+fn open_cursor_source(path: &Path) -> Result<(Connection, bool)> {
+    let conn = Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    // Fail fast on a lock so retry_while_busy's backoff decides how long to wait
+    conn.busy_timeout(std::time::Duration::ZERO)?;
+
+    let probe = retry_while_busy(|| {
+        conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |row| {
+            row.get::<_, i64>(0)
+        })
+    });
+    match probe {
+        Ok(_) => Ok((conn, false)),
+        Err(e) if is_busy(&e) => {
+            log::warn!(
+                "{} is locked, probably by a running Cursor; reading its last checkpoint",
+                path.display()
+            );
+            // Percent-encode the characters that would end the path part of the URI
+            let path = path
+                .to_string_lossy()
+                .replace('%', "%25")
+                .replace('?', "%3f")
+                .replace('#', "%23");
+            let conn = Connection::open_with_flags(
+                format!("file:{}?immutable=1", path),
+                rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY | rusqlite::OpenFlags::SQLITE_OPEN_URI,
+            )?;
+            Ok((conn, true))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Fingerprint of a conversation's title and message sequence, so the same
+/// chat imported under different ids can be recognised
+fn content_hash<'a>(title: &str, messages: impl IntoIterator<Item = (&'a str, &'a str)>) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(title.trim().as_bytes());
+    for (role, content) in messages {
+        hasher.update([0]);
+        hasher.update(role.as_bytes());
+        hasher.update([0]);
+        hasher.update(content.trim().as_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Hash conversations imported before deduplication existed
+fn backfill_content_hashes(conn: &Connection) -> Result<usize> {
+    let ids: Vec<(String, String)> = conn
+        .prepare(
+            "SELECT id, COALESCE(original_title, '') FROM conversations
+             WHERE content_hash IS NULL",
+        )?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<_, _>>()?;
+    if ids.is_empty() {
+        return Ok(0);
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    {
+        let mut messages = tx.prepare(
+            "SELECT role, COALESCE(content, '') FROM messages
+             WHERE conversation_id = ? ORDER BY sequence",
+        )?;
+        let mut update = tx.prepare("UPDATE conversations SET content_hash = ? WHERE id = ?")?;
+        for (id, title) in &ids {
+            let rows: Vec<(String, String)> = messages
+                .query_map(params![id], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<Result<_, _>>()?;
+            let hash = content_hash(title, rows.iter().map(|(r, c)| (r.as_str(), c.as_str())));
+            update.execute(params![hash, id])?;
+        }
+    }
+    tx.commit()?;
+    Ok(ids.len())
+}
+
+/// Words in a message, as stored in `messages.word_count`
+fn count_words(content: &str) -> usize {
+    content.split_whitespace().count()
+}
+
+/// Count words for messages written before counts were stored, or copied in
+/// from an export that didn't have them
+fn backfill_word_counts(conn: &Connection) -> Result<usize> {
+    let rows: Vec<(i64, String)> = conn
+        .prepare("SELECT rowid, COALESCE(content, '') FROM messages WHERE word_count IS NULL")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<_, _>>()?;
+    if rows.is_empty() {
+        return Ok(0);
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    {
+        let mut update = tx.prepare("UPDATE messages SET word_count = ? WHERE rowid = ?")?;
+        for (rowid, content) in &rows {
+            update.execute(params![count_words(content), rowid])?;
+        }
+    }
+    tx.commit()?;
+    Ok(rows.len())
+}
+
+/// Whether a conversation with this content hash is already in the library
+fn has_content_hash(conn: &Connection, hash: &str) -> rusqlite::Result<bool> {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM conversations WHERE content_hash = ?)",
+        params![hash],
+        |row| row.get(0),
+    )
+}
+
+/// Reading speed behind the Archive's reading-time estimates
+pub const READING_WORDS_PER_MINUTE: usize = 230;
+
+/// Estimated minutes to read `words`, never less than one
+pub fn reading_minutes(words: usize) -> usize {
+    words.div_ceil(READING_WORDS_PER_MINUTE).max(1)
+}
+
+/// A conversation read from a Cursor database, ready to insert
+struct ParsedConversation {
+    title: String,
+    messages: Vec<ParsedMessage>,
+    /// Bubbles that were unreadable or didn't parse
+    lost: usize,
+}
+
+/// Read and parse one conversation's bubbles; empty bubbles are dropped.
+/// Fails only if the conversation can't be read at all.
+fn read_cursor_conversation(src_conn: &Connection, conv_id: &str) -> rusqlite::Result<ParsedConversation> {
+    let pattern = format!("bubbleId:{}:%", conv_id);
+    let bubbles = retry_while_busy(|| {
+        let mut msg_stmt = src_conn.prepare_cached(
+            "SELECT key, value FROM cursorDiskKV WHERE key LIKE ? ORDER BY key",
+        )?;
+        // Query and handle value as either BLOB or TEXT
+        let rows = msg_stmt.query_map(params![pattern], |row| {
+            let key: String = row.get(0)?;
+            let value_bytes: Vec<u8> = row
+                .get::<_, Vec<u8>>(1)
+                .or_else(|_| row.get::<_, String>(1).map(|s| s.into_bytes()))?;
+            Ok((key, value_bytes))
+        })?;
+        read_rows(rows)
+    });
+    let (bubbles, mut lost) = bubbles?;
+
+    let mut messages: Vec<ParsedMessage> = Vec::new();
+    let mut title_candidates = Vec::new();
+
+    for (key, value) in bubbles {
+        let msg_id = key.split(':').next_back().unwrap_or("").to_string();
+
+        if let Ok(data) = serde_json::from_slice::<Value>(&value) {
+            let msg_type = data.get("type").and_then(|v| v.as_i64()).unwrap_or(0);
+
+            // Determine role based on type and toolFormerData presence
+            let (base_role, is_tool_call) = if data.get("toolFormerData").is_some() {
+                ("tool_call", true)
+            } else if msg_type == 1 {
+                ("user", false)
+            } else {
+                ("assistant", false)
+            };
+
+            let (content, tool_call, thinking) = extract_message_content(&data);
+
+            // Skip completely empty messages (unless they're tool calls)
+            if content.is_empty() && !is_tool_call && thinking.is_none() {
+                continue;
+            }
+
+            // Collect title candidates from user messages
+            if base_role == "user" && !content.is_empty() && title_candidates.len() < 3 {
+                title_candidates.push(content.chars().take(100).collect::<String>());
+            }
+
+            let created_at = extract_created_at(&data);
+            let tool_call_id = tool_call
+                .as_ref()
+                .map(|tc| tc.tool_id.clone())
+                .filter(|id| !id.is_empty());
+            let tool_result = extract_tool_result(&data);
+
+            messages.push((
+                msg_id.clone(),
+                base_role.to_string(),
+                content,
+                messages.len(),
+                tool_call,
+                thinking,
+                created_at.clone(),
+                extract_parent_id(&data),
+                tool_call_id.clone(),
+            ));
+
+            // The output lives in the same bubble; keep it as its own message
+            if let Some(result) = tool_result {
+                messages.push((
+                    format!("{}:result", msg_id),
+                    "tool_result".to_string(),
+                    result,
+                    messages.len(),
+                    None,
+                    None,
+                    created_at,
+                    None,
+                    tool_call_id,
+                ));
+            }
+        } else {
+            lost += 1;
+        }
+    }
+
+    let title = title_candidates
+        .first()
+        .map(|t| {
+            let truncated: String = t.chars().take(60).collect();
+            if truncated.len() < t.len() {
+                format!("{}...", truncated)
+            } else {
+                truncated
+            }
+        })
+        .unwrap_or_else(|| "Untitled".to_string());
+
+    Ok(ParsedConversation {
+        title,
+        messages,
+        lost,
+    })
+}
+
+/// What changed in a Cursor database since it was last imported
+struct CursorChanges {
+    /// Conversations with bubbles written since the watermark, with their row counts
+    rows: Vec<(String, usize)>,
+    /// Conversations whose rows couldn't be read
+    unreadable: usize,
+    /// Newest rowid in the database, the next watermark
+    max_rowid: i64,
+}
+
+/// Conversations an import looks at: those changed since the watermark, then
+/// ones a previous import couldn't read (they sit below the watermark, so
+/// they're tried again by id)
+fn import_candidates(
+    conn: &Connection,
+    source: &str,
+    changes: &CursorChanges,
+) -> rusqlite::Result<Vec<String>> {
+    let mut conv_ids: Vec<String> = changes.rows.iter().map(|(id, _)| id.clone()).collect();
+    let mut stmt = conn.prepare("SELECT conversation_id FROM import_retries WHERE source = ?")?;
+    for conv_id in stmt.query_map(params![source], |row| row.get::<_, String>(0))? {
+        let conv_id = conv_id?;
+        if !conv_ids.contains(&conv_id) {
+            conv_ids.push(conv_id);
+        }
+    }
+    Ok(conv_ids)
+}
+
+/// Dry-run summary of what importing a single Cursor database would do
+#[derive(Debug, Clone, Default)]
+pub struct ImportPreview {
+    pub version: String,
+    pub path: PathBuf,
+    pub conversations: usize,
+    pub messages: usize,
+    pub new_conversations: usize,
+    pub new_messages: usize,
+    /// Already in the library, by id or with identical content
+    pub existing_conversations: usize,
+    /// Cursor had the database locked; counts come from its last checkpoint
+    pub locked: bool,
+    /// Set when the source database could not be read
+    pub error: Option<String>,
+}
+
+/// Match tool results to the calls they answer, as call index → result index.
+///
+/// Results pair by `tool_call_id` when both sides have one, otherwise with the
+/// oldest unanswered call in the run of tool messages just before them.
+pub fn pair_tool_results(messages: &[Message]) -> HashMap<usize, usize> {
+    let mut pairs = HashMap::new();
+    let mut calls_by_id: HashMap<&str, usize> = HashMap::new();
+    let mut pending: Vec<usize> = Vec::new();
+
+    for (i, msg) in messages.iter().enumerate() {
+        let id = msg.tool_call_id.as_deref();
+        if msg.role == MessageRole::ToolResult {
+            let call = id.and_then(|id| calls_by_id.remove(id)).or_else(|| {
+                pending
+                    .iter()
+                    .copied()
+                    .find(|&c| id.is_none() || messages[c].tool_call_id.is_none())
+            });
+            if let Some(call) = call {
+                pending.retain(|&c| c != call);
+                if let Some(call_id) = messages[call].tool_call_id.as_deref() {
+                    calls_by_id.remove(call_id);
+                }
+                pairs.insert(call, i);
+            }
+        } else if msg.role == MessageRole::ToolCall || msg.tool_call.is_some() {
+            if let Some(id) = id {
+                calls_by_id.insert(id, i);
+            }
+            pending.push(i);
+        } else {
+            pending.clear();
+        }
+    }
+
+    pairs
+}
+
+/// Find all Cursor state databases on this machine as (path, version) pairs
+pub fn discover_cursor_databases() -> Vec<(PathBuf, String)> {
+    let mut db_paths = Vec::new();
+    let home = match dirs::home_dir() {
+        Some(h) => h,
+        None => return db_paths,
+    };
+
+    // Main Cursor database
+    let main_db = home.join(".config/Cursor/User/globalStorage/state.vscdb");
+    if main_db.exists() {
+        db_paths.push((main_db, "default".to_string()));
+    }
+
+    // Versioned Cursor databases
+    if let Ok(entries) = std::fs::read_dir(&home) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !name.starts_with(".cursor-") {
+                continue;
+            }
+            if entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false) {
+                let version = name.strip_prefix(".cursor-").unwrap_or(&name).to_string();
+                let db_path = entry.path().join("User/globalStorage/state.vscdb");
+                if db_path.exists() {
+                    db_paths.push((db_path, version));
+                }
+            }
+        }
+    }
+
+    db_paths
+}
+
+/// Shared column list for conversation queries, read by `conversation_from_row`
+const CONVERSATION_SELECT: &str = "SELECT c.id, c.source_version,
+        COALESCE(t.title, c.original_title), cat.name,
+        c.message_count, c.is_favorite,
+        (SELECT json_group_array(tag) FROM
+            (SELECT tag FROM tags WHERE conversation_id = c.id ORDER BY rowid)),
+        c.is_archived,
+        COALESCE((SELECT MAX(m.created_at) FROM messages m
+                  WHERE m.conversation_id = c.id), c.imported_at),
+        c.notes,
+        (SELECT COALESCE(SUM(m.word_count), 0) FROM messages m
+         WHERE m.conversation_id = c.id AND m.role IN ('user', 'assistant'))
+     FROM conversations c
+     LEFT JOIN categories cat ON c.category_id = cat.id
+     LEFT JOIN conversation_titles t ON t.conversation_id = c.id";
+
+fn conversation_from_row(row: &rusqlite::Row) -> rusqlite::Result<Conversation> {
+    let tags_json: String = row
+        .get::<_, Option<String>>(6)?
+        .unwrap_or_else(|| "[]".to_string());
+    let tags: Vec<String> = serde_json::from_str(&tags_json).unwrap_or_default();
+
+    Ok(Conversation {
+        id: row.get(0)?,
+        source_version: row.get(1)?,
+        title: row
+            .get::<_, Option<String>>(2)?
+            .unwrap_or_else(|| "Untitled".to_string()),
+        category: row
+            .get::<_, Option<String>>(3)?
+            .unwrap_or_else(|| "Uncategorized".to_string()),
+        message_count: row.get(4)?,
+        is_favorite: row.get::<_, i32>(5)? != 0,
+        is_archived: row.get::<_, Option<i32>>(7)?.unwrap_or(0) != 0,
+        user_tags: tags,
+        updated_at: row.get(8)?,
+        notes: row.get(9)?,
+        word_count: row.get(10)?,
+    })
+}
+
+/// Turn typed text into an FTS5 query: every word must appear, the last one
+/// as a prefix so results follow the typing. Quoting keeps punctuation like
+/// `-` or `:` from being read as query syntax.
+fn fts_query(text: &str) -> Option<String> {
+    let words: Vec<String> = text
+        .split_whitespace()
+        .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+        .collect();
+    if words.is_empty() {
+        return None;
+    }
+    Some(format!("{}*", words.join(" ")))
+}
+
+/// Insert a bookmark, or update the label and note of the one already on the message
+fn upsert_bookmark(
+    conn: &Connection,
+    conv_id: &str,
+    msg_id: &str,
+    msg_seq: usize,
+    label: Option<&str>,
+    note: Option<&str>,
+    color: &str,
+) -> Result<String> {
+    let id = uuid::Uuid::new_v4().to_string();
+    let id = conn.query_row(
+        "INSERT INTO bookmarks (id, conversation_id, message_id, message_sequence, label, note, color)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+         ON CONFLICT(conversation_id, message_id) DO UPDATE SET
+             label = COALESCE(excluded.label, label),
+             note = COALESCE(excluded.note, note)
+         RETURNING id",
+        params![id, conv_id, msg_id, msg_seq, label, note, color],
+        |row| row.get(0),
+    )?;
+    Ok(id)
+}
+
+const MESSAGE_SELECT: &str = "SELECT id, conversation_id, sequence, role, content,
+            tool_name, tool_args, tool_status, thinking, created_at, parent_id, branch,
+            tool_call_id
+     FROM messages";
+
+fn message_from_row(row: &rusqlite::Row) -> rusqlite::Result<Message> {
+    let role_str: String = row.get(3)?;
+    let role = match role_str.as_str() {
+        "assistant" => MessageRole::Assistant,
+        "tool_call" => MessageRole::ToolCall,
+        "tool_result" => MessageRole::ToolResult,
+        _ => MessageRole::User,
+    };
+
+    // Reconstruct tool call info if present
+    let tool_call_id: Option<String> = row.get(12)?;
+    let tool_name: Option<String> = row.get(5)?;
+    let tool_call = tool_name.map(|name| {
+        let args: String = row
+            .get::<_, Option<String>>(6)
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        let status: String = row
+            .get::<_, Option<String>>(7)
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+
+        // Create preview from args
+        let args_preview = if let Ok(parsed) = serde_json::from_str::<Value>(&args) {
+            if let Some(obj) = parsed.as_object() {
+                obj.iter()
+                    .take(2)
+                    .map(|(k, v)| {
+                        format!(
+                            "{}: {}",
+                            k,
+                            v.to_string().chars().take(30).collect::<String>()
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            } else {
+                args.chars().take(100).collect()
+            }
+        } else {
+            args.chars().take(100).collect()
+        };
+
+        ToolCallInfo {
+            name,
+            args,
+            args_preview,
+            status,
+            tool_id: tool_call_id.clone().unwrap_or_default(),
+        }
+    });
+
+    // Detect content type based on content
+    let content: String = row.get::<_, Option<String>>(4)?.unwrap_or_default();
+    let has_code = content.contains("```");
+    let has_terminal = content.contains("$ ")
+        || content.contains("❯ ")
+        || content.contains("[e421@")
+        || content.contains("Command output:");
+
+    let content_type = if has_code && has_terminal {
+        ContentType::Mixed
+    } else if has_code {
+        ContentType::Code
+    } else if has_terminal {
+        ContentType::Terminal
+    } else if content.contains('#') || content.contains("**") || content.contains("- ") {
+        ContentType::Markdown
+    } else {
+        ContentType::Text
+    };
+
+    Ok(Message {
+        id: row.get(0)?,
+        conversation_id: row.get(1)?,
+        sequence: row.get(2)?,
+        role,
+        content,
+        tool_call,
+        thinking: row.get(8)?,
+        content_type,
+        has_code_blocks: has_code,
+        has_terminal_output: has_terminal,
+        files_edited: Vec::new(), // TODO: Parse from raw_json
+        created_at: row.get(9)?,
+        parent_id: row.get(10)?,
+        branch: row.get::<_, Option<usize>>(11)?.unwrap_or(0),
+        tool_call_id,
+    })
+}
+
 pub struct ChatDatabase {
     conn: Arc<Mutex<Connection>>,
     #[allow(dead_code)]
     data_dir: PathBuf,
 }
 
+/// One schema change made since the first release
+pub struct Migration {
+    /// Schema version the library is at once this step has run
+    pub version: i64,
+    /// Statement that makes the change
+    pub sql: &'static str,
+}
+
+/// Schema changes made since the first release, in order. A library records
+/// the version of the last step it has had under `schema_version` in its
+/// config table.
+///
+/// Append new steps, never edit or reorder old ones, and keep each safe to run
+/// again (see [`step_needed`]); new columns also go in `SCHEMA`, which fresh
+/// libraries are created from.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration { version: 1, sql: "ALTER TABLE messages ADD COLUMN tool_name TEXT" },
+    Migration { version: 2, sql: "ALTER TABLE messages ADD COLUMN tool_args TEXT" },
+    Migration { version: 3, sql: "ALTER TABLE messages ADD COLUMN tool_status TEXT" },
+    Migration { version: 4, sql: "ALTER TABLE messages ADD COLUMN thinking TEXT" },
+    Migration { version: 5, sql: "ALTER TABLE messages ADD COLUMN raw_json TEXT" },
+    Migration { version: 6, sql: "ALTER TABLE conversations ADD COLUMN is_archived INTEGER DEFAULT 0" },
+    Migration { version: 7, sql: "ALTER TABLE messages ADD COLUMN created_at TEXT" },
+    Migration { version: 8, sql: "ALTER TABLE conversations ADD COLUMN notes TEXT" },
+    Migration { version: 9, sql: "ALTER TABLE conversations ADD COLUMN languages_scanned INTEGER DEFAULT 0" },
+    Migration { version: 10, sql: "ALTER TABLE messages ADD COLUMN parent_id TEXT" },
+    Migration { version: 11, sql: "ALTER TABLE messages ADD COLUMN tool_call_id TEXT" },
+    Migration { version: 12, sql: "ALTER TABLE messages ADD COLUMN branch INTEGER DEFAULT 0" },
+    Migration { version: 13, sql: "ALTER TABLE display_preferences ADD COLUMN fg_color TEXT" },
+    Migration { version: 14, sql: "ALTER TABLE display_preferences ADD COLUMN bg_color TEXT" },
+    // Older versions allowed several bookmarks on one message; keep the first
+    // so SCHEMA's unique index can be created
+    Migration {
+        version: 15,
+        sql: "DELETE FROM bookmarks WHERE rowid NOT IN
+         (SELECT MIN(rowid) FROM bookmarks GROUP BY conversation_id, message_id)",
+    },
+    Migration {
+        version: 16,
+        sql: "CREATE TABLE IF NOT EXISTS registered_installs (
+            version TEXT PRIMARY KEY,
+            appimage_path TEXT,
+            data_dir TEXT NOT NULL,
+            registered_at TEXT DEFAULT (datetime('now'))
+        )",
+    },
+    Migration {
+        version: 17,
+        sql: "CREATE VIRTUAL TABLE IF NOT EXISTS messages_fts USING fts5(
+            message_id UNINDEXED, conversation_id UNINDEXED, content, thinking, tool_text
+        )",
+    },
+    // Index what's already there; SCHEMA's triggers keep it current from now on
+    Migration {
+        version: 18,
+        sql: "INSERT INTO messages_fts (rowid, message_id, conversation_id, content, thinking, tool_text)
+         SELECT rowid, id, conversation_id, content, thinking,
+                TRIM(COALESCE(tool_name, '') || ' ' || COALESCE(tool_args, ''))
+         FROM messages WHERE rowid NOT IN (SELECT rowid FROM messages_fts)",
+    },
+    Migration {
+        version: 19,
+        sql: "CREATE TABLE IF NOT EXISTS import_state (
+            source TEXT PRIMARY KEY,
+            last_rowid INTEGER NOT NULL,
+            updated_at TEXT DEFAULT (datetime('now'))
+        )",
+    },
+    Migration {
+        version: 20,
+        sql: "CREATE TABLE IF NOT EXISTS tags (
+            conversation_id TEXT NOT NULL,
+            tag TEXT NOT NULL,
+            PRIMARY KEY (conversation_id, tag)
+        )",
+    },
+    // Tags used to live on the conversation as a JSON list
+    Migration {
+        version: 21,
+        sql: "INSERT OR IGNORE INTO tags (conversation_id, tag)
+         SELECT c.id, j.value FROM conversations c, json_each(c.user_tags) j
+         WHERE json_valid(c.user_tags)",
+    },
+    Migration { version: 22, sql: "UPDATE conversations SET user_tags = '[]'" },
+    Migration {
+        version: 23,
+        sql: "CREATE TABLE IF NOT EXISTS import_retries (
+            source TEXT NOT NULL,
+            conversation_id TEXT NOT NULL,
+            PRIMARY KEY (source, conversation_id)
+        )",
+    },
+    Migration { version: 24, sql: "ALTER TABLE messages ADD COLUMN word_count INTEGER" },
+];
+
+/// Schema version written by this build
+pub const CURRENT_SCHEMA_VERSION: i64 = MIGRATIONS[MIGRATIONS.len() - 1].version;
+
+/// Config key the schema version is recorded under
+const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+fn schema_version(conn: &Connection, schema: &str) -> Result<i64> {
+    let recorded = if table_exists(conn, schema, "config")? {
+        conn.query_row(
+            &format!("SELECT value FROM {}.config WHERE key = ?1", schema),
+            [SCHEMA_VERSION_KEY],
+            |row| row.get::<_, String>(0),
+        )
+        .optional()?
+    } else {
+        None
+    };
+    let version = match recorded {
+        Some(value) => value
+            .parse()
+            .with_context(|| format!("Unreadable schema version {:?}", value))?,
+        // Libraries from before the version moved into config kept it here
+        None => conn.query_row(&format!("PRAGMA {}.user_version", schema), [], |row| {
+            row.get(0)
+        })?,
+    };
+    if version > CURRENT_SCHEMA_VERSION {
+        anyhow::bail!(
+            "Database schema version {} is newer than this Cursor Studio supports ({}); \
+             update Cursor Studio to open it",
+            version,
+            CURRENT_SCHEMA_VERSION
+        );
+    }
+    Ok(version)
+}
+
+fn record_schema_version(conn: &Connection, version: i64) -> Result<()> {
+    conn.execute_batch("CREATE TABLE IF NOT EXISTS config (key TEXT PRIMARY KEY, value TEXT NOT NULL)")?;
+    conn.execute(
+        "INSERT OR REPLACE INTO config (key, value) VALUES (?1, ?2)",
+        params![SCHEMA_VERSION_KEY, version.to_string()],
+    )?;
+    Ok(())
+}
+
+fn table_exists(conn: &Connection, schema: &str, table: &str) -> Result<bool> {
+    let exists = conn.query_row(
+        &format!(
+            "SELECT EXISTS (SELECT 1 FROM {}.sqlite_master WHERE type = 'table' AND name = ?1)",
+            schema
+        ),
+        [table],
+        |row| row.get(0),
+    )?;
+    Ok(exists)
+}
+
+fn column_exists(conn: &Connection, table: &str, column: &str) -> Result<bool> {
+    let exists = conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM pragma_table_info(?1) WHERE name = ?2)",
+        [table, column],
+        |row| row.get(0),
+    )?;
+    Ok(exists)
+}
+
+/// Whether a step still has work to do in this library.
+///
+/// A column that's already there isn't added again. Steps on a table the
+/// library doesn't have yet are skipped, as `SCHEMA` creates that table whole
+/// on reaching the latest version. Everything else is written to be safe to
+/// repeat (`IF NOT EXISTS`, `NOT IN`, `OR IGNORE`), so it always runs.
+fn step_needed(conn: &Connection, sql: &str) -> Result<bool> {
+    let words: Vec<&str> = sql.split_whitespace().take(6).collect();
+    let table = match words.as_slice() {
+        ["ALTER", "TABLE", table, ..] | ["DELETE", "FROM", table, ..] | ["UPDATE", table, ..] => {
+            *table
+        }
+        _ => return Ok(true),
+    };
+    if !table_exists(conn, "main", table)? {
+        return Ok(false);
+    }
+    match words.as_slice() {
+        ["ALTER", "TABLE", _, "ADD", "COLUMN", column] => Ok(!column_exists(conn, table, column)?),
+        _ => Ok(true),
+    }
+}
+
+/// Bring a library up to `CURRENT_SCHEMA_VERSION`, refusing one written by a
+/// newer build, and count the words of messages stored without a count
+fn run_migrations(conn: &Connection) -> Result<()> {
+    if schema_version(conn, "main")? < CURRENT_SCHEMA_VERSION {
+        migrate_to(conn, CURRENT_SCHEMA_VERSION)?;
+    }
+    backfill_word_counts(conn)?;
+    Ok(())
+}
+
+/// Run the steps between the library's version and `target`, then record
+/// `target`; returns the previous version. `SCHEMA` is only applied on
+/// reaching the latest version, as it describes that one.
+fn migrate_to(conn: &Connection, target: i64) -> Result<i64> {
+    run_migration_steps(conn, MIGRATIONS, SCHEMA, target)
+}
+
+/// [`migrate_to`] for any list of steps. Everything runs in one transaction,
+/// so if a step fails the library is left exactly as it was.
+fn run_migration_steps(
+    conn: &Connection,
+    migrations: &[Migration],
+    schema: &str,
+    target: i64,
+) -> Result<i64> {
+    let version = schema_version(conn, "main")?;
+    let latest = migrations.last().map_or(0, |m| m.version);
+    if !(0..=latest).contains(&target) {
+        anyhow::bail!(
+            "No schema version {} (this build knows 0 to {})",
+            target,
+            latest
+        );
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    // A brand-new library gets every table from SCHEMA as it is now
+    let fresh: bool = tx.query_row(
+        "SELECT NOT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table')",
+        [],
+        |row| row.get(0),
+    )?;
+    let steps = migrations
+        .iter()
+        .filter(|m| !fresh && m.version > version && m.version <= target);
+    for step in steps {
+        if !step_needed(&tx, step.sql)? {
+            continue;
+        }
+        tx.execute(step.sql, [])
+            .with_context(|| format!("Schema migration {} failed", step.version))?;
+        let summary = step.sql.lines().next().unwrap_or(step.sql);
+        log::info!("Schema migration {}: {}", step.version, summary);
+    }
+    if target == latest {
+        tx.execute_batch(schema)?;
+    }
+    record_schema_version(&tx, target)?;
+    tx.commit()?;
+    Ok(version)
+}
+
 impl ChatDatabase {
     /// Create a new database with a custom data directory (for testing)
     #[cfg(test)]
@@ -467,7 +1622,7 @@ impl ChatDatabase {
         std::fs::create_dir_all(&data_dir)?;
         let db_path = data_dir.join("studio.db");
         let conn = Connection::open(&db_path)?;
-        conn.execute_batch(SCHEMA)?;
+        run_migrations(&conn)?;
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
             data_dir,
@@ -483,14 +1638,7 @@ impl ChatDatabase {
 
         let db_path = data_dir.join("studio.db");
         let conn = Connection::open(&db_path)?;
-        conn.execute_batch(SCHEMA)?;
-
-        // Migration: add new columns if they don't exist
-        let _ = conn.execute("ALTER TABLE messages ADD COLUMN tool_name TEXT", []);
-        let _ = conn.execute("ALTER TABLE messages ADD COLUMN tool_args TEXT", []);
-        let _ = conn.execute("ALTER TABLE messages ADD COLUMN tool_status TEXT", []);
-        let _ = conn.execute("ALTER TABLE messages ADD COLUMN thinking TEXT", []);
-        let _ = conn.execute("ALTER TABLE messages ADD COLUMN raw_json TEXT", []);
+        run_migrations(&conn)?;
 
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
@@ -498,6 +1646,24 @@ impl ChatDatabase {
         })
     }
 
+    /// Schema version of the open library, see `MIGRATIONS`
+    pub fn schema_version(&self) -> Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        schema_version(&conn, "main")
+    }
+
+    /// Move the library to schema `version`, for tests and repairs; returns
+    /// the version it was at.
+    ///
+    /// Going forward runs the steps in between. Going back only lowers the
+    /// recorded version, as columns are never dropped - but since every step
+    /// is safe to repeat, migrating forward again re-applies the ones after
+    /// it, which repairs a library whose steps were partly lost.
+    pub fn run_migrations_to(&self, version: i64) -> Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        migrate_to(&conn, version)
+    }
+
     /// Get the path to the database file
     pub fn get_path(&self) -> PathBuf {
         self.data_dir.join("studio.db")
@@ -507,6 +1673,7 @@ impl ChatDatabase {
     pub fn open(path: &PathBuf) -> Result<Self> {
         let data_dir = path.parent().unwrap_or(path).to_path_buf();
         let conn = Connection::open(path)?;
+        run_migrations(&conn)?;
 
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
@@ -582,208 +1749,305 @@ impl ChatDatabase {
             }
         }
 
+        // 4. Installs registered from "Detect installations"
+        for install in self.registered_installs()? {
+            if seen_versions.insert(install.version.clone()) {
+                versions.push(CursorVersion {
+                    version: install.version,
+                    path: install.data_dir,
+                    is_installed: true,
+                    is_default: false,
+                });
+            }
+        }
+
         Ok(versions)
     }
 
-    /// Remove a version from tracking (does NOT delete files, that's done by CursorStudio)
-    pub fn remove_version(&self, version: &str) -> Result<()> {
-        log::info!("Database: remove_version called for {}", version);
-        Ok(())
-    }
-
-    pub fn get_conversations(&self, limit: usize) -> Result<Vec<Conversation>> {
+    /// Remember an install so it's listed (and launched) even though it
+    /// isn't where Cursor Studio looks; replaces any earlier one of the version
+    pub fn register_install(&self, install: &RegisteredInstall) -> Result<()> {
         let conn = self.conn.lock().unwrap();
-        let mut stmt = conn.prepare(
-            "SELECT c.id, c.source_version, c.original_title, cat.name, c.message_count, 
-                    c.is_favorite, c.user_tags
-             FROM conversations c
-             LEFT JOIN categories cat ON c.category_id = cat.id
-             WHERE c.is_archived = 0
-             ORDER BY c.imported_at DESC
-             LIMIT ?",
+        conn.execute(
+            "INSERT OR REPLACE INTO registered_installs (version, appimage_path, data_dir)
+             VALUES (?, ?, ?)",
+            params![
+                install.version,
+                install.appimage.as_ref().map(|p| p.to_string_lossy().to_string()),
+                install.data_dir.to_string_lossy().to_string(),
+            ],
         )?;
+        Ok(())
+    }
 
-        let rows = stmt.query_map(params![limit], |row| {
-            let tags_json: String = row
-                .get::<_, Option<String>>(6)?
-                .unwrap_or_else(|| "[]".to_string());
-            let tags: Vec<String> = serde_json::from_str(&tags_json).unwrap_or_default();
+    /// Forget a registered install; returns whether there was one
+    pub fn unregister_install(&self, version: &str) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        let removed = conn.execute(
+            "DELETE FROM registered_installs WHERE version = ?",
+            params![version],
+        )?;
+        Ok(removed > 0)
+    }
 
-            Ok(Conversation {
-                id: row.get(0)?,
-                source_version: row.get(1)?,
-                title: row
-                    .get::<_, Option<String>>(2)?
-                    .unwrap_or_else(|| "Untitled".to_string()),
-                category: row
-                    .get::<_, Option<String>>(3)?
-                    .unwrap_or_else(|| "Uncategorized".to_string()),
-                message_count: row.get(4)?,
-                is_favorite: row.get::<_, i32>(5)? != 0,
-                user_tags: tags,
+    pub fn registered_installs(&self) -> Result<Vec<RegisteredInstall>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT version, appimage_path, data_dir FROM registered_installs ORDER BY version",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(RegisteredInstall {
+                version: row.get(0)?,
+                appimage: row.get::<_, Option<String>>(1)?.map(PathBuf::from),
+                data_dir: PathBuf::from(row.get::<_, String>(2)?),
             })
         })?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    /// Remove a version from tracking (does NOT delete files, that's done by CursorStudio)
+    pub fn remove_version(&self, version: &str) -> Result<()> {
+        log::info!("Database: remove_version called for {}", version);
+        Ok(())
+    }
+
+    /// Newest conversations first, only those carrying `tag` if given;
+    /// `usize::MAX` fetches all of them
+    pub fn get_conversations(
+        &self,
+        limit: usize,
+        include_archived: bool,
+        tag: Option<&str>,
+    ) -> Result<Vec<Conversation>> {
+        // SQLite reads a negative LIMIT as no limit
+        let limit = i64::try_from(limit).unwrap_or(-1);
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE (?1 OR c.is_archived = 0)
+               AND (?3 IS NULL OR c.id IN (SELECT conversation_id FROM tags WHERE tag = ?3))
+             ORDER BY c.imported_at DESC
+             LIMIT ?2",
+            CONVERSATION_SELECT
+        ))?;
+
+        let rows = stmt.query_map(params![include_archived, limit, tag], conversation_from_row)?;
 
         rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
     }
 
-    pub fn get_messages(&self, conversation_id: &str) -> Result<Vec<Message>> {
+    /// Ids of every conversation in the library, archived ones included
+    pub fn conversation_ids(&self) -> Result<Vec<String>> {
         let conn = self.conn.lock().unwrap();
-        let mut stmt = conn.prepare(
-            "SELECT id, conversation_id, sequence, role, content, 
-                    tool_name, tool_args, tool_status, thinking
-             FROM messages WHERE conversation_id = ? ORDER BY sequence",
-        )?;
+        Ok(conversation_ids_in(&conn, "main")?)
+    }
 
-        let rows = stmt.query_map(params![conversation_id], |row| {
-            let role_str: String = row.get(3)?;
-            let role = match role_str.as_str() {
-                "assistant" => MessageRole::Assistant,
-                "tool_call" => MessageRole::ToolCall,
-                "tool_result" => MessageRole::ToolResult,
-                _ => MessageRole::User,
-            };
+    /// Look up a single conversation by id, archived or not
+    pub fn get_conversation(&self, conversation_id: &str) -> Result<Option<Conversation>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!("{} WHERE c.id = ?", CONVERSATION_SELECT))?;
+        let mut rows = stmt.query_map(params![conversation_id], conversation_from_row)?;
+        let conversation = rows.next().transpose()?;
+        Ok(conversation)
+    }
 
-            // Reconstruct tool call info if present
-            let tool_name: Option<String> = row.get(5)?;
-            let tool_call = tool_name.map(|name| {
-                let args: String = row
-                    .get::<_, Option<String>>(6)
-                    .ok()
-                    .flatten()
-                    .unwrap_or_default();
-                let status: String = row
-                    .get::<_, Option<String>>(7)
-                    .ok()
-                    .flatten()
-                    .unwrap_or_default();
-
-                // Create preview from args
-                let args_preview = if let Ok(parsed) = serde_json::from_str::<Value>(&args) {
-                    if let Some(obj) = parsed.as_object() {
-                        obj.iter()
-                            .take(2)
-                            .map(|(k, v)| {
-                                format!(
-                                    "{}: {}",
-                                    k,
-                                    v.to_string().chars().take(30).collect::<String>()
-                                )
-                            })
-                            .collect::<Vec<_>>()
-                            .join(", ")
-                    } else {
-                        args.chars().take(100).collect()
-                    }
-                } else {
-                    args.chars().take(100).collect()
-                };
+    pub fn get_messages(&self, conversation_id: &str) -> Result<Vec<Message>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE conversation_id = ? ORDER BY sequence",
+            MESSAGE_SELECT
+        ))?;
 
-                ToolCallInfo {
-                    name,
-                    args,
-                    args_preview,
-                    status,
-                    tool_id: String::new(),
-                }
-            });
+        let rows = stmt.query_map(params![conversation_id], message_from_row)?;
 
-            // Detect content type based on content
-            let content: String = row.get::<_, Option<String>>(4)?.unwrap_or_default();
-            let has_code = content.contains("```");
-            let has_terminal = content.contains("$ ")
-                || content.contains("❯ ")
-                || content.contains("[e421@")
-                || content.contains("Command output:");
-
-            let content_type = if has_code && has_terminal {
-                ContentType::Mixed
-            } else if has_code {
-                ContentType::Code
-            } else if has_terminal {
-                ContentType::Terminal
-            } else if content.contains('#') || content.contains("**") || content.contains("- ") {
-                ContentType::Markdown
-            } else {
-                ContentType::Text
-            };
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
 
-            Ok(Message {
-                id: row.get(0)?,
-                conversation_id: row.get(1)?,
-                sequence: row.get(2)?,
-                role,
-                content,
-                tool_call,
-                thinking: row.get(8)?,
-                content_type,
-                has_code_blocks: has_code,
-                has_terminal_output: has_terminal,
-                files_edited: Vec::new(), // TODO: Parse from raw_json
-            })
-        })?;
+    /// Up to `limit` messages starting at `offset` (in sequence order), for
+    /// conversations too large to load at once
+    pub fn get_messages_page(
+        &self,
+        conversation_id: &str,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<Message>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE conversation_id = ?1 ORDER BY sequence LIMIT ?2 OFFSET ?3",
+            MESSAGE_SELECT
+        ))?;
+
+        let rows = stmt.query_map(
+            params![conversation_id, limit as i64, offset as i64],
+            message_from_row,
+        )?;
 
         rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
     }
 
+    pub fn count_messages(&self, conversation_id: &str) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM messages WHERE conversation_id = ?",
+            params![conversation_id],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+
+    /// Search conversation titles, notes and tags. Archived conversations are included.
     pub fn search_conversations(&self, query: &str) -> Result<Vec<Conversation>> {
         let conn = self.conn.lock().unwrap();
-        let mut stmt = conn.prepare(
-            "SELECT c.id, c.source_version, c.original_title, cat.name, c.message_count, 
-                    c.is_favorite, c.user_tags
-             FROM conversations c
-             LEFT JOIN categories cat ON c.category_id = cat.id
-             WHERE c.is_archived = 0 AND c.original_title LIKE ?
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE t.title LIKE ?1 OR c.original_title LIKE ?1 OR c.notes LIKE ?1
+                OR c.id IN (SELECT conversation_id FROM tags WHERE tag LIKE ?1)
              ORDER BY c.imported_at DESC
              LIMIT 50",
-        )?;
+            CONVERSATION_SELECT
+        ))?;
 
         let pattern = format!("%{}%", query);
-        let rows = stmt.query_map(params![pattern], |row| {
-            let tags_json: String = row
-                .get::<_, Option<String>>(6)?
-                .unwrap_or_else(|| "[]".to_string());
-            let tags: Vec<String> = serde_json::from_str(&tags_json).unwrap_or_default();
+        let rows = stmt.query_map(params![pattern], conversation_from_row)?;
 
-            Ok(Conversation {
-                id: row.get(0)?,
-                source_version: row.get(1)?,
-                title: row
-                    .get::<_, Option<String>>(2)?
-                    .unwrap_or_else(|| "Untitled".to_string()),
-                category: row
-                    .get::<_, Option<String>>(3)?
-                    .unwrap_or_else(|| "Uncategorized".to_string()),
-                message_count: row.get(4)?,
-                is_favorite: row.get::<_, i32>(5)? != 0,
-                user_tags: tags,
-            })
-        })?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    /// Full-text search over message content, thinking and tool calls, best
+    /// matches first. Archived conversations are included.
+    pub fn search_messages(&self, query: &str) -> Result<Vec<MessageHit>> {
+        let Some(query) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT f.conversation_id, COALESCE(t.title, c.original_title), f.message_id, m.role,
+                    snippet(messages_fts, -1, ?2, ?3, '…', 16)
+             FROM messages_fts f
+             JOIN messages m ON m.rowid = f.rowid
+             LEFT JOIN conversations c ON c.id = f.conversation_id
+             LEFT JOIN conversation_titles t ON t.conversation_id = f.conversation_id
+             WHERE messages_fts MATCH ?1
+             ORDER BY f.rank
+             LIMIT 100",
+        )?;
+        let rows = stmt.query_map(
+            params![
+                query,
+                SNIPPET_MATCH_START.to_string(),
+                SNIPPET_MATCH_END.to_string()
+            ],
+            |row| {
+                Ok(MessageHit {
+                    conversation_id: row.get(0)?,
+                    conversation_title: row
+                        .get::<_, Option<String>>(1)?
+                        .unwrap_or_else(|| "Untitled".to_string()),
+                    message_id: row.get(2)?,
+                    role: row.get(3)?,
+                    snippet: row.get(4)?,
+                })
+            },
+        )?;
 
         rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
     }
 
-    pub fn get_stats(&self) -> Result<(usize, usize, usize)> {
+    /// Returns (conversations, messages, favorites)
+    pub fn get_stats(&self, include_archived: bool) -> Result<(usize, usize, usize)> {
         let conn = self.conn.lock().unwrap();
 
         let total: usize = conn.query_row(
-            "SELECT COUNT(*) FROM conversations WHERE is_archived = 0",
-            [],
+            "SELECT COUNT(*) FROM conversations WHERE (?1 OR is_archived = 0)",
+            params![include_archived],
             |row| row.get(0),
         )?;
 
-        let messages: usize =
-            conn.query_row("SELECT COUNT(*) FROM messages", [], |row| row.get(0))?;
+        let messages: usize = conn.query_row(
+            "SELECT COUNT(*) FROM messages
+             WHERE ?1 OR conversation_id IN (SELECT id FROM conversations WHERE is_archived = 0)",
+            params![include_archived],
+            |row| row.get(0),
+        )?;
 
         let favorites: usize = conn.query_row(
-            "SELECT COUNT(*) FROM conversations WHERE is_favorite = 1",
-            [],
+            "SELECT COUNT(*) FROM conversations WHERE is_favorite = 1 AND (?1 OR is_archived = 0)",
+            params![include_archived],
             |row| row.get(0),
         )?;
 
         Ok((total, messages, favorites))
     }
 
+    /// Conversations started and messages sent per day, oldest first.
+    ///
+    /// Conversations count on the day of their first message; anything without
+    /// a timestamp falls back to when it was imported.
+    pub fn get_daily_activity(&self, include_archived: bool) -> Result<Vec<DayActivity>> {
+        let conn = self.conn.lock().unwrap();
+        let mut days: std::collections::BTreeMap<String, DayActivity> = Default::default();
+
+        let mut stmt = conn.prepare(
+            "SELECT substr(COALESCE((SELECT MIN(m.created_at) FROM messages m
+                                     WHERE m.conversation_id = c.id), c.imported_at), 1, 10) AS day,
+                    COUNT(*)
+             FROM conversations c
+             WHERE ?1 OR c.is_archived = 0
+             GROUP BY day",
+        )?;
+        let rows = stmt.query_map(params![include_archived], |row| {
+            Ok((row.get::<_, Option<String>>(0)?, row.get::<_, usize>(1)?))
+        })?;
+        for row in rows {
+            let (day, count) = row?;
+            if let Some(day) = day {
+                let entry = days
+                    .entry(day)
+                    .or_insert_with_key(|d| DayActivity::new(d.clone()));
+                entry.conversations = count;
+            }
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT substr(COALESCE(m.created_at, c.imported_at), 1, 10) AS day, COUNT(*)
+             FROM messages m JOIN conversations c ON c.id = m.conversation_id
+             WHERE ?1 OR c.is_archived = 0
+             GROUP BY day",
+        )?;
+        let rows = stmt.query_map(params![include_archived], |row| {
+            Ok((row.get::<_, Option<String>>(0)?, row.get::<_, usize>(1)?))
+        })?;
+        for row in rows {
+            let (day, count) = row?;
+            if let Some(day) = day {
+                let entry = days
+                    .entry(day)
+                    .or_insert_with_key(|d| DayActivity::new(d.clone()));
+                entry.messages = count;
+            }
+        }
+
+        Ok(days.into_values().collect())
+    }
+
+    /// Conversation count per source version, largest first
+    pub fn get_version_counts(&self, include_archived: bool) -> Result<Vec<(String, usize)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT source_version, COUNT(*) FROM conversations
+             WHERE ?1 OR is_archived = 0
+             GROUP BY source_version
+             ORDER BY COUNT(*) DESC, source_version",
+        )?;
+        let rows = stmt.query_map(params![include_archived], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    /// Conversation count for every source version, archived chats included
+    pub fn conversation_counts_by_version(&self) -> Result<HashMap<String, usize>> {
+        Ok(self.get_version_counts(true)?.into_iter().collect())
+    }
+
     /// Get detailed message type statistics
     pub fn get_detailed_stats(&self) -> Result<MessageStats> {
         let conn = self.conn.lock().unwrap();
@@ -851,6 +2115,290 @@ impl ChatDatabase {
         Ok(())
     }
 
+    pub fn set_favorite(&self, conversation_id: &str, favorite: bool) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE conversations SET is_favorite = ? WHERE id = ?",
+            params![favorite, conversation_id],
+        )?;
+        Ok(())
+    }
+
+    /// Add a user tag to a conversation (no-op if already tagged)
+    pub fn add_tag(&self, conversation_id: &str, tag: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR IGNORE INTO tags (conversation_id, tag) VALUES (?, ?)",
+            params![conversation_id, tag],
+        )?;
+        Ok(())
+    }
+
+    /// Remove a tag from a conversation, including detected language tags
+    pub fn remove_tag(&self, conversation_id: &str, tag: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM tags WHERE conversation_id = ? AND tag = ?",
+            params![conversation_id, tag],
+        )?;
+        Ok(())
+    }
+
+    /// A conversation's tags in the order they were added
+    pub fn tags_for_conversation(&self, conversation_id: &str) -> Result<Vec<String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt =
+            conn.prepare("SELECT tag FROM tags WHERE conversation_id = ? ORDER BY rowid")?;
+        let tags = stmt.query_map(params![conversation_id], |row| row.get(0))?;
+        tags.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    /// Every conversation carrying `tag`, archived ones included
+    pub fn conversations_with_tag(&self, tag: &str) -> Result<Vec<Conversation>> {
+        self.get_conversations(usize::MAX, true, Some(tag))
+    }
+
+    /// Detect the conversation's main programming languages and replace its
+    /// `lang:` tags with them. Returns the detected languages.
+    pub fn tag_languages(&self, conversation_id: &str) -> Result<Vec<String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT content FROM messages
+             WHERE conversation_id = ? AND content LIKE '%```%'
+             ORDER BY sequence",
+        )?;
+        let contents = stmt
+            .query_map(params![conversation_id], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+        let languages = detect_languages(&contents);
+
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM tags WHERE conversation_id = ? AND substr(tag, 1, ?) = ?",
+            params![
+                conversation_id,
+                LANGUAGE_TAG_PREFIX.len(),
+                LANGUAGE_TAG_PREFIX
+            ],
+        )?;
+        for language in &languages {
+            tx.execute(
+                "INSERT OR IGNORE INTO tags (conversation_id, tag) VALUES (?, ?)",
+                params![conversation_id, language_tag(language)],
+            )?;
+        }
+        tx.execute(
+            "UPDATE conversations SET languages_scanned = 1 WHERE id = ?",
+            params![conversation_id],
+        )?;
+        tx.commit()?;
+        Ok(languages)
+    }
+
+    /// Tag languages on conversations that haven't been scanned yet, e.g. after
+    /// an import. Conversations are only scanned once, so language tags the
+    /// user removed stay removed. Returns how many conversations got a tag.
+    pub fn tag_unscanned_languages(&self) -> Result<usize> {
+        let ids: Vec<String> = {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn
+                .prepare("SELECT id FROM conversations WHERE COALESCE(languages_scanned, 0) = 0")?;
+            let ids = stmt.query_map([], |row| row.get(0))?;
+            ids.collect::<rusqlite::Result<_>>()?
+        };
+
+        let mut tagged = 0;
+        for id in ids {
+            if !self.tag_languages(&id)?.is_empty() {
+                tagged += 1;
+            }
+        }
+        Ok(tagged)
+    }
+
+    /// Set the conversation's note; a blank note clears it
+    pub fn set_conversation_note(&self, conversation_id: &str, note: &str) -> Result<()> {
+        let note = Some(note).filter(|n| !n.trim().is_empty());
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE conversations SET notes = ? WHERE id = ?",
+            params![note, conversation_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_conversation_note(&self, conversation_id: &str) -> Result<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+        let note = conn.query_row(
+            "SELECT notes FROM conversations WHERE id = ?",
+            params![conversation_id],
+            |row| row.get(0),
+        )?;
+        Ok(note)
+    }
+
+    /// Permanently delete a conversation with its messages, segments, bookmarks, tags and title
+    pub fn delete_conversation(&self, conversation_id: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM messages WHERE conversation_id = ?",
+            params![conversation_id],
+        )?;
+        tx.execute(
+            "DELETE FROM request_segments WHERE conversation_id = ?",
+            params![conversation_id],
+        )?;
+        tx.execute(
+            "DELETE FROM bookmarks WHERE conversation_id = ?",
+            params![conversation_id],
+        )?;
+        tx.execute(
+            "DELETE FROM conversation_titles WHERE conversation_id = ?",
+            params![conversation_id],
+        )?;
+        tx.execute(
+            "DELETE FROM tags WHERE conversation_id = ?",
+            params![conversation_id],
+        )?;
+        tx.execute(
+            "DELETE FROM conversations WHERE id = ?",
+            params![conversation_id],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Give a conversation its own title; an empty title restores the imported one.
+    ///
+    /// Like bookmarks, titles are kept through cache clears and reapplied on reimport.
+    pub fn rename_conversation(&self, conversation_id: &str, new_title: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let new_title = new_title.trim();
+        if new_title.is_empty() {
+            conn.execute(
+                "DELETE FROM conversation_titles WHERE conversation_id = ?",
+                params![conversation_id],
+            )?;
+        } else {
+            conn.execute(
+                "INSERT OR REPLACE INTO conversation_titles (conversation_id, title) VALUES (?, ?)",
+                params![conversation_id, new_title],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Archive or unarchive a conversation (hidden from the default list, still searchable)
+    pub fn set_archived(&self, conversation_id: &str, archived: bool) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE conversations SET is_archived = ? WHERE id = ?",
+            params![archived, conversation_id],
+        )?;
+        Ok(())
+    }
+
+    // ==================== SEARCH & REPLACE ====================
+
+    /// What replacing `pattern` in the given conversations' messages would change
+    pub fn preview_replace(
+        &self,
+        conversation_ids: &[String],
+        pattern: &Regex,
+        replacement: &str,
+    ) -> Result<ReplacePreview> {
+        let conn = self.conn.lock().unwrap();
+        let edits = replacement_edits(&conn, conversation_ids, pattern, replacement)?;
+
+        let mut preview = ReplacePreview {
+            messages: edits.len(),
+            conversations: edits
+                .iter()
+                .map(|e| e.conversation_id.as_str())
+                .collect::<std::collections::HashSet<_>>()
+                .len(),
+            ..Default::default()
+        };
+        for edit in &edits {
+            preview.matches += edit.matches;
+            if preview.samples.len() < REPLACE_SAMPLES {
+                let sample = replace_sample(edit, pattern, replacement);
+                preview.samples.push(sample);
+            }
+        }
+        Ok(preview)
+    }
+
+    /// Replace `pattern` in the given conversations' messages in one
+    /// transaction, backing up the original text.
+    ///
+    /// Returns the backup batch id (for `undo_replace`) and the number of
+    /// messages changed; no batch is recorded when nothing matched.
+    pub fn apply_replace(
+        &self,
+        conversation_ids: &[String],
+        pattern: &Regex,
+        replacement: &str,
+    ) -> Result<(Option<String>, usize)> {
+        let conn = self.conn.lock().unwrap();
+        let edits = replacement_edits(&conn, conversation_ids, pattern, replacement)?;
+        if edits.is_empty() {
+            return Ok((None, 0));
+        }
+
+        let batch_id = uuid::Uuid::new_v4().to_string();
+        let tx = conn.unchecked_transaction()?;
+        for edit in &edits {
+            tx.execute(
+                "INSERT INTO message_backups (batch_id, message_id, content) VALUES (?, ?, ?)",
+                params![batch_id, edit.message_id, edit.before],
+            )?;
+            tx.execute(
+                "UPDATE messages SET content = ?, word_count = ? WHERE id = ?",
+                params![edit.after, count_words(&edit.after), edit.message_id],
+            )?;
+        }
+        tx.commit()?;
+        Ok((Some(batch_id), edits.len()))
+    }
+
+    /// The most recent replace batch still available to undo, with its message count
+    pub fn last_replace_batch(&self) -> Result<Option<(String, usize)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT batch_id, COUNT(*) FROM message_backups
+             GROUP BY batch_id ORDER BY MAX(rowid) DESC LIMIT 1",
+        )?;
+        let mut rows = stmt.query([])?;
+        match rows.next()? {
+            Some(row) => Ok(Some((row.get(0)?, row.get::<_, i64>(1)? as usize))),
+            None => Ok(None),
+        }
+    }
+
+    /// Put back the message text saved by a replace batch and drop the backup
+    pub fn undo_replace(&self, batch_id: &str) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        // Counts are cleared here and taken again from the restored text below
+        let restored = tx.execute(
+            "UPDATE messages SET content = (
+                 SELECT b.content FROM message_backups b
+                 WHERE b.batch_id = ?1 AND b.message_id = messages.id
+             ), word_count = NULL
+             WHERE id IN (SELECT message_id FROM message_backups WHERE batch_id = ?1)",
+            params![batch_id],
+        )?;
+        tx.execute(
+            "DELETE FROM message_backups WHERE batch_id = ?",
+            params![batch_id],
+        )?;
+        tx.commit()?;
+        backfill_word_counts(&conn)?;
+        Ok(restored)
+    }
+
     /// Clear all imported data (for re-import)
     pub fn clear_all(&self) -> Result<()> {
         let conn = self.conn.lock().unwrap();
@@ -876,7 +2424,10 @@ impl ChatDatabase {
 
         conn.execute("DELETE FROM messages", [])?;
         conn.execute("DELETE FROM conversations", [])?;
-        // Note: Bookmarks are NOT cleared - they persist across cache clears
+        // So the re-import reads every source from the start
+        conn.execute("DELETE FROM import_state", [])?;
+        conn.execute("DELETE FROM import_retries", [])?;
+        // Note: Bookmarks and tags are NOT cleared - they persist across cache clears
         Ok(())
     }
 
@@ -954,6 +2505,10 @@ impl ChatDatabase {
 
     // ==================== BOOKMARK METHODS ====================
 
+    /// Bookmark a message, or update the label and note of its existing
+    /// bookmark - each message carries at most one.
+    ///
+    /// Returns the bookmark id.
     pub fn add_bookmark(
         &self,
         conv_id: &str,
@@ -964,15 +2519,79 @@ impl ChatDatabase {
         color: &str,
     ) -> Result<String> {
         let conn = self.conn.lock().unwrap();
-        let id = uuid::Uuid::new_v4().to_string();
+        upsert_bookmark(&conn, conv_id, msg_id, msg_seq, label, note, color)
+    }
 
-        conn.execute(
-            "INSERT INTO bookmarks (id, conversation_id, message_id, message_sequence, label, note, color)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            params![id, conv_id, msg_id, msg_seq, label, note, color],
-        )?;
+    /// All bookmarks as JSON for `import_bookmarks` on another machine
+    pub fn export_bookmarks(&self) -> Result<String> {
+        let bookmarks = self
+            .get_all_bookmarks()?
+            .into_iter()
+            .map(|b| BookmarkJson {
+                conversation_id: b.conversation_id,
+                message_id: b.message_id,
+                seq: b.message_sequence,
+                label: b.label,
+                note: b.note,
+                color: b.color,
+            })
+            .collect();
+        Ok(serde_json::to_string_pretty(&BookmarksFile { bookmarks })?)
+    }
+
+    /// Add the bookmarks from `export_bookmarks`. Each goes on the message with
+    /// its id, or failing that the one at its position in the conversation,
+    /// since message ids can differ between imports of the same chat.
+    pub fn import_bookmarks(&self, json: &str) -> Result<BookmarkImport> {
+        let file: BookmarksFile =
+            serde_json::from_str(json).context("Not a bookmarks export")?;
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        let mut report = BookmarkImport::default();
+
+        for bookmark in file.bookmarks {
+            let by_id: Option<String> = tx
+                .query_row(
+                    "SELECT id FROM messages WHERE conversation_id = ?1 AND id = ?2",
+                    params![bookmark.conversation_id, bookmark.message_id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            let message = match by_id {
+                Some(id) => Some(id),
+                None => tx
+                    .query_row(
+                        "SELECT id FROM messages WHERE conversation_id = ?1 AND sequence = ?2
+                         LIMIT 1",
+                        params![bookmark.conversation_id, bookmark.seq],
+                        |row| row.get(0),
+                    )
+                    .optional()?,
+            };
+
+            let message_id = match message {
+                Some(id) => {
+                    report.attached += 1;
+                    id
+                }
+                None => {
+                    report.orphaned += 1;
+                    bookmark.message_id
+                }
+            };
+            upsert_bookmark(
+                &tx,
+                &bookmark.conversation_id,
+                &message_id,
+                bookmark.seq,
+                bookmark.label.as_deref(),
+                bookmark.note.as_deref(),
+                &bookmark.color,
+            )?;
+        }
 
-        Ok(id)
+        tx.commit()?;
+        Ok(report)
     }
 
     pub fn get_bookmarks(&self, conv_id: &str) -> Result<Vec<Bookmark>> {
@@ -1083,10 +2702,14 @@ impl ChatDatabase {
                 .ok();
 
             if let Some(msg_id) = new_msg_id {
-                conn.execute(
-                    "UPDATE bookmarks SET message_id = ?1 WHERE id = ?2",
+                let updated = conn.execute(
+                    "UPDATE OR IGNORE bookmarks SET message_id = ?1 WHERE id = ?2",
                     params![msg_id, bookmark_id],
                 )?;
+                if updated == 0 {
+                    // The message already has a bookmark; this one is redundant
+                    conn.execute("DELETE FROM bookmarks WHERE id = ?1", [&bookmark_id])?;
+                }
                 results.push((bookmark_id, true));
             } else {
                 results.push((bookmark_id, false));
@@ -1101,7 +2724,8 @@ impl ChatDatabase {
     pub fn get_display_preferences(&self) -> Result<Vec<DisplayPreference>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT content_type, alignment, style, collapsed_by_default FROM display_preferences",
+            "SELECT content_type, alignment, style, collapsed_by_default, fg_color, bg_color
+             FROM display_preferences",
         )?;
 
         let rows = stmt.query_map([], |row| {
@@ -1110,6 +2734,8 @@ impl ChatDatabase {
                 alignment: row.get(1)?,
                 style: row.get(2)?,
                 collapsed_by_default: row.get::<_, i32>(3)? != 0,
+                fg_color: row.get(4)?,
+                bg_color: row.get(5)?,
             })
         })?;
 
@@ -1122,128 +2748,126 @@ impl ChatDatabase {
         alignment: &str,
         style: &str,
         collapsed: bool,
+        fg_color: Option<&str>,
+        bg_color: Option<&str>,
     ) -> Result<()> {
         let conn = self.conn.lock().unwrap();
         conn.execute(
-            "INSERT OR REPLACE INTO display_preferences (content_type, alignment, style, collapsed_by_default)
-             VALUES (?1, ?2, ?3, ?4)",
-            params![content_type, alignment, style, collapsed as i32],
+            "INSERT OR REPLACE INTO display_preferences
+             (content_type, alignment, style, collapsed_by_default, fg_color, bg_color)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                content_type,
+                alignment,
+                style,
+                collapsed as i32,
+                fg_color,
+                bg_color
+            ],
         )?;
         Ok(())
     }
 
-    pub fn import_from_cursor(&self, db_path: PathBuf, version: &str) -> Result<(usize, usize)> {
-        let src_conn =
-            Connection::open_with_flags(&db_path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)?;
-
-        let mut imported = 0;
-        let mut skipped = 0;
+    /// Import every conversation in a Cursor `state.vscdb`.
+    ///
+    /// Reads are retried while Cursor has the database locked, falling back to
+    /// its last checkpoint if the lock persists. Unreadable rows are skipped
+    /// and counted so a damaged database still yields the conversations that
+    /// survive.
+    pub fn import_from_cursor(&self, db_path: PathBuf, version: &str) -> Result<CursorImport> {
+        let (src_conn, locked) = open_cursor_source(&db_path)?;
+
+        let mut report = CursorImport {
+            locked,
+            ..Default::default()
+        };
 
-        // Find all conversation IDs from bubbleId keys
-        let mut stmt = src_conn.prepare(
-            "SELECT DISTINCT substr(key, 10, 36) FROM cursorDiskKV WHERE key LIKE 'bubbleId:%'",
-        )?;
-        let conv_ids: Vec<String> = stmt
-            .query_map([], |row| row.get(0))?
-            .filter_map(|r| r.ok())
-            .collect();
+        let source = db_path.to_string_lossy().to_string();
+        let changes = self.cursor_changes(&src_conn, &db_path)?;
+        report.lost_conversations += changes.unreadable;
+        report.rows_scanned = changes.rows.iter().map(|(_, count)| count).sum();
 
         let dst_conn = self.conn.lock().unwrap();
+        backfill_content_hashes(&dst_conn)?;
+
+        // The watermark, the conversations and the retry list below all land
+        // together or not at all
+        let tx = dst_conn.unchecked_transaction()?;
+
+        let conv_ids = import_candidates(&tx, &source, &changes)?;
+        tx.execute("DELETE FROM import_retries WHERE source = ?", params![source])?;
 
         for conv_id in conv_ids {
-            let exists: i32 = dst_conn.query_row(
+            let exists: i32 = tx.query_row(
                 "SELECT COUNT(*) FROM conversations WHERE id = ?",
                 params![&conv_id],
                 |row| row.get(0),
             )?;
 
             if exists > 0 {
-                skipped += 1;
+                report.skipped += 1;
                 continue;
             }
 
-            let mut msg_stmt = src_conn
-                .prepare("SELECT key, value FROM cursorDiskKV WHERE key LIKE ? ORDER BY key")?;
-            let pattern = format!("bubbleId:{}:%", conv_id);
-
-            let mut messages: Vec<ParsedMessage> = Vec::new();
-            let mut title_candidates = Vec::new();
-
-            // Query and handle value as either BLOB or TEXT
-            let rows = msg_stmt.query_map(params![pattern], |row| {
-                let key: String = row.get(0)?;
-                let value_bytes: Vec<u8> = row
-                    .get::<_, Vec<u8>>(1)
-                    .or_else(|_| row.get::<_, String>(1).map(|s| s.into_bytes()))?;
-                Ok((key, value_bytes))
-            })?;
-
-            for row_result in rows {
-                let (key, value) = match row_result {
-                    Ok(r) => r,
-                    Err(_) => continue,
-                };
-
-                let msg_id = key.split(':').next_back().unwrap_or("").to_string();
-
-                if let Ok(data) = serde_json::from_slice::<Value>(&value) {
-                    let msg_type = data.get("type").and_then(|v| v.as_i64()).unwrap_or(0);
-
-                    // Determine role based on type and toolFormerData presence
-                    let (base_role, is_tool_call) = if data.get("toolFormerData").is_some() {
-                        ("tool_call", true)
-                    } else if msg_type == 1 {
-                        ("user", false)
-                    } else {
-                        ("assistant", false)
-                    };
-
-                    let (content, tool_call, thinking) = extract_message_content(&data);
-
-                    // Skip completely empty messages (unless they're tool calls)
-                    if content.is_empty() && !is_tool_call && thinking.is_none() {
-                        continue;
-                    }
-
-                    // Collect title candidates from user messages
-                    if base_role == "user" && !content.is_empty() && title_candidates.len() < 3 {
-                        title_candidates.push(content.chars().take(100).collect::<String>());
-                    }
-
-                    messages.push((
-                        msg_id,
-                        base_role.to_string(),
-                        content,
-                        messages.len(),
-                        tool_call,
-                        thinking,
-                    ));
+            let conv = match read_cursor_conversation(&src_conn, &conv_id) {
+                Ok(conv) => conv,
+                Err(e) => {
+                    log::warn!("Skipping unreadable conversation {}: {}", conv_id, e);
+                    report.lost_conversations += 1;
+                    tx.execute(
+                        "INSERT OR IGNORE INTO import_retries (source, conversation_id) VALUES (?, ?)",
+                        params![source, conv_id],
+                    )?;
+                    continue;
+                }
+            };
+            report.lost_messages += conv.lost;
+            if conv.messages.is_empty() {
+                if conv.lost > 0 {
+                    report.lost_conversations += 1;
                 }
+                continue;
             }
-
-            if messages.is_empty() {
+            let ParsedConversation { title, messages, .. } = conv;
+
+            let hash = content_hash(
+                &title,
+                messages.iter().map(|m| (m.1.as_str(), m.2.as_str())),
+            );
+            if has_content_hash(&tx, &hash)? {
+                report.duplicates += 1;
                 continue;
             }
 
-            let title = title_candidates
-                .first()
-                .map(|t| {
-                    let truncated: String = t.chars().take(60).collect();
-                    if truncated.len() < t.len() {
-                        format!("{}...", truncated)
-                    } else {
-                        truncated
-                    }
-                })
-                .unwrap_or_else(|| "Untitled".to_string());
-
-            dst_conn.execute(
-                "INSERT INTO conversations (id, source_version, original_title, message_count, category_id, imported_at)
-                 VALUES (?, ?, ?, ?, 1, datetime('now'))",
-                params![conv_id, version, title, messages.len()],
+            tx.execute(
+                "INSERT INTO conversations (id, source_version, original_title, message_count, category_id, content_hash, imported_at)
+                 VALUES (?, ?, ?, ?, 1, ?, datetime('now'))",
+                params![conv_id, version, title, messages.len(), hash],
             )?;
 
-            for (msg_id, role, content, seq, tool_call, thinking) in messages {
+            // Edits of the same message become numbered branches in import order
+            let mut branch_counts: HashMap<String, usize> = HashMap::new();
+
+            for (
+                msg_id,
+                role,
+                content,
+                seq,
+                tool_call,
+                thinking,
+                created_at,
+                parent_id,
+                tool_call_id,
+            ) in messages
+            {
+                let branch = match &parent_id {
+                    Some(parent) => {
+                        let count = branch_counts.entry(parent.clone()).or_insert(0);
+                        *count += 1;
+                        *count - 1
+                    }
+                    None => 0,
+                };
                 let (tool_name, tool_args, tool_status) = match &tool_call {
                     Some(tc) => (
                         Some(tc.name.clone()),
@@ -1253,17 +2877,172 @@ impl ChatDatabase {
                     None => (None, None, None),
                 };
 
-                dst_conn.execute(
-                    "INSERT OR IGNORE INTO messages (id, conversation_id, sequence, role, content, tool_name, tool_args, tool_status, thinking)
-                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
-                    params![msg_id, conv_id, seq, role, content, tool_name, tool_args, tool_status, thinking],
+                tx.execute(
+                    "INSERT OR IGNORE INTO messages (id, conversation_id, sequence, role, content, tool_name, tool_args, tool_status, thinking, created_at, parent_id, branch, tool_call_id, word_count)
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                    params![msg_id, conv_id, seq, role, content, tool_name, tool_args, tool_status, thinking, created_at, parent_id, branch, tool_call_id, count_words(&content)],
                 )?;
             }
 
-            imported += 1;
+            report.imported += 1;
+            report.imported_ids.push(conv_id);
+        }
+
+        if report.duplicates > 0 {
+            log::info!(
+                "Skipped {} conversations from {} already in the library under another id",
+                report.duplicates,
+                db_path.display()
+            );
+        }
+        if report.is_damaged() {
+            log::warn!(
+                "Salvaged {} conversations from {}; lost {} conversations and {} messages",
+                report.imported,
+                db_path.display(),
+                report.lost_conversations,
+                report.lost_messages
+            );
+        }
+
+        // Cursor replaces a row to change it, so bubbles that didn't parse
+        // only come back above the watermark; read errors were recorded above
+        tx.execute(
+            "INSERT OR REPLACE INTO import_state (source, last_rowid, updated_at)
+             VALUES (?, ?, datetime('now'))",
+            params![source, changes.max_rowid],
+        )?;
+        tx.commit()?;
+        Ok(report)
+    }
+
+    /// Conversations with bubbles written to a Cursor database since its last
+    /// import, read without holding the library lock.
+    ///
+    /// Cursor rewrites a bubble by replacing its row, so anything new or
+    /// changed since the last import has a rowid above the watermark. A
+    /// rebuilt (vacuumed) database can renumber its rows below it; then
+    /// everything is read again.
+    fn cursor_changes(&self, src_conn: &Connection, db_path: &Path) -> Result<CursorChanges> {
+        let mut watermark = self.last_import_watermark(db_path)?.unwrap_or(0);
+        let max_rowid: i64 = retry_while_busy(|| {
+            src_conn.query_row(
+                "SELECT COALESCE(MAX(rowid), 0) FROM cursorDiskKV",
+                [],
+                |row| row.get(0),
+            )
+        })?;
+        if max_rowid < watermark {
+            log::info!("{} was rebuilt since the last import; rescanning", db_path.display());
+            watermark = 0;
+        }
+
+        let (rows, unreadable) = retry_while_busy(|| {
+            let mut stmt = src_conn.prepare(
+                "SELECT substr(key, 10, 36), COUNT(*) FROM cursorDiskKV
+                 WHERE key LIKE 'bubbleId:%' AND rowid > ?
+                 GROUP BY 1",
+            )?;
+            let rows = stmt.query_map(params![watermark], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, usize>(1)?))
+            })?;
+            read_rows(rows)
+        })?;
+        Ok(CursorChanges {
+            rows,
+            unreadable,
+            max_rowid,
+        })
+    }
+
+    /// Newest `cursorDiskKV` rowid imported from a Cursor database, if it was
+    /// imported before
+    pub fn last_import_watermark(&self, source: &Path) -> Result<Option<i64>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT last_rowid FROM import_state WHERE source = ?",
+            params![source.to_string_lossy()],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(Into::into)
+    }
+
+    /// Scan a Cursor database without writing anything.
+    ///
+    /// Looks at the same conversations `import_from_cursor` would: those
+    /// changed since the last import and ones queued for a retry. New ones are
+    /// parsed, so a chat already in the library under another id counts as
+    /// existing, and their message counts match what the import would add.
+    pub fn preview_import_from_cursor(
+        &self,
+        db_path: &Path,
+        version: &str,
+    ) -> Result<ImportPreview> {
+        let (src_conn, locked) = open_cursor_source(db_path)?;
+        let changes = self.cursor_changes(&src_conn, db_path)?;
+
+        let mut preview = ImportPreview {
+            version: version.to_string(),
+            path: db_path.to_path_buf(),
+            locked,
+            ..Default::default()
+        };
+
+        let source = db_path.to_string_lossy().to_string();
+        let dst_conn = self.conn.lock().unwrap();
+        for conv_id in import_candidates(&dst_conn, &source, &changes)? {
+            let rows = changes
+                .rows
+                .iter()
+                .find(|(id, _)| *id == conv_id)
+                .map_or(0, |(_, count)| *count);
+            preview.conversations += 1;
+            preview.messages += rows;
+
+            let exists: i32 = dst_conn.query_row(
+                "SELECT COUNT(*) FROM conversations WHERE id = ?",
+                params![&conv_id],
+                |row| row.get(0),
+            )?;
+            if exists > 0 {
+                preview.existing_conversations += 1;
+                continue;
+            }
+
+            let conv = read_cursor_conversation(&src_conn, &conv_id)?;
+            if conv.messages.is_empty() {
+                continue;
+            }
+            let hash = content_hash(
+                &conv.title,
+                conv.messages.iter().map(|m| (m.1.as_str(), m.2.as_str())),
+            );
+            if has_content_hash(&dst_conn, &hash)? {
+                preview.existing_conversations += 1;
+            } else {
+                preview.new_conversations += 1;
+                preview.new_messages += conv.messages.len();
+            }
         }
 
-        Ok((imported, skipped))
+        Ok(preview)
+    }
+
+    /// Preview an import of every discovered Cursor database
+    pub fn preview_import_all(&self) -> Vec<ImportPreview> {
+        discover_cursor_databases()
+            .into_iter()
+            .map(|(path, version)| {
+                self.preview_import_from_cursor(&path, &version)
+                    .unwrap_or_else(|e| ImportPreview {
+                        version,
+                        path,
+                        error: Some(e.to_string()),
+                        ..Default::default()
+                    })
+            })
+            .collect()
     }
 
     pub fn import_all(&self) -> Result<(usize, usize)> {
@@ -1273,9 +3052,9 @@ impl ChatDatabase {
 
         let main_db = home.join(".config/Cursor/User/globalStorage/state.vscdb");
         if main_db.exists() {
-            let (i, s) = self.import_from_cursor(main_db, "default")?;
-            total_imported += i;
-            total_skipped += s;
+            let report = self.import_from_cursor(main_db, "default")?;
+            total_imported += report.imported;
+            total_skipped += report.skipped;
         }
 
         for entry in std::fs::read_dir(&home)? {
@@ -1287,15 +3066,388 @@ impl ChatDatabase {
                 let db_path = entry.path().join("User/globalStorage/state.vscdb");
 
                 if db_path.exists() {
-                    let (i, s) = self.import_from_cursor(db_path, &version)?;
-                    total_imported += i;
-                    total_skipped += s;
+                    let report = self.import_from_cursor(db_path, &version)?;
+                    total_imported += report.imported;
+                    total_skipped += report.skipped;
                 }
             }
         }
 
         Ok((total_imported, total_skipped))
     }
+
+    /// Import a ChatGPT or Claude `conversations.json`, or a directory holding one.
+    ///
+    /// Conversations are tagged with their source; ones already imported, by id
+    /// or with the same content, are skipped.
+    pub fn import_chat_export(&self, path: &Path) -> Result<(ExportSource, usize, usize)> {
+        let file = if path.is_dir() {
+            path.join("conversations.json")
+        } else {
+            path.to_path_buf()
+        };
+        let text = std::fs::read_to_string(&file)
+            .with_context(|| format!("Failed to read {}", file.display()))?;
+        let (source, conversations) = parse_export(&text)?;
+
+        let conn = self.conn.lock().unwrap();
+        backfill_content_hashes(&conn)?;
+        // All or nothing: a record that fails to insert leaves the library as it was
+        let tx = conn.unchecked_transaction()?;
+        let mut imported = 0;
+        let mut skipped = 0;
+
+        let role_name = |role: &MessageRole| match role {
+            MessageRole::Assistant => "assistant",
+            _ => "user",
+        };
+        for conv in conversations {
+            let exists: i32 = tx.query_row(
+                "SELECT COUNT(*) FROM conversations WHERE id = ?",
+                params![&conv.id],
+                |row| row.get(0),
+            )?;
+            let messages = conv.messages.iter();
+            let hash = content_hash(
+                &conv.title,
+                messages.map(|m| (role_name(&m.role), m.content.as_str())),
+            );
+            if exists > 0 || has_content_hash(&tx, &hash)? {
+                skipped += 1;
+                continue;
+            }
+
+            tx.execute(
+                "INSERT INTO conversations (id, source_version, original_title, message_count, category_id, content_hash, imported_at)
+                 VALUES (?, ?, ?, ?, 1, ?, datetime('now'))",
+                params![conv.id, source.label(), conv.title, conv.messages.len(), hash],
+            )?;
+            tx.execute(
+                "INSERT OR IGNORE INTO tags (conversation_id, tag) VALUES (?, ?)",
+                params![conv.id, source.tag()],
+            )?;
+
+            for (seq, msg) in conv.messages.iter().enumerate() {
+                let role = role_name(&msg.role);
+                tx.execute(
+                    "INSERT OR IGNORE INTO messages (id, conversation_id, sequence, role, content, created_at, word_count)
+                     VALUES (?, ?, ?, ?, ?, ?, ?)",
+                    params![msg.id, conv.id, seq, role, msg.content, msg.created_at, count_words(&msg.content)],
+                )?;
+            }
+
+            imported += 1;
+        }
+        tx.commit()?;
+
+        Ok((source, imported, skipped))
+    }
+
+    /// Insert a conversation in the `ConversationJson` format, e.g. pasted from the clipboard.
+    ///
+    /// Returns the conversation id and whether it was new; one already in the
+    /// library is left as it is.
+    pub fn import_conversation_json(&self, text: &str) -> Result<(String, bool)> {
+        let pasted: ConversationJson =
+            serde_json::from_str(text.trim()).context("Not a conversation copied as JSON")?;
+        let conv = &pasted.conversation;
+        if conv.id.trim().is_empty() {
+            anyhow::bail!("Conversation has no id");
+        }
+        let mut messages = pasted.messages.iter();
+        if let Some(msg) = messages.find(|m| m.conversation_id != conv.id) {
+            anyhow::bail!(
+                "Message {} belongs to conversation {}, not {}",
+                msg.id,
+                msg.conversation_id,
+                conv.id
+            );
+        }
+
+        let conn = self.conn.lock().unwrap();
+        let exists: i32 = conn.query_row(
+            "SELECT COUNT(*) FROM conversations WHERE id = ?",
+            params![&conv.id],
+            |row| row.get(0),
+        )?;
+        if exists > 0 {
+            return Ok((conv.id.clone(), false));
+        }
+
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT INTO conversations (id, source_version, original_title, message_count, category_id, is_favorite, is_archived, notes, imported_at)
+             VALUES (?, ?, ?, ?, COALESCE((SELECT id FROM categories WHERE name = ?), 1), ?, ?, ?, datetime('now'))",
+            params![
+                conv.id,
+                conv.source_version,
+                conv.title,
+                pasted.messages.len(),
+                conv.category,
+                conv.is_favorite,
+                conv.is_archived,
+                conv.notes
+            ],
+        )?;
+        for tag in &conv.user_tags {
+            tx.execute(
+                "INSERT OR IGNORE INTO tags (conversation_id, tag) VALUES (?, ?)",
+                params![conv.id, tag],
+            )?;
+        }
+        for msg in &pasted.messages {
+            let tool_call = msg.tool_call.as_ref();
+            tx.execute(
+                "INSERT OR IGNORE INTO messages (id, conversation_id, sequence, role, content, tool_name, tool_args, tool_status, thinking, content_type, has_code_blocks, has_terminal_output, files_edited, created_at, parent_id, branch, tool_call_id, word_count)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    msg.id,
+                    conv.id,
+                    msg.sequence,
+                    msg.role.as_str(),
+                    msg.content,
+                    tool_call.map(|tc| &tc.name),
+                    tool_call.map(|tc| &tc.args),
+                    tool_call.map(|tc| &tc.status),
+                    msg.thinking,
+                    msg.content_type.as_str(),
+                    msg.has_code_blocks,
+                    msg.has_terminal_output,
+                    serde_json::to_string(&msg.files_edited)?,
+                    msg.created_at,
+                    msg.parent_id,
+                    msg.branch,
+                    msg.tool_call_id,
+                    count_words(&msg.content)
+                ],
+            )?;
+        }
+        tx.commit()?;
+
+        Ok((conv.id.clone(), true))
+    }
+
+    /// Write the given conversations to a standalone database with the same schema.
+    ///
+    /// Messages, bookmarks, request segments and tags go along; categories are
+    /// local to each library, so exported chats land in Uncategorized.
+    pub fn export_to_sqlite(&self, conversation_ids: &[String], dest: &Path) -> Result<usize> {
+        if dest.exists() {
+            std::fs::remove_file(dest)?;
+        }
+        let export = Connection::open(dest)?;
+        export.execute_batch(SCHEMA)?;
+        record_schema_version(&export, CURRENT_SCHEMA_VERSION)?;
+        drop(export);
+
+        let conn = self.conn.lock().unwrap();
+        attach(&conn, dest, "export")?;
+        let result = copy_conversations(&conn, "main", "export", conversation_ids);
+        conn.execute("DETACH DATABASE export", [])?;
+        Ok(result?.0)
+    }
+
+    /// Import every conversation from a database written by `export_to_sqlite`.
+    ///
+    /// Returns (imported, skipped); conversations already in the library are skipped.
+    pub fn import_from_sqlite(&self, src: &Path) -> Result<(usize, usize)> {
+        let conn = self.conn.lock().unwrap();
+        attach(&conn, src, "import")?;
+        let ids = schema_version(&conn, "import").and_then(|_| {
+            conversation_ids_in(&conn, "import").context("Not a Cursor Studio database")
+        });
+        let result = ids.and_then(|ids| copy_conversations(&conn, "import", "main", &ids));
+        conn.execute("DETACH DATABASE import", [])?;
+        // Exports from before word counts were stored don't carry them
+        backfill_word_counts(&conn)?;
+        result
+    }
+}
+
+/// Excerpts kept in a `ReplacePreview`
+const REPLACE_SAMPLES: usize = 5;
+
+/// Characters of context shown either side of a match in a preview sample
+const SAMPLE_CONTEXT: usize = 40;
+
+/// A message whose content a search-and-replace would change
+struct ReplaceEdit {
+    message_id: String,
+    conversation_id: String,
+    before: String,
+    after: String,
+    matches: usize,
+}
+
+fn replacement_edits(
+    conn: &Connection,
+    conversation_ids: &[String],
+    pattern: &Regex,
+    replacement: &str,
+) -> Result<Vec<ReplaceEdit>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, content FROM messages
+         WHERE conversation_id = ? AND content IS NOT NULL
+         ORDER BY sequence",
+    )?;
+
+    let mut edits = Vec::new();
+    for conversation_id in conversation_ids {
+        let mut rows = stmt.query(params![conversation_id])?;
+        while let Some(row) = rows.next()? {
+            let before: String = row.get(1)?;
+            let matches = pattern.find_iter(&before).count();
+            if matches == 0 {
+                continue;
+            }
+            let after = pattern.replace_all(&before, replacement).into_owned();
+            if after == before {
+                continue;
+            }
+            edits.push(ReplaceEdit {
+                message_id: row.get(0)?,
+                conversation_id: conversation_id.clone(),
+                before,
+                after,
+                matches,
+            });
+        }
+    }
+    Ok(edits)
+}
+
+/// Excerpt around the first match of an edit, before and after replacing
+fn replace_sample(edit: &ReplaceEdit, pattern: &Regex, replacement: &str) -> ReplaceSample {
+    let text = &edit.before;
+    let (start, end) = pattern
+        .find(text)
+        .map(|m| (m.start(), m.end()))
+        .unwrap_or((0, 0));
+
+    let mut from = start.saturating_sub(SAMPLE_CONTEXT);
+    while !text.is_char_boundary(from) {
+        from -= 1;
+    }
+    let mut to = (end + SAMPLE_CONTEXT).min(text.len());
+    while !text.is_char_boundary(to) {
+        to += 1;
+    }
+
+    let excerpt = &text[from..to];
+    ReplaceSample {
+        conversation_id: edit.conversation_id.clone(),
+        message_id: edit.message_id.clone(),
+        before: excerpt.to_string(),
+        after: pattern.replace_all(excerpt, replacement).into_owned(),
+    }
+}
+
+/// Tables holding per-conversation rows, copied along with the conversation
+const CONVERSATION_TABLES: &[&str] = &[
+    "messages",
+    "bookmarks",
+    "request_segments",
+    "conversation_titles",
+    "tags",
+];
+
+fn attach(conn: &Connection, path: &Path, schema: &str) -> Result<()> {
+    let path = path.to_str().context("Database path is not valid UTF-8")?;
+    conn.execute(&format!("ATTACH DATABASE ? AS {}", schema), params![path])?;
+    Ok(())
+}
+
+fn conversation_ids_in(conn: &Connection, schema: &str) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("SELECT id FROM {}.conversations", schema))?;
+    let ids = stmt.query_map([], |row| row.get(0))?;
+    ids.collect()
+}
+
+/// Columns of `table` present in both attached databases, as a SQL column list
+fn shared_columns(conn: &Connection, table: &str, from: &str, to: &str) -> Result<String> {
+    let columns = |schema: &str| -> Result<Vec<String>> {
+        let mut stmt = conn.prepare("SELECT name FROM pragma_table_info(?, ?)")?;
+        let names = stmt.query_map(params![table, schema], |row| row.get(0))?;
+        Ok(names.collect::<rusqlite::Result<_>>()?)
+    };
+    let target = columns(to)?;
+    // Category ids only mean something in the library that created them
+    let shared: Vec<String> = columns(from)?
+        .into_iter()
+        .filter(|c| c != "category_id" && target.contains(c))
+        .collect();
+    Ok(shared.join(", "))
+}
+
+/// Copy conversations and their rows between attached databases.
+///
+/// Returns (copied, skipped); conversations already in `to` are skipped.
+fn copy_conversations(
+    conn: &Connection,
+    from: &str,
+    to: &str,
+    conversation_ids: &[String],
+) -> Result<(usize, usize)> {
+    let conv_columns = shared_columns(conn, "conversations", from, to)?;
+    let mut table_columns = Vec::new();
+    for table in CONVERSATION_TABLES {
+        table_columns.push((table, shared_columns(conn, table, from, to)?));
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    let mut copied = 0;
+    let mut skipped = 0;
+    for id in conversation_ids {
+        let exists: i32 = tx.query_row(
+            &format!("SELECT COUNT(*) FROM {}.conversations WHERE id = ?", to),
+            params![id],
+            |row| row.get(0),
+        )?;
+        if exists > 0 {
+            skipped += 1;
+            continue;
+        }
+
+        let inserted = tx.execute(
+            &format!(
+                "INSERT INTO {to}.conversations ({cols}) SELECT {cols} FROM {from}.conversations WHERE id = ?",
+                cols = conv_columns
+            ),
+            params![id],
+        )?;
+        if inserted == 0 {
+            continue;
+        }
+        for (table, cols) in &table_columns {
+            // Missing from an older library
+            if cols.is_empty() {
+                continue;
+            }
+            tx.execute(
+                &format!(
+                    "INSERT OR IGNORE INTO {to}.{table} ({cols}) SELECT {cols} FROM {from}.{table} WHERE conversation_id = ?"
+                ),
+                params![id],
+            )?;
+        }
+        // Exports from before the tags table keep them on the conversation
+        tx.execute(
+            &format!(
+                "INSERT OR IGNORE INTO {to}.tags (conversation_id, tag)
+                 SELECT c.id, j.value FROM {to}.conversations c, json_each(c.user_tags) j
+                 WHERE c.id = ?1 AND json_valid(c.user_tags)"
+            ),
+            params![id],
+        )?;
+        tx.execute(
+            &format!("UPDATE {to}.conversations SET user_tags = '[]' WHERE id = ?"),
+            params![id],
+        )?;
+        copied += 1;
+    }
+    tx.commit()?;
+
+    Ok((copied, skipped))
 }
 
 #[cfg(test)]
@@ -1308,12 +3460,249 @@ mod tests {
         ChatDatabase::new_with_path(temp_dir).unwrap()
     }
 
+    /// Build a minimal Cursor state.vscdb with `count` user bubbles per conversation
+    fn create_test_cursor_db(conversations: &[(&str, usize)]) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("cursor-source-{}.vscdb", uuid::Uuid::new_v4()));
+        let conn = Connection::open(&path).unwrap();
+        conn.execute(
+            "CREATE TABLE cursorDiskKV (key TEXT UNIQUE ON CONFLICT REPLACE, value BLOB)",
+            [],
+        )
+        .unwrap();
+        // Bubble ids are unique across conversations, as Cursor's are
+        let mut bubble = 0;
+        for (conv_id, count) in conversations {
+            for i in 0..*count {
+                bubble += 1;
+                conn.execute(
+                    "INSERT INTO cursorDiskKV (key, value) VALUES (?, ?)",
+                    params![
+                        format!("bubbleId:{}:msg-{:04}", conv_id, bubble - 1),
+                        format!(r#"{{"type": 1, "text": "message {}"}}"#, i)
+                    ],
+                )
+                .unwrap();
+            }
+        }
+        path
+    }
+
     #[test]
     fn test_database_creation() {
         let db = create_test_db();
         assert!(db.get_path().parent().unwrap().exists());
     }
 
+    #[test]
+    fn test_schema_version() {
+        let path = create_test_db().get_path();
+        let conn = Connection::open(&path).unwrap();
+        assert_eq!(schema_version(&conn, "main").unwrap(), CURRENT_SCHEMA_VERSION);
+
+        // A library from before versioning, with a duplicate bookmark to clean up
+        conn.execute_batch(
+            "DROP INDEX idx_bookmark_message;
+             INSERT INTO bookmarks (id, conversation_id, message_id, message_sequence)
+             VALUES ('a', 'conv', 'msg', 1), ('b', 'conv', 'msg', 1);
+             DELETE FROM config WHERE key = 'schema_version';
+             PRAGMA user_version = 0;",
+        )
+        .unwrap();
+        let db = ChatDatabase::open(&path).unwrap();
+        assert_eq!(db.get_bookmarks("conv").unwrap().len(), 1);
+        assert_eq!(schema_version(&conn, "main").unwrap(), CURRENT_SCHEMA_VERSION);
+
+        // Written by a newer build: refused, not touched
+        record_schema_version(&conn, CURRENT_SCHEMA_VERSION + 1).unwrap();
+        let err = ChatDatabase::open(&path).err().unwrap();
+        assert!(err.to_string().contains("newer than"));
+    }
+
+    /// A library as the first versioned build left it, at schema version 1
+    fn create_v1_library() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("cursor-studio-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("studio.db");
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch(V1_LIBRARY)
+            .unwrap();
+        path
+    }
+
+    const V1_LIBRARY: &str = "
+         CREATE TABLE conversations (
+             id TEXT PRIMARY KEY,
+             source_version TEXT NOT NULL,
+             imported_at TEXT DEFAULT (datetime('now')),
+             original_title TEXT,
+             category_id INTEGER DEFAULT 1,
+             user_tags TEXT DEFAULT '[]',
+             message_count INTEGER DEFAULT 0,
+             is_favorite INTEGER DEFAULT 0,
+             content_hash TEXT
+         );
+         CREATE TABLE messages (
+             id TEXT PRIMARY KEY,
+             conversation_id TEXT NOT NULL,
+             sequence INTEGER NOT NULL,
+             role TEXT NOT NULL,
+             content TEXT,
+             tool_name TEXT,
+             content_type TEXT DEFAULT 'text',
+             has_code_blocks INTEGER DEFAULT 0,
+             has_terminal_output INTEGER DEFAULT 0,
+             files_edited TEXT DEFAULT '[]'
+         );
+         CREATE TABLE bookmarks (
+             id TEXT PRIMARY KEY,
+             conversation_id TEXT NOT NULL,
+             message_id TEXT NOT NULL,
+             message_sequence INTEGER NOT NULL,
+             label TEXT,
+             note TEXT,
+             created_at TEXT DEFAULT (datetime('now')),
+             color TEXT DEFAULT '#ffd700'
+         );
+         INSERT INTO conversations (id, source_version, original_title, message_count, is_favorite, user_tags)
+         VALUES ('conv', '0.42.0', 'Old chat', 2, 1, '[\"rust\",\"io\"]');
+         INSERT INTO messages (id, conversation_id, sequence, role, content, tool_name)
+         VALUES ('m1', 'conv', 0, 'user', 'How do I read a file?', NULL),
+                ('m2', 'conv', 1, 'assistant', 'Use std::fs::read_to_string', 'read_file');
+         INSERT INTO bookmarks (id, conversation_id, message_id, message_sequence, label)
+         VALUES ('b1', 'conv', 'm2', 1, 'Answer'), ('b2', 'conv', 'm2', 1, 'Again');
+         PRAGMA user_version = 1;";
+
+    fn assert_v1_data(db: &ChatDatabase) {
+        let conv = db.get_conversation("conv").unwrap().unwrap();
+        assert_eq!(conv.title, "Old chat");
+        assert!(conv.is_favorite);
+        assert!(!conv.is_archived);
+        assert_eq!(conv.user_tags, vec!["rust".to_string(), "io".to_string()]);
+        let messages = db.get_messages("conv").unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1].content, "Use std::fs::read_to_string");
+        let bookmarks = db.get_bookmarks("conv").unwrap();
+        assert_eq!(bookmarks.len(), 1);
+        assert_eq!(bookmarks[0].label.as_deref(), Some("Answer"));
+    }
+
+    #[test]
+    fn test_migrate_v1_library() {
+        let db = ChatDatabase::open(&create_v1_library()).unwrap();
+        assert_eq!(db.schema_version().unwrap(), CURRENT_SCHEMA_VERSION);
+        assert_v1_data(&db);
+        // Messages from before the full-text index are searchable
+        let hits = db.search_messages("read_to_string").unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].message_id, "m2");
+    }
+
+    #[test]
+    fn test_run_migrations_to() {
+        // Opened without migrating, to take the steps one at a time
+        let path = create_v1_library();
+        let db = ChatDatabase {
+            conn: Arc::new(Mutex::new(Connection::open(&path).unwrap())),
+            data_dir: path.parent().unwrap().to_path_buf(),
+        };
+        for version in 2..=CURRENT_SCHEMA_VERSION {
+            assert_eq!(db.run_migrations_to(version).unwrap(), version - 1);
+            let conn = db.conn.lock().unwrap();
+            let messages: i64 = conn
+                .query_row("SELECT COUNT(*) FROM messages", [], |row| row.get(0))
+                .unwrap();
+            assert_eq!(messages, 2, "messages lost at version {}", version);
+        }
+        assert_v1_data(&db);
+
+        // Every step is safe to repeat: back to 1 and forward again
+        assert_eq!(db.run_migrations_to(1).unwrap(), CURRENT_SCHEMA_VERSION);
+        db.run_migrations_to(CURRENT_SCHEMA_VERSION).unwrap();
+        assert_v1_data(&db);
+
+        assert!(db.run_migrations_to(CURRENT_SCHEMA_VERSION + 1).is_err());
+        assert_eq!(db.schema_version().unwrap(), CURRENT_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_failed_migration_rolls_back() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE notes (id TEXT PRIMARY KEY)")
+            .unwrap();
+        let steps = [
+            Migration { version: 1, sql: "ALTER TABLE notes ADD COLUMN body TEXT" },
+            Migration { version: 2, sql: "ALTER TABLE notes ADD COLUMN" },
+        ];
+        let err = run_migration_steps(&conn, &steps, "", 2).unwrap_err();
+        assert!(format!("{:#}", err).contains("Schema migration 2 failed"));
+
+        // Step 1 went with it, and the version wasn't bumped
+        assert_eq!(schema_version(&conn, "main").unwrap(), 0);
+        assert!(conn.prepare("SELECT body FROM notes").is_err());
+
+        assert_eq!(run_migration_steps(&conn, &steps[..1], "", 1).unwrap(), 0);
+        assert_eq!(schema_version(&conn, "main").unwrap(), 1);
+        assert!(conn.prepare("SELECT body FROM notes").is_ok());
+    }
+
+    #[test]
+    fn test_migration_steps_skip_applied_work() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE notes (id TEXT PRIMARY KEY, body TEXT)")
+            .unwrap();
+        let steps = [
+            // Already there, and on a table SCHEMA would create whole
+            Migration { version: 1, sql: "ALTER TABLE notes ADD COLUMN body TEXT" },
+            Migration { version: 2, sql: "ALTER TABLE later ADD COLUMN body TEXT" },
+            Migration { version: 3, sql: "DELETE FROM later" },
+            Migration { version: 4, sql: "ALTER TABLE notes ADD COLUMN title TEXT" },
+        ];
+        assert_eq!(run_migration_steps(&conn, &steps, "", 4).unwrap(), 0);
+        assert!(conn.prepare("SELECT title FROM notes").is_ok());
+        assert_eq!(schema_version(&conn, "main").unwrap(), 4);
+
+        // Other failures, "no such table" included, are not swallowed
+        let steps = [Migration { version: 1, sql: "INSERT INTO later SELECT id FROM notes" }];
+        conn.execute_batch("DELETE FROM config").unwrap();
+        let err = run_migration_steps(&conn, &steps, "", 1).unwrap_err();
+        assert!(format!("{:#}", err).contains("no such table"));
+    }
+
+    #[test]
+    fn test_migration_versions_are_consecutive() {
+        for (i, step) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(step.version, i as i64 + 1);
+        }
+    }
+
+    #[test]
+    fn test_registered_installs() {
+        let db = create_test_db();
+        let install = RegisteredInstall {
+            version: "9.8.7".to_string(),
+            appimage: Some(PathBuf::from("/opt/cursor/Cursor-9.8.7-x86_64.AppImage")),
+            data_dir: PathBuf::from("/data/.cursor-9.8.7"),
+        };
+        db.register_install(&install).unwrap();
+        // Registering again replaces it
+        db.register_install(&install).unwrap();
+        assert_eq!(db.registered_installs().unwrap(), vec![install.clone()]);
+
+        let version = db
+            .get_versions()
+            .unwrap()
+            .into_iter()
+            .find(|v| v.version == "9.8.7")
+            .unwrap();
+        assert_eq!(version.path, install.data_dir);
+
+        assert!(db.unregister_install("9.8.7").unwrap());
+        assert!(!db.unregister_install("9.8.7").unwrap());
+        assert!(db.registered_installs().unwrap().is_empty());
+    }
+
     #[test]
     fn test_config_get_set() {
         let db = create_test_db();
@@ -1373,8 +3762,8 @@ mod tests {
     fn test_display_preferences() {
         let db = create_test_db();
 
-        // Set a preference (content_type, alignment, style, collapsed)
-        db.set_display_preference("user", "right", "default", false)
+        // Set a preference (content_type, alignment, style, collapsed, fg, bg)
+        db.set_display_preference("user", "right", "default", false, None, None)
             .unwrap();
 
         // Get all preferences
@@ -1382,6 +3771,23 @@ mod tests {
         let user_pref = prefs.iter().find(|p| p.content_type == "user");
         assert!(user_pref.is_some());
         assert_eq!(user_pref.unwrap().alignment, "right");
+        assert_eq!(user_pref.unwrap().fg_color, None);
+
+        // Color overrides round-trip
+        db.set_display_preference(
+            "thinking",
+            "left",
+            "collapsed",
+            true,
+            Some("#ff8800"),
+            Some("#202020"),
+        )
+        .unwrap();
+        let prefs = db.get_display_preferences().unwrap();
+        let thinking = prefs.iter().find(|p| p.content_type == "thinking").unwrap();
+        assert_eq!(thinking.fg_color.as_deref(), Some("#ff8800"));
+        assert_eq!(thinking.bg_color.as_deref(), Some("#202020"));
+        assert!(thinking.collapsed_by_default);
     }
 
     #[test]
@@ -1420,4 +3826,822 @@ mod tests {
         let bookmarks = db.get_bookmarks("conv123").unwrap();
         assert_eq!(bookmarks.len(), 0);
     }
+
+    #[test]
+    fn test_bookmark_same_message_updates() {
+        let db = create_test_db();
+
+        let first = db
+            .add_bookmark("conv123", "msg456", 1, Some("First"), Some("Note"), "gold")
+            .unwrap();
+        let second = db
+            .add_bookmark("conv123", "msg456", 1, Some("Second"), None, "gold")
+            .unwrap();
+        assert_eq!(first, second);
+
+        // One bookmark, new label, note left alone
+        let bookmarks = db.get_bookmarks("conv123").unwrap();
+        assert_eq!(bookmarks.len(), 1);
+        assert_eq!(bookmarks[0].label, Some("Second".to_string()));
+        assert_eq!(bookmarks[0].note, Some("Note".to_string()));
+    }
+
+    #[test]
+    fn test_bookmark_export_import() {
+        let source_db = create_test_db();
+        let conv = "b0b0b0b0-b0b0-b0b0-b0b0-b0b0b0b0b0b0";
+        let source = create_test_cursor_db(&[(conv, 3)]);
+        source_db.import_from_cursor(source.clone(), "test").unwrap();
+        source_db
+            .add_bookmark(conv, "msg-0001", 1, Some("By id"), Some("Note"), "#ff0000")
+            .unwrap();
+        source_db
+            .add_bookmark(conv, "old-msg-id", 2, Some("By seq"), None, "gold")
+            .unwrap();
+        source_db
+            .add_bookmark("elsewhere", "msg-9", 0, Some("Orphan"), None, "gold")
+            .unwrap();
+        let json = source_db.export_bookmarks().unwrap();
+
+        let db = create_test_db();
+        db.import_from_cursor(source, "test").unwrap();
+        let report = db.import_bookmarks(&json).unwrap();
+        assert_eq!(report, BookmarkImport { attached: 2, orphaned: 1 });
+
+        let bookmarks = db.get_bookmarks(conv).unwrap();
+        assert_eq!(bookmarks.len(), 2);
+        assert_eq!(bookmarks[0].message_id, "msg-0001");
+        assert_eq!(bookmarks[0].note.as_deref(), Some("Note"));
+        assert_eq!(bookmarks[0].color, "#ff0000");
+        // Reattached by position
+        assert_eq!(bookmarks[1].message_id, "msg-0002");
+        assert_eq!(bookmarks[1].label.as_deref(), Some("By seq"));
+        // Kept for when its conversation shows up
+        assert_eq!(db.get_bookmarks("elsewhere").unwrap().len(), 1);
+
+        // Importing again updates rather than duplicates
+        assert_eq!(db.import_bookmarks(&json).unwrap().attached, 2);
+        assert_eq!(db.get_all_bookmarks().unwrap().len(), 3);
+        assert!(db.import_bookmarks("[]").is_err());
+    }
+
+    #[test]
+    fn test_import_preview_does_not_write() {
+        let db = create_test_db();
+        let conv_a = "aaaaaaaa-aaaa-aaaa-aaaa-aaaaaaaaaaaa";
+        let conv_b = "bbbbbbbb-bbbb-bbbb-bbbb-bbbbbbbbbbbb";
+        let source = create_test_cursor_db(&[(conv_a, 3), (conv_b, 2)]);
+
+        let preview = db.preview_import_from_cursor(&source, "test").unwrap();
+        assert_eq!(preview.conversations, 2);
+        assert_eq!(preview.messages, 5);
+        assert_eq!(preview.new_conversations, 2);
+        assert_eq!(db.get_stats(true).unwrap().0, 0);
+
+        db.import_from_cursor(source.clone(), "test").unwrap();
+        let preview = db.preview_import_from_cursor(&source, "test").unwrap();
+        assert_eq!(preview.conversations, 0, "nothing changed since the import");
+
+        // Cursor rewrote a bubble of an imported chat
+        Connection::open(&source)
+            .unwrap()
+            .execute(
+                "UPDATE cursorDiskKV SET rowid = rowid + 100 WHERE key LIKE ?",
+                params![format!("bubbleId:{}:%", conv_a)],
+            )
+            .unwrap();
+        let preview = db.preview_import_from_cursor(&source, "test").unwrap();
+        assert_eq!((preview.conversations, preview.existing_conversations), (1, 1));
+        assert_eq!(preview.new_conversations, 0);
+
+        // The same chat under another id is a duplicate, not new
+        let copy = create_test_cursor_db(&[("cccccccc-cccc-cccc-cccc-cccccccccccc", 2)]);
+        let fresh = create_test_db();
+        fresh.import_from_cursor(copy.clone(), "test").unwrap();
+        let preview = fresh.preview_import_from_cursor(&source, "test").unwrap();
+        assert_eq!(preview.new_conversations, 1);
+        assert_eq!(preview.new_messages, 3);
+        assert_eq!(preview.existing_conversations, 1);
+    }
+
+    #[test]
+    fn test_import_salvages_damaged_conversations() {
+        let db = create_test_db();
+        let (partial, broken, intact) = (
+            "d1d1d1d1-d1d1-d1d1-d1d1-d1d1d1d1d1d1",
+            "d2d2d2d2-d2d2-d2d2-d2d2-d2d2d2d2d2d2",
+            "d3d3d3d3-d3d3-d3d3-d3d3-d3d3d3d3d3d3",
+        );
+        let source = create_test_cursor_db(&[(partial, 3), (broken, 1), (intact, 2)]);
+        {
+            let conn = Connection::open(&source).unwrap();
+            conn.execute(
+                "UPDATE cursorDiskKV SET value = '{\"type\": 1, \"te' WHERE key LIKE ?",
+                params![format!("bubbleId:{}:msg-0001", partial)],
+            )
+            .unwrap();
+            conn.execute(
+                "UPDATE cursorDiskKV SET value = x'00ff' WHERE key LIKE ?",
+                params![format!("bubbleId:{}:%", broken)],
+            )
+            .unwrap();
+        }
+
+        let report = db.import_from_cursor(source.clone(), "test").unwrap();
+        assert_eq!(
+            report,
+            CursorImport {
+                imported: 2,
+                imported_ids: vec![partial.to_string(), intact.to_string()],
+                skipped: 0,
+                duplicates: 0,
+                lost_conversations: 1,
+                lost_messages: 2,
+                locked: false,
+                rows_scanned: 6,
+            }
+        );
+        assert_eq!(db.get_messages(partial).unwrap().len(), 2);
+        assert!(db.get_conversation(broken).unwrap().is_none());
+        // Bad bubbles stay bad until Cursor rewrites them above the watermark
+        assert_eq!(db.last_import_watermark(&source).unwrap(), Some(6));
+        assert_eq!(
+            db.import_from_cursor(source, "test").unwrap(),
+            CursorImport::default()
+        );
+    }
+
+    #[test]
+    fn test_import_retries_unreadable_conversations() {
+        let db = create_test_db();
+        let retried = "c1c1c1c1-c1c1-c1c1-c1c1-c1c1c1c1c1c1";
+        let source = create_test_cursor_db(&[(retried, 2)]);
+        db.import_from_cursor(source.clone(), "test").unwrap();
+
+        // As if the last import failed to read it
+        {
+            let conn = db.conn.lock().unwrap();
+            conn.execute("DELETE FROM messages WHERE conversation_id = ?", params![retried])
+                .unwrap();
+            conn.execute("DELETE FROM conversations WHERE id = ?", params![retried])
+                .unwrap();
+            conn.execute(
+                "INSERT INTO import_retries (source, conversation_id) VALUES (?, ?)",
+                params![source.to_string_lossy(), retried],
+            )
+            .unwrap();
+        }
+
+        // Read again by id although it's below the watermark
+        let report = db.import_from_cursor(source.clone(), "test").unwrap();
+        assert_eq!((report.imported, report.rows_scanned), (1, 0));
+        assert!(db.get_conversation(retried).unwrap().is_some());
+        assert_eq!(db.import_from_cursor(source, "test").unwrap().imported, 0);
+    }
+
+    #[test]
+    fn test_import_retries_while_locked() {
+        let db = create_test_db();
+        let conv = "e1e1e1e1-e1e1-e1e1-e1e1-e1e1e1e1e1e1";
+        let source = create_test_cursor_db(&[(conv, 2)]);
+
+        // Hold an exclusive lock, as a running Cursor mid-write would
+        let lock = Connection::open(&source).unwrap();
+        lock.execute_batch("BEGIN EXCLUSIVE").unwrap();
+        let release = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(150));
+            lock.execute_batch("COMMIT").unwrap();
+        });
+
+        let report = db.import_from_cursor(source, "test").unwrap();
+        release.join().unwrap();
+        assert_eq!(report.imported, 1);
+        assert!(!report.is_damaged() && !report.locked);
+    }
+
+    #[test]
+    fn test_import_reads_checkpoint_while_locked() {
+        let db = create_test_db();
+        let conv = "e2e2e2e2-e2e2-e2e2-e2e2-e2e2e2e2e2e2";
+        let source = create_test_cursor_db(&[(conv, 2)]);
+
+        // A lock that outlasts every retry
+        let lock = Connection::open(&source).unwrap();
+        lock.execute_batch("BEGIN EXCLUSIVE").unwrap();
+
+        let report = db.import_from_cursor(source, "test").unwrap();
+        assert!(report.locked);
+        assert_eq!(report.imported, 1);
+        assert_eq!(db.get_messages(conv).unwrap().len(), 2);
+        lock.execute_batch("ROLLBACK").unwrap();
+    }
+
+    #[test]
+    fn test_import_skips_duplicate_content() {
+        let db = create_test_db();
+        let (first, copy, other) = (
+            "f1f1f1f1-f1f1-f1f1-f1f1-f1f1f1f1f1f1",
+            "f2f2f2f2-f2f2-f2f2-f2f2-f2f2f2f2f2f2",
+            "f3f3f3f3-f3f3-f3f3-f3f3-f3f3f3f3f3f3",
+        );
+        let report = db
+            .import_from_cursor(create_test_cursor_db(&[(first, 2), (other, 3)]), "0.41.0")
+            .unwrap();
+        assert_eq!((report.imported, report.duplicates), (2, 0));
+
+        // Libraries from before deduplication have no hashes yet
+        db.conn
+            .lock()
+            .unwrap()
+            .execute("UPDATE conversations SET content_hash = NULL", [])
+            .unwrap();
+
+        // Same chat under another id, as found in a second version directory
+        let report = db
+            .import_from_cursor(create_test_cursor_db(&[(copy, 2)]), "0.42.0")
+            .unwrap();
+        assert_eq!(report.imported, 0);
+        assert_eq!(report.duplicates, 1);
+        assert!(db.get_conversation(copy).unwrap().is_none());
+        assert_eq!(db.get_stats(true).unwrap().0, 2);
+    }
+
+    #[test]
+    fn test_incremental_import() {
+        let db = create_test_db();
+        let (first, second) = (
+            "a1a1a1a1-a1a1-a1a1-a1a1-a1a1a1a1a1a1",
+            "b2b2b2b2-b2b2-b2b2-b2b2-b2b2b2b2b2b2",
+        );
+        let source = create_test_cursor_db(&[(first, 2)]);
+        assert_eq!(db.last_import_watermark(&source).unwrap(), None);
+
+        let report = db.import_from_cursor(source.clone(), "test").unwrap();
+        assert_eq!((report.imported, report.rows_scanned), (1, 2));
+        assert_eq!(db.last_import_watermark(&source).unwrap(), Some(2));
+
+        // Nothing new: the second pass doesn't read a single bubble
+        let report = db.import_from_cursor(source.clone(), "test").unwrap();
+        assert_eq!(report, CursorImport::default());
+
+        // Only what Cursor wrote since is read
+        Connection::open(&source)
+            .unwrap()
+            .execute(
+                "INSERT INTO cursorDiskKV (key, value) VALUES (?, '{\"type\": 1, \"text\": \"hi\"}')",
+                params![format!("bubbleId:{}:msg-0100", second)],
+            )
+            .unwrap();
+        let report = db.import_from_cursor(source.clone(), "test").unwrap();
+        assert_eq!((report.imported, report.rows_scanned), (1, 1));
+        assert_eq!(db.last_import_watermark(&source).unwrap(), Some(3));
+
+        // Clearing the library starts over
+        db.clear_all().unwrap();
+        assert_eq!(db.last_import_watermark(&source).unwrap(), None);
+        let report = db.import_from_cursor(source, "test").unwrap();
+        assert_eq!((report.imported, report.rows_scanned), (2, 3));
+    }
+
+    #[test]
+    fn test_archiving() {
+        let db = create_test_db();
+        let conv = "cccccccc-cccc-cccc-cccc-cccccccccccc";
+        let source = create_test_cursor_db(&[(conv, 2)]);
+        db.import_from_cursor(source, "test").unwrap();
+
+        db.set_archived(conv, true).unwrap();
+        assert!(db.get_conversations(50, false, None).unwrap().is_empty());
+        assert_eq!(db.get_conversations(50, true, None).unwrap().len(), 1);
+        assert_eq!(db.get_conversations(usize::MAX, true, None).unwrap().len(), 1);
+        assert!(db.search_conversations("message").unwrap()[0].is_archived);
+        assert!(db.get_conversation(conv).unwrap().unwrap().is_archived);
+        assert_eq!(db.get_stats(false).unwrap(), (0, 0, 0));
+        assert_eq!(db.get_stats(true).unwrap().0, 1);
+
+        db.set_archived(conv, false).unwrap();
+        assert_eq!(db.get_conversations(50, false, None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_tags() {
+        let db = create_test_db();
+        let (first, second) = (
+            "7a7a7a7a-7a7a-7a7a-7a7a-7a7a7a7a7a7a",
+            "7b7b7b7b-7b7b-7b7b-7b7b-7b7b7b7b7b7b",
+        );
+        let source = create_test_cursor_db(&[(first, 1), (second, 2)]);
+        db.import_from_cursor(source.clone(), "test").unwrap();
+
+        db.add_tag(first, "work").unwrap();
+        db.add_tag(first, "parser").unwrap();
+        db.add_tag(second, "work").unwrap();
+        db.add_tag(second, "work").unwrap();
+        assert_eq!(db.tags_for_conversation(first).unwrap(), vec!["work", "parser"]);
+        assert_eq!(db.conversations_with_tag("work").unwrap().len(), 2);
+        let parser = db.get_conversations(50, false, Some("parser")).unwrap();
+        assert_eq!(parser.len(), 1);
+        assert_eq!(parser[0].user_tags, vec!["work", "parser"]);
+        assert_eq!(db.search_conversations("pars").unwrap().len(), 1);
+
+        db.remove_tag(first, "work").unwrap();
+        assert_eq!(db.tags_for_conversation(first).unwrap(), vec!["parser"]);
+
+        // Kept through a cache clear and back with the reimported chat
+        db.clear_all().unwrap();
+        assert!(db.conversations_with_tag("parser").unwrap().is_empty());
+        db.import_from_cursor(source, "test").unwrap();
+        assert_eq!(db.conversations_with_tag("parser").unwrap()[0].id, first);
+
+        db.delete_conversation(first).unwrap();
+        assert!(db.tags_for_conversation(first).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_bulk_helpers() {
+        let db = create_test_db();
+        let conv = "dddddddd-dddd-dddd-dddd-dddddddddddd";
+        let source = create_test_cursor_db(&[(conv, 2)]);
+        db.import_from_cursor(source, "test").unwrap();
+
+        db.set_favorite(conv, true).unwrap();
+        db.add_tag(conv, "project-x").unwrap();
+        db.add_tag(conv, "project-x").unwrap();
+        let convs = db.get_conversations(50, false, None).unwrap();
+        assert!(convs[0].is_favorite);
+        assert_eq!(convs[0].user_tags, vec!["project-x".to_string()]);
+
+        db.add_bookmark(conv, "msg-0000", 0, None, None, "gold")
+            .unwrap();
+        db.delete_conversation(conv).unwrap();
+        assert!(db.get_conversations(50, true, None).unwrap().is_empty());
+        assert!(db.get_messages(conv).unwrap().is_empty());
+        assert!(db.get_bookmarks(conv).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_search_and_replace() {
+        let db = create_test_db();
+        let conv = "abababab-abab-abab-abab-abababababab";
+        let other = "cdcdcdcd-cdcd-cdcd-cdcd-cdcdcdcdcdcd";
+        let source = create_test_cursor_db(&[(conv, 3), (other, 1)]);
+        db.import_from_cursor(source, "test").unwrap();
+
+        let ids = vec![conv.to_string()];
+        let pattern = Regex::new(r"message (\d)").unwrap();
+        let preview = db.preview_replace(&ids, &pattern, "msg #$1").unwrap();
+        assert_eq!(preview.messages, 3);
+        assert_eq!(preview.conversations, 1);
+        assert_eq!(preview.matches, 3);
+        assert_eq!(preview.samples[0].before, "message 0");
+        assert_eq!(preview.samples[0].after, "msg #0");
+        // Previewing doesn't write
+        assert_eq!(db.get_messages(conv).unwrap()[0].content, "message 0");
+
+        let (batch, changed) = db.apply_replace(&ids, &pattern, "msg #$1").unwrap();
+        assert_eq!(changed, 3);
+        assert_eq!(db.get_messages(conv).unwrap()[1].content, "msg #1");
+        // Unselected conversations are untouched
+        assert_eq!(db.get_messages(other).unwrap()[0].content, "message 0");
+
+        let batch = batch.unwrap();
+        assert_eq!(db.last_replace_batch().unwrap(), Some((batch.clone(), 3)));
+        assert_eq!(db.undo_replace(&batch).unwrap(), 3);
+        assert_eq!(db.get_messages(conv).unwrap()[1].content, "message 1");
+        assert_eq!(db.last_replace_batch().unwrap(), None);
+
+        let (batch, changed) = db.apply_replace(&ids, &pattern, "$0").unwrap();
+        assert_eq!((batch, changed), (None, 0));
+    }
+
+    #[test]
+    fn test_search_messages() {
+        let db = create_test_db();
+        let conv = "efefefef-efef-efef-efef-efefefefefef";
+        let other = "fefefefe-fefe-fefe-fefe-fefefefefefe";
+        let source = create_test_cursor_db(&[(conv, 3), (other, 1)]);
+        db.import_from_cursor(source, "test").unwrap();
+
+        assert_eq!(db.search_messages("message").unwrap().len(), 4);
+        // The last word matches as a prefix, while typing
+        assert_eq!(db.search_messages("mess").unwrap().len(), 4);
+        let hits = db.search_messages("message 2").unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].conversation_id, conv);
+        assert_eq!(hits[0].message_id, db.get_messages(conv).unwrap()[2].id);
+        assert_eq!(
+            hits[0].snippet,
+            format!("{0}message{1} {0}2{1}", SNIPPET_MATCH_START, SNIPPET_MATCH_END)
+        );
+        // Quotes and operators are searched as text, not parsed
+        assert_eq!(db.search_messages("\"message-").unwrap().len(), 4);
+        assert!(db.search_messages("message OR 2").unwrap().is_empty());
+        assert!(db.search_messages("  ").unwrap().is_empty());
+
+        // Edits and deletes reach the index
+        let pattern = Regex::new("message 1").unwrap();
+        db.apply_replace(&[conv.to_string()], &pattern, "renamed entry")
+            .unwrap();
+        assert_eq!(db.search_messages("renamed").unwrap().len(), 1);
+        db.delete_conversation(conv).unwrap();
+        assert!(db.search_messages("renamed").unwrap().is_empty());
+        assert_eq!(db.search_messages("message").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_language_tags() {
+        let db = create_test_db();
+        let conv = "efefefef-efef-efef-efef-efefefefefef";
+        let source = create_test_cursor_db(&[(conv, 1)]);
+        db.import_from_cursor(source, "test").unwrap();
+        db.add_tag(conv, "project-x").unwrap();
+        db.conn
+            .lock()
+            .unwrap()
+            .execute(
+                "UPDATE messages SET content = ? WHERE conversation_id = ?",
+                params!["```rust\nfn main() {}\n```", conv],
+            )
+            .unwrap();
+
+        assert_eq!(db.tag_unscanned_languages().unwrap(), 1);
+        let tags = |db: &ChatDatabase| db.get_conversation(conv).unwrap().unwrap().user_tags;
+        let tagged = vec!["project-x".to_string(), "lang:rust".to_string()];
+        assert_eq!(tags(&db), tagged);
+        assert_eq!(db.search_conversations("lang:rust").unwrap().len(), 1);
+
+        // A removed auto-tag isn't brought back by later imports
+        db.remove_tag(conv, "lang:rust").unwrap();
+        assert_eq!(db.tag_unscanned_languages().unwrap(), 0);
+        assert_eq!(tags(&db), vec!["project-x".to_string()]);
+
+        // Detecting on demand does re-tag
+        assert_eq!(db.tag_languages(conv).unwrap(), vec!["rust".to_string()]);
+        assert_eq!(tags(&db), tagged);
+    }
+
+    #[test]
+    fn test_messages_page() {
+        let db = create_test_db();
+        let conv = "eeeeeeee-eeee-eeee-eeee-eeeeeeeeeeee";
+        let source = create_test_cursor_db(&[(conv, 5)]);
+        db.import_from_cursor(source, "test").unwrap();
+
+        assert_eq!(db.count_messages(conv).unwrap(), 5);
+        let all = db.get_messages(conv).unwrap();
+        let first = db.get_messages_page(conv, 0, 2).unwrap();
+        let rest = db.get_messages_page(conv, 2, 10).unwrap();
+        assert_eq!(first.len(), 2);
+        assert_eq!(rest.len(), 3);
+        assert_eq!(first[0].id, all[0].id);
+        assert_eq!(rest[0].id, all[2].id);
+    }
+
+    #[test]
+    fn test_import_branches() {
+        let db = create_test_db();
+        let conv = "ffffffff-ffff-ffff-ffff-ffffffffffff";
+        let source = create_test_cursor_db(&[(conv, 2)]);
+        let conn = Connection::open(&source).unwrap();
+        for id in ["msg-0002", "msg-0003"] {
+            conn.execute(
+                "INSERT INTO cursorDiskKV (key, value) VALUES (?, ?)",
+                params![
+                    format!("bubbleId:{}:{}", conv, id),
+                    r#"{"type": 2, "text": "reply", "parentBubbleId": "msg-0000"}"#
+                ],
+            )
+            .unwrap();
+        }
+        drop(conn);
+        db.import_from_cursor(source, "test").unwrap();
+
+        let branches: Vec<_> = db
+            .get_messages(conv)
+            .unwrap()
+            .into_iter()
+            .map(|m| (m.parent_id, m.branch))
+            .collect();
+        let parent = Some("msg-0000".to_string());
+        assert_eq!(
+            branches,
+            vec![(None, 0), (None, 0), (parent.clone(), 0), (parent, 1)]
+        );
+    }
+
+    #[test]
+    fn test_message_is_empty() {
+        let db = create_test_db();
+        let conv = "abababab-abab-abab-abab-abababababab";
+        let source = create_test_cursor_db(&[(conv, 1)]);
+        db.import_from_cursor(source, "test").unwrap();
+        let mut msg = db.get_messages(conv).unwrap().remove(0);
+        assert!(!msg.is_empty());
+
+        msg.content = " \n".to_string();
+        assert!(msg.is_empty());
+        msg.thinking = Some("planning".to_string());
+        assert!(!msg.is_empty());
+    }
+
+    #[test]
+    fn test_import_chat_export() {
+        let db = create_test_db();
+        let dir = std::env::temp_dir().join(format!("chat-export-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let export = serde_json::json!([{
+            "uuid": "k1",
+            "name": "Greetings",
+            "chat_messages": [
+                {"uuid": "m1", "sender": "human", "text": "hello"},
+                {"uuid": "m2", "sender": "assistant", "text": "hi"}
+            ]
+        }]);
+        std::fs::write(dir.join("conversations.json"), export.to_string()).unwrap();
+
+        let (source, imported, skipped) = db.import_chat_export(&dir).unwrap();
+        assert_eq!((source, imported, skipped), (ExportSource::Claude, 1, 0));
+        let (_, imported, skipped) = db.import_chat_export(&dir).unwrap();
+        assert_eq!((imported, skipped), (0, 1));
+
+        let conv = db.get_conversation("claude-k1").unwrap().unwrap();
+        assert_eq!(conv.source_version, "Claude");
+        assert_eq!(conv.user_tags, vec!["claude".to_string()]);
+        let roles: Vec<_> = db
+            .get_messages("claude-k1")
+            .unwrap()
+            .into_iter()
+            .map(|m| m.role)
+            .collect();
+        assert_eq!(roles, [MessageRole::User, MessageRole::Assistant]);
+    }
+
+    #[test]
+    fn test_import_chat_export_is_all_or_nothing() {
+        let db = create_test_db();
+        let dir = std::env::temp_dir().join(format!("chat-export-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let export = serde_json::json!([
+            {"uuid": "k1", "name": "Fine", "chat_messages": [
+                {"uuid": "m1", "sender": "human", "text": "hello"}
+            ]},
+            {"uuid": "k2", "name": "Bad", "chat_messages": [
+                {"uuid": "m2", "sender": "human", "text": "hi"}
+            ]}
+        ]);
+        std::fs::write(dir.join("conversations.json"), export.to_string()).unwrap();
+        // The second record fails to insert
+        db.conn
+            .lock()
+            .unwrap()
+            .execute_batch(
+                "CREATE TRIGGER reject_k2 BEFORE INSERT ON conversations
+                 WHEN NEW.id = 'claude-k2' BEGIN SELECT RAISE(ABORT, 'bad record'); END",
+            )
+            .unwrap();
+
+        assert!(db.import_chat_export(&dir).is_err());
+        assert!(db.get_conversation("claude-k1").unwrap().is_none());
+        assert!(db.get_messages("claude-k1").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_sqlite_export_round_trip() {
+        let db = create_test_db();
+        let (kept, left) = (
+            "cdcdcdcd-cdcd-cdcd-cdcd-cdcdcdcdcdcd",
+            "efefefef-efef-efef-efef-efefefefefef",
+        );
+        let source = create_test_cursor_db(&[(kept, 2), (left, 1)]);
+        db.import_from_cursor(source, "test").unwrap();
+        db.add_tag(kept, "shared").unwrap();
+        let msg = db.get_messages(kept).unwrap().remove(0);
+        db.add_bookmark(kept, &msg.id, 0, Some("start"), None, "#ffd700")
+            .unwrap();
+
+        let file = std::env::temp_dir().join(format!("{}.cursor-studio.db", uuid::Uuid::new_v4()));
+        assert_eq!(db.export_to_sqlite(&[kept.to_string()], &file).unwrap(), 1);
+
+        let other = create_test_db();
+        assert_eq!(other.import_from_sqlite(&file).unwrap(), (1, 0));
+        assert_eq!(other.import_from_sqlite(&file).unwrap(), (0, 1));
+        assert!(other.get_conversation(left).unwrap().is_none());
+
+        let conv = other.get_conversation(kept).unwrap().unwrap();
+        assert_eq!(conv.user_tags, vec!["shared".to_string()]);
+        assert_eq!(other.get_messages(kept).unwrap().len(), 2);
+        let bookmarks = other.get_bookmarks(kept).unwrap();
+        assert_eq!(bookmarks[0].label.as_deref(), Some("start"));
+    }
+
+    #[test]
+    fn test_conversation_json_round_trip() {
+        let db = create_test_db();
+        let conv = "9a9a9a9a-9a9a-9a9a-9a9a-9a9a9a9a9a9a";
+        let source = create_test_cursor_db(&[(conv, 2)]);
+        db.import_from_cursor(source, "test").unwrap();
+        db.add_tag(conv, "shared").unwrap();
+        let copied = serde_json::to_string(&ConversationJson {
+            conversation: db.get_conversation(conv).unwrap().unwrap(),
+            messages: db.get_messages(conv).unwrap(),
+        })
+        .unwrap();
+
+        let other = create_test_db();
+        assert_eq!(
+            other.import_conversation_json(&copied).unwrap(),
+            (conv.to_string(), true)
+        );
+        assert_eq!(
+            other.import_conversation_json(&copied).unwrap(),
+            (conv.to_string(), false)
+        );
+        let pasted = other.get_conversation(conv).unwrap().unwrap();
+        assert_eq!(pasted.user_tags, vec!["shared".to_string()]);
+        assert_eq!(
+            other.get_messages(conv).unwrap()[1].content,
+            db.get_messages(conv).unwrap()[1].content
+        );
+
+        let err = other
+            .import_conversation_json("{\"messages\": []}")
+            .unwrap_err();
+        assert!(format!("{:#}", err).contains("missing field `conversation`"));
+        let foreign = copied.replacen(
+            &format!("\"conversation_id\":\"{}\"", conv),
+            "\"conversation_id\":\"x\"",
+            1,
+        );
+        assert!(other.import_conversation_json(&foreign).is_err());
+    }
+
+    #[test]
+    fn test_conversation_notes() {
+        let db = create_test_db();
+        let conv = "12121212-1212-1212-1212-121212121212";
+        let source = create_test_cursor_db(&[(conv, 1)]);
+        db.import_from_cursor(source, "test").unwrap();
+        assert_eq!(db.get_conversation_note(conv).unwrap(), None);
+
+        db.set_conversation_note(conv, "TODO: port the parser")
+            .unwrap();
+        let note = db.get_conversation(conv).unwrap().unwrap().notes;
+        assert_eq!(note.as_deref(), Some("TODO: port the parser"));
+        assert_eq!(db.search_conversations("parser").unwrap().len(), 1);
+
+        db.set_conversation_note(conv, "  ").unwrap();
+        assert_eq!(db.get_conversation_note(conv).unwrap(), None);
+    }
+
+    #[test]
+    fn test_rename_conversation() {
+        let db = create_test_db();
+        let conv = "13131313-1313-1313-1313-131313131313";
+        let source = create_test_cursor_db(&[(conv, 1)]);
+        db.import_from_cursor(source.clone(), "test").unwrap();
+        let imported = db.get_conversation(conv).unwrap().unwrap().title;
+
+        db.rename_conversation(conv, "  Parser rewrite ").unwrap();
+        let title = |db: &ChatDatabase| db.get_conversation(conv).unwrap().unwrap().title;
+        assert_eq!(title(&db), "Parser rewrite");
+        assert_eq!(db.search_conversations("rewrite").unwrap().len(), 1);
+
+        // Reapplied after the cache is cleared and the conversation reimported
+        db.clear_all().unwrap();
+        db.import_from_cursor(source, "test").unwrap();
+        assert_eq!(title(&db), "Parser rewrite");
+
+        db.rename_conversation(conv, "").unwrap();
+        assert_eq!(title(&db), imported);
+    }
+
+    #[test]
+    fn test_word_counts() {
+        let db = create_test_db();
+        let conv = "78787878-7878-7878-7878-787878787878";
+        let source = create_test_cursor_db(&[(conv, 3)]);
+        db.import_from_cursor(source, "test").unwrap();
+
+        // Each test bubble reads "message N"
+        let words = |db: &ChatDatabase| db.get_conversation(conv).unwrap().unwrap().word_count;
+        assert_eq!(words(&db), 6);
+
+        // Edits keep the stored counts current
+        let pattern = Regex::new("message").unwrap();
+        let (batch, _) = db.apply_replace(&[conv.to_string()], &pattern, "a longer message").unwrap();
+        assert_eq!(words(&db), 12);
+        db.undo_replace(&batch.unwrap()).unwrap();
+        assert_eq!(words(&db), 6);
+
+        // Libraries from before counts were stored get them on open
+        db.conn.lock().unwrap().execute("UPDATE messages SET word_count = NULL", []).unwrap();
+        let db = ChatDatabase::open(&db.get_path()).unwrap();
+        assert_eq!(words(&db), 6);
+
+        assert_eq!(reading_minutes(0), 1);
+        assert_eq!(reading_minutes(READING_WORDS_PER_MINUTE), 1);
+        assert_eq!(reading_minutes(READING_WORDS_PER_MINUTE + 1), 2);
+    }
+
+    #[test]
+    fn test_daily_activity() {
+        let db = create_test_db();
+        let (a, b) = (
+            "34343434-3434-3434-3434-343434343434",
+            "56565656-5656-5656-5656-565656565656",
+        );
+        let source = create_test_cursor_db(&[(a, 2), (b, 1)]);
+        db.import_from_cursor(source, "0.42.0").unwrap();
+        {
+            let conn = db.conn.lock().unwrap();
+            conn.execute(
+                "UPDATE messages SET created_at = '2024-05-01T09:00:00Z' WHERE conversation_id = ?",
+                params![a],
+            )
+            .unwrap();
+            conn.execute(
+                "UPDATE messages SET created_at = '2024-05-03T09:00:00Z' WHERE conversation_id = ?",
+                params![b],
+            )
+            .unwrap();
+        }
+
+        let days = db.get_daily_activity(true).unwrap();
+        let summary: Vec<_> = days
+            .iter()
+            .map(|d| (d.day.as_str(), d.conversations, d.messages))
+            .collect();
+        assert_eq!(summary, [("2024-05-01", 1, 2), ("2024-05-03", 1, 1)]);
+        assert_eq!(
+            db.get_version_counts(true).unwrap(),
+            vec![("0.42.0".to_string(), 2)]
+        );
+        let by_version = db.conversation_counts_by_version().unwrap();
+        assert_eq!(by_version.get("0.42.0"), Some(&2));
+        assert_eq!(by_version.get("default"), None);
+    }
+
+    #[test]
+    fn test_pair_tool_results() {
+        let msg = |role: MessageRole, tool_call_id: Option<&str>| Message {
+            id: String::new(),
+            conversation_id: String::new(),
+            sequence: 0,
+            role,
+            content: String::new(),
+            tool_call: None,
+            thinking: None,
+            content_type: ContentType::Text,
+            has_code_blocks: false,
+            has_terminal_output: false,
+            files_edited: Vec::new(),
+            created_at: None,
+            parent_id: None,
+            branch: 0,
+            tool_call_id: tool_call_id.map(str::to_string),
+        };
+        let messages = vec![
+            msg(MessageRole::ToolCall, Some("a")),
+            msg(MessageRole::ToolCall, Some("b")),
+            msg(MessageRole::ToolResult, Some("b")),
+            msg(MessageRole::ToolResult, Some("a")),
+            msg(MessageRole::ToolCall, None),
+            msg(MessageRole::ToolCall, None),
+            msg(MessageRole::ToolResult, None),
+            msg(MessageRole::Assistant, None),
+            msg(MessageRole::ToolResult, None),
+        ];
+
+        let pairs = pair_tool_results(&messages);
+        assert_eq!(pairs.len(), 3);
+        assert_eq!(pairs[&0], 3);
+        assert_eq!(pairs[&1], 2);
+        assert_eq!(pairs[&4], 6);
+    }
+
+    #[test]
+    fn test_extract_tool_result() {
+        let text = serde_json::json!({"toolFormerData": {"result": "ok"}});
+        assert_eq!(extract_tool_result(&text).as_deref(), Some("ok"));
+        let empty = serde_json::json!({"toolFormerData": {"result": "  "}});
+        assert!(extract_tool_result(&empty).is_none());
+        assert!(extract_tool_result(&serde_json::json!({"type": 2})).is_none());
+    }
+
+    #[test]
+    fn test_extract_created_at() {
+        let iso = serde_json::json!({"createdAt": "2024-05-01T12:30:00.000Z"});
+        assert_eq!(
+            extract_created_at(&iso).as_deref(),
+            Some("2024-05-01T12:30:00Z")
+        );
+
+        let millis = serde_json::json!({"timingInfo": {"clientStartTime": 1714566600000u64}});
+        assert_eq!(
+            extract_created_at(&millis).as_deref(),
+            Some("2024-05-01T12:30:00Z")
+        );
+
+        assert!(extract_created_at(&serde_json::json!({"type": 1})).is_none());
+    }
 }
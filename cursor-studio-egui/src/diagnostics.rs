@@ -0,0 +1,360 @@
+//! Self-test behind Settings → Diagnostics
+//! Each check covers one of the ways the app can fail quietly (unwritable
+//! library, no Cursor installs, missing fonts, unreachable download hosts, ...)
+//! and the report renders as plain text for pasting into bug reports
+
+use crate::versions;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CheckStatus {
+    Pass,
+    /// Works, but something is missing or degraded
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    pub fn icon(self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "✓",
+            CheckStatus::Warn => "⚠",
+            CheckStatus::Fail => "✗",
+        }
+    }
+
+    fn tag(self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "PASS",
+            CheckStatus::Warn => "WARN",
+            CheckStatus::Fail => "FAIL",
+        }
+    }
+}
+
+/// The outcome of one check
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl Check {
+    fn new(name: &'static str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// What the checks need from the running app, gathered on the UI thread so
+/// the (slow, networked) checks can run in the background
+#[derive(Debug, Clone)]
+pub struct Inputs {
+    pub db_path: PathBuf,
+    /// Cursor installs as (version, data directory)
+    pub cursor_installs: Vec<(String, PathBuf)>,
+    /// Cursor state databases found for chat import
+    pub cursor_databases: usize,
+    pub loaded_fonts: Vec<String>,
+    pub download_urls: Vec<String>,
+    /// Proxy configured in Settings, if any
+    pub proxy: Option<String>,
+    pub connect_timeout: Duration,
+    pub p2p_binary: Option<PathBuf>,
+    pub config_path: Option<PathBuf>,
+    /// Why config.json didn't parse, if it exists and doesn't
+    pub config_error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Report {
+    /// Local time the checks ran, YYYY-MM-DD HH:MM:SS
+    pub generated_at: String,
+    pub checks: Vec<Check>,
+}
+
+impl Report {
+    pub fn count(&self, status: CheckStatus) -> usize {
+        self.checks.iter().filter(|c| c.status == status).count()
+    }
+
+    /// Plain-text report for bug reports
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "Cursor Studio {} diagnostics ({}, {}/{})\n",
+            env!("CARGO_PKG_VERSION"),
+            self.generated_at,
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        );
+        for check in &self.checks {
+            text.push_str(&format!(
+                "[{}] {}: {}\n",
+                check.status.tag(),
+                check.name,
+                check.detail
+            ));
+        }
+        text
+    }
+}
+
+/// Run every check; blocks on the network checks
+pub fn run(inputs: &Inputs) -> Report {
+    let mut checks = vec![
+        check_database(&inputs.db_path),
+        check_cursor_installs(&inputs.cursor_installs, inputs.cursor_databases),
+        check_fonts(&inputs.loaded_fonts),
+    ];
+    for host in download_hosts(&inputs.download_urls) {
+        checks.push(check_host(
+            &host,
+            inputs.proxy.as_deref(),
+            inputs.connect_timeout,
+        ));
+    }
+    checks.push(check_p2p_binary(inputs.p2p_binary.as_deref()));
+    checks.push(check_config(
+        inputs.config_path.as_deref(),
+        inputs.config_error.as_deref(),
+    ));
+    Report {
+        generated_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        checks,
+    }
+}
+
+/// The library file and its directory both need to be writable: SQLite
+/// keeps its journal next to the database
+pub fn check_database(db_path: &Path) -> Check {
+    const NAME: &str = "Chat library";
+    let Some(dir) = db_path.parent() else {
+        return Check::new(NAME, CheckStatus::Fail, "Database path has no directory");
+    };
+    if let Err(e) = std::fs::OpenOptions::new().append(true).open(db_path) {
+        return Check::new(
+            NAME,
+            CheckStatus::Fail,
+            format!("{} isn't writable: {}", db_path.display(), e),
+        );
+    }
+    let probe = dir.join(format!(".diagnostics-{}", std::process::id()));
+    match std::fs::write(&probe, b"") {
+        Ok(()) => {
+            let _ = std::fs::remove_file(&probe);
+            Check::new(NAME, CheckStatus::Pass, format!("{} is writable", db_path.display()))
+        }
+        Err(e) => Check::new(
+            NAME,
+            CheckStatus::Fail,
+            format!("{} isn't writable: {}", dir.display(), e),
+        ),
+    }
+}
+
+pub fn check_cursor_installs(installs: &[(String, PathBuf)], databases: usize) -> Check {
+    const NAME: &str = "Cursor installs";
+    if installs.is_empty() {
+        return Check::new(
+            NAME,
+            CheckStatus::Warn,
+            "No Cursor data directories found (~/.config/Cursor or ~/.cursor-*)",
+        );
+    }
+    let versions: Vec<&str> = installs.iter().map(|(v, _)| v.as_str()).collect();
+    let detail = format!(
+        "{} found ({}); {} with chat databases",
+        installs.len(),
+        versions.join(", "),
+        databases
+    );
+    let status = if databases == 0 {
+        CheckStatus::Warn
+    } else {
+        CheckStatus::Pass
+    };
+    Check::new(NAME, status, detail)
+}
+
+/// egui's built-in fonts cover text, but not every symbol or emoji the UI uses
+pub fn check_fonts(loaded: &[String]) -> Check {
+    const NAME: &str = "Fonts";
+    let has_mono = loaded.iter().any(|f| f.contains("Mono"));
+    let has_symbols = loaded
+        .iter()
+        .any(|f| f.contains("Emoji") || f.contains("Symbol"));
+    let mut missing = Vec::new();
+    if !has_mono {
+        missing.push("monospace");
+    }
+    if !has_symbols {
+        missing.push("emoji/symbol");
+    }
+    let found = if loaded.is_empty() {
+        "none".to_string()
+    } else {
+        loaded.join(", ")
+    };
+    if missing.is_empty() {
+        Check::new(NAME, CheckStatus::Pass, found)
+    } else {
+        Check::new(
+            NAME,
+            CheckStatus::Warn,
+            format!("No {} font, using built-ins (loaded: {})", missing.join(" or "), found),
+        )
+    }
+}
+
+/// Distinct `scheme://host` origins of the download URLs
+pub fn download_hosts(urls: &[String]) -> Vec<String> {
+    let mut hosts: Vec<String> = Vec::new();
+    for url in urls {
+        let Some((scheme, rest)) = url.split_once("://") else {
+            continue;
+        };
+        let host = rest.split('/').next().unwrap_or("");
+        let origin = format!("{}://{}", scheme, host);
+        if !host.is_empty() && !hosts.contains(&origin) {
+            hosts.push(origin);
+        }
+    }
+    hosts
+}
+
+/// Any HTTP answer counts: only failing to connect means downloads can't work
+fn check_host(origin: &str, configured_proxy: Option<&str>, timeout: Duration) -> Check {
+    const NAME: &str = "Download host";
+    let proxy = versions::effective_proxy(origin, configured_proxy);
+    let via = proxy
+        .as_deref()
+        .map(|p| format!(" via {}", versions::redact_proxy(p)))
+        .unwrap_or_default();
+
+    let mut builder = reqwest::blocking::Client::builder()
+        .user_agent("cursor-studio/0.2.0")
+        .no_proxy()
+        .connect_timeout(timeout)
+        .timeout(timeout * 2);
+    if let Some(proxy) = &proxy {
+        match reqwest::Proxy::all(proxy) {
+            Ok(proxy) => builder = builder.proxy(proxy),
+            Err(e) => {
+                return Check::new(NAME, CheckStatus::Fail, format!("Invalid proxy{}: {}", via, e))
+            }
+        }
+    }
+    let result = builder
+        .build()
+        .map_err(|e| e.to_string())
+        .and_then(|client| client.head(origin).send().map_err(|e| e.to_string()));
+    match result {
+        Ok(response) => Check::new(
+            NAME,
+            CheckStatus::Pass,
+            format!("{} reachable{} (HTTP {})", origin, via, response.status().as_u16()),
+        ),
+        Err(e) => Check::new(
+            NAME,
+            CheckStatus::Fail,
+            format!("{} unreachable{}: {}", origin, via, e),
+        ),
+    }
+}
+
+pub fn check_p2p_binary(path: Option<&Path>) -> Check {
+    const NAME: &str = "p2p-sync binary";
+    match path {
+        Some(path) => Check::new(NAME, CheckStatus::Pass, path.display().to_string()),
+        None => Check::new(
+            NAME,
+            CheckStatus::Warn,
+            "Not found next to cursor-studio, in target/release or ~/.cargo/bin; P2P sync won't start",
+        ),
+    }
+}
+
+pub fn check_config(path: Option<&Path>, error: Option<&str>) -> Check {
+    const NAME: &str = "config.json";
+    match (path, error) {
+        (None, _) => Check::new(NAME, CheckStatus::Warn, "No config directory on this system"),
+        (Some(path), Some(error)) => Check::new(
+            NAME,
+            CheckStatus::Fail,
+            format!("{} doesn't parse, so it's ignored: {}", path.display(), error),
+        ),
+        (Some(path), None) if path.exists() => {
+            Check::new(NAME, CheckStatus::Pass, format!("{} loaded", path.display()))
+        }
+        (Some(path), None) => Check::new(
+            NAME,
+            CheckStatus::Pass,
+            format!("No {}, using in-app settings", path.display()),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_database() {
+        let dir = std::env::temp_dir().join(format!("diagnostics-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db_path = dir.join("chats.db");
+        assert_eq!(check_database(&db_path).status, CheckStatus::Fail);
+
+        std::fs::write(&db_path, b"").unwrap();
+        assert_eq!(check_database(&db_path).status, CheckStatus::Pass);
+        // The probe file is cleaned up
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_check_fonts_and_installs() {
+        let fonts = vec!["JetBrainsMono-Regular".to_string()];
+        let check = check_fonts(&fonts);
+        assert_eq!(check.status, CheckStatus::Warn);
+        assert!(check.detail.contains("emoji/symbol"));
+        let fonts = vec![
+            "JetBrainsMono-Regular".to_string(),
+            "NotoColorEmoji".to_string(),
+        ];
+        assert_eq!(check_fonts(&fonts).status, CheckStatus::Pass);
+
+        assert_eq!(check_cursor_installs(&[], 0).status, CheckStatus::Warn);
+        let installs = vec![("default".to_string(), PathBuf::from("/home/u/.config/Cursor"))];
+        assert_eq!(check_cursor_installs(&installs, 1).status, CheckStatus::Pass);
+    }
+
+    #[test]
+    fn test_report_text() {
+        assert_eq!(
+            download_hosts(&[
+                "https://downloads.cursor.com/production/a/Cursor.AppImage".to_string(),
+                "https://downloads.cursor.com/production/b/Cursor.AppImage".to_string(),
+            ]),
+            vec!["https://downloads.cursor.com".to_string()]
+        );
+
+        let report = Report {
+            generated_at: "2024-05-01 09:00:00".to_string(),
+            checks: vec![
+                check_p2p_binary(None),
+                check_config(Some(Path::new("/cfg/config.json")), Some("expected value")),
+            ],
+        };
+        assert_eq!(report.count(CheckStatus::Fail), 1);
+        let text = report.to_text();
+        assert!(text.contains("2024-05-01 09:00:00"));
+        assert!(text.contains("[WARN] p2p-sync binary: Not found"));
+        assert!(text.contains("[FAIL] config.json: /cfg/config.json doesn't parse"));
+    }
+}
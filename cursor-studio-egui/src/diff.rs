@@ -0,0 +1,220 @@
+//! Unified diff parsing for tool results that contain patches
+//!
+//! Parsing is strict: anything that doesn't read as a well-formed unified diff
+//! returns `None` so callers fall back to plain rendering.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLineKind {
+    Context,
+    Added,
+    Removed,
+    /// `\ No newline at end of file` and similar markers
+    Note,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    pub old_line: Option<usize>,
+    pub new_line: Option<usize>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffHunk {
+    /// The `@@ -a,b +c,d @@` line, including any section name
+    pub header: String,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiffFile {
+    /// Path from the `+++`/`---` headers, when present
+    pub path: Option<String>,
+    pub hunks: Vec<DiffHunk>,
+}
+
+/// File header lines that may precede the hunks of a file
+const FILE_HEADER_PREFIXES: &[&str] = &[
+    "diff ",
+    "index ",
+    "new file mode",
+    "deleted file mode",
+    "old mode",
+    "new mode",
+    "similarity index",
+    "rename from",
+    "rename to",
+];
+
+/// Parse `text` as a unified diff, or `None` if it isn't clearly one
+pub fn parse_unified_diff(text: &str) -> Option<Vec<DiffFile>> {
+    let mut files: Vec<DiffFile> = Vec::new();
+    let mut lines = text.trim_matches('\n').lines().peekable();
+
+    while let Some(line) = lines.next() {
+        if line.starts_with("diff ") {
+            files.push(DiffFile::default());
+        } else if let Some(old) = line.strip_prefix("--- ") {
+            let new_path = lines.next()?.strip_prefix("+++ ")?;
+            // A `---` right after another file's hunks starts a new file
+            if !files.last().is_some_and(|f| f.hunks.is_empty()) {
+                files.push(DiffFile::default());
+            }
+            let file = files.last_mut()?;
+            file.path = header_path(new_path).or_else(|| header_path(old));
+        } else if line.starts_with("@@") {
+            let (old_start, old_count, new_start, new_count) = parse_hunk_header(line)?;
+            if files.is_empty() {
+                files.push(DiffFile::default());
+            }
+
+            let mut hunk = DiffHunk {
+                header: line.to_string(),
+                lines: Vec::new(),
+            };
+            let (mut old_left, mut new_left) = (old_count, new_count);
+            let (mut old_line, mut new_line) = (old_start, new_start);
+            loop {
+                let note_follows = lines.peek().is_some_and(|l| l.starts_with('\\'));
+                if old_left == 0 && new_left == 0 && !note_follows {
+                    break;
+                }
+                let body = lines.next()?;
+                let (kind, text) = match body.chars().next() {
+                    Some('+') => (DiffLineKind::Added, &body[1..]),
+                    Some('-') => (DiffLineKind::Removed, &body[1..]),
+                    Some(' ') => (DiffLineKind::Context, &body[1..]),
+                    Some('\\') => (DiffLineKind::Note, body),
+                    // Some tools drop the leading space from blank context lines
+                    None => (DiffLineKind::Context, ""),
+                    _ => return None,
+                };
+
+                let (old_no, new_no) = match kind {
+                    DiffLineKind::Added => (None, Some(new_line)),
+                    DiffLineKind::Removed => (Some(old_line), None),
+                    DiffLineKind::Context => (Some(old_line), Some(new_line)),
+                    DiffLineKind::Note => (None, None),
+                };
+                if old_no.is_some() {
+                    old_left = old_left.checked_sub(1)?;
+                    old_line += 1;
+                }
+                if new_no.is_some() {
+                    new_left = new_left.checked_sub(1)?;
+                    new_line += 1;
+                }
+
+                hunk.lines.push(DiffLine {
+                    kind,
+                    old_line: old_no,
+                    new_line: new_no,
+                    text: text.to_string(),
+                });
+            }
+            files.last_mut()?.hunks.push(hunk);
+        } else if !line.trim().is_empty() && !is_file_header(line) {
+            return None;
+        }
+    }
+
+    let has_hunks = files.iter().any(|f| !f.hunks.is_empty());
+    has_hunks.then_some(files)
+}
+
+fn is_file_header(line: &str) -> bool {
+    FILE_HEADER_PREFIXES.iter().any(|p| line.starts_with(p))
+}
+
+/// `(old_start, old_count, new_start, new_count)` from `@@ -a,b +c,d @@`
+fn parse_hunk_header(line: &str) -> Option<(usize, usize, usize, usize)> {
+    let ranges = line.strip_prefix("@@ ")?.split(" @@").next()?;
+    let (old, new) = ranges.split_once(' ')?;
+    let (old_start, old_count) = parse_range(old.strip_prefix('-')?)?;
+    let (new_start, new_count) = parse_range(new.strip_prefix('+')?)?;
+    Some((old_start, old_count, new_start, new_count))
+}
+
+fn parse_range(range: &str) -> Option<(usize, usize)> {
+    match range.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
+
+/// Path from a `---`/`+++` header, without the `a/`/`b/` prefix or timestamp
+fn header_path(header: &str) -> Option<String> {
+    let path = header.split('\t').next()?.trim();
+    if path.is_empty() || path == "/dev/null" {
+        return None;
+    }
+    let path = path
+        .strip_prefix("a/")
+        .or_else(|| path.strip_prefix("b/"))
+        .unwrap_or(path);
+    Some(path.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATCH: &str = "diff --git a/src/lib.rs b/src/lib.rs
+index 83db48f..bf269f4 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,4 @@ mod tests
+ fn main() {
+-    println!(\"hi\");
++    println!(\"hello\");
++    println!(\"world\");
+ }
+";
+
+    #[test]
+    fn test_parse_unified_diff() {
+        let files = parse_unified_diff(PATCH).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path.as_deref(), Some("src/lib.rs"));
+
+        let hunk = &files[0].hunks[0];
+        assert_eq!(hunk.header, "@@ -1,3 +1,4 @@ mod tests");
+        let kinds: Vec<_> = hunk.lines.iter().map(|l| l.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                DiffLineKind::Context,
+                DiffLineKind::Removed,
+                DiffLineKind::Added,
+                DiffLineKind::Added,
+                DiffLineKind::Context,
+            ]
+        );
+        assert_eq!(hunk.lines[3].new_line, Some(3));
+        assert_eq!(hunk.lines[4].old_line, Some(3));
+        assert_eq!(hunk.lines[4].new_line, Some(4));
+    }
+
+    #[test]
+    fn test_multiple_files_and_bare_hunks() {
+        let two = "--- a/one.txt\n+++ b/one.txt\n@@ -1 +1 @@\n-a\n+b\n\
+                   --- /dev/null\n+++ b/two.txt\n@@ -0,0 +1 @@\n+new\n";
+        let files = parse_unified_diff(two).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[1].path.as_deref(), Some("two.txt"));
+
+        let bare = parse_unified_diff("@@ -1,2 +1,2 @@\n x\n-y\n+z\n").unwrap();
+        assert_eq!(bare[0].path, None);
+        assert_eq!(bare[0].hunks[0].lines.len(), 3);
+    }
+
+    #[test]
+    fn test_uncertain_content_is_rejected() {
+        assert!(parse_unified_diff("Edited src/lib.rs\n@@ -1 +1 @@\n-a\n+b").is_none());
+        assert!(parse_unified_diff("--- a/x\n+++ b/x\n").is_none());
+        // Body shorter than the header claims
+        assert!(parse_unified_diff("@@ -1,3 +1,3 @@\n a\n").is_none());
+        assert!(parse_unified_diff("- item one\n- item two").is_none());
+    }
+}
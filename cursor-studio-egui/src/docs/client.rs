@@ -101,7 +101,7 @@ impl DocsClient {
                     chunks_count: row.get::<_, i64>(4)? as usize,
                     last_indexed: row.get(5)?,
                     created_at: row.get(6)?,
-                    error_message: None, // TODO: fetch from error_log table if available
+                    error_message: None, // Filled in below for failed sources
                     security_tier: None,
                     alerts_count: 0,
                 })
@@ -110,6 +110,11 @@ impl DocsClient {
             .collect::<Result<Vec<_>, _>>()
             .map_err(|e| format!("Failed to collect results: {}", e))?;
 
+        let sources = sources
+            .into_iter()
+            .map(|source| with_failure_reason(&conn, source))
+            .collect();
+
         Ok(sources)
     }
 
@@ -148,7 +153,7 @@ impl DocsClient {
                     chunks_count: row.get::<_, i64>(4)? as usize,
                     last_indexed: row.get(5)?,
                     created_at: row.get(6)?,
-                    error_message: None, // TODO: fetch from error_log table if available
+                    error_message: None, // Filled in below for failed sources
                     security_tier: None,
                     alerts_count: 0,
                 })
@@ -156,13 +161,25 @@ impl DocsClient {
             .optional()
             .map_err(|e| format!("Failed to execute query: {}", e))?;
 
-        Ok(source)
+        Ok(source.map(|source| with_failure_reason(&conn, source)))
     }
 
-    /// Search indexed content using FTS5
+    /// Search indexed content using FTS5, falling back to a plain substring
+    /// scan when the database has no FTS index
     pub fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>, String> {
         let conn = self.get_connection()?;
 
+        let has_fts: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE name = 'doc_chunks_fts')",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+        if !has_fts {
+            return self.search_like(&conn, query, limit);
+        }
+
         // Using FTS5 match query
         let mut stmt = conn
             .prepare(
@@ -182,7 +199,7 @@ impl DocsClient {
             .map_err(|e| format!("Failed to prepare search query: {}", e))?;
 
         let results = stmt
-            .query_map([query, &limit.to_string()], |row| {
+            .query_map([&fts_query(query), &limit.to_string()], |row| {
                 let content: String = row.get(4)?;
                 let snippet = create_snippet(&content, query, 150);
 
@@ -207,6 +224,62 @@ impl DocsClient {
         Ok(results)
     }
 
+    /// Case-insensitive substring search over chunk content and titles
+    fn search_like(
+        &self,
+        conn: &Connection,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<SearchResult>, String> {
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT
+                    c.id, c.source_id, c.url, c.title, c.content, c.position,
+                    s.name as source_name
+                FROM doc_chunks c
+                JOIN doc_sources s ON c.source_id = s.id
+                WHERE c.content LIKE ?1 ESCAPE '\' OR c.title LIKE ?1 ESCAPE '\'
+                ORDER BY s.name, c.position
+                LIMIT ?2
+                "#,
+            )
+            .map_err(|e| format!("Failed to prepare search query: {}", e))?;
+
+        let pattern = format!(
+            "%{}%",
+            query
+                .trim()
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_")
+        );
+        let results = stmt
+            .query_map([&pattern, &limit.to_string()], |row| {
+                let content: String = row.get(4)?;
+                let snippet = create_snippet(&content, query, 150);
+
+                Ok(SearchResult {
+                    chunk: DocChunk {
+                        id: row.get(0)?,
+                        source_id: row.get(1)?,
+                        url: row.get(2)?,
+                        title: row.get(3)?,
+                        content,
+                        position: row.get::<_, i64>(5)? as usize,
+                    },
+                    source_name: row.get(6)?,
+                    score: 0.0,
+                    snippet,
+                })
+            })
+            .map_err(|e| format!("Failed to execute search: {}", e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to collect search results: {}", e))?;
+
+        Ok(results)
+    }
+
     /// Get chunks for a specific source
     pub fn get_chunks(&self, source_id: &str, limit: usize) -> Result<Vec<DocChunk>, String> {
         let conn = self.get_connection()?;
@@ -241,6 +314,51 @@ impl DocsClient {
         Ok(chunks)
     }
 
+    /// Visit every chunk of a source in order without loading them all at once
+    ///
+    /// Stops at the first error returned by `visit`. Returns the number of chunks visited.
+    pub fn for_each_chunk(
+        &self,
+        source_id: &str,
+        mut visit: impl FnMut(DocChunk) -> Result<(), String>,
+    ) -> Result<usize, String> {
+        let conn = self.get_connection()?;
+
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT id, source_id, url, title, content, position
+                FROM doc_chunks
+                WHERE source_id = ?
+                ORDER BY position
+                "#,
+            )
+            .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+        let mut rows = stmt
+            .query([source_id])
+            .map_err(|e| format!("Failed to execute query: {}", e))?;
+
+        let mut count = 0;
+        while let Some(row) = rows
+            .next()
+            .map_err(|e| format!("Failed to read chunk: {}", e))?
+        {
+            let chunk = DocChunk {
+                id: row.get(0).map_err(|e| e.to_string())?,
+                source_id: row.get(1).map_err(|e| e.to_string())?,
+                url: row.get(2).map_err(|e| e.to_string())?,
+                title: row.get(3).map_err(|e| e.to_string())?,
+                content: row.get(4).map_err(|e| e.to_string())?,
+                position: row.get::<_, i64>(5).map_err(|e| e.to_string())? as usize,
+            };
+            visit(chunk)?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
     /// Get statistics
     pub fn get_stats(&self) -> Result<DocsStats, String> {
         let conn = self.get_connection()?;
@@ -346,15 +464,71 @@ impl DocsClient {
     }
 }
 
-/// Create a snippet with the search term highlighted
+/// Attach the most recent scrape job error to a failed source
+///
+/// Older cursor-docs databases have no `scrape_jobs` table; the source is returned unchanged.
+fn with_failure_reason(conn: &Connection, mut source: DocSource) -> DocSource {
+    if source.status == SourceStatus::Failed {
+        source.error_message = conn
+            .query_row(
+                r#"
+                SELECT error FROM scrape_jobs
+                WHERE source_id = ? AND error IS NOT NULL AND error != ''
+                ORDER BY COALESCE(completed_at, started_at, created_at) DESC
+                LIMIT 1
+                "#,
+                [&source.id],
+                |row| row.get(0),
+            )
+            .optional()
+            .ok()
+            .flatten();
+    }
+    source
+}
+
+/// Quote each word so user input can't trip FTS5 query syntax (`-`, `:`, `"`...)
+fn fts_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Largest char boundary in `s` at or below `index`
+fn floor_char_boundary(s: &str, index: usize) -> usize {
+    let mut index = index.min(s.len());
+    while !s.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// Create a snippet around the first match of the query (or of any of its words)
 fn create_snippet(content: &str, query: &str, max_len: usize) -> String {
     let lower_content = content.to_lowercase();
     let lower_query = query.to_lowercase();
 
+    // Lowercasing can change byte lengths for some scripts; only trust positions
+    // when it didn't
+    let found = if lower_content.len() == content.len() {
+        lower_content
+            .find(lower_query.trim())
+            .map(|pos| (pos, lower_query.trim().len()))
+            .or_else(|| {
+                lower_query
+                    .split_whitespace()
+                    .find_map(|term| lower_content.find(term).map(|pos| (pos, term.len())))
+            })
+    } else {
+        None
+    };
+
     // Find the first occurrence of the query
-    if let Some(pos) = lower_content.find(&lower_query) {
-        let start = pos.saturating_sub(max_len / 2);
-        let end = (pos + query.len() + max_len / 2).min(content.len());
+    if let Some((pos, match_len)) = found {
+        let start = floor_char_boundary(content, pos.saturating_sub(max_len / 2));
+        let end = floor_char_boundary(content, pos + match_len + max_len / 2);
 
         let mut snippet = String::new();
         if start > 0 {
@@ -368,7 +542,7 @@ fn create_snippet(content: &str, query: &str, max_len: usize) -> String {
     } else {
         // No match found, return beginning of content
         if content.len() > max_len {
-            format!("{}...", &content[..max_len])
+            format!("{}...", &content[..floor_char_boundary(content, max_len)])
         } else {
             content.to_string()
         }
@@ -386,6 +560,25 @@ mod tests {
         assert!(snippet.contains("important"));
     }
 
+    #[test]
+    fn test_create_snippet_multi_word_and_unicode() {
+        let content = format!("{} nix flakes are great", "ü".repeat(100));
+        let snippet = create_snippet(&content, "Flakes missing", 20);
+        assert!(snippet.contains("flakes"));
+        assert!(snippet.starts_with("..."));
+
+        let no_match = create_snippet(&"é".repeat(100), "zzz", 15);
+        assert!(no_match.ends_with("..."));
+    }
+
+    #[test]
+    fn test_fts_query_quotes_terms() {
+        assert_eq!(
+            fts_query("nix-shell  foo\"bar"),
+            "\"nix-shell\" \"foo\"\"bar\""
+        );
+    }
+
     #[test]
     fn test_default_db_path() {
         let path = DocsClient::default_db_path();
@@ -0,0 +1,177 @@
+//! Export an indexed documentation source to Markdown or JSON
+//!
+//! Chunks are streamed from the database straight into the output file so
+//! sources with hundreds of chunks never sit in memory all at once.
+
+use super::client::DocsClient;
+use super::models::{DocChunk, DocSource};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Output format for [`export_source`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocExportFormat {
+    Markdown,
+    Json,
+}
+
+impl DocExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            DocExportFormat::Markdown => "md",
+            DocExportFormat::Json => "json",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DocExportFormat::Markdown => "Markdown",
+            DocExportFormat::Json => "JSON",
+        }
+    }
+}
+
+/// Write all chunks of `source_id` into `dir`, returning the file path and chunk count
+pub fn export_source(
+    client: &DocsClient,
+    source_id: &str,
+    format: DocExportFormat,
+    dir: &Path,
+) -> Result<(PathBuf, usize), String> {
+    let source = client
+        .get_source(source_id)?
+        .ok_or_else(|| "Source not found".to_string())?;
+
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let path = dir.join(format!(
+        "docs-{}.{}",
+        file_stem(source.display_name()),
+        format.extension()
+    ));
+    let file =
+        File::create(&path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    let mut out = BufWriter::new(file);
+    let io_err = |e: std::io::Error| format!("Failed to write export: {}", e);
+
+    let count = match format {
+        DocExportFormat::Markdown => {
+            write_markdown_header(&mut out, &source).map_err(io_err)?;
+            client.for_each_chunk(source_id, |chunk| {
+                write_markdown_chunk(&mut out, &chunk).map_err(io_err)
+            })?
+        }
+        DocExportFormat::Json => {
+            let source_json = serde_json::to_string(&source).map_err(|e| e.to_string())?;
+            write!(out, "{{\"source\":{},\"chunks\":[", source_json).map_err(io_err)?;
+            let mut first = true;
+            let count = client.for_each_chunk(source_id, |chunk| {
+                let chunk_json = serde_json::to_string(&chunk).map_err(|e| e.to_string())?;
+                let separator = if first { "\n" } else { ",\n" };
+                first = false;
+                write!(out, "{}{}", separator, chunk_json).map_err(io_err)
+            })?;
+            writeln!(out, "\n]}}").map_err(io_err)?;
+            count
+        }
+    };
+
+    out.flush().map_err(io_err)?;
+    Ok((path, count))
+}
+
+fn write_markdown_header(out: &mut impl Write, source: &DocSource) -> std::io::Result<()> {
+    writeln!(out, "# {}\n", source.display_name())?;
+    writeln!(out, "**Source:** {}", source.url)?;
+    if let Some(last) = &source.last_indexed {
+        writeln!(out, "**Last indexed:** {}", last)?;
+    }
+    writeln!(out, "**Chunks:** {}\n", source.chunks_count)?;
+    writeln!(out, "---\n")
+}
+
+fn write_markdown_chunk(out: &mut impl Write, chunk: &DocChunk) -> std::io::Result<()> {
+    let title = if chunk.title.is_empty() {
+        "(untitled)"
+    } else {
+        &chunk.title
+    };
+    writeln!(out, "## {}\n", title)?;
+    if !chunk.url.is_empty() {
+        writeln!(out, "<{}>\n", chunk.url)?;
+    }
+    writeln!(out, "{}\n", chunk.content.trim_end())
+}
+
+/// Filesystem-safe name derived from a source name
+fn file_stem(name: &str) -> String {
+    let stem: String = name
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect();
+    let stem = stem.trim_matches('-');
+    if stem.is_empty() {
+        "source".to_string()
+    } else {
+        stem.chars().take(60).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+
+    fn fixture_db() -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("cursor-docs-export-{}.db", uuid::Uuid::new_v4()));
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(
+            r#"
+            CREATE TABLE doc_sources (
+                id TEXT PRIMARY KEY, url TEXT, name TEXT, status TEXT,
+                chunks_count INTEGER, last_indexed TEXT, created_at TEXT
+            );
+            CREATE TABLE doc_chunks (
+                id TEXT PRIMARY KEY, source_id TEXT, url TEXT, title TEXT,
+                content TEXT, position INTEGER
+            );
+            INSERT INTO doc_sources VALUES
+                ('s1', 'https://docs.example.com', 'Example Docs', 'indexed', 2, NULL,
+                 '2025-01-01');
+            INSERT INTO doc_chunks VALUES
+                ('c2', 's1', 'https://docs.example.com/b', 'Second', 'More text', 1),
+                ('c1', 's1', 'https://docs.example.com/a', 'First', 'Hello world', 0);
+            "#,
+        )
+        .unwrap();
+        path
+    }
+
+    #[test]
+    fn test_export_source_markdown_and_json() {
+        let db = fixture_db();
+        let client = DocsClient::new_sqlite(db.clone());
+        let dir = std::env::temp_dir().join(format!("cursor-docs-out-{}", uuid::Uuid::new_v4()));
+
+        let (md_path, count) =
+            export_source(&client, "s1", DocExportFormat::Markdown, &dir).unwrap();
+        assert_eq!(count, 2);
+        let md = std::fs::read_to_string(&md_path).unwrap();
+        assert!(md.starts_with("# Example Docs"));
+        assert!(md.find("## First").unwrap() < md.find("## Second").unwrap());
+
+        let (json_path, _) = export_source(&client, "s1", DocExportFormat::Json, &dir).unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&json_path).unwrap()).unwrap();
+        assert_eq!(json["source"]["name"], "Example Docs");
+        assert_eq!(json["chunks"][0]["content"], "Hello world");
+        assert_eq!(json["chunks"].as_array().unwrap().len(), 2);
+
+        assert!(export_source(&client, "missing", DocExportFormat::Json, &dir).is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::remove_file(&db);
+    }
+}
@@ -4,13 +4,16 @@
 //! - Add documentation sources by URL
 //! - View indexed sources and their status
 //! - Search indexed content
+//! - Export a source's indexed content to Markdown or JSON
 //! - Manage (refresh, delete) sources
 
 mod client;
+mod export;
 mod models;
 pub mod ui;
 
 pub use client::DocsClient;
+pub use export::{export_source, DocExportFormat};
 pub use models::*;
 pub use ui::{DocsPanel, DocsPanelEvent, DocsTheme};
 
@@ -14,6 +14,13 @@ use std::sync::mpsc;
 pub enum DocsPanelEvent {
     /// Open a source in the main editor area
     OpenSource { source_id: String, source_name: String },
+    /// Open a source and scroll to one of its chunks (from a search hit)
+    OpenChunk {
+        source_id: String,
+        source_name: String,
+        chunk_id: String,
+        position: usize,
+    },
     /// Status message to show in main app
     StatusMessage(String),
 }
@@ -66,6 +73,8 @@ pub struct DocsPanel {
     indexing_receiver: Option<mpsc::Receiver<IndexingUpdate>>,
     /// Pending delete confirmation (source_id, source_name)
     pending_delete: Option<(String, String)>,
+    /// Last indexing error reported by the CLI, keyed by source URL
+    failure_reasons: std::collections::HashMap<String, String>,
 }
 
 /// Update from background indexing thread
@@ -107,6 +116,7 @@ impl DocsPanel {
             pending_events: Vec::new(),
             indexing_receiver: None,
             pending_delete: None,
+            failure_reasons: std::collections::HashMap::new(),
         };
 
         panel.refresh();
@@ -147,12 +157,14 @@ impl DocsPanel {
                 }
                 IndexingUpdate::Complete { url, chunks } => {
                     self.indexing_jobs.retain(|j| j.url != url);
+                    self.failure_reasons.remove(&url);
                     self.success_message = Some(format!("Indexed {} ({} chunks)", url, chunks));
                     needs_refresh = true;
                 }
                 IndexingUpdate::Error { url, error } => {
                     self.indexing_jobs.retain(|j| j.url != url);
                     self.error_message = Some(format!("Failed to index {}: {}", url, error));
+                    self.failure_reasons.insert(url, error.trim().to_string());
                     needs_refresh = true;
                 }
             }
@@ -179,6 +191,35 @@ impl DocsPanel {
         self.last_refresh = std::time::Instant::now();
     }
 
+    /// Why indexing of `source` last failed, if it did
+    ///
+    /// Prefers the reason recorded by cursor-docs, then the CLI error seen in this session.
+    pub fn failure_reason(&self, source: &DocSource) -> Option<String> {
+        source
+            .error_message
+            .clone()
+            .or_else(|| self.failure_reasons.get(&source.url).cloned())
+            .or_else(|| {
+                (source.status == SourceStatus::Failed)
+                    .then(|| "Indexing failed (no details recorded)".to_string())
+            })
+    }
+
+    /// Whether an indexing job for `url` is currently running
+    pub fn is_indexing(&self, url: &str) -> bool {
+        self.indexing_jobs.iter().any(|job| job.url == url)
+    }
+
+    /// Start indexing a source again with its original URL and name
+    pub fn retry_indexing(&mut self, source: &DocSource) {
+        if self.is_indexing(&source.url) {
+            return;
+        }
+        self.failure_reasons.remove(&source.url);
+        let name = (!source.name.trim().is_empty()).then(|| source.name.clone());
+        self.start_indexing(source.url.clone(), name);
+    }
+
     /// Perform search
     pub fn search(&mut self) {
         if self.search_query.trim().is_empty() {
@@ -198,7 +239,7 @@ impl DocsPanel {
         }
     }
 
-    /// Add a new source via cursor-docs CLI
+    /// Add the source entered in the add form
     fn add_source(&mut self) {
         let url = self.add_url_input.trim().to_string();
         if url.is_empty() {
@@ -211,6 +252,14 @@ impl DocsPanel {
         } else {
             Some(self.add_name_input.trim().to_string())
         };
+        self.start_indexing(url, name);
+        self.add_url_input.clear();
+        self.add_name_input.clear();
+        self.show_add_form = false;
+    }
+
+    /// Index `url` in the background via cursor-docs CLI
+    fn start_indexing(&mut self, url: String, name: Option<String>) {
         let max_pages = self.add_max_pages;
 
         // Build command
@@ -364,9 +413,6 @@ impl DocsPanel {
         });
 
         self.success_message = Some(format!("Adding {} (max {} pages)...", url, max_pages));
-        self.add_url_input.clear();
-        self.add_name_input.clear();
-        self.show_add_form = false;
     }
 
     /// Request deletion of a source (shows confirmation)
@@ -505,7 +551,7 @@ impl DocsPanel {
                             ui.add_space(4.0);
                             ui.label(
                                 RichText::new(format!("Delete \"{}\" and all its indexed content?", source_name))
-                                    .size(11.0)
+                                    .size(theme.text_size(11.0))
                                     .color(theme.fg()),
                             );
                             ui.add_space(8.0);
@@ -515,7 +561,7 @@ impl DocsPanel {
                                         Vec2::new(80.0, 26.0),
                                         egui::Button::new(
                                             RichText::new("🗑️ Delete")
-                                                .size(11.0)
+                                                .size(theme.text_size(11.0))
                                                 .color(Color32::WHITE),
                                         )
                                         .fill(theme.error())
@@ -529,9 +575,11 @@ impl DocsPanel {
                                 if ui
                                     .add_sized(
                                         Vec2::new(80.0, 26.0),
-                                        egui::Button::new(RichText::new("Cancel").size(11.0))
-                                            .fill(theme.button_bg())
-                                            .rounding(Rounding::same(4.0)),
+                                        egui::Button::new(
+                                            RichText::new("Cancel").size(theme.text_size(11.0)),
+                                        )
+                                        .fill(theme.button_bg())
+                                        .rounding(Rounding::same(4.0)),
                                     )
                                     .clicked()
                                 {
@@ -546,14 +594,22 @@ impl DocsPanel {
                     ui.add_space(8.0);
                     ui.horizontal(|ui| {
                         ui.label(RichText::new("⚠️").size(12.0));
-                        ui.label(RichText::new(msg).color(theme.error()).size(10.0));
+                        ui.label(
+                            RichText::new(msg)
+                                .color(theme.error())
+                                .size(theme.text_size(10.0)),
+                        );
                     });
                 }
                 if let Some(ref msg) = self.success_message {
                     ui.add_space(8.0);
                     ui.horizontal(|ui| {
                         ui.label(RichText::new("✅").size(12.0));
-                        ui.label(RichText::new(msg).color(theme.success()).size(10.0));
+                        ui.label(
+                            RichText::new(msg)
+                                .color(theme.success())
+                                .size(theme.text_size(10.0)),
+                        );
                     });
                 }
             });
@@ -578,10 +634,10 @@ impl DocsPanel {
                     BackendStatus::Connecting => ("◐", theme.accent()),
                     BackendStatus::Error => ("●", theme.error()),
                 };
-                ui.label(RichText::new(icon).size(10.0).color(color));
+                ui.label(RichText::new(icon).size(theme.text_size(10.0)).color(color));
                 ui.label(
                     RichText::new(self.backend_status.label())
-                        .size(9.0)
+                        .size(theme.text_size(9.0))
                         .color(theme.fg_dim()),
                 );
             });
@@ -628,7 +684,7 @@ impl DocsPanel {
                         );
                         ui.label(
                             RichText::new(label)
-                                .size(9.0)
+                                .size(theme.text_size(9.0))
                                 .color(theme.fg_dim()),
                         );
                     });
@@ -645,7 +701,7 @@ impl DocsPanel {
                 Vec2::new(100.0, 28.0),
                 egui::Button::new(
                     RichText::new("➕ Add URL")
-                        .size(11.0)
+                        .size(theme.text_size(11.0))
                         .color(Color32::WHITE),
                 )
                 .fill(theme.accent())
@@ -677,7 +733,7 @@ impl DocsPanel {
             .show(ui, |ui| {
                 ui.label(
                     RichText::new("Add Documentation Source")
-                        .size(11.0)
+                        .size(theme.text_size(11.0))
                         .color(theme.fg())
                         .strong(),
                 );
@@ -689,7 +745,11 @@ impl DocsPanel {
                     .spacing([8.0, 6.0])
                     .show(ui, |ui| {
                         // URL row
-                        ui.label(RichText::new("URL:").size(10.0).color(theme.fg_dim()));
+                        ui.label(
+                            RichText::new("URL:")
+                                .size(theme.text_size(10.0))
+                                .color(theme.fg_dim()),
+                        );
                         ui.add_sized(
                             Vec2::new(ui.available_width() - 8.0, 24.0),
                             egui::TextEdit::singleline(&mut self.add_url_input)
@@ -698,7 +758,11 @@ impl DocsPanel {
                         ui.end_row();
 
                         // Name row
-                        ui.label(RichText::new("Name:").size(10.0).color(theme.fg_dim()));
+                        ui.label(
+                            RichText::new("Name:")
+                                .size(theme.text_size(10.0))
+                                .color(theme.fg_dim()),
+                        );
                         ui.add_sized(
                             Vec2::new(ui.available_width() - 8.0, 24.0),
                             egui::TextEdit::singleline(&mut self.add_name_input)
@@ -707,7 +771,11 @@ impl DocsPanel {
                         ui.end_row();
 
                         // Max pages row
-                        ui.label(RichText::new("Max pages:").size(10.0).color(theme.fg_dim()));
+                        ui.label(
+                            RichText::new("Max pages:")
+                                .size(theme.text_size(10.0))
+                                .color(theme.fg_dim()),
+                        );
                         ui.horizontal(|ui| {
                             ui.add(
                                 egui::DragValue::new(&mut self.add_max_pages)
@@ -716,7 +784,7 @@ impl DocsPanel {
                             );
                             ui.label(
                                 RichText::new("(default: 1000)")
-                                    .size(9.0)
+                                    .size(theme.text_size(9.0))
                                     .color(theme.fg_dim()),
                             );
                         });
@@ -731,7 +799,7 @@ impl DocsPanel {
                         Vec2::new(80.0, 26.0),
                         egui::Button::new(
                             RichText::new("Add")
-                                .size(11.0)
+                                .size(theme.text_size(11.0))
                                 .color(Color32::WHITE),
                         )
                         .fill(theme.accent())
@@ -743,7 +811,7 @@ impl DocsPanel {
 
                     let cancel_btn = ui.add_sized(
                         Vec2::new(80.0, 26.0),
-                        egui::Button::new(RichText::new("Cancel").size(11.0))
+                        egui::Button::new(RichText::new("Cancel").size(theme.text_size(11.0)))
                             .fill(theme.button_bg())
                             .rounding(Rounding::same(4.0)),
                     );
@@ -761,7 +829,7 @@ impl DocsPanel {
             ui.add_space(4.0);
             ui.label(
                 RichText::new("INDEXING")
-                    .size(10.0)
+                    .size(theme.text_size(10.0))
                     .color(theme.accent())
                     .strong(),
             );
@@ -781,7 +849,7 @@ impl DocsPanel {
                         ui.vertical(|ui| {
                             ui.label(
                                 RichText::new(&job.name)
-                                    .size(11.0)
+                                    .size(theme.text_size(11.0))
                                     .color(theme.fg())
                                     .strong(),
                             );
@@ -790,7 +858,7 @@ impl DocsPanel {
                                     "{} • Page {}/{}",
                                     job.status, job.current_page, job.max_pages
                                 ))
-                                .size(9.0)
+                                .size(theme.text_size(9.0))
                                 .color(theme.fg_dim()),
                             );
                             
@@ -840,42 +908,66 @@ impl DocsPanel {
             ui.add_space(4.0);
             ui.label(
                 RichText::new(format!("Results: {}", self.search_results.len()))
-                    .size(10.0)
+                    .size(theme.text_size(10.0))
                     .color(theme.fg_dim()),
             );
         });
 
+        let mut open: Option<DocsPanelEvent> = None;
         egui::ScrollArea::vertical()
             .max_height(180.0)
             .show(ui, |ui| {
                 for result in &self.search_results {
                     ui.add_space(2.0);
-                    egui::Frame::none()
+                    let response = egui::Frame::none()
                         .fill(theme.card_bg())
                         .rounding(Rounding::same(4.0))
                         .inner_margin(egui::Margin::same(8.0))
                         .show(ui, |ui| {
+                            ui.set_width(ui.available_width());
                             ui.label(
                                 RichText::new(&result.chunk.title)
-                                    .size(11.0)
+                                    .size(theme.text_size(11.0))
                                     .color(theme.fg())
                                     .strong(),
                             );
-                            ui.label(
-                                RichText::new(&result.snippet)
-                                    .size(10.0)
-                                    .color(theme.fg_dim()),
-                            );
+                            ui.label(highlighted_snippet(
+                                &result.snippet,
+                                &self.search_query,
+                                theme,
+                            ));
                             ui.horizontal(|ui| {
                                 ui.label(
                                     RichText::new(&result.source_name)
-                                        .size(9.0)
+                                        .size(theme.text_size(9.0))
                                         .color(theme.accent()),
                                 );
+                                ui.label(
+                                    RichText::new(format!("#{}", result.chunk.position + 1))
+                                        .size(theme.text_size(9.0))
+                                        .color(theme.fg_dim()),
+                                );
                             });
+                        })
+                        .response
+                        .interact(egui::Sense::click())
+                        .on_hover_cursor(egui::CursorIcon::PointingHand)
+                        .on_hover_text("Open source at this chunk");
+
+                    if response.clicked() {
+                        open = Some(DocsPanelEvent::OpenChunk {
+                            source_id: result.chunk.source_id.clone(),
+                            source_name: result.source_name.clone(),
+                            chunk_id: result.chunk.id.clone(),
+                            position: result.chunk.position,
                         });
+                    }
                 }
             });
+
+        if let Some(event) = open {
+            self.pending_events.push(event);
+        }
     }
 
     fn show_sources_list(&mut self, ui: &mut egui::Ui, theme: &dyn DocsTheme) {
@@ -883,7 +975,7 @@ impl DocsPanel {
             ui.add_space(4.0);
             ui.label(
                 RichText::new("SOURCES")
-                    .size(10.0)
+                    .size(theme.text_size(10.0))
                     .color(theme.fg_dim())
                     .strong(),
             );
@@ -891,7 +983,7 @@ impl DocsPanel {
                 ui.add_space(4.0);
                 ui.label(
                     RichText::new(format!("{} total", self.sources.len()))
-                        .size(9.0)
+                        .size(theme.text_size(9.0))
                         .color(theme.fg_dim()),
                 );
             });
@@ -907,13 +999,13 @@ impl DocsPanel {
                     ui.vertical_centered(|ui| {
                         ui.label(
                             RichText::new("No sources indexed yet")
-                                .size(11.0)
+                                .size(theme.text_size(11.0))
                                 .color(theme.fg_dim()),
                         );
                         ui.add_space(4.0);
                         ui.label(
                             RichText::new("Click \"Add URL\" to index documentation")
-                                .size(10.0)
+                                .size(theme.text_size(10.0))
                                 .color(theme.fg_dim()),
                         );
                     });
@@ -924,6 +1016,9 @@ impl DocsPanel {
         // Source list with consistent layout
         for source in self.sources.clone() {
             let is_selected = self.selected_source.as_ref() == Some(&source.id);
+            let is_indexing = self.is_indexing(&source.url);
+            let failure = self.failure_reason(&source);
+            let mut retry: Option<DocSource> = None;
             
             let bg = if is_selected {
                 theme.selection_bg()
@@ -943,7 +1038,7 @@ impl DocsPanel {
                         // Status icon - with tooltip for failed sources
                         let status_tooltip = match source.status {
                             super::models::SourceStatus::Failed => {
-                                source.error_message.clone()
+                                failure.clone()
                                     .unwrap_or_else(|| "Indexing failed (check CLI for details)".to_string())
                             }
                             super::models::SourceStatus::Indexed => {
@@ -969,7 +1064,7 @@ impl DocsPanel {
                         
                         ui.label(
                             RichText::new(&truncated)
-                                .size(11.0)
+                                .size(theme.text_size(11.0))
                                 .color(theme.fg())
                                 .strong(),
                         );
@@ -990,7 +1085,7 @@ impl DocsPanel {
                                 .show(ui, |ui| {
                                     ui.label(
                                         RichText::new(format!("{}", source.chunks_count))
-                                            .size(11.0)
+                                            .size(theme.text_size(11.0))
                                             .color(chunk_color)
                                             .strong(),
                                     );
@@ -1001,10 +1096,31 @@ impl DocsPanel {
                     // URL on second line
                     ui.label(
                         RichText::new(&source.url)
-                            .size(9.0)
+                            .size(theme.text_size(9.0))
                             .color(theme.fg_dim()),
                     );
 
+                    // Failure reason and retry, shown even when not selected
+                    if let Some(reason) = &failure {
+                        let summary = reason.lines().next().unwrap_or("");
+                        ui.horizontal(|ui| {
+                            ui.label(
+                                RichText::new(format!("⚠ {}", summary))
+                                    .size(theme.text_size(9.0))
+                                    .color(theme.error()),
+                            )
+                            .on_hover_text(reason);
+                            if !is_indexing
+                                && ui
+                                    .small_button("↻ Retry")
+                                    .on_hover_text("Retry indexing this source")
+                                    .clicked()
+                            {
+                                retry = Some(source.clone());
+                            }
+                        });
+                    }
+
                     // Action buttons when selected
                     if is_selected {
                         ui.add_space(6.0);
@@ -1015,7 +1131,7 @@ impl DocsPanel {
                                     Vec2::new(70.0, 22.0),
                                     egui::Button::new(
                                         RichText::new("📖 View")
-                                            .size(10.0)
+                                            .size(theme.text_size(10.0))
                                             .color(Color32::WHITE),
                                     )
                                     .fill(theme.accent())
@@ -1034,9 +1150,11 @@ impl DocsPanel {
                             if ui
                                 .add_sized(
                                     Vec2::new(70.0, 22.0),
-                                    egui::Button::new(RichText::new("🔄 Refresh").size(10.0))
-                                        .fill(theme.button_bg())
-                                        .rounding(Rounding::same(3.0)),
+                                    egui::Button::new(
+                                        RichText::new("🔄 Refresh").size(theme.text_size(10.0)),
+                                    )
+                                    .fill(theme.button_bg())
+                                    .rounding(Rounding::same(3.0)),
                                 )
                                 .on_hover_text("Re-index this source")
                                 .clicked()
@@ -1053,7 +1171,7 @@ impl DocsPanel {
                                     Vec2::new(70.0, 22.0),
                                     egui::Button::new(
                                         RichText::new("🗑️ Delete")
-                                            .size(10.0)
+                                            .size(theme.text_size(10.0))
                                             .color(theme.error()),
                                     )
                                     .fill(theme.button_bg())
@@ -1077,11 +1195,70 @@ impl DocsPanel {
                 }
             }
 
+            if let Some(source) = retry {
+                self.retry_indexing(&source);
+                let message = format!("⏳ Retrying indexing of {}", source.display_name());
+                self.pending_events
+                    .push(DocsPanelEvent::StatusMessage(message));
+            }
+
             ui.add_space(4.0);
         }
     }
 }
 
+/// Byte ranges of case-insensitive matches of any query word, sorted and non-overlapping
+fn match_ranges(text: &str, query: &str) -> Vec<std::ops::Range<usize>> {
+    // ASCII lowercasing keeps byte offsets valid for the original text
+    let haystack = text.to_ascii_lowercase();
+    let mut ranges: Vec<std::ops::Range<usize>> = query
+        .split_whitespace()
+        .map(|term| term.to_ascii_lowercase())
+        .flat_map(|term| {
+            haystack
+                .match_indices(term.as_str())
+                .map(|(pos, m)| pos..pos + m.len())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    ranges.sort_by_key(|r| r.start);
+
+    let mut merged: Vec<std::ops::Range<usize>> = Vec::new();
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// Search snippet with the matched query words emphasised
+fn highlighted_snippet(snippet: &str, query: &str, theme: &dyn DocsTheme) -> egui::text::LayoutJob {
+    use egui::text::{LayoutJob, TextFormat};
+
+    let normal = TextFormat {
+        font_id: egui::FontId::proportional(theme.text_size(10.0)),
+        color: theme.fg_dim(),
+        ..Default::default()
+    };
+    let highlight = TextFormat {
+        color: theme.fg(),
+        background: theme.selection_bg(),
+        ..normal.clone()
+    };
+
+    let mut job = LayoutJob::default();
+    let mut cursor = 0;
+    for range in match_ranges(snippet, query) {
+        job.append(&snippet[cursor..range.start], 0.0, normal.clone());
+        job.append(&snippet[range.clone()], 0.0, highlight.clone());
+        cursor = range.end;
+    }
+    job.append(&snippet[cursor..], 0.0, normal);
+    job
+}
+
 /// Theme trait for docs panel
 pub trait DocsTheme {
     fn bg(&self) -> Color32;
@@ -1093,6 +1270,10 @@ pub trait DocsTheme {
     fn card_bg(&self) -> Color32;
     fn button_bg(&self) -> Color32;
     fn selection_bg(&self) -> Color32;
+    /// A fixed label size raised to the user's minimum text size
+    fn text_size(&self, size: f32) -> f32 {
+        size
+    }
 }
 
 /// Default dark theme
@@ -1109,3 +1290,15 @@ impl DocsTheme for DarkDocsTheme {
     fn button_bg(&self) -> Color32 { Color32::from_rgb(60, 60, 60) }
     fn selection_bg(&self) -> Color32 { Color32::from_rgb(59, 130, 246).gamma_multiply(0.3) }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_ranges_merges_overlaps() {
+        let ranges = match_ranges("Nix flakes: nixpkgs and flakes", "nix NIXPKGS flake");
+        assert_eq!(ranges, vec![0..3, 4..9, 12..19, 24..29]);
+        assert!(match_ranges("anything", "  ").is_empty());
+    }
+}
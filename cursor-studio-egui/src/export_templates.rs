@@ -0,0 +1,430 @@
+//! Markdown export templates
+//!
+//! A small Handlebars-like syntax: `{{name}}` inserts a value,
+//! `{{#each messages}}…{{/each}}` repeats for every message and
+//! `{{#if name}}…{{/if}}` keeps its body only when the value isn't empty. A
+//! block tag alone on its line takes the line with it, so templates can be laid
+//! out readably without leaving blank lines in the output.
+//!
+//! Users add templates as `<name>.md` files in [`templates_dir`].
+
+use crate::database::{Conversation, Message, MessageRole};
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Name of the built-in renderer, which has no template source
+pub const DEFAULT_TEMPLATE: &str = "default";
+
+const MINIMAL: &str = "# {{title}}
+
+{{#each messages}}
+{{#if content}}
+**{{role_label}}:** {{content}}
+
+{{/if}}
+{{/each}}
+";
+
+const QA: &str = "# {{title}}
+
+{{#each dialogue}}
+{{#if is_user}}
+**Q:** {{content}}
+
+{{/if}}
+{{#if is_assistant}}
+**A:** {{content}}
+
+---
+
+{{/if}}
+{{/each}}
+";
+
+/// Templates that ship with the app, besides the default renderer
+const BUILTIN: [(&str, &str); 2] = [("minimal", MINIMAL), ("qa", QA)];
+
+/// Lists `{{#each}}` can iterate: every message, or only user and assistant
+/// messages with text
+const LISTS: [&str; 2] = ["messages", "dialogue"];
+
+const CONVERSATION_VARS: [&str; 8] = [
+    "title",
+    "id",
+    "source",
+    "category",
+    "tags",
+    "notes",
+    "message_count",
+    "exported",
+];
+
+const MESSAGE_VARS: [&str; 12] = [
+    "index",
+    "role",
+    "role_label",
+    "content",
+    "thinking",
+    "tool_name",
+    "tool_args",
+    "tool_status",
+    "created_at",
+    "is_user",
+    "is_assistant",
+    "is_tool",
+];
+
+type Vars = HashMap<&'static str, String>;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Text(String),
+    Var(String),
+    Each(String, Vec<Node>),
+    If(String, Vec<Node>),
+}
+
+/// A parsed export template
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+/// Where user templates live: `~/.config/cursor-studio/export-templates`
+pub fn templates_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("cursor-studio").join("export-templates"))
+}
+
+/// Display name for a template in the export dialog
+pub fn label(name: &str) -> String {
+    match name {
+        DEFAULT_TEMPLATE => "Default".to_string(),
+        "minimal" => "Minimal".to_string(),
+        "qa" => "Q&A only".to_string(),
+        _ => name.to_string(),
+    }
+}
+
+/// The default renderer, the built-in templates, then user templates by name.
+/// A user template named like a built-in one replaces it.
+pub fn available() -> Vec<String> {
+    let mut names: Vec<String> = std::iter::once(DEFAULT_TEMPLATE)
+        .chain(BUILTIN.iter().map(|(name, _)| *name))
+        .map(String::from)
+        .collect();
+
+    let mut user: Vec<String> = templates_dir()
+        .and_then(|dir| std::fs::read_dir(dir).ok())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let stem = path.file_stem()?.to_str()?.to_string();
+            (path.extension()? == "md").then_some(stem)
+        })
+        .filter(|name| !names.contains(name))
+        .collect();
+    user.sort();
+    names.extend(user);
+    names
+}
+
+/// Load a template by name; `None` means the default renderer
+pub fn load(name: &str) -> Result<Option<Template>> {
+    if name == DEFAULT_TEMPLATE {
+        return Ok(None);
+    }
+    let user_path = templates_dir().map(|dir| dir.join(format!("{}.md", name)));
+    if let Some(path) = user_path.filter(|p| p.exists()) {
+        let source = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        return Template::parse(&source)
+            .map(Some)
+            .with_context(|| format!("Template {}", path.display()));
+    }
+    match BUILTIN.iter().find(|(builtin, _)| *builtin == name) {
+        Some((_, source)) => Template::parse(source).map(Some),
+        None => bail!("No export template named \"{}\"", name),
+    }
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Result<Self> {
+        // Open blocks as (tag, name, nodes before the block)
+        let mut stack: Vec<(&str, String, Vec<Node>)> = Vec::new();
+        let mut nodes = Vec::new();
+        let mut rest = source;
+
+        while let Some(open) = rest.find("{{") {
+            let after = &rest[open + 2..];
+            let Some(close) = after.find("}}") else {
+                bail!("Unclosed \"{{{{\"");
+            };
+            let tag = after[..close].trim();
+            let mut text = &rest[..open];
+            let mut next = &after[close + 2..];
+
+            // A block tag alone on its line removes the whole line
+            if tag.starts_with('#') || tag.starts_with('/') {
+                let line_start = text.rfind('\n').map_or(0, |i| i + 1);
+                if let Some(line_end) = next.find('\n') {
+                    if text[line_start..].trim().is_empty() && next[..line_end].trim().is_empty() {
+                        text = &text[..line_start];
+                        next = &next[line_end + 1..];
+                    }
+                }
+            }
+            if !text.is_empty() {
+                nodes.push(Node::Text(text.to_string()));
+            }
+
+            if let Some(block) = tag.strip_prefix('#') {
+                let (kind, name) = block.split_once(' ').unwrap_or((block, ""));
+                let name = name.trim();
+                match kind {
+                    "each" if LISTS.contains(&name) => {}
+                    "each" => bail!("Unknown list \"{}\" in {{{{#each}}}}", name),
+                    "if" => check_var(name)?,
+                    _ => bail!("Unknown block {{{{#{}}}}}", kind),
+                }
+                let outer = std::mem::take(&mut nodes);
+                stack.push((kind, name.to_string(), outer));
+            } else if let Some(kind) = tag.strip_prefix('/') {
+                let Some((open_kind, name, outer)) = stack.pop() else {
+                    bail!("{{{{/{}}}}} without an opening block", kind);
+                };
+                if kind != open_kind {
+                    bail!("{{{{/{}}}}} closes {{{{#{} {}}}}}", kind, open_kind, name);
+                }
+                let body = std::mem::replace(&mut nodes, outer);
+                nodes.push(match open_kind {
+                    "each" => Node::Each(name, body),
+                    _ => Node::If(name, body),
+                });
+            } else {
+                check_var(tag)?;
+                nodes.push(Node::Var(tag.to_string()));
+            }
+            rest = next;
+        }
+
+        if let Some((kind, name, _)) = stack.last() {
+            bail!("{{{{#{} {}}}}} is never closed", kind, name);
+        }
+        if !rest.is_empty() {
+            nodes.push(Node::Text(rest.to_string()));
+        }
+        Ok(Self { nodes })
+    }
+
+    pub fn render(&self, conv: &Conversation, messages: &[Message]) -> String {
+        let vars: Vars = HashMap::from([
+            ("title", conv.title.clone()),
+            ("id", conv.id.clone()),
+            ("source", conv.source_version.clone()),
+            ("category", conv.category.clone()),
+            ("tags", conv.user_tags.join(", ")),
+            ("notes", conv.notes.clone().unwrap_or_default()),
+            ("message_count", messages.len().to_string()),
+            (
+                "exported",
+                chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            ),
+        ]);
+        let all: Vec<Vars> = messages
+            .iter()
+            .enumerate()
+            .map(|(i, m)| message_vars(i, m))
+            .collect();
+        let dialogue: Vec<Vars> = messages
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| matches!(m.role, MessageRole::User | MessageRole::Assistant))
+            .filter(|(_, m)| !m.content.trim().is_empty())
+            .map(|(i, m)| message_vars(i, m))
+            .collect();
+        let lists = HashMap::from([("messages", all), ("dialogue", dialogue)]);
+
+        let mut out = String::new();
+        render_nodes(&self.nodes, &vars, None, &lists, &mut out);
+        out
+    }
+}
+
+fn check_var(name: &str) -> Result<()> {
+    if CONVERSATION_VARS.contains(&name) || MESSAGE_VARS.contains(&name) {
+        Ok(())
+    } else {
+        bail!("Unknown placeholder {{{{{}}}}}", name)
+    }
+}
+
+fn message_vars(index: usize, msg: &Message) -> Vars {
+    let flag = |set: bool| String::from(if set { "true" } else { "" });
+    let tool = msg.tool_call.as_ref();
+    HashMap::from([
+        ("index", (index + 1).to_string()),
+        ("role", msg.role.as_str().to_string()),
+        (
+            "role_label",
+            match msg.role {
+                MessageRole::User => "User",
+                MessageRole::Assistant => "Assistant",
+                MessageRole::ToolCall => "Tool call",
+                MessageRole::ToolResult => "Tool result",
+            }
+            .to_string(),
+        ),
+        ("content", msg.content.clone()),
+        ("thinking", msg.thinking.clone().unwrap_or_default()),
+        (
+            "tool_name",
+            tool.map(|t| t.name.clone()).unwrap_or_default(),
+        ),
+        (
+            "tool_args",
+            tool.map(|t| t.args.clone()).unwrap_or_default(),
+        ),
+        (
+            "tool_status",
+            tool.map(|t| t.status.clone()).unwrap_or_default(),
+        ),
+        ("created_at", msg.created_at.clone().unwrap_or_default()),
+        ("is_user", flag(msg.role == MessageRole::User)),
+        ("is_assistant", flag(msg.role == MessageRole::Assistant)),
+        (
+            "is_tool",
+            flag(matches!(
+                msg.role,
+                MessageRole::ToolCall | MessageRole::ToolResult
+            )),
+        ),
+    ])
+}
+
+fn render_nodes(
+    nodes: &[Node],
+    vars: &Vars,
+    item: Option<&Vars>,
+    lists: &HashMap<&str, Vec<Vars>>,
+    out: &mut String,
+) {
+    let lookup = |name: &str| {
+        item.and_then(|item| item.get(name))
+            .or_else(|| vars.get(name))
+            .map_or("", |value| value.as_str())
+    };
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Var(name) => out.push_str(lookup(name)),
+            Node::If(name, body) => {
+                if !lookup(name).trim().is_empty() {
+                    render_nodes(body, vars, item, lists, out);
+                }
+            }
+            Node::Each(list, body) => {
+                for entry in lists.get(list.as_str()).into_iter().flatten() {
+                    render_nodes(body, vars, Some(entry), lists, out);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{ContentType, ToolCallInfo};
+
+    fn message(role: MessageRole, content: &str) -> Message {
+        Message {
+            id: String::new(),
+            conversation_id: "c1".to_string(),
+            sequence: 0,
+            role,
+            content: content.to_string(),
+            tool_call: None,
+            thinking: None,
+            content_type: ContentType::Text,
+            has_code_blocks: false,
+            has_terminal_output: false,
+            files_edited: Vec::new(),
+            created_at: None,
+            parent_id: None,
+            branch: 0,
+            tool_call_id: None,
+        }
+    }
+
+    fn conversation() -> (Conversation, Vec<Message>) {
+        let conv = Conversation {
+            id: "c1".to_string(),
+            source_version: "0.42.0".to_string(),
+            title: "Fix the build".to_string(),
+            category: "Uncategorized".to_string(),
+            message_count: 3,
+            is_favorite: false,
+            is_archived: false,
+            user_tags: Vec::new(),
+            updated_at: None,
+            notes: None,
+            word_count: 0,
+        };
+        let mut tool = message(MessageRole::ToolCall, "");
+        tool.tool_call = Some(ToolCallInfo {
+            name: "run_terminal_cmd".to_string(),
+            args: "{}".to_string(),
+            args_preview: String::new(),
+            status: "completed".to_string(),
+            tool_id: "t1".to_string(),
+        });
+        let messages = vec![
+            message(MessageRole::User, "Why does it fail?"),
+            tool,
+            message(MessageRole::Assistant, "A missing import."),
+        ];
+        (conv, messages)
+    }
+
+    #[test]
+    fn test_builtin_templates() {
+        let (conv, messages) = conversation();
+        let minimal = load("minimal").unwrap().unwrap();
+        assert_eq!(
+            minimal.render(&conv, &messages),
+            "# Fix the build\n\n**User:** Why does it fail?\n\n**Assistant:** A missing import.\n\n"
+        );
+        let qa = load("qa").unwrap().unwrap();
+        assert_eq!(
+            qa.render(&conv, &messages),
+            "# Fix the build\n\n**Q:** Why does it fail?\n\n**A:** A missing import.\n\n---\n\n"
+        );
+        assert!(load(DEFAULT_TEMPLATE).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_placeholders() {
+        let (conv, messages) = conversation();
+        let template = Template::parse(
+            "{{#each messages}}{{index}}:{{role}}{{tool_name}} {{/each}}({{source}})",
+        )
+        .unwrap();
+        assert_eq!(
+            template.render(&conv, &messages),
+            "1:user 2:tool_callrun_terminal_cmd 3:assistant (0.42.0)"
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Template::parse("{{titel}}").is_err());
+        assert!(Template::parse("{{#each messages}}{{content}}").is_err());
+        assert!(Template::parse("{{#each chats}}{{/each}}").is_err());
+        assert!(Template::parse("{{#if notes}}{{/each}}").is_err());
+        assert!(Template::parse("{{/if}}").is_err());
+        assert!(Template::parse("{{title").is_err());
+    }
+}
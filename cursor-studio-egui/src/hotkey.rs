@@ -0,0 +1,55 @@
+//! User-configurable key chords such as `Ctrl+Shift+H`
+
+use eframe::egui::{Key, KeyboardShortcut, Modifiers};
+
+/// Parse a `+`-separated chord: modifiers in any order, then exactly one key.
+///
+/// A chord needs Ctrl, Alt or Cmd, otherwise pressing it would also type
+/// into whichever text field has focus.
+pub fn parse_shortcut(text: &str) -> Option<KeyboardShortcut> {
+    let mut modifiers = Modifiers::NONE;
+    let mut key = None;
+    for part in text.split('+').map(str::trim) {
+        match part.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" => modifiers.ctrl = true,
+            "alt" => modifiers.alt = true,
+            "shift" => modifiers.shift = true,
+            "cmd" | "command" => modifiers.command = true,
+            _ if key.is_some() => return None,
+            _ => key = Some(Key::from_name(part)?),
+        }
+    }
+
+    if !(modifiers.ctrl || modifiers.alt || modifiers.command) {
+        return None;
+    }
+    Some(KeyboardShortcut::new(modifiers, key?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_shortcut() {
+        let chord = parse_shortcut("Ctrl+Shift+H").unwrap();
+        assert_eq!(chord.logical_key, Key::H);
+        assert!(chord.modifiers.ctrl && chord.modifiers.shift && !chord.modifiers.alt);
+
+        let chord = parse_shortcut(" alt + F12 ").unwrap();
+        assert_eq!(chord.logical_key, Key::F12);
+        assert!(chord.modifiers.alt);
+    }
+
+    #[test]
+    fn test_parse_shortcut_rejects_bad_chords() {
+        // Would type into text fields
+        assert!(parse_shortcut("H").is_none());
+        assert!(parse_shortcut("Shift+H").is_none());
+        // No key, unknown key, or two keys
+        assert!(parse_shortcut("Ctrl+Shift").is_none());
+        assert!(parse_shortcut("Ctrl+Nope").is_none());
+        assert!(parse_shortcut("Ctrl+A+B").is_none());
+        assert!(parse_shortcut("").is_none());
+    }
+}
@@ -0,0 +1,285 @@
+//! Detection of the programming languages a conversation is about
+//!
+//! Only fenced code blocks are looked at, and only a sample of them, so
+//! tagging a whole library stays cheap. A fence's info string decides when it
+//! names a language; otherwise a few keyword checks on the block's first lines
+//! make a guess, and blocks that match nothing are ignored.
+//!
+//! The same fence parsing pulls every code block out of a conversation for
+//! copying or saving in one piece.
+
+/// Prefix marking tags applied by detection, so they can be told apart from
+/// (and replaced without touching) tags the user added
+pub const LANGUAGE_TAG_PREFIX: &str = "lang:";
+
+/// Code blocks looked at per conversation
+const MAX_BLOCKS: usize = 24;
+
+/// Lines of each block that count towards its weight and are scanned for keywords
+const MAX_BLOCK_LINES: usize = 40;
+
+/// Share of the sampled code a language needs to count as dominant
+const MIN_SHARE: f32 = 0.25;
+
+/// Most languages tagged on one conversation
+const MAX_LANGUAGES: usize = 3;
+
+pub fn language_tag(language: &str) -> String {
+    format!("{}{}", LANGUAGE_TAG_PREFIX, language)
+}
+
+/// Fenced code blocks in `text` as (info string, body)
+fn code_blocks(text: &str) -> Vec<(&str, &str)> {
+    let mut blocks = Vec::new();
+    let mut rest = text;
+    while let Some(open) = rest.find("```") {
+        let after = &rest[open + 3..];
+        let Some(line_end) = after.find('\n') else {
+            break;
+        };
+        let info = after[..line_end].trim();
+        let body = &after[line_end + 1..];
+        let Some(close) = body.find("```") else {
+            break;
+        };
+        blocks.push((info, &body[..close]));
+        rest = &body[close + 3..];
+    }
+    blocks
+}
+
+/// Canonical language name for a fence info string or file extension
+fn normalize(name: &str) -> Option<&'static str> {
+    let language = match name.to_lowercase().as_str() {
+        "rust" | "rs" => "rust",
+        "python" | "py" | "python3" => "python",
+        "javascript" | "js" | "jsx" | "mjs" | "cjs" => "javascript",
+        "typescript" | "ts" | "tsx" => "typescript",
+        "shell" | "sh" | "bash" | "zsh" | "fish" | "console" => "shell",
+        "nix" => "nix",
+        "go" | "golang" => "go",
+        "c" | "h" => "c",
+        "cpp" | "c++" | "cc" | "cxx" | "hpp" => "cpp",
+        "csharp" | "cs" | "c#" => "csharp",
+        "java" => "java",
+        "kotlin" | "kt" | "kts" => "kotlin",
+        "swift" => "swift",
+        "ruby" | "rb" => "ruby",
+        "php" => "php",
+        "lua" => "lua",
+        "haskell" | "hs" => "haskell",
+        "elixir" | "ex" | "exs" => "elixir",
+        "zig" => "zig",
+        "sql" => "sql",
+        "html" | "htm" => "html",
+        "css" | "scss" | "sass" => "css",
+        "toml" => "toml",
+        "yaml" | "yml" => "yaml",
+        "json" | "jsonc" => "json",
+        "dockerfile" | "docker" => "dockerfile",
+        _ => return None,
+    };
+    Some(language)
+}
+
+/// Language named by a fence info string: `rust`, `src/main.rs`, or
+/// Cursor's `12:30:src/main.rs` line-range form
+fn language_from_info(info: &str) -> Option<&'static str> {
+    let first = info.split_whitespace().next()?;
+    let path = first.rsplit(':').next().unwrap_or(first);
+    normalize(path).or_else(|| {
+        let (_, ext) = path.rsplit_once('.')?;
+        normalize(ext)
+    })
+}
+
+/// Keyword guess for an unlabelled block, from its first lines
+fn guess_language(lines: &[&str]) -> Option<&'static str> {
+    let has = |needle: &str| lines.iter().any(|line| line.contains(needle));
+    let starts = |prefix: &str| {
+        lines
+            .iter()
+            .any(|line| line.trim_start().starts_with(prefix))
+    };
+
+    if starts("fn ")
+        || starts("pub fn ")
+        || starts("impl ")
+        || has("let mut ")
+        || starts("use std::")
+    {
+        Some("rust")
+    } else if has("mkDerivation") || has("{ pkgs") || has("pkgs.") || starts("inherit ") {
+        Some("nix")
+    } else if starts("package main") || (has(":= ") && starts("func ")) {
+        Some("go")
+    } else if starts("def ") || (starts("from ") && has(" import ")) || starts("elif ") {
+        Some("python")
+    } else if has(": string") || has(": number") || starts("interface ") || starts("export type ") {
+        Some("typescript")
+    } else if has("=> {") || (starts("const ") && has("require(")) || has("console.log(") {
+        Some("javascript")
+    } else if starts("#include") {
+        Some("cpp")
+    } else if starts("public class ") || has("System.out.println") {
+        Some("java")
+    } else if starts("#!/bin/bash") || starts("#!/usr/bin/env bash") || starts("$ ") {
+        Some("shell")
+    } else if starts("SELECT ") || starts("CREATE TABLE") || starts("INSERT INTO") {
+        Some("sql")
+    } else {
+        None
+    }
+}
+
+/// A fenced code block taken from a conversation
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeBlock {
+    /// The fence's info string, e.g. `rust` or `12:30:src/main.rs`
+    pub info: String,
+    pub body: String,
+}
+
+impl CodeBlock {
+    /// Language named by the fence, else guessed from the first lines
+    pub fn language(&self) -> Option<&'static str> {
+        language_from_info(&self.info).or_else(|| {
+            let lines: Vec<&str> = self.body.lines().take(MAX_BLOCK_LINES).collect();
+            guess_language(&lines)
+        })
+    }
+
+    fn to_markdown(&self) -> String {
+        let newline = if self.body.ends_with('\n') { "" } else { "\n" };
+        format!("```{}\n{}{}```\n", self.info, self.body, newline)
+    }
+}
+
+/// Every fenced code block in `contents` (message texts) in order; a block
+/// repeating the one before it is kept once
+pub fn extract_code_blocks(contents: &[String]) -> Vec<CodeBlock> {
+    let mut blocks: Vec<CodeBlock> = Vec::new();
+    for (info, body) in contents.iter().flat_map(|c| code_blocks(c)) {
+        let repeated = blocks
+            .last()
+            .is_some_and(|last| last.body.trim_end() == body.trim_end());
+        if repeated {
+            continue;
+        }
+        blocks.push(CodeBlock {
+            info: info.to_string(),
+            body: body.to_string(),
+        });
+    }
+    blocks
+}
+
+/// Code blocks as one markdown document. With `by_language` they're grouped
+/// under a heading per language, in order of first appearance.
+pub fn code_blocks_markdown(blocks: &[CodeBlock], by_language: bool) -> String {
+    if !by_language {
+        let fenced: Vec<String> = blocks.iter().map(CodeBlock::to_markdown).collect();
+        return fenced.join("\n");
+    }
+
+    let mut groups: Vec<(&str, Vec<String>)> = Vec::new();
+    for block in blocks {
+        let language = block.language().unwrap_or("other");
+        match groups.iter_mut().find(|(name, _)| *name == language) {
+            Some((_, group)) => group.push(block.to_markdown()),
+            None => groups.push((language, vec![block.to_markdown()])),
+        }
+    }
+    let sections: Vec<String> = groups
+        .into_iter()
+        .map(|(language, group)| format!("## {}\n\n{}", language, group.join("\n")))
+        .collect();
+    sections.join("\n")
+}
+
+/// Dominant languages across the code blocks of `contents` (message texts),
+/// most used first
+pub fn detect_languages(contents: &[String]) -> Vec<String> {
+    let blocks: Vec<(&str, &str)> = contents.iter().flat_map(|c| code_blocks(c)).collect();
+    if blocks.is_empty() {
+        return Vec::new();
+    }
+
+    // Spread the sample over the whole conversation, not just its start
+    let step = blocks.len().div_ceil(MAX_BLOCKS);
+    let mut weights: Vec<(&'static str, usize)> = Vec::new();
+    for (info, body) in blocks.into_iter().step_by(step) {
+        let lines: Vec<&str> = body.lines().take(MAX_BLOCK_LINES).collect();
+        let Some(language) = language_from_info(info).or_else(|| guess_language(&lines)) else {
+            continue;
+        };
+        match weights.iter_mut().find(|(name, _)| *name == language) {
+            Some((_, weight)) => *weight += lines.len().max(1),
+            None => weights.push((language, lines.len().max(1))),
+        }
+    }
+
+    let total: usize = weights.iter().map(|(_, weight)| weight).sum();
+    weights.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    weights
+        .into_iter()
+        .filter(|(_, weight)| *weight as f32 >= total as f32 * MIN_SHARE)
+        .take(MAX_LANGUAGES)
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_from_info() {
+        assert_eq!(language_from_info("rs"), Some("rust"));
+        assert_eq!(language_from_info("12:30:src/main.rs"), Some("rust"));
+        assert_eq!(language_from_info("flake.nix"), Some("nix"));
+        assert_eq!(language_from_info("Python title=\"x\""), Some("python"));
+        assert_eq!(language_from_info("text"), None);
+        assert_eq!(language_from_info(""), None);
+    }
+
+    #[test]
+    fn test_detect_languages() {
+        let contents = vec![
+            "Try this:\n```rust\nfn main() {\n    println!(\"hi\");\n}\n```".to_string(),
+            "```\nlet mut x = 1;\nx += 1;\n```\nand build with\n```sh\ncargo build\n```"
+                .to_string(),
+            "No code here".to_string(),
+        ];
+        // Five lines of Rust against one of shell, which falls under the share
+        assert_eq!(detect_languages(&contents), vec!["rust".to_string()]);
+
+        let mixed = vec!["```py\nx = 1\n```\n```go\npackage main\n```".to_string()];
+        assert_eq!(
+            detect_languages(&mixed),
+            vec!["go".to_string(), "python".to_string()]
+        );
+        assert!(detect_languages(&["```\nplain words\n```".to_string()]).is_empty());
+    }
+
+    #[test]
+    fn test_extract_code_blocks() {
+        let contents = vec![
+            "```rust\nfn main() {}\n```\nSame again:\n```rust\nfn main() {}\n```".to_string(),
+            "```sh\ncargo run\n```\n```\nfn helper() {}\n```".to_string(),
+        ];
+        let blocks = extract_code_blocks(&contents);
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[2].language(), Some("rust"));
+
+        assert_eq!(
+            code_blocks_markdown(&blocks, false),
+            "```rust\nfn main() {}\n```\n\n```sh\ncargo run\n```\n\n```\nfn helper() {}\n```\n"
+        );
+        assert_eq!(
+            code_blocks_markdown(&blocks, true),
+            "## rust\n\n```rust\nfn main() {}\n```\n\n```\nfn helper() {}\n```\n\n\
+             ## shell\n\n```sh\ncargo run\n```\n"
+        );
+    }
+}
@@ -4,8 +4,10 @@
 
 pub mod approval;
 pub mod chat;
+pub mod chat_exports;
 pub mod database;
 pub mod diagram;
+pub mod languages;
 pub mod security;
 pub mod sync;
 pub mod theme;
@@ -24,7 +26,9 @@ pub use diagram::{
 };
 pub use versions::{
     download_and_verify, download_and_verify_simple, download_version_sync,
-    get_available_versions, get_cache_dir, get_latest_stable, get_version_info, install_version,
-    is_version_installed, verify_hash, verify_hash_detailed, AvailableVersion, DownloadEvent,
-    DownloadProgress, DownloadState, HashVerificationResult,
+    download_version_sync_with, effective_proxy, get_available_versions, get_cache_dir,
+    get_latest_stable, get_version_info, install_version, is_version_installed, set_cache_dir,
+    validate_cache_dir, verify_hash, verify_hash_detailed, AvailableVersion, DownloadEvent,
+    DownloadOptions, DownloadProgress, DownloadState, HashVerificationResult, InstallError,
+    VersionPaths,
 };
@@ -0,0 +1,129 @@
+//! In-memory log sink for the in-app log viewer
+//! Wraps env_logger so stderr output is unchanged while recent records are kept for display
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Maximum number of records kept in memory (oldest are dropped first)
+pub const MAX_RECORDS: usize = 1000;
+
+/// Records at this level or more severe are captured regardless of RUST_LOG
+const CAPTURE_LEVEL: Level = Level::Info;
+
+static RECORDS: Mutex<VecDeque<LogEntry>> = Mutex::new(VecDeque::new());
+
+/// A captured log record
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    /// Local time, HH:MM:SS
+    pub time: String,
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+}
+
+impl LogEntry {
+    /// Single-line form used for display and "copy logs"
+    pub fn format_line(&self) -> String {
+        format!(
+            "{} {:<5} [{}] {}",
+            self.time, self.level, self.target, self.message
+        )
+    }
+}
+
+struct BufferedLogger {
+    inner: env_logger::Logger,
+}
+
+impl Log for BufferedLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= CAPTURE_LEVEL || self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.inner.matches(record) {
+            self.inner.log(record);
+        }
+        if record.level() <= CAPTURE_LEVEL {
+            push(LogEntry {
+                time: chrono::Local::now().format("%H:%M:%S").to_string(),
+                level: record.level(),
+                target: record.target().to_string(),
+                message: record.args().to_string(),
+            });
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+/// Install the buffering logger (replaces `env_logger::init()`)
+pub fn init() {
+    let inner = env_logger::Builder::from_default_env().build();
+    let max_level = inner.filter().max(CAPTURE_LEVEL.to_level_filter());
+    if log::set_boxed_logger(Box::new(BufferedLogger { inner })).is_ok() {
+        log::set_max_level(max_level);
+    }
+}
+
+fn push(entry: LogEntry) {
+    let mut records = RECORDS.lock().unwrap();
+    if records.len() >= MAX_RECORDS {
+        records.pop_front();
+    }
+    records.push_back(entry);
+}
+
+/// Captured records at `min_level` or more severe, oldest first
+pub fn recent(min_level: LevelFilter) -> Vec<LogEntry> {
+    RECORDS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|entry| entry.level <= min_level)
+        .cloned()
+        .collect()
+}
+
+/// Drop all captured records
+pub fn clear() {
+    RECORDS.lock().unwrap().clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(level: Level, message: &str) -> LogEntry {
+        LogEntry {
+            time: "12:00:00".to_string(),
+            level,
+            target: "test".to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_buffer_cap_and_filter() {
+        clear();
+        for i in 0..MAX_RECORDS + 10 {
+            push(entry(Level::Info, &format!("info {}", i)));
+        }
+        push(entry(Level::Warn, "disk almost full"));
+
+        let all = recent(LevelFilter::Info);
+        assert_eq!(all.len(), MAX_RECORDS);
+        assert_eq!(all[0].message, "info 11");
+
+        let warnings = recent(LevelFilter::Warn);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            warnings[0].format_line(),
+            "12:00:00 WARN  [test] disk almost full"
+        );
+    }
+}
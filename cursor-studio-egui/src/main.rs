@@ -1,11 +1,20 @@
 //! Cursor Studio - Version Manager + Chat Library + Documentation Index
 //! Built with egui for native Wayland support
 
+mod activity;
 mod ai_workspace;
 mod approval;
+mod branches;
 mod chat;
+mod chat_exports;
 mod database;
+mod diagnostics;
+mod diff;
 mod docs;
+mod export_templates;
+mod hotkey;
+mod languages;
+mod log_buffer;
 mod modes;
 mod security;
 mod sync;
@@ -14,16 +23,21 @@ mod versions;
 
 use approval::{ApprovalManager, ApprovalMode, ApprovalOperation, ApprovalResult};
 // ApprovalMode is used in Settings panel for download confirmation style
-use versions::{get_available_versions, get_version_info, AvailableVersion, DownloadState};
+use versions::{
+    get_available_versions, get_version_info, AvailableVersion, DownloadEvent, DownloadState,
+};
 
+use branches::{BranchChoices, ConversationTree};
 use database::{
-    Bookmark, ChatDatabase, Conversation, CursorVersion, DisplayPreference, Message, MessageRole,
-    MessageStats,
+    pair_tool_results, Bookmark, ChatDatabase, Conversation, CursorVersion, DayActivity,
+    DisplayPreference, ImportPreview, Message, MessageHit, MessageRole, MessageStats,
+    ReplacePreview,
 };
 use eframe::egui::{self, Color32, CursorIcon, RichText, Rounding, Stroke, Vec2};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
-use theme::Theme;
+use std::sync::atomic::Ordering;
+use theme::{color_theme_setting, color_to_hex, Theme, ThemeCache};
 
 // ═══════════════════════════════════════════════════════════════════════════
 // UI SPACING CONSTANTS - Use these for consistent panel layouts
@@ -37,11 +51,53 @@ const SECTION_SPACING: f32 = 12.0;
 const ELEMENT_SPACING: f32 = 8.0;
 /// Small spacing for tight layouts
 const TIGHT_SPACING: f32 = 4.0;
-/// Card inner margin
-const CARD_MARGIN: f32 = 8.0;
 /// Card rounding
 const CARD_ROUNDING: f32 = 6.0;
 
+/// Number of conversations remembered by the quick switcher
+const RECENT_CONVERSATIONS_MAX: usize = 20;
+/// Interrupted (`.part`) downloads older than this are pruned from the cache
+const STALE_DOWNLOAD_AGE: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+/// Cursor release notes
+const CURSOR_CHANGELOG_URL: &str = "https://www.cursor.com/changelog";
+/// How long a toast notification stays up (the last second fades out)
+const TOAST_DURATION: std::time::Duration = std::time::Duration::from_secs(6);
+/// Conversations with more messages than this ask before opening
+const LARGE_CONVERSATION_MESSAGES: usize = 5_000;
+/// Messages loaded per page for large conversations
+const MESSAGE_PAGE_SIZE: usize = 500;
+/// How long conversations brought in by a refresh stay marked as new in the library
+const NEW_CONVERSATION_MARK: std::time::Duration = std::time::Duration::from_secs(30);
+/// Chord that toggles the privacy screen until the user picks another
+const DEFAULT_PRIVACY_HOTKEY: &str = "Ctrl+Shift+H";
+/// Status messages kept for the status bar history popup
+const STATUS_HISTORY_MAX: usize = 20;
+/// Hover time before the theme picker previews a theme
+const THEME_PREVIEW_DELAY: std::time::Duration = std::time::Duration::from_millis(200);
+/// How often Cursor's settings.json is checked for a theme change while following it
+const CURSOR_THEME_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+/// Size budget for a "continue in Cursor" context prompt (~3k tokens)
+const CONTINUE_PROMPT_MAX_CHARS: usize = 12_000;
+/// Longest single message quoted in a context prompt
+const CONTINUE_MESSAGE_MAX_CHARS: usize = 2_000;
+/// Tool results longer than this many lines start collapsed
+const TOOL_RESULT_PREVIEW_LINES: usize = 8;
+/// Tool results longer than this many characters start collapsed
+const TOOL_RESULT_PREVIEW_CHARS: usize = 1_200;
+/// Messages read from each end of a conversation for its hover preview
+const PREVIEW_WINDOW_MESSAGES: usize = 20;
+/// Longest message excerpt in a hover preview
+const PREVIEW_MAX_CHARS: usize = 240;
+/// Diff hunks up to this many lines start expanded
+const DIFF_HUNK_OPEN_LINES: usize = 40;
+/// Message lines shown on each side of an expanded security finding
+const FINDING_CONTEXT_LINES: usize = 3;
+/// Server sync (connect / pull) needs the SurrealDB client from the full build
+const SERVER_SYNC_AVAILABLE: bool = cfg!(feature = "surrealdb-store");
+/// Tooltip on server sync controls in builds without it
+const SERVER_SYNC_DISABLED_HINT: &str = "Server sync isn't in this build: it needs the \
+    'surrealdb-store' feature from the full build (nu rebuild.nu without --lite)";
+
 /// External config from Home Manager or other sources
 /// Located at ~/.config/cursor-studio/config.json
 #[derive(Debug, Default, serde::Deserialize)]
@@ -55,9 +111,14 @@ struct ExternalConfig {
     #[serde(default)]
     status_bar_font_size: Option<f32>,
     #[serde(default)]
+    min_text_size: Option<f32>,
+    #[serde(default)]
     display_prefs: Vec<ExternalDisplayPref>,
     #[serde(default)]
     cursor_data_dir: Option<String>,
+    /// Where downloaded AppImages are cached; overrides the Settings value
+    #[serde(default)]
+    cache_dir: Option<String>,
     #[serde(default)]
     security: Option<ExternalSecurityConfig>,
     #[serde(default)]
@@ -72,6 +133,10 @@ struct ExternalDisplayPref {
     style: String,
     #[serde(default)]
     collapsed: bool,
+    #[serde(default)]
+    fg_color: Option<String>,
+    #[serde(default)]
+    bg_color: Option<String>,
 }
 
 #[derive(Debug, Default, serde::Deserialize)]
@@ -82,6 +147,8 @@ struct ExternalSecurityConfig {
     sensitive_data_scan: bool,
     #[serde(default)]
     blocklist_path: Option<String>,
+    #[serde(default)]
+    severity: security::SeverityMap,
 }
 
 #[derive(Debug, Default, serde::Deserialize)]
@@ -94,10 +161,19 @@ struct ExternalResourceConfig {
 
 impl ExternalConfig {
     /// Load config from ~/.config/cursor-studio/config.json if it exists
+    /// ~/.config/cursor-studio/config.json
+    fn path() -> Option<PathBuf> {
+        Some(dirs::config_dir()?.join("cursor-studio").join("config.json"))
+    }
+
+    /// Why config.json is being ignored, if it exists but doesn't parse
+    fn parse_error() -> Option<String> {
+        let content = std::fs::read_to_string(Self::path()?).ok()?;
+        serde_json::from_str::<Self>(&content).err().map(|e| e.to_string())
+    }
+
     fn load() -> Option<Self> {
-        let config_path = dirs::config_dir()?
-            .join("cursor-studio")
-            .join("config.json");
+        let config_path = Self::path()?;
         if config_path.exists() {
             let content = std::fs::read_to_string(&config_path).ok()?;
             match serde_json::from_str(&content) {
@@ -121,7 +197,7 @@ impl ExternalConfig {
 // See versions.rs for the full list with download URLs and hashes
 
 fn main() -> eframe::Result<()> {
-    env_logger::init();
+    log_buffer::init();
 
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
@@ -136,7 +212,7 @@ fn main() -> eframe::Result<()> {
         options,
         Box::new(|cc| {
             // Configure fonts with Unicode fallbacks
-            configure_fonts(&cc.egui_ctx);
+            let loaded_fonts = configure_fonts(&cc.egui_ctx);
 
             let mut style = (*cc.egui_ctx.style()).clone();
             style.visuals.widgets.inactive.bg_fill = Color32::from_rgb(60, 60, 60);
@@ -146,13 +222,16 @@ fn main() -> eframe::Result<()> {
                 Stroke::new(1.0, Color32::from_rgb(0, 120, 212));
             cc.egui_ctx.set_style(style);
 
-            Ok(Box::new(CursorStudio::new()))
+            let mut app = CursorStudio::new();
+            app.loaded_fonts = loaded_fonts;
+            Ok(Box::new(app))
         }),
     )
 }
 
 /// Configure fonts with proper Unicode support for terminal characters
-fn configure_fonts(ctx: &egui::Context) {
+/// Install the fonts found on this system; returns their names
+fn configure_fonts(ctx: &egui::Context) -> Vec<String> {
     use egui::{FontData, FontDefinitions, FontFamily};
 
     let mut fonts = FontDefinitions::default();
@@ -296,6 +375,7 @@ fn configure_fonts(ctx: &egui::Context) {
         loaded_fonts
     );
     ctx.set_fonts(fonts);
+    loaded_fonts
 }
 
 #[derive(PartialEq, Clone, Copy)]
@@ -315,7 +395,16 @@ enum RightSidebarMode {
     Modes,    // Custom modes management 🎭
 }
 
-#[derive(Clone)]
+/// Window areas that F6 / Shift+F6 move keyboard focus between, in visual order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum FocusRegion {
+    ActivityBar,
+    LeftSidebar,
+    Editor,
+    RightSidebar,
+}
+
+#[derive(Clone, PartialEq)]
 enum Tab {
     Dashboard,
     Conversation(String),
@@ -332,6 +421,8 @@ enum ExportFormat {
     JsonLines,
     OpenAIJsonl,
     AlpacaJson,
+    /// Standalone library database, importable by another Cursor Studio
+    Sqlite,
 }
 
 impl ExportFormat {
@@ -343,6 +434,7 @@ impl ExportFormat {
             Self::JsonLines => "JSON Lines",
             Self::OpenAIJsonl => "OpenAI JSONL (training)",
             Self::AlpacaJson => "Alpaca JSON (training)",
+            Self::Sqlite => "SQLite (portable)",
         }
     }
 
@@ -351,6 +443,92 @@ impl ExportFormat {
             Self::Markdown | Self::MarkdownObsidian => "md",
             Self::Json | Self::AlpacaJson => "json",
             Self::JsonLines | Self::OpenAIJsonl => "jsonl",
+            Self::Sqlite => "cursor-studio.db",
+        }
+    }
+
+    fn is_markdown(&self) -> bool {
+        matches!(self, Self::Markdown | Self::MarkdownObsidian)
+    }
+
+    /// Written by the app itself rather than the cursor-docs CLI
+    fn is_in_app(&self) -> bool {
+        self.is_markdown() || *self == Self::Sqlite
+    }
+}
+
+/// Dashboard activity data, re-queried after `ACTIVITY_REFRESH`
+struct DashboardActivity {
+    loaded_at: std::time::Instant,
+    include_archived: bool,
+    days: Vec<DayActivity>,
+    versions: Vec<(String, usize)>,
+}
+
+const ACTIVITY_REFRESH: std::time::Duration = std::time::Duration::from_secs(30);
+/// Versions listed in the dashboard breakdown before "+n more"
+const ACTIVITY_TOP_VERSIONS: usize = 6;
+
+/// Spacing preset for the conversation view and cards
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+enum Density {
+    Compact,
+    #[default]
+    Comfortable,
+    Spacious,
+}
+
+impl Density {
+    const ALL: [Density; 3] = [Self::Compact, Self::Comfortable, Self::Spacious];
+
+    fn from_key(key: &str) -> Self {
+        match key {
+            "compact" => Self::Compact,
+            "spacious" => Self::Spacious,
+            _ => Self::Comfortable,
+        }
+    }
+
+    fn key(&self) -> &'static str {
+        match self {
+            Self::Compact => "compact",
+            Self::Comfortable => "comfortable",
+            Self::Spacious => "spacious",
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Compact => "Compact",
+            Self::Comfortable => "Comfortable",
+            Self::Spacious => "Spacious",
+        }
+    }
+
+    /// Gap between messages
+    fn message_spacing(&self) -> f32 {
+        match self {
+            Self::Compact => 6.0,
+            Self::Comfortable => 12.0,
+            Self::Spacious => 20.0,
+        }
+    }
+
+    /// Padding inside message boxes
+    fn message_padding(&self) -> egui::Margin {
+        match self {
+            Self::Compact => egui::Margin::symmetric(8.0, 4.0),
+            Self::Comfortable => egui::Margin::symmetric(12.0, 8.0),
+            Self::Spacious => egui::Margin::symmetric(16.0, 12.0),
+        }
+    }
+
+    /// Card inner margin
+    fn card_margin(&self) -> f32 {
+        match self {
+            Self::Compact => 6.0,
+            Self::Comfortable => 8.0,
+            Self::Spacious => 12.0,
         }
     }
 }
@@ -361,6 +539,246 @@ enum BookmarkAction {
     Remove(String, String),     // bookmark_id, conv_id
 }
 
+/// Persisted result of `find_vscode_themes`, reused at startup while the
+/// extensions directory is unchanged
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ThemeIndex {
+    extensions_mtime: u64,
+    themes: Vec<(String, Option<PathBuf>)>,
+}
+
+/// Metadata shown in the Manager's version details card
+struct VersionDetails {
+    version: String,
+    /// Registry entry (download URL, hash, release date)
+    info: Option<AvailableVersion>,
+    /// User data directory, for installed versions
+    data_dir: Option<PathBuf>,
+    appimage: Option<PathBuf>,
+    installed_size: Option<u64>,
+}
+
+/// A running download thread and its progress channel
+struct ActiveDownload {
+    thread: std::thread::JoinHandle<Result<FinishedDownload, String>>,
+    receiver: std::sync::mpsc::Receiver<DownloadEvent>,
+    /// Latest progress percentage received, if any
+    progress: Option<f32>,
+    /// Proxy the download goes through (credentials redacted)
+    proxy: Option<String>,
+}
+
+/// What a download thread hands back once the file is in the cache
+struct FinishedDownload {
+    path: PathBuf,
+    /// Whether the registry hash matched (or why it couldn't be checked);
+    /// `None` if the registry has no hash for the version
+    hash_check: Option<Result<bool, String>>,
+}
+
+/// Results of "Detect installations", waiting for the user to pick which to register
+struct InstallDetection {
+    found: Vec<versions::DetectedInstall>,
+    /// Ticked for registering, parallel to `found`
+    selected: Vec<bool>,
+}
+
+/// Pages of the first-run setup wizard
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OnboardingStep {
+    Detect,
+    Import,
+    Theme,
+    DefaultVersion,
+}
+
+impl OnboardingStep {
+    const ALL: [OnboardingStep; 4] = [
+        Self::Detect,
+        Self::Import,
+        Self::Theme,
+        Self::DefaultVersion,
+    ];
+
+    fn title(&self) -> &'static str {
+        match self {
+            Self::Detect => "Installs",
+            Self::Import => "Import",
+            Self::Theme => "Theme",
+            Self::DefaultVersion => "Default",
+        }
+    }
+
+    fn index(&self) -> usize {
+        Self::ALL.iter().position(|s| s == self).unwrap_or(0)
+    }
+
+    fn next(&self) -> Option<Self> {
+        Self::ALL.get(self.index() + 1).copied()
+    }
+
+    fn prev(&self) -> Option<Self> {
+        self.index().checked_sub(1).map(|i| Self::ALL[i])
+    }
+}
+
+/// Conversation state of the right-hand split pane; swapped with the main
+/// conversation fields while that pane renders
+#[derive(Default)]
+struct SplitPane {
+    conv_id: String,
+    messages: Vec<Message>,
+    bookmarks: Vec<Bookmark>,
+    scroll_to_message_id: Option<String>,
+    search_query: String,
+    search_results: Vec<usize>,
+    search_index: usize,
+    goto_input: String,
+}
+
+/// Severity of a status message, derived from its icon prefix
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StatusSeverity {
+    Success,
+    Error,
+    Warning,
+    Info,
+}
+
+impl StatusSeverity {
+    fn of(message: &str) -> Self {
+        if message.starts_with('✓') {
+            Self::Success
+        } else if message.starts_with('✗') {
+            Self::Error
+        } else if message.starts_with('⚠') {
+            Self::Warning
+        } else {
+            Self::Info
+        }
+    }
+
+    fn color(&self, theme: Theme) -> Color32 {
+        match self {
+            Self::Success => theme.success,
+            Self::Error => theme.error,
+            Self::Warning => theme.warning,
+            Self::Info => theme.fg,
+        }
+    }
+}
+
+/// A past status bar message
+struct StatusEntry {
+    message: String,
+    severity: StatusSeverity,
+    at: chrono::DateTime<chrono::Local>,
+}
+
+impl StatusEntry {
+    fn new(message: &str) -> Self {
+        Self {
+            message: message.to_string(),
+            severity: StatusSeverity::of(message),
+            at: chrono::Local::now(),
+        }
+    }
+}
+
+/// Transient notification in the bottom-right corner
+struct Toast {
+    message: String,
+    /// Conversation opened when the toast is clicked
+    conversation_id: Option<String>,
+    shown_at: std::time::Instant,
+}
+
+/// Where the latest export was written, for the status bar's open buttons
+#[derive(Clone)]
+struct LastExport {
+    folder: PathBuf,
+    /// None when the export wrote one file per chat
+    file: Option<PathBuf>,
+}
+
+impl LastExport {
+    fn file(path: PathBuf) -> Self {
+        let folder = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        Self {
+            folder,
+            file: Some(path),
+        }
+    }
+
+    fn folder(folder: PathBuf) -> Self {
+        Self { folder, file: None }
+    }
+}
+
+/// First question and last answer of a conversation, for the Archive hover preview
+struct ConversationPreview {
+    first_user: Option<String>,
+    last_assistant: Option<String>,
+}
+
+/// Thread with its own database connection that loads hover previews, so
+/// hovering the Archive list never waits on a query
+struct PreviewWorker {
+    requests: std::sync::mpsc::Sender<String>,
+    results: std::sync::mpsc::Receiver<(String, ConversationPreview)>,
+}
+
+impl PreviewWorker {
+    fn spawn(db_path: PathBuf) -> Self {
+        let (requests, request_rx) = std::sync::mpsc::channel::<String>();
+        let (result_tx, results) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let db = match ChatDatabase::open(&db_path) {
+                Ok(db) => db,
+                Err(e) => {
+                    log::warn!("Conversation previews unavailable: {}", e);
+                    return;
+                }
+            };
+            for conv_id in request_rx {
+                let preview = load_conversation_preview(&db, &conv_id);
+                if result_tx.send((conv_id, preview)).is_err() {
+                    break;
+                }
+            }
+        });
+        Self { requests, results }
+    }
+}
+
+/// Size estimate for a batch of version downloads
+struct DownloadPlan {
+    versions: Vec<String>,
+    /// Estimated size of each entry in `versions`, if known
+    sizes: Vec<Option<u64>>,
+    total_bytes: u64,
+    /// Versions whose size couldn't be determined
+    unknown_sizes: usize,
+    /// Bytes already used by downloads and installed versions
+    used_bytes: u64,
+}
+
+/// Actions from the Archive multi-select bar
+enum BulkAction {
+    ClearSelection,
+    SelectAll,
+    Favorite,
+    Archive,
+    Tag(String),
+    Export,
+    Delete,
+    Replace,
+}
+
+/// A finished move of the download cache: the new directory, its Settings
+/// text, and the (files, bytes) moved
+type CacheMove = (PathBuf, String, anyhow::Result<(usize, u64)>);
+
 /// Main application state
 struct CursorStudio {
     theme: Theme,
@@ -374,23 +792,66 @@ struct CursorStudio {
 
     // Mode
     left_mode: SidebarMode,
+    /// Focus the search box the next time the Search panel renders (Ctrl+Shift+F)
+    focus_search_pending: bool,
     right_mode: RightSidebarMode,
 
+    // Keyboard focus
+    /// First focusable widget of each region this frame, the target of F6
+    focus_anchors: Vec<(FocusRegion, egui::Id)>,
+    /// Region F6 last moved focus to
+    focus_region: Option<FocusRegion>,
+
     // Tabs
     tabs: Vec<Tab>,
+    /// Tabs kept leftmost (after the Dashboard)
+    pinned_tabs: Vec<Tab>,
+    /// Conversation shown side by side with the active tab
+    split: Option<SplitPane>,
+    /// Huge conversation waiting for confirmation: (conv_id, message count)
+    pending_large_conversation: Option<(String, usize)>,
+    /// Conversations loaded a page at a time: conv_id -> (loaded, total)
+    paged_conversations: std::collections::HashMap<String, (usize, usize)>,
     active_tab: usize,
 
     // Data
     versions: Vec<CursorVersion>,
+    /// Conversations imported from each version, keyed like `CursorVersion.version`
+    version_chat_counts: std::collections::HashMap<String, usize>,
+    /// The newest `conversations_loaded` chats; "Load more" raises the limit
     conversations: Vec<Conversation>,
+    conversation_page_size: usize,
+    conversations_loaded: usize,
+    /// Chats in the library under the current archive filter
+    conversations_total: usize,
+    /// Every chat in the library, archived included, as of the last reload
+    library_total: usize,
+    /// Hover previews by conversation id; None while the worker is loading one
+    conversation_previews: std::collections::HashMap<String, Option<ConversationPreview>>,
+    /// Every conversation id as of the last refresh, to tell what the next one brings in
+    known_conversations: std::collections::HashSet<String>,
+    /// Conversations that arrived on a refresh, and when, for the "new" mark
+    new_conversations: std::collections::HashMap<String, std::time::Instant>,
+    preview_worker: Option<PreviewWorker>,
     current_messages: Vec<Message>,
 
     // Search
     search_query: String,
     search_results: Vec<Conversation>,
+    /// Messages whose text matches the search, from the full-text index
+    message_hits: Vec<MessageHit>,
 
     // Status messages
     status_message: Option<String>,
+    last_export: Option<LastExport>,
+    /// Current page of the setup wizard (None when it isn't showing)
+    onboarding_step: Option<OnboardingStep>,
+    install_detection: Option<InstallDetection>,
+    /// Extra directories "Detect installations" searches, separated by ':'
+    install_search_dirs: String,
+    /// Recent status messages, newest first
+    status_history: std::collections::VecDeque<StatusEntry>,
+    show_status_history: bool,
 
     // Settings state
     show_theme_picker: bool,
@@ -398,44 +859,162 @@ struct CursorStudio {
     show_launch_picker: bool,
     available_themes: Vec<(String, Option<PathBuf>)>,
     current_theme_name: String,
+    /// Mirror the color theme selected in the launch version's Cursor
+    follow_cursor_theme: bool,
+    cursor_theme_checked: Option<std::time::Instant>,
+    cursor_settings_mtime: Option<std::time::SystemTime>,
+    /// Last `workbench.colorTheme` read (inner None: not detected), None before the first read
+    followed_cursor_theme: Option<Option<String>>,
 
     // Version management - separated concerns
     default_version: String, // Persisted default for new launches
     launch_version: String,  // Currently selected version to launch
+    /// Pinned versions shown first in the launch picker and as quick-launch buttons
+    favorite_versions: Vec<String>,
+    /// Extra environment variables for launched Cursor processes (name, value)
+    launch_env: Vec<(String, String)>,
 
     // Toggle states
     auto_sync_enabled: bool,
     import_on_start: bool,
+    /// Periodically run the incremental import while the app is open
+    auto_import_enabled: bool,
+    auto_import_interval_mins: usize,
+    /// When the auto-import timer last fired (or app start)
+    auto_import_timer: std::time::Instant,
+    /// RFC 3339 time of the last auto-import, shown in the status bar
+    last_auto_import: Option<String>,
+    toasts: Vec<Toast>,
+    /// Minutes without input before chat content is hidden (0 = never)
+    idle_lock_mins: usize,
+    /// Seconds between checks for unsaved setting changes (0 = only on exit)
+    settings_autosave_secs: usize,
+    settings_autosave_timer: std::time::Instant,
+    /// Settings as last written by `save_settings`
+    saved_settings: Vec<(&'static str, String)>,
+    last_input: std::time::Instant,
+    idle_locked: bool,
+    /// Chord that toggles the privacy screen, always a valid `hotkey::parse_shortcut` input
+    privacy_hotkey: String,
+    privacy_hotkey_input: String,
+    privacy_screen: bool,
     show_all_versions: bool,
 
     // Hover state for theme picker
     hovered_theme: Option<String>,
+    /// Theme name the pointer settled on, and since when (debounces parsing)
+    theme_hover_since: Option<(String, std::time::Instant)>,
+    /// Temporarily applied theme while hovering in the picker (None if it failed to parse)
+    theme_preview: Option<(String, Option<Theme>)>,
+    theme_cache: ThemeCache,
 
     // Import state
     import_in_progress: bool,
     import_progress: Option<(usize, usize)>, // (current, total)
+    /// Copies skipped by the running import, reported when it completes
+    import_duplicates: usize,
+    /// Conversations added by the running import, for the completion toast
+    import_new_ids: Vec<String>,
+    /// Asks the import worker to stop after the current database until cleared
+    import_pause: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    /// The worker has actually stopped (set by `ImportProgress::Paused`)
+    import_paused: bool,
     import_warning_shown: bool,
     last_import_error: Option<String>,
+    import_preview: Option<Vec<ImportPreview>>,
+    /// Dry-run scan running in the background, delivering `import_preview`
+    import_preview_receiver: Option<std::sync::mpsc::Receiver<Vec<ImportPreview>>>,
+
+    // Archive visibility
+    show_archived: bool,
+    /// Only list conversations with this tag (clicked in the Archive list)
+    archive_tag_filter: Option<String>,
+    stats_exclude_archived: bool,
+    relative_timestamps: bool,
+    /// Wrap long lines in code blocks instead of scrolling horizontally
+    code_wrap: bool,
+    /// Leave messages with no content, thinking or tool call out of the conversation view
+    hide_empty_messages: bool,
+    /// Per-conversation overrides of `hide_empty_messages`, for debugging imports
+    empty_message_overrides: std::collections::HashMap<String, bool>,
+    /// Show debugging actions such as copying a conversation as JSON
+    developer_mode: bool,
+
+    // Quick switcher (Ctrl+E / Ctrl+Tab), most recent first
+    recent_conversations: Vec<String>,
+    show_quick_switcher: bool,
+    quick_switcher_items: Vec<(String, String)>, // (conv_id, title)
+    quick_switcher_index: usize,
+
+    // Archive multi-select
+    selected_conversations: std::collections::HashSet<String>,
+    bulk_tag_input: String,
+
+    // Search-and-replace over the selected conversations' messages
+    show_replace_panel: bool,
+    replace_find: String,
+    replace_with: String,
+    replace_regex: bool,
+    replace_preview: Option<ReplacePreview>,
+    replace_error: Option<String>,
+    /// Last applied replace (backup batch id, messages), for Undo
+    last_replace_batch: Option<(String, usize)>,
 
     // Export dialog state
     show_export_dialog: bool,
     export_format: ExportFormat,
     export_output_dir: String,
+    export_combined: bool,
+    /// Markdown export template name (see `export_templates::available`)
+    export_template: String,
+
+    // ChatGPT/Claude export import
+    show_external_import: bool,
+    external_import_path: String,
+
+    // Conversation pasted as JSON
+    show_paste_conversation: bool,
+    paste_conversation_input: String,
+    paste_conversation_error: Option<String>,
 
     // Bookmark state
     current_bookmarks: Vec<Bookmark>,
     show_bookmark_panel: bool,
+    /// Bookmarks file path typed into the bookmark panel, while importing
+    bookmark_import_path: Option<String>,
     adding_bookmark_for: Option<String>, // message_id
     bookmark_label_input: String,
     bookmark_note_input: String,
+    /// Conversation note being edited, as (conversation id, draft)
+    note_editor: Option<(String, String)>,
+    /// Conversation title being renamed inline, as (conversation id, draft)
+    title_editor: Option<(String, String)>,
+    /// Whether the rename started from the tab bar rather than the library
+    title_editor_in_tab: bool,
+    title_editor_focus_pending: bool,
+
+    // Branch state for chats with edited messages
+    branch_choices: BranchChoices,
+    show_branch_tree: bool,
 
     // Display preferences
     display_prefs: Vec<DisplayPreference>,
+    /// Color picked in Settings but not saved yet; written once the drag ends
+    unsaved_display_pref: Option<DisplayPreference>,
+
+    // Log viewer (bottom panel)
+    log_viewer_open: bool,
+    log_level_filter: log::LevelFilter,
 
     // UI customization
     font_scale: f32,           // 0.8 - 1.5 scale factor
     message_spacing: f32,      // 8.0 - 24.0 pixels
     status_bar_font_size: f32, // 8.0 - 14.0 pixels
+    min_text_size: f32,        // 8.0 - 12.0, floor for small fixed-size labels
+    density: Density,
+    activity_bucket: activity::Bucket,
+    dashboard_activity: Option<DashboardActivity>,
+    conversation_days: Option<std::sync::Arc<ConversationDays>>,
 
     // Async import
     import_thread: Option<std::thread::JoinHandle<Result<(usize, usize), String>>>,
@@ -448,8 +1027,24 @@ struct CursorStudio {
     max_vram_mb: usize,
     storage_limit_mb: usize,
 
+    /// Fonts found at startup, for diagnostics
+    loaded_fonts: Vec<String>,
+    diagnostics_thread: Option<std::thread::JoinHandle<diagnostics::Report>>,
+    diagnostics_report: Option<diagnostics::Report>,
+
     // Security scan results
     security_scan_results: Option<SecurityScanResults>,
+    /// Characters of each finding shown in the Sentinel panel
+    secret_preview_chars: usize,
+    /// Severity given to each class of chat finding
+    severity_map: security::SeverityMap,
+    /// Severity levels filtered out of the Sentinel panel
+    hidden_severities: std::collections::HashSet<security::FindingSeverity>,
+    /// Group findings by severity instead of by type
+    findings_by_severity: bool,
+    /// Expanded findings, with the message lines around them (`None` if the
+    /// message no longer has the text)
+    finding_contexts: std::collections::HashMap<security::ChatFinding, Option<String>>,
 
     // NPM security scanner
     npm_scanner: security::SecurityScanner,
@@ -462,11 +1057,14 @@ struct CursorStudio {
     // NPM scan state
     npm_scan_path: String,
     show_npm_scan_results: bool,
+    /// Include NPM scan results in the SARIF export
+    sarif_include_npm: bool,
 
     // Conversation search
     conv_search_query: String,
     conv_search_results: Vec<usize>, // indices of matching messages
     conv_search_index: usize,        // current result index
+    goto_message_input: String,      // "go to message #" box
 
     // Sync state
     sync_server_url: String,
@@ -485,16 +1083,52 @@ struct CursorStudio {
 
     // Version download state
     available_versions: Vec<AvailableVersion>,
-    download_state: DownloadState,
-    download_progress: Option<f32>,
-    download_thread: Option<std::thread::JoinHandle<Result<PathBuf, String>>>,
-    download_receiver: Option<std::sync::mpsc::Receiver<f32>>,
+    /// Downloads that are running or failed; finished ones are dropped, so a
+    /// version without an entry is idle
+    download_state: std::collections::HashMap<String, DownloadState>,
+    /// Running downloads, at most `max_cpu_threads` at a time
+    active_downloads: std::collections::HashMap<String, ActiveDownload>,
+    /// Proxy URL from Settings; empty means use HTTP(S)_PROXY from the environment
+    download_proxy: String,
+    download_connect_timeout_secs: usize,
+    download_read_timeout_secs: usize,
+    download_retries: usize,
+    /// Write a launcher entry to ~/.local/share/applications for each install
+    create_desktop_entries: bool,
+    // Batch downloads: versions ticked in the Manager, their size estimate, and the queue
+    download_selection: std::collections::HashSet<String>,
+    download_plan: Option<DownloadPlan>,
+    download_plan_receiver: Option<std::sync::mpsc::Receiver<DownloadPlan>>,
+    download_queue: std::collections::VecDeque<String>,
+    /// Bytes used by cached downloads and installed versions, as last measured
+    /// (see `refresh_storage_usage`); `None` until the first measurement lands
+    storage_used_bytes: Option<u64>,
+    /// Background measurement of `storage_used_bytes`
+    storage_usage_thread: Option<std::thread::JoinHandle<u64>>,
+    /// Disk usage changed while measuring; measure again once it finishes
+    storage_usage_stale: bool,
+    /// Download cache directory as entered in Settings; empty for the default
+    cache_dir_input: String,
+    /// Cache directory comes from config.json and can't be changed in Settings
+    cache_dir_managed: bool,
+    /// Move cached downloads along when the cache directory changes
+    relocate_cache: bool,
+    /// Cached downloads being moved to a new cache directory
+    cache_move_receiver: Option<std::sync::mpsc::Receiver<CacheMove>>,
+    /// Version whose details card is open in the Manager
+    version_details: Option<VersionDetails>,
 
     // Security scan threading (background scan for UI responsiveness)
     security_scan_thread: Option<std::thread::JoinHandle<SecurityScanResults>>,
     security_scan_progress: Option<(usize, usize)>, // (scanned, total)
     security_scan_receiver: Option<std::sync::mpsc::Receiver<(usize, usize)>>,
     
+    // Installed version integrity check (background re-hash)
+    verify_thread: Option<std::thread::JoinHandle<Vec<(String, versions::IntegrityStatus)>>>,
+    verify_progress: Option<(usize, usize)>, // (checked, total)
+    verify_receiver: Option<std::sync::mpsc::Receiver<(usize, usize)>>,
+    verify_results: Option<Vec<(String, versions::IntegrityStatus)>>,
+
     // NPM scan threading
     npm_scan_thread: Option<std::thread::JoinHandle<Vec<(PathBuf, Vec<security::PackageScanResult>)>>>,
     npm_scan_progress: Option<String>,
@@ -505,6 +1139,8 @@ struct CursorStudio {
 
     // Documentation Index panel (cursor-docs integration)
     docs_panel: docs::DocsPanel,
+    /// Chunk (id, position) to scroll to in an indexed source tab, from a search hit
+    doc_scroll_target: Option<(String, usize)>,
     
     // Custom Modes panel (replaces Cursor 2.1+ removed custom modes)
     modes_panel: modes::ModesPanel,
@@ -514,9 +1150,47 @@ struct CursorStudio {
 struct SecurityScanResults {
     total_messages: usize,
     scanned_at: String,
-    potential_api_keys: Vec<(String, String, String)>, // (conv_id, msg_id, preview)
-    potential_passwords: Vec<(String, String, String)>,
-    potential_secrets: Vec<(String, String, String)>,
+    potential_api_keys: Vec<security::ChatFinding>,
+    potential_passwords: Vec<security::ChatFinding>,
+    potential_secrets: Vec<security::ChatFinding>,
+}
+
+impl SecurityScanResults {
+    fn findings(&self, kind: security::SecretKind) -> &[security::ChatFinding] {
+        match kind {
+            security::SecretKind::ApiKey => &self.potential_api_keys,
+            security::SecretKind::Password => &self.potential_passwords,
+            security::SecretKind::Secret => &self.potential_secrets,
+        }
+    }
+
+    fn severity_count(&self, severity: security::FindingSeverity) -> usize {
+        security::SecretKind::ALL
+            .into_iter()
+            .flat_map(|kind| self.findings(kind))
+            .filter(|f| f.severity == severity)
+            .count()
+    }
+
+    fn findings_mut(&mut self, kind: security::SecretKind) -> &mut Vec<security::ChatFinding> {
+        match kind {
+            security::SecretKind::ApiKey => &mut self.potential_api_keys,
+            security::SecretKind::Password => &mut self.potential_passwords,
+            security::SecretKind::Secret => &mut self.potential_secrets,
+        }
+    }
+
+    /// SARIF 2.1.0 log of the secret findings, plus the NPM scan results if given
+    fn to_sarif(
+        &self,
+        npm: Option<&[(PathBuf, Vec<security::PackageScanResult>)]>,
+    ) -> serde_json::Value {
+        let chat: Vec<_> = security::SecretKind::ALL
+            .into_iter()
+            .map(|kind| (kind, self.findings(kind)))
+            .collect();
+        security::sarif_log(&chat, npm.unwrap_or_default())
+    }
 }
 
 /// Progress updates during import
@@ -525,6 +1199,20 @@ enum ImportProgress {
     Started(usize),           // total databases to process
     Processing(usize, usize), // current database, total databases
     Completed(usize, usize),  // imported conversations, skipped (already existed)
+    /// Sent before `Completed`: conversations skipped as copies of ones in the
+    /// library under another id
+    Deduplicated(usize),
+    /// Sent before `Completed`: ids of the conversations added
+    Added(Vec<String>),
+    /// Sent before `Completed` when some source databases were damaged
+    Salvaged(database::CursorImport),
+    /// Versions whose database Cursor kept locked, read from their last checkpoint
+    Locked(Vec<String>),
+    /// Paused on request after finishing a database (databases done, total)
+    Paused(usize, usize),
+    Resumed,
+    /// A source database that couldn't be imported at all (version, error)
+    SourceFailed(String, String),
     Error(String),
 }
 
@@ -535,15 +1223,85 @@ impl CursorStudio {
 
         let db = ChatDatabase::new().expect("Failed to open database");
         let versions = db.get_versions().unwrap_or_default();
-        let conversations = db.get_conversations(50).unwrap_or_default();
-
-        let available_themes = Self::find_vscode_themes();
+        let show_archived = db.get_config_bool("ui.show_archived", false);
+        let stats_exclude_archived = db.get_config_bool("ui.stats_exclude_archived", true);
+        let conversation_page_size = db.get_config_usize("ui.conversation_page_size", 200);
+        let conversations = db
+            .get_conversations(conversation_page_size, show_archived, None)
+            .unwrap_or_default();
+        let conversations_total = db.get_stats(show_archived).map_or(0, |(total, _, _)| total);
+        let library_total = db.get_stats(true).map_or(0, |(total, _, _)| total);
+        let version_chat_counts = db.conversation_counts_by_version().unwrap_or_default();
+        let privacy_hotkey = db
+            .get_config("ui.privacy_hotkey")
+            .filter(|chord| hotkey::parse_shortcut(chord).is_some())
+            .unwrap_or_else(|| DEFAULT_PRIVACY_HOTKEY.to_string());
+
+        let available_themes = Self::load_theme_index(&db);
+
+        let export_template = db
+            .get_config("ui.export_template")
+            .unwrap_or_else(|| export_templates::DEFAULT_TEMPLATE.to_string());
+
+        // Download cache location: config.json wins over the Settings value
+        let cache_dir_managed = ext_config
+            .as_ref()
+            .and_then(|c| c.cache_dir.as_ref())
+            .is_some();
+        let cache_dir_input = ext_config
+            .as_ref()
+            .and_then(|c| c.cache_dir.clone())
+            .or_else(|| db.get_config("ui.cache_dir"))
+            .unwrap_or_default();
+        let mut cache_dir_warning = None;
+        if !cache_dir_input.trim().is_empty() {
+            let dir = expand_home(cache_dir_input.trim());
+            match versions::validate_cache_dir(&dir) {
+                Ok(()) => versions::set_cache_dir(Some(dir)),
+                Err(e) => {
+                    log::warn!("Download cache {} is unusable: {:#}", dir.display(), e);
+                    cache_dir_warning = Some(format!(
+                        "⚠ Download cache {} is unusable, using the default",
+                        dir.display()
+                    ));
+                }
+            }
+        }
 
-        // Find default version from installed versions
-        let default_version = versions
-            .iter()
-            .find(|v| v.is_default)
-            .map(|v| v.version.clone())
+        // Drop interrupted downloads and cached copies of installed versions
+        let startup_status = match versions::prune_cache(STALE_DOWNLOAD_AGE, false, &[]) {
+            Ok(pruned) => {
+                for error in &pruned.errors {
+                    log::warn!("Download cache prune: {}", error);
+                }
+                match pruned.reclaimed {
+                    0 => None,
+                    freed => {
+                        log::info!("Pruned {} bytes from the download cache", freed);
+                        Some(format!(
+                            "✓ Cleaned {} of stale cached downloads",
+                            versions::format_size(freed)
+                        ))
+                    }
+                }
+            }
+            Err(e) => {
+                log::warn!("Failed to prune download cache: {}", e);
+                None
+            }
+        };
+        let startup_status = cache_dir_warning.or(startup_status);
+
+        // Saved default version if it's still installed, else the main installation
+        let default_version = db
+            .get_config("ui.default_version")
+            .filter(|saved| versions.iter().any(|v| v.version == *saved))
+            .or_else(|| {
+                versions
+                    .iter()
+                    .find(|v| v.is_default)
+                    .map(|v| v.version.clone())
+            })
             .unwrap_or_else(|| "default".to_string());
 
         // Launch version starts as default
@@ -564,6 +1322,8 @@ impl CursorStudio {
                             p.style.clone()
                         },
                         collapsed_by_default: p.collapsed,
+                        fg_color: p.fg_color.clone(),
+                        bg_color: p.bg_color.clone(),
                     })
                     .collect()
             } else {
@@ -586,6 +1346,10 @@ impl CursorStudio {
             .as_ref()
             .and_then(|c| c.status_bar_font_size)
             .unwrap_or_else(|| db.get_config_f32("ui.status_bar_font_size", 11.0));
+        let min_text_size = ext_config
+            .as_ref()
+            .and_then(|c| c.min_text_size)
+            .unwrap_or_else(|| db.get_config_f32("ui.min_text_size", 8.0));
 
         // Resource limits: external config takes priority
         let default_threads = std::thread::available_parallelism()
@@ -613,38 +1377,128 @@ impl CursorStudio {
             .and_then(|r| r.storage_limit_mb)
             .unwrap_or_else(|| db.get_config_usize("res.storage_limit_mb", 10240));
 
-        Self {
+        // First run: nothing imported, not configured, wizard never completed
+        let first_run = !db.get_config_bool("ui.onboarded", false)
+            && conversations.is_empty()
+            && ext_config.is_none();
+
+        let mut app = Self {
             theme: Theme::dark(),
-            db,
             left_sidebar_visible: true,
             right_sidebar_visible: true,
             left_sidebar_width: 280.0,
             right_sidebar_width: 300.0,
             left_mode: SidebarMode::Manager,
+            focus_search_pending: false,
             right_mode: RightSidebarMode::Archive,
+            focus_anchors: Vec::new(),
+            focus_region: None,
             tabs: vec![Tab::Dashboard],
+            pinned_tabs: Vec::new(),
+            split: None,
+            pending_large_conversation: None,
+            paged_conversations: std::collections::HashMap::new(),
             active_tab: 0,
             versions,
+            version_chat_counts,
             conversations,
+            conversation_page_size,
+            conversations_loaded: conversation_page_size,
+            conversations_total,
+            library_total,
+            conversation_previews: std::collections::HashMap::new(),
+            known_conversations: std::collections::HashSet::new(),
+            new_conversations: std::collections::HashMap::new(),
+            preview_worker: None,
             current_messages: vec![],
             search_query: String::new(),
             search_results: vec![],
-            status_message: None,
+            message_hits: vec![],
+            onboarding_step: first_run.then_some(OnboardingStep::Detect),
+            install_detection: None,
+            install_search_dirs: db.get_config("ui.install_search_dirs").unwrap_or_default(),
+            status_history: startup_status
+                .as_deref()
+                .map(StatusEntry::new)
+                .into_iter()
+                .collect(),
+            show_status_history: false,
+            status_message: startup_status,
+            last_export: None,
             show_theme_picker: false,
             show_version_picker: false,
             show_launch_picker: false,
             available_themes,
             current_theme_name: "Dark+ (default dark)".to_string(),
+            follow_cursor_theme: db.get_config_bool("ui.follow_cursor_theme", false),
+            cursor_theme_checked: None,
+            cursor_settings_mtime: None,
+            followed_cursor_theme: None,
             default_version,
             launch_version,
+            favorite_versions: db
+                .get_config("ui.favorite_versions")
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default(),
+            launch_env: db
+                .get_config("ui.launch_env")
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default(),
             auto_sync_enabled: true,
             import_on_start: false,
+            auto_import_enabled: db.get_config_bool("ui.auto_import", false),
+            auto_import_interval_mins: db.get_config_usize("ui.auto_import_interval_mins", 15),
+            auto_import_timer: std::time::Instant::now(),
+            last_auto_import: None,
+            toasts: Vec::new(),
+            idle_lock_mins: db.get_config_usize("ui.idle_lock_mins", 0),
+            settings_autosave_secs: db.get_config_usize("ui.settings_autosave_secs", 30),
+            settings_autosave_timer: std::time::Instant::now(),
+            saved_settings: Vec::new(),
+            last_input: std::time::Instant::now(),
+            idle_locked: false,
+            privacy_hotkey_input: privacy_hotkey.clone(),
+            privacy_hotkey,
+            privacy_screen: false,
             show_all_versions: true, // Default to showing all versions
             hovered_theme: None,
+            theme_hover_since: None,
+            theme_preview: None,
+            theme_cache: ThemeCache::default(),
             import_in_progress: false,
             import_progress: None,
+            import_duplicates: 0,
+            import_new_ids: Vec::new(),
+            import_pause: Default::default(),
+            import_paused: false,
             import_warning_shown: false,
             last_import_error: None,
+            import_preview: None,
+            import_preview_receiver: None,
+            show_archived,
+            archive_tag_filter: None,
+            stats_exclude_archived,
+            relative_timestamps: db.get_config_bool("ui.relative_timestamps", true),
+            code_wrap: db.get_config_bool("ui.code_wrap", false),
+            hide_empty_messages: db.get_config_bool("ui.hide_empty_messages", false),
+            empty_message_overrides: std::collections::HashMap::new(),
+            developer_mode: db.get_config_bool("ui.developer_mode", false),
+            recent_conversations: db
+                .get_config("ui.recent_conversations")
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default(),
+            show_quick_switcher: false,
+            quick_switcher_items: Vec::new(),
+            quick_switcher_index: 0,
+            selected_conversations: std::collections::HashSet::new(),
+            bulk_tag_input: String::new(),
+            show_replace_panel: false,
+            replace_find: String::new(),
+            replace_with: String::new(),
+            replace_regex: false,
+            replace_preview: None,
+            replace_error: None,
+            last_replace_batch: db.last_replace_batch().ok().flatten(),
             // Export dialog state
             show_export_dialog: false,
             export_format: ExportFormat::default(),
@@ -653,18 +1507,47 @@ impl CursorStudio {
                 .join("cursor-exports")
                 .to_string_lossy()
                 .to_string(),
+            export_combined: false,
+            export_template,
+            show_external_import: false,
+            external_import_path: String::new(),
+            show_paste_conversation: false,
+            paste_conversation_input: String::new(),
+            paste_conversation_error: None,
             // Bookmark state
             current_bookmarks: vec![],
             show_bookmark_panel: false,
+            bookmark_import_path: None,
             adding_bookmark_for: None,
             bookmark_label_input: String::new(),
+            note_editor: None,
+            title_editor: None,
+            title_editor_in_tab: false,
+            title_editor_focus_pending: false,
             bookmark_note_input: String::new(),
+            // Branch state
+            branch_choices: BranchChoices::new(),
+            show_branch_tree: false,
             // Display preferences
             display_prefs,
+            unsaved_display_pref: None,
+            log_viewer_open: false,
+            log_level_filter: log::LevelFilter::Info,
             // UI customization (loaded from config above)
             font_scale,
             message_spacing,
             status_bar_font_size,
+            min_text_size,
+            density: db
+                .get_config("ui.density")
+                .map(|key| Density::from_key(&key))
+                .unwrap_or_default(),
+            activity_bucket: db
+                .get_config("ui.activity_bucket")
+                .map(|key| activity::Bucket::from_key(&key))
+                .unwrap_or_default(),
+            dashboard_activity: None,
+            conversation_days: None,
             // Async import
             import_thread: None,
             import_receiver: None,
@@ -674,8 +1557,24 @@ impl CursorStudio {
             max_ram_mb,
             max_vram_mb,
             storage_limit_mb,
+            loaded_fonts: Vec::new(),
+            diagnostics_thread: None,
+            diagnostics_report: None,
+
             // Security scan
             security_scan_results: None,
+            secret_preview_chars: db.get_config_usize(
+                "ui.secret_preview_chars",
+                security::DEFAULT_PREVIEW_CHARS,
+            ),
+            severity_map: ext_config
+                .as_ref()
+                .and_then(|c| c.security.as_ref())
+                .map(|s| s.severity)
+                .unwrap_or_default(),
+            hidden_severities: std::collections::HashSet::new(),
+            findings_by_severity: false,
+            finding_contexts: std::collections::HashMap::new(),
 
             // NPM security
             npm_scanner: security::SecurityScanner::new(),
@@ -690,11 +1589,13 @@ impl CursorStudio {
                 .map(|h| h.to_string_lossy().to_string())
                 .unwrap_or_else(|| "/home".to_string()),
             show_npm_scan_results: false,
+            sarif_include_npm: true,
 
             // Conversation search
             conv_search_query: String::new(),
             conv_search_results: Vec::new(),
             conv_search_index: 0,
+            goto_message_input: String::new(),
 
             // Sync state
             sync_server_url: "http://localhost:8420".to_string(),
@@ -713,15 +1614,35 @@ impl CursorStudio {
 
             // Version download state
             available_versions: get_available_versions(),
-            download_state: DownloadState::Idle,
-            download_progress: None,
-            download_thread: None,
-            download_receiver: None,
+            download_state: std::collections::HashMap::new(),
+            active_downloads: std::collections::HashMap::new(),
+            download_proxy: db.get_config("ui.download_proxy").unwrap_or_default(),
+            download_connect_timeout_secs: db.get_config_usize("ui.download_connect_timeout", 30),
+            download_read_timeout_secs: db.get_config_usize("ui.download_read_timeout", 60),
+            download_retries: db.get_config_usize("ui.download_retries", 3),
+            create_desktop_entries: db.get_config_bool("ui.create_desktop_entries", false),
+            download_selection: std::collections::HashSet::new(),
+            download_plan: None,
+            download_plan_receiver: None,
+            download_queue: std::collections::VecDeque::new(),
+            cache_dir_input,
+            cache_dir_managed,
+            relocate_cache: true,
+            cache_move_receiver: None,
+            storage_used_bytes: None,
+            storage_usage_thread: Some(std::thread::spawn(versions::storage_usage_bytes)),
+            storage_usage_stale: false,
+            version_details: None,
 
             // Security scan threading
             security_scan_thread: None,
             security_scan_progress: None,
             security_scan_receiver: None,
+
+            verify_thread: None,
+            verify_progress: None,
+            verify_receiver: None,
+            verify_results: None,
             
             // NPM scan threading
             npm_scan_thread: None,
@@ -733,12 +1654,23 @@ impl CursorStudio {
 
             // Documentation Index panel
             docs_panel: docs::DocsPanel::new(),
+            doc_scroll_target: None,
             
             // Custom Modes panel
-            modes_panel: modes::ModesPanel::new(
-                std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."))
-            ),
-        }
+            modes_panel: modes::ModesPanel::new(),
+
+            // Last: the fields above read their saved settings from it
+            db,
+        };
+        app.restore_saved_theme();
+        app.saved_settings = app.persisted_settings();
+        app.known_conversations = app
+            .db
+            .conversation_ids()
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        app
     }
 
     /// # TODO(P1): Release v0.3.0 - Settings Persistence
@@ -747,31 +1679,107 @@ impl CursorStudio {
     /// - [ ] Save last opened conversation ID
     /// - [ ] Call save_settings on app close (implement on_close_event)
     /// - [ ] Add settings export/import for backup
-    fn save_settings(&self) {
-        // UI settings
-        let _ = self
-            .db
-            .set_config("ui.font_scale", &self.font_scale.to_string());
-        let _ = self
-            .db
-            .set_config("ui.message_spacing", &self.message_spacing.to_string());
-        let _ = self.db.set_config(
-            "ui.status_bar_font_size",
-            &self.status_bar_font_size.to_string(),
-        );
-        // Resource settings
-        let _ = self
-            .db
-            .set_config("res.max_cpu_threads", &self.max_cpu_threads.to_string());
-        let _ = self
-            .db
-            .set_config("res.max_ram_mb", &self.max_ram_mb.to_string());
-        let _ = self
-            .db
-            .set_config("res.max_vram_mb", &self.max_vram_mb.to_string());
-        let _ = self
-            .db
-            .set_config("res.storage_limit_mb", &self.storage_limit_mb.to_string());
+    fn save_settings(&mut self) {
+        let settings = self.persisted_settings();
+        for (key, value) in &settings {
+            let _ = self.db.set_config(key, value);
+        }
+        self.saved_settings = settings;
+        self.settings_autosave_timer = std::time::Instant::now();
+    }
+
+    /// Every setting `save_settings` writes, as (config key, value)
+    fn persisted_settings(&self) -> Vec<(&'static str, String)> {
+        vec![
+            // UI settings
+            ("ui.font_scale", self.font_scale.to_string()),
+            ("ui.message_spacing", self.message_spacing.to_string()),
+            (
+                "ui.status_bar_font_size",
+                self.status_bar_font_size.to_string(),
+            ),
+            ("ui.min_text_size", self.min_text_size.to_string()),
+            ("ui.density", self.density.key().to_string()),
+            ("ui.activity_bucket", self.activity_bucket.key().to_string()),
+            ("ui.show_archived", self.show_archived.to_string()),
+            (
+                "ui.stats_exclude_archived",
+                self.stats_exclude_archived.to_string(),
+            ),
+            (
+                "ui.relative_timestamps",
+                self.relative_timestamps.to_string(),
+            ),
+            ("ui.code_wrap", self.code_wrap.to_string()),
+            (
+                "ui.hide_empty_messages",
+                self.hide_empty_messages.to_string(),
+            ),
+            ("ui.developer_mode", self.developer_mode.to_string()),
+            ("ui.auto_import", self.auto_import_enabled.to_string()),
+            (
+                "ui.auto_import_interval_mins",
+                self.auto_import_interval_mins.to_string(),
+            ),
+            ("ui.idle_lock_mins", self.idle_lock_mins.to_string()),
+            ("ui.install_search_dirs", self.install_search_dirs.clone()),
+            (
+                "ui.secret_preview_chars",
+                self.secret_preview_chars.to_string(),
+            ),
+            (
+                "ui.follow_cursor_theme",
+                self.follow_cursor_theme.to_string(),
+            ),
+            (
+                "ui.conversation_page_size",
+                self.conversation_page_size.to_string(),
+            ),
+            ("ui.privacy_hotkey", self.privacy_hotkey.clone()),
+            (
+                "ui.download_connect_timeout",
+                self.download_connect_timeout_secs.to_string(),
+            ),
+            (
+                "ui.download_read_timeout",
+                self.download_read_timeout_secs.to_string(),
+            ),
+            ("ui.download_retries", self.download_retries.to_string()),
+            (
+                "ui.create_desktop_entries",
+                self.create_desktop_entries.to_string(),
+            ),
+            (
+                "ui.settings_autosave_secs",
+                self.settings_autosave_secs.to_string(),
+            ),
+            // Resource settings
+            ("res.max_cpu_threads", self.max_cpu_threads.to_string()),
+            ("res.max_ram_mb", self.max_ram_mb.to_string()),
+            ("res.max_vram_mb", self.max_vram_mb.to_string()),
+            ("res.storage_limit_mb", self.storage_limit_mb.to_string()),
+        ]
+    }
+
+    /// Write settings that changed since the last save, at most every
+    /// `settings_autosave_secs`, so a crash doesn't lose them. Comparing with
+    /// what was last written also catches fields changed without an explicit
+    /// `save_settings` call.
+    fn maybe_autosave_settings(&mut self, ctx: &egui::Context) {
+        if self.settings_autosave_secs == 0 {
+            return;
+        }
+
+        let interval = std::time::Duration::from_secs(self.settings_autosave_secs as u64);
+        if self.settings_autosave_timer.elapsed() < interval {
+            schedule_repaint_at(ctx, self.settings_autosave_timer + interval);
+            return;
+        }
+        self.settings_autosave_timer = std::time::Instant::now();
+        if self.persisted_settings() != self.saved_settings {
+            log::debug!("Auto-saving changed settings");
+            self.save_settings();
+        }
     }
 
     fn run_security_scan(&mut self) {
@@ -787,7 +1795,8 @@ impl CursorStudio {
 
         // Collect conversation data to pass to thread (avoiding self borrow)
         let db_path = self.db.get_path();
-        let total_convs = self.conversations.len();
+        let total_convs = self.db.get_stats(true).map_or(0, |(total, _, _)| total);
+        let severity_map = self.severity_map;
 
         self.set_status(&format!("🔍 Starting security scan of {} conversations...", total_convs));
         self.security_scan_progress = Some((0, total_convs));
@@ -809,16 +1818,13 @@ impl CursorStudio {
             };
 
             // Patterns to detect sensitive data
-            let api_key_pattern = Regex::new(r#"(?i)(api[_-]?key|apikey|api_token|auth_token)[=:\s]*['"]?([a-zA-Z0-9_\-]{20,})['"]?"#).ok();
-            let password_pattern =
-                Regex::new(r#"(?i)(password|passwd|pwd)[=:\s]*['"]?([^\s'"]{8,})['"]?"#).ok();
-            let secret_pattern = Regex::new(
-                r#"(?i)(secret|private_key|access_token|bearer)[=:\s]*['"]?([a-zA-Z0-9_\-]{16,})['"]?"#,
-            )
-            .ok();
+            let patterns: Vec<(security::SecretKind, Regex)> = security::SecretKind::ALL
+                .into_iter()
+                .filter_map(|kind| Regex::new(kind.pattern()).ok().map(|p| (kind, p)))
+                .collect();
 
             // Get all conversations (use large limit to scan everything)
-            let conversations = db.get_conversations(usize::MAX).unwrap_or_default();
+            let conversations = db.get_conversations(usize::MAX, true, None).unwrap_or_default();
             let total = conversations.len();
 
             // Scan all conversations
@@ -829,71 +1835,15 @@ impl CursorStudio {
                 if let Ok(messages) = db.get_messages(&conv.id) {
                     for msg in messages {
                         results.total_messages += 1;
-                        let content = &msg.content;
-
-                        // Check for API keys
-                        if let Some(ref pattern) = api_key_pattern {
-                            for cap in pattern.captures_iter(content) {
-                                let preview = cap
-                                    .get(0)
-                                    .map(|m| {
-                                        let s = m.as_str();
-                                        if s.len() > 50 {
-                                            format!("{}...", &s.chars().take(50).collect::<String>())
-                                        } else {
-                                            s.to_string()
-                                        }
-                                    })
-                                    .unwrap_or_default();
-                                results.potential_api_keys.push((
-                                    conv.id.clone(),
-                                    msg.id.clone(),
-                                    preview,
-                                ));
-                            }
-                        }
-
-                        // Check for passwords
-                        if let Some(ref pattern) = password_pattern {
-                            for cap in pattern.captures_iter(content) {
-                                let preview = cap
-                                    .get(0)
-                                    .map(|m| {
-                                        let s = m.as_str();
-                                        if s.len() > 50 {
-                                            format!("{}...", &s.chars().take(50).collect::<String>())
-                                        } else {
-                                            s.to_string()
-                                        }
-                                    })
-                                    .unwrap_or_default();
-                                results.potential_passwords.push((
-                                    conv.id.clone(),
-                                    msg.id.clone(),
-                                    preview,
-                                ));
-                            }
-                        }
 
-                        // Check for secrets
-                        if let Some(ref pattern) = secret_pattern {
-                            for cap in pattern.captures_iter(content) {
-                                let preview = cap
-                                    .get(0)
-                                    .map(|m| {
-                                        let s = m.as_str();
-                                        if s.len() > 50 {
-                                            format!("{}...", &s.chars().take(50).collect::<String>())
-                                        } else {
-                                            s.to_string()
-                                        }
-                                    })
-                                    .unwrap_or_default();
-                                results.potential_secrets.push((
-                                    conv.id.clone(),
-                                    msg.id.clone(),
-                                    preview,
-                                ));
+                        for (kind, pattern) in &patterns {
+                            for found in pattern.find_iter(&msg.content) {
+                                results.findings_mut(*kind).push(security::ChatFinding {
+                                    conversation_id: conv.id.clone(),
+                                    message_id: msg.id.clone(),
+                                    matched: found.as_str().to_string(),
+                                    severity: severity_map.classify(*kind, found.as_str()),
+                                });
                             }
                         }
                     }
@@ -906,6 +1856,64 @@ impl CursorStudio {
         self.security_scan_thread = Some(handle);
     }
 
+    /// Run the self-test in the background; network checks can take a while
+    fn run_diagnostics(&mut self) {
+        if self.diagnostics_thread.is_some() {
+            return;
+        }
+        let inputs = diagnostics::Inputs {
+            db_path: self.db.get_path(),
+            cursor_installs: self
+                .versions
+                .iter()
+                .map(|v| (v.version.clone(), v.path.clone()))
+                .collect(),
+            cursor_databases: database::discover_cursor_databases().len(),
+            loaded_fonts: self.loaded_fonts.clone(),
+            download_urls: self
+                .available_versions
+                .iter()
+                .flat_map(|v| std::iter::once(&v.download_url).chain(&v.mirror_urls))
+                .cloned()
+                .collect(),
+            proxy: Some(self.download_proxy.clone()).filter(|p| !p.trim().is_empty()),
+            connect_timeout: std::time::Duration::from_secs(
+                self.download_connect_timeout_secs as u64,
+            ),
+            p2p_binary: self.find_p2p_binary(),
+            config_path: ExternalConfig::path(),
+            config_error: ExternalConfig::parse_error(),
+        };
+        self.set_status("🩺 Running diagnostics...");
+        self.diagnostics_thread = Some(std::thread::spawn(move || diagnostics::run(&inputs)));
+    }
+
+    fn poll_diagnostics(&mut self) {
+        let Some(handle) = self.diagnostics_thread.take() else {
+            return;
+        };
+        if !handle.is_finished() {
+            self.diagnostics_thread = Some(handle);
+            return;
+        }
+        match handle.join() {
+            Ok(report) => {
+                let failed = report.count(diagnostics::CheckStatus::Fail);
+                let warned = report.count(diagnostics::CheckStatus::Warn);
+                if failed + warned == 0 {
+                    self.set_status("✓ Diagnostics: all checks passed");
+                } else {
+                    self.set_status(&format!(
+                        "⚠️ Diagnostics: {} failed, {} warnings",
+                        failed, warned
+                    ));
+                }
+                self.diagnostics_report = Some(report);
+            }
+            Err(_) => self.set_status("✗ Diagnostics thread panicked"),
+        }
+    }
+
     /// Poll security scan thread for completion (called from update loop)
     fn poll_security_scan(&mut self) {
         // Check for progress updates
@@ -925,11 +1933,14 @@ impl CursorStudio {
                             + results.potential_secrets.len();
 
                         self.set_status(&format!(
-                            "🔍 Scanned {} messages, found {} potential sensitive items",
-                            results.total_messages, total_found
+                            "🔍 Scanned {} messages, found {} potential sensitive items ({} high)",
+                            results.total_messages,
+                            total_found,
+                            results.severity_count(security::FindingSeverity::High)
                         ));
 
                         self.security_scan_results = Some(results);
+                        self.finding_contexts.clear();
                         self.security_scan_progress = None;
                         self.security_scan_receiver = None;
                     }
@@ -946,11 +1957,152 @@ impl CursorStudio {
         }
     }
 
-    fn find_vscode_themes() -> Vec<(String, Option<PathBuf>)> {
-        let mut themes = vec![
-            ("Dark+ (default dark)".to_string(), None),
-            ("Light+ (default light)".to_string(), None),
-        ];
+    /// Re-hash every installed AppImage against the registry in the background
+    fn run_version_verification(&mut self) {
+        if self.verify_thread.is_some() {
+            self.set_status("⏳ Verification already in progress...");
+            return;
+        }
+
+        // Only Cursor Studio managed installs have an AppImage to hash
+        let to_verify: Vec<String> = self
+            .versions
+            .iter()
+            .map(|v| v.version.clone())
+            .filter(|v| versions::get_installed_appimage(v).is_some())
+            .collect();
+        if to_verify.is_empty() {
+            self.set_status("⚠️ No installed AppImages to verify");
+            return;
+        }
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.verify_receiver = Some(rx);
+        self.verify_progress = Some((0, to_verify.len()));
+        self.verify_results = None;
+        self.set_status(&format!(
+            "🔍 Verifying {} installed versions...",
+            to_verify.len()
+        ));
+
+        let handle = std::thread::spawn(move || {
+            let total = to_verify.len();
+            let mut results = Vec::with_capacity(total);
+            for (idx, version) in to_verify.into_iter().enumerate() {
+                let status = versions::verify_installed_version(&version);
+                results.push((version, status));
+                let _ = tx.send((idx + 1, total));
+            }
+            results
+        });
+        self.verify_thread = Some(handle);
+    }
+
+    fn poll_version_verification(&mut self) {
+        if let Some(ref rx) = self.verify_receiver {
+            while let Ok(progress) = rx.try_recv() {
+                self.verify_progress = Some(progress);
+            }
+        }
+
+        if let Some(handle) = self.verify_thread.take() {
+            if handle.is_finished() {
+                match handle.join() {
+                    Ok(results) => {
+                        let failed = results.iter().filter(|(_, s)| s.is_failure()).count();
+                        if failed > 0 {
+                            self.set_status(&format!(
+                                "⚠️ {} of {} installed versions failed verification",
+                                failed,
+                                results.len()
+                            ));
+                        } else {
+                            self.set_status(&format!(
+                                "✓ Verified {} installed versions",
+                                results.len()
+                            ));
+                        }
+                        self.verify_results = Some(results);
+                    }
+                    Err(_) => {
+                        self.set_status("✗ Verification thread panicked");
+                    }
+                }
+                self.verify_progress = None;
+                self.verify_receiver = None;
+            } else {
+                // Thread still running, put it back
+                self.verify_thread = Some(handle);
+            }
+        }
+    }
+
+    /// Failed integrity check for a version from the last verification run
+    fn integrity_failure(&self, version: &str) -> Option<&versions::IntegrityStatus> {
+        self.verify_results
+            .as_ref()?
+            .iter()
+            .find(|(v, status)| v == version && status.is_failure())
+            .map(|(_, status)| status)
+    }
+
+    /// Modification time of ~/.cursor/extensions (changes when extensions are added or removed)
+    fn extensions_mtime() -> Option<u64> {
+        let modified = std::fs::metadata(dirs::home_dir()?.join(".cursor/extensions"))
+            .and_then(|meta| meta.modified())
+            .ok()?;
+        modified
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()
+            .map(|d| d.as_secs())
+    }
+
+    /// Theme list from the persisted index, rescanning only if extensions changed
+    fn load_theme_index(db: &ChatDatabase) -> Vec<(String, Option<PathBuf>)> {
+        let mtime = Self::extensions_mtime().unwrap_or(0);
+        if let Some(index) = db
+            .get_config("ui.theme_index")
+            .and_then(|json| serde_json::from_str::<ThemeIndex>(&json).ok())
+        {
+            if index.extensions_mtime == mtime {
+                return index.themes;
+            }
+        }
+
+        let themes = Self::find_vscode_themes();
+        Self::save_theme_index(db, &themes);
+        themes
+    }
+
+    fn save_theme_index(db: &ChatDatabase, themes: &[(String, Option<PathBuf>)]) {
+        let index = ThemeIndex {
+            extensions_mtime: Self::extensions_mtime().unwrap_or(0),
+            themes: themes.to_vec(),
+        };
+        if let Ok(json) = serde_json::to_string(&index) {
+            let _ = db.set_config("ui.theme_index", &json);
+        }
+    }
+
+    /// Rescan theme extensions and drop cached parses
+    fn rescan_themes(&mut self) {
+        let old_count = self.available_themes.len();
+        self.available_themes = Self::find_vscode_themes();
+        Self::save_theme_index(&self.db, &self.available_themes);
+        self.theme_cache.clear();
+        self.theme_preview = None;
+        self.set_status(&format!(
+            "✓ Found {} themes (was {})",
+            self.available_themes.len(),
+            old_count
+        ));
+    }
+
+    fn find_vscode_themes() -> Vec<(String, Option<PathBuf>)> {
+        let mut themes = vec![
+            ("Dark+ (default dark)".to_string(), None),
+            ("Light+ (default light)".to_string(), None),
+        ];
 
         if let Some(home) = dirs::home_dir() {
             let cursor_extensions = home.join(".cursor/extensions");
@@ -1000,26 +2152,167 @@ impl CursorStudio {
     }
 
     fn refresh_versions(&mut self) {
+        versions::reload_versions_overlay();
+        self.available_versions = get_available_versions();
         self.versions = self.db.get_versions().unwrap_or_default();
+        self.refresh_storage_usage();
         self.set_status("✓ Refreshed versions");
     }
 
+    /// Search for Cursor installs; unlisted ones start ticked for registering
+    fn detect_installations(&mut self) {
+        let extra_dirs: Vec<PathBuf> = self
+            .install_search_dirs
+            .split(':')
+            .map(str::trim)
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .collect();
+        let found = versions::detect_installations(&extra_dirs);
+        let selected = found
+            .iter()
+            .map(|f| !self.versions.iter().any(|v| v.version == f.version))
+            .collect();
+        self.set_status(&format!("🔍 Found {} Cursor installations", found.len()));
+        self.install_detection = Some(InstallDetection { found, selected });
+    }
+
+    fn register_detected_installs(&mut self) {
+        let Some(detection) = self.install_detection.take() else {
+            return;
+        };
+        let mut registered = 0;
+        for (install, _) in detection
+            .found
+            .into_iter()
+            .zip(detection.selected)
+            .filter(|(_, selected)| *selected)
+        {
+            let result = self.db.register_install(&database::RegisteredInstall {
+                version: install.version.clone(),
+                appimage: install.appimage,
+                data_dir: install.data_dir,
+            });
+            match result {
+                Ok(()) => registered += 1,
+                Err(e) => log::warn!("Failed to register v{}: {}", install.version, e),
+            }
+        }
+        self.versions = self.db.get_versions().unwrap_or_default();
+        self.refresh_version_chat_counts();
+        self.set_status(&format!("✓ Registered {} installations", registered));
+    }
+
     fn refresh_chats(&mut self) {
-        self.conversations = self.db.get_conversations(50).unwrap_or_default();
-        self.set_status("✓ Refreshed chat library");
+        self.reload_conversations();
+        self.refresh_version_chat_counts();
+        let new_conversations = self.track_new_conversations();
+        self.set_status(&format!(
+            "✓ Refreshed chat library: {}",
+            refresh_summary(new_conversations, 0)
+        ));
     }
 
     fn refresh_all(&mut self) {
         self.versions = self.db.get_versions().unwrap_or_default();
-        self.conversations = self.db.get_conversations(50).unwrap_or_default();
+        self.reload_conversations();
+        self.refresh_version_chat_counts();
+        let new_conversations = self.track_new_conversations();
 
         // Also refresh bookmarks if a conversation is currently open
+        let mut new_messages = 0;
         if let Some(Tab::Conversation(conv_id)) = self.tabs.get(self.active_tab).cloned() {
             self.current_bookmarks = self.db.get_bookmarks(&conv_id).unwrap_or_default();
-            self.current_messages = self.db.get_messages(&conv_id).unwrap_or_default();
+            let before = self.current_messages.len();
+            self.current_messages = self.load_messages(&conv_id);
+            new_messages = self.current_messages.len().saturating_sub(before);
+        }
+
+        self.set_status(&format!(
+            "✓ Refreshed all data: {}",
+            refresh_summary(new_conversations, new_messages)
+        ));
+    }
+
+    /// Compare the library's conversations with the last refresh, marking the
+    /// ones that arrived since; returns how many did
+    fn track_new_conversations(&mut self) -> usize {
+        let Ok(ids) = self.db.conversation_ids() else {
+            return 0;
+        };
+        let now = std::time::Instant::now();
+        let mut arrived = 0;
+        for id in &ids {
+            if !self.known_conversations.contains(id) {
+                self.new_conversations.insert(id.clone(), now);
+                arrived += 1;
+            }
+        }
+        self.known_conversations = ids.into_iter().collect();
+        arrived
+    }
+
+    /// Re-fetch the loaded page of the library, keeping however many "Load more" added
+    fn reload_conversations(&mut self) {
+        // Imports and edits can change a chat's endpoints
+        self.conversation_previews.clear();
+        let tag = self.archive_tag_filter.as_deref();
+        self.conversations = self
+            .db
+            .get_conversations(self.conversations_loaded, self.show_archived, tag)
+            .unwrap_or_default();
+        self.conversations_total = match tag {
+            Some(tag) => self
+                .db
+                .get_conversations(usize::MAX, self.show_archived, Some(tag))
+                .map_or(self.conversations.len(), |convs| convs.len()),
+            None => self
+                .db
+                .get_stats(self.show_archived)
+                .map_or(self.conversations.len(), |(total, _, _)| total),
+        };
+        self.library_total = self.db.get_stats(true).map_or(0, |(total, _, _)| total);
+    }
+
+    /// Queue a hover preview unless it is cached or already loading
+    fn request_conversation_preview(&mut self, conv_id: &str) {
+        if self.conversation_previews.contains_key(conv_id) {
+            return;
+        }
+        let db_path = self.db.get_path();
+        let worker = self
+            .preview_worker
+            .get_or_insert_with(|| PreviewWorker::spawn(db_path));
+        if worker.requests.send(conv_id.to_string()).is_ok() {
+            self.conversation_previews.insert(conv_id.to_string(), None);
+        }
+    }
+
+    fn poll_conversation_previews(&mut self, ctx: &egui::Context) {
+        let Some(worker) = &self.preview_worker else {
+            return;
+        };
+        for (conv_id, preview) in worker.results.try_iter() {
+            self.conversation_previews.insert(conv_id, Some(preview));
         }
+        if self.conversation_previews.values().any(Option::is_none) {
+            ctx.request_repaint_after(std::time::Duration::from_millis(50));
+        }
+    }
+
+    /// Every conversation under the archive filter, for actions meant to cover the whole library
+    fn all_conversations(&self) -> Vec<Conversation> {
+        self.db
+            .get_conversations(
+                usize::MAX,
+                self.show_archived,
+                self.archive_tag_filter.as_deref(),
+            )
+            .unwrap_or_default()
+    }
 
-        self.set_status("✓ Refreshed all data");
+    fn refresh_version_chat_counts(&mut self) {
+        self.version_chat_counts = self.db.conversation_counts_by_version().unwrap_or_default();
     }
 
     // ==================== BOOKMARK METHODS ====================
@@ -1041,12 +2334,17 @@ impl CursorStudio {
             Some(self.bookmark_note_input.as_str())
         };
 
+        let existing = self.is_bookmarked(msg_id).is_some();
         match self
             .db
             .add_bookmark(conv_id, msg_id, msg_seq, label, note, "#ffd700")
         {
             Ok(_) => {
-                self.set_status("✓ Bookmark added");
+                self.set_status(if existing {
+                    "✓ Bookmark updated"
+                } else {
+                    "✓ Bookmark added"
+                });
                 self.refresh_bookmarks(conv_id);
             }
             Err(e) => {
@@ -1072,6 +2370,42 @@ impl CursorStudio {
         }
     }
 
+    /// Start renaming a conversation inline, from its tab or library row
+    fn begin_rename(&mut self, conv_id: &str, in_tab: bool) {
+        let title = self
+            .conversations
+            .iter()
+            .find(|c| c.id == conv_id)
+            .map(|c| c.title.clone())
+            .unwrap_or_default();
+        self.title_editor = Some((conv_id.to_string(), title));
+        self.title_editor_in_tab = in_tab;
+        self.title_editor_focus_pending = true;
+    }
+
+    fn finish_rename(&mut self, save: bool) {
+        let Some((conv_id, draft)) = self.title_editor.take().filter(|_| save) else {
+            return;
+        };
+        match self.db.rename_conversation(&conv_id, &draft) {
+            Ok(()) => {
+                // Reread, as an empty title brings back the imported one
+                if let Ok(Some(renamed)) = self.db.get_conversation(&conv_id) {
+                    for conv in self
+                        .conversations
+                        .iter_mut()
+                        .chain(self.search_results.iter_mut())
+                        .filter(|c| c.id == conv_id)
+                    {
+                        conv.title = renamed.title.clone();
+                    }
+                }
+                self.set_status("✓ Conversation renamed");
+            }
+            Err(e) => self.set_status(&format!("✗ Rename failed: {}", e)),
+        }
+    }
+
     fn is_bookmarked(&self, msg_id: &str) -> Option<&Bookmark> {
         self.current_bookmarks
             .iter()
@@ -1095,7 +2429,7 @@ impl CursorStudio {
             // Open the conversation
             self.tabs.push(Tab::Conversation(conv_id.to_string()));
             self.active_tab = self.tabs.len() - 1;
-            self.current_messages = self.db.get_messages(conv_id).unwrap_or_default();
+            self.current_messages = self.load_messages(conv_id);
         }
 
         // Set the scroll target - the UI will pick this up
@@ -1103,6 +2437,40 @@ impl CursorStudio {
         self.set_status(&format!("📍 Jumped to message"));
     }
 
+    /// Scroll to the message whose sequence number is in the "go to" box
+    fn goto_message_sequence(&mut self) {
+        let input = self.goto_message_input.trim().trim_start_matches('#');
+        let Ok(sequence) = input.parse::<usize>() else {
+            self.set_status(&format!("⚠️ Not a message number: {}", input));
+            return;
+        };
+        let (first, last) = match (self.current_messages.first(), self.current_messages.last()) {
+            (Some(first), Some(last)) => (first.sequence, last.sequence),
+            _ => return,
+        };
+        let target = self
+            .current_messages
+            .iter()
+            .find(|m| m.sequence == sequence)
+            .map(|m| m.id.clone());
+
+        match target {
+            Some(msg_id) => {
+                self.scroll_to_message_id = Some(msg_id);
+                self.goto_message_input.clear();
+                self.set_status(&format!("📍 Jumped to message #{}", sequence));
+            }
+            None if sequence > last => self.set_status(&format!(
+                "⚠️ Message #{} is past the last loaded message (#{})",
+                sequence, last
+            )),
+            None => self.set_status(&format!(
+                "⚠️ Message #{} not found (range #{}-#{})",
+                sequence, first, last
+            )),
+        }
+    }
+
     fn scan_npm_packages(&mut self) {
         // Don't start another scan if one is already running
         if self.npm_scan_thread.is_some() {
@@ -1190,118 +2558,186 @@ impl CursorStudio {
     /// - [ ] Add option to include/exclude tool calls
     /// - [ ] Show export progress for large conversations
     fn export_conversation_to_markdown(&mut self, conv_id: &str) {
-        // Get conversation info
-        let conv = match self.conversations.iter().find(|c| c.id == conv_id) {
-            Some(c) => c.clone(),
-            None => {
+        let Some(template) = self.load_export_template() else {
+            return;
+        };
+        match self.write_conversation_markdown(conv_id, &markdown_export_dir(), template.as_ref()) {
+            Ok(export_path) => {
+                self.set_status(&format!("✓ Exported to {}", export_path.display()));
+                self.last_export = Some(LastExport::file(export_path));
+            }
+            Err(e) => {
+                self.set_status(&format!("✗ {}", e));
+            }
+        }
+    }
+
+    /// Copy the full structured conversation (messages, roles, tool calls,
+    /// thinking, timestamps) to the clipboard as pretty JSON for bug reports
+    fn copy_conversation_json(&mut self, ctx: &egui::Context, conv_id: &str) {
+        let conv = match self.db.get_conversation(conv_id) {
+            Ok(Some(conv)) => conv,
+            Ok(None) => {
                 self.set_status("✗ Conversation not found");
                 return;
             }
+            Err(e) => {
+                self.set_status(&format!("✗ Failed to load conversation: {}", e));
+                return;
+            }
         };
-
-        // Get messages
         let messages = match self.db.get_messages(conv_id) {
-            Ok(m) => m,
+            Ok(messages) => messages,
             Err(e) => {
                 self.set_status(&format!("✗ Failed to load messages: {}", e));
                 return;
             }
         };
 
-        // Build markdown content
-        let mut md = String::new();
-
-        // Header
-        md.push_str(&format!("# {}\n\n", conv.title));
-        md.push_str(&format!(
-            "**Exported:** {}\n",
-            chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
-        ));
-        md.push_str(&format!("**Messages:** {}\n", messages.len()));
-        md.push_str(&format!("**Source:** Cursor Studio v0.2.1\n\n"));
-        md.push_str("---\n\n");
-
-        for msg in &messages {
-            // Role header
-            let role_icon = match msg.role {
-                MessageRole::User => "👤 **USER**",
-                MessageRole::Assistant => "🤖 **ASSISTANT**",
-                MessageRole::ToolCall => "🔧 **TOOL CALL**",
-                MessageRole::ToolResult => "📋 **TOOL RESULT**",
-            };
-            md.push_str(&format!("### {}\n\n", role_icon));
+        let count = messages.len();
+        let payload = database::ConversationJson {
+            conversation: conv,
+            messages,
+        };
+        match serde_json::to_string_pretty(&payload) {
+            Ok(json) => {
+                ctx.copy_text(json);
+                self.set_status(&format!(
+                    "⚠️ Copied {} messages as JSON - review for secrets before sharing",
+                    count
+                ));
+            }
+            Err(e) => self.set_status(&format!("✗ Failed to serialize conversation: {}", e)),
+        }
+    }
 
-            // Tool call info
-            if let Some(ref tc) = msg.tool_call {
-                md.push_str(&format!("> **Tool:** `{}`\n", tc.name));
-                if !tc.args.is_empty() {
-                    // Pretty print args if JSON
-                    let args_display = if let Ok(parsed) =
-                        serde_json::from_str::<serde_json::Value>(&tc.args)
-                    {
-                        serde_json::to_string_pretty(&parsed).unwrap_or_else(|_| tc.args.clone())
-                    } else {
-                        tc.args.clone()
-                    };
-                    md.push_str(&format!(
-                        "> ```json\n> {}\n> ```\n",
-                        args_display.replace('\n', "\n> ")
-                    ));
-                }
-                md.push_str(&format!("> **Status:** {}\n\n", tc.status));
+    /// Every code block of a conversation as one markdown document, with the
+    /// block count; `None` (with the reason in the status bar) if there are none
+    fn conversation_code(&mut self, conv_id: &str, by_language: bool) -> Option<(String, usize)> {
+        let messages = match self.db.get_messages(conv_id) {
+            Ok(messages) => messages,
+            Err(e) => {
+                self.set_status(&format!("✗ Failed to load messages: {}", e));
+                return None;
             }
+        };
+        let contents: Vec<String> = messages.into_iter().map(|m| m.content).collect();
+        let blocks = languages::extract_code_blocks(&contents);
+        if blocks.is_empty() {
+            self.set_status("⚠ No code blocks in this conversation");
+            return None;
+        }
+        let markdown = languages::code_blocks_markdown(&blocks, by_language);
+        Some((markdown, blocks.len()))
+    }
 
-            // Thinking block
-            if let Some(ref thinking) = msg.thinking {
-                if !thinking.is_empty() {
-                    md.push_str("<details>\n<summary>💭 Thinking...</summary>\n\n");
-                    md.push_str(thinking);
-                    md.push_str("\n\n</details>\n\n");
-                }
+    fn copy_conversation_code(&mut self, ctx: &egui::Context, conv_id: &str, by_language: bool) {
+        if let Some((markdown, count)) = self.conversation_code(conv_id, by_language) {
+            ctx.copy_text(markdown);
+            self.set_status(&format!("✓ Copied {} code blocks", count));
+        }
+    }
+
+    fn export_conversation_code(&mut self, conv_id: &str, by_language: bool) {
+        let Some((markdown, count)) = self.conversation_code(conv_id, by_language) else {
+            return;
+        };
+        let title = self
+            .conversations
+            .iter()
+            .find(|c| c.id == conv_id)
+            .map(|c| c.title.clone())
+            .unwrap_or_else(|| "conversation".to_string());
+        let export_dir = markdown_export_dir();
+        let path = export_dir.join(format!("{}-code.md", export_file_stem(&title)));
+        match std::fs::create_dir_all(&export_dir).and_then(|_| std::fs::write(&path, markdown)) {
+            Ok(()) => {
+                self.set_status(&format!(
+                    "✓ Saved {} code blocks to {}",
+                    count,
+                    path.display()
+                ));
+                self.last_export = Some(LastExport::file(path));
             }
+            Err(e) => self.set_status(&format!("✗ Export failed: {}", e)),
+        }
+    }
 
-            // Main content
-            if !msg.content.is_empty() {
-                md.push_str(&msg.content);
-                md.push_str("\n");
+    /// Copy a context prompt for resuming a conversation in a new Cursor chat.
+    /// With `bookmarked_only`, just the bookmarked messages are included.
+    fn continue_in_cursor(&mut self, ctx: &egui::Context, conv_id: &str, bookmarked_only: bool) {
+        let title = self
+            .conversations
+            .iter()
+            .find(|c| c.id == conv_id)
+            .map(|c| c.title.clone())
+            .unwrap_or_else(|| "Untitled".to_string());
+        let mut messages = match self.db.get_messages(conv_id) {
+            Ok(messages) => messages,
+            Err(e) => {
+                self.set_status(&format!("✗ Failed to load messages: {}", e));
+                return;
             }
+        };
+        if bookmarked_only {
+            let bookmarks = &self.current_bookmarks;
+            messages.retain(|m| bookmarks.iter().any(|b| b.message_id == m.id));
+        }
 
-            md.push_str("\n---\n\n");
+        let prompt = continuation_prompt(&title, &messages);
+        let approx_tokens = prompt.len() / 4;
+        ctx.copy_text(prompt);
+        self.set_status(&format!(
+            "✓ Copied context prompt (~{} tokens) - paste it into a new Cursor chat (Ctrl+L)",
+            approx_tokens
+        ));
+    }
+
+    /// The export template picked in the export dialog, `Some(None)` for the
+    /// default layout; `None` (with the error in the status bar) if it's broken
+    fn load_export_template(&mut self) -> Option<Option<export_templates::Template>> {
+        match export_templates::load(&self.export_template) {
+            Ok(template) => Some(template),
+            Err(e) => {
+                self.set_status(&format!("✗ {:#}", e));
+                None
+            }
         }
+    }
 
-        // Save to file
-        let filename = format!(
-            "{}.md",
-            conv.title
-                .chars()
-                .filter(|c| c.is_alphanumeric() || *c == ' ' || *c == '-')
-                .take(50)
-                .collect::<String>()
-                .trim()
-                .replace(' ', "_")
-        );
+    /// Render a conversation to markdown and write it into `export_dir`
+    fn write_conversation_markdown(
+        &self,
+        conv_id: &str,
+        export_dir: &std::path::Path,
+        template: Option<&export_templates::Template>,
+    ) -> Result<PathBuf, String> {
+        // Get conversation info
+        let conv = self
+            .conversations
+            .iter()
+            .find(|c| c.id == conv_id)
+            .cloned()
+            .ok_or_else(|| "Conversation not found".to_string())?;
+
+        // Get messages
+        let messages = self
+            .db
+            .get_messages(conv_id)
+            .map_err(|e| format!("Failed to load messages: {}", e))?;
+
+        let md = render_markdown(&conv, &messages, template);
 
-        let export_dir = dirs::document_dir()
-            .or_else(|| dirs::home_dir())
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("cursor-studio-exports");
+        // Save to file
+        let filename = format!("{}.md", export_file_stem(&conv.title));
 
         // Create directory if needed
-        if let Err(e) = std::fs::create_dir_all(&export_dir) {
-            self.set_status(&format!("✗ Failed to create export directory: {}", e));
-            return;
-        }
+        std::fs::create_dir_all(export_dir)
+            .map_err(|e| format!("Failed to create export directory: {}", e))?;
 
         let export_path = export_dir.join(&filename);
-
-        match std::fs::write(&export_path, md) {
-            Ok(_) => {
-                self.set_status(&format!("✓ Exported to {}", export_path.display()));
-            }
-            Err(e) => {
-                self.set_status(&format!("✗ Export failed: {}", e));
-            }
-        }
+        std::fs::write(&export_path, md).map_err(|e| format!("Export failed: {}", e))?;
+        Ok(export_path)
     }
 
     fn search_in_conversation(&mut self, query: &str) {
@@ -1395,6 +2831,8 @@ impl CursorStudio {
 
     fn set_status(&mut self, msg: &str) {
         self.status_message = Some(msg.to_string());
+        self.status_history.push_front(StatusEntry::new(msg));
+        self.status_history.truncate(STATUS_HISTORY_MAX);
         log::info!("{}", msg);
     }
 
@@ -1423,89 +2861,256 @@ impl CursorStudio {
         self.do_import_internal(false); // false = don't reattach bookmarks
     }
 
-    fn do_import_internal(&mut self, reattach_bookmarks: bool) {
-        self.import_in_progress = true;
-        self.import_needs_bookmark_reattach = reattach_bookmarks;
-        self.last_import_error = None;
-        self.set_status("⏳ Starting async import...");
-
-        // Create channel for progress updates
-        let (tx, rx) = std::sync::mpsc::channel();
-        self.import_receiver = Some(rx);
+    /// Hide the window's content once there has been no input for the idle timeout
+    fn track_idle_lock(&mut self, ctx: &egui::Context) {
+        if self.idle_locked {
+            return;
+        }
+        if ctx.input(|i| !i.events.is_empty()) {
+            self.last_input = std::time::Instant::now();
+        }
+        if self.idle_lock_mins == 0 {
+            return;
+        }
 
-        // Get database path and spawn import thread
-        let db_path = self.db.get_path();
+        let timeout = std::time::Duration::from_secs(self.idle_lock_mins as u64 * 60);
+        if self.last_input.elapsed() >= timeout {
+            self.idle_locked = true;
+            log::info!("Locked after {} min without input", self.idle_lock_mins);
+        } else {
+            schedule_repaint_at(ctx, self.last_input + timeout);
+        }
+    }
 
-        let _handle = std::thread::spawn(move || -> Result<(usize, usize), String> {
-            // Create new database connection in thread
-            let import_db = match database::ChatDatabase::open(&db_path) {
-                Ok(db) => db,
-                Err(e) => {
-                    let _ = tx.send(ImportProgress::Error(e.to_string()));
-                    return Err(e.to_string());
-                }
-            };
+    /// Full-window cover shown instead of the panels while idle-locked
+    fn show_idle_lock(&mut self, ctx: &egui::Context, theme: Theme) {
+        let mut unlock = false;
+        egui::CentralPanel::default()
+            .frame(egui::Frame::none().fill(theme.bg))
+            .show(ctx, |ui| {
+                let cover = ui.interact(
+                    ui.max_rect(),
+                    egui::Id::new("idle_lock"),
+                    egui::Sense::click(),
+                );
+                ui.vertical_centered(|ui| {
+                    ui.add_space(ui.available_height() / 2.0 - 40.0);
+                    ui.label(RichText::new("🔒").size(32.0));
+                    ui.add_space(ELEMENT_SPACING);
+                    ui.label(
+                        RichText::new("Locked after inactivity")
+                            .color(theme.fg_bright)
+                            .size(16.0),
+                    );
+                    ui.label(
+                        RichText::new("Click anywhere to resume")
+                            .color(theme.fg_dim)
+                            .size(theme.text_size(11.0)),
+                    );
+                });
+                if cover.hovered() {
+                    ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+                }
+                unlock = cover.clicked();
+            });
 
-            // Find all Cursor databases to import
-            let home = match dirs::home_dir() {
-                Some(h) => h,
-                None => {
-                    let _ = tx.send(ImportProgress::Error("No home directory".to_string()));
-                    return Err("No home directory".to_string());
+        if unlock {
+            self.idle_locked = false;
+            self.last_input = std::time::Instant::now();
+        }
+    }
+
+    fn handle_privacy_hotkey(&mut self, ctx: &egui::Context) {
+        let Some(chord) = hotkey::parse_shortcut(&self.privacy_hotkey) else {
+            return;
+        };
+        // Consumed here so the key never reaches a focused text field
+        if ctx.input_mut(|i| i.consume_shortcut(&chord)) {
+            self.privacy_screen = !self.privacy_screen;
+        }
+    }
+
+    /// Neutral splash covering every panel until the privacy hotkey is pressed again
+    fn show_privacy_screen(&mut self, ctx: &egui::Context, theme: Theme) {
+        egui::CentralPanel::default()
+            .frame(egui::Frame::none().fill(theme.bg))
+            .show(ctx, |ui| {
+                ui.vertical_centered(|ui| {
+                    ui.add_space(ui.available_height() / 2.0 - 20.0);
+                    ui.label(
+                        RichText::new("Cursor Studio")
+                            .color(theme.fg_dim)
+                            .size(18.0),
+                    );
+                });
+            });
+    }
+
+    /// Run the incremental import when the auto-import interval has elapsed
+    fn maybe_auto_import(&mut self, ctx: &egui::Context) {
+        if !self.auto_import_enabled {
+            return;
+        }
+
+        let interval = std::time::Duration::from_secs(self.auto_import_interval_mins as u64 * 60);
+        if self.auto_import_timer.elapsed() < interval {
+            schedule_repaint_at(ctx, self.auto_import_timer + interval);
+            return;
+        }
+        if self.import_in_progress {
+            return;
+        }
+
+        self.auto_import_timer = std::time::Instant::now();
+        self.last_auto_import = Some(chrono::Local::now().to_rfc3339());
+        log::info!("Auto-import triggered");
+        self.do_import_internal(false);
+    }
+
+    /// Dry-run: count what an import would bring in without writing anything.
+    /// The Cursor databases are scanned in the background.
+    fn preview_import(&mut self) {
+        if self.import_preview_receiver.is_some() {
+            return;
+        }
+        self.set_status("🔍 Scanning Cursor databases...");
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.import_preview_receiver = Some(rx);
+        let db_path = self.db.get_path();
+        std::thread::spawn(move || match database::ChatDatabase::open(&db_path) {
+            Ok(db) => {
+                let _ = tx.send(db.preview_import_all());
+            }
+            Err(e) => log::warn!("Import preview failed to open the library: {}", e),
+        });
+    }
+
+    /// Pick up a finished import preview
+    fn poll_import_preview(&mut self, ctx: &egui::Context) {
+        let Some(rx) = &self.import_preview_receiver else {
+            return;
+        };
+        let previews = match rx.try_recv() {
+            Ok(previews) => previews,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                self.import_preview_receiver = None;
+                self.set_status("✗ Failed to preview the import");
+                return;
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => {
+                ctx.request_repaint_after(std::time::Duration::from_millis(100));
+                return;
+            }
+        };
+        self.import_preview_receiver = None;
+        if previews.is_empty() {
+            self.set_status("⚠️ No Cursor databases found to import from");
+        } else {
+            let new_convs: usize = previews.iter().map(|p| p.new_conversations).sum();
+            self.set_status(&format!(
+                "🔍 Preview: {} new conversations across {} database(s)",
+                new_convs,
+                previews.len()
+            ));
+        }
+        self.import_preview = Some(previews);
+    }
+
+    fn do_import_internal(&mut self, reattach_bookmarks: bool) {
+        self.import_in_progress = true;
+        self.import_needs_bookmark_reattach = reattach_bookmarks;
+        self.last_import_error = None;
+        self.set_status("⏳ Starting async import...");
+
+        // Create channel for progress updates
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.import_receiver = Some(rx);
+
+        // Get database path and spawn import thread
+        let db_path = self.db.get_path();
+        self.import_pause = Default::default();
+        self.import_paused = false;
+        let pause = self.import_pause.clone();
+
+        let _handle = std::thread::spawn(move || -> Result<(usize, usize), String> {
+            // Create new database connection in thread
+            let import_db = match database::ChatDatabase::open(&db_path) {
+                Ok(db) => db,
+                Err(e) => {
+                    let _ = tx.send(ImportProgress::Error(e.to_string()));
+                    return Err(e.to_string());
                 }
             };
 
-            let mut db_paths: Vec<(std::path::PathBuf, String)> = Vec::new();
-
-            // Main Cursor database
-            let main_db = home.join(".config/Cursor/User/globalStorage/state.vscdb");
-            if main_db.exists() {
-                db_paths.push((main_db, "default".to_string()));
-            }
-
-            // Versioned Cursor databases
-            if let Ok(entries) = std::fs::read_dir(&home) {
-                for entry in entries.flatten() {
-                    let name = entry.file_name().to_string_lossy().to_string();
-                    if name.starts_with(".cursor-") {
-                        if let Ok(ft) = entry.file_type() {
-                            if ft.is_dir() {
-                                let version =
-                                    name.strip_prefix(".cursor-").unwrap_or(&name).to_string();
-                                let db_path = entry.path().join("User/globalStorage/state.vscdb");
-                                if db_path.exists() {
-                                    db_paths.push((db_path, version));
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+            // Find all Cursor databases to import
+            let db_paths = database::discover_cursor_databases();
 
             let total = db_paths.len();
             let _ = tx.send(ImportProgress::Started(total));
 
             let mut total_imported = 0;
             let mut total_skipped = 0;
+            let mut total_duplicates = 0;
+            let mut added = Vec::new();
+            let mut damaged = database::CursorImport::default();
+            let mut locked = Vec::new();
 
             for (idx, (path, version)) in db_paths.into_iter().enumerate() {
                 let _ = tx.send(ImportProgress::Processing(idx + 1, total));
 
                 match import_db.import_from_cursor(path, &version) {
-                    Ok((imported, skipped)) => {
-                        total_imported += imported;
-                        total_skipped += skipped;
+                    Ok(report) => {
+                        total_imported += report.imported;
+                        total_skipped += report.skipped;
+                        total_duplicates += report.duplicates;
+                        added.extend_from_slice(&report.imported_ids);
+                        if report.is_damaged() {
+                            damaged.imported += report.imported;
+                            damaged.lost_conversations += report.lost_conversations;
+                            damaged.lost_messages += report.lost_messages;
+                        }
+                        if report.locked {
+                            locked.push(version);
+                        }
                     }
                     Err(e) => {
                         log::warn!("Failed to import {}: {}", version, e);
+                        let _ = tx.send(ImportProgress::SourceFailed(version, e.to_string()));
                         // Continue with other imports
                     }
                 }
 
                 // Small delay to keep UI responsive
                 std::thread::sleep(std::time::Duration::from_millis(10));
+
+                // Everything so far is committed, so pausing here loses nothing
+                if pause.load(Ordering::Relaxed) && idx + 1 < total {
+                    let _ = tx.send(ImportProgress::Paused(idx + 1, total));
+                    while pause.load(Ordering::Relaxed) {
+                        std::thread::sleep(std::time::Duration::from_millis(200));
+                    }
+                    let _ = tx.send(ImportProgress::Resumed);
+                }
+            }
+
+            match import_db.tag_unscanned_languages() {
+                Ok(tagged) => log::info!("Tagged languages on {} conversations", tagged),
+                Err(e) => log::warn!("Language tagging failed: {}", e),
             }
 
+            if damaged.is_damaged() {
+                let _ = tx.send(ImportProgress::Salvaged(damaged));
+            }
+            if total_duplicates > 0 {
+                let _ = tx.send(ImportProgress::Deduplicated(total_duplicates));
+            }
+            if !added.is_empty() {
+                let _ = tx.send(ImportProgress::Added(added));
+            }
+            if !locked.is_empty() {
+                let _ = tx.send(ImportProgress::Locked(locked));
+            }
             let _ = tx.send(ImportProgress::Completed(total_imported, total_skipped));
             Ok((total_imported, total_skipped))
         });
@@ -1513,6 +3118,94 @@ impl CursorStudio {
         // Note: We don't store the handle since we track completion via channel
     }
 
+    /// Pause the running import after its current database, or resume it
+    fn toggle_import_pause(&mut self) {
+        if self.import_pause.load(Ordering::Relaxed) {
+            self.import_pause.store(false, Ordering::Relaxed);
+            self.import_paused = false;
+            self.set_status("▶ Import resumed");
+        } else {
+            self.import_pause.store(true, Ordering::Relaxed);
+            self.set_status("⏸ Pausing import after the current database...");
+        }
+    }
+
+    /// Import a ChatGPT/Claude export or a `.cursor-studio.db` in the background,
+    /// reporting like a Cursor import
+    fn do_import_external(&mut self) {
+        if self.import_in_progress {
+            self.set_status("⏳ Import already in progress...");
+            return;
+        }
+        let path = expand_home(self.external_import_path.trim());
+        if !path.exists() {
+            self.set_status(&format!("✗ Not found: {}", path.display()));
+            return;
+        }
+
+        self.import_in_progress = true;
+        self.import_needs_bookmark_reattach = false;
+        self.last_import_error = None;
+        self.show_external_import = false;
+        self.set_status("⏳ Importing external chats...");
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.import_receiver = Some(rx);
+        let db_path = self.db.get_path();
+
+        std::thread::spawn(move || {
+            let _ = tx.send(ImportProgress::Started(1));
+            let is_sqlite = path.extension().is_some_and(|ext| ext == "db");
+            let result = database::ChatDatabase::open(&db_path).and_then(|db| {
+                let counts = if is_sqlite {
+                    db.import_from_sqlite(&path)?
+                } else {
+                    let (source, imported, skipped) = db.import_chat_export(&path)?;
+                    log::info!("Imported {} {} conversations", imported, source.label());
+                    (imported, skipped)
+                };
+                if let Err(e) = db.tag_unscanned_languages() {
+                    log::warn!("Language tagging failed: {}", e);
+                }
+                Ok(counts)
+            });
+            match result {
+                Ok((imported, skipped)) => {
+                    let _ = tx.send(ImportProgress::Completed(imported, skipped));
+                }
+                Err(e) => {
+                    let _ = tx.send(ImportProgress::Error(format!("{:#}", e)));
+                }
+            }
+        });
+    }
+
+    /// Insert the conversation pasted into the paste dialog and open it
+    fn import_pasted_conversation(&mut self) {
+        match self
+            .db
+            .import_conversation_json(&self.paste_conversation_input)
+        {
+            Ok((conv_id, is_new)) => {
+                self.show_paste_conversation = false;
+                self.paste_conversation_input.clear();
+                self.paste_conversation_error = None;
+                if is_new {
+                    self.reload_conversations();
+                    self.refresh_version_chat_counts();
+                    self.set_status("✓ Imported pasted conversation");
+                } else {
+                    self.set_status("Pasted conversation is already in the library");
+                }
+                self.open_conversation(&conv_id);
+            }
+            Err(e) => {
+                self.paste_conversation_error = Some(format!("{:#}", e));
+                self.set_status("✗ Pasted text is not a valid conversation");
+            }
+        }
+    }
+
     fn do_sync(&mut self) {
         self.set_status("⏳ Syncing settings across versions...");
 
@@ -1539,6 +3232,12 @@ impl CursorStudio {
     }
 
     fn apply_theme(&mut self, theme_name: &str, theme_path: Option<&PathBuf>) {
+        // A manual pick takes over from Cursor's theme
+        if self.follow_cursor_theme {
+            self.follow_cursor_theme = false;
+            self.followed_cursor_theme = None;
+            self.save_settings();
+        }
         self.current_theme_name = theme_name.to_string();
 
         if theme_name.contains("Light") {
@@ -1552,12 +3251,34 @@ impl CursorStudio {
                 return;
             }
 
-            match Theme::from_vscode_file(path) {
-                Some(loaded_theme) => {
-                    self.theme = loaded_theme;
+            match self.theme_cache.load(path) {
+                Some(load) => {
+                    self.theme = load.theme;
                     // Recompute selected colors for proper contrast
                     self.theme.compute_selected_colors();
-                    self.set_status(&format!("✓ Applied theme: {}", theme_name));
+                    if load.fallback_count() == 0 {
+                        self.set_status(&format!("✓ Applied theme: {}", theme_name));
+                    } else {
+                        if !load.missing.is_empty() {
+                            log::info!(
+                                "Theme {} is missing colors: {:?}",
+                                theme_name,
+                                load.missing
+                            );
+                        }
+                        if !load.invalid.is_empty() {
+                            log::warn!(
+                                "Theme {} has unparseable colors: {:?}",
+                                theme_name,
+                                load.invalid
+                            );
+                        }
+                        self.set_status(&format!(
+                            "⚠ Loaded {} with {} missing colors (using defaults)",
+                            theme_name,
+                            load.fallback_count()
+                        ));
+                    }
                 }
                 None => {
                     log::warn!("Failed to parse theme: {:?}", path);
@@ -1574,11 +3295,185 @@ impl CursorStudio {
             self.set_status(&format!("✓ Applied dark theme: {}", theme_name));
         }
 
+        let _ = self.db.set_config("ui.theme", theme_name);
         self.show_theme_picker = false;
         // Force repaint to apply new theme immediately
         // (handled by egui automatically)
     }
 
+    /// Re-apply the theme saved by `apply_theme`, without a status message
+    fn restore_saved_theme(&mut self) {
+        let Some(name) = self.db.get_config("ui.theme") else {
+            return;
+        };
+        let path = self
+            .available_themes
+            .iter()
+            .find(|(theme_name, _)| *theme_name == name)
+            .and_then(|(_, path)| path.clone());
+        if let Some(theme) = self.resolve_theme(&name, path.as_ref()) {
+            self.theme = theme;
+            self.current_theme_name = name;
+        }
+    }
+
+    /// settings.json of the Cursor the launch button starts
+    fn cursor_settings_path(&self) -> Option<PathBuf> {
+        let home = dirs::home_dir()?;
+        Some(if self.launch_version == "default" {
+            home.join(".config/Cursor/User/settings.json")
+        } else {
+            home.join(format!(
+                ".cursor-{}/User/settings.json",
+                self.launch_version
+            ))
+        })
+    }
+
+    /// Re-read Cursor's selected theme when its settings.json changes and apply it
+    fn poll_cursor_theme(&mut self, ctx: &egui::Context) {
+        if !self.follow_cursor_theme {
+            return;
+        }
+        if self
+            .cursor_theme_checked
+            .is_some_and(|checked| checked.elapsed() < CURSOR_THEME_POLL_INTERVAL)
+        {
+            return;
+        }
+        self.cursor_theme_checked = Some(std::time::Instant::now());
+        ctx.request_repaint_after(CURSOR_THEME_POLL_INTERVAL);
+
+        let path = self.cursor_settings_path();
+        let mtime = path
+            .as_ref()
+            .and_then(|p| std::fs::metadata(p).ok())
+            .and_then(|meta| meta.modified().ok());
+        if mtime.is_some() && mtime == self.cursor_settings_mtime {
+            return;
+        }
+        self.cursor_settings_mtime = mtime;
+
+        let detected = path
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|content| color_theme_setting(&content));
+        if self.followed_cursor_theme.as_ref() == Some(&detected) {
+            return;
+        }
+        self.followed_cursor_theme = Some(detected.clone());
+        match detected {
+            Some(name) => self.apply_cursor_theme(&name),
+            None => self.set_status(&format!(
+                "⚠ Couldn't detect Cursor's theme, keeping {} (pick one manually)",
+                self.current_theme_name
+            )),
+        }
+    }
+
+    /// Apply the theme Cursor reports, by label or else by its light/dark family
+    fn apply_cursor_theme(&mut self, name: &str) {
+        let find = |themes: &[(String, Option<PathBuf>)]| {
+            themes.iter().find(|(label, _)| label == name).cloned()
+        };
+        let mut entry = find(&self.available_themes);
+        if entry.is_none() {
+            // The theme may come from an extension installed since the last scan
+            self.available_themes = Self::find_vscode_themes();
+            Self::save_theme_index(&self.db, &self.available_themes);
+            entry = find(&self.available_themes);
+        }
+        let entry = entry.or_else(|| {
+            // Built-in Cursor/VS Code themes have no extension file to load
+            let builtin = if name.contains("Light") {
+                "Light+ (default light)"
+            } else if name.contains("Dark") {
+                "Dark+ (default dark)"
+            } else {
+                return None;
+            };
+            Some((builtin.to_string(), None))
+        });
+
+        let Some((label, path)) = entry else {
+            self.set_status(&format!(
+                "⚠ Cursor theme {} not found, keeping {} (pick one manually)",
+                name, self.current_theme_name
+            ));
+            return;
+        };
+        match self.resolve_theme(&label, path.as_ref()) {
+            Some(theme) => {
+                self.theme = theme;
+                self.current_theme_name = label;
+                self.set_status(&format!("✓ Following Cursor theme: {}", name));
+            }
+            None => self.set_status(&format!(
+                "⚠ Couldn't load Cursor theme {}, keeping {}",
+                name, self.current_theme_name
+            )),
+        }
+    }
+
+    /// Resolve a picker entry to a theme (parsed files come from the cache)
+    fn resolve_theme(&mut self, theme_name: &str, theme_path: Option<&PathBuf>) -> Option<Theme> {
+        if theme_name.contains("Light") {
+            return Some(Theme::light());
+        }
+        match theme_path {
+            Some(path) => {
+                let mut theme = self.theme_cache.load(path)?.theme;
+                theme.compute_selected_colors();
+                Some(theme)
+            }
+            None => Some(Theme::dark()),
+        }
+    }
+
+    /// Preview the theme hovered in the picker once the pointer has rested on
+    /// it for `THEME_PREVIEW_DELAY`; revert as soon as the hover ends
+    fn update_theme_preview(&mut self, ctx: &egui::Context) {
+        let hovered = match &self.hovered_theme {
+            Some(name) if self.show_theme_picker && *name != self.current_theme_name => {
+                name.clone()
+            }
+            _ => {
+                self.theme_hover_since = None;
+                self.theme_preview = None;
+                return;
+            }
+        };
+        if self
+            .theme_preview
+            .as_ref()
+            .is_some_and(|(name, _)| *name == hovered)
+        {
+            return;
+        }
+
+        match &self.theme_hover_since {
+            Some((name, since)) if *name == hovered => {
+                let elapsed = since.elapsed();
+                if elapsed < THEME_PREVIEW_DELAY {
+                    ctx.request_repaint_after(THEME_PREVIEW_DELAY - elapsed);
+                    return;
+                }
+            }
+            _ => {
+                self.theme_hover_since = Some((hovered, std::time::Instant::now()));
+                ctx.request_repaint_after(THEME_PREVIEW_DELAY);
+                return;
+            }
+        }
+
+        let path = self
+            .available_themes
+            .iter()
+            .find(|(name, _)| *name == hovered)
+            .and_then(|(_, path)| path.clone());
+        let preview = self.resolve_theme(&hovered, path.as_ref());
+        self.theme_preview = Some((hovered, preview));
+    }
+
     fn set_default_version(&mut self, version: &str) {
         self.default_version = version.to_string();
         // Also update launch version to match new default
@@ -1586,7 +3481,7 @@ impl CursorStudio {
         let display_name = Self::version_display_name(version);
         self.set_status(&format!("✓ Set default version: {}", display_name));
         self.show_version_picker = false;
-        // TODO: Persist this choice to config file
+        let _ = self.db.set_config("ui.default_version", version);
     }
 
     fn set_launch_version(&mut self, version: &str) {
@@ -1596,20 +3491,32 @@ impl CursorStudio {
         self.show_launch_picker = false;
     }
 
+    /// Pin or unpin a version for quick launching and persist the set
+    fn toggle_favorite_version(&mut self, version: &str) {
+        if self.favorite_versions.iter().any(|v| v == version) {
+            self.favorite_versions.retain(|v| v != version);
+        } else {
+            self.favorite_versions.push(version.to_string());
+        }
+        if let Ok(json) = serde_json::to_string(&self.favorite_versions) {
+            let _ = self.db.set_config("ui.favorite_versions", &json);
+        }
+    }
+
     /// Remove an installed version (cleanup)
     fn remove_version(&mut self, version: &str) -> Result<(), String> {
         use std::fs;
-        let home = dirs::home_dir().ok_or("No home directory")?;
-        let paths_to_remove = [
-            home.join(format!(".cursor-{}", version)),
-            home.join(format!(".cursor-studio/versions/cursor-{}", version)),
-            home.join(format!(".cache/cursor-{}", version)),
-        ];
+        let paths = versions::VersionPaths::for_version(version).ok_or("No home directory")?;
         let mut removed_any = false;
         let mut errors = Vec::new();
-        for path in &paths_to_remove {
+        for path in paths.all() {
             if path.exists() {
-                match fs::remove_dir_all(path) {
+                let result = if path.is_dir() {
+                    fs::remove_dir_all(path)
+                } else {
+                    fs::remove_file(path)
+                };
+                match result {
                     Ok(_) => {
                         log::info!("Removed: {:?}", path);
                         removed_any = true;
@@ -1643,9 +3550,22 @@ impl CursorStudio {
         let display_name = Self::version_display_name(version);
 
         // Determine the command to run based on version
-        let result = if version == "default" {
+        let registered = self
+            .db
+            .registered_installs()
+            .unwrap_or_default()
+            .into_iter()
+            .find(|i| &i.version == version && i.appimage.as_ref().is_some_and(|p| p.exists()));
+
+        let mut command = if version == "default" {
             // Launch main Cursor installation
-            Command::new("cursor").spawn()
+            Command::new("cursor")
+        } else if let Some(install) = registered.and_then(|i| Some((i.appimage?, i.data_dir))) {
+            // Registered from "Detect installations"
+            log::info!("Launching registered AppImage: {:?}", install.0);
+            let mut cmd = Command::new(&install.0);
+            cmd.arg("--user-data-dir").arg(&install.1);
+            cmd
         } else {
             // Try to find version-specific installation
             if let Some(home) = dirs::home_dir() {
@@ -1672,34 +3592,37 @@ impl CursorStudio {
 
                 if studio_path.exists() {
                     log::info!("Launching from cursor-studio: {:?}", studio_path);
-                    Command::new(&studio_path)
-                        .arg("--user-data-dir")
-                        .arg(home.join(format!(".cursor-{}", version)))
-                        .spawn()
+                    let mut cmd = Command::new(&studio_path);
+                    cmd.arg("--user-data-dir")
+                        .arg(home.join(format!(".cursor-{}", version)));
+                    cmd
                 } else if which::which(&nix_binary).is_ok() {
                     log::info!("Launching Nix binary: {}", nix_binary);
-                    Command::new(&nix_binary).spawn()
+                    Command::new(&nix_binary)
                 } else if versioned_path.exists() {
                     log::info!("Launching from versioned path: {:?}", versioned_path);
-                    Command::new(&versioned_path).spawn()
+                    Command::new(&versioned_path)
                 } else if appimage_path.exists() {
                     log::info!("Launching from AppImage: {:?}", appimage_path);
-                    Command::new(&appimage_path)
-                        .arg("--user-data-dir")
-                        .arg(home.join(format!(".cursor-{}", version)))
-                        .spawn()
+                    let mut cmd = Command::new(&appimage_path);
+                    cmd.arg("--user-data-dir")
+                        .arg(home.join(format!(".cursor-{}", version)));
+                    cmd
                 } else {
                     // Fall back to main cursor with env var hint
                     log::warn!("No installation found for v{}, falling back to main cursor", version);
-                    Command::new("cursor")
-                        .env("CURSOR_VERSION", version)
-                        .spawn()
+                    let mut cmd = Command::new("cursor");
+                    cmd.env("CURSOR_VERSION", version);
+                    cmd
                 }
             } else {
-                Command::new("cursor").spawn()
+                Command::new("cursor")
             }
         };
 
+        // User-configured variables are merged onto the inherited environment
+        let result = command.envs(launch_env_vars(&self.launch_env)).spawn();
+
         match result {
             Ok(_) => self.set_status(&format!("✓ Launching {}...", display_name)),
             Err(e) => self.set_status(&format!("✗ Failed to launch: {}", e)),
@@ -1767,9 +3690,12 @@ impl CursorStudio {
 
     /// Start downloading a version in the background
     fn start_download(&mut self, version: &str) {
-        // Check if already downloading
-        if matches!(self.download_state, DownloadState::Downloading { .. }) {
-            self.set_status("⏳ A download is already in progress");
+        if self.is_downloading(version) || self.download_queue.iter().any(|v| v == version) {
+            self.set_status(&format!("⏳ v{} is already downloading", version));
+            return;
+        }
+        if self.cache_move_receiver.is_some() {
+            self.set_status("⏳ Wait for the download cache to finish moving");
             return;
         }
 
@@ -1785,39 +3711,31 @@ impl CursorStudio {
             }
         };
 
+        // Refuse downloads that would blow the storage budget
+        let size_estimate = version_info.size_bytes.unwrap_or(150_000_000); // ~150MB estimate
+        if !self.check_storage_budget(size_estimate) {
+            return;
+        }
+
         // Request approval
         let operation = ApprovalOperation::Download {
             version: version.to_string(),
-            size_estimate: Some(150_000_000), // ~150MB estimate
+            size_estimate: Some(size_estimate),
         };
 
         let result = self.approval_manager.request(operation);
         match result {
             ApprovalResult::Approved => {
-                // Proceed with download
-                self.set_status(&format!("⏳ Starting download of v{}...", version));
-                self.download_state = DownloadState::Downloading {
-                    progress: 0.0,
-                    version: version.to_string(),
-                };
-
-                // Create progress channel
-                let (tx, rx) = std::sync::mpsc::channel();
-                self.download_receiver = Some(rx);
-
-                // Clone data for thread
-                let version_clone = version_info.clone();
-                let cache_dir = versions::get_cache_dir();
-
-                // Spawn download thread
-                let handle = std::thread::spawn(move || -> Result<PathBuf, String> {
-                    versions::download_version_sync(&version_clone, &cache_dir, move |progress| {
-                        let _ = tx.send(progress);
-                    })
-                    .map_err(|e| e.to_string())
-                });
-
-                self.download_thread = Some(handle);
+                if self.active_downloads.len() < self.max_cpu_threads.max(1) {
+                    self.begin_download(version_info);
+                } else {
+                    self.download_queue.push_back(version.to_string());
+                    self.set_status(&format!(
+                        "⏳ Queued v{} ({} downloads running)",
+                        version,
+                        self.active_downloads.len()
+                    ));
+                }
             }
             ApprovalResult::Denied => {
                 // First click - show confirmation message
@@ -1839,4988 +3757,11414 @@ impl CursorStudio {
         }
     }
 
-    /// Check download progress and handle completion
-    fn check_download_progress(&mut self) {
-        // Check for progress updates
-        if let Some(ref rx) = self.download_receiver {
-            while let Ok(progress) = rx.try_recv() {
-                self.download_progress = Some(progress);
-                if let DownloadState::Downloading { version, .. } = &self.download_state {
-                    self.download_state = DownloadState::Downloading {
-                        progress,
-                        version: version.clone(),
-                    };
-                }
-            }
-        }
+    /// Whether `version` is downloading (or resuming) right now
+    fn is_downloading(&self, version: &str) -> bool {
+        self.download_state_of(version).active_version().is_some()
+    }
 
-        // Check if thread completed
-        if let Some(handle) = self.download_thread.take() {
-            if handle.is_finished() {
-                match handle.join() {
-                    Ok(Ok(path)) => {
-                        if let DownloadState::Downloading { version, .. } = &self.download_state {
-                            let version = version.clone();
+    /// Download state of `version`; `Idle` unless it's running or has failed
+    fn download_state_of(&self, version: &str) -> &DownloadState {
+        self.download_state.get(version).unwrap_or(&DownloadState::Idle)
+    }
 
-                            // Verify hash if available
-                            let hash_status = if let Some(version_info) = get_version_info(&version)
-                            {
-                                if let Some(ref expected_hash) = version_info.sha256_hash {
-                                    match versions::verify_hash(&path, expected_hash) {
-                                        Ok(true) => Some("✓ Hash verified"),
-                                        Ok(false) => {
-                                            // Hash mismatch - delete file and fail
-                                            let _ = std::fs::remove_file(&path);
-                                            self.download_state = DownloadState::Failed {
-                                                version: version.clone(),
-                                                error: "Hash verification failed - file deleted"
-                                                    .to_string(),
-                                            };
-                                            self.set_status("✗ Hash verification failed - downloaded file was corrupted");
-                                            self.download_receiver = None;
-                                            self.download_progress = None;
-                                            return;
-                                        }
-                                        Err(e) => {
-                                            log::warn!("Hash verification error: {}", e);
-                                            Some("⚠ Hash verification error")
-                                        }
-                                    }
-                                } else {
-                                    Some("⚠ No hash available")
-                                }
-                            } else {
-                                None
-                            };
+    /// Open the details card for a version, or close it if already open
+    fn toggle_version_details(&mut self, version: &str) {
+        if self
+            .version_details
+            .as_ref()
+            .is_some_and(|d| d.version == version)
+        {
+            self.version_details = None;
+            return;
+        }
 
-                            // Install the downloaded AppImage to the proper location
-                            match versions::install_version(&path, &version) {
-                                Ok(installed_path) => {
-                                    log::info!("Installed v{} to {:?}", version, installed_path);
-                                    
-                                    // Create user data directory for this version if it doesn't exist
-                                    if let Some(home) = dirs::home_dir() {
-                                        let data_dir = home.join(format!(".cursor-{}", version));
-                                        if !data_dir.exists() {
-                                            if let Err(e) = std::fs::create_dir_all(&data_dir) {
-                                                log::warn!("Failed to create data dir: {}", e);
-                                            }
-                                        }
-                                    }
-                                    
-                                    // Refresh versions list to show the new installation
-                                    // (get_versions scans the filesystem, so it will find the new install)
-                                    self.versions = self.db.get_versions().unwrap_or_default();
-                                    
-                                    // Also refresh available_versions to update installed status
-                                    self.available_versions = get_available_versions();
-                                    
-                                    self.download_state = DownloadState::Completed {
-                                        version: version.clone(),
-                                        path: installed_path,
-                                    };
+        let appimage = versions::get_installed_appimage(version);
+        let installed_size = appimage
+            .as_ref()
+            .and_then(|path| std::fs::metadata(path).ok())
+            .map(|meta| meta.len());
+        let data_dir = self
+            .versions
+            .iter()
+            .find(|v| v.version == version)
+            .map(|v| v.path.clone());
+        self.version_details = Some(VersionDetails {
+            version: version.to_string(),
+            info: get_version_info(version),
+            data_dir,
+            appimage,
+            installed_size,
+        });
+    }
 
-                                    let status_msg = match hash_status {
-                                        Some(hash_msg) => {
-                                            format!("✓ Installed v{} - {}", version, hash_msg)
-                                        }
-                                        None => format!("✓ Installed v{}", version),
-                                    };
-                                    self.set_status(&status_msg);
+    /// Registry metadata and on-disk state for the selected version
+    fn show_version_details(&mut self, ui: &mut egui::Ui, theme: Theme) {
+        let density = self.density;
+        let Some(details) = &self.version_details else {
+            return;
+        };
+        let mut close = false;
+        let mut copied: Option<&str> = None;
+
+        let row = |ui: &mut egui::Ui, label: &str, value: &str| {
+            ui.horizontal(|ui| {
+                ui.label(RichText::new(label).color(theme.fg_dim).size(theme.text_size(10.0)));
+                ui.label(RichText::new(value).color(theme.fg).size(theme.text_size(10.0)));
+            });
+        };
+
+        ui.horizontal(|ui| {
+            ui.add_space(8.0);
+            accent_card_frame(theme, density).show(ui, |ui| {
+                ui.set_width(ui.available_width() - 8.0);
+                ui.horizontal(|ui| {
+                    ui.label(
+                        RichText::new(Self::version_display_name(&details.version))
+                            .color(theme.fg_bright)
+                            .strong()
+                            .size(13.0),
+                    );
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        let close_btn = ui
+                            .add(
+                                egui::Button::new(RichText::new("✕").size(theme.text_size(10.0)))
+                                    .frame(false),
+                            )
+                            .on_hover_text("Close details");
+                        accessible_label(ui, &close_btn, "Close details");
+                        if close_btn.clicked() {
+                            close = true;
+                        }
+                    });
+                });
+                ui.add_space(TIGHT_SPACING);
+
+                let installed = details.data_dir.is_some() || details.appimage.is_some();
+                row(ui, "Installed:", if installed { "yes" } else { "no" });
+                if let Some(dir) = &details.data_dir {
+                    row(ui, "Data:", &dir.display().to_string());
+                }
+                if let Some(path) = &details.appimage {
+                    row(ui, "AppImage:", &path.display().to_string());
+                }
+                if let Some(size) = details.installed_size {
+                    row(ui, "Size:", &versions::format_size(size));
+                }
+
+                match &details.info {
+                    Some(info) => {
+                        row(
+                            ui,
+                            "Released:",
+                            info.release_date.as_deref().unwrap_or("unknown"),
+                        );
+                        let channel = if info.is_stable {
+                            "stable"
+                        } else {
+                            "pre-release"
+                        };
+                        row(ui, "Channel:", channel);
+                        if let Some(commit) = &info.commit_hash {
+                            row(ui, "Commit:", &commit.chars().take(12).collect::<String>());
+                        }
+                        ui.horizontal(|ui| {
+                            if ui
+                                .small_button("📋 Copy URL")
+                                .on_hover_text(&info.download_url)
+                                .clicked()
+                            {
+                                ui.ctx().copy_text(info.download_url.clone());
+                                copied = Some("download URL");
+                            }
+                            match &info.sha256_hash {
+                                Some(hash) => {
+                                    if ui
+                                        .small_button("📋 Copy hash")
+                                        .on_hover_text(hash)
+                                        .clicked()
+                                    {
+                                        ui.ctx().copy_text(hash.clone());
+                                        copied = Some("hash");
+                                    }
                                 }
-                                Err(e) => {
-                                    log::error!("Failed to install v{}: {}", version, e);
-                                    self.download_state = DownloadState::Failed {
-                                        version: version.clone(),
-                                        error: format!("Installation failed: {}", e),
-                                    };
-                                    self.set_status(&format!("✗ Installation failed: {}", e));
+                                None => {
+                                    ui.label(
+                                        RichText::new("No hash in registry")
+                                            .color(theme.warning)
+                                            .size(theme.text_size(10.0)),
+                                    );
                                 }
                             }
-                        }
-                        self.download_receiver = None;
-                        self.download_progress = None;
-                    }
-                    Ok(Err(e)) => {
-                        if let DownloadState::Downloading { version, .. } = &self.download_state {
-                            self.download_state = DownloadState::Failed {
-                                version: version.clone(),
-                                error: e.clone(),
-                            };
-                        }
-                        self.set_status(&format!("✗ Download failed: {}", e));
-                        self.download_receiver = None;
-                        self.download_progress = None;
+                        });
                     }
-                    Err(_) => {
-                        self.set_status("✗ Download thread panicked");
-                        self.download_state = DownloadState::Idle;
-                        self.download_receiver = None;
-                        self.download_progress = None;
+                    None => {
+                        ui.label(
+                            RichText::new("Not in the download registry")
+                                .color(theme.fg_dim)
+                                .italics()
+                                .size(theme.text_size(10.0)),
+                        );
                     }
                 }
-            } else {
-                // Thread still running, put it back
-                self.download_thread = Some(handle);
-            }
+                ui.hyperlink_to(
+                    RichText::new("Changelog ↗").color(theme.accent).size(theme.text_size(10.0)),
+                    CURSOR_CHANGELOG_URL,
+                );
+            });
+        });
+
+        if close {
+            self.version_details = None;
+        }
+        if let Some(what) = copied {
+            self.set_status(&format!("✓ Copied {} to clipboard", what));
         }
     }
-}
-
-/// Compare version strings (e.g., "2.1.34" > "1.7.43")
-fn version_compare(a: &str, b: &str) -> std::cmp::Ordering {
-    let parse_version =
-        |s: &str| -> Vec<u32> { s.split('.').filter_map(|p| p.parse().ok()).collect() };
-
-    let va = parse_version(a);
-    let vb = parse_version(b);
 
-    for (a, b) in va.iter().zip(vb.iter()) {
-        match a.cmp(b) {
-            std::cmp::Ordering::Equal => continue,
-            other => return other,
+    /// Re-measure disk usage of the cache and install directories in the
+    /// background; `storage_used_bytes` keeps the last result until then
+    fn refresh_storage_usage(&mut self) {
+        if self.storage_usage_thread.is_some() {
+            self.storage_usage_stale = true;
+            return;
         }
+        self.storage_usage_thread = Some(std::thread::spawn(versions::storage_usage_bytes));
     }
-    va.len().cmp(&vb.len())
-}
-
-// Helper for styled buttons
-fn styled_button(ui: &mut egui::Ui, text: &str, min_size: Vec2) -> egui::Response {
-    let btn = egui::Button::new(RichText::new(text).size(12.0))
-        .min_size(min_size)
-        .rounding(Rounding::same(4.0));
 
-    let response = ui.add(btn);
+    /// Pick up a finished storage measurement (called from update loop)
+    fn poll_storage_usage(&mut self, ctx: &egui::Context) {
+        let Some(handle) = self.storage_usage_thread.take() else {
+            return;
+        };
+        if !handle.is_finished() {
+            // Thread still running, put it back
+            self.storage_usage_thread = Some(handle);
+            ctx.request_repaint_after(std::time::Duration::from_millis(200));
+            return;
+        }
+        match handle.join() {
+            Ok(bytes) => self.storage_used_bytes = Some(bytes),
+            Err(_) => log::warn!("Storage usage thread panicked"),
+        }
+        if std::mem::take(&mut self.storage_usage_stale) {
+            self.refresh_storage_usage();
+        }
+    }
 
-    if response.hovered() {
-        ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+    /// Check that `needed` more bytes fit under `storage_limit_mb` as last
+    /// measured, reporting the shortfall in the status bar if not. Nothing
+    /// fits until disk usage has been measured once.
+    fn check_storage_budget(&mut self, needed: u64) -> bool {
+        let Some(used) = self.storage_used_bytes else {
+            // The first measurement may have failed; take another
+            if self.storage_usage_thread.is_none() {
+                self.refresh_storage_usage();
+            }
+            self.set_status("⏳ Still measuring disk usage - try again in a moment");
+            return false;
+        };
+        let limit = self.storage_limit_mb as u64 * 1024 * 1024;
+        if used + needed <= limit {
+            return true;
+        }
+        self.set_status(&format!(
+            "✗ Storage limit reached: {} used of {}, need ~{} more - clean the download cache or raise the limit in Settings",
+            versions::format_size(used),
+            versions::format_size(limit),
+            versions::format_size(needed)
+        ));
+        false
     }
 
-    response
-}
+    /// Delete cached copies of installed versions and stale interrupted
+    /// downloads; anything still downloading is left alone
+    fn clean_download_cache(&mut self) {
+        let downloading: Vec<String> = self.active_downloads.keys().cloned().collect();
+        match versions::prune_cache(STALE_DOWNLOAD_AGE, false, &downloading) {
+            Ok(pruned) => {
+                for error in &pruned.errors {
+                    log::warn!("Download cache prune: {}", error);
+                }
+                match (pruned.reclaimed, pruned.errors.len()) {
+                    (0, 0) => self.set_status("✓ Download cache is already clean"),
+                    (freed, 0) => self.set_status(&format!(
+                        "✓ Freed {} of cached downloads",
+                        versions::format_size(freed)
+                    )),
+                    (freed, failed) => self.set_status(&format!(
+                        "⚠ Freed {} of cached downloads; {} file(s) couldn't be removed (see log)",
+                        versions::format_size(freed),
+                        failed
+                    )),
+                }
+            }
+            Err(e) => self.set_status(&format!("✗ Failed to clean cache: {}", e)),
+        }
+        self.refresh_storage_usage();
+    }
 
-fn styled_button_accent(
-    ui: &mut egui::Ui,
-    text: &str,
-    min_size: Vec2,
-    theme: Theme,
-) -> egui::Response {
-    let btn = egui::Button::new(RichText::new(text).size(13.0).color(Color32::WHITE))
-        .min_size(min_size)
-        .rounding(Rounding::same(4.0))
-        .fill(theme.accent);
+    /// Switch the download cache to the directory entered in Settings (empty
+    /// for the default), moving cached downloads along if asked to
+    fn apply_cache_dir(&mut self) {
+        if !self.active_downloads.is_empty() || !self.download_queue.is_empty() {
+            self.set_status("⚠ Wait for downloads to finish before moving the cache");
+            return;
+        }
+        if self.cache_move_receiver.is_some() {
+            self.set_status("⏳ Still moving cached downloads");
+            return;
+        }
+        let input = self.cache_dir_input.trim().to_string();
+        let new_dir = if input.is_empty() {
+            versions::default_cache_dir()
+        } else {
+            expand_home(&input)
+        };
+        if let Err(e) = versions::validate_cache_dir(&new_dir) {
+            self.set_status(&format!("✗ {:#}", e));
+            return;
+        }
 
-    let response = ui.add(btn);
+        if !self.relocate_cache {
+            self.use_cache_dir(new_dir, input, (0, 0));
+            return;
+        }
 
-    if response.hovered() {
-        ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+        // Copying across filesystems can take a while; the old directory
+        // stays in use until everything has arrived
+        let old_dir = versions::get_cache_dir();
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let moved = versions::relocate_cache(&old_dir, &new_dir);
+            let _ = tx.send((new_dir, input, moved));
+        });
+        self.cache_move_receiver = Some(rx);
+        self.set_status("⏳ Moving cached downloads...");
     }
 
-    response
-}
-
-fn toggle_switch(ui: &mut egui::Ui, on: &mut bool, theme: Theme) -> egui::Response {
-    let desired_size = Vec2::new(40.0, 20.0);
-    let (rect, response) = ui.allocate_exact_size(desired_size, egui::Sense::click());
-
-    if response.clicked() {
-        *on = !*on;
+    /// Switch to the new cache directory once its downloads have moved
+    /// (called from update loop)
+    fn poll_cache_move(&mut self, ctx: &egui::Context) {
+        let Some(rx) = &self.cache_move_receiver else {
+            return;
+        };
+        let (new_dir, input, moved) = match rx.try_recv() {
+            Ok(result) => result,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                self.cache_move_receiver = None;
+                self.set_status("✗ Failed to move cached downloads");
+                self.refresh_storage_usage();
+                return;
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => {
+                ctx.request_repaint_after(std::time::Duration::from_millis(200));
+                return;
+            }
+        };
+        self.cache_move_receiver = None;
+        match moved {
+            Ok(moved) => self.use_cache_dir(new_dir, input, moved),
+            Err(e) => {
+                self.set_status(&format!("✗ Failed to move cached downloads: {:#}", e));
+                self.refresh_storage_usage();
+            }
+        }
     }
 
-    if response.hovered() {
-        ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+    /// Make `new_dir` the download cache and save it, reporting what moved
+    fn use_cache_dir(&mut self, new_dir: PathBuf, input: String, moved: (usize, u64)) {
+        versions::set_cache_dir(Some(new_dir.clone()).filter(|_| !input.is_empty()));
+        let _ = self.db.set_config("ui.cache_dir", &input);
+        self.refresh_storage_usage();
+        let (files, bytes) = moved;
+        if files > 0 {
+            self.set_status(&format!(
+                "✓ Download cache moved to {} ({} files, {})",
+                new_dir.display(),
+                files,
+                versions::format_size(bytes)
+            ));
+        } else {
+            self.set_status(&format!("✓ Download cache: {}", new_dir.display()));
+        }
     }
 
-    if ui.is_rect_visible(rect) {
-        let how_on = ui.ctx().animate_bool_responsive(response.id, *on);
-        let bg_color = Color32::from_rgb(
-            (60.0 + (theme.accent.r() as f32 - 60.0) * how_on) as u8,
-            (60.0 + (theme.accent.g() as f32 - 60.0) * how_on) as u8,
-            (60.0 + (theme.accent.b() as f32 - 60.0) * how_on) as u8,
-        );
+    /// Storage usage vs. `storage_limit_mb`, with a shortcut to free space
+    fn show_storage_usage_bar(&mut self, ui: &mut egui::Ui, theme: Theme) {
+        let limit = self.storage_limit_mb as u64 * 1024 * 1024;
+        let used = self.storage_used_bytes.unwrap_or(0);
+        let fraction = (used as f32 / limit.max(1) as f32).min(1.0);
+        let bar_color = if fraction >= 1.0 {
+            theme.error
+        } else if fraction >= 0.9 {
+            theme.warning
+        } else {
+            theme.accent
+        };
+        let mut clean_clicked = false;
+        let mut restore_clicked = false;
 
-        ui.painter()
-            .rect_filled(rect, Rounding::same(10.0), bg_color);
+        ui.horizontal(|ui| {
+            ui.add_space(16.0);
+            ui.label(
+                RichText::new(match self.storage_used_bytes {
+                    Some(used) => format!(
+                        "💾 {} / {}",
+                        versions::format_size(used),
+                        versions::format_size(limit)
+                    ),
+                    None => format!("💾 measuring… / {}", versions::format_size(limit)),
+                })
+                .color(theme.fg_dim)
+                .size(theme.text_size(10.0)),
+            )
+            .on_hover_text("Cached downloads and installed versions vs. the storage limit");
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.add_space(8.0);
+                if ui
+                    .small_button("🧹 Clean cache")
+                    .on_hover_text("Delete cached copies of installed versions and interrupted downloads older than a day")
+                    .clicked()
+                {
+                    clean_clicked = true;
+                }
+                if ui
+                    .small_button("♻ Restore missing")
+                    .on_hover_text("Re-download versions your chats came from that are gone")
+                    .clicked()
+                {
+                    restore_clicked = true;
+                }
+            });
+        });
 
-        let circle_x = rect.left() + 10.0 + (rect.width() - 20.0) * how_on;
-        let circle_center = egui::pos2(circle_x, rect.center().y);
-        ui.painter()
-            .circle_filled(circle_center, 8.0, Color32::WHITE);
+        ui.horizontal(|ui| {
+            ui.add_space(16.0);
+            let bar_width = ui.available_width() - 32.0;
+            let (rect, _) =
+                ui.allocate_exact_size(Vec2::new(bar_width.max(50.0), 4.0), egui::Sense::hover());
+            ui.painter()
+                .rect_filled(rect, Rounding::same(2.0), theme.input_bg);
+            let used_rect =
+                egui::Rect::from_min_size(rect.min, Vec2::new(rect.width() * fraction, 4.0));
+            ui.painter()
+                .rect_filled(used_rect, Rounding::same(2.0), bar_color);
+        });
+
+        if clean_clicked {
+            self.clean_download_cache();
+        } else if restore_clicked {
+            self.plan_restore_missing();
+        }
     }
 
-    response
-}
+    /// Spawn the download thread for an approved version
+    fn begin_download(&mut self, version_info: AvailableVersion) {
+        let version = version_info.version.clone();
+        let options = versions::DownloadOptions {
+            proxy: Some(self.download_proxy.clone()).filter(|p| !p.trim().is_empty()),
+            connect_timeout: std::time::Duration::from_secs(
+                self.download_connect_timeout_secs as u64,
+            ),
+            read_timeout: std::time::Duration::from_secs(self.download_read_timeout_secs as u64),
+            retries: self.download_retries as u32,
+        };
+        let proxy = versions::effective_proxy(&version_info.download_url, options.proxy.as_deref())
+            .map(|proxy| versions::redact_proxy(&proxy));
+        match &proxy {
+            Some(proxy) => self.set_status(&format!(
+                "⏳ Starting download of v{} via proxy {}...",
+                version, proxy
+            )),
+            None => self.set_status(&format!("⏳ Starting download of v{}...", version)),
+        }
 
-// ═══════════════════════════════════════════════════════════════════════════
-// PANEL LAYOUT HELPERS - Consistent UI across all panels
-// ═══════════════════════════════════════════════════════════════════════════
+        let cache_dir = versions::get_cache_dir();
+        let resumed_bytes = versions::partial_download_len(&version, &cache_dir);
+        let state = if resumed_bytes > 0 {
+            self.set_status(&format!(
+                "⏳ Resuming download of v{} ({} already downloaded)...",
+                version,
+                versions::format_size(resumed_bytes)
+            ));
+            DownloadState::Resuming {
+                version: version.clone(),
+                resumed_bytes,
+            }
+        } else {
+            DownloadState::Downloading {
+                progress: 0.0,
+                version: version.clone(),
+            }
+        };
+        self.download_state.insert(version.clone(), state);
 
-/// Render a panel section header (e.g., "SOURCES", "SECURITY")
-fn panel_header(ui: &mut egui::Ui, label: &str, theme: Theme) {
-    ui.horizontal(|ui| {
-        ui.add_space(PANEL_PADDING);
-        ui.label(
-            RichText::new(label)
-                .size(10.0)
-                .color(theme.fg_dim)
-                .strong(),
+        // Create progress channel
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        // Spawn download thread
+        let handle = std::thread::spawn(move || -> Result<FinishedDownload, String> {
+            let event_tx = tx.clone();
+            let on_event = move |event| {
+                let _ = event_tx.send(event);
+            };
+            let path =
+                versions::download_version_sync_with(&version_info, &cache_dir, &options, on_event)
+                    .map_err(|e| format!("{:#}", e))?;
+
+            // Hashed here rather than on the UI thread, so the panel can show progress
+            let hash_check = version_info.sha256_hash.as_ref().map(|expected| {
+                let _ = tx.send(DownloadEvent::Verifying);
+                let report = |percent| {
+                    let _ = tx.send(DownloadEvent::VerifyProgress { percent });
+                };
+                versions::verify_hash_detailed(&path, expected, Some(&report))
+                    .map(|result| {
+                        log::info!(
+                            "Hashed {} of v{}: {}",
+                            versions::format_size(result.bytes_hashed),
+                            version_info.version,
+                            if result.matches { "match" } else { "MISMATCH" }
+                        );
+                        result.matches
+                    })
+                    .map_err(|e| e.to_string())
+            });
+            Ok(FinishedDownload { path, hash_check })
+        });
+
+        self.active_downloads.insert(
+            version,
+            ActiveDownload {
+                thread: handle,
+                receiver: rx,
+                progress: None,
+                proxy,
+            },
         );
-    });
-}
+    }
 
-/// Render a panel section header with icon
-fn panel_header_with_icon(ui: &mut egui::Ui, icon: &str, label: &str, theme: Theme) {
-    ui.horizontal(|ui| {
-        ui.add_space(PANEL_PADDING);
-        ui.label(
-            RichText::new(format!("{} {}", icon, label))
-                .size(11.0)
-                .color(theme.accent)
-                .strong(),
-        );
-    });
-}
+    /// Estimate the total size of the selected versions in the background
+    fn plan_batch_download(&mut self) {
+        let mut selected: Vec<String> = self.download_selection.iter().cloned().collect();
+        selected.sort_by(|a, b| version_compare(b, a));
 
-/// Render a stat card with icon, value, and label
-fn stat_card(ui: &mut egui::Ui, icon: &str, value: &str, label: &str, theme: Theme, width: f32) {
-    egui::Frame::none()
-        .fill(theme.code_bg)
-        .rounding(Rounding::same(CARD_ROUNDING))
-        .inner_margin(egui::Margin::symmetric(CARD_MARGIN, CARD_MARGIN - 2.0))
-        .show(ui, |ui| {
-            ui.set_width(width);
-            ui.horizontal(|ui| {
-                ui.label(RichText::new(icon).size(16.0));
-                ui.add_space(TIGHT_SPACING);
-                ui.vertical(|ui| {
-                    ui.label(
-                        RichText::new(value)
-                            .size(14.0)
-                            .color(theme.fg)
-                            .strong(),
-                    );
-                    ui.label(
-                        RichText::new(label)
-                            .size(9.0)
-                            .color(theme.fg_dim),
-                    );
-                });
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.download_plan = None;
+        self.download_plan_receiver = Some(rx);
+        self.set_status(&format!(
+            "⏳ Estimating download size for {} versions...",
+            selected.len()
+        ));
+
+        std::thread::spawn(move || {
+            let mut sizes = Vec::with_capacity(selected.len());
+            let mut total_bytes = 0;
+            let mut unknown_sizes = 0;
+            for version in &selected {
+                let size =
+                    get_version_info(version).and_then(|v| versions::estimate_download_size(&v));
+                match size {
+                    Some(size) => total_bytes += size,
+                    None => unknown_sizes += 1,
+                }
+                sizes.push(size);
+            }
+            let _ = tx.send(DownloadPlan {
+                versions: selected,
+                sizes,
+                total_bytes,
+                unknown_sizes,
+                used_bytes: versions::storage_usage_bytes(),
             });
         });
-}
-
-/// Create a card frame with standard styling
-fn card_frame(theme: Theme) -> egui::Frame {
-    egui::Frame::none()
-        .fill(theme.code_bg)
-        .rounding(Rounding::same(CARD_ROUNDING))
-        .inner_margin(egui::Margin::same(CARD_MARGIN))
-}
-
-/// Create an accent-tinted card frame
-fn accent_card_frame(theme: Theme) -> egui::Frame {
-    egui::Frame::none()
-        .fill(theme.accent.gamma_multiply(0.15))
-        .rounding(Rounding::same(CARD_ROUNDING))
-        .inner_margin(egui::Margin::same(CARD_MARGIN))
-}
+    }
 
-/// Create a warning card frame
-fn warning_card_frame(theme: Theme) -> egui::Frame {
-    egui::Frame::none()
-        .fill(theme.warning.gamma_multiply(0.15))
-        .rounding(Rounding::same(CARD_ROUNDING))
-        .inner_margin(egui::Margin::same(CARD_MARGIN))
-        .stroke(Stroke::new(1.0, theme.warning.gamma_multiply(0.3)))
-}
+    /// Versions that chats in the library came from but that are no longer
+    /// on disk, e.g. after a home reset, as (downloadable, not in the registry)
+    fn missing_versions(&self) -> (Vec<String>, usize) {
+        let used = match self.db.get_version_counts(true) {
+            Ok(counts) => counts,
+            Err(e) => {
+                log::warn!("Failed to list library versions: {}", e);
+                Vec::new()
+            }
+        };
+        let mut missing = Vec::new();
+        let mut unknown = 0;
+        for (version, _) in used {
+            let skip = version.is_empty() || version == "default";
+            if skip || versions::is_version_installed(&version) {
+                continue;
+            }
+            if get_version_info(&version).is_some() {
+                missing.push(version);
+            } else {
+                unknown += 1;
+            }
+        }
+        (missing, unknown)
+    }
 
-impl eframe::App for CursorStudio {
-    /// Save settings when the app is about to exit
-    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
-        log::info!("Saving settings on exit...");
-        self.save_settings();
-        log::info!("Settings saved successfully");
+    /// Select every missing library version and estimate the download, so
+    /// the batch bar can list them for approval
+    fn plan_restore_missing(&mut self) {
+        if !self.active_downloads.is_empty() || !self.download_queue.is_empty() {
+            self.set_status("⚠ Wait for the current downloads to finish");
+            return;
+        }
+        let (missing, unknown) = self.missing_versions();
+        if missing.is_empty() {
+            match unknown {
+                0 => self.set_status("✓ Every version in the library is installed"),
+                n => self.set_status(&format!(
+                    "⚠ {} missing versions aren't in the registry and can't be downloaded",
+                    n
+                )),
+            }
+            return;
+        }
+        if unknown > 0 {
+            log::warn!("{} missing versions aren't in the registry", unknown);
+        }
+        self.download_selection = missing.into_iter().collect();
+        self.plan_batch_download();
     }
 
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        // Poll background threads for completion
-        self.check_download_progress();
-        self.poll_security_scan();
-        self.poll_npm_scan();
+    /// Pick up a finished size estimate
+    fn poll_download_plan(&mut self) {
+        let Some(rx) = &self.download_plan_receiver else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(plan) => {
+                self.download_plan_receiver = None;
+                let mut summary = format!(
+                    "🔍 Will download ~{} across {} versions",
+                    versions::format_size(plan.total_bytes),
+                    plan.versions.len()
+                );
+                if plan.unknown_sizes > 0 {
+                    summary.push_str(&format!(" ({} size unknown)", plan.unknown_sizes));
+                }
+                self.set_status(&summary);
+                self.download_plan = Some(plan);
+            }
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                self.download_plan_receiver = None;
+                self.set_status("✗ Failed to estimate download size");
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => {}
+        }
+    }
 
-        // Clean up expired approval requests
-        self.approval_manager.cleanup_expired();
+    /// Queue every version in the current plan (after approval)
+    fn start_batch_download(&mut self) {
+        if self.cache_move_receiver.is_some() {
+            self.set_status("⏳ Wait for the download cache to finish moving");
+            return;
+        }
+        let Some(plan) = &self.download_plan else {
+            return;
+        };
+        let total_bytes = plan.total_bytes;
+        // A total missing some versions would understate the download
+        let operation = ApprovalOperation::DownloadMultiple {
+            versions: plan.versions.clone(),
+            size_estimate: Some(total_bytes).filter(|_| plan.unknown_sizes == 0),
+        };
+        let title = operation.title();
 
-        let theme = self.theme;
+        if !self.check_storage_budget(total_bytes) {
+            return;
+        }
 
-        let mut visuals = if theme.bg.r() > 128 {
-            egui::Visuals::light()
-        } else {
-            egui::Visuals::dark()
-        };
-        visuals.panel_fill = theme.bg;
-        visuals.window_fill = theme.sidebar_bg;
-        visuals.faint_bg_color = theme.input_bg;
-        visuals.extreme_bg_color = theme.code_bg;
-        visuals.widgets.noninteractive.bg_fill = theme.sidebar_bg;
-        visuals.widgets.inactive.bg_fill = theme.input_bg;
-        visuals.widgets.hovered.bg_fill = theme.list_hover;
-        visuals.widgets.active.bg_fill = theme.accent;
-        visuals.selection.bg_fill = theme.selection;
-        ctx.set_visuals(visuals);
+        match self.approval_manager.request(operation) {
+            ApprovalResult::Approved => {
+                if let Some(plan) = self.download_plan.take() {
+                    self.download_queue.extend(plan.versions);
+                }
+                self.download_selection.clear();
+                self.advance_download_queue();
+            }
+            ApprovalResult::Denied => {
+                if let Some(msg) = self.approval_manager.get_pending_message(&title) {
+                    self.set_status(&msg);
+                } else {
+                    self.set_status("⚠️ Click again to confirm batch download");
+                }
+            }
+            _ => {
+                self.set_status("Download cancelled");
+            }
+        }
+    }
 
-        // Apply font scaling - base is 1.0 (default), range 0.8-1.5
-        // egui uses pixels_per_point for DPI/scale - we modify it relative to native
-        let native_ppp = ctx.native_pixels_per_point().unwrap_or(1.0);
-        ctx.set_pixels_per_point(native_ppp * self.font_scale);
+    /// Start queued downloads while fewer than `max_cpu_threads` are running
+    fn advance_download_queue(&mut self) {
+        while self.active_downloads.len() < self.max_cpu_threads.max(1) {
+            let Some(version) = self.download_queue.pop_front() else {
+                return;
+            };
+            if self.is_downloading(&version) {
+                continue;
+            }
+            match get_version_info(&version) {
+                Some(info) => self.begin_download(info),
+                None => log::warn!("Skipping queued download of unknown version {}", version),
+            }
+        }
+    }
 
-        egui::SidePanel::left("activity_bar")
-            .exact_width(48.0)
-            .resizable(false)
-            .frame(egui::Frame::none().fill(theme.activitybar_bg))
-            .show(ctx, |ui| {
-                self.show_activity_bar(ui, theme);
-            });
+    /// Selection summary, size estimate and confirm button for batch downloads
+    fn show_download_batch_bar(&mut self, ui: &mut egui::Ui, theme: Theme) {
+        let mut plan_clicked = false;
+        let mut download_clicked = false;
+        let mut clear_clicked = false;
+        let estimating = self.download_plan_receiver.is_some();
 
-        egui::TopBottomPanel::bottom("status_bar")
-            .exact_height(24.0)
-            .frame(
-                egui::Frame::none()
-                    .fill(theme.statusbar_bg)
-                    .inner_margin(egui::Margin::symmetric(12.0, 4.0)),
-            )
-            .show(ctx, |ui| {
-                self.show_status_bar(ui);
+        ui.horizontal(|ui| {
+            ui.add_space(16.0);
+            ui.label(
+                RichText::new(format!("{} selected", self.download_selection.len()))
+                    .color(theme.fg)
+                    .size(theme.text_size(11.0)),
+            );
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.add_space(8.0);
+                if ui.small_button("Clear").clicked() {
+                    clear_clicked = true;
+                }
+                if estimating {
+                    ui.add(egui::Spinner::new().size(12.0));
+                } else if self.download_plan.is_none()
+                    && ui
+                        .small_button("⬇ Download…")
+                        .on_hover_text("Estimate the total size before downloading")
+                        .clicked()
+                {
+                    plan_clicked = true;
+                }
             });
+        });
 
-        if self.left_sidebar_visible {
-            egui::SidePanel::left("left_sidebar")
-                .default_width(self.left_sidebar_width)
-                .width_range(200.0..=500.0)
-                .resizable(true)
-                .frame(
-                    egui::Frame::none()
-                        .fill(theme.sidebar_bg)
-                        .inner_margin(egui::Margin::ZERO),
-                )
-                .show(ctx, |ui| {
-                    self.left_sidebar_width = ui.available_width();
+        if let Some(plan) = &self.download_plan {
+            let budget = self.storage_limit_mb as u64 * 1024 * 1024;
+            let remaining = budget.saturating_sub(plan.used_bytes);
 
-                    match self.left_mode {
-                        SidebarMode::Manager => self.show_manager_panel(ui, theme),
-                        SidebarMode::Search => self.show_search_panel(ui, theme),
-                        SidebarMode::Settings => self.show_settings_panel(ui, theme),
+            ui.horizontal(|ui| {
+                ui.add_space(16.0);
+                let mut summary = format!(
+                    "Will download ~{} across {} versions",
+                    versions::format_size(plan.total_bytes),
+                    plan.versions.len()
+                );
+                if plan.unknown_sizes > 0 {
+                    summary.push_str(&format!(" ({} size unknown)", plan.unknown_sizes));
+                }
+                ui.label(RichText::new(summary).color(theme.fg_dim).size(theme.text_size(10.0)));
+            });
+            egui::ScrollArea::vertical()
+                .id_salt("download_plan_versions")
+                .max_height(96.0)
+                .show(ui, |ui| {
+                    for (version, size) in plan.versions.iter().zip(&plan.sizes) {
+                        let size = size.map_or("size unknown".to_string(), versions::format_size);
+                        ui.horizontal(|ui| {
+                            ui.add_space(24.0);
+                            ui.label(
+                                RichText::new(format!("v{}", version))
+                                    .color(theme.fg)
+                                    .size(theme.text_size(10.0)),
+                            );
+                            ui.label(
+                                RichText::new(size)
+                                    .color(theme.fg_dim)
+                                    .size(theme.text_size(10.0)),
+                            );
+                        });
                     }
                 });
+            if plan.total_bytes > remaining {
+                ui.horizontal(|ui| {
+                    ui.add_space(16.0);
+                    ui.label(
+                        RichText::new(format!(
+                            "⚠ Exceeds storage limit ({} of {} free)",
+                            versions::format_size(remaining),
+                            versions::format_size(budget)
+                        ))
+                        .color(theme.warning)
+                        .size(theme.text_size(10.0)),
+                    );
+                });
+            }
+            ui.horizontal(|ui| {
+                ui.add_space(16.0);
+                if ui.small_button("⬇ Download All").clicked() {
+                    download_clicked = true;
+                }
+            });
         }
+        ui.add_space(4.0);
 
-        if self.right_sidebar_visible {
-            egui::SidePanel::right("right_sidebar")
-                .default_width(self.right_sidebar_width)
-                .width_range(200.0..=500.0)
-                .resizable(true)
-                .frame(
-                    egui::Frame::none()
-                        .fill(theme.sidebar_bg)
-                        .inner_margin(egui::Margin::ZERO),
-                )
-                .show(ctx, |ui| {
-                    self.right_sidebar_width = ui.available_width();
-
-                    // Mode selector header (VS Code style)
-                    ui.horizontal(|ui| {
-                        ui.add_space(8.0);
-
-                        // Archive button (Chat History)
-                        let archive_selected = self.right_mode == RightSidebarMode::Archive;
-                        let archive_btn = ui
-                            .add(
-                                egui::Button::new(RichText::new("📚").size(16.0).color(
-                                    if archive_selected {
-                                        theme.accent
-                                    } else {
-                                        theme.fg_dim
-                                    },
-                                ))
-                                .frame(false)
-                                .min_size(Vec2::new(32.0, 28.0)),
-                            )
-                            .on_hover_text("Archive (Chat History)");
-                        if archive_btn.clicked() {
-                            self.right_mode = RightSidebarMode::Archive;
-                        }
-                        if archive_btn.hovered() {
-                            ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
-                        }
+        if clear_clicked {
+            self.download_selection.clear();
+            self.download_plan = None;
+        } else if plan_clicked {
+            self.plan_batch_download();
+        } else if download_clicked {
+            self.start_batch_download();
+        }
+    }
 
-                        // Sentinel button (Security)
-                        let sentinel_selected = self.right_mode == RightSidebarMode::Sentinel;
-                        let sentinel_btn = ui
-                            .add(
-                                egui::Button::new(RichText::new("🛡️").size(16.0).color(
-                                    if sentinel_selected {
-                                        theme.accent
-                                    } else {
-                                        theme.fg_dim
-                                    },
-                                ))
-                                .frame(false)
-                                .min_size(Vec2::new(32.0, 28.0)),
-                            )
-                            .on_hover_text("Sentinel (Security)");
-                        if sentinel_btn.clicked() {
-                            self.right_mode = RightSidebarMode::Sentinel;
+    /// Drain progress from every running download and finish the ones whose
+    /// thread has exited
+    fn check_download_progress(&mut self) {
+        let versions: Vec<String> = self.active_downloads.keys().cloned().collect();
+        let mut fallbacks = Vec::new();
+        for version in versions {
+            let Some(download) = self.active_downloads.get_mut(&version) else {
+                continue;
+            };
+            while let Ok(event) = download.receiver.try_recv() {
+                let state = match event {
+                    DownloadEvent::Progress { percent } => {
+                        // A resumed download first reports where it picked up; stay
+                        // "Resuming" until bytes past that point arrive
+                        let resume_point = download.progress.is_none()
+                            && matches!(
+                                self.download_state.get(&version),
+                                Some(DownloadState::Resuming { .. })
+                            );
+                        download.progress = Some(percent);
+                        if resume_point {
+                            continue;
                         }
-                        if sentinel_btn.hovered() {
-                            ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+                        DownloadState::Downloading {
+                            progress: percent,
+                            version: version.clone(),
                         }
+                    }
+                    DownloadEvent::MirrorFallback { from, to } => {
+                        fallbacks.push(format!(
+                            "⚠ v{}: {} failed, trying mirror {}",
+                            version,
+                            url_host(&from),
+                            url_host(&to)
+                        ));
+                        continue;
+                    }
+                    DownloadEvent::Verifying => DownloadState::Verifying {
+                        version: version.clone(),
+                        progress: 0.0,
+                    },
+                    DownloadEvent::VerifyProgress { percent } => DownloadState::Verifying {
+                        version: version.clone(),
+                        progress: percent,
+                    },
+                    _ => continue,
+                };
+                self.download_state.insert(version.clone(), state);
+            }
 
-                        // Bridge button (Sync)
-                        let bridge_selected = self.right_mode == RightSidebarMode::Bridge;
-                        let bridge_btn = ui
-                            .add(
-                                egui::Button::new(RichText::new("🔗").size(16.0).color(
-                                    if bridge_selected {
-                                        theme.accent
-                                    } else {
-                                        theme.fg_dim
-                                    },
-                                ))
-                                .frame(false)
-                                .min_size(Vec2::new(32.0, 28.0)),
-                            )
-                            .on_hover_text("Bridge (Sync)");
-                        if bridge_btn.clicked() {
-                            self.right_mode = RightSidebarMode::Bridge;
-                        }
-                        if bridge_btn.hovered() {
-                            ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
-                        }
+            if download.thread.is_finished() {
+                if let Some(download) = self.active_downloads.remove(&version) {
+                    let result = download.thread.join();
+                    self.finish_download(version, result, download.proxy);
+                }
+            }
+        }
+        for message in fallbacks {
+            self.set_status(&message);
+        }
+    }
 
-                        // Index (Docs) button
-                        let index_selected = self.right_mode == RightSidebarMode::Index;
-                        let index_btn = ui
-                            .add(
-                                egui::Button::new(RichText::new("📖").size(16.0).color(
-                                    if index_selected {
-                                        theme.accent
-                                    } else {
-                                        theme.fg_dim
-                                    },
-                                ))
-                                .frame(false)
-                                .min_size(Vec2::new(32.0, 28.0)),
-                            )
-                            .on_hover_text("Index (Documentation)");
-                        if index_btn.clicked() {
-                            self.right_mode = RightSidebarMode::Index;
-                        }
-                        if index_btn.hovered() {
-                            ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
-                        }
+    /// Verify and install a finished download, or record why it failed
+    fn finish_download(
+        &mut self,
+        version: String,
+        result: std::thread::Result<Result<FinishedDownload, String>>,
+        proxy: Option<String>,
+    ) {
+        match result {
+            Ok(Ok(FinishedDownload { path, hash_check })) => {
+                let hash_status = match hash_check {
+                    Some(Ok(true)) => "✓ Hash verified",
+                    Some(Ok(false)) => {
+                        // Hash mismatch - delete file and fail
+                        let _ = std::fs::remove_file(&path);
+                        self.download_state.insert(
+                            version.clone(),
+                            DownloadState::Failed {
+                                version,
+                                error: "Hash verification failed - file deleted".to_string(),
+                            },
+                        );
+                        self.set_status("✗ Hash verification failed - downloaded file was corrupted");
+                        return;
+                    }
+                    Some(Err(e)) => {
+                        log::warn!("Hash verification error: {}", e);
+                        "⚠ Hash verification error"
+                    }
+                    None => "⚠ No hash available",
+                };
 
-                        // Forge (Data Transform) button
-                        let forge_selected = self.right_mode == RightSidebarMode::Forge;
-                        let forge_btn = ui
-                            .add(
-                                egui::Button::new(RichText::new("🔥").size(16.0).color(
-                                    if forge_selected {
-                                        theme.accent
-                                    } else {
-                                        theme.fg_dim
-                                    },
-                                ))
-                                .frame(false)
-                                .min_size(Vec2::new(32.0, 28.0)),
-                            )
-                            .on_hover_text("Forge (Data Transform)");
-                        if forge_btn.clicked() {
-                            self.right_mode = RightSidebarMode::Forge;
-                        }
-                        if forge_btn.hovered() {
-                            ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
-                        }
+                // Install the downloaded AppImage to the proper location
+                match versions::install_version(&path, &version, self.create_desktop_entries) {
+                    Ok(installed_path) => {
+                        log::info!("Installed v{} to {:?}", version, installed_path);
 
-                        // Modes (Custom Modes) button
-                        let modes_selected = self.right_mode == RightSidebarMode::Modes;
-                        let modes_btn = ui
-                            .add(
-                                egui::Button::new(RichText::new("🎭").size(16.0).color(
-                                    if modes_selected {
-                                        theme.accent
-                                    } else {
-                                        theme.fg_dim
-                                    },
-                                ))
-                                .frame(false)
-                                .min_size(Vec2::new(32.0, 28.0)),
-                            )
-                            .on_hover_text("Modes (Custom Modes)");
-                        if modes_btn.clicked() {
-                            self.right_mode = RightSidebarMode::Modes;
-                        }
-                        if modes_btn.hovered() {
-                            ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
-                        }
+                        // Refresh versions list to show the new installation
+                        // (get_versions scans the filesystem, so it will find the new install)
+                        self.versions = self.db.get_versions().unwrap_or_default();
 
-                        // Underline indicator for selected mode
-                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            ui.add_space(8.0);
-                            let mode_label = match self.right_mode {
-                                RightSidebarMode::Archive => "ARCHIVE",
-                                RightSidebarMode::Index => "INDEX",
-                                RightSidebarMode::Sentinel => "SENTINEL",
-                                RightSidebarMode::Bridge => "BRIDGE",
-                                RightSidebarMode::Forge => "FORGE",
-                                RightSidebarMode::Modes => "MODES",
-                            };
-                            ui.label(
-                                RichText::new(mode_label)
-                                    .size(10.0)
-                                    .color(theme.fg_dim)
-                                    .strong(),
-                            );
-                        });
-                    });
+                        // Also refresh available_versions to update installed status
+                        self.available_versions = get_available_versions();
+                        self.refresh_storage_usage();
 
-                    ui.add_space(4.0);
-                    ui.separator();
+                        // Nothing left to show for it
+                        self.download_state.remove(&version);
 
-                    // Show content based on mode
-                    match self.right_mode {
-                        RightSidebarMode::Archive => self.show_archive_panel(ui, theme),
-                        RightSidebarMode::Index => self.show_index_panel(ui, theme),
-                        RightSidebarMode::Sentinel => self.show_sentinel_panel(ui, theme),
-                        RightSidebarMode::Bridge => self.show_bridge_panel(ui, theme),
-                        RightSidebarMode::Forge => self.show_forge_panel(ui, theme),
-                        RightSidebarMode::Modes => self.show_modes_panel(ui, theme),
+                        self.set_status(&format!("✓ Installed v{} - {}", version, hash_status));
                     }
-                });
+                    Err(e) => {
+                        log::error!("Failed to install v{}: {}", version, e);
+                        self.set_status(&format!("✗ Installation failed: {}", e));
+                        self.download_state.insert(
+                            version.clone(),
+                            DownloadState::Failed {
+                                version,
+                                error: format!("Installation failed: {}", e),
+                            },
+                        );
+                    }
+                }
+            }
+            Ok(Err(e)) => {
+                match &proxy {
+                    Some(proxy) => self.set_status(&format!(
+                        "✗ Download of v{} failed (via proxy {}): {}",
+                        version, proxy, e
+                    )),
+                    None => self.set_status(&format!("✗ Download of v{} failed: {}", version, e)),
+                }
+                self.download_state.insert(
+                    version.clone(),
+                    DownloadState::Failed { version, error: e },
+                );
+            }
+            Err(_) => {
+                self.set_status(&format!("✗ Download thread for v{} panicked", version));
+                self.download_state.remove(&version);
+            }
         }
-
-        egui::CentralPanel::default()
-            .frame(egui::Frame::none().fill(theme.editor_bg))
-            .show(ctx, |ui| {
-                self.show_editor_area(ui, theme);
-            });
     }
 }
 
-impl CursorStudio {
-    fn show_activity_bar(&mut self, ui: &mut egui::Ui, theme: Theme) {
-        ui.vertical_centered(|ui| {
-            ui.add_space(12.0);
+/// Host part of a URL, for short status messages
+fn url_host(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    rest.split('/').next().unwrap_or(rest)
+}
 
-            self.activity_button(ui, "📁", "Versions", SidebarMode::Manager, theme);
-            ui.add_space(4.0);
+/// Directory that markdown exports are written to
+fn markdown_export_dir() -> PathBuf {
+    dirs::document_dir()
+        .or_else(|| dirs::home_dir())
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("cursor-studio-exports")
+}
 
-            self.activity_button(ui, "🔎", "Search", SidebarMode::Search, theme);
-            ui.add_space(4.0);
+/// Open a file or folder with the desktop's default application
+fn open_with_default_app(path: &Path) -> std::io::Result<()> {
+    Command::new("xdg-open").arg(path).spawn().map(|_| ())
+}
 
-            self.activity_button(ui, "⚙️", "Settings", SidebarMode::Settings, theme);
+/// Start the user's terminal emulator (`$TERMINAL`, else xterm) in `dir`
+fn open_terminal_in(dir: &Path) -> std::io::Result<()> {
+    let terminal = std::env::var("TERMINAL").unwrap_or_else(|_| "xterm".to_string());
+    Command::new(terminal).current_dir(dir).spawn().map(|_| ())
+}
 
-            ui.add_space(ui.available_height() - 50.0);
+/// Bars of chats started per bucket, with messages as a line on its own scale
+fn draw_activity_chart(
+    ui: &mut egui::Ui,
+    buckets: &[activity::ActivityBucket],
+    bucket: activity::Bucket,
+    theme: Theme,
+) {
+    let (rect, response) =
+        ui.allocate_exact_size(Vec2::new(ui.available_width(), 120.0), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let max_chats = buckets
+        .iter()
+        .map(|b| b.conversations)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let max_messages = buckets.iter().map(|b| b.messages).max().unwrap_or(0).max(1);
+    let slot = rect.width() / buckets.len() as f32;
+    let hovered = response
+        .hover_pos()
+        .map(|pos| ((pos.x - rect.left()) / slot) as usize)
+        .filter(|&i| i < buckets.len());
+
+    painter.line_segment(
+        [rect.left_bottom(), rect.right_bottom()],
+        Stroke::new(1.0, theme.border),
+    );
+    let mut line = Vec::with_capacity(buckets.len());
+    for (i, b) in buckets.iter().enumerate() {
+        let x = rect.left() + slot * (i as f32 + 0.5);
+        let height = rect.height() * b.conversations as f32 / max_chats as f32;
+        let bar = egui::Rect::from_min_max(
+            egui::pos2(x - slot * 0.35, rect.bottom() - height),
+            egui::pos2(x + slot * 0.35, rect.bottom()),
+        );
+        let color = if hovered == Some(i) {
+            theme.accent
+        } else {
+            theme.accent.linear_multiply(0.6)
+        };
+        painter.rect_filled(bar, 2.0, color);
+        let y = rect.bottom() - rect.height() * b.messages as f32 / max_messages as f32;
+        line.push(egui::pos2(x, y));
+    }
+    painter.add(egui::Shape::line(line, Stroke::new(1.5, theme.warning)));
+
+    if let Some(b) = hovered.map(|i| &buckets[i]) {
+        response.on_hover_text(format!(
+            "{} {}\n{} chats • {} messages",
+            bucket.label(),
+            bucket.format(b.start),
+            b.conversations,
+            b.messages
+        ));
+    }
 
-            let right_icon = if self.right_sidebar_visible {
-                "◨"
-            } else {
-                "◧"
-            };
-            let right_tooltip = if self.right_sidebar_visible {
-                "Hide Chat Library"
-            } else {
-                "Show Chat Library"
-            };
+    // Axis ends and legend
+    ui.horizontal(|ui| {
+        let small = |text: String, color: Color32| {
+            RichText::new(text).size(theme.text_size(10.0)).color(color)
+        };
+        if let (Some(first), Some(last)) = (buckets.first(), buckets.last()) {
+            ui.label(small(bucket.format(first.start), theme.fg_dim));
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(small(bucket.format(last.start), theme.fg_dim));
+                ui.add_space(12.0);
+                ui.label(small(
+                    format!("━ Messages (max {})", max_messages),
+                    theme.warning,
+                ));
+                ui.label(small(format!("▇ Chats (max {})", max_chats), theme.accent));
+            });
+        }
+    });
+}
 
-            let btn = ui
-                .add(
-                    egui::Button::new(RichText::new(right_icon).size(18.0).color(
-                        if self.right_sidebar_visible {
-                            theme.accent
-                        } else {
-                            theme.fg_dim
-                        },
-                    ))
-                    .frame(false)
-                    .min_size(Vec2::new(40.0, 40.0)),
-                )
-                .on_hover_text(right_tooltip);
+/// Filesystem-safe file stem derived from a conversation title
+/// What a refresh brought in, e.g. "3 new conversations, 12 new messages"
+fn refresh_summary(conversations: usize, messages: usize) -> String {
+    let mut parts = Vec::new();
+    if conversations > 0 {
+        parts.push(format!(
+            "{} new conversation{}",
+            conversations,
+            if conversations == 1 { "" } else { "s" }
+        ));
+    }
+    if messages > 0 {
+        parts.push(format!(
+            "{} new message{}",
+            messages,
+            if messages == 1 { "" } else { "s" }
+        ));
+    }
+    if parts.is_empty() {
+        "nothing new".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
 
-            if btn.hovered() {
-                ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
-            }
+fn export_file_stem(title: &str) -> String {
+    title
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == ' ' || *c == '-')
+        .take(50)
+        .collect::<String>()
+        .trim()
+        .replace(' ', "_")
+}
 
-            if btn.clicked() {
-                self.right_sidebar_visible = !self.right_sidebar_visible;
-            }
+/// Combine several conversations into one markdown document with a table of contents
+fn combined_markdown(
+    sections: &[(Conversation, Vec<Message>)],
+    template: Option<&export_templates::Template>,
+) -> String {
+    let mut md = String::new();
+
+    md.push_str("# Cursor Chat Export\n\n");
+    md.push_str(&format!(
+        "**Exported:** {}\n",
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+    ));
+    md.push_str(&format!("**Conversations:** {}\n\n", sections.len()));
+
+    md.push_str("## Table of Contents\n\n");
+    for (idx, (conv, messages)) in sections.iter().enumerate() {
+        md.push_str(&format!(
+            "{}. [{}](#conversation-{}) ({} messages)\n",
+            idx + 1,
+            conv.title.replace('[', "(").replace(']', ")"),
+            idx + 1,
+            messages.len()
+        ));
+    }
+    md.push_str("\n---\n\n");
 
-            ui.add_space(8.0);
-        });
+    // Explicit anchors keep TOC links stable regardless of how titles slugify
+    for (idx, (conv, messages)) in sections.iter().enumerate() {
+        md.push_str(&format!("<a id=\"conversation-{}\"></a>\n\n", idx + 1));
+        md.push_str(&render_markdown(conv, messages, template));
+        md.push('\n');
     }
 
-    fn activity_button(
-        &mut self,
-        ui: &mut egui::Ui,
-        icon: &str,
-        tooltip: &str,
-        mode: SidebarMode,
-        theme: Theme,
-    ) {
-        let is_active = self.left_sidebar_visible && self.left_mode == mode;
+    md
+}
 
-        let response = ui
-            .add(
-                egui::Button::new(RichText::new(icon).size(20.0).color(if is_active {
-                    theme.accent
-                } else {
-                    theme.fg_dim
-                }))
-                .frame(false)
-                .min_size(Vec2::new(40.0, 40.0)),
-            )
-            .on_hover_text(tooltip);
+/// Markdown for a conversation, laid out by `template` if one was picked
+fn render_markdown(
+    conv: &Conversation,
+    messages: &[Message],
+    template: Option<&export_templates::Template>,
+) -> String {
+    match template {
+        Some(template) => template.render(conv, messages),
+        None => conversation_to_markdown(conv, messages),
+    }
+}
 
-        if response.hovered() {
-            ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+/// Render a conversation and its messages as a markdown document
+fn conversation_to_markdown(conv: &Conversation, messages: &[Message]) -> String {
+    // Build markdown content
+    let mut md = String::new();
+
+    // Header
+    md.push_str(&format!("# {}\n\n", conv.title));
+    md.push_str(&format!(
+        "**Exported:** {}\n",
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+    ));
+    md.push_str(&format!("**Messages:** {}\n", messages.len()));
+    md.push_str(&format!("**Source:** Cursor Studio v0.2.1\n\n"));
+    if let Some(notes) = &conv.notes {
+        md.push_str(&format!(
+            "**Notes:**\n\n> {}\n\n",
+            notes.replace('\n', "\n> ")
+        ));
+    }
+    md.push_str("---\n\n");
+
+    for msg in messages {
+        // Role header
+        let role_icon = match msg.role {
+            MessageRole::User => "👤 **USER**",
+            MessageRole::Assistant => "🤖 **ASSISTANT**",
+            MessageRole::ToolCall => "🔧 **TOOL CALL**",
+            MessageRole::ToolResult => "📋 **TOOL RESULT**",
+        };
+        md.push_str(&format!("### {}\n\n", role_icon));
+
+        // Tool call info
+        if let Some(ref tc) = msg.tool_call {
+            md.push_str(&format!("> **Tool:** `{}`\n", tc.name));
+            if !tc.args.is_empty() {
+                // Pretty print args if JSON
+                let args_display =
+                    if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(&tc.args) {
+                        serde_json::to_string_pretty(&parsed).unwrap_or_else(|_| tc.args.clone())
+                    } else {
+                        tc.args.clone()
+                    };
+                md.push_str(&format!(
+                    "> ```json\n> {}\n> ```\n",
+                    args_display.replace('\n', "\n> ")
+                ));
+            }
+            md.push_str(&format!("> **Status:** {}\n\n", tc.status));
         }
 
-        if is_active {
-            let rect = response.rect;
-            ui.painter().vline(
-                rect.left() - 4.0,
-                rect.y_range(),
-                Stroke::new(3.0, theme.accent),
-            );
+        // Thinking block
+        if let Some(ref thinking) = msg.thinking {
+            if !thinking.is_empty() {
+                md.push_str("<details>\n<summary>💭 Thinking...</summary>\n\n");
+                md.push_str(thinking);
+                md.push_str("\n\n</details>\n\n");
+            }
         }
 
-        if response.clicked() {
-            if self.left_mode == mode && self.left_sidebar_visible {
-                self.left_sidebar_visible = false;
-            } else {
-                self.left_mode = mode;
-                self.left_sidebar_visible = true;
-            }
+        // Main content
+        if !msg.content.is_empty() {
+            md.push_str(&msg.content);
+            md.push_str("\n");
         }
+
+        md.push_str("\n---\n\n");
     }
 
-    fn show_manager_panel(&mut self, ui: &mut egui::Ui, theme: Theme) {
-        ui.vertical(|ui| {
-            // Header with controls
-            ui.add_space(12.0);
-            ui.horizontal(|ui| {
-                ui.add_space(16.0);
-                ui.label(
-                    RichText::new("VERSIONS")
-                        .size(11.0)
-                        .color(theme.fg_dim)
-                        .strong(),
-                );
+    md
+}
 
-                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    ui.add_space(8.0);
+/// First line of a message, shortened for the branch tree
+fn branch_preview(content: &str) -> String {
+    let line = content.lines().next().unwrap_or("").trim();
+    let short: String = line.chars().take(40).collect();
+    if short.len() < line.len() {
+        format!("{}...", short)
+    } else {
+        short
+    }
+}
 
-                    // Refresh button
-                    let refresh_btn = ui
-                        .add(
-                            egui::Button::new(RichText::new("↻").size(14.0).color(theme.fg_dim))
-                                .frame(false),
-                        )
-                        .on_hover_text("Refresh versions");
-                    if refresh_btn.hovered() {
-                        ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
-                    }
-                    if refresh_btn.clicked() {
-                        self.refresh_versions();
-                    }
+/// Inline conversation title field placed over `rect`.
+///
+/// Returns `Some(save)` once editing ends: Enter or clicking away saves, Escape cancels.
+fn title_editor_ui(
+    ui: &mut egui::Ui,
+    rect: egui::Rect,
+    draft: &mut String,
+    focus_pending: &mut bool,
+) -> Option<bool> {
+    let response = ui.put(
+        rect,
+        egui::TextEdit::singleline(draft).font(egui::FontId::proportional(12.0)),
+    );
+    accessible_label(ui, &response, "Conversation title");
+    if std::mem::take(focus_pending) {
+        response.request_focus();
+    }
+    (response.lost_focus() || (!response.has_focus() && response.clicked_elsewhere()))
+        .then(|| !ui.input(|i| i.key_pressed(egui::Key::Escape)))
+}
 
-                    ui.add_space(4.0);
+/// Archive hover card: full title, then the chat's first question and last answer
+fn conversation_preview_tooltip(
+    ui: &mut egui::Ui,
+    title: &str,
+    preview: Option<&Option<ConversationPreview>>,
+    theme: Theme,
+) {
+    ui.set_max_width(320.0);
+    ui.label(RichText::new(title).color(theme.fg).strong());
+    let Some(Some(preview)) = preview else {
+        ui.label(
+            RichText::new("Loading preview...")
+                .color(theme.fg_dim)
+                .italics()
+                .size(theme.text_size(10.0)),
+        );
+        return;
+    };
 
-                    // Toggle for showing all versions
-                    let toggle_text = if self.show_all_versions {
-                        "All"
-                    } else {
-                        "Installed"
-                    };
-                    let toggle_btn = ui
-                        .add(
-                            egui::Button::new(
-                                RichText::new(toggle_text).size(10.0).color(theme.accent),
-                            )
-                            .frame(false),
-                        )
-                        .on_hover_text("Toggle between installed and all available versions");
+    for (label, text) in [
+        ("👤 First question", &preview.first_user),
+        ("🤖 Last answer", &preview.last_assistant),
+    ] {
+        if let Some(text) = text {
+            ui.add_space(TIGHT_SPACING);
+            ui.label(
+                RichText::new(label)
+                    .color(theme.fg_dim)
+                    .size(theme.text_size(10.0)),
+            );
+            ui.label(RichText::new(text).color(theme.fg).size(theme.text_size(11.0)));
+        }
+    }
+}
 
-                    if toggle_btn.hovered() {
-                        ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
-                    }
+/// Read only the first and last pages of a conversation to find its endpoints
+fn load_conversation_preview(db: &ChatDatabase, conv_id: &str) -> ConversationPreview {
+    let excerpt = |msg: &Message| {
+        let text = msg.content.trim();
+        let short: String = text.chars().take(PREVIEW_MAX_CHARS).collect();
+        if short.len() < text.len() {
+            format!("{}...", short)
+        } else {
+            short
+        }
+    };
+    let has_text = |msg: &&Message| !msg.content.trim().is_empty();
+
+    let head = db
+        .get_messages_page(conv_id, 0, PREVIEW_WINDOW_MESSAGES)
+        .unwrap_or_default();
+    let total = db.count_messages(conv_id).unwrap_or(0);
+    let tail = db
+        .get_messages_page(
+            conv_id,
+            total.saturating_sub(PREVIEW_WINDOW_MESSAGES),
+            PREVIEW_WINDOW_MESSAGES,
+        )
+        .unwrap_or_default();
+
+    ConversationPreview {
+        first_user: head
+            .iter()
+            .filter(has_text)
+            .find(|m| m.role == MessageRole::User)
+            .map(excerpt),
+        last_assistant: tail
+            .iter()
+            .rev()
+            .filter(has_text)
+            .find(|m| m.role == MessageRole::Assistant)
+            .map(excerpt),
+    }
+}
 
-                    if toggle_btn.clicked() {
-                        self.show_all_versions = !self.show_all_versions;
-                        self.set_status(if self.show_all_versions {
-                            "Showing all available versions"
-                        } else {
-                            "Showing installed versions only"
-                        });
-                    }
-                });
-            });
-            ui.add_space(8.0);
+/// Build a prompt that seeds a new Cursor chat with an archived conversation.
+///
+/// Cursor has no way to import a chat, so this condenses the conversation into
+/// something to paste: the original request, the most recent user/assistant
+/// turns that fit in [`CONTINUE_PROMPT_MAX_CHARS`], and the files touched.
+fn continuation_prompt(title: &str, messages: &[Message]) -> String {
+    let turns: Vec<&Message> = messages
+        .iter()
+        .filter(|m| matches!(m.role, MessageRole::User | MessageRole::Assistant))
+        .filter(|m| !m.content.trim().is_empty())
+        .collect();
+
+    let render = |msg: &Message| {
+        let speaker = match msg.role {
+            MessageRole::User => "User",
+            _ => "Assistant",
+        };
+        let content = msg.content.trim();
+        let mut text: String = content.chars().take(CONTINUE_MESSAGE_MAX_CHARS).collect();
+        if text.len() < content.len() {
+            text.push_str(" […]");
+        }
+        format!("**{}:** {}\n\n", speaker, text)
+    };
 
-            // Current launch version indicator
-            let launch_display = Self::version_display_name(&self.launch_version);
-            ui.horizontal(|ui| {
-                ui.add_space(16.0);
-                ui.label(RichText::new("Launch:").size(11.0).color(theme.fg_dim));
+    // Newest turns first until the budget runs out
+    let mut recent = Vec::new();
+    let mut used = 0;
+    for &msg in turns.iter().rev() {
+        let block = render(msg);
+        if used + block.len() > CONTINUE_PROMPT_MAX_CHARS && !recent.is_empty() {
+            break;
+        }
+        used += block.len();
+        recent.push(block);
+    }
+    recent.reverse();
+    let omitted = turns.len() - recent.len();
+
+    let mut prompt = format!(
+        "I'm continuing an earlier Cursor conversation: \"{}\".\n\
+         Read the context below, summarize in a few lines where we left off, \
+         then wait for my next instruction.\n\n",
+        title
+    );
 
-                let launch_btn = ui
-                    .add(
-                        egui::Button::new(
-                            RichText::new(&launch_display)
-                                .size(11.0)
-                                .color(theme.accent),
-                        )
-                        .frame(false),
-                    )
-                    .on_hover_text("Click to change which version to launch");
+    // The opening request is the goal; keep it even when it falls outside the budget
+    let first_request = turns.iter().find(|m| m.role == MessageRole::User);
+    if let Some(&first) = first_request.filter(|_| omitted > 0) {
+        prompt.push_str("## Original request\n\n");
+        prompt.push_str(&render(first));
+    }
 
-                if launch_btn.hovered() {
-                    ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
-                }
+    prompt.push_str("## Conversation\n\n");
+    if omitted > 0 {
+        prompt.push_str(&format!("*[{} earlier messages omitted]*\n\n", omitted));
+    }
+    for block in recent {
+        prompt.push_str(&block);
+    }
 
-                if launch_btn.clicked() {
-                    self.show_launch_picker = !self.show_launch_picker;
-                }
-            });
+    let mut files: Vec<&str> = Vec::new();
+    for file in messages.iter().flat_map(|m| &m.files_edited) {
+        if !files.contains(&file.as_str()) {
+            files.push(file);
+        }
+    }
+    if !files.is_empty() {
+        prompt.push_str("## Files touched\n\n");
+        for file in files {
+            prompt.push_str(&format!("- `{}`\n", file));
+        }
+    }
 
-            // Launch version picker dropdown
-            if self.show_launch_picker {
-                ui.add_space(4.0);
-                egui::Frame::none()
-                    .fill(theme.input_bg)
-                    .rounding(Rounding::same(6.0))
-                    .stroke(Stroke::new(1.0, theme.border))
-                    .inner_margin(egui::Margin::same(6.0))
-                    .show(ui, |ui| {
-                        let versions = self.versions.clone();
-                        let mut selected: Option<String> = None;
+    prompt
+}
 
-                        for version in &versions {
-                            let is_current = version.version == self.launch_version;
-                            let label = Self::version_display_name(&version.version);
+/// Parse a stored timestamp (RFC 3339, or SQLite `datetime()` which is UTC)
+fn parse_timestamp(ts: &str) -> Option<chrono::DateTime<chrono::Local>> {
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(ts) {
+        return Some(dt.with_timezone(&chrono::Local));
+    }
+    chrono::NaiveDateTime::parse_from_str(ts, "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .map(|naive| naive.and_utc().with_timezone(&chrono::Local))
+}
 
-                            let bg = if is_current {
-                                theme.selection
-                            } else {
-                                Color32::TRANSPARENT
-                            };
+/// Compact word count for the sidebar badge (840 -> "840", 12400 -> "12k")
+fn format_word_count(words: usize) -> String {
+    match words {
+        0..=999 => words.to_string(),
+        1_000..=9_999 => format!("{:.1}k", words as f64 / 1000.0),
+        _ => format!("{}k", words / 1000),
+    }
+}
 
-                            egui::Frame::none()
-                                .fill(bg)
-                                .rounding(Rounding::same(4.0))
-                                .inner_margin(egui::Margin::symmetric(8.0, 4.0))
-                                .show(ui, |ui| {
-                                    let btn = ui.add(
-                                        egui::Button::new(
-                                            RichText::new(&label)
-                                                .color(if is_current {
-                                                    theme.accent
-                                                } else {
-                                                    theme.fg
-                                                })
-                                                .size(12.0),
-                                        )
-                                        .frame(false)
-                                        .min_size(Vec2::new(ui.available_width() - 16.0, 22.0)),
-                                    );
+/// Format a count with thousands separators (42000 -> "42,000")
+fn format_count(n: usize) -> String {
+    let digits = n.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
 
-                                    if btn.hovered() {
-                                        ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
-                                    }
+/// Format a timestamp for display as (shown text, absolute time for hover)
+fn format_timestamp(ts: &str, relative: bool) -> Option<(String, String)> {
+    let dt = parse_timestamp(ts)?;
+    let absolute = dt.format("%Y-%m-%d %H:%M").to_string();
+    if !relative {
+        return Some((absolute.clone(), absolute));
+    }
 
-                                    if btn.clicked() {
-                                        selected = Some(version.version.clone());
-                                    }
-                                });
-                        }
+    let secs = (chrono::Local::now() - dt).num_seconds();
+    let shown = if secs < 60 {
+        "just now".to_string()
+    } else if secs < 3600 {
+        format!("{}m ago", secs / 60)
+    } else if secs < 86_400 {
+        format!("{}h ago", secs / 3600)
+    } else if secs < 30 * 86_400 {
+        format!("{}d ago", secs / 86_400)
+    } else {
+        dt.format("%Y-%m-%d").to_string()
+    };
+    Some((shown, absolute))
+}
 
-                        if let Some(ver) = selected {
-                            self.set_launch_version(&ver);
-                        }
-                    });
-            }
+/// Local calendar day a message was sent on, if it carries a timestamp
+fn message_day(msg: &Message) -> Option<chrono::NaiveDate> {
+    msg.created_at
+        .as_deref()
+        .and_then(parse_timestamp)
+        .map(|dt| dt.date_naive())
+}
 
-            ui.add_space(8.0);
+/// Days of the open conversation, parsed once and rebuilt when its messages change
+struct ConversationDays {
+    conversation_id: String,
+    message_count: usize,
+    last_message_id: Option<String>,
+    /// Distinct days as (day, first message id, message count), in message order
+    days: Vec<(chrono::NaiveDate, String, usize)>,
+    /// Day each timestamped message was sent on, for the inline separators
+    message_days: std::collections::HashMap<String, chrono::NaiveDate>,
+}
 
-            // Legend for version icons
-            ui.horizontal(|ui| {
-                ui.add_space(16.0);
-                ui.label(RichText::new("★").color(theme.warning).size(10.0));
-                ui.label(RichText::new("default").color(theme.fg_dim).size(9.0));
-                ui.add_space(8.0);
-                ui.label(RichText::new("●").color(theme.success).size(10.0));
-                ui.label(RichText::new("installed").color(theme.fg_dim).size(9.0));
-                ui.add_space(8.0);
-                ui.label(RichText::new("⬇").color(theme.accent).size(10.0));
-                ui.label(RichText::new("download").color(theme.fg_dim).size(9.0));
-            });
-            ui.add_space(4.0);
+impl ConversationDays {
+    fn new(conversation_id: &str, messages: &[Message]) -> Self {
+        let mut days: Vec<(chrono::NaiveDate, String, usize)> = Vec::new();
+        let mut message_days = std::collections::HashMap::new();
+        for msg in messages {
+            let Some(day) = message_day(msg) else {
+                continue;
+            };
+            message_days.insert(msg.id.clone(), day);
+            match days.iter_mut().find(|(d, _, _)| *d == day) {
+                Some(entry) => entry.2 += 1,
+                None => days.push((day, msg.id.clone(), 1)),
+            }
+        }
+        Self {
+            conversation_id: conversation_id.to_string(),
+            message_count: messages.len(),
+            last_message_id: messages.last().map(|m| m.id.clone()),
+            days,
+            message_days,
+        }
+    }
 
-            // Version list
-            let all_versions = self.get_all_versions();
-            let available_height = ui.available_height() - 180.0; // Adjust for legend
-            let default_ver = self.default_version.clone();
+    /// Whether these are still the days of `messages`
+    fn is_current(&self, conversation_id: &str, messages: &[Message]) -> bool {
+        self.conversation_id == conversation_id
+            && self.message_count == messages.len()
+            && self.last_message_id.as_deref() == messages.last().map(|m| m.id.as_str())
+    }
+}
 
-            egui::ScrollArea::vertical()
-                .max_height(available_height.max(100.0))
-                .show(ui, |ui| {
-                    if all_versions.is_empty() {
-                        ui.horizontal(|ui| {
-                            ui.add_space(16.0);
-                            ui.label(
-                                RichText::new("No Cursor installations found")
-                                    .color(theme.fg_dim)
-                                    .italics(),
-                            );
-                        });
-                    }
+/// Subtle centered date divider between messages from different days
+fn day_separator(ui: &mut egui::Ui, day: chrono::NaiveDate, theme: Theme) {
+    ui.horizontal(|ui| {
+        let label = day.format("%a, %b %-d %Y").to_string();
+        let text_width = 120.0;
+        let line_width = ((ui.available_width() - text_width) / 2.0 - 16.0).max(0.0);
+        let stroke = Stroke::new(1.0, theme.border);
+
+        ui.add_space(16.0);
+        let (rect, _) = ui.allocate_exact_size(Vec2::new(line_width, 12.0), egui::Sense::hover());
+        ui.painter().hline(rect.x_range(), rect.center().y, stroke);
+        ui.add_sized(
+            Vec2::new(text_width, 12.0),
+            egui::Label::new(RichText::new(label).size(theme.text_size(10.0)).color(theme.fg_dim)),
+        );
+        let (rect, _) = ui.allocate_exact_size(Vec2::new(line_width, 12.0), egui::Sense::hover());
+        ui.painter().hline(rect.x_range(), rect.center().y, stroke);
+    });
+}
 
-                    for (version, is_installed) in &all_versions {
-                        let is_default = version == &default_ver
-                            || (version == "default" && default_ver == "default");
+/// Small dim timestamp shown in message headers, with the exact time on hover
+fn timestamp_label(ui: &mut egui::Ui, timestamp: Option<&(String, String)>, theme: Theme) {
+    if let Some((shown, absolute)) = timestamp {
+        ui.label(RichText::new(shown).color(theme.fg_dim).size(theme.text_size(10.0)))
+            .on_hover_text(absolute);
+    }
+}
 
-                        // Check if we have a hash for this version
-                        let has_hash = get_version_info(version)
-                            .map(|v| v.sha256_hash.is_some())
-                            .unwrap_or(false);
+/// Compare version strings (e.g., "2.1.34" > "1.7.43")
+fn version_compare(a: &str, b: &str) -> std::cmp::Ordering {
+    let parse_version =
+        |s: &str| -> Vec<u32> { s.split('.').filter_map(|p| p.parse().ok()).collect() };
 
-                        // Determine background color based on state
-                        let bg_color = if is_default {
-                            theme.selection
-                        } else if !is_installed && has_hash {
-                            // Downloadable with verified hash - subtle highlight
-                            theme.accent.linear_multiply(0.15)
-                        } else {
-                            Color32::TRANSPARENT
-                        };
+    let va = parse_version(a);
+    let vb = parse_version(b);
 
-                        egui::Frame::none()
-                            .fill(bg_color)
-                            .rounding(Rounding::same(4.0))
-                            .inner_margin(egui::Margin::symmetric(4.0, 3.0))
-                            .show(ui, |ui| {
-                                let response = ui
-                                    .horizontal(|ui| {
-                                        ui.add_space(8.0);
+    for (a, b) in va.iter().zip(vb.iter()) {
+        match a.cmp(b) {
+            std::cmp::Ordering::Equal => continue,
+            other => return other,
+        }
+    }
+    va.len().cmp(&vb.len())
+}
 
-                                        // Status icon
-                                        let (icon, icon_color) = if is_default {
-                                            ("★", theme.warning) // Default version
-                                        } else if *is_installed {
-                                            ("●", theme.success) // Installed
-                                        } else if has_hash {
-                                            ("⬇", theme.accent) // Downloadable with hash
-                                        } else {
-                                            ("○", theme.fg_dim.linear_multiply(0.5))
-                                            // No hash
-                                        };
-                                        ui.label(RichText::new(icon).color(icon_color).size(14.0));
+/// Configured launch variables with blank names dropped
+fn launch_env_vars(vars: &[(String, String)]) -> impl Iterator<Item = (&str, &str)> {
+    vars.iter()
+        .map(|(name, value)| (name.trim(), value.as_str()))
+        .filter(|(name, _)| !name.is_empty())
+}
 
-                                        ui.add_space(8.0);
+/// Make sure a frame runs at `deadline`: egui only repaints on input, so
+/// timers checked in `update` would otherwise wait for the mouse to move
+fn schedule_repaint_at(ctx: &egui::Context, deadline: std::time::Instant) {
+    ctx.request_repaint_after(deadline.saturating_duration_since(std::time::Instant::now()));
+}
 
-                                        let label = Self::version_display_name(version);
-                                        let text_color = if is_default {
-                                            theme.fg_bright
-                                        } else if *is_installed {
-                                            theme.fg
-                                        } else if has_hash {
-                                            theme.accent // Downloadable stands out
-                                        } else {
-                                            theme.fg_dim
-                                        };
+// Helper for styled buttons
+fn styled_button(ui: &mut egui::Ui, text: &str, min_size: Vec2) -> egui::Response {
+    let btn = egui::Button::new(RichText::new(text).size(12.0))
+        .min_size(min_size)
+        .rounding(Rounding::same(4.0));
 
-                                        // Build tooltip
-                                        let tooltip = if *is_installed {
-                                            if is_default {
-                                                "✓ Default version"
-                                            } else {
-                                                "Click to set as default"
-                                            }
-                                        } else if has_hash {
-                                            "Click to download (hash verified)"
-                                        } else {
-                                            "Click to download (no hash verification)"
-                                        };
+    let response = ui.add(btn);
+    paint_focus_ring(ui, &response);
 
-                                        let btn = ui
-                                            .add(
-                                                egui::Button::new(
-                                                    RichText::new(&label)
-                                                        .color(text_color)
-                                                        .size(13.0),
-                                                )
-                                                .frame(false),
-                                            )
-                                            .on_hover_text(tooltip);
+    if response.hovered() {
+        ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+    }
 
-                                        if btn.hovered() {
-                                            ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
-                                        }
+    response
+}
 
-                                        // Show additional status indicators
-                                        ui.with_layout(
-                                            egui::Layout::right_to_left(egui::Align::Center),
-                                            |ui| {
-                                                ui.add_space(8.0);
-                                                if !is_installed {
-                                                    if has_hash {
-                                                        ui.label(
-                                                            RichText::new("✓")
-                                                                .color(theme.success)
-                                                                .size(10.0),
-                                                        )
-                                                        .on_hover_text("Hash verified");
-                                                    } else {
-                                                        ui.label(
-                                                            RichText::new("?")
-                                                                .color(theme.warning)
-                                                                .size(10.0),
-                                                        )
-                                                        .on_hover_text(
-                                                            "No hash - download at your own risk",
-                                                        );
-                                                    }
-                                                }
-                                            },
-                                        );
+fn styled_button_accent(
+    ui: &mut egui::Ui,
+    text: &str,
+    min_size: Vec2,
+    theme: Theme,
+) -> egui::Response {
+    let btn = egui::Button::new(RichText::new(text).size(13.0).color(Color32::WHITE))
+        .min_size(min_size)
+        .rounding(Rounding::same(4.0))
+        .fill(theme.accent);
 
-                                        btn
-                                    })
-                                    .inner;
+    let response = ui.add(btn);
+    paint_focus_ring(ui, &response);
 
-                                if response.clicked() {
-                                    if *is_installed {
-                                        self.set_default_version(version);
-                                    } else {
-                                        // Start download for this version
-                                        self.start_download(version);
-                                    }
-                                }
-                            });
+    if response.hovered() {
+        ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+    }
 
-                        ui.add_space(2.0);
-                    }
-                });
+    response
+}
 
-            // Spacer
-            ui.add_space(ui.available_height() - 180.0);
+fn toggle_switch(ui: &mut egui::Ui, on: &mut bool, theme: Theme) -> egui::Response {
+    let desired_size = Vec2::new(40.0, 20.0);
+    let (rect, response) = ui.allocate_exact_size(desired_size, egui::Sense::click());
 
-            // Download status section (if downloading)
-            if let DownloadState::Downloading { progress, version } = &self.download_state {
-                ui.separator();
-                ui.add_space(8.0);
+    if response.clicked() {
+        *on = !*on;
+    }
+    // Announced as a checkbox; the setting's name is the label beside it
+    accessible_toggle(ui, &response, "", *on);
 
-                ui.horizontal(|ui| {
-                    ui.add_space(16.0);
-                    ui.label(
-                        RichText::new("DOWNLOADING")
-                            .size(11.0)
-                            .color(theme.accent)
-                            .strong(),
-                    );
-                });
-                ui.add_space(4.0);
+    if response.hovered() {
+        ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+    }
 
-                ui.horizontal(|ui| {
-                    ui.add_space(16.0);
-                    ui.add(egui::Spinner::new().size(14.0));
-                    ui.add_space(4.0);
-                    ui.label(
-                        RichText::new(format!("v{} - {:.0}%", version, progress))
-                            .color(theme.fg)
-                            .size(11.0),
-                    );
-                });
+    if ui.is_rect_visible(rect) {
+        let how_on = ui.ctx().animate_bool_responsive(response.id, *on);
+        let bg_color = Color32::from_rgb(
+            (60.0 + (theme.accent.r() as f32 - 60.0) * how_on) as u8,
+            (60.0 + (theme.accent.g() as f32 - 60.0) * how_on) as u8,
+            (60.0 + (theme.accent.b() as f32 - 60.0) * how_on) as u8,
+        );
 
-                // Progress bar
-                ui.horizontal(|ui| {
-                    ui.add_space(16.0);
-                    let bar_width = ui.available_width() - 32.0;
-                    let (rect, _) = ui.allocate_exact_size(
-                        Vec2::new(bar_width.max(50.0), 6.0),
-                        egui::Sense::hover(),
-                    );
+        ui.painter()
+            .rect_filled(rect, Rounding::same(10.0), bg_color);
 
-                    // Background
-                    ui.painter()
-                        .rect_filled(rect, Rounding::same(3.0), theme.input_bg);
+        let circle_x = rect.left() + 10.0 + (rect.width() - 20.0) * how_on;
+        let circle_center = egui::pos2(circle_x, rect.center().y);
+        ui.painter()
+            .circle_filled(circle_center, 8.0, Color32::WHITE);
+    }
+    paint_focus_ring(ui, &response);
 
-                    // Progress
-                    let progress_width = rect.width() * (progress / 100.0);
-                    let progress_rect = egui::Rect::from_min_size(
-                        rect.min,
-                        Vec2::new(progress_width, rect.height()),
-                    );
-                    ui.painter()
-                        .rect_filled(progress_rect, Rounding::same(3.0), theme.accent);
-                });
+    response
+}
 
-                ui.add_space(4.0);
+/// A path typed by the user, with a leading `~/` expanded to the home directory
+fn expand_home(input: &str) -> PathBuf {
+    match input.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir().unwrap_or_default().join(rest),
+        None => PathBuf::from(input),
+    }
+}
 
-                // Request repaint while downloading
-                ui.ctx().request_repaint();
-            }
+/// Give an icon-only widget an accessible name, so screen readers announce
+/// `label` instead of reading out the emoji
+fn accessible_label(ui: &egui::Ui, response: &egui::Response, label: &str) {
+    let enabled = ui.is_enabled();
+    response.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Button, enabled, label));
+}
 
-            // Download failed section with recovery options
-            if let DownloadState::Failed { version, error } = &self.download_state {
-                ui.separator();
-                ui.add_space(8.0);
+/// Like [`accessible_label`] for icon buttons that switch something on or off
+fn accessible_toggle(ui: &egui::Ui, response: &egui::Response, label: &str, selected: bool) {
+    let enabled = ui.is_enabled();
+    response.widget_info(|| {
+        egui::WidgetInfo::selected(egui::WidgetType::Checkbox, enabled, selected, label)
+    });
+}
 
-                ui.horizontal(|ui| {
-                    ui.add_space(16.0);
+/// Outline the widget that has keyboard focus. Frameless and custom-painted
+/// widgets otherwise look the same whether or not they are focused.
+fn paint_focus_ring(ui: &egui::Ui, response: &egui::Response) {
+    if response.has_focus() {
+        // Set to the theme accent in `update`
+        let color = ui.visuals().widgets.active.bg_fill;
+        ui.painter().rect_stroke(
+            response.rect.expand(2.0),
+            Rounding::same(4.0),
+            Stroke::new(2.0, color),
+        );
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// PANEL LAYOUT HELPERS - Consistent UI across all panels
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Render a panel section header (e.g., "SOURCES", "SECURITY")
+fn panel_header(ui: &mut egui::Ui, label: &str, theme: Theme) {
+    ui.horizontal(|ui| {
+        ui.add_space(PANEL_PADDING);
+        ui.label(
+            RichText::new(label)
+                .size(theme.text_size(10.0))
+                .color(theme.fg_dim)
+                .strong(),
+        );
+    });
+}
+
+/// Render a panel section header with icon
+fn panel_header_with_icon(ui: &mut egui::Ui, icon: &str, label: &str, theme: Theme) {
+    ui.horizontal(|ui| {
+        ui.add_space(PANEL_PADDING);
+        ui.label(
+            RichText::new(format!("{} {}", icon, label))
+                .size(theme.text_size(11.0))
+                .color(theme.accent)
+                .strong(),
+        );
+    });
+}
+
+/// Render a stat card with icon, value, and label
+fn stat_card(
+    ui: &mut egui::Ui,
+    icon: &str,
+    value: &str,
+    label: &str,
+    theme: Theme,
+    density: Density,
+    width: f32,
+) {
+    let margin = density.card_margin();
+    egui::Frame::none()
+        .fill(theme.code_bg)
+        .rounding(Rounding::same(CARD_ROUNDING))
+        .inner_margin(egui::Margin::symmetric(margin, margin - 2.0))
+        .show(ui, |ui| {
+            ui.set_width(width);
+            ui.horizontal(|ui| {
+                ui.label(RichText::new(icon).size(16.0));
+                ui.add_space(TIGHT_SPACING);
+                ui.vertical(|ui| {
                     ui.label(
-                        RichText::new("⚠ DOWNLOAD FAILED")
-                            .size(11.0)
-                            .color(theme.error)
+                        RichText::new(value)
+                            .size(14.0)
+                            .color(theme.fg)
                             .strong(),
                     );
-                });
-                ui.add_space(4.0);
-
-                ui.horizontal(|ui| {
-                    ui.add_space(16.0);
                     ui.label(
-                        RichText::new(format!("v{}: {}", version, error))
-                            .color(theme.fg_dim)
-                            .size(10.0),
+                        RichText::new(label)
+                            .size(theme.text_size(9.0))
+                            .color(theme.fg_dim),
                     );
                 });
-                ui.add_space(8.0);
+            });
+        });
+}
 
-                // Show manual download URL
-                if let Some(version_info) = get_version_info(version) {
-                    ui.horizontal(|ui| {
-                        ui.add_space(16.0);
-                        ui.label(
-                            RichText::new("Manual download:")
-                                .color(theme.fg_dim)
-                                .size(10.0),
-                        );
-                    });
-                    ui.add_space(2.0);
+/// Create a card frame with standard styling
+/// Sentinel panel (icon, label, color, rows shown) for a kind of chat finding
+fn secret_kind_style(
+    theme: Theme,
+    kind: security::SecretKind,
+) -> (&'static str, &'static str, Color32, usize) {
+    match kind {
+        security::SecretKind::ApiKey => ("🔑", "API Keys", theme.warning, 5),
+        security::SecretKind::Password => ("🔒", "Passwords", theme.error, 3),
+        security::SecretKind::Secret => ("🔐", "Secrets", theme.warning, 3),
+    }
+}
 
-                    // URL display with copy button
-                    egui::Frame::none()
-                        .fill(theme.code_bg)
-                        .rounding(Rounding::same(4.0))
-                        .inner_margin(egui::Margin::symmetric(8.0, 4.0))
-                        .show(ui, |ui| {
-                            ui.horizontal(|ui| {
-                                ui.add_space(8.0);
-                                let url = &version_info.download_url;
+/// Findings listed together in the Sentinel panel:
+/// (icon, label, color, rows shown, findings)
+type FindingGroup<'a> = (
+    &'a str,
+    &'a str,
+    Color32,
+    usize,
+    Vec<(security::SecretKind, &'a security::ChatFinding)>,
+);
+
+fn severity_color(theme: Theme, severity: security::FindingSeverity) -> Color32 {
+    match severity {
+        security::FindingSeverity::High => theme.error,
+        security::FindingSeverity::Medium => theme.warning,
+        security::FindingSeverity::Low => theme.accent,
+    }
+}
 
-                                // Truncate URL for display
-                                let display_url = if url.len() > 40 {
-                                    format!("{}...", &url[..40])
-                                } else {
-                                    url.clone()
-                                };
+fn card_frame(theme: Theme, density: Density) -> egui::Frame {
+    egui::Frame::none()
+        .fill(theme.code_bg)
+        .rounding(Rounding::same(CARD_ROUNDING))
+        .inner_margin(egui::Margin::same(density.card_margin()))
+}
 
-                                ui.label(
-                                    RichText::new(&display_url)
-                                        .color(theme.accent)
-                                        .size(9.0)
-                                        .family(egui::FontFamily::Monospace),
-                                );
+/// Sync capabilities as (name, compiled in, cargo feature that enables it)
+fn sync_capabilities() -> [(&'static str, bool, &'static str); 3] {
+    [
+        ("Elixir sync daemon", true, ""),
+        ("Server sync", SERVER_SYNC_AVAILABLE, "surrealdb-store"),
+        (
+            "P2P sync",
+            cfg!(all(feature = "p2p-sync", feature = "surrealdb-store")),
+            "p2p-sync",
+        ),
+    ]
+}
 
-                                ui.with_layout(
-                                    egui::Layout::right_to_left(egui::Align::Center),
-                                    |ui| {
-                                        if ui
-                                            .add(
-                                                egui::Button::new(RichText::new("📋").size(12.0))
-                                                    .frame(false),
-                                            )
-                                            .on_hover_text("Copy URL to clipboard")
-                                            .clicked()
-                                        {
-                                            ui.ctx().copy_text(url.clone());
-                                            // Note: set_status is not available in this scope, status shown via tooltip
-                                        }
-                                    },
-                                );
-                            });
-                        });
+/// Create an accent-tinted card frame
+fn accent_card_frame(theme: Theme, density: Density) -> egui::Frame {
+    egui::Frame::none()
+        .fill(theme.accent.gamma_multiply(0.15))
+        .rounding(Rounding::same(CARD_ROUNDING))
+        .inner_margin(egui::Margin::same(density.card_margin()))
+}
 
-                    ui.add_space(4.0);
+/// Create a warning card frame
+fn warning_card_frame(theme: Theme, density: Density) -> egui::Frame {
+    egui::Frame::none()
+        .fill(theme.warning.gamma_multiply(0.15))
+        .rounding(Rounding::same(CARD_ROUNDING))
+        .inner_margin(egui::Margin::same(density.card_margin()))
+        .stroke(Stroke::new(1.0, theme.warning.gamma_multiply(0.3)))
+}
 
-                    // Instructions
-                    ui.horizontal(|ui| {
-                        ui.add_space(16.0);
-                        ui.label(
-                            RichText::new("Download manually, then use CLI:")
-                                .color(theme.fg_dim)
-                                .size(9.0),
-                        );
-                    });
+impl eframe::App for CursorStudio {
+    /// Save settings when the app is about to exit
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        log::info!("Saving settings on exit...");
+        self.save_settings();
+        log::info!("Settings saved successfully");
+    }
 
-                    egui::Frame::none()
-                        .fill(theme.code_bg)
-                        .rounding(Rounding::same(4.0))
-                        .inner_margin(egui::Margin::symmetric(8.0, 4.0))
-                        .show(ui, |ui| {
-                            ui.horizontal(|ui| {
-                                ui.add_space(8.0);
-                                let cmd = format!("cursor-studio-cli import <file> -v {}", version);
-                                ui.label(
-                                    RichText::new(&cmd)
-                                        .color(theme.fg)
-                                        .size(9.0)
-                                        .family(egui::FontFamily::Monospace),
-                                );
-                            });
-                        });
-                }
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // Poll background threads for completion
+        self.check_download_progress();
+        self.advance_download_queue();
+        self.poll_download_plan();
+        self.poll_import_preview(ctx);
+        self.maybe_auto_import(ctx);
+        self.maybe_autosave_settings(ctx);
+        self.poll_security_scan();
+        self.poll_storage_usage(ctx);
+        self.poll_cache_move(ctx);
+        self.poll_diagnostics();
+        self.poll_version_verification();
+        self.poll_npm_scan();
+        self.sync_daemon_panel.poll(ctx);
+        self.track_idle_lock(ctx);
+        self.poll_cursor_theme(ctx);
 
-                ui.add_space(4.0);
+        // Clean up expired approval requests
+        self.approval_manager.cleanup_expired();
 
-                // Retry and dismiss buttons
-                let version_clone = version.clone();
-                ui.horizontal(|ui| {
-                    ui.add_space(16.0);
-                    if styled_button(ui, "🔄 Retry", Vec2::new(70.0, 24.0)).clicked() {
-                        self.download_state = DownloadState::Idle;
-                        self.start_download(&version_clone);
-                    }
-                    ui.add_space(4.0);
-                    if styled_button(ui, "✕ Dismiss", Vec2::new(70.0, 24.0)).clicked() {
-                        self.download_state = DownloadState::Idle;
-                    }
-                });
-                ui.add_space(4.0);
-            }
+        // Re-collected while the panels render, used by F6 at the end of the frame
+        self.focus_anchors.clear();
 
-            // Actions section
-            ui.separator();
-            ui.add_space(8.0);
+        self.update_theme_preview(ctx);
+        // Themes carry the minimum text size down to every label drawn with them
+        self.theme = self.theme.with_min_text_size(self.min_text_size);
+        let theme = match &self.theme_preview {
+            Some((_, Some(preview))) => preview.with_min_text_size(self.min_text_size),
+            _ => self.theme,
+        };
 
-            ui.horizontal(|ui| {
-                ui.add_space(16.0);
-                ui.label(
-                    RichText::new("ACTIONS")
-                        .size(11.0)
-                        .color(theme.fg_dim)
-                        .strong(),
-                );
-            });
-            ui.add_space(8.0);
+        let mut visuals = if theme.bg.r() > 128 {
+            egui::Visuals::light()
+        } else {
+            egui::Visuals::dark()
+        };
+        visuals.panel_fill = theme.bg;
+        visuals.window_fill = theme.sidebar_bg;
+        visuals.faint_bg_color = theme.input_bg;
+        visuals.extreme_bg_color = theme.code_bg;
+        visuals.widgets.noninteractive.bg_fill = theme.sidebar_bg;
+        visuals.widgets.inactive.bg_fill = theme.input_bg;
+        visuals.widgets.hovered.bg_fill = theme.list_hover;
+        visuals.widgets.active.bg_fill = theme.accent;
+        visuals.selection.bg_fill = theme.selection;
+        ctx.set_visuals(visuals);
 
-            ui.horizontal(|ui| {
-                ui.add_space(12.0);
-                if styled_button_accent(ui, "▶ Launch", Vec2::new(90.0, 28.0), theme).clicked() {
-                    self.launch_cursor();
-                }
-            });
-            ui.add_space(4.0);
+        // Apply font scaling - base is 1.0 (default), range 0.8-1.5
+        // egui uses pixels_per_point for DPI/scale - we modify it relative to native
+        let native_ppp = ctx.native_pixels_per_point().unwrap_or(1.0);
+        ctx.set_pixels_per_point(native_ppp * self.font_scale);
 
-            ui.horizontal(|ui| {
-                ui.add_space(12.0);
-                if styled_button(ui, "⚡ Sync All", Vec2::new(90.0, 28.0))
-                    .on_hover_text("Sync settings from default to all versions")
-                    .clicked()
-                {
-                    self.do_sync();
-                }
+        self.handle_privacy_hotkey(ctx);
+        if self.privacy_screen {
+            self.show_privacy_screen(ctx, theme);
+            return;
+        }
+        if self.idle_locked {
+            self.show_idle_lock(ctx, theme);
+            return;
+        }
+        self.handle_sidebar_hotkeys(ctx);
+        // Before the panels, so arrows and Enter reach the switcher while it's open
+        self.handle_quick_switcher_keys(ctx);
+
+        egui::SidePanel::left("activity_bar")
+            .exact_width(48.0)
+            .resizable(false)
+            .frame(egui::Frame::none().fill(theme.activitybar_bg))
+            .show(ctx, |ui| {
+                self.show_activity_bar(ui, theme);
             });
-            ui.add_space(8.0);
-        });
-    }
 
-    /// # TODO(P1): Release v0.3.0 - Global Search
-    /// - [ ] Add global_search() to search across ALL conversations
-    /// - [ ] Add filter buttons: All | 👤 User | 🤖 AI | 🔧 Tools
-    /// - [ ] Add date range filter
-    /// - [ ] Add "Jump to" buttons for search results
-    /// - [ ] Show conversation title in results
-    /// - [ ] Highlight matching text in results
-    fn show_search_panel(&mut self, ui: &mut egui::Ui, theme: Theme) {
-        ui.vertical(|ui| {
-            ui.add_space(12.0);
-            ui.horizontal(|ui| {
-                ui.add_space(16.0);
-                ui.label(
-                    RichText::new("SEARCH CHATS")
-                        .size(11.0)
-                        .color(theme.fg_dim)
-                        .strong(),
-                );
+        egui::TopBottomPanel::bottom("status_bar")
+            .exact_height(24.0)
+            .frame(
+                egui::Frame::none()
+                    .fill(theme.statusbar_bg)
+                    .inner_margin(egui::Margin::symmetric(12.0, 4.0)),
+            )
+            .show(ctx, |ui| {
+                self.show_status_bar(ui);
             });
-            ui.add_space(8.0);
 
-            ui.horizontal(|ui| {
-                ui.add_space(12.0);
-                let response = ui.add(
-                    egui::TextEdit::singleline(&mut self.search_query)
-                        .hint_text("Type to search...")
-                        .desired_width(ui.available_width() - 24.0)
-                        .margin(egui::Margin::symmetric(8.0, 6.0)),
-                );
+        if self.log_viewer_open {
+            egui::TopBottomPanel::bottom("log_viewer")
+                .resizable(true)
+                .default_height(180.0)
+                .height_range(80.0..=500.0)
+                .frame(
+                    egui::Frame::none()
+                        .fill(theme.sidebar_bg)
+                        .inner_margin(egui::Margin::symmetric(12.0, 6.0)),
+                )
+                .show(ctx, |ui| {
+                    self.show_log_viewer(ui, theme);
+                });
+        }
 
-                if response.changed() {
-                    if !self.search_query.is_empty() {
-                        self.search_results = self
-                            .db
-                            .search_conversations(&self.search_query)
-                            .unwrap_or_default();
-                    } else {
-                        self.search_results.clear();
+        if self.left_sidebar_visible {
+            egui::SidePanel::left("left_sidebar")
+                .default_width(self.left_sidebar_width)
+                .width_range(200.0..=500.0)
+                .resizable(true)
+                .frame(
+                    egui::Frame::none()
+                        .fill(theme.sidebar_bg)
+                        .inner_margin(egui::Margin::ZERO),
+                )
+                .show(ctx, |ui| {
+                    self.left_sidebar_width = ui.available_width();
+
+                    match self.left_mode {
+                        SidebarMode::Manager => self.show_manager_panel(ui, theme),
+                        SidebarMode::Search => self.show_search_panel(ui, theme),
+                        SidebarMode::Settings => self.show_settings_panel(ui, theme),
                     }
-                }
-            });
-            ui.add_space(8.0);
+                });
+        }
 
-            let results = self.search_results.clone();
-            let mut to_open: Option<String> = None;
+        if self.right_sidebar_visible {
+            egui::SidePanel::right("right_sidebar")
+                .default_width(self.right_sidebar_width)
+                .width_range(200.0..=500.0)
+                .resizable(true)
+                .frame(
+                    egui::Frame::none()
+                        .fill(theme.sidebar_bg)
+                        .inner_margin(egui::Margin::ZERO),
+                )
+                .show(ctx, |ui| {
+                    self.right_sidebar_width = ui.available_width();
 
-            if !results.is_empty() {
-                ui.horizontal(|ui| {
-                    ui.add_space(16.0);
-                    ui.label(
-                        RichText::new(format!("{} results", results.len()))
-                            .size(11.0)
-                            .color(theme.fg_dim),
-                    );
-                });
-                ui.add_space(4.0);
+                    // Mode selector header (VS Code style)
+                    ui.horizontal(|ui| {
+                        ui.add_space(8.0);
 
-                egui::ScrollArea::vertical().show(ui, |ui| {
-                    for conv in &results {
-                        ui.horizontal(|ui| {
-                            ui.add_space(12.0);
+                        let modes = [
+                            ("📚", "Archive (Chat History)", RightSidebarMode::Archive),
+                            ("🛡️", "Sentinel (Security)", RightSidebarMode::Sentinel),
+                            ("🔗", "Bridge (Sync)", RightSidebarMode::Bridge),
+                            ("📖", "Index (Documentation)", RightSidebarMode::Index),
+                            ("🔥", "Forge (Data Transform)", RightSidebarMode::Forge),
+                            ("🎭", "Modes (Custom Modes)", RightSidebarMode::Modes),
+                        ];
+                        for (icon, tooltip, mode) in modes {
+                            self.right_mode_button(ui, icon, tooltip, mode, theme);
+                        }
 
-                            let row = ui.add(
-                                egui::Button::new(
-                                    RichText::new(&conv.title).color(theme.fg).size(12.0),
-                                )
-                                .frame(false)
-                                .min_size(Vec2::new(ui.available_width() - 24.0, 24.0)),
+                        // Underline indicator for selected mode
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            ui.add_space(8.0);
+                            let mode_label = match self.right_mode {
+                                RightSidebarMode::Archive => "ARCHIVE",
+                                RightSidebarMode::Index => "INDEX",
+                                RightSidebarMode::Sentinel => "SENTINEL",
+                                RightSidebarMode::Bridge => "BRIDGE",
+                                RightSidebarMode::Forge => "FORGE",
+                                RightSidebarMode::Modes => "MODES",
+                            };
+                            ui.label(
+                                RichText::new(mode_label)
+                                    .size(theme.text_size(10.0))
+                                    .color(theme.fg_dim)
+                                    .strong(),
                             );
+                        });
+                    });
 
-                            if row.hovered() {
-                                ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
-                            }
+                    ui.add_space(4.0);
+                    ui.separator();
 
-                            if row.clicked() {
-                                to_open = Some(conv.id.clone());
-                            }
-                        });
+                    // Show content based on mode
+                    match self.right_mode {
+                        RightSidebarMode::Archive => self.show_archive_panel(ui, theme),
+                        RightSidebarMode::Index => self.show_index_panel(ui, theme),
+                        RightSidebarMode::Sentinel => self.show_sentinel_panel(ui, theme),
+                        RightSidebarMode::Bridge => self.show_bridge_panel(ui, theme),
+                        RightSidebarMode::Forge => self.show_forge_panel(ui, theme),
+                        RightSidebarMode::Modes => self.show_modes_panel(ui, theme),
                     }
                 });
-            } else if !self.search_query.is_empty() {
-                ui.horizontal(|ui| {
-                    ui.add_space(16.0);
-                    ui.label(
-                        RichText::new("No results found")
-                            .color(theme.fg_dim)
-                            .italics(),
-                    );
-                });
-            } else {
-                ui.horizontal(|ui| {
-                    ui.add_space(16.0);
-                    ui.label(
-                        RichText::new("Enter a search term")
-                            .color(theme.fg_dim)
-                            .italics(),
-                    );
-                });
-            }
+        }
 
-            if let Some(id) = to_open {
-                self.open_conversation(&id);
-            }
-        });
+        egui::CentralPanel::default()
+            .frame(egui::Frame::none().fill(theme.editor_bg))
+            .show(ctx, |ui| {
+                self.show_editor_area(ui, theme);
+            });
+
+        self.handle_focus_region_keys(ctx);
+        self.show_quick_switcher(ctx, theme);
+        self.show_onboarding(ctx, theme);
+        self.show_install_detection(ctx, theme);
+        self.show_large_conversation_prompt(ctx, theme);
+        self.show_status_history_popup(ctx, theme);
+        self.show_toasts(ctx, theme);
     }
+}
 
-    fn show_settings_panel(&mut self, ui: &mut egui::Ui, theme: Theme) {
-        // Wrap entire settings panel in ScrollArea for long content
-        egui::ScrollArea::vertical()
-            .auto_shrink([false; 2])
-            .show(ui, |ui| {
-            ui.add_space(12.0);
-            ui.horizontal(|ui| {
-                ui.add_space(16.0);
-                ui.label(
-                    RichText::new("APPEARANCE")
-                        .size(11.0)
-                        .color(theme.fg_dim)
-                        .strong(),
-                );
-            });
+impl CursorStudio {
+    fn show_activity_bar(&mut self, ui: &mut egui::Ui, theme: Theme) {
+        ui.vertical_centered(|ui| {
             ui.add_space(12.0);
 
-            // Theme dropdown
-            self.settings_dropdown_ui(
-                ui,
-                theme,
-                "Theme",
-                &self.current_theme_name.clone(),
-                "show_theme",
-            );
-
-            if self.show_theme_picker {
-                ui.add_space(4.0);
-                egui::Frame::none()
-                    .fill(theme.input_bg)
-                    .rounding(Rounding::same(6.0))
-                    .stroke(Stroke::new(1.0, theme.border))
-                    .inner_margin(egui::Margin::same(6.0))
-                    .show(ui, |ui| {
-                        // Refresh button at top
-                        ui.horizontal(|ui| {
-                            ui.label(RichText::new("Themes").color(theme.fg_dim).size(10.0));
-                            ui.with_layout(
-                                egui::Layout::right_to_left(egui::Align::Center),
-                                |ui| {
-                                    if ui
-                                        .add(
-                                            egui::Button::new(
-                                                RichText::new("↻").color(theme.fg_dim).size(12.0),
-                                            )
-                                            .frame(false),
-                                        )
-                                        .on_hover_text("Refresh theme list from disk")
-                                        .clicked()
-                                    {
-                                        let old_count = self.available_themes.len();
-                                        self.available_themes = Self::find_vscode_themes();
-                                        let new_count = self.available_themes.len();
-                                        self.set_status(&format!(
-                                            "✓ Found {} themes (was {})",
-                                            new_count, old_count
-                                        ));
-                                        // Force UI refresh
-                                        ui.ctx().request_repaint();
-                                    }
-                                },
-                            );
-                        });
-                        ui.add_space(4.0);
-                        ui.separator();
-                        ui.add_space(4.0);
+            self.activity_button(ui, "📁", "Versions", SidebarMode::Manager, theme);
+            ui.add_space(4.0);
 
-                        // Scrollable theme list
-                        let max_height = 300.0;
-                        egui::ScrollArea::vertical()
-                            .max_height(max_height)
-                            .auto_shrink([false; 2])
-                            .show(ui, |ui| {
-                                let themes = self.available_themes.clone();
-                                let mut selected_theme: Option<(String, Option<PathBuf>)> = None;
+            self.activity_button(ui, "🔎", "Search", SidebarMode::Search, theme);
+            ui.add_space(4.0);
 
-                                for (theme_name, theme_path) in &themes {
-                                    let is_current = &self.current_theme_name == theme_name;
-                                    let is_hovered =
-                                        self.hovered_theme.as_ref() == Some(theme_name);
+            self.activity_button(ui, "⚙️", "Settings", SidebarMode::Settings, theme);
 
-                                    let bg = if is_current {
-                                        theme.selection
-                                    } else if is_hovered {
-                                        theme.list_hover
-                                    } else {
-                                        Color32::TRANSPARENT
-                                    };
+            ui.add_space(ui.available_height() - 50.0);
 
-                                    egui::Frame::none()
-                                        .fill(bg)
-                                        .rounding(Rounding::same(4.0))
-                                        .inner_margin(egui::Margin::symmetric(8.0, 4.0))
-                                        .show(ui, |ui| {
-                                            let btn = ui.add(
-                                                egui::Button::new(
-                                                    RichText::new(theme_name)
-                                                        .color(if is_current {
-                                                            theme.accent
-                                                        } else {
-                                                            theme.fg
-                                                        })
-                                                        .size(12.0),
-                                                )
-                                                .frame(false)
-                                                .min_size(Vec2::new(
-                                                    ui.available_width() - 16.0,
-                                                    22.0,
-                                                )),
-                                            );
+            let right_icon = if self.right_sidebar_visible {
+                "◨"
+            } else {
+                "◧"
+            };
+            let right_tooltip = if self.right_sidebar_visible {
+                "Hide Chat Library (Ctrl+Shift+B)"
+            } else {
+                "Show Chat Library (Ctrl+Shift+B)"
+            };
 
-                                            if btn.hovered() {
-                                                ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
-                                                self.hovered_theme = Some(theme_name.clone());
-                                            }
+            let btn = ui
+                .add(
+                    egui::Button::new(RichText::new(right_icon).size(18.0).color(
+                        if self.right_sidebar_visible {
+                            theme.accent
+                        } else {
+                            theme.fg_dim
+                        },
+                    ))
+                    .frame(false)
+                    .min_size(Vec2::new(40.0, 40.0)),
+                )
+                .on_hover_text(right_tooltip);
+            accessible_toggle(ui, &btn, "Chat Library", self.right_sidebar_visible);
+            paint_focus_ring(ui, &btn);
 
-                                            if btn.clicked() {
-                                                selected_theme =
-                                                    Some((theme_name.clone(), theme_path.clone()));
-                                            }
-                                        });
-                                }
+            if btn.hovered() {
+                ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+            }
 
-                                if let Some((name, path)) = selected_theme {
-                                    self.apply_theme(&name, path.as_ref());
-                                }
-                            });
-                    });
-            } else {
-                self.hovered_theme = None;
+            if btn.clicked() {
+                self.right_sidebar_visible = !self.right_sidebar_visible;
             }
-            ui.add_space(12.0);
 
-            // Default Version dropdown
-            let version_display = Self::version_display_name(&self.default_version);
-            self.settings_dropdown_ui(
-                ui,
-                theme,
-                "Default Version",
-                &version_display,
-                "show_version",
-            );
+            ui.add_space(8.0);
+        });
+    }
 
-            if self.show_version_picker {
-                ui.add_space(4.0);
-                egui::Frame::none()
-                    .fill(theme.input_bg)
-                    .rounding(Rounding::same(6.0))
-                    .stroke(Stroke::new(1.0, theme.border))
-                    .inner_margin(egui::Margin::same(6.0))
-                    .show(ui, |ui| {
-                        let versions = self.versions.clone();
-                        let mut selected: Option<String> = None;
+    fn activity_button(
+        &mut self,
+        ui: &mut egui::Ui,
+        icon: &str,
+        tooltip: &str,
+        mode: SidebarMode,
+        theme: Theme,
+    ) {
+        let is_active = self.left_sidebar_visible && self.left_mode == mode;
 
-                        for version in &versions {
-                            let is_current = version.version == self.default_version;
-                            let label = Self::version_display_name(&version.version);
+        let response = ui
+            .add(
+                egui::Button::new(RichText::new(icon).size(20.0).color(if is_active {
+                    theme.accent
+                } else {
+                    theme.fg_dim
+                }))
+                .frame(false)
+                .min_size(Vec2::new(40.0, 40.0)),
+            )
+            .on_hover_text(tooltip);
+        self.mark_focus_anchor(FocusRegion::ActivityBar, &response);
+        accessible_toggle(ui, &response, tooltip, is_active);
+        paint_focus_ring(ui, &response);
 
-                            let bg = if is_current {
-                                theme.selection
-                            } else {
-                                Color32::TRANSPARENT
-                            };
+        if response.hovered() {
+            ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+        }
 
-                            egui::Frame::none()
-                                .fill(bg)
-                                .rounding(Rounding::same(4.0))
-                                .inner_margin(egui::Margin::symmetric(8.0, 4.0))
-                                .show(ui, |ui| {
-                                    let btn = ui.add(
-                                        egui::Button::new(
-                                            RichText::new(&label)
-                                                .color(if is_current {
-                                                    theme.accent
-                                                } else {
-                                                    theme.fg
-                                                })
-                                                .size(12.0),
-                                        )
-                                        .frame(false)
-                                        .min_size(Vec2::new(ui.available_width() - 16.0, 22.0)),
-                                    );
-
-                                    if btn.hovered() {
-                                        ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
-                                    }
-
-                                    if btn.clicked() {
-                                        selected = Some(version.version.clone());
-                                    }
-                                });
-                        }
+        if is_active {
+            let rect = response.rect;
+            ui.painter().vline(
+                rect.left() - 4.0,
+                rect.y_range(),
+                Stroke::new(3.0, theme.accent),
+            );
+        }
 
-                        if let Some(ver) = selected {
-                            self.set_default_version(&ver);
-                        }
-                    });
+        if response.clicked() {
+            if self.left_mode == mode && self.left_sidebar_visible {
+                self.left_sidebar_visible = false;
+            } else {
+                self.left_mode = mode;
+                self.left_sidebar_visible = true;
             }
+        }
+    }
 
-            ui.add_space(20.0);
-            ui.horizontal(|ui| {
-                ui.add_space(16.0);
-                ui.label(
-                    RichText::new("BEHAVIOR")
-                        .size(11.0)
-                        .color(theme.fg_dim)
-                        .strong(),
-                );
-            });
-            ui.add_space(12.0);
+    /// Icon button in the right sidebar's mode switcher
+    fn right_mode_button(
+        &mut self,
+        ui: &mut egui::Ui,
+        icon: &str,
+        tooltip: &str,
+        mode: RightSidebarMode,
+        theme: Theme,
+    ) {
+        let color = if self.right_mode == mode {
+            theme.accent
+        } else {
+            theme.fg_dim
+        };
+        let response = ui
+            .add(
+                egui::Button::new(RichText::new(icon).size(16.0).color(color))
+                    .frame(false)
+                    .min_size(Vec2::new(32.0, 28.0)),
+            )
+            .on_hover_text(tooltip);
+        self.mark_focus_anchor(FocusRegion::RightSidebar, &response);
+        // "Archive (Chat History)" is announced as "Archive"
+        let name = tooltip.split(" (").next().unwrap_or(tooltip);
+        accessible_toggle(ui, &response, name, self.right_mode == mode);
+        paint_focus_ring(ui, &response);
 
-            // Toggles
-            self.settings_toggle_ui(
-                ui,
-                theme,
-                "Auto Sync",
-                "Sync settings between versions automatically",
-                "auto_sync",
-            );
-            ui.add_space(8.0);
+        if response.clicked() {
+            self.right_mode = mode;
+        }
+        if response.hovered() {
+            ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+        }
+    }
 
-            self.settings_toggle_ui(
-                ui,
-                theme,
-                "Import on Start",
-                "Import new chats when app starts",
-                "import_on_start",
-            );
-            ui.add_space(8.0);
+    /// Remember the first focusable widget of a region as the place F6 jumps to
+    fn mark_focus_anchor(&mut self, region: FocusRegion, response: &egui::Response) {
+        if !self.focus_anchors.iter().any(|(r, _)| *r == region) {
+            self.focus_anchors.push((region, response.id));
+        }
+    }
 
-            self.settings_toggle_ui(
-                ui,
-                theme,
-                "Show All Versions",
-                "Include downloadable versions in list",
-                "show_all_versions",
-            );
-            ui.add_space(12.0);
+    /// VS Code's layout keys: Ctrl+B toggles the left sidebar, Ctrl+Shift+B the
+    /// right one, and Ctrl+Shift+F opens Search with the query box focused.
+    /// Ignored while a text field has focus.
+    fn handle_sidebar_hotkeys(&mut self, ctx: &egui::Context) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        let ctrl_shift = egui::Modifiers::CTRL | egui::Modifiers::SHIFT;
+        // Shifted chords first: a plain Ctrl+B check would also match Ctrl+Shift+B
+        let (right, search, left) = ctx.input_mut(|i| {
+            let right = i.consume_key(ctrl_shift, egui::Key::B);
+            let search = i.consume_key(ctrl_shift, egui::Key::F);
+            let left = i.consume_key(egui::Modifiers::CTRL, egui::Key::B);
+            (right, search, left)
+        });
 
-            // Approval Mode selector
-            ui.horizontal(|ui| {
-                ui.add_space(16.0);
-                ui.label(
-                    RichText::new("Download Approval")
-                        .color(theme.fg)
-                        .size(12.0),
-                );
-            });
-            ui.add_space(4.0);
-            ui.horizontal(|ui| {
-                ui.add_space(24.0);
+        if right {
+            self.right_sidebar_visible = !self.right_sidebar_visible;
+        }
+        if left {
+            self.left_sidebar_visible = !self.left_sidebar_visible;
+        }
+        if search {
+            self.left_mode = SidebarMode::Search;
+            self.left_sidebar_visible = true;
+            self.focus_search_pending = true;
+        }
+    }
 
-                let current_mode = self.approval_manager.mode();
-                let modes = [
-                    (
-                        ApprovalMode::Gui,
-                        "Double-click",
-                        "Require second click to confirm",
-                    ),
-                    (ApprovalMode::Terminal, "Terminal", "Use terminal prompts"),
-                    (
-                        ApprovalMode::AutoApprove,
-                        "Auto",
-                        "Skip confirmation (risky)",
-                    ),
-                ];
+    /// F6 / Shift+F6 move keyboard focus to the next / previous window region;
+    /// Tab and Shift+Tab then move between the controls inside it
+    fn handle_focus_region_keys(&mut self, ctx: &egui::Context) {
+        let (next, prev) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::F6),
+                i.consume_key(egui::Modifiers::SHIFT, egui::Key::F6),
+            )
+        });
+        if !next && !prev {
+            return;
+        }
 
-                for (mode, label, tooltip) in modes {
-                    let is_selected = current_mode == mode;
-                    let btn_text = RichText::new(label).size(10.0).color(if is_selected {
-                        theme.selected_fg
-                    } else {
-                        theme.fg
-                    });
+        let mut anchors = self.focus_anchors.clone();
+        anchors.sort_by_key(|(region, _)| *region);
+        if anchors.is_empty() {
+            return;
+        }
 
-                    let btn = egui::Button::new(btn_text)
-                        .fill(if is_selected {
-                            theme.accent
-                        } else {
-                            theme.input_bg
-                        })
-                        .min_size(egui::vec2(70.0, 22.0));
+        let has_focus = ctx.memory(|m| m.focused().is_some());
+        let current = self
+            .focus_region
+            .filter(|_| has_focus)
+            .and_then(|region| anchors.iter().position(|(r, _)| *r == region));
+        let index = match (current, next) {
+            (Some(i), true) => (i + 1) % anchors.len(),
+            (Some(i), false) => (i + anchors.len() - 1) % anchors.len(),
+            (None, true) => 0,
+            (None, false) => anchors.len() - 1,
+        };
 
-                    if ui.add(btn).on_hover_text(tooltip).clicked() {
-                        self.approval_manager.set_mode(mode);
-                        self.set_status(&format!("✓ Approval mode: {}", label));
-                    }
-                    ui.add_space(4.0);
-                }
-            });
+        let (region, id) = anchors[index];
+        ctx.memory_mut(|m| m.request_focus(id));
+        self.focus_region = Some(region);
+    }
 
-            ui.add_space(20.0);
+    fn show_manager_panel(&mut self, ui: &mut egui::Ui, theme: Theme) {
+        ui.vertical(|ui| {
+            // Header with controls
+            ui.add_space(12.0);
             ui.horizontal(|ui| {
                 ui.add_space(16.0);
                 ui.label(
-                    RichText::new("APPEARANCE")
-                        .size(11.0)
+                    RichText::new("VERSIONS")
+                        .size(theme.text_size(11.0))
                         .color(theme.fg_dim)
                         .strong(),
                 );
-            });
-            ui.add_space(8.0);
 
-            // Display Size presets
-            ui.horizontal(|ui| {
-                ui.add_space(16.0);
-                ui.label(RichText::new("Display Size").color(theme.fg).size(12.0));
-            });
-            ui.horizontal(|ui| {
-                ui.add_space(24.0);
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.add_space(8.0);
 
-                // Preset buttons
-                let presets = [
-                    ("Small", 0.85, 8.0, 9.0),
-                    ("Normal", 1.0, 12.0, 11.0),
-                    ("Large", 1.15, 16.0, 12.0),
-                    ("XL", 1.3, 20.0, 14.0),
-                ];
+                    // Refresh button
+                    let refresh_btn = ui
+                        .add(
+                            egui::Button::new(RichText::new("↻").size(14.0).color(theme.fg_dim))
+                                .frame(false),
+                        )
+                        .on_hover_text("Refresh versions");
+                    accessible_label(ui, &refresh_btn, "Refresh versions");
+                    self.mark_focus_anchor(FocusRegion::LeftSidebar, &refresh_btn);
+                    paint_focus_ring(ui, &refresh_btn);
+                    if refresh_btn.hovered() {
+                        ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+                    }
+                    if refresh_btn.clicked() {
+                        self.refresh_versions();
+                    }
 
-                for (name, scale, spacing, status_font) in presets {
-                    let is_selected = (self.font_scale - scale).abs() < 0.05;
-                    let btn_text = RichText::new(name).size(11.0).color(if is_selected {
-                        theme.selected_fg
+                    let detect_btn = ui
+                        .add(
+                            egui::Button::new(RichText::new("🔍").size(12.0).color(theme.fg_dim))
+                                .frame(false),
+                        )
+                        .on_hover_text("Detect installations");
+                    accessible_label(ui, &detect_btn, "Detect installations");
+                    paint_focus_ring(ui, &detect_btn);
+                    if detect_btn.hovered() {
+                        ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+                    }
+                    if detect_btn.clicked() {
+                        self.detect_installations();
+                    }
+
+                    ui.add_space(4.0);
+
+                    // Toggle for showing all versions
+                    let toggle_text = if self.show_all_versions {
+                        "All"
                     } else {
-                        theme.fg
-                    });
+                        "Installed"
+                    };
+                    let toggle_btn = ui
+                        .add(
+                            egui::Button::new(
+                                RichText::new(toggle_text)
+                                    .size(theme.text_size(10.0))
+                                    .color(theme.accent),
+                            )
+                            .frame(false),
+                        )
+                        .on_hover_text("Toggle between installed and all available versions");
 
-                    let btn = egui::Button::new(btn_text)
-                        .fill(if is_selected {
-                            theme.accent
-                        } else {
-                            theme.input_bg
-                        })
-                        .min_size(egui::vec2(50.0, 24.0));
+                    if toggle_btn.hovered() {
+                        ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+                    }
 
-                    if ui.add(btn).clicked() {
-                        self.font_scale = scale;
-                        self.message_spacing = spacing;
-                        self.status_bar_font_size = status_font;
-                        self.save_settings();
-                        self.set_status(&format!("✓ Display: {} ({:.0}%)", name, scale * 100.0));
+                    if toggle_btn.clicked() {
+                        self.show_all_versions = !self.show_all_versions;
+                        self.set_status(if self.show_all_versions {
+                            "Showing all available versions"
+                        } else {
+                            "Showing installed versions only"
+                        });
                     }
-                    ui.add_space(4.0);
-                }
+                });
             });
-            ui.add_space(12.0);
+            ui.add_space(8.0);
 
-            // Fine-tuning section (collapsible)
+            // Current launch version indicator
+            let launch_display = Self::version_display_name(&self.launch_version);
             ui.horizontal(|ui| {
                 ui.add_space(16.0);
-                ui.label(RichText::new("Fine-tune").color(theme.fg_dim).size(11.0));
-            });
-            ui.add_space(4.0);
-
-            // Font scale - compact with +/- buttons
-            ui.horizontal(|ui| {
-                ui.add_space(24.0);
-                ui.label(RichText::new("Scale").color(theme.fg_dim).size(10.0));
-                ui.add_space(8.0);
+                ui.label(RichText::new("Launch:").size(theme.text_size(11.0)).color(theme.fg_dim));
 
-                if ui.small_button("−").clicked() {
-                    self.font_scale = (self.font_scale - 0.05).max(0.7);
-                    self.save_settings();
-                }
-                ui.label(
-                    RichText::new(format!("{:.0}%", self.font_scale * 100.0))
-                        .color(theme.fg)
-                        .size(11.0)
-                        .monospace(),
-                );
-                if ui.small_button("+").clicked() {
-                    self.font_scale = (self.font_scale + 0.05).min(1.6);
-                    self.save_settings();
-                }
-
-                ui.add_space(16.0);
+                let launch_btn = ui
+                    .add(
+                        egui::Button::new(
+                            RichText::new(&launch_display)
+                                .size(theme.text_size(11.0))
+                                .color(theme.accent),
+                        )
+                        .frame(false),
+                    )
+                    .on_hover_text("Click to change which version to launch");
 
-                ui.label(RichText::new("Gap").color(theme.fg_dim).size(10.0));
-                ui.add_space(8.0);
-                if ui.small_button("−").clicked() {
-                    self.message_spacing = (self.message_spacing - 2.0).max(4.0);
-                    self.save_settings();
-                }
-                ui.label(
-                    RichText::new(format!("{:.0}px", self.message_spacing))
-                        .color(theme.fg)
-                        .size(11.0)
-                        .monospace(),
-                );
-                if ui.small_button("+").clicked() {
-                    self.message_spacing = (self.message_spacing + 2.0).min(32.0);
-                    self.save_settings();
+                if launch_btn.hovered() {
+                    ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
                 }
-            });
 
-            // Status bar font - compact
-            ui.horizontal(|ui| {
-                ui.add_space(24.0);
-                ui.label(RichText::new("Status").color(theme.fg_dim).size(10.0));
-                ui.add_space(4.0);
-                if ui.small_button("−").clicked() {
-                    self.status_bar_font_size = (self.status_bar_font_size - 1.0).max(8.0);
-                    self.save_settings();
-                }
-                ui.label(
-                    RichText::new(format!("{:.0}px", self.status_bar_font_size))
-                        .color(theme.fg)
-                        .size(11.0)
-                        .monospace(),
-                );
-                if ui.small_button("+").clicked() {
-                    self.status_bar_font_size = (self.status_bar_font_size + 1.0).min(16.0);
-                    self.save_settings();
+                if launch_btn.clicked() {
+                    self.show_launch_picker = !self.show_launch_picker;
                 }
             });
 
-            ui.add_space(20.0);
-            ui.horizontal(|ui| {
-                ui.add_space(16.0);
-                ui.label(
-                    RichText::new("MESSAGE ALIGNMENT")
-                        .size(11.0)
-                        .color(theme.fg_dim)
-                        .strong(),
-                );
-            });
-            ui.add_space(8.0);
-
-            // Display preferences for message types
-            let alignments = ["left", "right", "center"];
-            let content_types = [
-                ("user", "User Messages"),
-                ("assistant", "AI Responses"),
-                ("thinking", "Thinking Blocks"),
-                ("tool_call", "Tool Calls"),
-            ];
+            // Launch version picker dropdown
+            if self.show_launch_picker {
+                ui.add_space(4.0);
+                egui::Frame::none()
+                    .fill(theme.input_bg)
+                    .rounding(Rounding::same(6.0))
+                    .stroke(Stroke::new(1.0, theme.border))
+                    .inner_margin(egui::Margin::same(6.0))
+                    .show(ui, |ui| {
+                        let mut versions = self.versions.clone();
+                        // Favorites first, otherwise keep the existing order
+                        versions.sort_by_key(|v| !self.favorite_versions.contains(&v.version));
+                        let mut selected: Option<String> = None;
+                        let mut toggle_fav: Option<String> = None;
 
-            // Clone prefs to avoid borrow issues
-            let current_prefs = self.display_prefs.clone();
-            let mut pref_change: Option<(&str, &str)> = None;
+                        for version in &versions {
+                            let is_current = version.version == self.launch_version;
+                            let is_favorite = self.favorite_versions.contains(&version.version);
+                            let label = Self::version_display_name(&version.version);
 
-            for (content_type, label) in content_types {
-                ui.horizontal(|ui| {
-                    ui.add_space(16.0);
-                    ui.label(RichText::new(label).color(theme.fg).size(12.0));
+                            let bg = if is_current {
+                                theme.selection
+                            } else {
+                                Color32::TRANSPARENT
+                            };
 
-                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        ui.add_space(16.0);
+                            egui::Frame::none()
+                                .fill(bg)
+                                .rounding(Rounding::same(4.0))
+                                .inner_margin(egui::Margin::symmetric(8.0, 4.0))
+                                .show(ui, |ui| {
+                                    ui.horizontal(|ui| {
+                                        let fav_icon = if is_favorite { "★" } else { "☆" };
+                                        let fav_color = if is_favorite {
+                                            Color32::from_rgb(255, 215, 0)
+                                        } else {
+                                            theme.fg_dim
+                                        };
+                                        let fav_btn = ui
+                                            .add(
+                                                egui::Button::new(
+                                                    RichText::new(fav_icon)
+                                                        .color(fav_color)
+                                                        .size(12.0),
+                                                )
+                                                .frame(false),
+                                            )
+                                            .on_hover_text(if is_favorite {
+                                                "Unpin from quick launch"
+                                            } else {
+                                                "Pin for quick launch"
+                                            });
+                                        accessible_toggle(ui, &fav_btn, "Favorite", is_favorite);
+                                        if fav_btn.clicked() {
+                                            toggle_fav = Some(version.version.clone());
+                                        }
 
-                        // Find current alignment
-                        let current = current_prefs
-                            .iter()
-                            .find(|p| p.content_type == content_type)
-                            .map(|p| p.alignment.as_str())
-                            .unwrap_or("left");
+                                        let btn = ui.add(
+                                            egui::Button::new(
+                                                RichText::new(&label)
+                                                    .color(if is_current {
+                                                        theme.accent
+                                                    } else {
+                                                        theme.fg
+                                                    })
+                                                    .size(12.0),
+                                            )
+                                            .frame(false)
+                                            .min_size(Vec2::new(ui.available_width() - 8.0, 22.0)),
+                                        );
 
-                        // Show buttons in order: left, center, right (reversed for RTL layout)
-                        for align in ["right", "center", "left"].iter() {
-                            let is_selected = current == *align;
-                            let icon = match *align {
-                                "left" => "◀ L",
-                                "center" => "◆ C",
-                                "right" => "R ▶",
-                                _ => "•",
-                            };
+                                        if btn.hovered() {
+                                            ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+                                        }
 
-                            // Use frame + background for selected state (theme-independent visibility)
-                            let btn = if is_selected {
-                                ui.add(
-                                    egui::Button::new(
-                                        RichText::new(icon)
-                                            .color(theme.selected_fg)
-                                            .size(10.0)
-                                            .strong(),
-                                    )
-                                    .fill(theme.selected_bg)
-                                    .min_size(Vec2::new(28.0, 20.0)),
-                                )
-                            } else {
-                                ui.add(
-                                    egui::Button::new(
-                                        RichText::new(icon).color(theme.fg_dim).size(10.0),
-                                    )
-                                    .fill(Color32::TRANSPARENT)
-                                    .min_size(Vec2::new(28.0, 20.0)),
-                                )
-                            }
-                            .on_hover_text(*align);
+                                        if btn.clicked() {
+                                            selected = Some(version.version.clone());
+                                        }
+                                    });
+                                });
+                        }
 
-                            if btn.clicked() {
-                                pref_change = Some((content_type, *align));
-                            }
+                        if let Some(ver) = toggle_fav {
+                            self.toggle_favorite_version(&ver);
+                        }
+                        if let Some(ver) = selected {
+                            self.set_launch_version(&ver);
                         }
                     });
-                });
-                ui.add_space(4.0);
-            }
-
-            // Apply preference change after UI iteration
-            if let Some((content_type, align)) = pref_change {
-                if let Err(e) =
-                    self.db
-                        .set_display_preference(content_type, align, "default", false)
-                {
-                    self.set_status(&format!("✗ Failed to save preference: {}", e));
-                } else {
-                    self.display_prefs = self.db.get_display_preferences().unwrap_or_default();
-                    self.set_status(&format!("✓ Alignment changed to {}", align));
-                    // Request repaint to update message display immediately
-                    ui.ctx().request_repaint();
-                }
             }
 
-            ui.add_space(12.0);
-            ui.horizontal(|ui| {
-                ui.add_space(16.0);
-                ui.label(
-                    RichText::new("DATA")
-                        .size(11.0)
-                        .color(theme.fg_dim)
-                        .strong(),
-                );
-            });
             ui.add_space(8.0);
 
+            // Legend for version icons
             ui.horizontal(|ui| {
-                ui.add_space(12.0);
-                if styled_button(ui, "📂 Open Data Folder", Vec2::new(160.0, 32.0)).clicked() {
-                    if let Some(config_dir) = dirs::config_dir() {
-                        let data_dir = config_dir.join("cursor-studio");
-                        if let Err(e) = Command::new("xdg-open").arg(&data_dir).spawn() {
-                            self.set_status(&format!("✗ Failed to open: {}", e));
-                        } else {
-                            self.set_status("✓ Opened data folder");
-                        }
-                    }
-                }
+                ui.add_space(16.0);
+                ui.label(RichText::new("★").color(theme.warning).size(theme.text_size(10.0)));
+                ui.label(RichText::new("default").color(theme.fg_dim).size(theme.text_size(9.0)));
+                ui.add_space(8.0);
+                ui.label(RichText::new("●").color(theme.success).size(theme.text_size(10.0)));
+                ui.label(RichText::new("installed").color(theme.fg_dim).size(theme.text_size(9.0)));
+                ui.add_space(8.0);
+                ui.label(RichText::new("⬇").color(theme.accent).size(theme.text_size(10.0)));
+                ui.label(RichText::new("download").color(theme.fg_dim).size(theme.text_size(9.0)));
             });
             ui.add_space(4.0);
 
-            ui.horizontal(|ui| {
-                ui.add_space(12.0);
-                if styled_button(ui, "↻ Refresh All", Vec2::new(160.0, 32.0))
-                    .on_hover_text("Refresh all data")
-                    .clicked()
-                {
-                    self.refresh_all();
-                }
-            });
+            // Version list
+            let all_versions = self.get_all_versions();
+            let available_height = ui.available_height() - 180.0; // Adjust for legend
+            let default_ver = self.default_version.clone();
 
-            ui.add_space(20.0);
-            ui.horizontal(|ui| {
-                ui.add_space(16.0);
-                ui.label(
-                    RichText::new("VERSION CLEANUP")
-                        .size(11.0)
-                        .color(theme.fg_dim)
-                        .strong(),
-                );
-            });
-            ui.add_space(8.0);
+            egui::ScrollArea::vertical()
+                .max_height(available_height.max(100.0))
+                .show(ui, |ui| {
+                    if all_versions.is_empty() {
+                        ui.horizontal(|ui| {
+                            ui.add_space(16.0);
+                            ui.label(
+                                RichText::new("No Cursor installations found")
+                                    .color(theme.fg_dim)
+                                    .italics(),
+                            );
+                        });
+                    }
 
-            // Show installed versions with cleanup options
-            let installed_versions: Vec<_> = self.versions.iter()
-                .filter(|v| v.version != "default")
-                .map(|v| v.version.clone())
-                .collect();
+                    for (version, is_installed) in &all_versions {
+                        let is_default = version == &default_ver
+                            || (version == "default" && default_ver == "default");
+
+                        // Check if we have a hash for this version
+                        let has_hash = get_version_info(version)
+                            .map(|v| v.sha256_hash.is_some())
+                            .unwrap_or(false);
+
+                        // Determine background color based on state
+                        let bg_color = if is_default {
+                            theme.selection
+                        } else if !is_installed && has_hash {
+                            // Downloadable with verified hash - subtle highlight
+                            theme.accent.linear_multiply(0.15)
+                        } else {
+                            Color32::TRANSPARENT
+                        };
+
+                        egui::Frame::none()
+                            .fill(bg_color)
+                            .rounding(Rounding::same(4.0))
+                            .inner_margin(egui::Margin::symmetric(4.0, 3.0))
+                            .show(ui, |ui| {
+                                let response = ui
+                                    .horizontal(|ui| {
+                                        ui.add_space(8.0);
+
+                                        // Batch download selection
+                                        if !is_installed {
+                                            let mut checked =
+                                                self.download_selection.contains(version);
+                                            if ui
+                                                .checkbox(&mut checked, "")
+                                                .on_hover_text("Select for batch download")
+                                                .changed()
+                                            {
+                                                if checked {
+                                                    self.download_selection.insert(version.clone());
+                                                } else {
+                                                    self.download_selection.remove(version);
+                                                }
+                                                self.download_plan = None;
+                                            }
+                                        }
+
+                                        // Status icon
+                                        let (icon, icon_color) = if is_default {
+                                            ("★", theme.warning) // Default version
+                                        } else if *is_installed {
+                                            ("●", theme.success) // Installed
+                                        } else if has_hash {
+                                            ("⬇", theme.accent) // Downloadable with hash
+                                        } else {
+                                            ("○", theme.fg_dim.linear_multiply(0.5))
+                                            // No hash
+                                        };
+                                        ui.label(RichText::new(icon).color(icon_color).size(14.0));
+
+                                        ui.add_space(8.0);
+
+                                        let label = Self::version_display_name(version);
+                                        let text_color = if is_default {
+                                            theme.fg_bright
+                                        } else if *is_installed {
+                                            theme.fg
+                                        } else if has_hash {
+                                            theme.accent // Downloadable stands out
+                                        } else {
+                                            theme.fg_dim
+                                        };
+
+                                        // Build tooltip
+                                        let tooltip = if *is_installed {
+                                            if is_default {
+                                                "✓ Default version"
+                                            } else {
+                                                "Click to set as default"
+                                            }
+                                        } else if has_hash {
+                                            "Click to download (hash verified)"
+                                        } else {
+                                            "Click to download (no hash verification)"
+                                        };
+
+                                        let btn = ui
+                                            .add(
+                                                egui::Button::new(
+                                                    RichText::new(&label)
+                                                        .color(text_color)
+                                                        .size(13.0),
+                                                )
+                                                .frame(false),
+                                            )
+                                            .on_hover_text(tooltip);
+
+                                        if btn.hovered() {
+                                            ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+                                        }
+
+                                        // Show additional status indicators
+                                        ui.with_layout(
+                                            egui::Layout::right_to_left(egui::Align::Center),
+                                            |ui| {
+                                                ui.add_space(8.0);
+                                                if *is_installed {
+                                                    let chats = self
+                                                        .version_chat_counts
+                                                        .get(version)
+                                                        .copied()
+                                                        .unwrap_or(0);
+                                                    ui.label(
+                                                        RichText::new(format!("💬 {}", chats))
+                                                            .color(theme.fg_dim)
+                                                            .size(theme.text_size(10.0)),
+                                                    )
+                                                    .on_hover_text(format!(
+                                                        "{} chat{} imported from this version",
+                                                        chats,
+                                                        if chats == 1 { "" } else { "s" }
+                                                    ));
+                                                }
+                                                let details_open = self
+                                                    .version_details
+                                                    .as_ref()
+                                                    .is_some_and(|d| &d.version == version);
+                                                if ui
+                                                    .add(
+                                                        egui::Button::new(
+                                                            RichText::new("ⓘ")
+                                                                .color(if details_open {
+                                                                    theme.accent
+                                                                } else {
+                                                                    theme.fg_dim
+                                                                })
+                                                                .size(theme.text_size(11.0)),
+                                                        )
+                                                        .frame(false),
+                                                    )
+                                                    .on_hover_text("Show version details")
+                                                    .clicked()
+                                                {
+                                                    self.toggle_version_details(version);
+                                                }
+                                                if self.integrity_failure(version).is_some() {
+                                                    ui.label(
+                                                        RichText::new("⚠")
+                                                            .color(theme.error)
+                                                            .size(theme.text_size(11.0)),
+                                                    )
+                                                    .on_hover_text(
+                                                        "Installed binary failed verification",
+                                                    );
+                                                }
+                                                if !is_installed {
+                                                    if has_hash {
+                                                        ui.label(
+                                                            RichText::new("✓")
+                                                                .color(theme.success)
+                                                                .size(theme.text_size(10.0)),
+                                                        )
+                                                        .on_hover_text("Hash verified");
+                                                    } else {
+                                                        ui.label(
+                                                            RichText::new("?")
+                                                                .color(theme.warning)
+                                                                .size(theme.text_size(10.0)),
+                                                        )
+                                                        .on_hover_text(
+                                                            "No hash - download at your own risk",
+                                                        );
+                                                    }
+                                                }
+                                            },
+                                        );
+
+                                        btn
+                                    })
+                                    .inner;
+
+                                if response.clicked() {
+                                    if *is_installed {
+                                        self.set_default_version(version);
+                                    } else {
+                                        // Start download for this version
+                                        self.start_download(version);
+                                    }
+                                }
+                            });
+
+                        ui.add_space(2.0);
+                    }
+                });
+
+            if self.version_details.is_some() {
+                ui.add_space(4.0);
+                self.show_version_details(ui, theme);
+            }
+
+            if !self.download_selection.is_empty() {
+                ui.add_space(4.0);
+                self.show_download_batch_bar(ui, theme);
+            }
+
+            ui.add_space(4.0);
+            self.show_storage_usage_bar(ui, theme);
+
+            // Spacer, leaving room for each download beyond the first
+            let extra_downloads = self.active_downloads.len().saturating_sub(1);
+            ui.add_space((ui.available_height() - 180.0 - 40.0 * extra_downloads as f32).max(0.0));
+
+            // Download status section (one row per running download)
+            let mut active: Vec<(String, f32, String, Option<String>)> = self
+                .download_state
+                .values()
+                .filter_map(|state| {
+                    let (version, progress, text) = match state {
+                        DownloadState::Downloading { progress, version } => {
+                            (version, *progress, format!("{:.0}%", progress))
+                        }
+                        DownloadState::Verifying { version, progress } => {
+                            (version, *progress, format!("Verifying… {:.0}%", progress))
+                        }
+                        DownloadState::Resuming {
+                            version,
+                            resumed_bytes,
+                        } => {
+                            let resumed_at = self.active_downloads.get(version)?.progress;
+                            let text = match resumed_at {
+                                Some(progress) => format!("Resuming at {:.0}%", progress),
+                                None => format!(
+                                    "Resuming after {}",
+                                    versions::format_size(*resumed_bytes)
+                                ),
+                            };
+                            (version, resumed_at.unwrap_or(0.0), text)
+                        }
+                        _ => return None,
+                    };
+                    let proxy = self.active_downloads.get(version)?.proxy.clone();
+                    Some((version.clone(), progress, text, proxy))
+                })
+                .collect();
+            active.sort_by(|a, b| version_compare(&b.0, &a.0));
+            if !active.is_empty() {
+                ui.separator();
+                ui.add_space(8.0);
 
-            if installed_versions.is_empty() {
-                ui.horizontal(|ui| {
-                    ui.add_space(16.0);
-                    ui.label(
-                        RichText::new("No installed versions to clean up")
-                            .color(theme.fg_dim)
-                            .size(11.0)
-                            .italics(),
-                    );
-                });
-            } else {
                 ui.horizontal(|ui| {
                     ui.add_space(16.0);
                     ui.label(
-                        RichText::new("Select versions to remove:")
-                            .color(theme.fg)
-                            .size(11.0),
+                        RichText::new("DOWNLOADING")
+                            .size(theme.text_size(11.0))
+                            .color(theme.accent)
+                            .strong(),
                     );
+                    if !self.download_queue.is_empty() {
+                        ui.label(
+                            RichText::new(format!("+{} queued", self.download_queue.len()))
+                                .color(theme.fg_dim)
+                                .size(theme.text_size(10.0)),
+                        );
+                    }
                 });
                 ui.add_space(4.0);
+            }
 
-                // Version checkboxes
-                let mut to_remove: Vec<String> = Vec::new();
-                for version in &installed_versions {
-                    let checkbox_id = ui.make_persistent_id(format!("cleanup_{}", version));
-                    let mut checked = ui.data_mut(|d| d.get_temp::<bool>(checkbox_id).unwrap_or(false));
-
+            for (version, progress, progress_text, proxy) in &active {
+                ui.horizontal(|ui| {
+                    ui.add_space(16.0);
+                    ui.add(egui::Spinner::new().size(14.0));
+                    ui.add_space(4.0);
+                    ui.label(
+                        RichText::new(format!("v{} - {}", version, progress_text))
+                            .color(theme.fg)
+                            .size(theme.text_size(11.0)),
+                    );
+                });
+                if let Some(proxy) = proxy {
                     ui.horizontal(|ui| {
-                        ui.add_space(24.0);
-                        if ui.checkbox(&mut checked, format!("v{}", version)).changed() {
-                            ui.data_mut(|d| d.insert_temp(checkbox_id, checked));
-                        }
-                        ui.label(RichText::new("~150MB").color(theme.fg_dim).size(10.0));
+                        ui.add_space(16.0);
+                        ui.label(
+                            RichText::new(format!("via proxy {}", proxy))
+                                .color(theme.fg_dim)
+                                .size(theme.text_size(10.0)),
+                        );
                     });
+                }
 
-                    if checked {
-                        to_remove.push(version.clone());
+                // Progress bar
+                ui.horizontal(|ui| {
+                    ui.add_space(16.0);
+                    let bar_width = ui.available_width() - 32.0;
+                    let (rect, _) = ui.allocate_exact_size(
+                        Vec2::new(bar_width.max(50.0), 6.0),
+                        egui::Sense::hover(),
+                    );
+
+                    // Background
+                    ui.painter()
+                        .rect_filled(rect, Rounding::same(3.0), theme.input_bg);
+
+                    // Progress
+                    let progress_width = rect.width() * (progress / 100.0);
+                    let progress_rect = egui::Rect::from_min_size(
+                        rect.min,
+                        Vec2::new(progress_width, rect.height()),
+                    );
+                    ui.painter()
+                        .rect_filled(progress_rect, Rounding::same(3.0), theme.accent);
+                });
+
+                ui.add_space(4.0);
+            }
+            if !active.is_empty() {
+                // Request repaint while downloading
+                ui.ctx().request_repaint();
+            }
+
+            // Download failed section with recovery options
+            let mut failed: Vec<(String, String)> = self
+                .download_state
+                .values()
+                .filter_map(|state| match state {
+                    DownloadState::Failed { version, error } => {
+                        Some((version.clone(), error.clone()))
                     }
-                }
+                    _ => None,
+                })
+                .collect();
+            failed.sort_by(|a, b| version_compare(&b.0, &a.0));
+            for (version, error) in &failed {
+                ui.separator();
+                ui.add_space(8.0);
+
+                ui.horizontal(|ui| {
+                    ui.add_space(16.0);
+                    ui.label(
+                        RichText::new("⚠ DOWNLOAD FAILED")
+                            .size(theme.text_size(11.0))
+                            .color(theme.error)
+                            .strong(),
+                    );
+                });
+                ui.add_space(4.0);
 
+                ui.horizontal(|ui| {
+                    ui.add_space(16.0);
+                    ui.label(
+                        RichText::new(format!("v{}: {}", version, error))
+                            .color(theme.fg_dim)
+                            .size(theme.text_size(10.0)),
+                    );
+                });
                 ui.add_space(8.0);
 
-                if !to_remove.is_empty() {
+                // Show manual download URL
+                if let Some(version_info) = get_version_info(version) {
                     ui.horizontal(|ui| {
                         ui.add_space(16.0);
-                        let btn_text = format!("🗑️ Remove {} version{}", 
-                            to_remove.len(),
-                            if to_remove.len() > 1 { "s" } else { "" }
+                        ui.label(
+                            RichText::new("Manual download:")
+                                .color(theme.fg_dim)
+                                .size(theme.text_size(10.0)),
                         );
-                        if styled_button(ui, &btn_text, Vec2::new(160.0, 28.0))
-                            .on_hover_text(format!("Remove: {}", to_remove.join(", ")))
-                            .clicked()
-                        {
-                            let mut removed = 0;
-                            for version in &to_remove {
-                                if let Err(e) = self.remove_version(version) {
-                                    self.set_status(&format!("✗ Failed to remove {}: {}", version, e));
+                    });
+                    ui.add_space(2.0);
+
+                    // URL display with copy button
+                    egui::Frame::none()
+                        .fill(theme.code_bg)
+                        .rounding(Rounding::same(4.0))
+                        .inner_margin(egui::Margin::symmetric(8.0, 4.0))
+                        .show(ui, |ui| {
+                            ui.horizontal(|ui| {
+                                ui.add_space(8.0);
+                                let url = &version_info.download_url;
+
+                                // Truncate URL for display
+                                let display_url = if url.len() > 40 {
+                                    format!("{}...", &url[..40])
                                 } else {
-                                    removed += 1;
-                                    let checkbox_id = ui.make_persistent_id(format!("cleanup_{}", version));
-                                    ui.data_mut(|d| d.insert_temp(checkbox_id, false));
-                                }
-                            }
-                            if removed > 0 {
-                                self.refresh_versions();
-                                self.set_status(&format!("✓ Removed {} version{}", removed, if removed > 1 { "s" } else { "" }));
-                            }
-                        }
+                                    url.clone()
+                                };
+
+                                ui.label(
+                                    RichText::new(&display_url)
+                                        .color(theme.accent)
+                                        .size(theme.text_size(9.0))
+                                        .family(egui::FontFamily::Monospace),
+                                );
+
+                                ui.with_layout(
+                                    egui::Layout::right_to_left(egui::Align::Center),
+                                    |ui| {
+                                        if ui
+                                            .add(
+                                                egui::Button::new(RichText::new("📋").size(12.0))
+                                                    .frame(false),
+                                            )
+                                            .on_hover_text("Copy URL to clipboard")
+                                            .clicked()
+                                        {
+                                            ui.ctx().copy_text(url.clone());
+                                            // Note: set_status is not available in this scope, status shown via tooltip
+                                        }
+                                    },
+                                );
+                            });
+                        });
+
+                    ui.add_space(4.0);
+
+                    // Instructions
+                    ui.horizontal(|ui| {
+                        ui.add_space(16.0);
+                        ui.label(
+                            RichText::new("Download manually, then use CLI:")
+                                .color(theme.fg_dim)
+                                .size(theme.text_size(9.0)),
+                        );
                     });
+
+                    egui::Frame::none()
+                        .fill(theme.code_bg)
+                        .rounding(Rounding::same(4.0))
+                        .inner_margin(egui::Margin::symmetric(8.0, 4.0))
+                        .show(ui, |ui| {
+                            ui.horizontal(|ui| {
+                                ui.add_space(8.0);
+                                let cmd = format!("cursor-studio-cli import <file> --version {}", version);
+                                ui.label(
+                                    RichText::new(&cmd)
+                                        .color(theme.fg)
+                                        .size(theme.text_size(9.0))
+                                        .family(egui::FontFamily::Monospace),
+                                );
+                            });
+                        });
                 }
+
+                ui.add_space(4.0);
+
+                // Retry and dismiss buttons
+                ui.push_id(version, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.add_space(16.0);
+                        if styled_button(ui, "🔄 Retry", Vec2::new(70.0, 24.0)).clicked() {
+                            self.download_state.remove(version);
+                            self.start_download(version);
+                        }
+                        ui.add_space(4.0);
+                        if styled_button(ui, "✕ Dismiss", Vec2::new(70.0, 24.0)).clicked() {
+                            self.download_state.remove(version);
+                        }
+                    });
+                });
+                ui.add_space(4.0);
             }
 
-            ui.add_space(20.0);
+            // Actions section
+            ui.separator();
+            ui.add_space(8.0);
+
             ui.horizontal(|ui| {
                 ui.add_space(16.0);
                 ui.label(
-                    RichText::new("RESOURCES")
-                        .size(11.0)
+                    RichText::new("ACTIONS")
+                        .size(theme.text_size(11.0))
                         .color(theme.fg_dim)
                         .strong(),
                 );
             });
             ui.add_space(8.0);
 
-            // CPU threads
-            let max_threads = std::thread::available_parallelism()
-                .map(|n| n.get())
-                .unwrap_or(8);
-            ui.horizontal(|ui| {
-                ui.add_space(16.0);
-                ui.label(
-                    RichText::new(format!("CPU Threads (max {})", max_threads))
-                        .color(theme.fg)
-                        .size(12.0),
-                );
-            });
             ui.horizontal(|ui| {
-                ui.add_space(24.0);
-                let slider = ui.add(
-                    egui::Slider::new(&mut self.max_cpu_threads, 1..=max_threads).show_value(true),
-                );
-                if slider.changed() {
-                    self.save_settings();
-                    self.set_status(&format!("✓ Max CPU threads: {}", self.max_cpu_threads));
+                ui.add_space(12.0);
+                if styled_button_accent(ui, "▶ Launch", Vec2::new(90.0, 28.0), theme).clicked() {
+                    self.launch_cursor();
                 }
             });
-            ui.add_space(8.0);
+            ui.add_space(4.0);
 
-            // RAM limit
-            ui.horizontal(|ui| {
-                ui.add_space(16.0);
-                ui.label(RichText::new("RAM Limit").color(theme.fg).size(12.0));
-            });
-            ui.horizontal(|ui| {
-                ui.add_space(24.0);
-                let slider = ui.add(
-                    egui::Slider::new(&mut self.max_ram_mb, 512..=16384)
-                        .show_value(true)
-                        .suffix(" MB"),
-                );
-                if slider.changed() {
-                    self.save_settings();
-                    self.set_status(&format!("✓ RAM limit: {} MB", self.max_ram_mb));
+            // Quick launch for pinned versions
+            if !self.favorite_versions.is_empty() {
+                let favorites = self.favorite_versions.clone();
+                let mut quick_launch: Option<String> = None;
+                ui.horizontal_wrapped(|ui| {
+                    ui.add_space(12.0);
+                    for version in &favorites {
+                        let label = format!("★ {}", Self::version_display_name(version));
+                        if styled_button(ui, &label, Vec2::new(0.0, 24.0))
+                            .on_hover_text("Launch this pinned version")
+                            .clicked()
+                        {
+                            quick_launch = Some(version.clone());
+                        }
+                    }
+                });
+                if let Some(version) = quick_launch {
+                    self.set_launch_version(&version);
+                    self.launch_cursor();
                 }
-            });
-            ui.add_space(8.0);
+                ui.add_space(4.0);
+            }
 
-            // VRAM limit (for future GPU features)
-            ui.horizontal(|ui| {
-                ui.add_space(16.0);
-                ui.label(RichText::new("VRAM Limit").color(theme.fg).size(12.0));
-            });
             ui.horizontal(|ui| {
-                ui.add_space(24.0);
-                let slider = ui.add(
-                    egui::Slider::new(&mut self.max_vram_mb, 256..=32768)
-                        .show_value(true)
-                        .suffix(" MB"),
-                );
-                if slider.changed() {
-                    self.save_settings();
-                    self.set_status(&format!("✓ VRAM limit: {} MB", self.max_vram_mb));
+                ui.add_space(12.0);
+                if styled_button(ui, "⚡ Sync All", Vec2::new(90.0, 28.0))
+                    .on_hover_text("Sync settings from default to all versions")
+                    .clicked()
+                {
+                    self.do_sync();
                 }
             });
             ui.add_space(8.0);
+        });
+    }
 
-            // Storage limit
-            ui.horizontal(|ui| {
-                ui.add_space(16.0);
-                ui.label(RichText::new("Storage Limit").color(theme.fg).size(12.0));
-            });
-            ui.horizontal(|ui| {
-                ui.add_space(24.0);
-                let slider = ui.add(
-                    egui::Slider::new(&mut self.storage_limit_mb, 1024..=102400)
-                        .show_value(true)
-                        .custom_formatter(|v, _| format!("{:.1} GB", v / 1024.0))
-                        .custom_parser(|s| {
-                            s.replace(" GB", "").parse::<f64>().ok().map(|v| v * 1024.0)
-                        }),
-                );
-                if slider.changed() {
-                    self.save_settings();
-                    self.set_status(&format!(
-                        "✓ Storage limit: {:.1} GB",
-                        self.storage_limit_mb as f32 / 1024.0
-                    ));
-                }
-            });
-
-            ui.add_space(4.0);
+    /// # TODO(P1): Release v0.3.0 - Global Search
+    /// - [x] Add global_search() to search across ALL conversations
+    /// - [ ] Add filter buttons: All | 👤 User | 🤖 AI | 🔧 Tools
+    /// - [ ] Add date range filter
+    /// - [x] Add "Jump to" buttons for search results
+    /// - [x] Show conversation title in results
+    /// - [x] Highlight matching text in results
+    fn show_search_panel(&mut self, ui: &mut egui::Ui, theme: Theme) {
+        ui.vertical(|ui| {
+            ui.add_space(12.0);
             ui.horizontal(|ui| {
                 ui.add_space(16.0);
                 ui.label(
-                    RichText::new("Note: Resource limits are for future features (AI, caching)")
+                    RichText::new("SEARCH CHATS")
+                        .size(theme.text_size(11.0))
                         .color(theme.fg_dim)
-                        .size(9.0)
-                        .italics(),
+                        .strong(),
                 );
             });
-
-            ui.add_space(20.0);
-            ui.separator();
             ui.add_space(8.0);
 
             ui.horizontal(|ui| {
-                ui.add_space(16.0);
-                ui.label(
-                    RichText::new("ABOUT")
-                        .size(11.0)
-                        .color(theme.fg_dim)
-                        .strong(),
+                ui.add_space(12.0);
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.search_query)
+                        .hint_text("Type to search...")
+                        .desired_width(ui.available_width() - 24.0)
+                        .margin(egui::Margin::symmetric(8.0, 6.0)),
                 );
+                self.mark_focus_anchor(FocusRegion::LeftSidebar, &response);
+                if std::mem::take(&mut self.focus_search_pending) {
+                    response.request_focus();
+                }
+
+                if response.changed() {
+                    if !self.search_query.is_empty() {
+                        self.search_results = self
+                            .db
+                            .search_conversations(&self.search_query)
+                            .unwrap_or_default();
+                        self.message_hits = self
+                            .db
+                            .search_messages(&self.search_query)
+                            .unwrap_or_else(|e| {
+                                log::warn!("Message search failed: {}", e);
+                                Vec::new()
+                            });
+                    } else {
+                        self.search_results.clear();
+                        self.message_hits.clear();
+                    }
+                }
             });
             ui.add_space(8.0);
 
-            ui.horizontal(|ui| {
-                ui.add_space(16.0);
-                ui.label(
-                    RichText::new("Cursor Studio v0.2.0")
-                        .color(theme.fg_dim)
-                        .size(12.0),
-                );
-            });
-            ui.horizontal(|ui| {
-                ui.add_space(16.0);
-                ui.label(
-                    RichText::new("Built with egui + Rust")
-                        .color(theme.fg_dim)
-                        .size(11.0),
-                );
-            });
-        });
-    }
+            let results = self.search_results.clone();
+            let hits = self.message_hits.clone();
+            let mut to_open: Option<String> = None;
+            let mut jump_to_msg: Option<(String, String)> = None;
 
-    fn settings_dropdown_ui(
-        &mut self,
-        ui: &mut egui::Ui,
-        theme: Theme,
-        label: &str,
-        current_value: &str,
-        toggle_key: &str,
-    ) {
-        ui.horizontal(|ui| {
-            ui.add_space(16.0);
-            ui.label(RichText::new(label).color(theme.fg).size(13.0));
-        });
-        ui.horizontal(|ui| {
-            ui.add_space(16.0);
+            if !results.is_empty() || !hits.is_empty() {
+                ui.horizontal(|ui| {
+                    ui.add_space(16.0);
+                    ui.label(
+                        RichText::new(format!(
+                            "{} conversations, {} messages",
+                            results.len(),
+                            hits.len()
+                        ))
+                        .size(theme.text_size(11.0))
+                        .color(theme.fg_dim),
+                    );
+                });
+                ui.add_space(4.0);
 
-            let is_open = match toggle_key {
-                "show_theme" => self.show_theme_picker,
-                "show_version" => self.show_version_picker,
-                _ => false,
-            };
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for conv in &results {
+                        ui.horizontal(|ui| {
+                            ui.add_space(12.0);
 
-            let arrow = if is_open { "▲" } else { "▼" };
-            let display = format!("{} {}", current_value, arrow);
+                            let (label, color) = if conv.is_archived {
+                                (format!("📦 {}", conv.title), theme.fg_dim)
+                            } else {
+                                (conv.title.clone(), theme.fg)
+                            };
+                            let row = ui.add(
+                                egui::Button::new(RichText::new(label).color(color).size(12.0))
+                                    .frame(false)
+                                    .min_size(Vec2::new(ui.available_width() - 24.0, 24.0)),
+                            );
 
-            egui::Frame::none()
-                .fill(theme.input_bg)
-                .rounding(Rounding::same(4.0))
-                .stroke(Stroke::new(1.0, theme.border))
-                .inner_margin(egui::Margin::symmetric(10.0, 6.0))
-                .show(ui, |ui| {
-                    let btn = ui
-                        .add(
-                            egui::Button::new(RichText::new(&display).color(theme.fg).size(12.0))
-                                .frame(false)
-                                .min_size(Vec2::new(180.0, 20.0)),
-                        )
-                        .on_hover_text(format!("Click to change {}", label.to_lowercase()));
+                            if row.hovered() {
+                                ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+                            }
 
-                    if btn.hovered() {
-                        ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+                            if row.clicked() {
+                                to_open = Some(conv.id.clone());
+                            }
+                        });
                     }
 
-                    if btn.clicked() {
-                        match toggle_key {
-                            "show_theme" => self.show_theme_picker = !self.show_theme_picker,
-                            "show_version" => self.show_version_picker = !self.show_version_picker,
-                            _ => {}
-                        }
+                    if !hits.is_empty() {
+                        ui.add_space(8.0);
+                        ui.horizontal(|ui| {
+                            ui.add_space(16.0);
+                            ui.label(
+                                RichText::new("IN MESSAGES")
+                                    .size(theme.text_size(10.0))
+                                    .color(theme.fg_dim)
+                                    .strong(),
+                            );
+                        });
+                        ui.add_space(4.0);
+                    }
+                    for (i, hit) in hits.iter().enumerate() {
+                        ui.push_id(("message_hit", i), |ui| {
+                            ui.horizontal(|ui| {
+                                ui.add_space(12.0);
+                                ui.vertical(|ui| {
+                                    let icon = match hit.role.as_str() {
+                                        "user" => "👤",
+                                        "assistant" => "🤖",
+                                        _ => "🔧",
+                                    };
+                                    ui.label(
+                                        RichText::new(format!(
+                                            "{} {}",
+                                            icon, hit.conversation_title
+                                        ))
+                                        .size(theme.text_size(10.0))
+                                        .color(theme.fg_dim),
+                                    );
+                                    let job = snippet_layout(
+                                        &hit.snippet,
+                                        theme,
+                                        ui.available_width() - 12.0,
+                                    );
+                                    let row = ui.add(
+                                        egui::Label::new(job).sense(egui::Sense::click()),
+                                    );
+                                    if row.hovered() {
+                                        ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+                                    }
+                                    if row.on_hover_text("Jump to message").clicked() {
+                                        jump_to_msg = Some((
+                                            hit.conversation_id.clone(),
+                                            hit.message_id.clone(),
+                                        ));
+                                    }
+                                });
+                            });
+                            ui.add_space(6.0);
+                        });
                     }
                 });
-        });
-    }
-
-    fn settings_toggle_ui(
-        &mut self,
-        ui: &mut egui::Ui,
-        theme: Theme,
-        label: &str,
-        tooltip: &str,
-        toggle_key: &str,
-    ) {
-        ui.horizontal(|ui| {
-            ui.add_space(16.0);
-            ui.label(RichText::new(label).color(theme.fg).size(13.0));
-
-            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                ui.add_space(16.0);
-
-                let mut value = match toggle_key {
-                    "auto_sync" => self.auto_sync_enabled,
-                    "import_on_start" => self.import_on_start,
-                    "show_all_versions" => self.show_all_versions,
-                    _ => false,
-                };
+            } else if !self.search_query.is_empty() {
+                ui.horizontal(|ui| {
+                    ui.add_space(16.0);
+                    ui.label(
+                        RichText::new("No results found")
+                            .color(theme.fg_dim)
+                            .italics(),
+                    );
+                });
+            } else {
+                ui.horizontal(|ui| {
+                    ui.add_space(16.0);
+                    ui.label(
+                        RichText::new("Enter a search term")
+                            .color(theme.fg_dim)
+                            .italics(),
+                    );
+                });
+            }
 
-                if toggle_switch(ui, &mut value, theme).clicked() {
-                    match toggle_key {
-                        "auto_sync" => self.auto_sync_enabled = value,
-                        "import_on_start" => self.import_on_start = value,
-                        "show_all_versions" => self.show_all_versions = value,
-                        _ => {}
-                    }
-                    self.set_status(&format!(
-                        "✓ {} {}",
-                        label,
-                        if value { "enabled" } else { "disabled" }
-                    ));
-                }
-            });
-        })
-        .response
-        .on_hover_text(tooltip);
+            if let Some(id) = to_open {
+                self.open_conversation(&id);
+            }
+            if let Some((conv_id, msg_id)) = jump_to_msg {
+                self.scroll_to_message(&conv_id, &msg_id);
+            }
+        });
     }
 
-    fn show_archive_panel(&mut self, ui: &mut egui::Ui, theme: Theme) {
-        ui.vertical(|ui| {
+    fn show_settings_panel(&mut self, ui: &mut egui::Ui, theme: Theme) {
+        // Wrap entire settings panel in ScrollArea for long content
+        egui::ScrollArea::vertical()
+            .auto_shrink([false; 2])
+            .show(ui, |ui| {
             ui.add_space(12.0);
             ui.horizontal(|ui| {
                 ui.add_space(16.0);
                 ui.label(
-                    RichText::new("CHAT LIBRARY")
-                        .size(11.0)
+                    RichText::new("APPEARANCE")
+                        .size(theme.text_size(11.0))
                         .color(theme.fg_dim)
                         .strong(),
                 );
-
-                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    ui.add_space(12.0);
-                    let refresh_btn = ui
-                        .add(
-                            egui::Button::new(RichText::new("↻").size(14.0).color(theme.fg_dim))
-                                .frame(false),
-                        )
-                        .on_hover_text("Refresh chat library");
-                    if refresh_btn.hovered() {
-                        ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
-                    }
-                    if refresh_btn.clicked() {
-                        self.refresh_chats();
-                    }
-                });
             });
-            ui.add_space(8.0);
-
-            let convs = self.conversations.clone();
-            let mut to_open: Option<String> = None;
-            let mut to_toggle_fav: Option<String> = None;
+            ui.add_space(12.0);
 
-            let scroll_height = ui.available_height() - 100.0;
+            // Theme dropdown
+            self.settings_dropdown_ui(
+                ui,
+                theme,
+                "Theme",
+                &self.current_theme_name.clone(),
+                "show_theme",
+            );
 
-            egui::ScrollArea::vertical()
-                .max_height(scroll_height.max(100.0))
-                .show(ui, |ui| {
-                    if convs.is_empty() {
-                        ui.vertical_centered(|ui| {
-                            ui.add_space(20.0);
+            if self.show_theme_picker {
+                ui.add_space(4.0);
+                egui::Frame::none()
+                    .fill(theme.input_bg)
+                    .rounding(Rounding::same(6.0))
+                    .stroke(Stroke::new(1.0, theme.border))
+                    .inner_margin(egui::Margin::same(6.0))
+                    .show(ui, |ui| {
+                        // Refresh button at top
+                        ui.horizontal(|ui| {
                             ui.label(
-                                RichText::new("No chats imported yet")
+                                RichText::new("Themes")
                                     .color(theme.fg_dim)
-                                    .italics(),
+                                    .size(theme.text_size(10.0)),
                             );
-                            ui.add_space(8.0);
-                            ui.label(
-                                RichText::new("Click 'Import All' below")
-                                    .color(theme.fg_dim)
-                                    .size(11.0),
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    let refresh_btn = ui
+                                        .add(
+                                            egui::Button::new(
+                                                RichText::new("↻").color(theme.fg_dim).size(12.0),
+                                            )
+                                            .frame(false),
+                                        )
+                                        .on_hover_text("Refresh theme list from disk");
+                                    accessible_label(ui, &refresh_btn, "Refresh themes");
+                                    if refresh_btn.clicked() {
+                                        self.rescan_themes();
+                                        // Force UI refresh
+                                        ui.ctx().request_repaint();
+                                    }
+                                },
                             );
-
-                            // Show last error if any
-                            if let Some(err) = &self.last_import_error {
-                                ui.add_space(12.0);
-                                ui.label(RichText::new(err).color(theme.error).size(10.0));
-                            }
                         });
-                    }
+                        ui.add_space(4.0);
+                        ui.separator();
+                        ui.add_space(4.0);
 
-                    for conv in &convs {
-                        ui.horizontal(|ui| {
-                            ui.add_space(12.0);
+                        // Scrollable theme list
+                        let max_height = 300.0;
+                        egui::ScrollArea::vertical()
+                            .max_height(max_height)
+                            .auto_shrink([false; 2])
+                            .show(ui, |ui| {
+                                let themes = self.available_themes.clone();
+                                let mut selected_theme: Option<(String, Option<PathBuf>)> = None;
+                                let mut hovered_now: Option<String> = None;
 
-                            let star = if conv.is_favorite { "★" } else { "☆" };
-                            let star_color = if conv.is_favorite {
-                                theme.warning
-                            } else {
-                                theme.fg_dim
-                            };
-                            let star_btn = ui
-                                .add(
-                                    egui::Button::new(
-                                        RichText::new(star).color(star_color).size(14.0),
-                                    )
-                                    .frame(false)
-                                    .min_size(Vec2::new(20.0, 20.0)),
-                                )
-                                .on_hover_text("Toggle favorite");
-
-                            if star_btn.hovered() {
-                                ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
-                            }
-
-                            if star_btn.clicked() {
-                                to_toggle_fav = Some(conv.id.clone());
-                            }
+                                for (theme_name, theme_path) in &themes {
+                                    let is_current = &self.current_theme_name == theme_name;
+                                    let is_hovered =
+                                        self.hovered_theme.as_ref() == Some(theme_name);
 
-                            let title: String = conv.title.chars().take(25).collect();
-                            let title_btn = ui
-                                .add(
-                                    egui::Button::new(
-                                        RichText::new(&title).color(theme.fg).size(12.0),
-                                    )
-                                    .frame(false),
-                                )
-                                .on_hover_text(&conv.title);
+                                    let bg = if is_current {
+                                        theme.selection
+                                    } else if is_hovered {
+                                        theme.list_hover
+                                    } else {
+                                        Color32::TRANSPARENT
+                                    };
 
-                            if title_btn.hovered() {
-                                ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
-                            }
+                                    egui::Frame::none()
+                                        .fill(bg)
+                                        .rounding(Rounding::same(4.0))
+                                        .inner_margin(egui::Margin::symmetric(8.0, 4.0))
+                                        .show(ui, |ui| {
+                                            let btn = ui.add(
+                                                egui::Button::new(
+                                                    RichText::new(theme_name)
+                                                        .color(if is_current {
+                                                            theme.accent
+                                                        } else {
+                                                            theme.fg
+                                                        })
+                                                        .size(12.0),
+                                                )
+                                                .frame(false)
+                                                .min_size(Vec2::new(
+                                                    ui.available_width() - 16.0,
+                                                    22.0,
+                                                )),
+                                            );
 
-                            if title_btn.clicked() {
-                                to_open = Some(conv.id.clone());
-                            }
+                                            if btn.hovered() {
+                                                ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+                                                hovered_now = Some(theme_name.clone());
+                                            }
 
-                            ui.with_layout(
-                                egui::Layout::right_to_left(egui::Align::Center),
-                                |ui| {
-                                    ui.add_space(12.0);
-                                    ui.label(
-                                        RichText::new(format!("{}", conv.message_count))
-                                            .color(theme.fg_dim)
-                                            .size(10.0),
-                                    );
-                                },
-                            );
-                        });
-                        ui.add_space(2.0);
-                    }
-                });
+                                            if btn.clicked() {
+                                                selected_theme =
+                                                    Some((theme_name.clone(), theme_path.clone()));
+                                            }
+                                        });
+                                }
 
-            if let Some(id) = to_open {
-                self.open_conversation(&id);
-            }
+                                // Clears the live preview when the pointer leaves the list
+                                self.hovered_theme = hovered_now;
 
-            if let Some(id) = to_toggle_fav {
-                let _ = self.db.toggle_favorite(&id);
-                self.refresh_chats();
+                                if let Some((name, path)) = selected_theme {
+                                    self.apply_theme(&name, path.as_ref());
+                                }
+                            });
+                    });
+            } else {
+                self.hovered_theme = None;
             }
-
-            ui.add_space(ui.available_height() - 70.0);
-            ui.separator();
             ui.add_space(8.0);
 
-            ui.horizontal(|ui| {
-                ui.add_space(12.0);
-
-                if self.import_in_progress {
-                    // Animated spinner during import
-                    ui.horizontal(|ui| {
-                        ui.add(egui::Spinner::new());
-                        ui.label(RichText::new("Importing...").color(theme.accent).size(12.0));
-                    });
-                    // Keep repainting while importing
-                    ui.ctx().request_repaint();
-                } else {
-                    if styled_button_accent(ui, "⬇ Import All", Vec2::new(110.0, 28.0), theme)
-                        .clicked()
-                    {
-                        self.do_import();
-                    }
-                }
+            self.settings_toggle_ui(
+                ui,
+                theme,
+                "Follow Cursor Theme",
+                "Match the theme selected in the launch version's Cursor as it changes",
+                "follow_cursor_theme",
+            );
+            ui.add_space(12.0);
 
-                if styled_button(ui, "⬆ Export", Vec2::new(80.0, 28.0))
-                    .on_hover_text("Export chats to markdown or training data")
-                    .clicked()
-                {
-                    self.show_export_dialog = !self.show_export_dialog;
-                }
-            });
-            ui.add_space(8.0);
+            // Default Version dropdown
+            let version_display = Self::version_display_name(&self.default_version);
+            self.settings_dropdown_ui(
+                ui,
+                theme,
+                "Default Version",
+                &version_display,
+                "show_version",
+            );
 
-            // Export dialog
-            if self.show_export_dialog {
-                ui.add_space(8.0);
+            if self.show_version_picker {
+                ui.add_space(4.0);
                 egui::Frame::none()
-                    .fill(theme.code_bg)
-                    .rounding(Rounding::same(8.0))
-                    .inner_margin(egui::Margin::same(12.0))
+                    .fill(theme.input_bg)
+                    .rounding(Rounding::same(6.0))
+                    .stroke(Stroke::new(1.0, theme.border))
+                    .inner_margin(egui::Margin::same(6.0))
                     .show(ui, |ui| {
-                        ui.label(
-                            RichText::new("📤 Export Settings")
-                                .size(12.0)
-                                .color(theme.fg)
-                                .strong(),
-                        );
-                        ui.add_space(8.0);
+                        let versions = self.versions.clone();
+                        let mut selected: Option<String> = None;
 
-                        // Format selection
-                        ui.horizontal(|ui| {
-                            ui.label(RichText::new("Format:").size(11.0).color(theme.fg_dim));
-                            egui::ComboBox::from_id_salt("export_format")
-                                .selected_text(self.export_format.label())
-                                .show_ui(ui, |ui| {
-                                    ui.selectable_value(&mut self.export_format, ExportFormat::Markdown, "Markdown");
-                                    ui.selectable_value(&mut self.export_format, ExportFormat::MarkdownObsidian, "Markdown (Obsidian)");
-                                    ui.selectable_value(&mut self.export_format, ExportFormat::Json, "JSON");
-                                    ui.selectable_value(&mut self.export_format, ExportFormat::JsonLines, "JSON Lines");
-                                    ui.separator();
-                                    ui.label(RichText::new("Training Data").size(10.0).color(theme.fg_dim));
-                                    ui.selectable_value(&mut self.export_format, ExportFormat::OpenAIJsonl, "OpenAI JSONL");
-                                    ui.selectable_value(&mut self.export_format, ExportFormat::AlpacaJson, "Alpaca JSON");
-                                });
-                        });
+                        for version in &versions {
+                            let is_current = version.version == self.default_version;
+                            let label = Self::version_display_name(&version.version);
 
-                        ui.add_space(4.0);
+                            let bg = if is_current {
+                                theme.selection
+                            } else {
+                                Color32::TRANSPARENT
+                            };
 
-                        // Output directory
-                        ui.horizontal(|ui| {
-                            ui.label(RichText::new("Output:").size(11.0).color(theme.fg_dim));
-                            ui.add(egui::TextEdit::singleline(&mut self.export_output_dir)
-                                .desired_width(180.0));
-                        });
+                            egui::Frame::none()
+                                .fill(bg)
+                                .rounding(Rounding::same(4.0))
+                                .inner_margin(egui::Margin::symmetric(8.0, 4.0))
+                                .show(ui, |ui| {
+                                    let btn = ui.add(
+                                        egui::Button::new(
+                                            RichText::new(&label)
+                                                .color(if is_current {
+                                                    theme.accent
+                                                } else {
+                                                    theme.fg
+                                                })
+                                                .size(12.0),
+                                        )
+                                        .frame(false)
+                                        .min_size(Vec2::new(ui.available_width() - 16.0, 22.0)),
+                                    );
 
-                        ui.add_space(8.0);
+                                    if btn.hovered() {
+                                        ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+                                    }
 
-                        ui.horizontal(|ui| {
-                            if styled_button_accent(ui, "Export All", Vec2::new(90.0, 26.0), theme).clicked() {
-                                let cmd = format!(
-                                    "cd ~/nixos-cursor/services/cursor-docs && mix cursor_docs.chat --export-all --format {} --output-dir {}",
-                                    self.export_format.file_extension(),
-                                    self.export_output_dir
-                                );
-                                self.set_status(&format!("Run: {}", cmd));
-                                self.show_export_dialog = false;
-                            }
-                            if styled_button(ui, "Cancel", Vec2::new(70.0, 26.0)).clicked() {
-                                self.show_export_dialog = false;
-                            }
-                        });
+                                    if btn.clicked() {
+                                        selected = Some(version.version.clone());
+                                    }
+                                });
+                        }
 
-                        ui.add_space(4.0);
-                        ui.label(
-                            RichText::new("Tip: Use cursor-docs CLI for batch exports")
-                                .size(9.0)
-                                .color(theme.fg_dim)
-                                .italics(),
-                        );
+                        if let Some(ver) = selected {
+                            self.set_default_version(&ver);
+                        }
                     });
             }
-        });
-    }
-
-    /// # TODO(P1): Release v0.3.0 - Security Panel Polish
-    /// - [ ] Wire up NPM scan results display (show blocked packages)
-    /// - [ ] Add "Jump to" buttons for security findings
-    /// - [ ] Show CVE details in expandable sections
-    /// - [ ] Add Socket.dev links for package research
-    /// - [ ] Implement audit log export functionality
-    /// - [ ] Add scan history with timestamps
-    fn show_sentinel_panel(&mut self, ui: &mut egui::Ui, theme: Theme) {
-        egui::ScrollArea::vertical()
-            .auto_shrink([false; 2])
-            .show(ui, |ui| {
-                ui.add_space(SECTION_SPACING);
 
-                // Overview Section
-                panel_header(ui, "SECURITY OVERVIEW", theme);
-                ui.add_space(ELEMENT_SPACING);
+            ui.add_space(20.0);
+            ui.horizontal(|ui| {
+                ui.add_space(16.0);
+                ui.label(
+                    RichText::new("BEHAVIOR")
+                        .size(theme.text_size(11.0))
+                        .color(theme.fg_dim)
+                        .strong(),
+                );
+            });
+            ui.add_space(12.0);
 
-                // Security Status Card
-                card_frame(theme).show(ui, |ui| {
-                    ui.horizontal(|ui| {
-                        ui.label(RichText::new("🛡️").size(18.0));
-                        ui.add_space(ELEMENT_SPACING);
-                        ui.vertical(|ui| {
-                            ui.label(
-                                RichText::new("System Status")
-                                    .color(theme.fg)
-                                    .strong()
-                                    .size(12.0),
-                            );
-                            ui.label(
-                                RichText::new("All security checks passing")
-                                    .color(theme.success)
-                                    .size(10.0),
-                            );
-                        });
-                    });
-                });
-                ui.add_space(SECTION_SPACING);
-
-                // Data Privacy Section
-                panel_header(ui, "DATA PRIVACY", theme);
-                ui.add_space(ELEMENT_SPACING);
-
-                card_frame(theme).show(ui, |ui| {
-                    // Chat Data Location
-                    ui.horizontal(|ui| {
-                        ui.label(RichText::new("📁").size(12.0));
-                        ui.add_space(TIGHT_SPACING);
-                        ui.label(
-                            RichText::new("Chat Data Location")
-                                .color(theme.fg)
-                                .size(11.0),
-                        );
-                    });
-                    ui.add_space(TIGHT_SPACING);
-                    ui.horizontal(|ui| {
-                        ui.add_space(16.0);
-                        if let Some(config_dir) = dirs::config_dir() {
-                            let path = config_dir.join("cursor-studio");
-                            ui.label(
-                                RichText::new(path.to_string_lossy())
-                                    .color(theme.fg_dim)
-                                    .size(9.0)
-                                    .family(egui::FontFamily::Monospace),
-                            );
-                        }
-                    });
-                    
-                    ui.add_space(ELEMENT_SPACING);
-                    
-                    // Data Encryption
-                    ui.horizontal(|ui| {
-                        ui.label(RichText::new("🔐").size(12.0));
-                        ui.add_space(TIGHT_SPACING);
-                        ui.label(
-                            RichText::new("Data Encryption")
-                                .color(theme.fg)
-                                .size(11.0),
-                        );
-                    });
-                    ui.add_space(TIGHT_SPACING);
-                    ui.horizontal(|ui| {
-                        ui.add_space(16.0);
-                        ui.label(
-                            RichText::new("Local storage only (not encrypted)")
-                                .color(theme.warning)
-                                .size(10.0),
-                        );
-                    });
-                });
-                ui.add_space(SECTION_SPACING);
+            // Toggles
+            self.settings_toggle_ui(
+                ui,
+                theme,
+                "Auto Sync",
+                "Sync settings between versions automatically",
+                "auto_sync",
+            );
+            ui.add_space(8.0);
 
-                // API Keys Section
-                panel_header(ui, "API KEYS & TOKENS", theme);
-                ui.add_space(ELEMENT_SPACING);
+            self.settings_toggle_ui(
+                ui,
+                theme,
+                "Import on Start",
+                "Import new chats when app starts",
+                "import_on_start",
+            );
+            ui.add_space(8.0);
 
-                card_frame(theme).show(ui, |ui| {
-                    ui.horizontal(|ui| {
-                        ui.label(RichText::new("⚠️").size(12.0));
-                        ui.add_space(TIGHT_SPACING);
-                        ui.label(
-                            RichText::new("No API keys stored in Cursor Studio")
-                                .color(theme.fg_dim)
-                                .size(10.0),
-                        );
-                    });
-                    ui.add_space(TIGHT_SPACING);
+            self.settings_toggle_ui(
+                ui,
+                theme,
+                "Auto Import",
+                "Periodically import new chats while the app is open",
+                "auto_import",
+            );
+            if self.auto_import_enabled {
+                ui.horizontal(|ui| {
+                    ui.add_space(24.0);
                     ui.label(
-                        RichText::new("API keys are managed by Cursor directly")
+                        RichText::new("Every")
                             .color(theme.fg_dim)
-                            .size(9.0)
-                            .italics(),
+                            .size(theme.text_size(11.0)),
+                    );
+                    let slider = ui.add(
+                        egui::Slider::new(&mut self.auto_import_interval_mins, 1..=240)
+                            .show_value(true)
+                            .suffix(" min"),
                     );
+                    if slider.changed() {
+                        self.save_settings();
+                    }
                 });
-                ui.add_space(SECTION_SPACING);
+            }
+            ui.add_space(8.0);
 
-                // Scan Section
-                panel_header(ui, "SECURITY SCANS", theme);
-                ui.add_space(ELEMENT_SPACING);
+            ui.horizontal(|ui| {
+                ui.add_space(16.0);
+                ui.label(RichText::new("Chats per Page").color(theme.fg).size(12.0))
+                    .on_hover_text("How many chats the library loads at a time");
+                let slider = ui.add(
+                    egui::Slider::new(&mut self.conversation_page_size, 50..=1000).step_by(50.0),
+                );
+                if slider.changed() {
+                    self.conversations_loaded = self.conversation_page_size;
+                    self.save_settings();
+                    self.reload_conversations();
+                }
+            });
+            ui.add_space(8.0);
 
-                ui.horizontal(|ui| {
-                    ui.add_space(PANEL_PADDING);
-                    
-                    // Show scan in progress indicator or button
-                    if let Some((scanned, total)) = self.security_scan_progress {
-                        // Scan in progress - show progress bar
-                        let progress = if total > 0 { scanned as f32 / total as f32 } else { 0.0 };
-                        ui.vertical(|ui| {
-                            ui.add(egui::ProgressBar::new(progress)
-                                .desired_width(160.0)
-                                .text(format!("Scanning... {}/{}", scanned, total)));
-                        });
+            ui.horizontal(|ui| {
+                ui.add_space(16.0);
+                ui.label(RichText::new("Idle Lock").color(theme.fg).size(12.0))
+                    .on_hover_text("Hide chat content after a period without input");
+                let slider = ui.add(
+                    egui::Slider::new(&mut self.idle_lock_mins, 0..=120)
+                        .custom_formatter(|mins, _| {
+                            if mins == 0.0 {
+                                "Off".to_string()
+                            } else {
+                                format!("{} min", mins)
+                            }
+                        }),
+                );
+                if slider.changed() {
+                    self.last_input = std::time::Instant::now();
+                    self.save_settings();
+                }
+            });
+            ui.add_space(8.0);
+
+            ui.horizontal(|ui| {
+                ui.add_space(16.0);
+                ui.label(RichText::new("Auto-save").color(theme.fg).size(12.0))
+                    .on_hover_text("How often changed settings are written, so a crash keeps them");
+                let slider = ui.add(
+                    egui::Slider::new(&mut self.settings_autosave_secs, 0..=300)
+                        .step_by(5.0)
+                        .custom_formatter(|secs, _| {
+                            if secs == 0.0 {
+                                "On exit only".to_string()
+                            } else {
+                                format!("{} s", secs)
+                            }
+                        }),
+                );
+                if slider.changed() {
+                    self.save_settings();
+                }
+            });
+            ui.add_space(8.0);
+
+            ui.horizontal(|ui| {
+                ui.add_space(16.0);
+                ui.label(RichText::new("Privacy Hotkey").color(theme.fg).size(12.0))
+                    .on_hover_text("Instantly hide all content; press again to bring it back");
+                let field = ui.add(
+                    egui::TextEdit::singleline(&mut self.privacy_hotkey_input)
+                        .hint_text(DEFAULT_PRIVACY_HOTKEY)
+                        .desired_width(110.0),
+                );
+                if field.lost_focus() && self.privacy_hotkey_input.trim() != self.privacy_hotkey {
+                    let chord = self.privacy_hotkey_input.trim().to_string();
+                    if hotkey::parse_shortcut(&chord).is_some() {
+                        self.set_status(&format!("✓ Privacy hotkey set to {}", chord));
+                        self.privacy_hotkey = chord;
+                        self.save_settings();
                     } else {
-                        // No scan running - show button
-                        if styled_button(ui, "🔍 Scan Chat History", Vec2::new(160.0, 32.0))
-                            .on_hover_text("Scan for sensitive data in chat history")
-                            .clicked()
-                        {
-                            self.run_security_scan();
-                        }
+                        self.set_status(&format!(
+                            "✗ Invalid hotkey '{}': use Ctrl, Alt or Cmd plus one key",
+                            chord
+                        ));
                     }
-                });
-                ui.add_space(TIGHT_SPACING);
+                    self.privacy_hotkey_input = self.privacy_hotkey.clone();
+                }
+            });
+            ui.add_space(8.0);
 
-                ui.horizontal(|ui| {
-                    ui.add_space(PANEL_PADDING);
-                    if styled_button(ui, "🗑️ Purge Sensitive Data", Vec2::new(160.0, 28.0))
-                        .on_hover_text("Remove detected sensitive data from chat history")
-                        .clicked()
-                    {
-                        self.set_status("⚠️ Purge not yet implemented");
-                    }
-                });
+            self.settings_toggle_ui(
+                ui,
+                theme,
+                "Show All Versions",
+                "Include downloadable versions in list",
+                "show_all_versions",
+            );
+            ui.add_space(8.0);
 
-                // Show scan results if available
-                let mut jump_to_msg: Option<(String, String)> = None;
+            self.settings_toggle_ui(
+                ui,
+                theme,
+                "Launcher Entries",
+                "Add each installed version to the desktop's application menu",
+                "create_desktop_entries",
+            );
+            ui.add_space(8.0);
 
-                if let Some(ref results) = self.security_scan_results {
-                    ui.add_space(SECTION_SPACING);
-                    card_frame(theme).show(ui, |ui| {
-                            ui.label(
-                                RichText::new(format!("📊 Scan Results ({})", results.scanned_at))
-                                    .color(theme.fg)
-                                    .strong()
-                                    .size(11.0),
-                            );
-                            ui.add_space(4.0);
-                            ui.label(
-                                RichText::new(format!(
-                                    "Messages scanned: {}",
-                                    results.total_messages
-                                ))
-                                .color(theme.fg_dim)
-                                .size(10.0),
-                            );
+            self.settings_toggle_ui(
+                ui,
+                theme,
+                "Hide Archived in Stats",
+                "Exclude archived chats from dashboard and status bar counts",
+                "stats_exclude_archived",
+            );
+            ui.add_space(8.0);
 
-                            let total_found = results.potential_api_keys.len()
-                                + results.potential_passwords.len()
-                                + results.potential_secrets.len();
+            self.settings_toggle_ui(
+                ui,
+                theme,
+                "Relative Timestamps",
+                "Show \"2h ago\" instead of dates (hover for the exact time)",
+                "relative_timestamps",
+            );
+            ui.add_space(8.0);
 
-                            if total_found == 0 {
-                                ui.add_space(4.0);
-                                ui.label(
-                                    RichText::new("✓ No sensitive data detected")
-                                        .color(theme.success)
-                                        .size(11.0),
-                                );
-                            } else {
-                                ui.add_space(8.0);
+            self.settings_toggle_ui(
+                ui,
+                theme,
+                "Wrap Code Blocks",
+                "Wrap long code lines instead of scrolling sideways (each block can still be flipped)",
+                "code_wrap",
+            );
+            ui.add_space(8.0);
 
-                                if !results.potential_api_keys.is_empty() {
-                                    ui.horizontal(|ui| {
-                                        ui.label(RichText::new("🔑").size(12.0));
-                                        ui.label(
-                                            RichText::new(format!(
-                                                "API Keys: {}",
-                                                results.potential_api_keys.len()
-                                            ))
-                                            .color(theme.warning)
-                                            .size(10.0),
-                                        );
-                                    });
-                                    // Show first few with jump buttons
-                                    for (conv_id, msg_id, preview) in
-                                        results.potential_api_keys.iter().take(5)
-                                    {
-                                        ui.horizontal(|ui| {
-                                            ui.add_space(20.0);
-                                            if ui
-                                                .small_button("→")
-                                                .on_hover_text("Jump to message")
-                                                .clicked()
-                                            {
-                                                jump_to_msg =
-                                                    Some((conv_id.clone(), msg_id.clone()));
-                                            }
-                                            ui.label(
-                                                RichText::new(preview)
-                                                    .color(theme.fg_dim)
-                                                    .size(9.0)
-                                                    .family(egui::FontFamily::Monospace),
-                                            );
-                                        });
-                                    }
-                                    if results.potential_api_keys.len() > 5 {
-                                        ui.horizontal(|ui| {
-                                            ui.add_space(20.0);
-                                            ui.label(
-                                                RichText::new(format!(
-                                                    "... and {} more",
-                                                    results.potential_api_keys.len() - 5
-                                                ))
-                                                .color(theme.fg_dim)
-                                                .size(9.0),
-                                            );
-                                        });
-                                    }
-                                }
+            self.settings_toggle_ui(
+                ui,
+                theme,
+                "Hide Empty Messages",
+                "Leave out messages with no text, thinking or tool call (each chat can override)",
+                "hide_empty_messages",
+            );
+            ui.add_space(12.0);
 
-                                if !results.potential_passwords.is_empty() {
-                                    ui.add_space(4.0);
-                                    ui.horizontal(|ui| {
-                                        ui.label(RichText::new("🔒").size(12.0));
-                                        ui.label(
-                                            RichText::new(format!(
-                                                "Passwords: {}",
-                                                results.potential_passwords.len()
-                                            ))
-                                            .color(theme.error)
-                                            .size(10.0),
-                                        );
-                                    });
-                                    // Show with jump buttons
-                                    for (conv_id, msg_id, preview) in
-                                        results.potential_passwords.iter().take(3)
-                                    {
-                                        ui.horizontal(|ui| {
-                                            ui.add_space(20.0);
-                                            if ui
-                                                .small_button("→")
-                                                .on_hover_text("Jump to message")
-                                                .clicked()
-                                            {
-                                                jump_to_msg =
-                                                    Some((conv_id.clone(), msg_id.clone()));
-                                            }
-                                            ui.label(
-                                                RichText::new(preview)
-                                                    .color(theme.fg_dim)
-                                                    .size(9.0)
-                                                    .family(egui::FontFamily::Monospace),
-                                            );
-                                        });
-                                    }
-                                }
+            // Approval Mode selector
+            ui.horizontal(|ui| {
+                ui.add_space(16.0);
+                ui.label(
+                    RichText::new("Download Approval")
+                        .color(theme.fg)
+                        .size(12.0),
+                );
+            });
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                ui.add_space(24.0);
 
-                                if !results.potential_secrets.is_empty() {
-                                    ui.add_space(4.0);
-                                    ui.horizontal(|ui| {
-                                        ui.label(RichText::new("🔐").size(12.0));
-                                        ui.label(
-                                            RichText::new(format!(
-                                                "Secrets: {}",
-                                                results.potential_secrets.len()
-                                            ))
-                                            .color(theme.warning)
-                                            .size(10.0),
-                                        );
-                                    });
-                                    // Show with jump buttons
-                                    for (conv_id, msg_id, preview) in
-                                        results.potential_secrets.iter().take(3)
-                                    {
-                                        ui.horizontal(|ui| {
-                                            ui.add_space(20.0);
-                                            if ui
-                                                .small_button("→")
-                                                .on_hover_text("Jump to message")
-                                                .clicked()
-                                            {
-                                                jump_to_msg =
-                                                    Some((conv_id.clone(), msg_id.clone()));
-                                            }
-                                            ui.label(
-                                                RichText::new(preview)
-                                                    .color(theme.fg_dim)
-                                                    .size(9.0)
-                                                    .family(egui::FontFamily::Monospace),
-                                            );
-                                        });
-                                    }
-                                }
-                            }
-                        });
+                let current_mode = self.approval_manager.mode();
+                let modes = [
+                    (
+                        ApprovalMode::Gui,
+                        "Double-click",
+                        "Require second click to confirm",
+                    ),
+                    (ApprovalMode::Terminal, "Terminal", "Use terminal prompts"),
+                    (
+                        ApprovalMode::AutoApprove,
+                        "Auto",
+                        "Skip confirmation (risky)",
+                    ),
+                ];
+
+                for (mode, label, tooltip) in modes {
+                    let is_selected = current_mode == mode;
+                    let btn_text = RichText::new(label)
+                        .size(theme.text_size(10.0))
+                        .color(if is_selected { theme.selected_fg } else { theme.fg });
+
+                    let btn = egui::Button::new(btn_text)
+                        .fill(if is_selected {
+                            theme.accent
+                        } else {
+                            theme.input_bg
+                        })
+                        .min_size(egui::vec2(70.0, 22.0));
+
+                    if ui.add(btn).on_hover_text(tooltip).clicked() {
+                        self.approval_manager.set_mode(mode);
+                        self.set_status(&format!("✓ Approval mode: {}", label));
+                    }
+                    ui.add_space(4.0);
                 }
+            });
 
-                // Process jump-to after UI
-                if let Some((conv_id, msg_id)) = jump_to_msg {
-                    self.scroll_to_message(&conv_id, &msg_id);
+            ui.add_space(20.0);
+            self.show_launch_env_settings(ui, theme);
+
+            ui.add_space(20.0);
+            self.show_network_settings(ui, theme);
+
+            ui.add_space(20.0);
+            ui.horizontal(|ui| {
+                ui.add_space(16.0);
+                ui.label(
+                    RichText::new("APPEARANCE")
+                        .size(theme.text_size(11.0))
+                        .color(theme.fg_dim)
+                        .strong(),
+                );
+            });
+            ui.add_space(8.0);
+
+            // Display Size presets
+            ui.horizontal(|ui| {
+                ui.add_space(16.0);
+                ui.label(RichText::new("Display Size").color(theme.fg).size(12.0));
+            });
+            ui.horizontal(|ui| {
+                ui.add_space(24.0);
+
+                // Preset buttons
+                let presets = [
+                    ("Small", 0.85, 8.0, 9.0),
+                    ("Normal", 1.0, 12.0, 11.0),
+                    ("Large", 1.15, 16.0, 12.0),
+                    ("XL", 1.3, 20.0, 14.0),
+                ];
+
+                for (name, scale, spacing, status_font) in presets {
+                    let is_selected = (self.font_scale - scale).abs() < 0.05;
+                    let btn_text = RichText::new(name)
+                        .size(theme.text_size(11.0))
+                        .color(if is_selected { theme.selected_fg } else { theme.fg });
+
+                    let btn = egui::Button::new(btn_text)
+                        .fill(if is_selected {
+                            theme.accent
+                        } else {
+                            theme.input_bg
+                        })
+                        .min_size(egui::vec2(50.0, 24.0));
+
+                    if ui.add(btn).clicked() {
+                        self.font_scale = scale;
+                        self.message_spacing = spacing;
+                        self.status_bar_font_size = status_font;
+                        self.save_settings();
+                        self.set_status(&format!("✓ Display: {} ({:.0}%)", name, scale * 100.0));
+                    }
+                    ui.add_space(4.0);
                 }
+            });
+            ui.add_space(8.0);
 
+            // Density presets (conversation spacing, message padding, card margins)
+            ui.horizontal(|ui| {
                 ui.add_space(16.0);
+                ui.label(RichText::new("Density").color(theme.fg).size(12.0));
+            });
+            ui.horizontal(|ui| {
+                ui.add_space(24.0);
 
-                // NPM Package Security Section
-                ui.horizontal(|ui| {
-                    ui.add_space(16.0);
-                    ui.label(
-                        RichText::new("NPM PACKAGE SECURITY")
-                            .size(11.0)
-                            .color(theme.fg_dim)
-                            .strong(),
-                    );
-                });
+                for density in Density::ALL {
+                    let is_selected = self.density == density;
+                    let btn_text = RichText::new(density.label())
+                        .size(theme.text_size(11.0))
+                        .color(if is_selected { theme.selected_fg } else { theme.fg });
+
+                    let btn = egui::Button::new(btn_text)
+                        .fill(if is_selected {
+                            theme.accent
+                        } else {
+                            theme.input_bg
+                        })
+                        .min_size(egui::vec2(70.0, 24.0));
+
+                    if ui.add(btn).clicked() {
+                        self.density = density;
+                        self.message_spacing = density.message_spacing();
+                        self.save_settings();
+                        self.set_status(&format!("✓ Density: {}", density.label()));
+                    }
+                    ui.add_space(4.0);
+                }
+            });
+            ui.add_space(12.0);
+
+            // Fine-tuning section (collapsible)
+            ui.horizontal(|ui| {
+                ui.add_space(16.0);
+                ui.label(
+                    RichText::new("Fine-tune")
+                        .color(theme.fg_dim)
+                        .size(theme.text_size(11.0)),
+                );
+            });
+            ui.add_space(4.0);
+
+            // Font scale - compact with +/- buttons
+            ui.horizontal(|ui| {
+                ui.add_space(24.0);
+                ui.label(RichText::new("Scale").color(theme.fg_dim).size(theme.text_size(10.0)));
                 ui.add_space(8.0);
 
-                // Blocklist stats
-                let stats = self.npm_scanner.get_blocklist_stats();
-                egui::Frame::none()
-                    .fill(theme.code_bg)
-                    .rounding(Rounding::same(8.0))
-                    .inner_margin(egui::Margin::same(12.0))
-                    .show(ui, |ui| {
-                        ui.label(
-                            RichText::new("📦 Blocklist Database")
-                                .color(theme.fg)
-                                .strong()
-                                .size(11.0),
-                        );
-                        ui.add_space(4.0);
-                        ui.label(
-                            RichText::new(format!(
-                                "Version: {} • Updated: {}",
-                                stats.version, stats.last_updated
-                            ))
-                            .color(theme.fg_dim)
-                            .size(10.0),
-                        );
-                        ui.label(
-                            RichText::new(format!(
-                                "{} blocked packages • {} with CVEs",
-                                stats.total_packages, stats.packages_with_cve
-                            ))
-                            .color(theme.warning)
-                            .size(10.0),
-                        );
+                if ui.small_button("−").clicked() {
+                    self.font_scale = (self.font_scale - 0.05).max(0.7);
+                    self.save_settings();
+                }
+                ui.label(
+                    RichText::new(format!("{:.0}%", self.font_scale * 100.0))
+                        .color(theme.fg)
+                        .size(theme.text_size(11.0))
+                        .monospace(),
+                );
+                if ui.small_button("+").clicked() {
+                    self.font_scale = (self.font_scale + 0.05).min(1.6);
+                    self.save_settings();
+                }
 
-                        // Show categories (sorted for stable display order)
-                        ui.add_space(4.0);
-                        let mut categories: Vec<_> = stats.categories.iter().collect();
-                        categories.sort_by_key(|(name, _)| name.as_str());
-                        for (name, count) in categories {
+                ui.add_space(16.0);
+
+                ui.label(RichText::new("Gap").color(theme.fg_dim).size(theme.text_size(10.0)));
+                ui.add_space(8.0);
+                if ui.small_button("−").clicked() {
+                    self.message_spacing = (self.message_spacing - 2.0).max(4.0);
+                    self.save_settings();
+                }
+                ui.label(
+                    RichText::new(format!("{:.0}px", self.message_spacing))
+                        .color(theme.fg)
+                        .size(theme.text_size(11.0))
+                        .monospace(),
+                );
+                if ui.small_button("+").clicked() {
+                    self.message_spacing = (self.message_spacing + 2.0).min(32.0);
+                    self.save_settings();
+                }
+            });
+
+            // Status bar font - compact
+            ui.horizontal(|ui| {
+                ui.add_space(24.0);
+                ui.label(RichText::new("Status").color(theme.fg_dim).size(theme.text_size(10.0)));
+                ui.add_space(4.0);
+                if ui.small_button("−").clicked() {
+                    self.status_bar_font_size = (self.status_bar_font_size - 1.0).max(8.0);
+                    self.save_settings();
+                }
+                ui.label(
+                    RichText::new(format!("{:.0}px", self.status_bar_font_size))
+                        .color(theme.fg)
+                        .size(theme.text_size(11.0))
+                        .monospace(),
+                );
+                if ui.small_button("+").clicked() {
+                    self.status_bar_font_size = (self.status_bar_font_size + 1.0).min(16.0);
+                    self.save_settings();
+                }
+            });
+
+            // Minimum text size - floor for captions and other small fixed-size labels
+            ui.horizontal(|ui| {
+                ui.add_space(24.0);
+                ui.label(RichText::new("Min text").color(theme.fg_dim).size(theme.text_size(10.0)))
+                    .on_hover_text("Smallest size captions and status text are drawn at");
+                ui.add_space(4.0);
+                let (min, max) = (
+                    *theme::MIN_TEXT_SIZE_RANGE.start(),
+                    *theme::MIN_TEXT_SIZE_RANGE.end(),
+                );
+                if ui.small_button("−").clicked() {
+                    self.min_text_size = (self.min_text_size - 1.0).max(min);
+                    self.save_settings();
+                }
+                ui.label(
+                    RichText::new(format!("{:.0}px", self.min_text_size))
+                        .color(theme.fg)
+                        .size(theme.text_size(11.0))
+                        .monospace(),
+                );
+                if ui.small_button("+").clicked() {
+                    self.min_text_size = (self.min_text_size + 1.0).min(max);
+                    self.save_settings();
+                }
+            });
+
+            ui.add_space(20.0);
+            ui.horizontal(|ui| {
+                ui.add_space(16.0);
+                ui.label(
+                    RichText::new("MESSAGE ALIGNMENT")
+                        .size(theme.text_size(11.0))
+                        .color(theme.fg_dim)
+                        .strong(),
+                );
+            });
+            ui.add_space(8.0);
+
+            // Display preferences for message types
+            let alignments = ["left", "right", "center"];
+            let content_types = [
+                ("user", "User Messages"),
+                ("assistant", "AI Responses"),
+                ("thinking", "Thinking Blocks"),
+                ("tool_call", "Tool Calls"),
+            ];
+
+            // Clone prefs to avoid borrow issues
+            let current_prefs = self.display_prefs.clone();
+            let mut pref_change: Option<DisplayPreference> = None;
+            let mut color_change: Option<DisplayPreference> = None;
+
+            for (content_type, label) in content_types {
+                ui.horizontal(|ui| {
+                    ui.add_space(16.0);
+                    ui.label(RichText::new(label).color(theme.fg).size(12.0));
+
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.add_space(16.0);
+
+                        // Find current preference (or the schema default)
+                        let pref = current_prefs
+                            .iter()
+                            .find(|p| p.content_type == content_type)
+                            .cloned()
+                            .unwrap_or_else(|| DisplayPreference {
+                                content_type: content_type.to_string(),
+                                alignment: "left".to_string(),
+                                style: "default".to_string(),
+                                collapsed_by_default: false,
+                                fg_color: None,
+                                bg_color: None,
+                            });
+                        let current = pref.alignment.as_str();
+
+                        // Show buttons in order: left, center, right (reversed for RTL layout)
+                        for align in ["right", "center", "left"].iter() {
+                            let is_selected = current == *align;
+                            let icon = match *align {
+                                "left" => "◀ L",
+                                "center" => "◆ C",
+                                "right" => "R ▶",
+                                _ => "•",
+                            };
+
+                            // Use frame + background for selected state (theme-independent visibility)
+                            let btn = if is_selected {
+                                ui.add(
+                                    egui::Button::new(
+                                        RichText::new(icon)
+                                            .color(theme.selected_fg)
+                                            .size(theme.text_size(10.0))
+                                            .strong(),
+                                    )
+                                    .fill(theme.selected_bg)
+                                    .min_size(Vec2::new(28.0, 20.0)),
+                                )
+                            } else {
+                                ui.add(
+                                    egui::Button::new(
+                                        RichText::new(icon)
+                                            .color(theme.fg_dim)
+                                            .size(theme.text_size(10.0)),
+                                    )
+                                    .fill(Color32::TRANSPARENT)
+                                    .min_size(Vec2::new(28.0, 20.0)),
+                                )
+                            }
+                            .on_hover_text(*align);
+
+                            if btn.clicked() {
+                                pref_change = Some(DisplayPreference {
+                                    alignment: align.to_string(),
+                                    ..pref.clone()
+                                });
+                            }
+                        }
+
+                        ui.add_space(8.0);
+
+                        // Color overrides (background, then foreground in RTL order)
+                        let (fg_override, bg_override) = pref_colors(&current_prefs, content_type);
+                        let mut bg = bg_override.unwrap_or(theme.sidebar_bg);
+                        if egui::color_picker::color_edit_button_srgba(
+                            ui,
+                            &mut bg,
+                            egui::color_picker::Alpha::Opaque,
+                        )
+                        .on_hover_text("Background color")
+                        .changed()
+                        {
+                            color_change = Some(DisplayPreference {
+                                bg_color: Some(color_to_hex(bg)),
+                                ..pref.clone()
+                            });
+                        }
+                        let mut fg = fg_override.unwrap_or(role_color(content_type, theme));
+                        if egui::color_picker::color_edit_button_srgba(
+                            ui,
+                            &mut fg,
+                            egui::color_picker::Alpha::Opaque,
+                        )
+                        .on_hover_text("Text color")
+                        .changed()
+                        {
+                            color_change = Some(DisplayPreference {
+                                fg_color: Some(color_to_hex(fg)),
+                                ..pref.clone()
+                            });
+                        }
+                        if (fg_override.is_some() || bg_override.is_some())
+                            && ui
+                                .small_button("↺")
+                                .on_hover_text("Reset to theme colors")
+                                .clicked()
+                        {
+                            pref_change = Some(DisplayPreference {
+                                fg_color: None,
+                                bg_color: None,
+                                ..pref.clone()
+                            });
+                        }
+                    });
+                });
+                ui.add_space(4.0);
+            }
+
+            // A color picker reports a change every frame of a drag: show it
+            // right away, but only write it once the pointer is released
+            if let Some(pref) = color_change {
+                match self
+                    .display_prefs
+                    .iter_mut()
+                    .find(|p| p.content_type == pref.content_type)
+                {
+                    Some(existing) => *existing = pref.clone(),
+                    None => self.display_prefs.push(pref.clone()),
+                }
+                self.unsaved_display_pref = Some(pref);
+                ui.ctx().request_repaint();
+            }
+            if let Some(pref) = &pref_change {
+                // Built from the in-memory prefs, so it already has the color
+                if self
+                    .unsaved_display_pref
+                    .as_ref()
+                    .is_some_and(|p| p.content_type == pref.content_type)
+                {
+                    self.unsaved_display_pref = None;
+                }
+            } else if !ui.ctx().input(|i| i.pointer.any_down()) {
+                pref_change = self.unsaved_display_pref.take();
+            }
+
+            // Apply preference change after UI iteration
+            if let Some(pref) = pref_change {
+                let alignment_changed = !current_prefs.iter().any(|p| {
+                    p.content_type == pref.content_type && p.alignment == pref.alignment
+                });
+                if let Err(e) = self.db.set_display_preference(
+                    &pref.content_type,
+                    &pref.alignment,
+                    &pref.style,
+                    pref.collapsed_by_default,
+                    pref.fg_color.as_deref(),
+                    pref.bg_color.as_deref(),
+                ) {
+                    self.set_status(&format!("✗ Failed to save preference: {}", e));
+                } else {
+                    self.display_prefs = self.db.get_display_preferences().unwrap_or_default();
+                    if alignment_changed {
+                        self.set_status(&format!("✓ Alignment changed to {}", pref.alignment));
+                    }
+                    // Request repaint to update message display immediately
+                    ui.ctx().request_repaint();
+                }
+            }
+
+            ui.add_space(12.0);
+            ui.horizontal(|ui| {
+                ui.add_space(16.0);
+                ui.label(
+                    RichText::new("DATA")
+                        .size(theme.text_size(11.0))
+                        .color(theme.fg_dim)
+                        .strong(),
+                );
+            });
+            ui.add_space(8.0);
+
+            ui.horizontal(|ui| {
+                ui.add_space(12.0);
+                if styled_button(ui, "📂 Open Data Folder", Vec2::new(160.0, 32.0)).clicked() {
+                    if let Some(config_dir) = dirs::config_dir() {
+                        let data_dir = config_dir.join("cursor-studio");
+                        if let Err(e) = open_with_default_app(&data_dir) {
+                            self.set_status(&format!("✗ Failed to open: {}", e));
+                        } else {
+                            self.set_status("✓ Opened data folder");
+                        }
+                    }
+                }
+            });
+            ui.add_space(4.0);
+
+            ui.horizontal(|ui| {
+                ui.add_space(12.0);
+                if styled_button(ui, "↻ Refresh All", Vec2::new(160.0, 32.0))
+                    .on_hover_text("Refresh all data")
+                    .clicked()
+                {
+                    self.refresh_all();
+                }
+            });
+            ui.add_space(4.0);
+
+            ui.horizontal(|ui| {
+                ui.add_space(12.0);
+                if styled_button(ui, "🔍 Detect Installations", Vec2::new(160.0, 32.0))
+                    .on_hover_text("Search for Cursor AppImages and data directories to register")
+                    .clicked()
+                {
+                    self.detect_installations();
+                }
+            });
+            ui.add_space(4.0);
+
+            ui.horizontal(|ui| {
+                ui.add_space(12.0);
+                if styled_button(ui, "🧭 Setup Wizard", Vec2::new(160.0, 32.0))
+                    .on_hover_text("Run the first-run setup again")
+                    .clicked()
+                {
+                    self.onboarding_step = Some(OnboardingStep::Detect);
+                }
+            });
+            ui.add_space(8.0);
+
+            self.settings_toggle_ui(
+                ui,
+                theme,
+                "Developer Tools",
+                "Show debugging actions like copying a conversation as JSON",
+                "developer_mode",
+            );
+
+            ui.add_space(20.0);
+            ui.horizontal(|ui| {
+                ui.add_space(16.0);
+                ui.label(
+                    RichText::new("DIAGNOSTICS")
+                        .size(theme.text_size(11.0))
+                        .color(theme.fg_dim)
+                        .strong(),
+                );
+            });
+            ui.add_space(8.0);
+
+            ui.horizontal(|ui| {
+                ui.add_space(12.0);
+                if self.diagnostics_thread.is_some() {
+                    ui.spinner();
+                    ui.label(
+                        RichText::new("Running checks...")
+                            .color(theme.fg_dim)
+                            .size(theme.text_size(11.0)),
+                    );
+                } else if styled_button(ui, "🩺 Run Diagnostics", Vec2::new(160.0, 32.0))
+                    .on_hover_text(
+                        "Check the library, Cursor installs, fonts, download hosts, \
+                         p2p-sync and config.json",
+                    )
+                    .clicked()
+                {
+                    self.run_diagnostics();
+                }
+            });
+
+            if let Some(report) = &self.diagnostics_report {
+                ui.add_space(8.0);
+                for check in &report.checks {
+                    let color = match check.status {
+                        diagnostics::CheckStatus::Pass => theme.success,
+                        diagnostics::CheckStatus::Warn => theme.warning,
+                        diagnostics::CheckStatus::Fail => theme.error,
+                    };
+                    ui.horizontal(|ui| {
+                        ui.add_space(16.0);
+                        ui.label(RichText::new(check.status.icon()).color(color).size(12.0));
+                        ui.label(
+                            RichText::new(check.name)
+                                .color(theme.fg)
+                                .size(theme.text_size(11.0)),
+                        );
+                    });
+                    ui.horizontal(|ui| {
+                        ui.add_space(36.0);
+                        ui.add(
+                            egui::Label::new(
+                                RichText::new(&check.detail)
+                                    .color(theme.fg_dim)
+                                    .size(theme.text_size(10.0)),
+                            )
+                            .wrap(),
+                        );
+                    });
+                }
+                ui.add_space(4.0);
+                let mut copied = false;
+                ui.horizontal(|ui| {
+                    ui.add_space(12.0);
+                    if styled_button(ui, "📋 Copy Report", Vec2::new(160.0, 28.0))
+                        .on_hover_text("Copy as text for a bug report")
+                        .clicked()
+                    {
+                        ui.ctx().copy_text(report.to_text());
+                        copied = true;
+                    }
+                });
+                if copied {
+                    self.set_status("✓ Copied diagnostics report");
+                }
+            }
+
+            ui.add_space(20.0);
+            ui.horizontal(|ui| {
+                ui.add_space(16.0);
+                ui.label(
+                    RichText::new("VERSION CLEANUP")
+                        .size(theme.text_size(11.0))
+                        .color(theme.fg_dim)
+                        .strong(),
+                );
+            });
+            ui.add_space(8.0);
+
+            // Show installed versions with cleanup options
+            let installed_versions: Vec<_> = self.versions.iter()
+                .filter(|v| v.version != "default")
+                .map(|v| v.version.clone())
+                .collect();
+
+            if installed_versions.is_empty() {
+                ui.horizontal(|ui| {
+                    ui.add_space(16.0);
+                    ui.label(
+                        RichText::new("No installed versions to clean up")
+                            .color(theme.fg_dim)
+                            .size(theme.text_size(11.0))
+                            .italics(),
+                    );
+                });
+            } else {
+                ui.horizontal(|ui| {
+                    ui.add_space(16.0);
+                    ui.label(
+                        RichText::new("Select versions to remove:")
+                            .color(theme.fg)
+                            .size(theme.text_size(11.0)),
+                    );
+                });
+                ui.add_space(4.0);
+
+                // Version checkboxes
+                let mut to_remove: Vec<String> = Vec::new();
+                for version in &installed_versions {
+                    let checkbox_id = ui.make_persistent_id(format!("cleanup_{}", version));
+                    let mut checked = ui.data_mut(|d| d.get_temp::<bool>(checkbox_id).unwrap_or(false));
+
+                    ui.horizontal(|ui| {
+                        ui.add_space(24.0);
+                        if ui.checkbox(&mut checked, format!("v{}", version)).changed() {
+                            ui.data_mut(|d| d.insert_temp(checkbox_id, checked));
+                        }
+                        ui.label(
+                            RichText::new("~150MB")
+                                .color(theme.fg_dim)
+                                .size(theme.text_size(10.0)),
+                        );
+                    });
+
+                    if checked {
+                        to_remove.push(version.clone());
+                    }
+                }
+
+                ui.add_space(8.0);
+
+                if !to_remove.is_empty() {
+                    ui.horizontal(|ui| {
+                        ui.add_space(16.0);
+                        let btn_text = format!("🗑️ Remove {} version{}", 
+                            to_remove.len(),
+                            if to_remove.len() > 1 { "s" } else { "" }
+                        );
+                        if styled_button(ui, &btn_text, Vec2::new(160.0, 28.0))
+                            .on_hover_text(format!("Remove: {}", to_remove.join(", ")))
+                            .clicked()
+                        {
+                            let mut removed = 0;
+                            for version in &to_remove {
+                                if let Err(e) = self.remove_version(version) {
+                                    self.set_status(&format!("✗ Failed to remove {}: {}", version, e));
+                                } else {
+                                    removed += 1;
+                                    let checkbox_id = ui.make_persistent_id(format!("cleanup_{}", version));
+                                    ui.data_mut(|d| d.insert_temp(checkbox_id, false));
+                                }
+                            }
+                            if removed > 0 {
+                                self.refresh_versions();
+                                self.set_status(&format!("✓ Removed {} version{}", removed, if removed > 1 { "s" } else { "" }));
+                            }
+                        }
+                    });
+                }
+            }
+
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                ui.add_space(12.0);
+                if styled_button(ui, "🧹 Clean cache", Vec2::new(160.0, 28.0))
+                    .on_hover_text("Delete cached copies of installed versions and interrupted downloads older than a day")
+                    .clicked()
+                {
+                    self.clean_download_cache();
+                }
+            });
+            ui.add_space(4.0);
+
+            ui.horizontal(|ui| {
+                ui.add_space(12.0);
+                if let Some((checked, total)) = self.verify_progress {
+                    ui.add(egui::Spinner::new().size(14.0));
+                    ui.label(
+                        RichText::new(format!("Verifying {}/{}...", checked, total))
+                            .color(theme.fg_dim)
+                            .size(theme.text_size(11.0)),
+                    );
+                    ui.ctx().request_repaint();
+                } else if styled_button(ui, "🛡 Verify installed", Vec2::new(160.0, 28.0))
+                    .on_hover_text("Re-hash installed AppImages and compare with the registry")
+                    .clicked()
+                {
+                    self.run_version_verification();
+                }
+            });
+
+            if let Some(results) = &self.verify_results {
+                ui.add_space(4.0);
+                for (version, status) in results {
+                    let (icon, color, detail) = match status {
+                        versions::IntegrityStatus::Verified => ("✓", theme.success, "hash matches"),
+                        versions::IntegrityStatus::Mismatch { .. } => {
+                            ("✗", theme.error, "hash MISMATCH - reinstall this version")
+                        }
+                        versions::IntegrityStatus::NoHash => {
+                            ("?", theme.warning, "no registry hash to compare")
+                        }
+                        versions::IntegrityStatus::Error(_) => ("✗", theme.error, "could not read"),
+                    };
+                    let hover = match status {
+                        versions::IntegrityStatus::Mismatch { expected, computed } => {
+                            format!("Expected: {}\nComputed: {}", expected, computed)
+                        }
+                        versions::IntegrityStatus::Error(e) => e.clone(),
+                        _ => detail.to_string(),
+                    };
+                    ui.horizontal(|ui| {
+                        ui.add_space(24.0);
+                        ui.label(RichText::new(icon).color(color).size(theme.text_size(11.0)));
+                        ui.label(
+                            RichText::new(format!("v{} - {}", version, detail))
+                                .color(theme.fg)
+                                .size(theme.text_size(11.0)),
+                        )
+                        .on_hover_text(hover);
+                    });
+                }
+            }
+
+            ui.add_space(20.0);
+            ui.horizontal(|ui| {
+                ui.add_space(16.0);
+                ui.label(
+                    RichText::new("RESOURCES")
+                        .size(theme.text_size(11.0))
+                        .color(theme.fg_dim)
+                        .strong(),
+                );
+            });
+            ui.add_space(8.0);
+
+            // CPU threads
+            let max_threads = std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(8);
+            ui.horizontal(|ui| {
+                ui.add_space(16.0);
+                ui.label(
+                    RichText::new(format!("CPU Threads (max {})", max_threads))
+                        .color(theme.fg)
+                        .size(12.0),
+                );
+            });
+            ui.horizontal(|ui| {
+                ui.add_space(24.0);
+                let slider = ui.add(
+                    egui::Slider::new(&mut self.max_cpu_threads, 1..=max_threads).show_value(true),
+                );
+                if slider.changed() {
+                    self.save_settings();
+                    self.set_status(&format!("✓ Max CPU threads: {}", self.max_cpu_threads));
+                }
+            });
+            ui.add_space(8.0);
+
+            // RAM limit
+            ui.horizontal(|ui| {
+                ui.add_space(16.0);
+                ui.label(RichText::new("RAM Limit").color(theme.fg).size(12.0));
+            });
+            ui.horizontal(|ui| {
+                ui.add_space(24.0);
+                let slider = ui.add(
+                    egui::Slider::new(&mut self.max_ram_mb, 512..=16384)
+                        .show_value(true)
+                        .suffix(" MB"),
+                );
+                if slider.changed() {
+                    self.save_settings();
+                    self.set_status(&format!("✓ RAM limit: {} MB", self.max_ram_mb));
+                }
+            });
+            ui.add_space(8.0);
+
+            // VRAM limit (for future GPU features)
+            ui.horizontal(|ui| {
+                ui.add_space(16.0);
+                ui.label(RichText::new("VRAM Limit").color(theme.fg).size(12.0));
+            });
+            ui.horizontal(|ui| {
+                ui.add_space(24.0);
+                let slider = ui.add(
+                    egui::Slider::new(&mut self.max_vram_mb, 256..=32768)
+                        .show_value(true)
+                        .suffix(" MB"),
+                );
+                if slider.changed() {
+                    self.save_settings();
+                    self.set_status(&format!("✓ VRAM limit: {} MB", self.max_vram_mb));
+                }
+            });
+            ui.add_space(8.0);
+
+            // Storage limit
+            ui.horizontal(|ui| {
+                ui.add_space(16.0);
+                ui.label(RichText::new("Storage Limit").color(theme.fg).size(12.0));
+            });
+            ui.horizontal(|ui| {
+                ui.add_space(24.0);
+                let slider = ui.add(
+                    egui::Slider::new(&mut self.storage_limit_mb, 1024..=102400)
+                        .show_value(true)
+                        .custom_formatter(|v, _| format!("{:.1} GB", v / 1024.0))
+                        .custom_parser(|s| {
+                            s.replace(" GB", "").parse::<f64>().ok().map(|v| v * 1024.0)
+                        }),
+                );
+                if slider.changed() {
+                    self.save_settings();
+                    self.set_status(&format!(
+                        "✓ Storage limit: {:.1} GB",
+                        self.storage_limit_mb as f32 / 1024.0
+                    ));
+                }
+            });
+
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                ui.add_space(16.0);
+                ui.label(
+                    RichText::new(
+                        "Storage limit covers downloaded and installed versions; RAM/VRAM limits are for future features",
+                    )
+                        .color(theme.fg_dim)
+                        .size(theme.text_size(9.0))
+                        .italics(),
+                );
+            });
+            ui.add_space(12.0);
+
+            self.show_cache_dir_settings(ui, theme);
+
+            ui.add_space(20.0);
+            ui.separator();
+            ui.add_space(8.0);
+
+            ui.horizontal(|ui| {
+                ui.add_space(16.0);
+                ui.label(
+                    RichText::new("ABOUT")
+                        .size(theme.text_size(11.0))
+                        .color(theme.fg_dim)
+                        .strong(),
+                );
+            });
+            ui.add_space(8.0);
+
+            ui.horizontal(|ui| {
+                ui.add_space(16.0);
+                ui.label(
+                    RichText::new("Cursor Studio v0.2.0")
+                        .color(theme.fg_dim)
+                        .size(12.0),
+                );
+            });
+            ui.horizontal(|ui| {
+                ui.add_space(16.0);
+                ui.label(
+                    RichText::new("Built with egui + Rust")
+                        .color(theme.fg_dim)
+                        .size(theme.text_size(11.0)),
+                );
+            });
+        });
+    }
+
+    fn settings_dropdown_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        theme: Theme,
+        label: &str,
+        current_value: &str,
+        toggle_key: &str,
+    ) {
+        ui.horizontal(|ui| {
+            ui.add_space(16.0);
+            ui.label(RichText::new(label).color(theme.fg).size(13.0));
+        });
+        ui.horizontal(|ui| {
+            ui.add_space(16.0);
+
+            let is_open = match toggle_key {
+                "show_theme" => self.show_theme_picker,
+                "show_version" => self.show_version_picker,
+                _ => false,
+            };
+
+            let arrow = if is_open { "▲" } else { "▼" };
+            let display = format!("{} {}", current_value, arrow);
+
+            egui::Frame::none()
+                .fill(theme.input_bg)
+                .rounding(Rounding::same(4.0))
+                .stroke(Stroke::new(1.0, theme.border))
+                .inner_margin(egui::Margin::symmetric(10.0, 6.0))
+                .show(ui, |ui| {
+                    let btn = ui
+                        .add(
+                            egui::Button::new(RichText::new(&display).color(theme.fg).size(12.0))
+                                .frame(false)
+                                .min_size(Vec2::new(180.0, 20.0)),
+                        )
+                        .on_hover_text(format!("Click to change {}", label.to_lowercase()));
+                    self.mark_focus_anchor(FocusRegion::LeftSidebar, &btn);
+                    paint_focus_ring(ui, &btn);
+
+                    if btn.hovered() {
+                        ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+                    }
+
+                    if btn.clicked() {
+                        match toggle_key {
+                            "show_theme" => self.show_theme_picker = !self.show_theme_picker,
+                            "show_version" => self.show_version_picker = !self.show_version_picker,
+                            _ => {}
+                        }
+                    }
+                });
+        });
+    }
+
+    /// Editor for environment variables passed to launched Cursor processes
+    fn show_launch_env_settings(&mut self, ui: &mut egui::Ui, theme: Theme) {
+        ui.horizontal(|ui| {
+            ui.add_space(16.0);
+            ui.label(
+                RichText::new("LAUNCH ENVIRONMENT")
+                    .size(theme.text_size(11.0))
+                    .color(theme.fg_dim)
+                    .strong(),
+            );
+        });
+        ui.add_space(4.0);
+        ui.horizontal_wrapped(|ui| {
+            ui.add_space(16.0);
+            ui.label(
+                RichText::new(
+                    "Added to the inherited environment when launching Cursor. \
+                     On Wayland/NixOS, ELECTRON_OZONE_PLATFORM_HINT=wayland or \
+                     NIXOS_OZONE_WL=1 enables native rendering.",
+                )
+                .size(theme.text_size(10.0))
+                .color(theme.fg_dim),
+            );
+        });
+        ui.add_space(8.0);
+
+        let mut changed = false;
+        let mut remove: Option<usize> = None;
+        for (i, (name, value)) in self.launch_env.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.add_space(24.0);
+                changed |= ui
+                    .add(
+                        egui::TextEdit::singleline(name)
+                            .hint_text("NAME")
+                            .desired_width(150.0)
+                            .font(egui::TextStyle::Monospace),
+                    )
+                    .lost_focus();
+                ui.label(RichText::new("=").color(theme.fg_dim));
+                changed |= ui
+                    .add(
+                        egui::TextEdit::singleline(value)
+                            .hint_text("value")
+                            .desired_width(ui.available_width() - 48.0)
+                            .font(egui::TextStyle::Monospace),
+                    )
+                    .lost_focus();
+                let remove_btn = ui
+                    .add(egui::Button::new(RichText::new("✕").color(theme.fg_dim)).frame(false))
+                    .on_hover_text("Remove variable");
+                accessible_label(ui, &remove_btn, "Remove variable");
+                if remove_btn.clicked() {
+                    remove = Some(i);
+                }
+            });
+        }
+
+        ui.horizontal(|ui| {
+            ui.add_space(24.0);
+            if styled_button(ui, "+ Add", Vec2::new(60.0, 22.0)).clicked() {
+                self.launch_env.push((String::new(), String::new()));
+            }
+            let presets = [
+                ("ELECTRON_OZONE_PLATFORM_HINT", "wayland"),
+                ("NIXOS_OZONE_WL", "1"),
+            ];
+            for (name, value) in presets {
+                let exists = self.launch_env.iter().any(|(n, _)| n.trim() == name);
+                if !exists
+                    && styled_button(ui, &format!("+ {}", name), Vec2::new(0.0, 22.0))
+                        .on_hover_text(format!("Add {}={}", name, value))
+                        .clicked()
+                {
+                    self.launch_env.push((name.to_string(), value.to_string()));
+                    changed = true;
+                }
+            }
+        });
+
+        if let Some(i) = remove {
+            self.launch_env.remove(i);
+            changed = true;
+        }
+        if changed {
+            self.save_launch_env();
+        }
+    }
+
+    /// Where downloaded AppImages are kept
+    fn show_cache_dir_settings(&mut self, ui: &mut egui::Ui, theme: Theme) {
+        let mut apply = false;
+        ui.horizontal(|ui| {
+            ui.add_space(16.0);
+            ui.label(RichText::new("Download Cache").color(theme.fg).size(12.0));
+        });
+        ui.horizontal(|ui| {
+            ui.add_space(24.0);
+            let default_dir = versions::default_cache_dir().display().to_string();
+            let response = ui.add_enabled(
+                !self.cache_dir_managed,
+                egui::TextEdit::singleline(&mut self.cache_dir_input)
+                    .hint_text(default_dir)
+                    .desired_width(ui.available_width() - 100.0)
+                    .font(egui::TextStyle::Monospace),
+            );
+            let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if ui
+                .add_enabled_ui(!self.cache_dir_managed, |ui| {
+                    styled_button(ui, "Apply", Vec2::new(60.0, 22.0))
+                })
+                .inner
+                .clicked()
+                || submitted
+            {
+                apply = true;
+            }
+        });
+        if !self.cache_dir_managed {
+            ui.horizontal(|ui| {
+                ui.add_space(24.0);
+                ui.checkbox(&mut self.relocate_cache, "Move existing downloads")
+                    .on_hover_text("Move completed and partial downloads to the new directory");
+            });
+        }
+
+        let hint = if self.cache_dir_managed {
+            format!(
+                "Set in config.json: {}",
+                versions::get_cache_dir().display()
+            )
+        } else {
+            format!(
+                "In use: {} (empty for the default)",
+                versions::get_cache_dir().display()
+            )
+        };
+        ui.horizontal(|ui| {
+            ui.add_space(24.0);
+            ui.label(
+                RichText::new(hint)
+                    .color(theme.fg_dim)
+                    .size(theme.text_size(10.0)),
+            );
+        });
+
+        if apply {
+            self.apply_cache_dir();
+        }
+    }
+
+    /// Proxy used for version downloads
+    fn show_network_settings(&mut self, ui: &mut egui::Ui, theme: Theme) {
+        ui.horizontal(|ui| {
+            ui.add_space(16.0);
+            ui.label(
+                RichText::new("NETWORK")
+                    .size(theme.text_size(11.0))
+                    .color(theme.fg_dim)
+                    .strong(),
+            );
+        });
+        ui.add_space(8.0);
+
+        ui.horizontal(|ui| {
+            ui.add_space(16.0);
+            ui.label(RichText::new("Download Proxy").color(theme.fg).size(12.0));
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.download_proxy)
+                    .hint_text("http://proxy:3128")
+                    .desired_width(ui.available_width() - 24.0)
+                    .font(egui::TextStyle::Monospace),
+            );
+            if response.lost_focus() {
+                let _ = self
+                    .db
+                    .set_config("ui.download_proxy", self.download_proxy.trim());
+            }
+        });
+
+        // Show what an empty setting falls back to
+        let env_proxy = versions::effective_proxy("https://downloads.cursor.com", None);
+        let hint = if !self.download_proxy.trim().is_empty() {
+            "Overrides HTTP_PROXY / HTTPS_PROXY / NO_PROXY".to_string()
+        } else if let Some(proxy) = env_proxy {
+            let proxy = versions::redact_proxy(&proxy);
+            format!("Using {} from the environment", proxy)
+        } else {
+            "Empty: uses HTTP_PROXY / HTTPS_PROXY / NO_PROXY (none set)".to_string()
+        };
+        ui.horizontal(|ui| {
+            ui.add_space(24.0);
+            ui.label(
+                RichText::new(hint)
+                    .color(theme.fg_dim)
+                    .size(theme.text_size(10.0)),
+            );
+        });
+        ui.add_space(8.0);
+
+        let advanced_id = ui.make_persistent_id("network_advanced");
+        let mut show_advanced = ui.data_mut(|d| d.get_temp::<bool>(advanced_id).unwrap_or(false));
+        ui.horizontal(|ui| {
+            ui.add_space(16.0);
+            let toggle_text = if show_advanced {
+                "▼ Advanced"
+            } else {
+                "▶ Advanced"
+            };
+            let toggle = ui.add(
+                egui::Button::new(
+                    RichText::new(toggle_text)
+                        .color(theme.fg_dim)
+                        .size(theme.text_size(11.0)),
+                )
+                .frame(false),
+            );
+            if toggle.clicked() {
+                show_advanced = !show_advanced;
+                ui.data_mut(|d| d.insert_temp(advanced_id, show_advanced));
+            }
+        });
+        if !show_advanced {
+            return;
+        }
+
+        let mut changed = false;
+        let rows = [
+            (
+                "Connect timeout",
+                "Give up on a server that doesn't accept the connection",
+                &mut self.download_connect_timeout_secs,
+                5..=120,
+                " s",
+            ),
+            (
+                "Read timeout",
+                "Treat the download as stalled when no data arrives for this long",
+                &mut self.download_read_timeout_secs,
+                10..=600,
+                " s",
+            ),
+            (
+                "Retries",
+                "Extra attempts after a network failure (waits 1s, 2s, 4s, ...)",
+                &mut self.download_retries,
+                0..=10,
+                "",
+            ),
+        ];
+        for (label, tooltip, value, range, suffix) in rows {
+            ui.horizontal(|ui| {
+                ui.add_space(24.0);
+                ui.label(RichText::new(label).color(theme.fg).size(12.0))
+                    .on_hover_text(tooltip);
+                changed |= ui
+                    .add(egui::Slider::new(value, range).suffix(suffix))
+                    .changed();
+            });
+        }
+        if changed {
+            self.save_settings();
+        }
+    }
+
+    fn save_launch_env(&self) {
+        if let Ok(json) = serde_json::to_string(&self.launch_env) {
+            let _ = self.db.set_config("ui.launch_env", &json);
+        }
+    }
+
+    fn settings_toggle_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        theme: Theme,
+        label: &str,
+        tooltip: &str,
+        toggle_key: &str,
+    ) {
+        ui.horizontal(|ui| {
+            ui.add_space(16.0);
+            ui.label(RichText::new(label).color(theme.fg).size(13.0));
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.add_space(16.0);
+
+                let mut value = match toggle_key {
+                    "auto_sync" => self.auto_sync_enabled,
+                    "import_on_start" => self.import_on_start,
+                    "auto_import" => self.auto_import_enabled,
+                    "show_all_versions" => self.show_all_versions,
+                    "create_desktop_entries" => self.create_desktop_entries,
+                    "stats_exclude_archived" => self.stats_exclude_archived,
+                    "relative_timestamps" => self.relative_timestamps,
+                    "code_wrap" => self.code_wrap,
+                    "hide_empty_messages" => self.hide_empty_messages,
+                    "developer_mode" => self.developer_mode,
+                    "follow_cursor_theme" => self.follow_cursor_theme,
+                    _ => false,
+                };
+
+                if toggle_switch(ui, &mut value, theme).clicked() {
+                    match toggle_key {
+                        "auto_sync" => self.auto_sync_enabled = value,
+                        "import_on_start" => self.import_on_start = value,
+                        "auto_import" => {
+                            self.auto_import_enabled = value;
+                            // Count the interval from now rather than from app start
+                            self.auto_import_timer = std::time::Instant::now();
+                            self.save_settings();
+                        }
+                        "show_all_versions" => self.show_all_versions = value,
+                        "create_desktop_entries" => {
+                            self.create_desktop_entries = value;
+                            self.save_settings();
+                        }
+                        "stats_exclude_archived" => {
+                            self.stats_exclude_archived = value;
+                            self.save_settings();
+                        }
+                        "relative_timestamps" => {
+                            self.relative_timestamps = value;
+                            self.save_settings();
+                        }
+                        "code_wrap" => {
+                            self.code_wrap = value;
+                            self.save_settings();
+                        }
+                        "hide_empty_messages" => {
+                            self.hide_empty_messages = value;
+                            self.empty_message_overrides.clear();
+                            self.save_settings();
+                        }
+                        "developer_mode" => {
+                            self.developer_mode = value;
+                            self.save_settings();
+                        }
+                        "follow_cursor_theme" => {
+                            self.follow_cursor_theme = value;
+                            // Read settings.json again on the next frame
+                            self.cursor_theme_checked = None;
+                            self.cursor_settings_mtime = None;
+                            self.followed_cursor_theme = None;
+                            if !value {
+                                // Back to the manual pick (or the default without one)
+                                self.theme = Theme::dark();
+                                self.current_theme_name = "Dark+ (default dark)".to_string();
+                                self.restore_saved_theme();
+                            }
+                            self.save_settings();
+                        }
+                        _ => {}
+                    }
+                    self.set_status(&format!(
+                        "✓ {} {}",
+                        label,
+                        if value { "enabled" } else { "disabled" }
+                    ));
+                }
+            });
+        })
+        .response
+        .on_hover_text(tooltip);
+    }
+
+    /// Contextual action bar shown above the Archive list while items are selected
+    fn show_bulk_action_bar(&mut self, ui: &mut egui::Ui, theme: Theme) {
+        let density = self.density;
+        let mut action: Option<BulkAction> = None;
+        let count = self.selected_conversations.len();
+
+        ui.horizontal(|ui| {
+            ui.add_space(8.0);
+            accent_card_frame(theme, density).show(ui, |ui| {
+                ui.vertical(|ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            RichText::new(format!("{} selected", count))
+                                .size(theme.text_size(11.0))
+                                .color(theme.fg)
+                                .strong(),
+                        );
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            let clear_btn = ui
+                                .add(
+                                    egui::Button::new(
+                                        RichText::new("✕")
+                                            .size(theme.text_size(11.0))
+                                            .color(theme.fg_dim),
+                                    )
+                                    .frame(false),
+                                )
+                                .on_hover_text("Clear selection");
+                            accessible_label(ui, &clear_btn, "Clear selection");
+                            if clear_btn.clicked() {
+                                action = Some(BulkAction::ClearSelection);
+                            }
+                            if ui
+                                .add(
+                                    egui::Button::new(
+                                        RichText::new("All")
+                                            .size(theme.text_size(11.0))
+                                            .color(theme.fg_dim),
+                                    )
+                                    .frame(false),
+                                )
+                                .on_hover_text("Select all visible chats")
+                                .clicked()
+                            {
+                                action = Some(BulkAction::SelectAll);
+                            }
+                        });
+                    });
+                    ui.add_space(TIGHT_SPACING);
+                    ui.horizontal(|ui| {
+                        if styled_button(ui, "★", Vec2::new(28.0, 24.0))
+                            .on_hover_text("Favorite selected")
+                            .clicked()
+                        {
+                            action = Some(BulkAction::Favorite);
+                        }
+                        if styled_button(ui, "📦", Vec2::new(28.0, 24.0))
+                            .on_hover_text("Archive selected")
+                            .clicked()
+                        {
+                            action = Some(BulkAction::Archive);
+                        }
+                        if styled_button(ui, "⬆", Vec2::new(28.0, 24.0))
+                            .on_hover_text("Export selected to markdown")
+                            .clicked()
+                        {
+                            action = Some(BulkAction::Export);
+                        }
+                        if styled_button(ui, "🔁", Vec2::new(28.0, 24.0))
+                            .on_hover_text("Search and replace in selected")
+                            .clicked()
+                        {
+                            action = Some(BulkAction::Replace);
+                        }
+                        if styled_button(ui, "🗑", Vec2::new(28.0, 24.0))
+                            .on_hover_text("Delete selected (asks for confirmation)")
+                            .clicked()
+                        {
+                            action = Some(BulkAction::Delete);
+                        }
+                    });
+                    ui.add_space(TIGHT_SPACING);
+                    ui.horizontal(|ui| {
+                        let response = ui.add(
+                            egui::TextEdit::singleline(&mut self.bulk_tag_input)
+                                .hint_text("tag")
+                                .desired_width(110.0),
+                        );
+                        let submitted =
+                            response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                        if (styled_button(ui, "🏷 Tag", Vec2::new(60.0, 24.0))
+                            .on_hover_text("Add tag to selected")
+                            .clicked()
+                            || submitted)
+                            && !self.bulk_tag_input.trim().is_empty()
+                        {
+                            action = Some(BulkAction::Tag(self.bulk_tag_input.trim().to_string()));
+                        }
+                    });
+                });
+            });
+        });
+
+        if let Some(action) = action {
+            self.apply_bulk_action(action);
+        }
+    }
+
+    /// Search-and-replace over the selected chats: preview, apply (with approval), undo
+    fn show_replace_tool(&mut self, ui: &mut egui::Ui, theme: Theme) {
+        let mut preview = false;
+        let mut apply = false;
+        let mut undo = false;
+
+        ui.horizontal(|ui| {
+            ui.add_space(8.0);
+            egui::Frame::none()
+                .fill(theme.code_bg)
+                .rounding(Rounding::same(8.0))
+                .inner_margin(egui::Margin::same(12.0))
+                .show(ui, |ui| {
+                    ui.vertical(|ui| {
+                        ui.label(
+                            RichText::new("🔁 Search and Replace")
+                                .size(12.0)
+                                .color(theme.fg)
+                                .strong(),
+                        );
+                        ui.add_space(4.0);
+
+                        let find = ui.add(
+                            egui::TextEdit::singleline(&mut self.replace_find)
+                                .hint_text("find")
+                                .desired_width(200.0),
+                        );
+                        let with = ui.add(
+                            egui::TextEdit::singleline(&mut self.replace_with)
+                                .hint_text("replace with")
+                                .desired_width(200.0),
+                        );
+                        let regex_toggle = ui
+                            .checkbox(&mut self.replace_regex, "Regex")
+                            .on_hover_text("Use $1, $2… in the replacement for capture groups");
+                        // Any edit invalidates the preview the approval is based on
+                        if find.changed() || with.changed() || regex_toggle.changed() {
+                            self.replace_preview = None;
+                            self.replace_error = None;
+                        }
+
+                        if let Some(err) = &self.replace_error {
+                            ui.add_space(4.0);
+                            ui.label(
+                                RichText::new(format!("✗ {}", err))
+                                    .size(theme.text_size(10.0))
+                                    .color(theme.error),
+                            );
+                        }
+
+                        if let Some(result) = &self.replace_preview {
+                            ui.add_space(4.0);
+                            ui.label(
+                                RichText::new(format!(
+                                    "{} matches in {} messages across {} chats",
+                                    result.matches, result.messages, result.conversations
+                                ))
+                                .size(theme.text_size(10.0))
+                                .color(theme.fg),
+                            );
+                            for sample in &result.samples {
+                                ui.label(
+                                    RichText::new(format!("− {}", sample.before.trim()))
+                                        .size(theme.text_size(10.0))
+                                        .monospace()
+                                        .color(theme.error),
+                                );
+                                ui.label(
+                                    RichText::new(format!("+ {}", sample.after.trim()))
+                                        .size(theme.text_size(10.0))
+                                        .monospace()
+                                        .color(theme.success),
+                                );
+                            }
+                        }
+
+                        ui.add_space(8.0);
+                        ui.horizontal(|ui| {
+                            let ready = !self.replace_find.is_empty();
+                            if ui
+                                .add_enabled(ready, egui::Button::new("Preview"))
+                                .clicked()
+                            {
+                                preview = true;
+                            }
+                            let can_apply = self
+                                .replace_preview
+                                .as_ref()
+                                .is_some_and(|p| p.messages > 0);
+                            if ui
+                                .add_enabled(can_apply, egui::Button::new("Apply"))
+                                .on_hover_text("Original text is backed up for Undo")
+                                .clicked()
+                            {
+                                apply = true;
+                            }
+                            if let Some((_, messages)) = &self.last_replace_batch {
+                                if ui
+                                    .button("↶ Undo")
+                                    .on_hover_text(format!(
+                                        "Restore the {} messages changed by the last replace",
+                                        messages
+                                    ))
+                                    .clicked()
+                                {
+                                    undo = true;
+                                }
+                            }
+                        });
+                    });
+                });
+        });
+
+        if preview {
+            self.preview_replace();
+        }
+        if apply {
+            self.apply_replace();
+        }
+        if undo {
+            self.undo_replace();
+        }
+    }
+
+    fn apply_bulk_action(&mut self, action: BulkAction) {
+        let ids: Vec<String> = self.selected_conversations.iter().cloned().collect();
+        let count = ids.len();
+
+        match action {
+            BulkAction::ClearSelection => {
+                self.selected_conversations.clear();
+            }
+            BulkAction::SelectAll => {
+                self.selected_conversations =
+                    self.all_conversations().into_iter().map(|c| c.id).collect();
+            }
+            BulkAction::Favorite => {
+                let failed = ids
+                    .iter()
+                    .filter(|id| self.db.set_favorite(id, true).is_err())
+                    .count();
+                self.refresh_chats();
+                self.report_bulk_result("Favorited", count, failed);
+            }
+            BulkAction::Archive => {
+                let failed = ids
+                    .iter()
+                    .filter(|id| self.db.set_archived(id, true).is_err())
+                    .count();
+                self.selected_conversations.clear();
+                self.refresh_chats();
+                self.report_bulk_result("Archived", count, failed);
+            }
+            BulkAction::Tag(tag) => {
+                let failed = ids
+                    .iter()
+                    .filter(|id| self.db.add_tag(id, &tag).is_err())
+                    .count();
+                self.bulk_tag_input.clear();
+                self.refresh_chats();
+                self.report_bulk_result(&format!("Tagged '{}' on", tag), count, failed);
+            }
+            BulkAction::Export => {
+                self.export_markdown_batch(&ids, self.export_combined);
+            }
+            BulkAction::Replace => {
+                self.show_replace_panel = !self.show_replace_panel;
+                self.replace_preview = None;
+                self.replace_error = None;
+            }
+            BulkAction::Delete => {
+                let operation = ApprovalOperation::DeleteConversations { count };
+                let title = operation.title();
+                match self.approval_manager.request(operation) {
+                    ApprovalResult::Approved => {
+                        let failed = ids
+                            .iter()
+                            .filter(|id| self.db.delete_conversation(id).is_err())
+                            .count();
+                        // Close tabs of deleted conversations
+                        self.tabs
+                            .retain(|t| !matches!(t, Tab::Conversation(id) if ids.contains(id)));
+                        self.active_tab = self.active_tab.min(self.tabs.len().saturating_sub(1));
+                        self.selected_conversations.clear();
+                        self.refresh_chats();
+                        self.report_bulk_result("Deleted", count, failed);
+                    }
+                    ApprovalResult::Denied => {
+                        let msg = self
+                            .approval_manager
+                            .get_pending_message(&title)
+                            .unwrap_or_else(|| format!("⚠️ Click again to confirm: {}", title));
+                        self.set_status(&msg);
+                    }
+                    _ => {
+                        self.set_status("Delete cancelled");
+                    }
+                }
+            }
+        }
+    }
+
+    /// The replace panel's search as a regex and the replacement to expand.
+    /// Plain-text searches are escaped so `$` and regex syntax are literal.
+    fn replace_pattern(&self) -> Result<(regex::Regex, String), regex::Error> {
+        if self.replace_regex {
+            Ok((
+                regex::Regex::new(&self.replace_find)?,
+                self.replace_with.clone(),
+            ))
+        } else {
+            Ok((
+                regex::Regex::new(&regex::escape(&self.replace_find))?,
+                self.replace_with.replace('$', "$$"),
+            ))
+        }
+    }
+
+    fn preview_replace(&mut self) {
+        let ids: Vec<String> = self.selected_conversations.iter().cloned().collect();
+        let result = self
+            .replace_pattern()
+            .map_err(anyhow::Error::from)
+            .and_then(|(pattern, replacement)| {
+                self.db.preview_replace(&ids, &pattern, &replacement)
+            });
+        match result {
+            Ok(preview) => {
+                self.replace_error = None;
+                self.replace_preview = Some(preview);
+            }
+            Err(e) => {
+                self.replace_error = Some(format!("{:#}", e));
+                self.replace_preview = None;
+            }
+        }
+    }
+
+    /// Apply the previewed replacement once approved; the original text is backed up
+    fn apply_replace(&mut self) {
+        let Some(preview) = &self.replace_preview else {
+            return;
+        };
+        let operation = ApprovalOperation::ReplaceInMessages {
+            messages: preview.messages,
+            conversations: preview.conversations,
+        };
+        let title = operation.title();
+        match self.approval_manager.request(operation) {
+            ApprovalResult::Approved => {}
+            ApprovalResult::Denied => {
+                let msg = self
+                    .approval_manager
+                    .get_pending_message(&title)
+                    .unwrap_or_else(|| format!("⚠️ Click again to confirm: {}", title));
+                self.set_status(&msg);
+                return;
+            }
+            _ => {
+                self.set_status("Replace cancelled");
+                return;
+            }
+        }
+
+        let ids: Vec<String> = self.selected_conversations.iter().cloned().collect();
+        let result = self
+            .replace_pattern()
+            .map_err(anyhow::Error::from)
+            .and_then(|(pattern, replacement)| self.db.apply_replace(&ids, &pattern, &replacement));
+        match result {
+            Ok((batch, changed)) => {
+                if let Some(batch) = batch {
+                    self.last_replace_batch = Some((batch, changed));
+                }
+                self.replace_preview = None;
+                self.reload_open_messages();
+                self.set_status(&format!("✓ Replaced text in {} messages", changed));
+            }
+            Err(e) => self.set_status(&format!("✗ Replace failed: {}", e)),
+        }
+    }
+
+    /// Restore the text changed by the last replace from its backup
+    fn undo_replace(&mut self) {
+        let Some((batch, _)) = self.last_replace_batch.clone() else {
+            return;
+        };
+        match self.db.undo_replace(&batch) {
+            Ok(restored) => {
+                self.last_replace_batch = self.db.last_replace_batch().ok().flatten();
+                self.replace_preview = None;
+                self.reload_open_messages();
+                self.set_status(&format!("✓ Restored {} messages", restored));
+            }
+            Err(e) => self.set_status(&format!("✗ Undo failed: {}", e)),
+        }
+    }
+
+    /// Reload the active conversation's messages after their text was edited
+    fn reload_open_messages(&mut self) {
+        if let Some(Tab::Conversation(conv_id)) = self.tabs.get(self.active_tab).cloned() {
+            self.current_messages = self.load_messages(&conv_id);
+        }
+    }
+
+    /// Export several conversations as markdown into the export dialog's output directory,
+    /// either one file each or combined into a single document
+    fn export_markdown_batch(&mut self, ids: &[String], combined: bool) {
+        let Some(template) = self.load_export_template() else {
+            return;
+        };
+        let export_dir = PathBuf::from(&self.export_output_dir);
+        let count = ids.len();
+
+        if !combined {
+            let mut failed = 0;
+            for id in ids {
+                let written = self.write_conversation_markdown(id, &export_dir, template.as_ref());
+                if let Err(e) = written {
+                    log::warn!("Export of {} failed: {}", id, e);
+                    failed += 1;
+                }
+            }
+            if failed == 0 {
+                self.set_status(&format!(
+                    "✓ Exported {} chats to {}",
+                    count,
+                    export_dir.display()
+                ));
+            } else {
+                self.report_bulk_result("Exported", count, failed);
+            }
+            if failed < count {
+                self.last_export = Some(LastExport::folder(export_dir));
+            }
+            return;
+        }
+
+        // Keep the library's ordering rather than selection order; the
+        // selection can include chats beyond the loaded page
+        let mut sections = Vec::new();
+        let library = self
+            .db
+            .get_conversations(usize::MAX, true, None)
+            .unwrap_or_default();
+        for conv in library.into_iter().filter(|c| ids.contains(&c.id)) {
+            match self.db.get_messages(&conv.id) {
+                Ok(messages) => sections.push((conv, messages)),
+                Err(e) => log::warn!("Failed to load messages for {}: {}", conv.id, e),
+            }
+        }
+
+        if sections.is_empty() {
+            self.set_status("✗ Nothing to export");
+            return;
+        }
+
+        let md = combined_markdown(&sections, template.as_ref());
+        let filename = format!(
+            "cursor-chats-{}.md",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        );
+        let path = export_dir.join(&filename);
+        let result = std::fs::create_dir_all(&export_dir).and_then(|_| std::fs::write(&path, md));
+        match result {
+            Ok(()) => {
+                self.set_status(&format!(
+                    "✓ Exported {} chats to {}",
+                    sections.len(),
+                    path.display()
+                ));
+                self.last_export = Some(LastExport::file(path));
+            }
+            Err(e) => self.set_status(&format!("✗ Export failed: {}", e)),
+        }
+    }
+
+    /// Markdown template choice in the export dialog, with a shortcut to the
+    /// folder user templates go in
+    fn show_export_template_picker(&mut self, ui: &mut egui::Ui, theme: Theme) {
+        let before = self.export_template.clone();
+        let mut open_folder = false;
+        ui.horizontal(|ui| {
+            ui.label(
+                RichText::new("Template:")
+                    .size(theme.text_size(11.0))
+                    .color(theme.fg_dim),
+            );
+            egui::ComboBox::from_id_salt("export_template")
+                .selected_text(export_templates::label(&self.export_template))
+                .show_ui(ui, |ui| {
+                    for name in export_templates::available() {
+                        let label = export_templates::label(&name);
+                        ui.selectable_value(&mut self.export_template, name, label);
+                    }
+                });
+            let folder_btn = styled_button(ui, "📁", Vec2::new(24.0, 20.0)).on_hover_text(
+                "Open the templates folder: add <name>.md files using {{title}}, \
+                 {{#each messages}}…{{/each}}, {{role}}, {{content}} and {{#if …}}…{{/if}}",
+            );
+            accessible_label(ui, &folder_btn, "Open templates folder");
+            open_folder = folder_btn.clicked();
+        });
+
+        if self.export_template != before {
+            let _ = self
+                .db
+                .set_config("ui.export_template", &self.export_template);
+        }
+        if open_folder {
+            let Some(dir) = export_templates::templates_dir() else {
+                self.set_status("✗ No config directory");
+                return;
+            };
+            let result = std::fs::create_dir_all(&dir).and_then(|_| open_with_default_app(&dir));
+            if let Err(e) = result {
+                self.set_status(&format!("✗ Failed to open {}: {}", dir.display(), e));
+            }
+        }
+    }
+
+    /// Write the conversations to a portable `.cursor-studio.db` in the export folder
+    fn export_sqlite(&mut self, ids: &[String]) {
+        let export_dir = PathBuf::from(&self.export_output_dir);
+        let filename = format!(
+            "cursor-chats-{}.{}",
+            chrono::Local::now().format("%Y%m%d-%H%M%S"),
+            ExportFormat::Sqlite.file_extension()
+        );
+        let path = export_dir.join(filename);
+        let result = std::fs::create_dir_all(&export_dir)
+            .map_err(anyhow::Error::from)
+            .and_then(|_| self.db.export_to_sqlite(ids, &path));
+        match result {
+            Ok(count) => {
+                self.set_status(&format!("✓ Exported {} chats to {}", count, path.display()));
+                self.last_export = Some(LastExport::file(path));
+            }
+            Err(e) => self.set_status(&format!("✗ Export failed: {}", e)),
+        }
+    }
+
+    /// Write every bookmark to a JSON file in the export folder
+    fn export_bookmarks_file(&mut self) {
+        let export_dir = PathBuf::from(&self.export_output_dir);
+        let path = export_dir.join(format!(
+            "cursor-bookmarks-{}.json",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        let result = self.db.export_bookmarks().and_then(|json| {
+            std::fs::create_dir_all(&export_dir)?;
+            std::fs::write(&path, json)?;
+            Ok(())
+        });
+        match result {
+            Ok(()) => {
+                self.set_status(&format!("✓ Exported bookmarks to {}", path.display()));
+                self.last_export = Some(LastExport::file(path));
+            }
+            Err(e) => self.set_status(&format!("✗ Bookmark export failed: {}", e)),
+        }
+    }
+
+    /// Add the bookmarks from a file written by `export_bookmarks_file`
+    fn import_bookmarks_file(&mut self, path: &str) {
+        let path = expand_home(path.trim());
+        let result = std::fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| self.db.import_bookmarks(&json));
+        match result {
+            Ok(report) => {
+                self.bookmark_import_path = None;
+                if let Some(Tab::Conversation(id)) = self.tabs.get(self.active_tab) {
+                    self.current_bookmarks = self.db.get_bookmarks(id).unwrap_or_default();
+                }
+                if report.orphaned > 0 {
+                    self.set_status(&format!(
+                        "✓ Attached {} bookmarks; {} are for chats not in the library yet",
+                        report.attached, report.orphaned
+                    ));
+                } else {
+                    self.set_status(&format!("✓ Attached {} bookmarks", report.attached));
+                }
+            }
+            Err(e) => self.set_status(&format!("✗ Bookmark import failed: {:#}", e)),
+        }
+    }
+
+    /// Show or hide the message lines around a scan finding
+    fn toggle_finding_context(&mut self, finding: security::ChatFinding) {
+        if self.finding_contexts.remove(&finding).is_some() {
+            return;
+        }
+        let context = self
+            .db
+            .get_messages(&finding.conversation_id)
+            .ok()
+            .and_then(|messages| messages.into_iter().find(|m| m.id == finding.message_id))
+            .and_then(|msg| {
+                security::finding_context(&msg.content, &finding.matched, FINDING_CONTEXT_LINES)
+            });
+        self.finding_contexts.insert(finding, context);
+    }
+
+    /// Write the last security scan as SARIF into the export directory
+    fn export_security_sarif(&mut self) {
+        let Some(results) = &self.security_scan_results else {
+            self.set_status("⚠ Run a scan before exporting");
+            return;
+        };
+        let npm = self
+            .npm_scan_results
+            .as_deref()
+            .filter(|_| self.sarif_include_npm);
+        let sarif = results.to_sarif(npm);
+        let export_dir = PathBuf::from(&self.export_output_dir);
+        let path = export_dir.join(format!(
+            "cursor-studio-scan-{}.sarif",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        let result = serde_json::to_string_pretty(&sarif)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| {
+                std::fs::create_dir_all(&export_dir)?;
+                std::fs::write(&path, json)?;
+                Ok(())
+            });
+        match result {
+            Ok(()) => {
+                self.set_status(&format!("✓ Exported SARIF to {}", path.display()));
+                self.last_export = Some(LastExport::file(path));
+            }
+            Err(e) => self.set_status(&format!("✗ SARIF export failed: {}", e)),
+        }
+    }
+
+    fn report_bulk_result(&mut self, verb: &str, count: usize, failed: usize) {
+        if failed == 0 {
+            self.set_status(&format!("✓ {} {} chats", verb, count));
+        } else {
+            self.set_status(&format!(
+                "⚠️ {} {} of {} chats ({} failed)",
+                verb,
+                count - failed,
+                count,
+                failed
+            ));
+        }
+    }
+
+    fn show_archive_panel(&mut self, ui: &mut egui::Ui, theme: Theme) {
+        ui.vertical(|ui| {
+            ui.add_space(12.0);
+            ui.horizontal(|ui| {
+                ui.add_space(16.0);
+                ui.label(
+                    RichText::new("CHAT LIBRARY")
+                        .size(theme.text_size(11.0))
+                        .color(theme.fg_dim)
+                        .strong(),
+                );
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.add_space(12.0);
+                    let refresh_btn = ui
+                        .add(
+                            egui::Button::new(RichText::new("↻").size(14.0).color(theme.fg_dim))
+                                .frame(false),
+                        )
+                        .on_hover_text("Refresh chat library");
+                    accessible_label(ui, &refresh_btn, "Refresh chat library");
+                    if refresh_btn.hovered() {
+                        ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+                    }
+                    if refresh_btn.clicked() {
+                        self.refresh_chats();
+                    }
+
+                    let archived_color = if self.show_archived {
+                        theme.accent
+                    } else {
+                        theme.fg_dim
+                    };
+                    let archived_btn = ui
+                        .add(
+                            egui::Button::new(RichText::new("📦").size(13.0).color(archived_color))
+                                .frame(false),
+                        )
+                        .on_hover_text(if self.show_archived {
+                            "Hide archived chats"
+                        } else {
+                            "Show archived chats"
+                        });
+                    accessible_toggle(ui, &archived_btn, "Show archived chats", self.show_archived);
+                    if archived_btn.hovered() {
+                        ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+                    }
+                    if archived_btn.clicked() {
+                        self.show_archived = !self.show_archived;
+                        self.save_settings();
+                        self.refresh_chats();
+                    }
+                });
+            });
+            ui.add_space(8.0);
+
+            if !self.selected_conversations.is_empty() {
+                self.show_bulk_action_bar(ui, theme);
+                ui.add_space(8.0);
+                if self.show_replace_panel {
+                    self.show_replace_tool(ui, theme);
+                    ui.add_space(8.0);
+                }
+            }
+
+            self.poll_conversation_previews(ui.ctx());
+            // Marks on conversations a refresh brought in fade after a while
+            self.new_conversations
+                .retain(|_, arrived| arrived.elapsed() < NEW_CONVERSATION_MARK);
+            if !self.new_conversations.is_empty() {
+                ui.ctx()
+                    .request_repaint_after(std::time::Duration::from_secs(1));
+            }
+            let mut tag_filter: Option<Option<String>> = None;
+            if let Some(tag) = &self.archive_tag_filter {
+                ui.horizontal(|ui| {
+                    ui.add_space(16.0);
+                    ui.label(
+                        RichText::new(format!("🏷 {}", tag))
+                            .color(theme.accent)
+                            .size(theme.text_size(11.0)),
+                    );
+                    if ui
+                        .small_button("✕")
+                        .on_hover_text("Show all conversations")
+                        .clicked()
+                    {
+                        tag_filter = Some(None);
+                    }
+                });
+                ui.add_space(4.0);
+            }
+
+            let convs = self.conversations.clone();
+            let relative_timestamps = self.relative_timestamps;
+            let new_conversations = &self.new_conversations;
+            let active_tag = self.archive_tag_filter.as_deref();
+            let mut load_more = false;
+            let mut hovered_preview: Option<String> = None;
+            let mut to_open: Option<String> = None;
+            let mut to_toggle_fav: Option<String> = None;
+            let mut to_archive: Option<(String, bool)> = None;
+            let mut to_rename: Option<String> = None;
+            let mut finish_rename: Option<bool> = None;
+
+            let scroll_height = ui.available_height() - 100.0;
+
+            egui::ScrollArea::vertical()
+                .max_height(scroll_height.max(100.0))
+                .show(ui, |ui| {
+                    if convs.is_empty() {
+                        ui.vertical_centered(|ui| {
+                            ui.add_space(20.0);
+                            ui.label(
+                                RichText::new("No chats imported yet")
+                                    .color(theme.fg_dim)
+                                    .italics(),
+                            );
+                            ui.add_space(8.0);
+                            ui.label(
+                                RichText::new("Click 'Import All' below")
+                                    .color(theme.fg_dim)
+                                    .size(theme.text_size(11.0)),
+                            );
+
+                            // Show last error if any
+                            if let Some(err) = &self.last_import_error {
+                                ui.add_space(12.0);
+                                ui.label(
+                                    RichText::new(err)
+                                        .color(theme.error)
+                                        .size(theme.text_size(10.0)),
+                                );
+                            }
+                        });
+                    }
+
+                    for conv in &convs {
+                        ui.horizontal(|ui| {
+                            ui.add_space(8.0);
+
+                            let mut checked = self.selected_conversations.contains(&conv.id);
+                            if ui
+                                .checkbox(&mut checked, "")
+                                .on_hover_text("Select for bulk actions")
+                                .changed()
+                            {
+                                if checked {
+                                    self.selected_conversations.insert(conv.id.clone());
+                                } else {
+                                    self.selected_conversations.remove(&conv.id);
+                                }
+                            }
+
+                            let star = if conv.is_favorite { "★" } else { "☆" };
+                            let star_color = if conv.is_favorite {
+                                theme.warning
+                            } else {
+                                theme.fg_dim
+                            };
+                            let star_btn = ui
+                                .add(
+                                    egui::Button::new(
+                                        RichText::new(star).color(star_color).size(14.0),
+                                    )
+                                    .frame(false)
+                                    .min_size(Vec2::new(20.0, 20.0)),
+                                )
+                                .on_hover_text("Toggle favorite");
+                            accessible_toggle(ui, &star_btn, "Favorite", conv.is_favorite);
+
+                            if star_btn.hovered() {
+                                ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+                            }
+
+                            if star_btn.clicked() {
+                                to_toggle_fav = Some(conv.id.clone());
+                            }
+
+                            let draft = self
+                                .title_editor
+                                .as_mut()
+                                .filter(|(id, _)| id == &conv.id && !self.title_editor_in_tab)
+                                .map(|(_, draft)| draft);
+                            if let Some(draft) = draft {
+                                let (rect, _) = ui.allocate_exact_size(
+                                    Vec2::new(180.0, 18.0),
+                                    egui::Sense::hover(),
+                                );
+                                finish_rename = title_editor_ui(
+                                    ui,
+                                    rect,
+                                    draft,
+                                    &mut self.title_editor_focus_pending,
+                                );
+                            } else {
+                                let title: String = conv.title.chars().take(25).collect();
+                                let title_color = if conv.is_archived {
+                                    theme.fg_dim
+                                } else {
+                                    theme.fg
+                                };
+                                let previews = &self.conversation_previews;
+                                let title_btn = ui
+                                    .add(
+                                        egui::Button::new(
+                                            RichText::new(&title).color(title_color).size(12.0),
+                                        )
+                                        .frame(false),
+                                    )
+                                    .on_hover_ui(|ui| {
+                                        conversation_preview_tooltip(
+                                            ui,
+                                            &conv.title,
+                                            previews.get(&conv.id),
+                                            theme,
+                                        );
+                                    });
+
+                                if title_btn.hovered() {
+                                    ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+                                    hovered_preview = Some(conv.id.clone());
+                                }
+
+                                if title_btn.clicked() {
+                                    to_open = Some(conv.id.clone());
+                                }
+                                if title_btn.double_clicked() {
+                                    to_rename = Some(conv.id.clone());
+                                }
+                            }
+
+                            if new_conversations.contains_key(&conv.id) {
+                                ui.label(
+                                    RichText::new("●")
+                                        .color(theme.accent)
+                                        .size(theme.text_size(8.0)),
+                                )
+                                .on_hover_text("New since the last refresh");
+                            }
+
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    ui.add_space(12.0);
+                                    ui.label(
+                                        RichText::new(format!("{}", conv.message_count))
+                                            .color(theme.fg_dim)
+                                            .size(theme.text_size(10.0)),
+                                    );
+
+                                    if conv.word_count > 0 {
+                                        let words = conv.word_count;
+                                        let minutes = database::reading_minutes(words);
+                                        ui.label(
+                                            RichText::new(format!(
+                                                "{}w · {}m",
+                                                format_word_count(words),
+                                                minutes
+                                            ))
+                                                .color(theme.fg_dim)
+                                                .size(theme.text_size(9.0)),
+                                        )
+                                        .on_hover_text(format!(
+                                            "{} words, about {} min to read",
+                                            format_count(words),
+                                            minutes
+                                        ));
+                                    }
+
+                                    let (archive_icon, archive_tip) = if conv.is_archived {
+                                        ("📤", "Unarchive")
+                                    } else {
+                                        ("📦", "Archive (hide from list)")
+                                    };
+                                    let archive_btn = ui
+                                        .add(
+                                            egui::Button::new(
+                                                RichText::new(archive_icon)
+                                                    .color(theme.fg_dim)
+                                                    .size(theme.text_size(11.0)),
+                                            )
+                                            .frame(false),
+                                        )
+                                        .on_hover_text(archive_tip);
+                                    if archive_btn.hovered() {
+                                        ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+                                    }
+                                    if archive_btn.clicked() {
+                                        to_archive = Some((conv.id.clone(), !conv.is_archived));
+                                    }
+
+                                    if let Some((shown, absolute)) = conv
+                                        .updated_at
+                                        .as_deref()
+                                        .and_then(|ts| format_timestamp(ts, relative_timestamps))
+                                    {
+                                        ui.label(
+                                            RichText::new(shown)
+                                                .color(theme.fg_dim)
+                                                .size(theme.text_size(9.0)),
+                                        )
+                                        .on_hover_text(format!("Last updated {}", absolute));
+                                    }
+                                },
+                            );
+                        });
+                        if !conv.user_tags.is_empty() {
+                            ui.horizontal_wrapped(|ui| {
+                                // Line up under the title, past the checkbox and star
+                                ui.add_space(58.0);
+                                ui.spacing_mut().item_spacing.x = 4.0;
+                                for tag in &conv.user_tags {
+                                    let active = active_tag == Some(tag.as_str());
+                                    let chip = ui
+                                        .add(
+                                            egui::Button::new(
+                                                RichText::new(tag)
+                                                    .color(if active {
+                                                        theme.accent
+                                                    } else {
+                                                        theme.fg_dim
+                                                    })
+                                                    .size(theme.text_size(9.0)),
+                                            )
+                                            .fill(theme.input_bg)
+                                            .rounding(Rounding::same(8.0))
+                                            .small(),
+                                        )
+                                        .on_hover_text(if active {
+                                            "Show all conversations"
+                                        } else {
+                                            "Only show conversations with this tag"
+                                        });
+                                    if chip.hovered() {
+                                        ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+                                    }
+                                    if chip.clicked() {
+                                        tag_filter = Some((!active).then(|| tag.clone()));
+                                    }
+                                }
+                            });
+                        }
+                        ui.add_space(2.0);
+                    }
+
+                    if convs.len() < self.conversations_total {
+                        ui.add_space(4.0);
+                        ui.vertical_centered(|ui| {
+                            let label = format!(
+                                "Load more ({} of {})",
+                                convs.len(),
+                                self.conversations_total
+                            );
+                            if styled_button(ui, &label, Vec2::new(160.0, 24.0)).clicked() {
+                                load_more = true;
+                            }
+                        });
+                    }
+                });
+
+            if let Some(id) = hovered_preview {
+                self.request_conversation_preview(&id);
+            }
+
+            if let Some(tag) = tag_filter {
+                self.archive_tag_filter = tag;
+                self.conversations_loaded = self.conversation_page_size;
+                self.reload_conversations();
+            }
+
+            if load_more {
+                self.conversations_loaded = self.conversations.len() + self.conversation_page_size;
+                self.reload_conversations();
+            }
+
+            if let Some(id) = to_open {
+                self.open_conversation(&id);
+            }
+
+            if let Some(save) = finish_rename {
+                self.finish_rename(save);
+            }
+
+            if let Some(id) = to_rename {
+                self.begin_rename(&id, false);
+            }
+
+            if let Some(id) = to_toggle_fav {
+                let _ = self.db.toggle_favorite(&id);
+                self.refresh_chats();
+            }
+
+            if let Some((id, archived)) = to_archive {
+                match self.db.set_archived(&id, archived) {
+                    Ok(()) => {
+                        self.refresh_chats();
+                        self.set_status(if archived {
+                            "✓ Conversation archived"
+                        } else {
+                            "✓ Conversation restored"
+                        });
+                    }
+                    Err(e) => self.set_status(&format!("✗ Archive failed: {}", e)),
+                }
+            }
+
+            ui.add_space(ui.available_height() - 70.0);
+            ui.separator();
+            ui.add_space(8.0);
+
+            ui.horizontal(|ui| {
+                ui.add_space(12.0);
+
+                if self.import_in_progress {
+                    let pausing = self.import_pause.load(Ordering::Relaxed);
+                    if self.import_paused {
+                        ui.label(RichText::new("⏸ Paused").color(theme.warning).size(12.0));
+                    } else {
+                        // Animated spinner during import
+                        ui.horizontal(|ui| {
+                            ui.add(egui::Spinner::new());
+                            let label = if pausing { "Pausing..." } else { "Importing..." };
+                            ui.label(RichText::new(label).color(theme.accent).size(12.0));
+                        });
+                        // Keep repainting while importing; a paused worker sends nothing
+                        ui.ctx().request_repaint();
+                    }
+                    // Only the Cursor import works through several databases
+                    if matches!(self.import_progress, Some((_, total)) if total > 1) {
+                        let (label, hint) = if pausing {
+                            ("▶", "Resume import")
+                        } else {
+                            ("⏸", "Pause after the current database; progress is kept")
+                        };
+                        let pause_btn = styled_button(ui, label, Vec2::new(28.0, 28.0))
+                            .on_hover_text(hint);
+                        accessible_label(ui, &pause_btn, hint);
+                        if pause_btn.clicked() {
+                            self.toggle_import_pause();
+                        }
+                    }
+                } else {
+                    if styled_button_accent(ui, "⬇ Import All", Vec2::new(110.0, 28.0), theme)
+                        .clicked()
+                    {
+                        self.do_import();
+                    }
+                    if self.import_preview_receiver.is_some() {
+                        ui.add_sized(Vec2::new(28.0, 28.0), egui::Spinner::new().size(14.0))
+                            .on_hover_text("Scanning Cursor databases...");
+                    } else if styled_button(ui, "🔍", Vec2::new(28.0, 28.0))
+                        .on_hover_text("Preview import (dry run, nothing is written)")
+                        .clicked()
+                    {
+                        self.preview_import();
+                    }
+                    if styled_button(ui, "📥", Vec2::new(28.0, 28.0))
+                        .on_hover_text("Import external: ChatGPT/Claude export or SQLite file")
+                        .clicked()
+                    {
+                        self.show_external_import = !self.show_external_import;
+                    }
+                    if styled_button(ui, "📋", Vec2::new(28.0, 28.0))
+                        .on_hover_text("Paste a conversation copied as JSON")
+                        .clicked()
+                    {
+                        self.show_paste_conversation = !self.show_paste_conversation;
+                    }
+                }
+
+                if styled_button(ui, "⬆ Export", Vec2::new(80.0, 28.0))
+                    .on_hover_text("Export chats to markdown or training data")
+                    .clicked()
+                {
+                    self.show_export_dialog = !self.show_export_dialog;
+                }
+            });
+            ui.add_space(8.0);
+
+            // Import preview dialog
+            if let Some(previews) = self.import_preview.clone() {
+                let mut proceed = false;
+                let mut close = false;
+                ui.add_space(8.0);
+                egui::Frame::none()
+                    .fill(theme.code_bg)
+                    .rounding(Rounding::same(8.0))
+                    .inner_margin(egui::Margin::same(12.0))
+                    .show(ui, |ui| {
+                        ui.label(
+                            RichText::new("🔍 Import Preview")
+                                .size(12.0)
+                                .color(theme.fg)
+                                .strong(),
+                        );
+                        ui.add_space(8.0);
+
+                        if previews.is_empty() {
+                            ui.label(
+                                RichText::new("No Cursor databases found")
+                                    .size(theme.text_size(11.0))
+                                    .color(theme.fg_dim)
+                                    .italics(),
+                            );
+                        }
+
+                        for preview in &previews {
+                            ui.label(
+                                RichText::new(format!("📁 {}", preview.version))
+                                    .size(theme.text_size(11.0))
+                                    .color(theme.fg),
+                            )
+                            .on_hover_text(preview.path.display().to_string());
+
+                            if let Some(err) = &preview.error {
+                                ui.label(
+                                    RichText::new(format!("   ✗ {}", err))
+                                        .size(theme.text_size(10.0))
+                                        .color(theme.error),
+                                );
+                            } else {
+                                if preview.locked {
+                                    ui.label(
+                                        RichText::new("   ⚠ Cursor is running (last save only)")
+                                            .size(theme.text_size(10.0))
+                                            .color(theme.warning),
+                                    );
+                                }
+                                ui.label(
+                                    RichText::new(format!(
+                                        "   {} chats / {} messages",
+                                        preview.conversations, preview.messages
+                                    ))
+                                    .size(theme.text_size(10.0))
+                                    .color(theme.fg_dim),
+                                );
+                                let new_color = if preview.new_conversations > 0 {
+                                    theme.success
+                                } else {
+                                    theme.fg_dim
+                                };
+                                ui.label(
+                                    RichText::new(format!(
+                                        "   {} new ({} messages), {} already imported",
+                                        preview.new_conversations,
+                                        preview.new_messages,
+                                        preview.existing_conversations
+                                    ))
+                                    .size(theme.text_size(10.0))
+                                    .color(new_color),
+                                );
+                            }
+                            ui.add_space(4.0);
+                        }
+
+                        ui.add_space(4.0);
+                        ui.horizontal(|ui| {
+                            let has_new = previews.iter().any(|p| p.new_conversations > 0);
+                            if has_new {
+                                if styled_button_accent(ui, "Proceed", Vec2::new(80.0, 26.0), theme)
+                                    .on_hover_text("Run the real import")
+                                    .clicked()
+                                {
+                                    proceed = true;
+                                }
+                            } else {
+                                ui.label(
+                                    RichText::new("Nothing new to import")
+                                        .size(theme.text_size(10.0))
+                                        .color(theme.fg_dim),
+                                );
+                            }
+                            if styled_button(ui, "Close", Vec2::new(70.0, 26.0)).clicked() {
+                                close = true;
+                            }
+                        });
+                    });
+
+                if proceed {
+                    self.import_preview = None;
+                    self.do_import();
+                } else if close {
+                    self.import_preview = None;
+                }
+            }
+
+            // External chat export import
+            if self.show_external_import {
+                let mut start = false;
+                ui.add_space(8.0);
+                egui::Frame::none()
+                    .fill(theme.code_bg)
+                    .rounding(Rounding::same(8.0))
+                    .inner_margin(egui::Margin::same(12.0))
+                    .show(ui, |ui| {
+                        ui.label(
+                            RichText::new("📥 Import External")
+                                .size(12.0)
+                                .color(theme.fg)
+                                .strong(),
+                        );
+                        ui.add_space(4.0);
+                        ui.label(
+                            RichText::new(
+                                "conversations.json from a ChatGPT or Claude data export, \
+                                 the unzipped export folder, or a .cursor-studio.db \
+                                 from Export to SQLite",
+                            )
+                            .size(theme.text_size(10.0))
+                            .color(theme.fg_dim),
+                        );
+                        ui.add_space(8.0);
+
+                        ui.horizontal(|ui| {
+                            ui.label(
+                                RichText::new("File:")
+                                    .size(theme.text_size(11.0))
+                                    .color(theme.fg_dim),
+                            );
+                            let resp = ui.add(
+                                egui::TextEdit::singleline(&mut self.external_import_path)
+                                    .hint_text("~/Downloads/conversations.json")
+                                    .desired_width(180.0),
+                            );
+                            if resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                                start = true;
+                            }
+                        });
+
+                        ui.add_space(8.0);
+                        ui.horizontal(|ui| {
+                            let ready = !self.external_import_path.trim().is_empty();
+                            if ui
+                                .add_enabled_ui(ready, |ui| {
+                                    styled_button_accent(ui, "Import", Vec2::new(80.0, 26.0), theme)
+                                })
+                                .inner
+                                .clicked()
+                            {
+                                start = true;
+                            }
+                            if styled_button(ui, "Cancel", Vec2::new(70.0, 26.0)).clicked() {
+                                self.show_external_import = false;
+                            }
+                        });
+                    });
+                if start {
+                    self.do_import_external();
+                }
+            }
+
+            // Conversation pasted as JSON
+            if self.show_paste_conversation {
+                let mut start = false;
+                ui.add_space(8.0);
+                egui::Frame::none()
+                    .fill(theme.code_bg)
+                    .rounding(Rounding::same(8.0))
+                    .inner_margin(egui::Margin::same(12.0))
+                    .show(ui, |ui| {
+                        ui.label(
+                            RichText::new("📋 Paste Conversation")
+                                .size(12.0)
+                                .color(theme.fg)
+                                .strong(),
+                        );
+                        ui.add_space(4.0);
+                        ui.label(
+                            RichText::new("JSON from \"Copy conversation as JSON\" (Ctrl+V)")
+                                .size(theme.text_size(10.0))
+                                .color(theme.fg_dim),
+                        );
+                        ui.add_space(8.0);
+
+                        egui::ScrollArea::vertical()
+                            .max_height(120.0)
+                            .show(ui, |ui| {
+                                let resp = ui.add(
+                                    egui::TextEdit::multiline(&mut self.paste_conversation_input)
+                                        .code_editor()
+                                        .hint_text("{\"conversation\": ..., \"messages\": [...]}")
+                                        .desired_rows(4)
+                                        .desired_width(f32::INFINITY),
+                                );
+                                if resp.changed() {
+                                    self.paste_conversation_error = None;
+                                }
+                            });
+
+                        if let Some(err) = &self.paste_conversation_error {
+                            ui.add_space(4.0);
+                            ui.label(
+                                RichText::new(format!("✗ {}", err))
+                                    .size(theme.text_size(10.0))
+                                    .color(theme.error),
+                            );
+                        }
+
+                        ui.add_space(8.0);
+                        ui.horizontal(|ui| {
+                            let ready = !self.paste_conversation_input.trim().is_empty();
+                            if ui
+                                .add_enabled_ui(ready, |ui| {
+                                    styled_button_accent(ui, "Import", Vec2::new(80.0, 26.0), theme)
+                                })
+                                .inner
+                                .clicked()
+                            {
+                                start = true;
+                            }
+                            if styled_button(ui, "Cancel", Vec2::new(70.0, 26.0)).clicked() {
+                                self.show_paste_conversation = false;
+                                self.paste_conversation_error = None;
+                            }
+                        });
+                    });
+                if start {
+                    self.import_pasted_conversation();
+                }
+            }
+
+            // Export dialog
+            if self.show_export_dialog {
+                ui.add_space(8.0);
+                egui::Frame::none()
+                    .fill(theme.code_bg)
+                    .rounding(Rounding::same(8.0))
+                    .inner_margin(egui::Margin::same(12.0))
+                    .show(ui, |ui| {
+                        ui.label(
+                            RichText::new("📤 Export Settings")
+                                .size(12.0)
+                                .color(theme.fg)
+                                .strong(),
+                        );
+                        ui.add_space(8.0);
+
+                        // Format selection
+                        ui.horizontal(|ui| {
+                            ui.label(
+                                RichText::new("Format:")
+                                    .size(theme.text_size(11.0))
+                                    .color(theme.fg_dim),
+                            );
+                            egui::ComboBox::from_id_salt("export_format")
+                                .selected_text(self.export_format.label())
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(&mut self.export_format, ExportFormat::Markdown, "Markdown");
+                                    ui.selectable_value(&mut self.export_format, ExportFormat::MarkdownObsidian, "Markdown (Obsidian)");
+                                    ui.selectable_value(&mut self.export_format, ExportFormat::Json, "JSON");
+                                    ui.selectable_value(&mut self.export_format, ExportFormat::JsonLines, "JSON Lines");
+                                    ui.separator();
+                                    ui.label(
+                                        RichText::new("Training Data")
+                                            .size(theme.text_size(10.0))
+                                            .color(theme.fg_dim),
+                                    );
+                                    ui.selectable_value(&mut self.export_format, ExportFormat::OpenAIJsonl, "OpenAI JSONL");
+                                    ui.selectable_value(&mut self.export_format, ExportFormat::AlpacaJson, "Alpaca JSON");
+                                    ui.separator();
+                                    ui.selectable_value(&mut self.export_format, ExportFormat::Sqlite, "SQLite (portable)");
+                                });
+                        });
+
+                        ui.add_space(4.0);
+
+                        // Output directory
+                        ui.horizontal(|ui| {
+                            ui.label(
+                                RichText::new("Output:")
+                                    .size(theme.text_size(11.0))
+                                    .color(theme.fg_dim),
+                            );
+                            ui.add(egui::TextEdit::singleline(&mut self.export_output_dir)
+                                .desired_width(180.0));
+                        });
+
+                        if self.export_format.is_markdown() {
+                            ui.add_space(4.0);
+                            self.show_export_template_picker(ui, theme);
+                            ui.add_space(4.0);
+                            ui.checkbox(
+                                &mut self.export_combined,
+                                RichText::new("Combine into one file (with table of contents)")
+                                    .size(theme.text_size(11.0))
+                                    .color(theme.fg),
+                            );
+                        }
+
+                        ui.add_space(8.0);
+
+                        let selected_count = self.selected_conversations.len();
+                        if self.export_format.is_in_app() && selected_count > 0 {
+                            if styled_button_accent(
+                                ui,
+                                &format!("Export Selected ({})", selected_count),
+                                Vec2::new(150.0, 26.0),
+                                theme,
+                            )
+                            .clicked()
+                            {
+                                let ids: Vec<String> =
+                                    self.selected_conversations.iter().cloned().collect();
+                                if self.export_format == ExportFormat::Sqlite {
+                                    self.export_sqlite(&ids);
+                                } else {
+                                    self.export_markdown_batch(&ids, self.export_combined);
+                                }
+                                self.show_export_dialog = false;
+                            }
+                            ui.add_space(4.0);
+                        }
+
+                        ui.horizontal(|ui| {
+                            if styled_button_accent(ui, "Export All", Vec2::new(90.0, 26.0), theme).clicked() {
+                                if self.export_format == ExportFormat::Sqlite {
+                                    let ids: Vec<String> =
+                                        self.all_conversations().into_iter().map(|c| c.id).collect();
+                                    self.export_sqlite(&ids);
+                                } else {
+                                    let cmd = format!(
+                                        "cd ~/nixos-cursor/services/cursor-docs && mix cursor_docs.chat --export-all --format {} --output-dir {}",
+                                        self.export_format.file_extension(),
+                                        self.export_output_dir
+                                    );
+                                    self.set_status(&format!("Run: {}", cmd));
+                                }
+                                self.show_export_dialog = false;
+                            }
+                            if styled_button(ui, "Cancel", Vec2::new(70.0, 26.0)).clicked() {
+                                self.show_export_dialog = false;
+                            }
+                        });
+
+                        ui.add_space(4.0);
+                        ui.label(
+                            RichText::new("Tip: Use cursor-docs CLI for batch exports")
+                                .size(theme.text_size(9.0))
+                                .color(theme.fg_dim)
+                                .italics(),
+                        );
+                    });
+            }
+        });
+    }
+
+    /// # TODO(P1): Release v0.3.0 - Security Panel Polish
+    /// - [ ] Wire up NPM scan results display (show blocked packages)
+    /// - [ ] Add "Jump to" buttons for security findings
+    /// - [ ] Show CVE details in expandable sections
+    /// - [ ] Add Socket.dev links for package research
+    /// - [ ] Implement audit log export functionality
+    /// - [ ] Add scan history with timestamps
+    fn show_sentinel_panel(&mut self, ui: &mut egui::Ui, theme: Theme) {
+        let density = self.density;
+        egui::ScrollArea::vertical()
+            .auto_shrink([false; 2])
+            .show(ui, |ui| {
+                ui.add_space(SECTION_SPACING);
+
+                // Overview Section
+                panel_header(ui, "SECURITY OVERVIEW", theme);
+                ui.add_space(ELEMENT_SPACING);
+
+                // Security Status Card
+                card_frame(theme, density).show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new("🛡️").size(18.0));
+                        ui.add_space(ELEMENT_SPACING);
+                        ui.vertical(|ui| {
+                            ui.label(
+                                RichText::new("System Status")
+                                    .color(theme.fg)
+                                    .strong()
+                                    .size(12.0),
+                            );
+                            ui.label(
+                                RichText::new("All security checks passing")
+                                    .color(theme.success)
+                                    .size(theme.text_size(10.0)),
+                            );
+                        });
+                    });
+                });
+                ui.add_space(SECTION_SPACING);
+
+                // Data Privacy Section
+                panel_header(ui, "DATA PRIVACY", theme);
+                ui.add_space(ELEMENT_SPACING);
+
+                card_frame(theme, density).show(ui, |ui| {
+                    // Chat Data Location
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new("📁").size(12.0));
+                        ui.add_space(TIGHT_SPACING);
+                        ui.label(
+                            RichText::new("Chat Data Location")
+                                .color(theme.fg)
+                                .size(theme.text_size(11.0)),
+                        );
+                    });
+                    ui.add_space(TIGHT_SPACING);
+                    ui.horizontal(|ui| {
+                        ui.add_space(16.0);
+                        if let Some(config_dir) = dirs::config_dir() {
+                            let path = config_dir.join("cursor-studio");
+                            ui.label(
+                                RichText::new(path.to_string_lossy())
+                                    .color(theme.fg_dim)
+                                    .size(theme.text_size(9.0))
+                                    .family(egui::FontFamily::Monospace),
+                            );
+                        }
+                    });
+                    
+                    ui.add_space(ELEMENT_SPACING);
+                    
+                    // Data Encryption
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new("🔐").size(12.0));
+                        ui.add_space(TIGHT_SPACING);
+                        ui.label(
+                            RichText::new("Data Encryption")
+                                .color(theme.fg)
+                                .size(theme.text_size(11.0)),
+                        );
+                    });
+                    ui.add_space(TIGHT_SPACING);
+                    ui.horizontal(|ui| {
+                        ui.add_space(16.0);
+                        ui.label(
+                            RichText::new("Local storage only (not encrypted)")
+                                .color(theme.warning)
+                                .size(theme.text_size(10.0)),
+                        );
+                    });
+                });
+                ui.add_space(SECTION_SPACING);
+
+                // API Keys Section
+                panel_header(ui, "API KEYS & TOKENS", theme);
+                ui.add_space(ELEMENT_SPACING);
+
+                card_frame(theme, density).show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new("⚠️").size(12.0));
+                        ui.add_space(TIGHT_SPACING);
+                        ui.label(
+                            RichText::new("No API keys stored in Cursor Studio")
+                                .color(theme.fg_dim)
+                                .size(theme.text_size(10.0)),
+                        );
+                    });
+                    ui.add_space(TIGHT_SPACING);
+                    ui.label(
+                        RichText::new("API keys are managed by Cursor directly")
+                            .color(theme.fg_dim)
+                            .size(theme.text_size(9.0))
+                            .italics(),
+                    );
+                });
+                ui.add_space(SECTION_SPACING);
+
+                // Scan Section
+                panel_header(ui, "SECURITY SCANS", theme);
+                ui.add_space(ELEMENT_SPACING);
+
+                ui.horizontal(|ui| {
+                    ui.add_space(PANEL_PADDING);
+                    
+                    // Show scan in progress indicator or button
+                    if let Some((scanned, total)) = self.security_scan_progress {
+                        // Scan in progress - show progress bar
+                        let progress = if total > 0 { scanned as f32 / total as f32 } else { 0.0 };
+                        ui.vertical(|ui| {
+                            ui.add(egui::ProgressBar::new(progress)
+                                .desired_width(160.0)
+                                .text(format!("Scanning... {}/{}", scanned, total)));
+                        });
+                    } else {
+                        // No scan running - show button
+                        if styled_button(ui, "🔍 Scan Chat History", Vec2::new(160.0, 32.0))
+                            .on_hover_text("Scan for sensitive data in chat history")
+                            .clicked()
+                        {
+                            self.run_security_scan();
+                        }
+                    }
+                });
+                ui.add_space(TIGHT_SPACING);
+
+                ui.horizontal(|ui| {
+                    ui.add_space(PANEL_PADDING);
+                    if styled_button(ui, "🗑️ Purge Sensitive Data", Vec2::new(160.0, 28.0))
+                        .on_hover_text("Remove detected sensitive data from chat history")
+                        .clicked()
+                    {
+                        self.set_status("⚠️ Purge not yet implemented");
+                    }
+                });
+                ui.add_space(TIGHT_SPACING);
+
+                if self.security_scan_results.is_some() {
+                    ui.horizontal(|ui| {
+                        ui.add_space(PANEL_PADDING);
+                        if styled_button(ui, "📤 Export SARIF", Vec2::new(160.0, 28.0))
+                            .on_hover_text("Save findings as SARIF 2.1.0 for security dashboards")
+                            .clicked()
+                        {
+                            self.export_security_sarif();
+                        }
+                        if self.npm_scan_results.is_some() {
+                            ui.checkbox(&mut self.sarif_include_npm, "Include NPM");
+                        }
+                    });
+                }
+
+                // Show scan results if available
+                let mut jump_to_msg: Option<(String, String)> = None;
+                let mut toggle_context: Option<security::ChatFinding> = None;
+                let mut copy_value: Option<(security::SecretKind, String)> = None;
+                let mut preview_len_changed = false;
+
+                if let Some(ref results) = self.security_scan_results {
+                    ui.add_space(SECTION_SPACING);
+                    card_frame(theme, density).show(ui, |ui| {
+                            ui.label(
+                                RichText::new(format!("📊 Scan Results ({})", results.scanned_at))
+                                    .color(theme.fg)
+                                    .strong()
+                                    .size(theme.text_size(11.0)),
+                            );
+                            ui.add_space(4.0);
+                            ui.label(
+                                RichText::new(format!(
+                                    "Messages scanned: {}",
+                                    results.total_messages
+                                ))
+                                .color(theme.fg_dim)
+                                .size(theme.text_size(10.0)),
+                            );
+
+                            ui.horizontal(|ui| {
+                                ui.label(
+                                    RichText::new("Preview length")
+                                        .color(theme.fg_dim)
+                                        .size(theme.text_size(10.0)),
+                                )
+                                .on_hover_text("Characters of each finding shown before it's cut off");
+                                preview_len_changed = ui
+                                    .add(
+                                        egui::Slider::new(&mut self.secret_preview_chars, 10..=200)
+                                            .suffix(" chars"),
+                                    )
+                                    .changed();
+                            });
+
+                            let total_found = results.potential_api_keys.len()
+                                + results.potential_passwords.len()
+                                + results.potential_secrets.len();
+
+                            if total_found == 0 {
+                                ui.add_space(4.0);
+                                ui.label(
+                                    RichText::new("✓ No sensitive data detected")
+                                        .color(theme.success)
+                                        .size(theme.text_size(11.0)),
+                                );
+                            } else {
+                                ui.add_space(8.0);
+
+                                // Counts per level; click one to hide or show its findings
+                                ui.horizontal(|ui| {
+                                    for severity in security::FindingSeverity::ALL {
+                                        let hidden = self.hidden_severities.contains(&severity);
+                                        let color = if hidden {
+                                            theme.fg_dim
+                                        } else {
+                                            severity_color(theme, severity)
+                                        };
+                                        let chip = ui
+                                            .selectable_label(
+                                                !hidden,
+                                                RichText::new(format!(
+                                                    "{} {}",
+                                                    severity.label(),
+                                                    results.severity_count(severity)
+                                                ))
+                                                .color(color)
+                                                .size(theme.text_size(10.0)),
+                                            )
+                                            .on_hover_text(if hidden {
+                                                "Show these findings"
+                                            } else {
+                                                "Hide these findings"
+                                            });
+                                        if chip.clicked() && !self.hidden_severities.remove(&severity) {
+                                            self.hidden_severities.insert(severity);
+                                        }
+                                    }
+                                });
+                                ui.horizontal(|ui| {
+                                    ui.label(
+                                        RichText::new("Group by")
+                                            .color(theme.fg_dim)
+                                            .size(theme.text_size(10.0)),
+                                    );
+                                    ui.selectable_value(&mut self.findings_by_severity, false, "Type");
+                                    ui.selectable_value(&mut self.findings_by_severity, true, "Severity");
+                                });
+
+                                let mut groups: Vec<FindingGroup> = Vec::new();
+                                if self.findings_by_severity {
+                                    for severity in security::FindingSeverity::ALL {
+                                        if self.hidden_severities.contains(&severity) {
+                                            continue;
+                                        }
+                                        let findings = security::SecretKind::ALL
+                                            .into_iter()
+                                            .flat_map(|kind| {
+                                                results
+                                                    .findings(kind)
+                                                    .iter()
+                                                    .filter(move |f| f.severity == severity)
+                                                    .map(move |f| (kind, f))
+                                            })
+                                            .collect();
+                                        groups.push((
+                                            "●",
+                                            severity.label(),
+                                            severity_color(theme, severity),
+                                            5,
+                                            findings,
+                                        ));
+                                    }
+                                } else {
+                                    for kind in security::SecretKind::ALL {
+                                        let (icon, label, color, shown) =
+                                            secret_kind_style(theme, kind);
+                                        let mut findings: Vec<_> = results
+                                            .findings(kind)
+                                            .iter()
+                                            .filter(|f| !self.hidden_severities.contains(&f.severity))
+                                            .map(|f| (kind, f))
+                                            .collect();
+                                        // Highest severity first
+                                        findings.sort_by_key(|f| std::cmp::Reverse(f.1.severity));
+                                        groups.push((icon, label, color, shown, findings));
+                                    }
+                                }
+
+                                for (icon, label, color, shown, findings) in groups {
+                                    if findings.is_empty() {
+                                        continue;
+                                    }
+                                    ui.add_space(4.0);
+                                    ui.horizontal(|ui| {
+                                        ui.label(RichText::new(icon).color(color).size(12.0));
+                                        ui.label(
+                                            RichText::new(format!("{}: {}", label, findings.len()))
+                                                .color(color)
+                                                .size(theme.text_size(10.0)),
+                                        );
+                                    });
+                                    // Show first few with jump and context buttons
+                                    for &(kind, finding) in findings.iter().take(shown) {
+                                        let context = self.finding_contexts.get(finding);
+                                        ui.horizontal(|ui| {
+                                            ui.add_space(20.0);
+                                            if ui
+                                                .small_button("→")
+                                                .on_hover_text("Jump to message")
+                                                .clicked()
+                                            {
+                                                jump_to_msg = Some((
+                                                    finding.conversation_id.clone(),
+                                                    finding.message_id.clone(),
+                                                ));
+                                            }
+                                            let (arrow, hint) = if context.is_some() {
+                                                ("▾", "Hide context")
+                                            } else {
+                                                ("▸", "Show full context")
+                                            };
+                                            if ui.small_button(arrow).on_hover_text(hint).clicked() {
+                                                toggle_context = Some(finding.clone());
+                                            }
+                                            // Whichever of type and severity the group doesn't show
+                                            if self.findings_by_severity {
+                                                let (icon, label, _, _) =
+                                                    secret_kind_style(theme, kind);
+                                                ui.label(
+                                                    RichText::new(icon).size(theme.text_size(9.0)),
+                                                )
+                                                .on_hover_text(label);
+                                            } else {
+                                                ui.label(
+                                                    RichText::new("●")
+                                                        .color(severity_color(theme, finding.severity))
+                                                        .size(theme.text_size(9.0)),
+                                                )
+                                                .on_hover_text(format!(
+                                                    "{} severity",
+                                                    finding.severity.label()
+                                                ));
+                                            }
+                                            ui.label(
+                                                RichText::new(security::finding_preview(
+                                                    &finding.matched,
+                                                    self.secret_preview_chars,
+                                                ))
+                                                .color(theme.fg_dim)
+                                                .size(theme.text_size(9.0))
+                                                .family(egui::FontFamily::Monospace),
+                                            );
+                                        });
+                                        let Some(context) = context else {
+                                            continue;
+                                        };
+                                        ui.horizontal(|ui| {
+                                            ui.add_space(40.0);
+                                            ui.vertical(|ui| {
+                                                match context {
+                                                    Some(text) => ui.label(
+                                                        RichText::new(text)
+                                                            .color(theme.fg)
+                                                            .size(theme.text_size(9.0))
+                                                            .family(egui::FontFamily::Monospace),
+                                                    ),
+                                                    None => ui.label(
+                                                        RichText::new(
+                                                            "Message no longer contains this text",
+                                                        )
+                                                        .color(theme.fg_dim)
+                                                        .italics()
+                                                        .size(theme.text_size(9.0)),
+                                                    ),
+                                                };
+                                                ui.horizontal(|ui| {
+                                                    if ui
+                                                        .small_button("📋 Copy value")
+                                                        .on_hover_text(
+                                                            "⚠ Puts the secret on the clipboard, \
+                                                             where other apps can read it",
+                                                        )
+                                                        .clicked()
+                                                    {
+                                                        copy_value =
+                                                            Some((kind, finding.matched.clone()));
+                                                    }
+                                                    if ui.small_button("→ Jump to message").clicked() {
+                                                        jump_to_msg = Some((
+                                                            finding.conversation_id.clone(),
+                                                            finding.message_id.clone(),
+                                                        ));
+                                                    }
+                                                });
+                                            });
+                                        });
+                                    }
+                                    if findings.len() > shown {
+                                        ui.horizontal(|ui| {
+                                            ui.add_space(20.0);
+                                            ui.label(
+                                                RichText::new(format!(
+                                                    "... and {} more",
+                                                    findings.len() - shown
+                                                ))
+                                                .color(theme.fg_dim)
+                                                .size(theme.text_size(9.0)),
+                                            );
+                                        });
+                                    }
+                                }
+                            }
+                        });
+                }
+
+                // Process jump-to after UI
+                if let Some((conv_id, msg_id)) = jump_to_msg {
+                    self.scroll_to_message(&conv_id, &msg_id);
+                }
+                if let Some(finding) = toggle_context {
+                    self.toggle_finding_context(finding);
+                }
+                if let Some((kind, matched)) = copy_value {
+                    match kind.value(&matched) {
+                        Some(value) => {
+                            ui.ctx().copy_text(value);
+                            self.set_status(
+                                "⚠️ Copied secret to clipboard - clear it once you're done",
+                            );
+                        }
+                        None => self.set_status("✗ Couldn't find the value in this finding"),
+                    }
+                }
+                if preview_len_changed {
+                    self.save_settings();
+                }
+
+                ui.add_space(16.0);
+
+                // NPM Package Security Section
+                ui.horizontal(|ui| {
+                    ui.add_space(16.0);
+                    ui.label(
+                        RichText::new("NPM PACKAGE SECURITY")
+                            .size(theme.text_size(11.0))
+                            .color(theme.fg_dim)
+                            .strong(),
+                    );
+                });
+                ui.add_space(8.0);
+
+                // Blocklist stats
+                let stats = self.npm_scanner.get_blocklist_stats();
+                egui::Frame::none()
+                    .fill(theme.code_bg)
+                    .rounding(Rounding::same(8.0))
+                    .inner_margin(egui::Margin::same(12.0))
+                    .show(ui, |ui| {
+                        ui.label(
+                            RichText::new("📦 Blocklist Database")
+                                .color(theme.fg)
+                                .strong()
+                                .size(theme.text_size(11.0)),
+                        );
+                        ui.add_space(4.0);
+                        ui.label(
+                            RichText::new(format!(
+                                "Version: {} • Updated: {}",
+                                stats.version, stats.last_updated
+                            ))
+                            .color(theme.fg_dim)
+                            .size(theme.text_size(10.0)),
+                        );
+                        ui.label(
+                            RichText::new(format!(
+                                "{} blocked packages • {} with CVEs",
+                                stats.total_packages, stats.packages_with_cve
+                            ))
+                            .color(theme.warning)
+                            .size(theme.text_size(10.0)),
+                        );
+
+                        // Show categories (sorted for stable display order)
+                        ui.add_space(4.0);
+                        let mut categories: Vec<_> = stats.categories.iter().collect();
+                        categories.sort_by_key(|(name, _)| name.as_str());
+                        for (name, count) in categories {
+                            ui.horizontal(|ui| {
+                                ui.add_space(8.0);
+                                ui.label(
+                                    RichText::new(format!("• {}: {}", name, count))
+                                        .color(theme.fg_dim)
+                                        .size(theme.text_size(9.0)),
+                                );
+                            });
+                        }
+                    });
+                ui.add_space(8.0);
+
+                // NPM scan path input
+                ui.horizontal(|ui| {
+                    ui.add_space(16.0);
+                    ui.label(
+                        RichText::new("Scan Path:")
+                            .color(theme.fg)
+                            .size(theme.text_size(11.0)),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    ui.add_space(16.0);
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.npm_scan_path)
+                            .desired_width(ui.available_width() - 80.0)
+                            .hint_text("/path/to/project")
+                            .font(egui::FontId::monospace(theme.text_size(11.0))),
+                    );
+                });
+                ui.add_space(8.0);
+
+                ui.horizontal(|ui| {
+                    ui.add_space(16.0);
+                    
+                    // Show scan in progress indicator or button
+                    if let Some(ref progress_msg) = self.npm_scan_progress {
+                        // Scan in progress - show spinner/message
+                        ui.spinner();
+                        ui.label(
+                            RichText::new(progress_msg)
+                                .size(theme.text_size(11.0))
+                                .color(theme.fg_dim),
+                        );
+                    } else {
+                        // No scan running - show button
+                        if styled_button(ui, "🔍 Scan for Malicious Packages", Vec2::new(200.0, 32.0))
+                            .on_hover_text("Scan package.json files for known malicious packages")
+                            .clicked()
+                        {
+                            self.scan_npm_packages();
+                        }
+                    }
+                });
+
+                // Show NPM scan results if available
+                if self.show_npm_scan_results {
+                    if let Some(ref results) = self.npm_scan_results {
+                        ui.add_space(8.0);
+                        egui::Frame::none()
+                            .fill(theme.code_bg)
+                            .rounding(Rounding::same(8.0))
+                            .inner_margin(egui::Margin::same(12.0))
+                            .show(ui, |ui| {
+                                if results.is_empty() {
+                                    ui.label(
+                                        RichText::new("✓ No blocked packages found")
+                                            .color(theme.success)
+                                            .size(theme.text_size(11.0)),
+                                    );
+                                } else {
+                                    ui.label(
+                                        RichText::new(format!(
+                                            "⚠️ Found issues in {} files:",
+                                            results.len()
+                                        ))
+                                        .color(theme.error)
+                                        .strong()
+                                        .size(theme.text_size(11.0)),
+                                    );
+                                    ui.add_space(4.0);
+
+                                    for (path, packages) in results.iter().take(10) {
+                                        ui.label(
+                                            RichText::new(path.to_string_lossy())
+                                                .color(theme.fg)
+                                                .size(theme.text_size(10.0))
+                                                .family(egui::FontFamily::Monospace),
+                                        );
+                                        for pkg in packages {
+                                            ui.horizontal(|ui| {
+                                                ui.add_space(16.0);
+                                                ui.label(
+                                                    RichText::new(format!(
+                                                        "🚫 {} - {}",
+                                                        pkg.package_name,
+                                                        pkg.block_reason
+                                                            .as_deref()
+                                                            .unwrap_or("blocked")
+                                                    ))
+                                                    .color(theme.error)
+                                                    .size(theme.text_size(9.0)),
+                                                );
+                                                if let Some(cve) = &pkg.cve {
+                                                    ui.label(
+                                                        RichText::new(format!("[{}]", cve))
+                                                            .color(theme.warning)
+                                                            .size(theme.text_size(9.0)),
+                                                    );
+                                                }
+                                            });
+                                        }
+                                    }
+                                }
+                            });
+                    }
+                }
+
+                ui.add_space(16.0);
+
+                // Audit Log Section
+                ui.horizontal(|ui| {
+                    ui.add_space(16.0);
+                    ui.label(
+                        RichText::new("AUDIT LOG")
+                            .size(theme.text_size(11.0))
+                            .color(theme.fg_dim)
+                            .strong(),
+                    );
+                });
+                ui.add_space(8.0);
+
+                egui::Frame::none()
+                    .fill(theme.code_bg)
+                    .rounding(Rounding::same(8.0))
+                    .inner_margin(egui::Margin::same(12.0))
+                    .show(ui, |ui| {
+                        ui.label(
+                            RichText::new("Recent Activity")
+                                .color(theme.fg)
+                                .strong()
+                                .size(theme.text_size(11.0)),
+                        );
+                        ui.add_space(4.0);
+
+                        // Show dynamic events based on actual state
+                        ui.horizontal(|ui| {
+                            ui.label(
+                                RichText::new("✓")
+                                    .color(theme.success)
+                                    .size(theme.text_size(10.0)),
+                            );
+                            ui.add_space(4.0);
+                            ui.label(
+                                RichText::new("Database loaded")
+                                    .color(theme.fg_dim)
+                                    .size(theme.text_size(10.0)),
+                            );
+                        });
+
+                        // Show last security scan if available
+                        if let Some(ref results) = self.security_scan_results {
+                            let total_found = results.potential_api_keys.len()
+                                + results.potential_passwords.len()
+                                + results.potential_secrets.len();
+                            let (icon, color) = if total_found > 0 {
+                                ("⚠", theme.warning)
+                            } else {
+                                ("✓", theme.success)
+                            };
+                            ui.horizontal(|ui| {
+                                ui.label(
+                                    RichText::new(icon)
+                                        .color(color)
+                                        .size(theme.text_size(10.0)),
+                                );
+                                ui.add_space(4.0);
+                                ui.label(RichText::new(format!(
+                                    "Security scan: {} ({})",
+                                    results.scanned_at,
+                                    if total_found > 0 { format!("{} findings", total_found) } else { "clean".to_string() }
+                                )).color(theme.fg_dim).size(theme.text_size(10.0)));
+                            });
+                        }
+
+                        // Show chat import status
+                        let chat_count = self.conversations_total;
+                        if chat_count > 0 {
+                            ui.horizontal(|ui| {
+                                ui.label(
+                                    RichText::new("✓")
+                                        .color(theme.success)
+                                        .size(theme.text_size(10.0)),
+                                );
+                                ui.add_space(4.0);
+                                ui.label(RichText::new(format!(
+                                    "{} conversations imported",
+                                    chat_count
+                                )).color(theme.fg_dim).size(theme.text_size(10.0)));
+                            });
+                        } else {
+                            ui.horizontal(|ui| {
+                                ui.label(
+                                    RichText::new("○")
+                                        .color(theme.fg_dim)
+                                        .size(theme.text_size(10.0)),
+                                );
+                                ui.add_space(4.0);
+                                ui.label(
+                                    RichText::new("No conversations imported yet")
+                                        .color(theme.fg_dim)
+                                        .size(theme.text_size(10.0)),
+                                );
+                            });
+                        }
+                    });
+                ui.add_space(16.0);
+
+                // Settings Section
+                ui.horizontal(|ui| {
+                    ui.add_space(16.0);
+                    ui.label(
+                        RichText::new("SECURITY SETTINGS")
+                            .size(theme.text_size(11.0))
+                            .color(theme.fg_dim)
+                            .strong(),
+                    );
+                });
+                ui.add_space(8.0);
+
+                // Current features with status
+                let current_features = [
+                    ("✓", "Sensitive data detection (scan above)", theme.success),
+                    ("✓", "NPM malicious package scanning", theme.success),
+                    ("✓", "Jump-to-message navigation", theme.success),
+                    ("✓", "Idle session lock (Settings)", theme.success),
+                    ("○", "Encrypted local storage", theme.fg_dim),
+                    ("○", "Auto-redaction in exports", theme.fg_dim),
+                    ("○", "Audit log export", theme.fg_dim),
+                ];
+
+                for (icon, feature, color) in current_features {
+                    ui.horizontal(|ui| {
+                        ui.add_space(20.0);
+                        ui.label(RichText::new(icon).color(color).size(theme.text_size(10.0)));
+                        ui.add_space(4.0);
+                        ui.label(RichText::new(feature).color(color).size(theme.text_size(10.0)));
+                    });
+                }
+
+                ui.add_space(16.0);
+
+                // Development status note
+                egui::Frame::none()
+                    .fill(theme.input_bg)
+                    .rounding(Rounding::same(6.0))
+                    .inner_margin(egui::Margin::same(10.0))
+                    .show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label(RichText::new("🔧").size(12.0));
+                            ui.add_space(4.0);
+                            ui.label(
+                                RichText::new("Security features under active development")
+                                    .color(theme.fg_dim)
+                                    .size(theme.text_size(10.0))
+                                    .italics(),
+                            );
+                        });
+                    });
+
+                ui.add_space(20.0);
+            });
+    }
+
+    fn show_bridge_panel(&mut self, ui: &mut egui::Ui, theme: Theme) {
+        let density = self.density;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.add_space(SECTION_SPACING);
+
+            // ======================================
+            // BUILD CAPABILITIES
+            // ======================================
+            let capabilities = sync_capabilities();
+            let build_name = if capabilities.iter().all(|c| c.1) {
+                "Full build"
+            } else if capabilities.iter().skip(1).all(|c| !c.1) {
+                "Lite build"
+            } else {
+                "Custom build"
+            };
+            card_frame(theme, density).show(ui, |ui| {
+                ui.label(
+                    RichText::new(format!(
+                        "🧩 {} • v{}",
+                        build_name,
+                        env!("CARGO_PKG_VERSION")
+                    ))
+                    .color(theme.fg)
+                    .strong()
+                    .size(12.0),
+                );
+                ui.add_space(TIGHT_SPACING);
+                for (name, available, feature) in capabilities {
+                    let (icon, color) = if available {
+                        ("✓", theme.success)
+                    } else {
+                        ("✗", theme.fg_dim)
+                    };
+                    let row = ui.label(
+                        RichText::new(format!("{} {}", icon, name))
+                            .color(color)
+                            .size(theme.text_size(11.0)),
+                    );
+                    if !available {
+                        row.on_hover_text(format!(
+                            "Not in this build (needs the '{}' feature)",
+                            feature
+                        ));
+                    }
+                }
+                if !SERVER_SYNC_AVAILABLE {
+                    ui.add_space(TIGHT_SPACING);
+                    ui.label(
+                        RichText::new("Rebuild without --lite to enable server and P2P sync")
+                            .color(theme.fg_dim)
+                            .size(theme.text_size(10.0)),
+                    );
+                }
+            });
+            ui.add_space(SECTION_SPACING);
+
+            // ======================================
+            // ELIXIR SYNC DAEMON (Primary)
+            // ======================================
+            card_frame(theme, density).show(ui, |ui| {
+                // Render the Elixir sync daemon panel
+                self.sync_daemon_panel.ui(ui);
+            });
+            
+            ui.add_space(SECTION_SPACING);
+            ui.separator();
+            ui.add_space(SECTION_SPACING);
+
+            // ======================================
+            // LEGACY: Coming Soon Banner
+            // ======================================
+            accent_card_frame(theme, density).show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new("🔮").size(14.0));
+                    ui.add_space(ELEMENT_SPACING);
+                    ui.vertical(|ui| {
+                        ui.label(
+                            RichText::new("Future: P2P & Server Sync")
+                                .color(theme.accent)
+                                .strong()
+                                .size(12.0),
+                        );
+                        ui.label(
+                            RichText::new("Multi-device sync planned for v0.4.0")
+                                .color(theme.fg_dim)
+                                .size(theme.text_size(10.0)),
+                        );
+                    });
+                });
+            });
+            ui.add_space(SECTION_SPACING);
+
+            panel_header(ui, "DEVICE INFO", theme);
+            ui.add_space(ELEMENT_SPACING);
+
+            // Device Info Card
+            card_frame(theme, density).show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new("📱").size(16.0));
+                    ui.add_space(ELEMENT_SPACING);
+                    ui.vertical(|ui| {
+                        ui.label(
+                            RichText::new("This Device")
+                                .color(theme.fg)
+                                .strong()
+                                .size(theme.text_size(11.0)),
+                        );
+                        // Get hostname
+                        let hostname = hostname::get()
+                            .map(|h| h.to_string_lossy().to_string())
+                            .unwrap_or_else(|_| "unknown".to_string());
+                        ui.label(
+                            RichText::new(&hostname)
+                                .color(theme.fg_dim)
+                                .size(theme.text_size(10.0)),
+                        );
+                    });
+                });
+            });
+            ui.add_space(SECTION_SPACING);
+
+            // Server Connection
+            ui.add_space(ELEMENT_SPACING);
+
+            // Server controls are greyed out when this build can't sync with a server
+            ui.add_enabled_ui(SERVER_SYNC_AVAILABLE, |ui| {
+                // Server URL input
+                ui.horizontal(|ui| {
+                    ui.add_space(16.0);
+                    ui.label(RichText::new("Server URL:").color(theme.fg).size(12.0));
+                });
+                ui.horizontal(|ui| {
+                    ui.add_space(16.0);
+                    let response = ui
+                        .add(
+                            egui::TextEdit::singleline(&mut self.sync_server_url)
+                                .desired_width(ui.available_width() - 32.0)
+                                .margin(egui::Margin::symmetric(8.0, 6.0)),
+                        )
+                        .on_disabled_hover_text(SERVER_SYNC_DISABLED_HINT);
+                    if response.changed() {
+                        self.sync_server_connected = false;
+                    }
+                });
+                ui.add_space(8.0);
+
+                // Connection status
+                let status_color = if self.sync_server_connected {
+                    theme.success
+                } else {
+                    theme.fg_dim
+                };
+                let status_text = if self.sync_server_connected {
+                    format!(
+                        "✓ Connected ({} conversations)",
+                        self.sync_conversation_count
+                    )
+                } else if !SERVER_SYNC_AVAILABLE {
+                    "○ Not available in this build".to_string()
+                } else {
+                    "○ Not connected".to_string()
+                };
+                ui.horizontal(|ui| {
+                    ui.add_space(16.0);
+                    ui.label(
+                        RichText::new(&status_text)
+                            .color(status_color)
+                            .size(theme.text_size(11.0)),
+                    );
+                });
+                ui.add_space(8.0);
+
+                // Server actions
+                ui.horizontal(|ui| {
+                    ui.add_space(16.0);
+                    if styled_button(ui, "🔌 Connect", Vec2::new(100.0, 28.0))
+                        .on_disabled_hover_text(SERVER_SYNC_DISABLED_HINT)
+                        .clicked()
+                    {
+                        self.check_server_status();
+                    }
+                    ui.add_space(4.0);
+                    if self.sync_server_connected {
+                        if styled_button(ui, "📥 Pull", Vec2::new(80.0, 28.0))
+                            .on_hover_text("Pull conversations from server")
+                            .clicked()
+                        {
+                            self.pull_from_server();
+                        }
+                    }
+                });
+            });
+
+            // Last status message
+            if let Some(ref status) = self.sync_last_status {
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    ui.add_space(16.0);
+                    ui.label(RichText::new(status).color(theme.fg_dim).size(theme.text_size(10.0)));
+                });
+            }
+
+            ui.add_space(16.0);
+
+            // P2P Section
+            ui.horizontal(|ui| {
+                ui.add_space(16.0);
+                ui.label(
+                    RichText::new("PEER-TO-PEER")
+                        .size(theme.text_size(11.0))
+                        .color(theme.fg_dim)
+                        .strong(),
+                );
+            });
+            ui.add_space(8.0);
+
+            // P2P Daemon Controls
+            egui::Frame::none()
+                .fill(theme.code_bg)
+                .rounding(Rounding::same(8.0))
+                .inner_margin(egui::Margin::same(12.0))
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new("🔗").size(16.0));
+                        ui.add_space(8.0);
+                        ui.vertical(|ui| {
+                            ui.label(
+                                RichText::new("P2P Daemon")
+                                    .color(theme.fg)
+                                    .strong()
+                                    .size(12.0),
+                            );
+                            let status_text = if self.p2p_daemon_running {
+                                format!("Running on port {}", self.p2p_daemon_port)
+                            } else {
+                                "Stopped".to_string()
+                            };
+                            let status_color = if self.p2p_daemon_running {
+                                theme.success
+                            } else {
+                                theme.fg_dim
+                            };
+                            ui.label(
+                                RichText::new(&status_text)
+                                    .color(status_color)
+                                    .size(theme.text_size(11.0)),
+                            );
+                        });
+                    });
+                    ui.add_space(8.0);
+                    
+                    ui.horizontal(|ui| {
+                        if self.p2p_daemon_running {
+                            if styled_button(ui, "⏹ Stop", Vec2::new(80.0, 28.0))
+                                .on_hover_text("Stop P2P daemon")
+                                .clicked()
+                            {
+                                self.stop_p2p_daemon();
+                            }
+                        } else {
+                            if styled_button(ui, "▶ Start", Vec2::new(80.0, 28.0))
+                                .on_hover_text("Start P2P daemon for local network discovery")
+                                .clicked()
+                            {
+                                self.start_p2p_daemon();
+                            }
+                        }
+                        ui.add_space(8.0);
+                        ui.label(
+                            RichText::new("Port:")
+                                .color(theme.fg_dim)
+                                .size(theme.text_size(11.0)),
+                        );
+                        ui.add_space(4.0);
+                        let port_str = &mut format!("{}", self.p2p_daemon_port);
+                        let response = ui.add(
+                            egui::TextEdit::singleline(port_str)
+                                .desired_width(60.0)
+                                .font(egui::FontId::monospace(theme.text_size(11.0))),
+                        );
+                        if response.changed() {
+                            if let Ok(port) = port_str.parse::<u16>() {
+                                self.p2p_daemon_port = port;
+                            }
+                        }
+                    });
+                });
+            ui.add_space(8.0);
+
+            // Discovered Peers
+            egui::Frame::none()
+                .fill(theme.code_bg)
+                .rounding(Rounding::same(8.0))
+                .inner_margin(egui::Margin::same(12.0))
+                .show(ui, |ui| {
+                    ui.label(
+                        RichText::new("📡 Discovered Peers")
+                            .color(theme.fg)
+                            .size(12.0),
+                    );
+                    ui.add_space(4.0);
+
+                    if self.sync_p2p_peers.is_empty() {
+                        ui.label(
+                            RichText::new("No peers discovered yet")
+                                .color(theme.fg_dim)
+                                .size(theme.text_size(11.0))
+                                .italics(),
+                        );
+                        if self.p2p_daemon_running {
+                            ui.add_space(4.0);
+                            ui.label(
+                                RichText::new("Waiting for peers on local network...")
+                                    .color(theme.fg_dim)
+                                    .size(theme.text_size(10.0)),
+                            );
+                        } else {
+                            ui.add_space(4.0);
+                            ui.label(
+                                RichText::new("Start daemon to discover peers")
+                                    .color(theme.fg_dim)
+                                    .size(theme.text_size(10.0)),
+                            );
+                        }
+                    } else {
+                        for peer in &self.sync_p2p_peers {
+                            ui.horizontal(|ui| {
+                                ui.label(RichText::new("• ").color(theme.accent));
+                                ui.label(
+                                    RichText::new(peer)
+                                        .color(theme.fg)
+                                        .size(theme.text_size(11.0)),
+                                );
+                            });
+                        }
+                    }
+                });
+
+            ui.add_space(16.0);
+
+            // Quick Actions
+            ui.horizontal(|ui| {
+                ui.add_space(16.0);
+                ui.label(
+                    RichText::new("QUICK ACTIONS")
+                        .size(theme.text_size(11.0))
+                        .color(theme.fg_dim)
+                        .strong(),
+                );
+            });
+            ui.add_space(8.0);
+
+            ui.horizontal(|ui| {
+                ui.add_space(16.0);
+                if styled_button(ui, "📤 Export All", Vec2::new(100.0, 28.0))
+                    .on_hover_text("Export all conversations to JSON")
+                    .clicked()
+                {
+                    self.set_status("Export coming soon...");
+                }
+            });
+        });
+    }
+
+    /// Show the Index (Documentation) panel
+    /// Integrates with cursor-docs backend for doc indexing and search
+    fn show_index_panel(&mut self, ui: &mut egui::Ui, theme: Theme) {
+        // Create a theme adapter for the docs panel
+        struct ThemeAdapter(Theme);
+        impl docs::DocsTheme for ThemeAdapter {
+            fn bg(&self) -> Color32 { self.0.sidebar_bg }
+            fn fg(&self) -> Color32 { self.0.fg }
+            fn fg_dim(&self) -> Color32 { self.0.fg_dim }
+            fn accent(&self) -> Color32 { self.0.accent }
+            fn error(&self) -> Color32 { self.0.error }
+            fn success(&self) -> Color32 { self.0.success }
+            fn card_bg(&self) -> Color32 { self.0.code_bg }
+            fn button_bg(&self) -> Color32 { self.0.input_bg }
+            fn selection_bg(&self) -> Color32 { self.0.accent.gamma_multiply(0.3) }
+            fn text_size(&self, size: f32) -> f32 { self.0.text_size(size) }
+        }
+
+        // Show the panel (no ScrollArea - panel handles its own scrolling)
+        self.docs_panel.show(ui, &ThemeAdapter(theme));
+
+        // Handle events from the docs panel
+        for event in self.docs_panel.take_events() {
+            match event {
+                docs::DocsPanelEvent::OpenSource { source_id, source_name } => {
+                    // Open source in a new tab
+                    self.open_indexed_source(&source_id, &source_name);
+                }
+                docs::DocsPanelEvent::OpenChunk {
+                    source_id,
+                    source_name,
+                    chunk_id,
+                    position,
+                } => {
+                    self.open_indexed_source(&source_id, &source_name);
+                    self.doc_scroll_target = Some((chunk_id, position));
+                }
+                docs::DocsPanelEvent::StatusMessage(msg) => {
+                    self.set_status(&msg);
+                }
+            }
+        }
+    }
+
+    /// Open an indexed source in the editor area as a tab
+    fn open_indexed_source(&mut self, source_id: &str, source_name: &str) {
+        // Check if already open
+        for (i, tab) in self.tabs.iter().enumerate() {
+            if let Tab::IndexedDoc(id) = tab {
+                if id == source_id {
+                    self.active_tab = i;
+                    self.set_status(&format!("📖 Switched to: {}", source_name));
+                    return;
+                }
+            }
+        }
+        
+        // Open new tab
+        self.tabs.push(Tab::IndexedDoc(source_id.to_string()));
+        self.active_tab = self.tabs.len() - 1;
+        self.set_status(&format!("📖 Opened: {}", source_name));
+    }
+
+    /// Forge panel - Data transformation and training data preparation
+    fn show_forge_panel(&mut self, ui: &mut egui::Ui, theme: Theme) {
+        let density = self.density;
+        egui::ScrollArea::vertical()
+            .auto_shrink([false; 2])
+            .show(ui, |ui| {
+                ui.add_space(SECTION_SPACING);
+
+                // Header
+                panel_header_with_icon(ui, "🔥", "FORGE", theme);
+                ui.add_space(TIGHT_SPACING);
+                ui.horizontal(|ui| {
+                    ui.add_space(PANEL_PADDING);
+                    ui.label(
+                        RichText::new("Data transformation & training preparation")
+                            .size(theme.text_size(9.0))
+                            .color(theme.fg_dim),
+                    );
+                });
+
+                ui.add_space(SECTION_SPACING);
+
+                // Coming Soon Banner
+                warning_card_frame(theme, density)
+                    .show(ui, |ui| {
+                        ui.vertical_centered(|ui| {
+                            ui.label(
+                                RichText::new("🚧 Coming Soon")
+                                    .size(16.0)
+                                    .color(theme.warning)
+                                    .strong(),
+                            );
+                            ui.add_space(12.0);
+                            ui.label(
+                                RichText::new("The Forge will enable:")
+                                    .size(theme.text_size(11.0))
+                                    .color(theme.fg),
+                            );
+                            ui.add_space(8.0);
+                        });
+
+                        let features = [
+                            "📊 Combine chats + docs into training datasets",
+                            "🔄 Transform to OpenAI/Alpaca/ShareGPT formats",
+                            "🧹 Quality filtering and deduplication",
+                            "✂️ Train/validation/test splits",
+                            "🔒 PII redaction and anonymization",
+                            "📈 Dataset statistics and visualization",
+                        ];
+
+                        for feature in features {
+                            ui.horizontal(|ui| {
+                                ui.add_space(16.0);
+                                ui.label(
+                                    RichText::new(feature)
+                                        .size(theme.text_size(9.0))
+                                        .color(theme.fg_dim),
+                                );
+                            });
+                            ui.add_space(TIGHT_SPACING);
+                        }
+                    });
+
+                ui.add_space(SECTION_SPACING);
+
+                // Quick Actions (placeholder)
+                panel_header(ui, "QUICK ACTIONS", theme);
+                ui.add_space(ELEMENT_SPACING);
+
+                ui.horizontal(|ui| {
+                    ui.add_space(PANEL_PADDING);
+                    if styled_button(ui, "Export All Chats", Vec2::new(120.0, 26.0))
+                        .on_hover_text("Export all chats to training format")
+                        .clicked()
+                    {
+                        self.right_mode = RightSidebarMode::Archive;
+                        self.show_export_dialog = true;
+                    }
+                });
+
+                ui.add_space(ELEMENT_SPACING);
+
+                ui.horizontal(|ui| {
+                    ui.add_space(PANEL_PADDING);
+                    if styled_button(ui, "Index All Docs", Vec2::new(120.0, 26.0))
+                        .on_hover_text("View indexed documentation")
+                        .clicked()
+                    {
+                        self.right_mode = RightSidebarMode::Index;
+                    }
+                });
+
+                ui.add_space(SECTION_SPACING);
+
+                // CLI Reference
+                card_frame(theme, density)
+                    .show(ui, |ui| {
+                        ui.label(
+                            RichText::new("CLI Reference")
+                                .size(theme.text_size(11.0))
+                                .color(theme.fg)
+                                .strong(),
+                        );
+                        ui.add_space(8.0);
+
+                        let commands = [
+                            ("Export chats:", "mix cursor_docs.chat --export-all"),
+                            ("Index docs:", "mix cursor_docs.add https://docs.rs/..."),
+                            ("Search:", "mix cursor_docs.search \"query\""),
+                        ];
+
+                        for (label, cmd) in commands {
+                            ui.horizontal(|ui| {
+                                ui.label(
+                                    RichText::new(label)
+                                        .size(theme.text_size(9.0))
+                                        .color(theme.fg_dim),
+                                );
+                            });
+                            ui.horizontal(|ui| {
+                                ui.add_space(8.0);
+                                ui.label(
+                                    RichText::new(cmd)
+                                        .size(theme.text_size(9.0))
+                                        .color(theme.accent)
+                                        .monospace(),
+                                );
+                            });
+                            ui.add_space(4.0);
+                        }
+                    });
+            });
+    }
+
+    /// Modes panel - Custom modes management (replaces Cursor's removed custom modes)
+    fn show_modes_panel(&mut self, ui: &mut egui::Ui, theme: Theme) {
+        // Render the modes panel
+        self.modes_panel.show(ui, &theme);
+        
+        // Handle any events from the panel
+        for event in self.modes_panel.take_events() {
+            match event {
+                modes::ModesPanelEvent::ModeActivated(name) => {
+                    self.set_status(&format!("Mode activated: {}", name));
+                }
+                modes::ModesPanelEvent::ModeUpdated(name) => {
+                    self.set_status(&format!("Mode saved: {}", name));
+                }
+                modes::ModesPanelEvent::ModeDeleted(name) => {
+                    self.set_status(&format!("Mode deleted: {}", name));
+                }
+                modes::ModesPanelEvent::ModeInjected {
+                    mode,
+                    target,
+                    paths,
+                } => {
+                    let written = paths
+                        .iter()
+                        .map(|p| p.display().to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    log::info!("Mode '{}' injected ({:?}): {}", mode, target, written);
+                    self.set_status(&format!("Mode '{}' injected → {}", mode, written));
+                }
+                modes::ModesPanelEvent::VanillaSwap {
+                    from_mode,
+                    restored,
+                } => {
+                    self.set_status(&format!(
+                        "Swapped from '{}' to vanilla ({} files restored)",
+                        from_mode,
+                        restored.len()
+                    ));
+                }
+                modes::ModesPanelEvent::ModeExported { mode, path } => {
+                    self.set_status(&format!("✓ Mode '{}' exported to {}", mode, path.display()));
+                    self.last_export = Some(LastExport::file(path));
+                }
+                modes::ModesPanelEvent::ModeImported(name) => {
+                    self.set_status(&format!("✓ Mode imported: {}", name));
+                }
+            }
+        }
+    }
+
+    /// Find the p2p-sync binary in common locations
+    fn find_p2p_binary(&self) -> Option<PathBuf> {
+        // Check common locations
+        let candidates = [
+            // Same directory as cursor-studio
+            std::env::current_exe().ok().and_then(|p| p.parent().map(|d| d.join("p2p-sync"))),
+            // Cargo target directory
+            std::env::current_exe().ok().and_then(|p| {
+                p.parent()
+                    .and_then(|d| d.parent())
+                    .and_then(|d| d.parent())
+                    .map(|ws| ws.join("target/release/p2p-sync"))
+            }),
+            // Home directory cargo install location
+            dirs::home_dir().map(|h| h.join(".cargo/bin/p2p-sync")),
+            // Current directory
+            Some(PathBuf::from("./target/release/p2p-sync")),
+        ];
+
+        for candidate in candidates.into_iter().flatten() {
+            if candidate.exists() {
+                log::info!("Found p2p-sync at: {:?}", candidate);
+                return Some(candidate);
+            }
+        }
+
+        // Try PATH lookup via `which`
+        if let Ok(output) = std::process::Command::new("which")
+            .arg("p2p-sync")
+            .output()
+        {
+            if output.status.success() {
+                let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                if !path.is_empty() {
+                    return Some(PathBuf::from(path));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Start the P2P sync daemon as a background process
+    fn start_p2p_daemon(&mut self) {
+        if self.p2p_daemon_running {
+            self.set_status("⚠️ P2P daemon already running");
+            return;
+        }
+
+        // Find the p2p-sync binary
+        let binary = match self.find_p2p_binary() {
+            Some(path) => path,
+            None => {
+                self.set_status("✗ p2p-sync binary not found. Build with: cargo build --release --bin p2p-sync");
+                log::error!("p2p-sync binary not found in any expected location");
+                return;
+            }
+        };
+
+        self.set_status(&format!("🚀 Starting P2P daemon on port {}...", self.p2p_daemon_port));
+
+        match std::process::Command::new(&binary)
+            .args(["--port", &self.p2p_daemon_port.to_string(), "--import"])
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn()
+        {
+            Ok(child) => {
+                self.p2p_daemon_process = Some(child);
+                self.p2p_daemon_running = true;
+                self.set_status(&format!("✓ P2P daemon started on port {}", self.p2p_daemon_port));
+            }
+            Err(e) => {
+                self.set_status(&format!("✗ Failed to start P2P daemon: {}", e));
+                log::error!("Failed to start P2P daemon: {}", e);
+            }
+        }
+    }
+
+    /// Stop the P2P sync daemon
+    fn stop_p2p_daemon(&mut self) {
+        if let Some(mut process) = self.p2p_daemon_process.take() {
+            match process.kill() {
+                Ok(()) => {
+                    self.p2p_daemon_running = false;
+                    self.sync_p2p_peers.clear();
+                    self.set_status("⏹ P2P daemon stopped");
+                }
+                Err(e) => {
+                    self.set_status(&format!("✗ Failed to stop P2P daemon: {}", e));
+                    log::error!("Failed to stop P2P daemon: {}", e);
+                    // Put the process back if we couldn't kill it
+                    self.p2p_daemon_process = Some(process);
+                }
+            }
+        } else {
+            self.p2p_daemon_running = false;
+            self.set_status("P2P daemon was not running");
+        }
+    }
+
+    #[cfg(feature = "surrealdb-store")]
+    fn check_server_status(&mut self) {
+        use chat::ClientConfig;
+        use chat::SyncClient;
+
+        let config = ClientConfig {
+            server_url: self.sync_server_url.clone(),
+            device_id: "cursor-studio".to_string(),
+        };
+        let client = SyncClient::new(config);
+
+        match client.health() {
+            Ok(health) => {
+                self.sync_server_connected = true;
+                self.sync_conversation_count = health.conversations;
+                self.sync_last_status = Some(format!(
+                    "Server v{} • {} conversations",
+                    health.version, health.conversations
+                ));
+                self.set_status(&format!("✓ Connected to {}", self.sync_server_url));
+            }
+            Err(e) => {
+                self.sync_server_connected = false;
+                self.sync_conversation_count = 0;
+                self.sync_last_status = Some(format!("Connection failed: {}", e));
+                self.set_status(&format!("✗ Failed to connect: {}", e));
+            }
+        }
+    }
+
+    #[cfg(not(feature = "surrealdb-store"))]
+    fn check_server_status(&mut self) {
+        self.set_status("⚠️ Server sync requires 'full' build");
+    }
+
+    #[cfg(feature = "surrealdb-store")]
+    fn pull_from_server(&mut self) {
+        use chat::ClientConfig;
+        use chat::SyncClient;
+
+        let config = ClientConfig {
+            server_url: self.sync_server_url.clone(),
+            device_id: "cursor-studio".to_string(),
+        };
+        let client = SyncClient::new(config);
+
+        match client.pull(Some(100)) {
+            Ok(conversations) => {
+                let count = conversations.len();
+                self.sync_last_status = Some(format!("Pulled {} conversations", count));
+                self.set_status(&format!("✓ Pulled {} conversations from server", count));
+                // TODO: Merge into local database
+            }
+            Err(e) => {
+                self.sync_last_status = Some(format!("Pull failed: {}", e));
+                self.set_status(&format!("✗ Pull failed: {}", e));
+            }
+        }
+    }
+
+    #[cfg(not(feature = "surrealdb-store"))]
+    fn pull_from_server(&mut self) {
+        self.set_status("⚠️ Server sync requires 'full' build");
+    }
+
+    fn show_editor_area(&mut self, ui: &mut egui::Ui, theme: Theme) {
+        let tabs = self.tabs.clone();
+        let convs = self.conversations.clone();
+        let active_tab = self.active_tab;
+
+        let mut new_active: Option<usize> = None;
+        let mut to_close: Option<usize> = None;
+        let mut to_pin: Option<usize> = None;
+        let mut to_move: Option<(usize, usize)> = None;
+        let mut to_split: Option<usize> = None;
+        let mut to_rename: Option<String> = None;
+        let mut finish_rename: Option<bool> = None;
+
+        // Tab bar
+        ui.horizontal(|ui| {
+            ui.add_space(4.0);
+
+            let mut tab_rects: Vec<egui::Rect> = Vec::with_capacity(tabs.len());
+            let mut dragging: Option<usize> = None;
+            let mut dropped: Option<usize> = None;
+
+            for (i, tab) in tabs.iter().enumerate() {
+                let is_pinned = self.pinned_tabs.contains(tab);
+                let is_active = i == active_tab;
+                let bg = if is_active {
+                    theme.tab_active_bg
+                } else {
+                    theme.tab_bg
+                };
+
+                let title = match tab {
+                    Tab::Dashboard => "🏠 Dashboard".to_string(),
+                    Tab::Conversation(id) => {
+                        let title_text = convs
+                            .iter()
+                            .find(|c| &c.id == id)
+                            .map(|c| c.title.chars().take(18).collect::<String>())
+                            .unwrap_or_else(|| "Chat".to_string());
+                        format!("💬 {}", title_text)
+                    }
+                    Tab::IndexedDoc(source_id) => {
+                        // Get source name from docs panel
+                        self.docs_panel.client.get_source(source_id)
+                            .ok()
+                            .flatten()
+                            .map(|s| format!("📖 {}", s.display_name().chars().take(15).collect::<String>()))
+                            .unwrap_or_else(|| "📖 Docs".to_string())
+                    }
+                };
+
+                let title = if is_pinned {
+                    format!("📌{}", title)
+                } else {
+                    title
+                };
+
+                // Use a selectable button for better click handling; the Dashboard stays put
+                let tab_size = Vec2::new(140.0, 28.0);
+                let sense = if matches!(tab, Tab::Dashboard) {
+                    egui::Sense::click()
+                } else {
+                    egui::Sense::click_and_drag()
+                };
+                let (rect, response) = ui.allocate_exact_size(tab_size, sense);
+                self.mark_focus_anchor(FocusRegion::Editor, &response);
+                tab_rects.push(rect);
+                if response.dragged() {
+                    dragging = Some(i);
+                }
+                if response.drag_stopped() {
+                    dropped = Some(i);
+                }
+
+                // Draw background
+                let hover_bg = if response.hovered() && !is_active {
+                    theme.tab_hover_bg
+                } else {
+                    bg
+                };
+                ui.painter().rect_filled(rect, Rounding::ZERO, hover_bg);
+
+                // Active indicator
+                if is_active {
+                    ui.painter()
+                        .hline(rect.x_range(), rect.top(), Stroke::new(2.0, theme.accent));
+                }
+
+                response.widget_info(|| {
+                    egui::WidgetInfo::selected(
+                        egui::WidgetType::SelectableLabel,
+                        true,
+                        is_active,
+                        &title,
+                    )
+                });
+                paint_focus_ring(ui, &response);
+
+                // Tab title, or the rename field while renaming
+                let draft = match (tab, self.title_editor.as_mut()) {
+                    (Tab::Conversation(id), Some((editing, draft)))
+                        if editing == id && self.title_editor_in_tab =>
+                    {
+                        Some(draft)
+                    }
+                    _ => None,
+                };
+                if let Some(draft) = draft {
+                    let edit_rect = egui::Rect::from_min_max(
+                        rect.left_top() + Vec2::new(6.0, 4.0),
+                        rect.right_bottom() - Vec2::new(26.0, 4.0),
+                    );
+                    finish_rename =
+                        title_editor_ui(ui, edit_rect, draft, &mut self.title_editor_focus_pending);
+                } else {
+                    let text_pos = rect.left_center() + Vec2::new(8.0, 0.0);
+                    ui.painter().text(
+                        text_pos,
+                        egui::Align2::LEFT_CENTER,
+                        &title,
+                        egui::FontId::proportional(12.0),
+                        if is_active { theme.fg } else { theme.fg_dim },
+                    );
+                }
+
+                // Close button (only for non-dashboard tabs)
+                if !matches!(tab, Tab::Dashboard) {
+                    let close_rect = egui::Rect::from_center_size(
+                        rect.right_center() - Vec2::new(14.0, 0.0),
+                        Vec2::splat(16.0),
+                    );
+                    let close_response =
+                        ui.interact(close_rect, ui.id().with(("close", i)), egui::Sense::click());
+
+                    let close_color = if close_response.hovered() {
+                        theme.error
+                    } else {
+                        theme.fg_dim
+                    };
+
+                    ui.painter().text(
+                        close_rect.center(),
+                        egui::Align2::CENTER_CENTER,
+                        "×",
+                        egui::FontId::proportional(14.0),
+                        close_color,
+                    );
+                    accessible_label(ui, &close_response, "Close tab");
+                    paint_focus_ring(ui, &close_response);
+
+                    if close_response.clicked() {
+                        to_close = Some(i);
+                    }
+                    if close_response.hovered() {
+                        ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+                    }
+                }
+
+                // Split button (conversations only, shown on hover)
+                if matches!(tab, Tab::Conversation(_)) {
+                    let split_rect = egui::Rect::from_center_size(
+                        rect.right_center() - Vec2::new(32.0, 0.0),
+                        Vec2::splat(16.0),
+                    );
+                    let split_response = ui
+                        .interact(split_rect, ui.id().with(("split", i)), egui::Sense::click())
+                        .on_hover_text("Open in split view");
+                    accessible_label(ui, &split_response, "Open in split view");
+                    paint_focus_ring(ui, &split_response);
+                    if response.hovered() || split_response.hovered() || split_response.has_focus()
+                    {
+                        let split_color = if split_response.hovered() {
+                            theme.accent
+                        } else {
+                            theme.fg_dim
+                        };
+                        ui.painter().text(
+                            split_rect.center(),
+                            egui::Align2::CENTER_CENTER,
+                            "◨",
+                            egui::FontId::proportional(12.0),
+                            split_color,
+                        );
+                    }
+                    if split_response.clicked() {
+                        to_split = Some(i);
+                    }
+                }
+
+                // Handle tab click (but not on close button area)
+                if response.clicked() && to_close.is_none() && to_split.is_none() {
+                    new_active = Some(i);
+                }
+                if let Tab::Conversation(id) = tab {
+                    if response.double_clicked() {
+                        to_rename = Some(id.clone());
+                    }
+                }
+
+                if response.hovered() {
+                    ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+                }
+
+                if !matches!(tab, Tab::Dashboard) {
+                    response.context_menu(|ui| {
+                        let pin_label = if is_pinned {
+                            "Unpin tab"
+                        } else {
+                            "📌 Pin tab"
+                        };
+                        if ui.button(pin_label).clicked() {
+                            to_pin = Some(i);
+                            ui.close_menu();
+                        }
+                        if matches!(tab, Tab::Conversation(_))
+                            && ui.button("◨ Open in split view").clicked()
+                        {
+                            to_split = Some(i);
+                            ui.close_menu();
+                        }
+                        if let Tab::Conversation(id) = tab {
+                            if ui.button("✏ Rename").clicked() {
+                                to_rename = Some(id.clone());
+                                ui.close_menu();
+                            }
+                        }
+                        if ui.button("Close tab").clicked() {
+                            to_close = Some(i);
+                            ui.close_menu();
+                        }
+                    });
+                }
+            }
+
+            // Drag-to-reorder: mark the drop position, move on release
+            if let Some(source) = dragging.or(dropped) {
+                let pointer_x = ui.ctx().pointer_interact_pos().map(|p| p.x);
+                let target = pointer_x.map(|x| {
+                    tab_rects
+                        .iter()
+                        .position(|r| x < r.right())
+                        .unwrap_or(tab_rects.len() - 1)
+                });
+                if let Some(target) = target.filter(|t| *t != source) {
+                    let rect = tab_rects[target];
+                    let x = if target < source {
+                        rect.left()
+                    } else {
+                        rect.right()
+                    };
+                    ui.painter()
+                        .vline(x, rect.y_range(), Stroke::new(2.0, theme.accent));
+                    if dropped.is_some() {
+                        to_move = Some((source, target));
+                    }
+                }
+                ui.ctx().set_cursor_icon(CursorIcon::Grabbing);
+            }
+        });
+
+        // Process tab changes
+        if let Some(i) = to_close {
+            self.close_tab(i);
+        }
+        if let Some(i) = to_pin {
+            self.toggle_tab_pin(i);
+        }
+        if let Some((from, target)) = to_move {
+            self.move_tab(from, target);
+        }
+        if let Some(i) = to_split {
+            self.split_tab(i);
+        }
+        if let Some(save) = finish_rename {
+            self.finish_rename(save);
+        }
+        if let Some(id) = to_rename {
+            self.begin_rename(&id, true);
+        }
+        if let Some(i) = new_active {
+            if i != self.active_tab {
+                self.active_tab = i;
+                // Auto-refresh data when switching tabs
+                self.on_tab_switch();
+            }
+        }
+
+        ui.add(egui::Separator::default().spacing(0.0));
+
+        // Show active tab content, next to the split pane when one is open
+        if self.split.is_some() {
+            ui.columns(2, |columns| {
+                self.show_active_tab(&mut columns[0], theme);
+                self.show_split_pane(&mut columns[1], theme);
+            });
+        } else {
+            self.show_active_tab(ui, theme);
+        }
+    }
+
+    fn show_active_tab(&mut self, ui: &mut egui::Ui, theme: Theme) {
+        if let Some(tab) = self.tabs.get(self.active_tab) {
+            match tab {
+                Tab::Dashboard => self.show_dashboard(ui, theme),
+                Tab::Conversation(id) => {
+                    let id = id.clone();
+                    self.show_conversation_tab(ui, theme, &id);
+                }
+                Tab::IndexedDoc(source_id) => {
+                    let source_id = source_id.clone();
+                    self.show_indexed_doc_tab(ui, theme, &source_id);
+                }
+            }
+        }
+    }
+
+    /// Move a conversation tab into the split pane; a conversation already
+    /// there goes back to the tab bar
+    fn split_tab(&mut self, index: usize) {
+        let Some(Tab::Conversation(conv_id)) = self.tabs.get(index).cloned() else {
+            return;
+        };
+        self.close_tab(index);
+        if let Some(previous) = self.split.take() {
+            self.tabs.push(Tab::Conversation(previous.conv_id));
+        }
+        self.split = Some(SplitPane {
+            conv_id,
+            ..Default::default()
+        });
+    }
+
+    /// Swap the main conversation state with the split pane's
+    fn swap_split_state(&mut self, pane: &mut SplitPane) {
+        std::mem::swap(&mut self.current_messages, &mut pane.messages);
+        std::mem::swap(&mut self.current_bookmarks, &mut pane.bookmarks);
+        std::mem::swap(
+            &mut self.scroll_to_message_id,
+            &mut pane.scroll_to_message_id,
+        );
+        std::mem::swap(&mut self.conv_search_query, &mut pane.search_query);
+        std::mem::swap(&mut self.conv_search_results, &mut pane.search_results);
+        std::mem::swap(&mut self.conv_search_index, &mut pane.search_index);
+        std::mem::swap(&mut self.goto_message_input, &mut pane.goto_input);
+    }
+
+    /// Right-hand pane of the split view, rendered with its own messages and scroll
+    fn show_split_pane(&mut self, ui: &mut egui::Ui, theme: Theme) {
+        let Some(mut pane) = self.split.take() else {
+            return;
+        };
+
+        let mut close = false;
+        let mut to_tab = false;
+        ui.horizontal(|ui| {
+            ui.label(
+                RichText::new("◨ SPLIT VIEW")
+                    .size(theme.text_size(10.0))
+                    .color(theme.fg_dim)
+                    .strong(),
+            );
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui
+                    .small_button("✕")
+                    .on_hover_text("Close split view")
+                    .clicked()
+                {
+                    close = true;
+                }
+                if ui
+                    .small_button("⇤")
+                    .on_hover_text("Move back to tabs")
+                    .clicked()
+                {
+                    to_tab = true;
+                }
+            });
+        });
+
+        let conv_id = pane.conv_id.clone();
+        self.swap_split_state(&mut pane);
+        self.show_conversation_tab(ui, theme, &conv_id);
+        self.swap_split_state(&mut pane);
+
+        if to_tab {
+            self.open_conversation(&conv_id);
+        } else if !close {
+            self.split = Some(pane);
+        }
+    }
+
+    /// Show indexed documentation source in a tab
+    fn show_indexed_doc_tab(&mut self, ui: &mut egui::Ui, theme: Theme, source_id: &str) {
+        // Get source details from docs_panel
+        let source = self.docs_panel.client.get_source(source_id);
+        let mut export_format: Option<docs::DocExportFormat> = None;
+        let mut retry_source: Option<docs::DocSource> = None;
+        let mut refresh_status = false;
+
+        match source {
+            Ok(Some(source)) => {
+                let failure = self.docs_panel.failure_reason(&source);
+                let is_indexing = self.docs_panel.is_indexing(&source.url);
+                let density = self.density;
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        ui.add_space(16.0);
+                        
+                        // Header
+                        ui.horizontal(|ui| {
+                            ui.add_space(16.0);
+                            ui.label(
+                                RichText::new(format!("📖 {}", source.display_name()))
+                                    .size(18.0)
+                                    .color(theme.fg)
+                                    .strong(),
+                            );
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    ui.add_space(16.0);
+                                    let formats = [
+                                        docs::DocExportFormat::Json,
+                                        docs::DocExportFormat::Markdown,
+                                    ];
+                                    for format in formats {
+                                        if ui
+                                            .small_button(format!("📤 {}", format.label()))
+                                            .on_hover_text(format!(
+                                                "Export source: write all indexed chunks as {}",
+                                                format.label()
+                                            ))
+                                            .clicked()
+                                        {
+                                            export_format = Some(format);
+                                        }
+                                    }
+                                },
+                            );
+                        });
+                        
+                        ui.add_space(8.0);
+                        
+                        // Source info
+                        ui.horizontal(|ui| {
+                            ui.add_space(16.0);
+                            ui.label(
+                                RichText::new(&source.url)
+                                    .size(theme.text_size(11.0))
+                                    .color(theme.fg_dim),
+                            );
+                        });
+                        
+                        ui.horizontal(|ui| {
+                            ui.add_space(16.0);
+                            ui.label(
+                                RichText::new(format!(
+                                    "{} {} • {} chunks",
+                                    source.status.icon(),
+                                    source.status.label(),
+                                    source.chunks_count
+                                ))
+                                .size(theme.text_size(11.0))
+                                .color(theme.fg_dim),
+                            );
+                        });
+                        
+                        if let Some(ref last) = source.last_indexed {
                             ui.horizontal(|ui| {
-                                ui.add_space(8.0);
+                                ui.add_space(16.0);
                                 ui.label(
-                                    RichText::new(format!("• {}: {}", name, count))
-                                        .color(theme.fg_dim)
-                                        .size(9.0),
+                                    RichText::new(format!("Last indexed: {}", last))
+                                        .size(theme.text_size(10.0))
+                                        .color(theme.fg_dim),
                                 );
                             });
                         }
-                    });
-                ui.add_space(8.0);
-
-                // NPM scan path input
-                ui.horizontal(|ui| {
-                    ui.add_space(16.0);
-                    ui.label(RichText::new("Scan Path:").color(theme.fg).size(11.0));
-                });
-                ui.horizontal(|ui| {
-                    ui.add_space(16.0);
-                    ui.add(
-                        egui::TextEdit::singleline(&mut self.npm_scan_path)
-                            .desired_width(ui.available_width() - 80.0)
-                            .hint_text("/path/to/project")
-                            .font(egui::FontId::monospace(11.0)),
-                    );
-                });
-                ui.add_space(8.0);
-
-                ui.horizontal(|ui| {
-                    ui.add_space(16.0);
-                    
-                    // Show scan in progress indicator or button
-                    if let Some(ref progress_msg) = self.npm_scan_progress {
-                        // Scan in progress - show spinner/message
-                        ui.spinner();
-                        ui.label(RichText::new(progress_msg).size(11.0).color(theme.fg_dim));
-                    } else {
-                        // No scan running - show button
-                        if styled_button(ui, "🔍 Scan for Malicious Packages", Vec2::new(200.0, 32.0))
-                            .on_hover_text("Scan package.json files for known malicious packages")
-                            .clicked()
-                        {
-                            self.scan_npm_packages();
-                        }
-                    }
-                });
 
-                // Show NPM scan results if available
-                if self.show_npm_scan_results {
-                    if let Some(ref results) = self.npm_scan_results {
-                        ui.add_space(8.0);
-                        egui::Frame::none()
-                            .fill(theme.code_bg)
-                            .rounding(Rounding::same(8.0))
-                            .inner_margin(egui::Margin::same(12.0))
-                            .show(ui, |ui| {
-                                if results.is_empty() {
+                        if let Some(reason) = &failure {
+                            ui.add_space(8.0);
+                            warning_card_frame(theme, density)
+                                .outer_margin(egui::Margin::symmetric(16.0, 0.0))
+                                .show(ui, |ui| {
                                     ui.label(
-                                        RichText::new("✓ No blocked packages found")
-                                            .color(theme.success)
-                                            .size(11.0),
+                                        RichText::new("⚠ Indexing failed")
+                                            .size(12.0)
+                                            .color(theme.error)
+                                            .strong(),
                                     );
-                                } else {
                                     ui.label(
-                                        RichText::new(format!(
-                                            "⚠️ Found issues in {} files:",
-                                            results.len()
-                                        ))
-                                        .color(theme.error)
-                                        .strong()
-                                        .size(11.0),
+                                        RichText::new(reason)
+                                            .size(theme.text_size(10.0))
+                                            .color(theme.fg_dim)
+                                            .monospace(),
                                     );
-                                    ui.add_space(4.0);
-
-                                    for (path, packages) in results.iter().take(10) {
+                                    ui.add_space(6.0);
+                                    let button_size = Vec2::new(120.0, 24.0);
+                                    ui.horizontal(|ui| {
+                                        if is_indexing {
+                                            ui.add(egui::Spinner::new().size(12.0));
+                                            ui.label(
+                                                RichText::new("Indexing...")
+                                                    .size(theme.text_size(10.0))
+                                                    .color(theme.fg_dim),
+                                            );
+                                        } else if styled_button(ui, "↻ Retry indexing", button_size)
+                                            .clicked()
+                                        {
+                                            retry_source = Some(source.clone());
+                                        }
+                                        if styled_button(ui, "🔄 Refresh status", button_size)
+                                            .clicked()
+                                        {
+                                            refresh_status = true;
+                                        }
+                                    });
+                                });
+                        }
+                        
+                        ui.add_space(16.0);
+                        ui.add(egui::Separator::default());
+                        ui.add_space(12.0);
+                        
+                        // Chunks section
+                        ui.horizontal(|ui| {
+                            ui.add_space(16.0);
+                            ui.label(
+                                RichText::new("INDEXED CONTENT")
+                                    .size(theme.text_size(11.0))
+                                    .color(theme.fg_dim)
+                                    .strong(),
+                            );
+                        });
+                        ui.add_space(8.0);
+                        
+                        // Load and display chunks (far enough to include a search hit)
+                        let limit = self
+                            .doc_scroll_target
+                            .as_ref()
+                            .map_or(100, |(_, position)| (position + 1).max(100));
+                        match self.docs_panel.client.get_chunks(source_id, limit) {
+                            Ok(chunks) => {
+                                if chunks.is_empty() {
+                                    ui.horizontal(|ui| {
+                                        ui.add_space(16.0);
                                         ui.label(
-                                            RichText::new(path.to_string_lossy())
-                                                .color(theme.fg)
-                                                .size(10.0)
-                                                .family(egui::FontFamily::Monospace),
+                                            RichText::new("No content indexed yet")
+                                                .size(theme.text_size(11.0))
+                                                .color(theme.fg_dim),
                                         );
-                                        for pkg in packages {
-                                            ui.horizontal(|ui| {
-                                                ui.add_space(16.0);
-                                                ui.label(
-                                                    RichText::new(format!(
-                                                        "🚫 {} - {}",
-                                                        pkg.package_name,
-                                                        pkg.block_reason
-                                                            .as_deref()
-                                                            .unwrap_or("blocked")
-                                                    ))
-                                                    .color(theme.error)
-                                                    .size(9.0),
-                                                );
-                                                if let Some(cve) = &pkg.cve {
+                                    });
+                                } else {
+                                    for (i, chunk) in chunks.iter().enumerate() {
+                                        let is_target = self
+                                            .doc_scroll_target
+                                            .as_ref()
+                                            .is_some_and(|(id, _)| id == &chunk.id);
+                                        if is_target {
+                                            ui.scroll_to_cursor(Some(egui::Align::Center));
+                                            self.doc_scroll_target = None;
+                                        }
+                                        egui::Frame::none()
+                                            .fill(theme.code_bg)
+                                            .stroke(if is_target {
+                                                Stroke::new(1.0, theme.accent)
+                                            } else {
+                                                Stroke::NONE
+                                            })
+                                            .rounding(Rounding::same(4.0))
+                                            .inner_margin(egui::Margin::same(12.0))
+                                            .outer_margin(egui::Margin::symmetric(16.0, 4.0))
+                                            .show(ui, |ui| {
+                                                ui.horizontal(|ui| {
                                                     ui.label(
-                                                        RichText::new(format!("[{}]", cve))
-                                                            .color(theme.warning)
-                                                            .size(9.0),
+                                                        RichText::new(format!("#{}", i + 1))
+                                                            .size(theme.text_size(10.0))
+                                                            .color(theme.fg_dim),
                                                     );
-                                                }
+                                                    ui.label(
+                                                        RichText::new(&chunk.title)
+                                                            .size(theme.text_size(11.0))
+                                                            .color(theme.fg)
+                                                            .strong(),
+                                                    );
+                                                });
+                                                ui.add_space(4.0);
+                                                
+                                                // Show preview of content (first 300 chars)
+                                                let preview = if chunk.content.len() > 300 {
+                                                    format!("{}...", &chunk.content[..300])
+                                                } else {
+                                                    chunk.content.clone()
+                                                };
+                                                ui.label(
+                                                    RichText::new(preview)
+                                                        .size(theme.text_size(10.0))
+                                                        .color(theme.fg_dim),
+                                                );
                                             });
-                                        }
+                                    }
+                                    
+                                    if chunks.len() >= limit {
+                                        ui.horizontal(|ui| {
+                                            ui.add_space(16.0);
+                                            ui.label(
+                                                RichText::new(format!(
+                                                    "Showing first {} chunks...",
+                                                    limit
+                                                ))
+                                                .size(theme.text_size(10.0))
+                                                .color(theme.fg_dim)
+                                                .italics(),
+                                            );
+                                        });
                                     }
                                 }
-                            });
-                    }
-                }
-
-                ui.add_space(16.0);
-
-                // Audit Log Section
-                ui.horizontal(|ui| {
-                    ui.add_space(16.0);
-                    ui.label(
-                        RichText::new("AUDIT LOG")
-                            .size(11.0)
-                            .color(theme.fg_dim)
-                            .strong(),
-                    );
-                });
-                ui.add_space(8.0);
-
-                egui::Frame::none()
-                    .fill(theme.code_bg)
-                    .rounding(Rounding::same(8.0))
-                    .inner_margin(egui::Margin::same(12.0))
-                    .show(ui, |ui| {
-                        ui.label(
-                            RichText::new("Recent Activity")
-                                .color(theme.fg)
-                                .strong()
-                                .size(11.0),
-                        );
-                        ui.add_space(4.0);
-
-                        // Show dynamic events based on actual state
-                        ui.horizontal(|ui| {
-                            ui.label(RichText::new("✓").color(theme.success).size(10.0));
-                            ui.add_space(4.0);
-                            ui.label(RichText::new("Database loaded").color(theme.fg_dim).size(10.0));
-                        });
-
-                        // Show last security scan if available
-                        if let Some(ref results) = self.security_scan_results {
-                            let total_found = results.potential_api_keys.len()
-                                + results.potential_passwords.len()
-                                + results.potential_secrets.len();
-                            let (icon, color) = if total_found > 0 {
-                                ("⚠", theme.warning)
-                            } else {
-                                ("✓", theme.success)
-                            };
-                            ui.horizontal(|ui| {
-                                ui.label(RichText::new(icon).color(color).size(10.0));
-                                ui.add_space(4.0);
-                                ui.label(RichText::new(format!(
-                                    "Security scan: {} ({})",
-                                    results.scanned_at,
-                                    if total_found > 0 { format!("{} findings", total_found) } else { "clean".to_string() }
-                                )).color(theme.fg_dim).size(10.0));
-                            });
-                        }
-
-                        // Show chat import status
-                        let chat_count = self.conversations.len();
-                        if chat_count > 0 {
-                            ui.horizontal(|ui| {
-                                ui.label(RichText::new("✓").color(theme.success).size(10.0));
-                                ui.add_space(4.0);
-                                ui.label(RichText::new(format!(
-                                    "{} conversations imported",
-                                    chat_count
-                                )).color(theme.fg_dim).size(10.0));
-                            });
-                        } else {
-                            ui.horizontal(|ui| {
-                                ui.label(RichText::new("○").color(theme.fg_dim).size(10.0));
-                                ui.add_space(4.0);
-                                ui.label(RichText::new("No conversations imported yet").color(theme.fg_dim).size(10.0));
-                            });
+                            }
+                            Err(e) => {
+                                ui.horizontal(|ui| {
+                                    ui.add_space(16.0);
+                                    ui.label(
+                                        RichText::new(format!("Failed to load chunks: {}", e))
+                                            .size(theme.text_size(11.0))
+                                            .color(theme.error),
+                                    );
+                                });
+                            }
                         }
                     });
-                ui.add_space(16.0);
-
-                // Settings Section
-                ui.horizontal(|ui| {
-                    ui.add_space(16.0);
+            }
+            Ok(None) => {
+                ui.centered_and_justified(|ui| {
                     ui.label(
-                        RichText::new("SECURITY SETTINGS")
-                            .size(11.0)
-                            .color(theme.fg_dim)
-                            .strong(),
+                        RichText::new("Source not found")
+                            .size(14.0)
+                            .color(theme.fg_dim),
                     );
                 });
-                ui.add_space(8.0);
+            }
+            Err(e) => {
+                ui.centered_and_justified(|ui| {
+                    ui.label(
+                        RichText::new(format!("Error loading source: {}", e))
+                            .size(14.0)
+                            .color(theme.error),
+                    );
+                });
+            }
+        }
 
-                // Current features with status
-                let current_features = [
-                    ("✓", "Sensitive data detection (scan above)", theme.success),
-                    ("✓", "NPM malicious package scanning", theme.success),
-                    ("✓", "Jump-to-message navigation", theme.success),
-                    ("○", "Encrypted local storage", theme.fg_dim),
-                    ("○", "Auto-redaction in exports", theme.fg_dim),
-                    ("○", "Session timeout settings", theme.fg_dim),
-                    ("○", "Audit log export", theme.fg_dim),
-                ];
+        if let Some(source) = retry_source {
+            self.docs_panel.retry_indexing(&source);
+            self.set_status(&format!(
+                "⏳ Retrying indexing of {}",
+                source.display_name()
+            ));
+        }
+        if refresh_status {
+            self.docs_panel.refresh();
+            self.set_status("✓ Index status refreshed");
+        }
 
-                for (icon, feature, color) in current_features {
-                    ui.horizontal(|ui| {
-                        ui.add_space(20.0);
-                        ui.label(RichText::new(icon).color(color).size(10.0));
-                        ui.add_space(4.0);
-                        ui.label(RichText::new(feature).color(color).size(10.0));
-                    });
+        if let Some(format) = export_format {
+            let dir = markdown_export_dir();
+            match docs::export_source(&self.docs_panel.client, source_id, format, &dir) {
+                Ok((path, count)) => {
+                    self.set_status(&format!(
+                        "✓ Exported {} chunks to {}",
+                        count,
+                        path.display()
+                    ));
+                    self.last_export = Some(LastExport::file(path));
                 }
+                Err(e) => self.set_status(&format!("✗ Export failed: {}", e)),
+            }
+        }
+    }
 
-                ui.add_space(16.0);
-
-                // Development status note
-                egui::Frame::none()
-                    .fill(theme.input_bg)
-                    .rounding(Rounding::same(6.0))
-                    .inner_margin(egui::Margin::same(10.0))
-                    .show(ui, |ui| {
-                        ui.horizontal(|ui| {
-                            ui.label(RichText::new("🔧").size(12.0));
-                            ui.add_space(4.0);
-                            ui.label(
-                                RichText::new("Security features under active development")
-                                    .color(theme.fg_dim)
-                                    .size(10.0)
-                                    .italics(),
-                            );
-                        });
-                    });
+    fn show_dashboard(&mut self, ui: &mut egui::Ui, theme: Theme) {
+        let (total, messages, favorites) = self
+            .db
+            .get_stats(!self.stats_exclude_archived)
+            .unwrap_or((0, 0, 0));
 
-                ui.add_space(20.0);
-            });
-    }
+        let mut do_import = false;
+        let mut do_launch = false;
 
-    fn show_bridge_panel(&mut self, ui: &mut egui::Ui, theme: Theme) {
         egui::ScrollArea::vertical().show(ui, |ui| {
-            ui.add_space(SECTION_SPACING);
+            ui.vertical_centered(|ui| {
+                ui.add_space(ui.available_height() / 6.0);
 
-            // ======================================
-            // ELIXIR SYNC DAEMON (Primary)
-            // ======================================
-            card_frame(theme).show(ui, |ui| {
-                // Render the Elixir sync daemon panel
-                self.sync_daemon_panel.ui(ui);
-            });
-            
-            ui.add_space(SECTION_SPACING);
-            ui.separator();
-            ui.add_space(SECTION_SPACING);
+                ui.label(RichText::new("CURSOR").size(42.0).color(theme.fg).strong());
+                ui.label(
+                    RichText::new("STUDIO")
+                        .size(42.0)
+                        .color(theme.accent)
+                        .strong(),
+                );
+                ui.add_space(8.0);
+                ui.label(
+                    RichText::new("Open Source Cursor IDE Manager")
+                        .size(14.0)
+                        .color(theme.fg_dim),
+                );
+                ui.add_space(4.0);
+                ui.label(
+                    RichText::new("v0.2.0-rc1")
+                        .size(theme.text_size(11.0))
+                        .color(theme.accent.linear_multiply(0.7)),
+                );
 
-            // ======================================
-            // LEGACY: Coming Soon Banner
-            // ======================================
-            accent_card_frame(theme).show(ui, |ui| {
-                ui.horizontal(|ui| {
-                    ui.label(RichText::new("🔮").size(14.0));
-                    ui.add_space(ELEMENT_SPACING);
-                    ui.vertical(|ui| {
+                ui.add_space(24.0);
+
+                // Stats - use columns for reliable layout
+                let version_count = self.versions.len();
+                ui.columns(4, |cols| {
+                    // Chats
+                    cols[0].vertical_centered(|ui| {
                         ui.label(
-                            RichText::new("Future: P2P & Server Sync")
+                            RichText::new(format!("{}", total))
+                                .size(28.0)
                                 .color(theme.accent)
-                                .strong()
-                                .size(12.0),
+                                .strong(),
                         );
                         ui.label(
-                            RichText::new("Multi-device sync planned for v0.4.0")
-                                .color(theme.fg_dim)
-                                .size(10.0),
+                            RichText::new("Chats")
+                                .size(theme.text_size(11.0))
+                                .color(theme.fg_dim),
                         );
                     });
-                });
-            });
-            ui.add_space(SECTION_SPACING);
-
-            panel_header(ui, "DEVICE INFO", theme);
-            ui.add_space(ELEMENT_SPACING);
 
-            // Device Info Card
-            card_frame(theme).show(ui, |ui| {
-                ui.horizontal(|ui| {
-                    ui.label(RichText::new("📱").size(16.0));
-                    ui.add_space(ELEMENT_SPACING);
-                    ui.vertical(|ui| {
+                    // Messages
+                    cols[1].vertical_centered(|ui| {
                         ui.label(
-                            RichText::new("This Device")
+                            RichText::new(format!("{}", messages))
+                                .size(28.0)
                                 .color(theme.fg)
-                                .strong()
-                                .size(11.0),
+                                .strong(),
+                        );
+                        ui.label(
+                            RichText::new("Messages")
+                                .size(theme.text_size(11.0))
+                                .color(theme.fg_dim),
                         );
-                        // Get hostname
-                        let hostname = hostname::get()
-                            .map(|h| h.to_string_lossy().to_string())
-                            .unwrap_or_else(|_| "unknown".to_string());
-                        ui.label(RichText::new(&hostname).color(theme.fg_dim).size(10.0));
                     });
-                });
-            });
-            ui.add_space(SECTION_SPACING);
-
-            // Server Connection
-            ui.add_space(ELEMENT_SPACING);
-
-            // Server URL input
-            ui.horizontal(|ui| {
-                ui.add_space(16.0);
-                ui.label(RichText::new("Server URL:").color(theme.fg).size(12.0));
-            });
-            ui.horizontal(|ui| {
-                ui.add_space(16.0);
-                let response = ui.add(
-                    egui::TextEdit::singleline(&mut self.sync_server_url)
-                        .desired_width(ui.available_width() - 32.0)
-                        .margin(egui::Margin::symmetric(8.0, 6.0)),
-                );
-                if response.changed() {
-                    self.sync_server_connected = false;
-                }
-            });
-            ui.add_space(8.0);
-
-            // Connection status
-            let status_color = if self.sync_server_connected {
-                theme.success
-            } else {
-                theme.fg_dim
-            };
-            let status_text = if self.sync_server_connected {
-                format!(
-                    "✓ Connected ({} conversations)",
-                    self.sync_conversation_count
-                )
-            } else {
-                "○ Not connected".to_string()
-            };
-            ui.horizontal(|ui| {
-                ui.add_space(16.0);
-                ui.label(RichText::new(&status_text).color(status_color).size(11.0));
-            });
-            ui.add_space(8.0);
-
-            // Server actions
-            ui.horizontal(|ui| {
-                ui.add_space(16.0);
-                if styled_button(ui, "🔌 Connect", Vec2::new(100.0, 28.0)).clicked() {
-                    self.check_server_status();
-                }
-                ui.add_space(4.0);
-                if self.sync_server_connected {
-                    if styled_button(ui, "📥 Pull", Vec2::new(80.0, 28.0))
-                        .on_hover_text("Pull conversations from server")
-                        .clicked()
-                    {
-                        self.pull_from_server();
-                    }
-                }
-            });
-
-            // Last status message
-            if let Some(ref status) = self.sync_last_status {
-                ui.add_space(4.0);
-                ui.horizontal(|ui| {
-                    ui.add_space(16.0);
-                    ui.label(RichText::new(status).color(theme.fg_dim).size(10.0));
-                });
-            }
-
-            ui.add_space(16.0);
-
-            // P2P Section
-            ui.horizontal(|ui| {
-                ui.add_space(16.0);
-                ui.label(
-                    RichText::new("PEER-TO-PEER")
-                        .size(11.0)
-                        .color(theme.fg_dim)
-                        .strong(),
-                );
-            });
-            ui.add_space(8.0);
 
-            // P2P Daemon Controls
-            egui::Frame::none()
-                .fill(theme.code_bg)
-                .rounding(Rounding::same(8.0))
-                .inner_margin(egui::Margin::same(12.0))
-                .show(ui, |ui| {
-                    ui.horizontal(|ui| {
-                        ui.label(RichText::new("🔗").size(16.0));
-                        ui.add_space(8.0);
-                        ui.vertical(|ui| {
-                            ui.label(
-                                RichText::new("P2P Daemon")
-                                    .color(theme.fg)
-                                    .strong()
-                                    .size(12.0),
-                            );
-                            let status_text = if self.p2p_daemon_running {
-                                format!("Running on port {}", self.p2p_daemon_port)
-                            } else {
-                                "Stopped".to_string()
-                            };
-                            let status_color = if self.p2p_daemon_running {
-                                theme.success
-                            } else {
-                                theme.fg_dim
-                            };
-                            ui.label(RichText::new(&status_text).color(status_color).size(11.0));
-                        });
+                    // Favorites
+                    cols[2].vertical_centered(|ui| {
+                        ui.label(
+                            RichText::new(format!("{}", favorites))
+                                .size(28.0)
+                                .color(Color32::from_rgb(255, 215, 0))
+                                .strong(),
+                        );
+                        ui.label(
+                            RichText::new("Favorites")
+                                .size(theme.text_size(11.0))
+                                .color(theme.fg_dim),
+                        );
                     });
-                    ui.add_space(8.0);
-                    
-                    ui.horizontal(|ui| {
-                        if self.p2p_daemon_running {
-                            if styled_button(ui, "⏹ Stop", Vec2::new(80.0, 28.0))
-                                .on_hover_text("Stop P2P daemon")
-                                .clicked()
-                            {
-                                self.stop_p2p_daemon();
-                            }
-                        } else {
-                            if styled_button(ui, "▶ Start", Vec2::new(80.0, 28.0))
-                                .on_hover_text("Start P2P daemon for local network discovery")
-                                .clicked()
-                            {
-                                self.start_p2p_daemon();
-                            }
-                        }
-                        ui.add_space(8.0);
-                        ui.label(RichText::new("Port:").color(theme.fg_dim).size(11.0));
-                        ui.add_space(4.0);
-                        let port_str = &mut format!("{}", self.p2p_daemon_port);
-                        let response = ui.add(
-                            egui::TextEdit::singleline(port_str)
-                                .desired_width(60.0)
-                                .font(egui::FontId::monospace(11.0)),
+
+                    // Versions
+                    cols[3].vertical_centered(|ui| {
+                        ui.label(
+                            RichText::new(format!("{}", version_count))
+                                .size(28.0)
+                                .color(theme.success)
+                                .strong(),
+                        );
+                        ui.label(
+                            RichText::new("Versions")
+                                .size(theme.text_size(11.0))
+                                .color(theme.fg_dim),
                         );
-                        if response.changed() {
-                            if let Ok(port) = port_str.parse::<u16>() {
-                                self.p2p_daemon_port = port;
-                            }
-                        }
                     });
                 });
-            ui.add_space(8.0);
 
-            // Discovered Peers
-            egui::Frame::none()
-                .fill(theme.code_bg)
-                .rounding(Rounding::same(8.0))
-                .inner_margin(egui::Margin::same(12.0))
-                .show(ui, |ui| {
-                    ui.label(
-                        RichText::new("📡 Discovered Peers")
-                            .color(theme.fg)
-                            .size(12.0),
-                    );
-                    ui.add_space(4.0);
+                // Show current launch version
+                ui.add_space(16.0);
+                let launch_display = Self::version_display_name(&self.launch_version);
+                ui.label(
+                    RichText::new(format!("🚀 {}", launch_display))
+                        .size(12.0)
+                        .color(theme.accent),
+                );
 
-                    if self.sync_p2p_peers.is_empty() {
-                        ui.label(
-                            RichText::new("No peers discovered yet")
-                                .color(theme.fg_dim)
-                                .size(11.0)
-                                .italics(),
-                        );
-                        if self.p2p_daemon_running {
-                            ui.add_space(4.0);
-                            ui.label(
-                                RichText::new("Waiting for peers on local network...")
-                                    .color(theme.fg_dim)
-                                    .size(10.0),
-                            );
-                        } else {
-                            ui.add_space(4.0);
-                            ui.label(
-                                RichText::new("Start daemon to discover peers")
-                                    .color(theme.fg_dim)
-                                    .size(10.0),
-                            );
-                        }
-                    } else {
-                        for peer in &self.sync_p2p_peers {
+                ui.add_space(32.0);
+
+                // Buttons - use columns for proper centering
+                ui.columns(3, |cols| {
+                    // Import button
+                    cols[0].vertical_centered(|ui| {
+                        if self.import_in_progress {
                             ui.horizontal(|ui| {
-                                ui.label(RichText::new("• ").color(theme.accent));
-                                ui.label(RichText::new(peer).color(theme.fg).size(11.0));
+                                ui.add(egui::Spinner::new().size(16.0));
+                                ui.label(RichText::new("...").color(theme.accent));
                             });
+                            ui.ctx().request_repaint();
+                        } else if styled_button_accent(
+                            ui,
+                            "⬇ Import",
+                            Vec2::new(100.0, 32.0),
+                            theme,
+                        )
+                        .clicked()
+                        {
+                            if !self.import_warning_shown {
+                                self.import_warning_shown = true;
+                                self.set_status("⚠️ Click again to confirm");
+                            } else {
+                                do_import = true;
+                                self.import_warning_shown = false;
+                            }
                         }
-                    }
-                });
+                    });
 
-            ui.add_space(16.0);
+                    // Reimport button
+                    cols[1].vertical_centered(|ui| {
+                        if styled_button(ui, "🔄 Reimport", Vec2::new(100.0, 32.0)).clicked() {
+                            self.do_clear_and_reimport();
+                        }
+                    });
 
-            // Quick Actions
-            ui.horizontal(|ui| {
-                ui.add_space(16.0);
-                ui.label(
-                    RichText::new("QUICK ACTIONS")
-                        .size(11.0)
-                        .color(theme.fg_dim)
-                        .strong(),
-                );
-            });
-            ui.add_space(8.0);
+                    // Launch button
+                    cols[2].vertical_centered(|ui| {
+                        if styled_button_accent(ui, "▶ Launch", Vec2::new(100.0, 32.0), theme)
+                            .clicked()
+                        {
+                            do_launch = true;
+                        }
+                    });
+                });
 
-            ui.horizontal(|ui| {
-                ui.add_space(16.0);
-                if styled_button(ui, "📤 Export All", Vec2::new(100.0, 28.0))
-                    .on_hover_text("Export all conversations to JSON")
-                    .clicked()
-                {
-                    self.set_status("Export coming soon...");
+                // Warning hint
+                if self.import_warning_shown {
+                    ui.vertical_centered(|ui| {
+                        ui.label(
+                            RichText::new("⚠️ Click Import again to confirm")
+                                .color(theme.warning)
+                                .size(theme.text_size(10.0)),
+                        );
+                    });
                 }
+
+                ui.add_space(24.0);
+
+                // Quick tips - centered
+                ui.vertical_centered(|ui| {
+                    ui.label(
+                        RichText::new("Quick Tips")
+                            .size(theme.text_size(11.0))
+                            .color(theme.fg_dim)
+                            .strong(),
+                    );
+                    ui.add_space(4.0);
+                    ui.label(
+                        RichText::new("Sidebar: Versions • Search • Settings")
+                            .size(theme.text_size(10.0))
+                            .color(theme.fg_dim),
+                    );
+                    ui.label(
+                        RichText::new("Click version → set default • Drag edges → resize")
+                            .size(theme.text_size(10.0))
+                            .color(theme.fg_dim),
+                    );
+                });
+
+                ui.add_space(24.0);
+                let width = ui.available_width().min(560.0);
+                ui.allocate_ui_with_layout(
+                    Vec2::new(width, 0.0),
+                    egui::Layout::top_down(egui::Align::Min),
+                    |ui| self.show_activity_card(ui, theme),
+                );
+                ui.add_space(24.0);
             });
         });
-    }
 
-    /// Show the Index (Documentation) panel
-    /// Integrates with cursor-docs backend for doc indexing and search
-    fn show_index_panel(&mut self, ui: &mut egui::Ui, theme: Theme) {
-        // Create a theme adapter for the docs panel
-        struct ThemeAdapter(Theme);
-        impl docs::DocsTheme for ThemeAdapter {
-            fn bg(&self) -> Color32 { self.0.sidebar_bg }
-            fn fg(&self) -> Color32 { self.0.fg }
-            fn fg_dim(&self) -> Color32 { self.0.fg_dim }
-            fn accent(&self) -> Color32 { self.0.accent }
-            fn error(&self) -> Color32 { self.0.error }
-            fn success(&self) -> Color32 { self.0.success }
-            fn card_bg(&self) -> Color32 { self.0.code_bg }
-            fn button_bg(&self) -> Color32 { self.0.input_bg }
-            fn selection_bg(&self) -> Color32 { self.0.accent.gamma_multiply(0.3) }
+        if do_import {
+            self.do_import();
         }
 
-        // Show the panel (no ScrollArea - panel handles its own scrolling)
-        self.docs_panel.show(ui, &ThemeAdapter(theme));
-
-        // Handle events from the docs panel
-        for event in self.docs_panel.take_events() {
-            match event {
-                docs::DocsPanelEvent::OpenSource { source_id, source_name } => {
-                    // Open source in a new tab
-                    self.open_indexed_source(&source_id, &source_name);
-                }
-                docs::DocsPanelEvent::StatusMessage(msg) => {
-                    self.set_status(&msg);
-                }
-            }
+        if do_launch {
+            self.launch_cursor();
         }
     }
 
-    /// Open an indexed source in the editor area as a tab
-    fn open_indexed_source(&mut self, source_id: &str, source_name: &str) {
-        // Check if already open
-        for (i, tab) in self.tabs.iter().enumerate() {
-            if let Tab::IndexedDoc(id) = tab {
-                if id == source_id {
-                    self.active_tab = i;
-                    self.set_status(&format!("📖 Switched to: {}", source_name));
-                    return;
-                }
-            }
+    /// Chats and messages over time plus a per-version breakdown
+    fn show_activity_card(&mut self, ui: &mut egui::Ui, theme: Theme) {
+        let include_archived = !self.stats_exclude_archived;
+        let fresh = self.dashboard_activity.as_ref().is_some_and(|a| {
+            a.include_archived == include_archived && a.loaded_at.elapsed() < ACTIVITY_REFRESH
+        });
+        if !fresh {
+            self.dashboard_activity = Some(DashboardActivity {
+                loaded_at: std::time::Instant::now(),
+                include_archived,
+                days: self
+                    .db
+                    .get_daily_activity(include_archived)
+                    .unwrap_or_default(),
+                versions: self
+                    .db
+                    .get_version_counts(include_archived)
+                    .unwrap_or_default(),
+            });
         }
-        
-        // Open new tab
-        self.tabs.push(Tab::IndexedDoc(source_id.to_string()));
-        self.active_tab = self.tabs.len() - 1;
-        self.set_status(&format!("📖 Opened: {}", source_name));
-    }
+        let Some(data) = &self.dashboard_activity else {
+            return;
+        };
 
-    /// Forge panel - Data transformation and training data preparation
-    fn show_forge_panel(&mut self, ui: &mut egui::Ui, theme: Theme) {
-        egui::ScrollArea::vertical()
-            .auto_shrink([false; 2])
-            .show(ui, |ui| {
-                ui.add_space(SECTION_SPACING);
+        let bucket = self.activity_bucket;
+        let today = chrono::Utc::now().date_naive();
+        let buckets = activity::bucket_activity(&data.days, bucket, today);
+        let versions = data.versions.clone();
+        let mut new_bucket = None;
 
-                // Header
-                panel_header_with_icon(ui, "🔥", "FORGE", theme);
-                ui.add_space(TIGHT_SPACING);
+        card_frame(theme, self.density).show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            ui.horizontal(|ui| {
+                ui.label(
+                    RichText::new("📈 Activity")
+                        .size(12.0)
+                        .color(theme.fg)
+                        .strong(),
+                );
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    for option in activity::Bucket::ALL.iter().rev() {
+                        let label = RichText::new(option.label()).size(theme.text_size(11.0));
+                        if ui.selectable_label(bucket == *option, label).clicked() {
+                            new_bucket = Some(*option);
+                        }
+                    }
+                });
+            });
+            ui.add_space(8.0);
+
+            if versions.is_empty() {
+                ui.label(
+                    RichText::new("No chats yet - import some to see activity here")
+                        .size(theme.text_size(11.0))
+                        .color(theme.fg_dim),
+                );
+                return;
+            }
+
+            if buckets.is_empty() {
+                ui.label(
+                    RichText::new(format!(
+                        "No activity in the last {} {}s",
+                        bucket.span(),
+                        bucket.label().to_lowercase()
+                    ))
+                    .size(theme.text_size(11.0))
+                    .color(theme.fg_dim),
+                );
+            } else {
+                draw_activity_chart(ui, &buckets, bucket, theme);
+            }
+
+            ui.add_space(12.0);
+            ui.label(
+                RichText::new("By version")
+                    .size(theme.text_size(11.0))
+                    .color(theme.fg_dim)
+                    .strong(),
+            );
+            ui.add_space(4.0);
+            let largest = versions.first().map(|(_, n)| *n).unwrap_or(1).max(1);
+            for (version, count) in versions.iter().take(ACTIVITY_TOP_VERSIONS) {
                 ui.horizontal(|ui| {
-                    ui.add_space(PANEL_PADDING);
+                    let name = if version == "default"
+                        || version.starts_with(|c: char| c.is_ascii_digit())
+                    {
+                        Self::version_display_name(version)
+                    } else {
+                        version.clone()
+                    };
+                    ui.add_sized(
+                        [110.0, 14.0],
+                        egui::Label::new(
+                            RichText::new(name).size(theme.text_size(11.0)).color(theme.fg),
+                        )
+                        .truncate(),
+                    );
+                    let full = (ui.available_width() - 48.0).max(20.0);
+                    let (rect, _) =
+                        ui.allocate_exact_size(Vec2::new(full, 10.0), egui::Sense::hover());
+                    let filled =
+                        rect.with_max_x(rect.left() + full * *count as f32 / largest as f32);
+                    ui.painter().rect_filled(rect, 2.0, theme.input_bg);
+                    ui.painter()
+                        .rect_filled(filled, 2.0, theme.success.linear_multiply(0.7));
                     ui.label(
-                        RichText::new("Data transformation & training preparation")
-                            .size(9.0)
+                        RichText::new(count.to_string())
+                            .size(theme.text_size(11.0))
                             .color(theme.fg_dim),
                     );
                 });
+            }
+            if versions.len() > ACTIVITY_TOP_VERSIONS {
+                ui.label(
+                    RichText::new(format!("+{} more", versions.len() - ACTIVITY_TOP_VERSIONS))
+                        .size(theme.text_size(10.0))
+                        .color(theme.fg_dim),
+                );
+            }
+        });
 
-                ui.add_space(SECTION_SPACING);
-
-                // Coming Soon Banner
-                warning_card_frame(theme)
-                    .show(ui, |ui| {
-                        ui.vertical_centered(|ui| {
-                            ui.label(
-                                RichText::new("🚧 Coming Soon")
-                                    .size(16.0)
-                                    .color(theme.warning)
-                                    .strong(),
-                            );
-                            ui.add_space(12.0);
-                            ui.label(
-                                RichText::new("The Forge will enable:")
-                                    .size(11.0)
-                                    .color(theme.fg),
-                            );
-                            ui.add_space(8.0);
-                        });
-
-                        let features = [
-                            "📊 Combine chats + docs into training datasets",
-                            "🔄 Transform to OpenAI/Alpaca/ShareGPT formats",
-                            "🧹 Quality filtering and deduplication",
-                            "✂️ Train/validation/test splits",
-                            "🔒 PII redaction and anonymization",
-                            "📈 Dataset statistics and visualization",
-                        ];
-
-                        for feature in features {
-                            ui.horizontal(|ui| {
-                                ui.add_space(16.0);
-                                ui.label(RichText::new(feature).size(9.0).color(theme.fg_dim));
-                            });
-                            ui.add_space(TIGHT_SPACING);
-                        }
-                    });
-
-                ui.add_space(SECTION_SPACING);
+        if let Some(bucket) = new_bucket {
+            self.activity_bucket = bucket;
+            self.save_settings();
+        }
+    }
 
-                // Quick Actions (placeholder)
-                panel_header(ui, "QUICK ACTIONS", theme);
-                ui.add_space(ELEMENT_SPACING);
+    fn show_conversation_tab(&mut self, ui: &mut egui::Ui, theme: Theme, conv_id: &str) {
+        // Load messages and bookmarks if switching conversations
+        let needs_reload = self.current_messages.is_empty()
+            || self.current_messages.first().map(|m| &m.conversation_id)
+                != Some(&conv_id.to_string());
 
-                ui.horizontal(|ui| {
-                    ui.add_space(PANEL_PADDING);
-                    if styled_button(ui, "Export All Chats", Vec2::new(120.0, 26.0))
-                        .on_hover_text("Export all chats to training format")
-                        .clicked()
-                    {
-                        self.right_mode = RightSidebarMode::Archive;
-                        self.show_export_dialog = true;
-                    }
-                });
+        if needs_reload {
+            self.current_messages = self.load_messages(conv_id);
+            self.current_bookmarks = self.db.get_bookmarks(conv_id).unwrap_or_default();
+        }
 
-                ui.add_space(ELEMENT_SPACING);
+        // Conversation header - modern card style
+        let bookmark_count = self.current_bookmarks.len();
+        let message_count = self.current_messages.len();
 
-                ui.horizontal(|ui| {
-                    ui.add_space(PANEL_PADDING);
-                    if styled_button(ui, "Index All Docs", Vec2::new(120.0, 26.0))
-                        .on_hover_text("View indexed documentation")
-                        .clicked()
-                    {
-                        self.right_mode = RightSidebarMode::Index;
-                    }
-                });
+        // Clone conversation data to avoid borrow conflicts in closures
+        let conv_data = self
+            .conversations
+            .iter()
+            .find(|c| c.id == conv_id)
+            .map(|c| {
+                (
+                    c.title.clone(),
+                    c.source_version.clone(),
+                    c.is_favorite,
+                    c.notes.clone(),
+                    c.user_tags.clone(),
+                )
+            });
 
-                ui.add_space(SECTION_SPACING);
+        let mut toggle_favorite = false;
+        let mut do_export_header = false;
+        let mut edit_note = false;
+        let mut save_note: Option<bool> = None;
+        let mut detect_languages = false;
+        let mut remove_tag: Option<String> = None;
 
-                // CLI Reference
-                card_frame(theme)
-                    .show(ui, |ui| {
+        if let Some((conv_title, conv_source, is_favorite, conv_notes, conv_tags)) = conv_data {
+            egui::Frame::none()
+                .fill(theme.sidebar_bg)
+                .inner_margin(egui::Margin::symmetric(16.0, 12.0))
+                .stroke(Stroke::new(1.0, theme.border))
+                .show(ui, |ui| {
+                    // Top row: Title and actions
+                    ui.horizontal(|ui| {
+                        // Title (truncated if needed)
+                        let title: String = if conv_title.len() > 50 {
+                            format!("{}...", conv_title.chars().take(50).collect::<String>())
+                        } else {
+                            conv_title.clone()
+                        };
                         ui.label(
-                            RichText::new("CLI Reference")
-                                .size(11.0)
-                                .color(theme.fg)
+                            RichText::new(title)
+                                .size(14.0)
+                                .color(theme.fg_bright)
                                 .strong(),
                         );
-                        ui.add_space(8.0);
 
-                        let commands = [
-                            ("Export chats:", "mix cursor_docs.chat --export-all"),
-                            ("Index docs:", "mix cursor_docs.add https://docs.rs/..."),
-                            ("Search:", "mix cursor_docs.search \"query\""),
-                        ];
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            // Export button
+                            let export_btn = ui
+                                .add(egui::Button::new(RichText::new("⬇").size(12.0)).frame(false))
+                                .on_hover_text("Export conversation");
+                            accessible_label(ui, &export_btn, "Export conversation");
+                            if export_btn.clicked() {
+                                self.export_conversation_to_markdown(conv_id);
+                            }
 
-                        for (label, cmd) in commands {
-                            ui.horizontal(|ui| {
-                                ui.label(RichText::new(label).size(9.0).color(theme.fg_dim));
-                            });
-                            ui.horizontal(|ui| {
-                                ui.add_space(8.0);
-                                ui.label(
-                                    RichText::new(cmd)
-                                        .size(9.0)
-                                        .color(theme.accent)
-                                        .monospace(),
-                                );
+                            // All code blocks at once; right-click to group or save them
+                            let code_btn = ui
+                                .add(
+                                    egui::Button::new(
+                                        RichText::new("</>").size(theme.text_size(11.0)),
+                                    )
+                                    .frame(false),
+                                )
+                                .on_hover_text("Copy all code blocks\nRight-click for more");
+                            accessible_label(ui, &code_btn, "Copy all code blocks");
+                            if code_btn.clicked() {
+                                self.copy_conversation_code(ui.ctx(), conv_id, false);
+                            }
+                            code_btn.context_menu(|ui| {
+                                if ui.button("📋 Copy grouped by language").clicked() {
+                                    self.copy_conversation_code(ui.ctx(), conv_id, true);
+                                    ui.close_menu();
+                                }
+                                if ui.button("💾 Save to file").clicked() {
+                                    self.export_conversation_code(conv_id, false);
+                                    ui.close_menu();
+                                }
+                                if ui.button("💾 Save grouped by language").clicked() {
+                                    self.export_conversation_code(conv_id, true);
+                                    ui.close_menu();
+                                }
                             });
-                            ui.add_space(4.0);
-                        }
+
+                            let detect_btn = ui
+                                .add(egui::Button::new(RichText::new("🔤").size(12.0)).frame(false))
+                                .on_hover_text("Detect programming languages and tag them");
+                            accessible_label(ui, &detect_btn, "Detect languages");
+                            if detect_btn.clicked() {
+                                detect_languages = true;
+                            }
+
+                            let note_btn = ui
+                                .add(egui::Button::new(RichText::new("📝").size(12.0)).frame(false))
+                                .on_hover_text("Edit conversation note");
+                            accessible_label(ui, &note_btn, "Edit conversation note");
+                            if note_btn.clicked() {
+                                edit_note = true;
+                            }
+
+                            // Raw JSON copy for bug reports (developer tools only)
+                            if self.developer_mode
+                                && ui
+                                    .add(
+                                        egui::Button::new(
+                                            RichText::new("{ }").size(theme.text_size(11.0)),
+                                        )
+                                        .frame(false),
+                                    )
+                                    .on_hover_text(
+                                        "Copy conversation as JSON\n⚠ May contain secrets from your chats",
+                                    )
+                                    .clicked()
+                            {
+                                self.copy_conversation_json(ui.ctx(), conv_id);
+                            }
+
+                            // Bookmark panel toggle
+                            let bookmark_icon = if self.show_bookmark_panel {
+                                "📑"
+                            } else {
+                                "🔖"
+                            };
+                            let bookmarks_btn = ui
+                                .add(
+                                    egui::Button::new(RichText::new(bookmark_icon).size(12.0))
+                                        .frame(false),
+                                )
+                                .on_hover_text(if self.show_bookmark_panel {
+                                    "Hide bookmarks"
+                                } else {
+                                    "Show bookmarks"
+                                });
+                            let shown = self.show_bookmark_panel;
+                            accessible_toggle(ui, &bookmarks_btn, "Bookmarks panel", shown);
+                            if bookmarks_btn.clicked() {
+                                self.show_bookmark_panel = !self.show_bookmark_panel;
+                            }
+
+                            // Favorite toggle
+                            let fav_icon = if is_favorite { "★" } else { "☆" };
+                            let fav_color = if is_favorite {
+                                Color32::from_rgb(255, 215, 0)
+                            } else {
+                                theme.fg_dim
+                            };
+                            let fav_btn = ui
+                                .add(
+                                    egui::Button::new(
+                                        RichText::new(fav_icon).color(fav_color).size(12.0),
+                                    )
+                                    .frame(false),
+                                )
+                                .on_hover_text(if is_favorite {
+                                    "Remove from favorites"
+                                } else {
+                                    "Add to favorites"
+                                });
+                            accessible_toggle(ui, &fav_btn, "Favorite", is_favorite);
+                            if fav_btn.clicked() {
+                                toggle_favorite = true;
+                            }
+                        });
                     });
-            });
-    }
 
-    /// Modes panel - Custom modes management (replaces Cursor's removed custom modes)
-    fn show_modes_panel(&mut self, ui: &mut egui::Ui, theme: Theme) {
-        // Render the modes panel
-        self.modes_panel.show(ui, &theme);
-        
-        // Handle any events from the panel
-        for event in self.modes_panel.take_events() {
-            match event {
-                modes::ModesPanelEvent::ModeActivated(name) => {
-                    self.set_status(&format!("Mode activated: {}", name));
-                }
-                modes::ModesPanelEvent::ModeUpdated(name) => {
-                    self.set_status(&format!("Mode saved: {}", name));
-                }
-                modes::ModesPanelEvent::ModeDeleted(name) => {
-                    self.set_status(&format!("Mode deleted: {}", name));
-                }
-                modes::ModesPanelEvent::ModeInjected { mode, target } => {
-                    self.set_status(&format!("Mode '{}' injected ({:?})", mode, target));
-                }
-                modes::ModesPanelEvent::VanillaSwap { from_mode } => {
-                    self.set_status(&format!("Swapped from '{}' to vanilla", from_mode));
-                }
-            }
-        }
-    }
+                    ui.add_space(6.0);
 
-    /// Find the p2p-sync binary in common locations
-    fn find_p2p_binary(&self) -> Option<PathBuf> {
-        // Check common locations
-        let candidates = [
-            // Same directory as cursor-studio
-            std::env::current_exe().ok().and_then(|p| p.parent().map(|d| d.join("p2p-sync"))),
-            // Cargo target directory
-            std::env::current_exe().ok().and_then(|p| {
-                p.parent()
-                    .and_then(|d| d.parent())
-                    .and_then(|d| d.parent())
-                    .map(|ws| ws.join("target/release/p2p-sync"))
-            }),
-            // Home directory cargo install location
-            dirs::home_dir().map(|h| h.join(".cargo/bin/p2p-sync")),
-            // Current directory
-            Some(PathBuf::from("./target/release/p2p-sync")),
-        ];
+                    // Stats row
+                    ui.horizontal(|ui| {
+                        // Message count
+                        ui.label(
+                            RichText::new(format!("💬 {}", message_count))
+                                .color(theme.fg_dim)
+                                .size(theme.text_size(11.0)),
+                        );
 
-        for candidate in candidates.into_iter().flatten() {
-            if candidate.exists() {
-                log::info!("Found p2p-sync at: {:?}", candidate);
-                return Some(candidate);
-            }
-        }
+                        ui.add_space(12.0);
 
-        // Try PATH lookup via `which`
-        if let Ok(output) = std::process::Command::new("which")
-            .arg("p2p-sync")
-            .output()
-        {
-            if output.status.success() {
-                let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                if !path.is_empty() {
-                    return Some(PathBuf::from(path));
-                }
-            }
-        }
+                        // Bookmark count (with loading indicator if 0 but might be loading)
+                        if bookmark_count > 0 {
+                            ui.label(
+                                RichText::new(format!("🔖 {}", bookmark_count))
+                                    .color(Color32::from_rgb(255, 215, 0))
+                                    .size(theme.text_size(11.0)),
+                            );
+                        }
 
-        None
-    }
+                        ui.add_space(12.0);
 
-    /// Start the P2P sync daemon as a background process
-    fn start_p2p_daemon(&mut self) {
-        if self.p2p_daemon_running {
-            self.set_status("⚠️ P2P daemon already running");
-            return;
-        }
+                        // Source version
+                        ui.label(
+                            RichText::new(format!("📁 {}", conv_source))
+                                .color(theme.fg_dim)
+                                .size(theme.text_size(10.0)),
+                        );
+                    });
 
-        // Find the p2p-sync binary
-        let binary = match self.find_p2p_binary() {
-            Some(path) => path,
-            None => {
-                self.set_status("✗ p2p-sync binary not found. Build with: cargo build --release --bin p2p-sync");
-                log::error!("p2p-sync binary not found in any expected location");
-                return;
-            }
-        };
+                    // Tags, with detected languages marked; ✕ removes one
+                    if !conv_tags.is_empty() {
+                        ui.add_space(6.0);
+                        ui.horizontal_wrapped(|ui| {
+                            for tag in &conv_tags {
+                                let label = match tag.strip_prefix(languages::LANGUAGE_TAG_PREFIX) {
+                                    Some(language) => format!("🔤 {}", language),
+                                    None => format!("🏷 {}", tag),
+                                };
+                                ui.label(
+                                    RichText::new(label)
+                                        .color(theme.fg_dim)
+                                        .size(theme.text_size(10.0)),
+                                );
+                                let remove_btn = ui
+                                    .add(
+                                        egui::Button::new(
+                                            RichText::new("✕")
+                                                .color(theme.fg_dim)
+                                                .size(theme.text_size(9.0)),
+                                        )
+                                        .frame(false),
+                                    )
+                                    .on_hover_text("Remove tag");
+                                accessible_label(ui, &remove_btn, &format!("Remove tag {}", tag));
+                                if remove_btn.clicked() {
+                                    remove_tag = Some(tag.clone());
+                                }
+                                ui.add_space(6.0);
+                            }
+                        });
+                    }
 
-        self.set_status(&format!("🚀 Starting P2P daemon on port {}...", self.p2p_daemon_port));
+                    // Conversation note: summary, TODOs, own context
+                    let editing = self.note_editor.as_ref().is_some_and(|(id, _)| id == conv_id);
+                    if editing {
+                        ui.add_space(6.0);
+                        if let Some((_, draft)) = self.note_editor.as_mut() {
+                            ui.add(
+                                egui::TextEdit::multiline(draft)
+                                    .hint_text("Summary, TODOs, context for later...")
+                                    .desired_rows(3)
+                                    .desired_width(f32::INFINITY),
+                            );
+                        }
+                        ui.horizontal(|ui| {
+                            if ui.small_button("✓ Save").clicked() {
+                                save_note = Some(true);
+                            }
+                            if ui.small_button("Cancel").clicked() {
+                                save_note = Some(false);
+                            }
+                        });
+                    } else if let Some(notes) = &conv_notes {
+                        ui.add_space(6.0);
+                        let note_label = ui
+                            .add(
+                                egui::Label::new(
+                                    RichText::new(format!("📝 {}", notes))
+                                        .color(theme.fg_dim)
+                                        .size(theme.text_size(11.0))
+                                        .italics(),
+                                )
+                                .sense(egui::Sense::click()),
+                            )
+                            .on_hover_text("Click to edit note");
+                        if note_label.clicked() {
+                            edit_note = true;
+                        }
+                    }
+                });
 
-        match std::process::Command::new(&binary)
-            .args(["--port", &self.p2p_daemon_port.to_string(), "--import"])
-            .stdout(std::process::Stdio::null())
-            .stderr(std::process::Stdio::null())
-            .spawn()
-        {
-            Ok(child) => {
-                self.p2p_daemon_process = Some(child);
-                self.p2p_daemon_running = true;
-                self.set_status(&format!("✓ P2P daemon started on port {}", self.p2p_daemon_port));
-            }
-            Err(e) => {
-                self.set_status(&format!("✗ Failed to start P2P daemon: {}", e));
-                log::error!("Failed to start P2P daemon: {}", e);
+            // Handle actions outside the closure to avoid borrow issues
+            if edit_note {
+                let note = self.db.get_conversation_note(conv_id).ok().flatten();
+                self.note_editor = Some((conv_id.to_string(), note.unwrap_or_default()));
             }
-        }
-    }
 
-    /// Stop the P2P sync daemon
-    fn stop_p2p_daemon(&mut self) {
-        if let Some(mut process) = self.p2p_daemon_process.take() {
-            match process.kill() {
-                Ok(()) => {
-                    self.p2p_daemon_running = false;
-                    self.sync_p2p_peers.clear();
-                    self.set_status("⏹ P2P daemon stopped");
-                }
-                Err(e) => {
-                    self.set_status(&format!("✗ Failed to stop P2P daemon: {}", e));
-                    log::error!("Failed to stop P2P daemon: {}", e);
-                    // Put the process back if we couldn't kill it
-                    self.p2p_daemon_process = Some(process);
+            if let Some(save) = save_note {
+                if let Some((_, draft)) = self.note_editor.take().filter(|_| save) {
+                    match self.db.set_conversation_note(conv_id, &draft) {
+                        Ok(()) => {
+                            let notes = Some(draft.trim().to_string()).filter(|n| !n.is_empty());
+                            if let Some(conv) =
+                                self.conversations.iter_mut().find(|c| c.id == conv_id)
+                            {
+                                conv.notes = notes;
+                            }
+                            self.set_status("✓ Note saved");
+                        }
+                        Err(e) => self.set_status(&format!("✗ Failed to save note: {}", e)),
+                    }
                 }
             }
-        } else {
-            self.p2p_daemon_running = false;
-            self.set_status("P2P daemon was not running");
-        }
-    }
-
-    #[cfg(feature = "surrealdb-store")]
-    fn check_server_status(&mut self) {
-        use chat::ClientConfig;
-        use chat::SyncClient;
-
-        let config = ClientConfig {
-            server_url: self.sync_server_url.clone(),
-            device_id: "cursor-studio".to_string(),
-        };
-        let client = SyncClient::new(config);
 
-        match client.health() {
-            Ok(health) => {
-                self.sync_server_connected = true;
-                self.sync_conversation_count = health.conversations;
-                self.sync_last_status = Some(format!(
-                    "Server v{} • {} conversations",
-                    health.version, health.conversations
-                ));
-                self.set_status(&format!("✓ Connected to {}", self.sync_server_url));
-            }
-            Err(e) => {
-                self.sync_server_connected = false;
-                self.sync_conversation_count = 0;
-                self.sync_last_status = Some(format!("Connection failed: {}", e));
-                self.set_status(&format!("✗ Failed to connect: {}", e));
+            if toggle_favorite {
+                let _ = self.db.toggle_favorite(conv_id);
+                self.reload_conversations();
             }
-        }
-    }
-
-    #[cfg(not(feature = "surrealdb-store"))]
-    fn check_server_status(&mut self) {
-        self.set_status("⚠️ Server sync requires 'full' build");
-    }
 
-    #[cfg(feature = "surrealdb-store")]
-    fn pull_from_server(&mut self) {
-        use chat::ClientConfig;
-        use chat::SyncClient;
-
-        let config = ClientConfig {
-            server_url: self.sync_server_url.clone(),
-            device_id: "cursor-studio".to_string(),
-        };
-        let client = SyncClient::new(config);
-
-        match client.pull(Some(100)) {
-            Ok(conversations) => {
-                let count = conversations.len();
-                self.sync_last_status = Some(format!("Pulled {} conversations", count));
-                self.set_status(&format!("✓ Pulled {} conversations from server", count));
-                // TODO: Merge into local database
+            if detect_languages {
+                match self.db.tag_languages(conv_id) {
+                    Ok(languages) if languages.is_empty() => {
+                        self.set_status("No programming languages detected");
+                    }
+                    Ok(languages) => {
+                        self.set_status(&format!("✓ Tagged {}", languages.join(", ")));
+                    }
+                    Err(e) => self.set_status(&format!("✗ Language detection failed: {}", e)),
+                }
+                self.reload_conversations();
             }
-            Err(e) => {
-                self.sync_last_status = Some(format!("Pull failed: {}", e));
-                self.set_status(&format!("✗ Pull failed: {}", e));
+
+            if let Some(tag) = remove_tag {
+                match self.db.remove_tag(conv_id, &tag) {
+                    Ok(()) => self.set_status(&format!("✓ Removed tag '{}'", tag)),
+                    Err(e) => self.set_status(&format!("✗ Failed to remove tag: {}", e)),
+                }
+                self.reload_conversations();
             }
-        }
-    }
 
-    #[cfg(not(feature = "surrealdb-store"))]
-    fn pull_from_server(&mut self) {
-        self.set_status("⚠️ Server sync requires 'full' build");
-    }
+            ui.add_space(8.0);
+        }
 
-    fn show_editor_area(&mut self, ui: &mut egui::Ui, theme: Theme) {
-        let tabs = self.tabs.clone();
-        let convs = self.conversations.clone();
-        let active_tab = self.active_tab;
+        // Branch structure, for chats where a message was edited and regenerated
+        let tree = ConversationTree::new(&self.current_messages);
+        let empty_count = self
+            .current_messages
+            .iter()
+            .filter(|m| m.is_empty())
+            .count();
+        let hide_empty = self
+            .empty_message_overrides
+            .get(conv_id)
+            .copied()
+            .unwrap_or(self.hide_empty_messages);
 
-        let mut new_active: Option<usize> = None;
-        let mut to_close: Option<usize> = None;
+        // Toolbar: Export and Search
+        let mut do_export = false;
+        let mut do_continue: Option<bool> = None;
+        let mut do_search = false;
+        let mut do_goto = false;
+        let mut search_query_changed = false;
 
-        // Tab bar
         ui.horizontal(|ui| {
-            ui.add_space(4.0);
-
-            for (i, tab) in tabs.iter().enumerate() {
-                let is_active = i == active_tab;
-                let bg = if is_active {
-                    theme.tab_active_bg
-                } else {
-                    theme.tab_bg
-                };
+            ui.add_space(16.0);
 
-                let title = match tab {
-                    Tab::Dashboard => "🏠 Dashboard".to_string(),
-                    Tab::Conversation(id) => {
-                        let title_text = convs
-                            .iter()
-                            .find(|c| &c.id == id)
-                            .map(|c| c.title.chars().take(18).collect::<String>())
-                            .unwrap_or_else(|| "Chat".to_string());
-                        format!("💬 {}", title_text)
-                    }
-                    Tab::IndexedDoc(source_id) => {
-                        // Get source name from docs panel
-                        self.docs_panel.client.get_source(source_id)
-                            .ok()
-                            .flatten()
-                            .map(|s| format!("📖 {}", s.display_name().chars().take(15).collect::<String>()))
-                            .unwrap_or_else(|| "📖 Docs".to_string())
-                    }
-                };
+            // Export button
+            if ui
+                .small_button("📤 Export")
+                .on_hover_text("Export to Markdown")
+                .clicked()
+            {
+                do_export = true;
+            }
 
-                // Use a selectable button for better click handling
-                let tab_size = Vec2::new(140.0, 28.0);
-                let (rect, response) = ui.allocate_exact_size(tab_size, egui::Sense::click());
+            // Resume in Cursor via a copied context prompt
+            ui.menu_button("↪ Continue in Cursor", |ui| {
+                if ui
+                    .button("Whole conversation")
+                    .on_hover_text("Original request plus the most recent messages")
+                    .clicked()
+                {
+                    do_continue = Some(false);
+                    ui.close_menu();
+                }
+                let bookmarked = ui.add_enabled(
+                    bookmark_count > 0,
+                    egui::Button::new(format!("Bookmarked messages ({})", bookmark_count)),
+                );
+                if bookmarked.clicked() {
+                    do_continue = Some(true);
+                    ui.close_menu();
+                }
+            })
+            .response
+            .on_hover_text("Copy a context prompt that seeds a new Cursor chat");
 
-                // Draw background
-                let hover_bg = if response.hovered() && !is_active {
-                    theme.tab_hover_bg
-                } else {
-                    bg
-                };
-                ui.painter().rect_filled(rect, Rounding::ZERO, hover_bg);
+            // Branch tree toggle, only for chats with edited messages
+            if !tree.is_linear() {
+                let label = format!("🔀 Branches ({})", tree.points.len());
+                if ui
+                    .selectable_label(self.show_branch_tree, label)
+                    .on_hover_text("Show where messages were edited and regenerated")
+                    .clicked()
+                {
+                    self.show_branch_tree = !self.show_branch_tree;
+                }
+            }
 
-                // Active indicator
-                if is_active {
-                    ui.painter()
-                        .hline(rect.x_range(), rect.top(), Stroke::new(2.0, theme.accent));
+            // Per-chat override of the "Hide Empty Messages" setting
+            if empty_count > 0 {
+                let label = format!("{} empty", empty_count);
+                if ui
+                    .selectable_label(!hide_empty, label)
+                    .on_hover_text(if hide_empty {
+                        "Show messages with no content in this chat"
+                    } else {
+                        "Hide messages with no content in this chat"
+                    })
+                    .clicked()
+                {
+                    self.empty_message_overrides
+                        .insert(conv_id.to_string(), !hide_empty);
                 }
+            }
 
-                // Tab title
-                let text_pos = rect.left_center() + Vec2::new(8.0, 0.0);
-                ui.painter().text(
-                    text_pos,
-                    egui::Align2::LEFT_CENTER,
-                    &title,
-                    egui::FontId::proportional(12.0),
-                    if is_active { theme.fg } else { theme.fg_dim },
-                );
+            ui.add_space(8.0);
 
-                // Close button (only for non-dashboard tabs)
-                if !matches!(tab, Tab::Dashboard) {
-                    let close_rect = egui::Rect::from_center_size(
-                        rect.right_center() - Vec2::new(14.0, 0.0),
-                        Vec2::splat(16.0),
-                    );
-                    let close_response =
-                        ui.interact(close_rect, ui.id().with(("close", i)), egui::Sense::click());
+            // Search box
+            ui.label(RichText::new("🔍").size(12.0));
+            let search_response = ui.add(
+                egui::TextEdit::singleline(&mut self.conv_search_query)
+                    .desired_width(150.0)
+                    .hint_text("Search in chat...")
+                    .font(egui::FontId::proportional(theme.text_size(11.0))),
+            );
 
-                    let close_color = if close_response.hovered() {
-                        theme.error
-                    } else {
-                        theme.fg_dim
-                    };
+            if search_response.changed() {
+                search_query_changed = true;
+            }
 
-                    ui.painter().text(
-                        close_rect.center(),
-                        egui::Align2::CENTER_CENTER,
-                        "×",
-                        egui::FontId::proportional(14.0),
-                        close_color,
-                    );
+            // Enter to search
+            if search_response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                do_search = true;
+            }
 
-                    if close_response.clicked() {
-                        to_close = Some(i);
-                    }
-                    if close_response.hovered() {
-                        ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
-                    }
-                }
+            // Search nav buttons
+            if !self.conv_search_results.is_empty() {
+                ui.label(
+                    RichText::new(format!(
+                        "{}/{}",
+                        self.conv_search_index + 1,
+                        self.conv_search_results.len()
+                    ))
+                    .size(theme.text_size(10.0))
+                    .color(theme.fg_dim),
+                );
 
-                // Handle tab click (but not on close button area)
-                if response.clicked() && to_close.is_none() {
-                    new_active = Some(i);
+                if ui
+                    .small_button("◀")
+                    .on_hover_text("Previous result")
+                    .clicked()
+                {
+                    self.jump_to_prev_search_result();
                 }
+                if ui.small_button("▶").on_hover_text("Next result").clicked() {
+                    self.jump_to_next_search_result();
+                }
+            }
 
-                if response.hovered() {
-                    ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+            // Search button
+            if ui.small_button("Find").clicked() {
+                do_search = true;
+            }
+
+            // Go to message by sequence number
+            if let (Some(first), Some(last)) =
+                (self.current_messages.first(), self.current_messages.last())
+            {
+                ui.add_space(8.0);
+                let goto_response = ui
+                    .add(
+                        egui::TextEdit::singleline(&mut self.goto_message_input)
+                            .desired_width(70.0)
+                            .hint_text(format!("# {}-{}", first.sequence, last.sequence))
+                            .font(egui::FontId::proportional(theme.text_size(11.0))),
+                    )
+                    .on_hover_text(format!(
+                        "Go to message number ({} messages loaded)",
+                        message_count
+                    ));
+                if goto_response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    do_goto = true;
                 }
             }
         });
 
-        // Process tab changes
-        if let Some(i) = to_close {
-            self.close_tab(i);
+        // Handle actions
+        if do_export {
+            self.export_conversation_to_markdown(conv_id);
         }
-        if let Some(i) = new_active {
-            if i != self.active_tab {
-                self.active_tab = i;
-                // Auto-refresh data when switching tabs
-                self.on_tab_switch();
-            }
+
+        if let Some(bookmarked_only) = do_continue {
+            self.continue_in_cursor(ui.ctx(), conv_id, bookmarked_only);
         }
 
-        ui.add(egui::Separator::default().spacing(0.0));
+        if do_goto {
+            self.goto_message_sequence();
+        }
 
-        // Show active tab content
-        if let Some(tab) = self.tabs.get(self.active_tab) {
-            match tab {
-                Tab::Dashboard => self.show_dashboard(ui, theme),
-                Tab::Conversation(id) => {
-                    let id = id.clone();
-                    self.show_conversation_tab(ui, theme, &id);
-                }
-                Tab::IndexedDoc(source_id) => {
-                    let source_id = source_id.clone();
-                    self.show_indexed_doc_tab(ui, theme, &source_id);
-                }
-            }
+        if do_search || (search_query_changed && self.conv_search_query.len() >= 2) {
+            let query = self.conv_search_query.clone();
+            self.search_in_conversation(&query);
         }
-    }
 
-    /// Show indexed documentation source in a tab
-    fn show_indexed_doc_tab(&mut self, ui: &mut egui::Ui, theme: Theme, source_id: &str) {
-        // Get source details from docs_panel
-        let source = self.docs_panel.client.get_source(source_id);
-        
-        match source {
-            Ok(Some(source)) => {
-                egui::ScrollArea::vertical()
-                    .auto_shrink([false, false])
+        ui.add_space(8.0);
+
+        // Date scrubber for conversations spanning multiple days
+        let conversation_days = match &self.conversation_days {
+            Some(days) if days.is_current(conv_id, &self.current_messages) => days.clone(),
+            _ => {
+                let days =
+                    std::sync::Arc::new(ConversationDays::new(conv_id, &self.current_messages));
+                self.conversation_days = Some(days.clone());
+                days
+            }
+        };
+        let days = &conversation_days.days;
+        if days.len() > 1 {
+            let mut jump_to_day: Option<String> = None;
+            ui.horizontal(|ui| {
+                ui.add_space(16.0);
+                let first = days.first().map(|d| d.0.format("%b %-d").to_string());
+                let last = days.last().map(|d| d.0.format("%b %-d").to_string());
+                ui.label(
+                    RichText::new(format!(
+                        "📅 {} → {}",
+                        first.unwrap_or_default(),
+                        last.unwrap_or_default()
+                    ))
+                    .size(theme.text_size(10.0))
+                    .color(theme.fg_dim),
+                );
+                egui::ScrollArea::horizontal()
+                    .id_salt("date_scrubber")
                     .show(ui, |ui| {
-                        ui.add_space(16.0);
-                        
-                        // Header
                         ui.horizontal(|ui| {
-                            ui.add_space(16.0);
-                            ui.label(
-                                RichText::new(format!("📖 {}", source.display_name()))
-                                    .size(18.0)
-                                    .color(theme.fg)
-                                    .strong(),
-                            );
+                            for (day, first_msg_id, count) in days {
+                                if ui
+                                    .small_button(day.format("%b %-d").to_string())
+                                    .on_hover_text(format!(
+                                        "{} • {} messages",
+                                        day.format("%A, %B %-d %Y"),
+                                        count
+                                    ))
+                                    .clicked()
+                                {
+                                    jump_to_day = Some(first_msg_id.clone());
+                                }
+                            }
                         });
-                        
-                        ui.add_space(8.0);
-                        
-                        // Source info
-                        ui.horizontal(|ui| {
-                            ui.add_space(16.0);
-                            ui.label(
-                                RichText::new(&source.url)
-                                    .size(11.0)
-                                    .color(theme.fg_dim),
-                            );
+                    });
+            });
+            if let Some(msg_id) = jump_to_day {
+                self.scroll_to_message_id = Some(msg_id);
+            }
+            ui.add_space(4.0);
+        }
+
+        // Bookmark panel (if visible)
+        if self.show_bookmark_panel {
+            let mut export_bookmarks = false;
+            let mut import_bookmarks: Option<String> = None;
+            egui::Frame::none()
+                .fill(theme.sidebar_bg)
+                .inner_margin(8.0)
+                .rounding(Rounding::same(4.0))
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            RichText::new("📑 Bookmarks")
+                                .color(theme.fg)
+                                .strong()
+                                .size(12.0),
+                        );
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui
+                                .small_button("⬇ Import")
+                                .on_hover_text("Add bookmarks exported on another machine")
+                                .clicked()
+                            {
+                                self.bookmark_import_path = match self.bookmark_import_path {
+                                    Some(_) => None,
+                                    None => Some(String::new()),
+                                };
+                            }
+                            if ui
+                                .small_button("⬆ Export")
+                                .on_hover_text("Save all bookmarks as JSON in the export folder")
+                                .clicked()
+                            {
+                                export_bookmarks = true;
+                            }
                         });
-                        
+                    });
+                    if let Some(path) = &mut self.bookmark_import_path {
                         ui.horizontal(|ui| {
-                            ui.add_space(16.0);
-                            ui.label(
-                                RichText::new(format!(
-                                    "{} {} • {} chunks",
-                                    source.status.icon(),
-                                    source.status.label(),
-                                    source.chunks_count
-                                ))
-                                .size(11.0)
-                                .color(theme.fg_dim),
+                            let resp = ui.add(
+                                egui::TextEdit::singleline(path)
+                                    .hint_text("~/Documents/cursor-bookmarks.json")
+                                    .desired_width(ui.available_width() - 60.0),
                             );
+                            let enter =
+                                resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                            if (ui.small_button("Import").clicked() || enter)
+                                && !path.trim().is_empty()
+                            {
+                                import_bookmarks = Some(path.clone());
+                            }
                         });
-                        
-                        if let Some(ref last) = source.last_indexed {
-                            ui.horizontal(|ui| {
-                                ui.add_space(16.0);
-                                ui.label(
-                                    RichText::new(format!("Last indexed: {}", last))
-                                        .size(10.0)
-                                        .color(theme.fg_dim),
-                                );
-                            });
-                        }
-                        
-                        ui.add_space(16.0);
-                        ui.add(egui::Separator::default());
-                        ui.add_space(12.0);
-                        
-                        // Chunks section
+                    }
+                    ui.add_space(4.0);
+
+                    let bookmarks = self.current_bookmarks.clone();
+                    let mut jump_to: Option<(String, String)> = None;
+
+                    if bookmarks.is_empty() {
+                        ui.label(
+                            RichText::new("No bookmarks in this conversation")
+                                .color(theme.fg_dim)
+                                .italics()
+                                .size(theme.text_size(11.0)),
+                        );
+                    }
+
+                    for bookmark in &bookmarks {
                         ui.horizontal(|ui| {
-                            ui.add_space(16.0);
+                            // Bookmark color indicator
+                            let color = Color32::from_rgb(255, 215, 0);
+                            ui.painter().circle_filled(
+                                ui.cursor().min + Vec2::new(6.0, 8.0),
+                                4.0,
+                                color,
+                            );
+                            ui.add_space(12.0);
+
+                            // Label or default (create owned string first)
+                            let default_label = format!("Msg #{}", bookmark.message_sequence);
+                            let label = bookmark.label.as_deref().unwrap_or(&default_label);
                             ui.label(
-                                RichText::new("INDEXED CONTENT")
-                                    .size(11.0)
-                                    .color(theme.fg_dim)
-                                    .strong(),
+                                RichText::new(label)
+                                    .color(theme.fg)
+                                    .size(theme.text_size(11.0)),
                             );
-                        });
-                        ui.add_space(8.0);
-                        
-                        // Load and display chunks
-                        match self.docs_panel.client.get_chunks(source_id, 100) {
-                            Ok(chunks) => {
-                                if chunks.is_empty() {
-                                    ui.horizontal(|ui| {
-                                        ui.add_space(16.0);
-                                        ui.label(
-                                            RichText::new("No content indexed yet")
-                                                .size(11.0)
-                                                .color(theme.fg_dim),
-                                        );
-                                    });
-                                } else {
-                                    for (i, chunk) in chunks.iter().enumerate() {
-                                        egui::Frame::none()
-                                            .fill(theme.code_bg)
-                                            .rounding(Rounding::same(4.0))
-                                            .inner_margin(egui::Margin::same(12.0))
-                                            .outer_margin(egui::Margin::symmetric(16.0, 4.0))
-                                            .show(ui, |ui| {
-                                                ui.horizontal(|ui| {
-                                                    ui.label(
-                                                        RichText::new(format!("#{}", i + 1))
-                                                            .size(10.0)
-                                                            .color(theme.fg_dim),
-                                                    );
-                                                    ui.label(
-                                                        RichText::new(&chunk.title)
-                                                            .size(11.0)
-                                                            .color(theme.fg)
-                                                            .strong(),
-                                                    );
-                                                });
-                                                ui.add_space(4.0);
-                                                
-                                                // Show preview of content (first 300 chars)
-                                                let preview = if chunk.content.len() > 300 {
-                                                    format!("{}...", &chunk.content[..300])
-                                                } else {
-                                                    chunk.content.clone()
-                                                };
-                                                ui.label(
-                                                    RichText::new(preview)
-                                                        .size(10.0)
-                                                        .color(theme.fg_dim),
-                                                );
-                                            });
-                                    }
-                                    
-                                    if chunks.len() >= 100 {
-                                        ui.horizontal(|ui| {
-                                            ui.add_space(16.0);
-                                            ui.label(
-                                                RichText::new("Showing first 100 chunks...")
-                                                    .size(10.0)
-                                                    .color(theme.fg_dim)
-                                                    .italics(),
-                                            );
-                                        });
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                ui.horizontal(|ui| {
-                                    ui.add_space(16.0);
-                                    ui.label(
-                                        RichText::new(format!("Failed to load chunks: {}", e))
-                                            .size(11.0)
-                                            .color(theme.error),
-                                    );
-                                });
+
+                            // Jump button
+                            if ui
+                                .small_button("→")
+                                .on_hover_text("Jump to message")
+                                .clicked()
+                            {
+                                jump_to = Some((
+                                    bookmark.conversation_id.clone(),
+                                    bookmark.message_id.clone(),
+                                ));
                             }
-                        }
-                    });
-            }
-            Ok(None) => {
-                ui.centered_and_justified(|ui| {
-                    ui.label(
-                        RichText::new("Source not found")
-                            .size(14.0)
-                            .color(theme.fg_dim),
-                    );
+                        });
+                    }
+
+                    // Process jump after UI iteration
+                    if let Some((cid, mid)) = jump_to {
+                        self.scroll_to_message_id = Some(mid);
+                    }
                 });
+            if export_bookmarks {
+                self.export_bookmarks_file();
             }
-            Err(e) => {
-                ui.centered_and_justified(|ui| {
+            if let Some(path) = import_bookmarks {
+                self.import_bookmarks_file(&path);
+            }
+            ui.add_space(4.0);
+        }
+
+        // Branch tree (if visible)
+        if self.show_branch_tree && !tree.is_linear() {
+            let mut jump_to: Option<String> = None;
+            egui::Frame::none()
+                .fill(theme.sidebar_bg)
+                .inner_margin(8.0)
+                .rounding(Rounding::same(4.0))
+                .show(ui, |ui| {
                     ui.label(
-                        RichText::new(format!("Error loading source: {}", e))
-                            .size(14.0)
-                            .color(theme.error),
+                        RichText::new("🔀 Branches")
+                            .color(theme.fg)
+                            .strong()
+                            .size(12.0),
                     );
+                    ui.add_space(4.0);
+
+                    let messages = &self.current_messages;
+                    for (depth, point) in tree.outline() {
+                        let branch_point = &tree.points[point];
+                        let chosen = tree.chosen(messages, point, &self.branch_choices);
+                        let parent = &messages[branch_point.parent];
+                        ui.horizontal(|ui| {
+                            ui.add_space(depth as f32 * 16.0);
+                            ui.label(
+                                RichText::new(format!(
+                                    "#{} {}",
+                                    parent.sequence,
+                                    branch_preview(&parent.content)
+                                ))
+                                .color(theme.fg_dim)
+                                .size(theme.text_size(11.0)),
+                            );
+                            for (pos, &child) in branch_point.children.iter().enumerate() {
+                                let head = &messages[child];
+                                if ui
+                                    .selectable_label(pos == chosen, format!("{}", pos + 1))
+                                    .on_hover_text(branch_preview(&head.content))
+                                    .clicked()
+                                {
+                                    jump_to = Some(head.id.clone());
+                                }
+                            }
+                        });
+                    }
                 });
+            if let Some(msg_id) = jump_to {
+                self.scroll_to_message_id = Some(msg_id);
             }
+            ui.add_space(4.0);
         }
-    }
 
-    fn show_dashboard(&mut self, ui: &mut egui::Ui, theme: Theme) {
-        let (total, messages, favorites) = self.db.get_stats().unwrap_or((0, 0, 0));
+        ui.separator();
 
-        let mut do_import = false;
-        let mut do_launch = false;
+        // Jumps into another branch switch to it; linear chats show every message
+        let mut msgs: Vec<Message> = if tree.is_linear() {
+            self.current_messages.clone()
+        } else {
+            let target = self
+                .scroll_to_message_id
+                .as_ref()
+                .and_then(|id| self.current_messages.iter().position(|m| &m.id == id));
+            if let Some(index) = target {
+                tree.reveal(&self.current_messages, &mut self.branch_choices, index);
+            }
+            tree.visible(&self.current_messages, &self.branch_choices)
+                .into_iter()
+                .map(|i| self.current_messages[i].clone())
+                .collect()
+        };
+        if hide_empty {
+            msgs.retain(|m| !m.is_empty());
+        }
+        let branch_heads: std::collections::HashMap<String, (usize, usize)> = tree
+            .heads()
+            .into_iter()
+            .map(|(index, head)| (self.current_messages[index].id.clone(), head))
+            .collect();
+        let mut switch_branch: Option<usize> = None;
+        let tool_results = pair_tool_results(&msgs);
+        let attached_results: std::collections::HashSet<usize> =
+            tool_results.values().copied().collect();
+        let display_prefs = self.display_prefs.clone();
+        let conv_id = conv_id.to_string();
+        let mut bookmark_actions: Vec<BookmarkAction> = Vec::new();
+        let message_spacing = self.message_spacing;
+        let message_padding = self.density.message_padding();
+        let relative_timestamps = self.relative_timestamps;
+        let code_wrap = self.code_wrap;
+        let scroll_target = self.scroll_to_message_id.clone();
+        let thinking_colors = pref_colors(&display_prefs, "thinking");
+        let page_state = self.paged_conversations.get(&conv_id).copied();
+        let mut load_more = false;
 
-        ui.vertical_centered(|ui| {
-            ui.add_space(ui.available_height() / 4.0);
+        let scroll_area = egui::ScrollArea::vertical().auto_shrink([false; 2]);
+        let show_day_separators = days.len() > 1;
+        let mut last_day: Option<chrono::NaiveDate> = None;
 
-            ui.label(RichText::new("CURSOR").size(42.0).color(theme.fg).strong());
-            ui.label(
-                RichText::new("STUDIO")
-                    .size(42.0)
-                    .color(theme.accent)
-                    .strong(),
-            );
+        scroll_area.show(ui, |ui| {
             ui.add_space(8.0);
-            ui.label(
-                RichText::new("Open Source Cursor IDE Manager")
-                    .size(14.0)
-                    .color(theme.fg_dim),
-            );
-            ui.add_space(4.0);
-            ui.label(
-                RichText::new("v0.2.0-rc1")
-                    .size(11.0)
-                    .color(theme.accent.linear_multiply(0.7)),
-            );
 
-            ui.add_space(24.0);
-
-            // Stats - use columns for reliable layout
-            let version_count = self.versions.len();
-            ui.columns(4, |cols| {
-                // Chats
-                cols[0].vertical_centered(|ui| {
+            if msgs.is_empty() {
+                ui.vertical_centered(|ui| {
+                    ui.add_space(40.0);
                     ui.label(
-                        RichText::new(format!("{}", total))
-                            .size(28.0)
-                            .color(theme.accent)
-                            .strong(),
+                        RichText::new("No messages found")
+                            .color(theme.fg_dim)
+                            .italics(),
                     );
-                    ui.label(RichText::new("Chats").size(11.0).color(theme.fg_dim));
                 });
+            }
 
-                // Messages
-                cols[1].vertical_centered(|ui| {
-                    ui.label(
-                        RichText::new(format!("{}", messages))
-                            .size(28.0)
-                            .color(theme.fg)
-                            .strong(),
-                    );
-                    ui.label(RichText::new("Messages").size(11.0).color(theme.fg_dim));
-                });
+            for (index, msg) in msgs.iter().enumerate() {
+                // Results paired with a call are drawn inside the call's block
+                if attached_results.contains(&index) {
+                    continue;
+                }
+                let tool_result = tool_results.get(&index).map(|&r| &msgs[r]);
+
+                // Inline day separator when the date changes
+                if show_day_separators {
+                    if let Some(&day) = conversation_days.message_days.get(&msg.id) {
+                        if last_day != Some(day) {
+                            last_day = Some(day);
+                            ui.add_space(message_spacing);
+                            day_separator(ui, day, theme);
+                        }
+                    }
+                }
 
-                // Favorites
-                cols[2].vertical_centered(|ui| {
-                    ui.label(
-                        RichText::new(format!("{}", favorites))
-                            .size(28.0)
-                            .color(Color32::from_rgb(255, 215, 0))
-                            .strong(),
-                    );
-                    ui.label(RichText::new("Favorites").size(11.0).color(theme.fg_dim));
-                });
+                ui.add_space(message_spacing);
 
-                // Versions
-                cols[3].vertical_centered(|ui| {
-                    ui.label(
-                        RichText::new(format!("{}", version_count))
-                            .size(28.0)
-                            .color(theme.success)
-                            .strong(),
-                    );
-                    ui.label(RichText::new("Versions").size(11.0).color(theme.fg_dim));
-                });
-            });
+                // Check if this message is the scroll target
+                let is_scroll_target = scroll_target
+                    .as_ref()
+                    .map(|id| id == &msg.id || tool_result.is_some_and(|r| &r.id == id))
+                    .unwrap_or(false);
 
-            // Show current launch version
-            ui.add_space(16.0);
-            let launch_display = Self::version_display_name(&self.launch_version);
-            ui.label(
-                RichText::new(format!("🚀 {}", launch_display))
-                    .size(12.0)
-                    .color(theme.accent),
-            );
+                // If this is the scroll target, scroll to it and highlight
+                if is_scroll_target {
+                    ui.scroll_to_cursor(Some(egui::Align::Center));
+                }
+
+                // Determine alignment based on role AND display preferences
+                let is_user = matches!(msg.role, MessageRole::User);
+                let max_width = ui.available_width() * 0.66; // Messages take 2/3 of tab width
 
-            ui.add_space(32.0);
+                // Get alignment from display preferences
+                let content_type_key = match msg.role {
+                    MessageRole::User => "user",
+                    MessageRole::Assistant => "assistant",
+                    MessageRole::ToolCall | MessageRole::ToolResult => "tool_call",
+                };
+                let alignment = display_prefs
+                    .iter()
+                    .find(|p| p.content_type == content_type_key)
+                    .map(|p| p.alignment.as_str())
+                    .unwrap_or(if is_user { "right" } else { "left" });
 
-            // Buttons - use columns for proper centering
-            ui.columns(3, |cols| {
-                // Import button
-                cols[0].vertical_centered(|ui| {
-                    if self.import_in_progress {
-                        ui.horizontal(|ui| {
-                            ui.add(egui::Spinner::new().size(16.0));
-                            ui.label(RichText::new("...").color(theme.accent));
-                        });
-                        ui.ctx().request_repaint();
-                    } else if styled_button_accent(ui, "⬇ Import", Vec2::new(100.0, 32.0), theme)
-                        .clicked()
-                    {
-                        if !self.import_warning_shown {
-                            self.import_warning_shown = true;
-                            self.set_status("⚠️ Click again to confirm");
-                        } else {
-                            do_import = true;
-                            self.import_warning_shown = false;
+                let use_right_align = alignment == "right";
+                let use_center_align = alignment == "center";
+
+                // Per-role color overrides from Settings, applied over the theme
+                let (fg_override, bg_override) = pref_colors(&display_prefs, content_type_key);
+                let body_theme = Theme {
+                    fg: fg_override.unwrap_or(theme.fg),
+                    ..theme
+                };
+
+                // Determine icon, label, and color based on role and tool call
+                let (icon, label, color) = if msg.tool_call.is_some() {
+                    let tc = msg.tool_call.as_ref().unwrap();
+                    let status_icon = match tc.status.as_str() {
+                        "completed" => "✓",
+                        "running" => "⏳",
+                        "error" => "✗",
+                        _ => "•",
+                    };
+                    (
+                        format!("🔧{}", status_icon),
+                        format!("TOOL: {}", tc.name),
+                        theme.warning,
+                    )
+                } else {
+                    match msg.role {
+                        MessageRole::User => ("👤".to_string(), "USER".to_string(), theme.accent),
+                        MessageRole::Assistant => {
+                            ("🤖".to_string(), "ASSISTANT".to_string(), theme.success)
+                        }
+                        MessageRole::ToolCall => {
+                            ("🔧".to_string(), "TOOL CALL".to_string(), theme.warning)
+                        }
+                        MessageRole::ToolResult => {
+                            ("📋".to_string(), "TOOL RESULT".to_string(), theme.fg_dim)
                         }
                     }
-                });
+                };
+
+                let color = fg_override.unwrap_or(color);
+
+                let timestamp = msg
+                    .created_at
+                    .as_deref()
+                    .and_then(|ts| format_timestamp(ts, relative_timestamps));
+
+                // An already bookmarked message offers removal instead of a second bookmark
+                let existing_bookmark = self.is_bookmarked(&msg.id).map(|b| b.id.clone());
+                let is_bookmarked = existing_bookmark.is_some();
+                let msg_id = msg.id.clone();
+                let conv_id_clone = conv_id.to_string();
+                let msg_seq = msg.sequence;
+
+                // Switcher above messages that start one of several branches
+                if let Some(&(point, pos)) = branch_heads.get(&msg.id) {
+                    let children = &tree.points[point].children;
+                    ui.horizontal(|ui| {
+                        if use_right_align {
+                            ui.add_space((ui.available_width() - 110.0).max(16.0));
+                        } else {
+                            ui.add_space(16.0);
+                        }
+                        let prev = ui.add_enabled(pos > 0, egui::Button::new("◀").small());
+                        accessible_label(ui, &prev, "Previous branch");
+                        ui.label(
+                            RichText::new(format!("🔀 {}/{}", pos + 1, children.len()))
+                                .color(theme.fg_dim)
+                                .size(theme.text_size(10.0)),
+                        );
+                        let next = ui
+                            .add_enabled(pos + 1 < children.len(), egui::Button::new("▶").small());
+                        accessible_label(ui, &next, "Next branch");
+                        if prev.clicked() {
+                            switch_branch = Some(children[pos - 1]);
+                        } else if next.clicked() {
+                            switch_branch = Some(children[pos + 1]);
+                        }
+                    });
+                }
+
+                // === RIGHT-ALIGNED MESSAGES (bubble style) ===
+                // Position box on right, but keep internal content left-to-right
+                if use_right_align {
+                    ui.horizontal(|ui| {
+                        // Spacer pushes content to the right
+                        let available = ui.available_width();
+                        let box_width = max_width.min(available * 0.75);
+                        ui.add_space(available - box_width - 16.0);
+
+                        // Frame with normal left-to-right layout inside
+                        egui::Frame::none()
+                            .fill(bg_override.unwrap_or(theme.accent.linear_multiply(0.15)))
+                            .rounding(Rounding::same(12.0))
+                            .inner_margin(message_padding)
+                            .show(ui, |ui| {
+                                ui.set_max_width(box_width);
+                                // Force left-to-right layout inside the box
+                                ui.with_layout(egui::Layout::top_down(egui::Align::LEFT), |ui| {
+                                    // Header row with bookmark button
+                                    ui.horizontal(|ui| {
+                                        // Bookmark indicator/button
+                                        let bookmark_icon =
+                                            if is_bookmarked { "🔖" } else { "⭐" };
+                                        let bookmark_color = if is_bookmarked {
+                                            Color32::from_rgb(255, 215, 0)
+                                        } else {
+                                            Color32::from_rgb(100, 100, 100)
+                                        };
+                                        let bookmark_btn = ui
+                                            .add(
+                                                egui::Button::new(
+                                                    RichText::new(bookmark_icon)
+                                                        .color(bookmark_color)
+                                                        .size(14.0),
+                                                )
+                                                .frame(false)
+                                                .min_size(Vec2::new(20.0, 20.0)),
+                                            )
+                                            .on_hover_text(if is_bookmarked {
+                                                "Remove bookmark"
+                                            } else {
+                                                "Add bookmark"
+                                            });
+
+                                        if bookmark_btn.hovered() {
+                                            ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+                                        }
+
+                                        if bookmark_btn.clicked() {
+                                            if let Some(bookmark_id) = &existing_bookmark {
+                                                bookmark_actions.push(BookmarkAction::Remove(
+                                                    bookmark_id.clone(),
+                                                    conv_id_clone.clone(),
+                                                ));
+                                            } else {
+                                                bookmark_actions.push(BookmarkAction::Add(
+                                                    conv_id_clone.clone(),
+                                                    msg_id.clone(),
+                                                    msg_seq,
+                                                ));
+                                            }
+                                        }
 
-                // Reimport button
-                cols[1].vertical_centered(|ui| {
-                    if styled_button(ui, "🔄 Reimport", Vec2::new(100.0, 32.0)).clicked() {
-                        self.do_clear_and_reimport();
-                    }
-                });
+                                        ui.label(
+                                            RichText::new(format!("{} {}", icon, label))
+                                                .color(color)
+                                                .strong()
+                                                .size(theme.text_size(11.0)),
+                                        );
+                                        timestamp_label(ui, timestamp.as_ref(), theme);
+                                    });
 
-                // Launch button
-                cols[2].vertical_centered(|ui| {
-                    if styled_button_accent(ui, "▶ Launch", Vec2::new(100.0, 32.0), theme).clicked()
-                    {
-                        do_launch = true;
-                    }
-                });
-            });
+                                    ui.add_space(4.0);
 
-            // Warning hint
-            if self.import_warning_shown {
-                ui.vertical_centered(|ui| {
-                    ui.label(
-                        RichText::new("⚠️ Click Import again to confirm")
-                            .color(theme.warning)
-                            .size(10.0),
-                    );
-                });
-            }
+                                    // Render full message body (tool calls, thinking, content)
+                                    render_message_body(
+                                        ui,
+                                        msg,
+                                        tool_result,
+                                        body_theme,
+                                        code_wrap,
+                                        thinking_colors,
+                                    );
+                                });
+                            });
 
-            ui.add_space(24.0);
+                        ui.add_space(16.0);
+                    });
+                    continue; // Skip the rest for right-aligned messages
+                }
 
-            // Quick tips - centered
-            ui.vertical_centered(|ui| {
-                ui.label(
-                    RichText::new("Quick Tips")
-                        .size(11.0)
-                        .color(theme.fg_dim)
-                        .strong(),
-                );
-                ui.add_space(4.0);
-                ui.label(
-                    RichText::new("Sidebar: Versions • Search • Settings")
-                        .size(10.0)
-                        .color(theme.fg_dim),
-                );
-                ui.label(
-                    RichText::new("Click version → set default • Drag edges → resize")
-                        .size(10.0)
-                        .color(theme.fg_dim),
-                );
-            });
-        });
+                // === CENTER-ALIGNED MESSAGES ===
+                if use_center_align {
+                    let box_width = max_width.min(ui.available_width() * 0.66);
 
-        if do_import {
-            self.do_import();
-        }
+                    // Use vertical_centered for proper centering
+                    ui.vertical_centered(|ui| {
+                        egui::Frame::none()
+                            .fill(bg_override.unwrap_or(theme.sidebar_bg))
+                            .rounding(Rounding::same(8.0))
+                            .inner_margin(message_padding)
+                            .show(ui, |ui| {
+                                ui.set_max_width(box_width);
+                                // Force left-to-right layout inside
+                                ui.with_layout(egui::Layout::top_down(egui::Align::LEFT), |ui| {
+                                    // Header with bookmark
+                                    ui.horizontal(|ui| {
+                                        let bookmark_icon =
+                                            if is_bookmarked { "🔖" } else { "⭐" };
+                                        let bookmark_color = if is_bookmarked {
+                                            Color32::from_rgb(255, 215, 0)
+                                        } else {
+                                            Color32::from_rgb(100, 100, 100)
+                                        };
+                                        let bookmark_btn = ui.add(
+                                            egui::Button::new(
+                                                RichText::new(bookmark_icon)
+                                                    .color(bookmark_color)
+                                                    .size(14.0),
+                                            )
+                                            .frame(false)
+                                            .min_size(Vec2::new(20.0, 20.0)),
+                                        );
+                                        if bookmark_btn.clicked() {
+                                            if let Some(bookmark_id) = &existing_bookmark {
+                                                bookmark_actions.push(BookmarkAction::Remove(
+                                                    bookmark_id.clone(),
+                                                    conv_id_clone.clone(),
+                                                ));
+                                            } else {
+                                                bookmark_actions.push(BookmarkAction::Add(
+                                                    conv_id_clone.clone(),
+                                                    msg_id.clone(),
+                                                    msg_seq,
+                                                ));
+                                            }
+                                        }
 
-        if do_launch {
-            self.launch_cursor();
-        }
-    }
+                                        ui.label(
+                                            RichText::new(format!("{} {}", icon, label))
+                                                .color(color)
+                                                .strong()
+                                                .size(12.0),
+                                        );
+                                        timestamp_label(ui, timestamp.as_ref(), theme);
+                                    });
+                                    ui.add_space(4.0);
 
-    fn show_conversation_tab(&mut self, ui: &mut egui::Ui, theme: Theme, conv_id: &str) {
-        // Load messages and bookmarks if switching conversations
-        let needs_reload = self.current_messages.is_empty()
-            || self.current_messages.first().map(|m| &m.conversation_id)
-                != Some(&conv_id.to_string());
+                                    // Render full message body (tool calls, thinking, content)
+                                    render_message_body(
+                                        ui,
+                                        msg,
+                                        tool_result,
+                                        body_theme,
+                                        code_wrap,
+                                        thinking_colors,
+                                    );
+                                });
+                            });
+                    });
 
-        if needs_reload {
-            self.current_messages = self.db.get_messages(conv_id).unwrap_or_default();
-            self.current_bookmarks = self.db.get_bookmarks(conv_id).unwrap_or_default();
-        }
+                    ui.add_space(message_spacing);
+                    continue;
+                }
 
-        // Conversation header - modern card style
-        let bookmark_count = self.current_bookmarks.len();
-        let message_count = self.current_messages.len();
+                // === LEFT-ALIGNED MESSAGES ===
+                // Same box-based approach as center/right, but positioned on left
+                ui.horizontal(|ui| {
+                    // Small left margin for left-aligned messages
+                    ui.add_space(16.0);
 
-        // Clone conversation data to avoid borrow conflicts in closures
-        let conv_data = self
-            .conversations
-            .iter()
-            .find(|c| c.id == conv_id)
-            .map(|c| (c.title.clone(), c.source_version.clone(), c.is_favorite));
+                    let available = ui.available_width();
+                    let box_width = max_width.min(available * 0.66); // 2/3 width rule
 
-        let mut toggle_favorite = false;
-        let mut do_export_header = false;
+                    // Highlight for scroll target
+                    let base_fill = bg_override.unwrap_or(theme.sidebar_bg);
+                    let box_fill = if is_scroll_target {
+                        base_fill.linear_multiply(1.2)
+                    } else {
+                        base_fill
+                    };
 
-        if let Some((conv_title, conv_source, is_favorite)) = conv_data {
-            egui::Frame::none()
-                .fill(theme.sidebar_bg)
-                .inner_margin(egui::Margin::symmetric(16.0, 12.0))
-                .stroke(Stroke::new(1.0, theme.border))
-                .show(ui, |ui| {
-                    // Top row: Title and actions
-                    ui.horizontal(|ui| {
-                        // Title (truncated if needed)
-                        let title: String = if conv_title.len() > 50 {
-                            format!("{}...", conv_title.chars().take(50).collect::<String>())
+                    egui::Frame::none()
+                        .fill(box_fill)
+                        .rounding(Rounding::same(8.0))
+                        .inner_margin(message_padding)
+                        .stroke(if is_scroll_target {
+                            Stroke::new(2.0, theme.accent)
                         } else {
-                            conv_title.clone()
-                        };
-                        ui.label(
-                            RichText::new(title)
-                                .size(14.0)
-                                .color(theme.fg_bright)
-                                .strong(),
-                        );
-
-                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            // Export button
-                            if ui
-                                .add(egui::Button::new(RichText::new("⬇").size(12.0)).frame(false))
-                                .on_hover_text("Export conversation")
-                                .clicked()
-                            {
-                                self.export_conversation_to_markdown(conv_id);
-                            }
-
-                            // Bookmark panel toggle
-                            let bookmark_icon = if self.show_bookmark_panel {
-                                "📑"
-                            } else {
-                                "🔖"
-                            };
-                            if ui
-                                .add(
-                                    egui::Button::new(RichText::new(bookmark_icon).size(12.0))
-                                        .frame(false),
-                                )
-                                .on_hover_text(if self.show_bookmark_panel {
-                                    "Hide bookmarks"
-                                } else {
-                                    "Show bookmarks"
-                                })
-                                .clicked()
-                            {
-                                self.show_bookmark_panel = !self.show_bookmark_panel;
-                            }
+                            Stroke::NONE
+                        })
+                        .show(ui, |ui| {
+                            ui.set_max_width(box_width);
+                            // Force left-to-right layout inside
+                            ui.with_layout(egui::Layout::top_down(egui::Align::LEFT), |ui| {
+                                // Header with bookmark
+                                ui.horizontal(|ui| {
+                                    let bookmark_icon = if is_bookmarked { "🔖" } else { "⭐" };
+                                    let bookmark_color = if is_bookmarked {
+                                        Color32::from_rgb(255, 215, 0)
+                                    } else {
+                                        Color32::from_rgb(100, 100, 100)
+                                    };
+                                    let bookmark_btn = ui
+                                        .add(
+                                            egui::Button::new(
+                                                RichText::new(bookmark_icon)
+                                                    .color(bookmark_color)
+                                                    .size(14.0),
+                                            )
+                                            .frame(false)
+                                            .min_size(Vec2::new(20.0, 20.0)),
+                                        )
+                                        .on_hover_text(if is_bookmarked {
+                                            "Remove bookmark"
+                                        } else {
+                                            "Add bookmark"
+                                        });
 
-                            // Favorite toggle
-                            let fav_icon = if is_favorite { "★" } else { "☆" };
-                            let fav_color = if is_favorite {
-                                Color32::from_rgb(255, 215, 0)
-                            } else {
-                                theme.fg_dim
-                            };
-                            if ui
-                                .add(
-                                    egui::Button::new(
-                                        RichText::new(fav_icon).color(fav_color).size(12.0),
-                                    )
-                                    .frame(false),
-                                )
-                                .on_hover_text(if is_favorite {
-                                    "Remove from favorites"
-                                } else {
-                                    "Add to favorites"
-                                })
-                                .clicked()
-                            {
-                                toggle_favorite = true;
-                            }
-                        });
-                    });
+                                    if bookmark_btn.hovered() {
+                                        ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+                                    }
 
-                    ui.add_space(6.0);
+                                    if bookmark_btn.clicked() {
+                                        if let Some(bookmark_id) = &existing_bookmark {
+                                            bookmark_actions.push(BookmarkAction::Remove(
+                                                bookmark_id.clone(),
+                                                conv_id_clone.clone(),
+                                            ));
+                                        } else {
+                                            bookmark_actions.push(BookmarkAction::Add(
+                                                conv_id_clone.clone(),
+                                                msg_id.clone(),
+                                                msg_seq,
+                                            ));
+                                        }
+                                    }
 
-                    // Stats row
-                    ui.horizontal(|ui| {
-                        // Message count
-                        ui.label(
-                            RichText::new(format!("💬 {}", message_count))
-                                .color(theme.fg_dim)
-                                .size(11.0),
-                        );
+                                    ui.label(
+                                        RichText::new(format!("{} {}", icon, label))
+                                            .color(color)
+                                            .strong()
+                                            .size(12.0),
+                                    );
+                                    timestamp_label(ui, timestamp.as_ref(), theme);
+                                });
 
-                        ui.add_space(12.0);
+                                ui.add_space(4.0);
 
-                        // Bookmark count (with loading indicator if 0 but might be loading)
-                        if bookmark_count > 0 {
-                            ui.label(
-                                RichText::new(format!("🔖 {}", bookmark_count))
-                                    .color(Color32::from_rgb(255, 215, 0))
-                                    .size(11.0),
-                            );
-                        }
+                                // Render full message body (tool calls, thinking, content)
+                                render_message_body(
+                                    ui,
+                                    msg,
+                                    tool_result,
+                                    body_theme,
+                                    code_wrap,
+                                    thinking_colors,
+                                );
+                            });
+                        });
+                });
 
-                        ui.add_space(12.0);
+                ui.add_space(message_spacing);
+            }
 
-                        // Source version
-                        ui.label(
-                            RichText::new(format!("📁 {}", conv_source))
-                                .color(theme.fg_dim)
-                                .size(10.0),
-                        );
-                    });
+            // Paged conversations: fetch the next window on request
+            if let Some((loaded, total)) = page_state.filter(|(loaded, total)| loaded < total) {
+                ui.add_space(message_spacing);
+                ui.vertical_centered(|ui| {
+                    let label = format!(
+                        "⬇ Load {} more ({} of {} shown)",
+                        MESSAGE_PAGE_SIZE.min(total - loaded),
+                        format_count(loaded),
+                        format_count(total)
+                    );
+                    if ui.button(label).clicked() {
+                        load_more = true;
+                    }
                 });
+            }
 
-            // Handle actions outside the closure to avoid borrow issues
-            if toggle_favorite {
-                let _ = self.db.toggle_favorite(conv_id);
-                self.conversations = self.db.get_conversations(50).unwrap_or_default();
+            ui.add_space(16.0);
+        });
+
+        if load_more {
+            self.load_more_messages(&conv_id);
+        }
+
+        if let Some(index) = switch_branch {
+            tree.reveal(&self.current_messages, &mut self.branch_choices, index);
+        }
+
+        // Process bookmark actions after UI rendering
+        for action in bookmark_actions {
+            match action {
+                BookmarkAction::Add(cid, mid, seq) => {
+                    self.add_bookmark(&cid, &mid, seq);
+                }
+                BookmarkAction::Remove(bid, cid) => {
+                    self.delete_bookmark(&bid, &cid);
+                }
             }
+        }
 
-            ui.add_space(8.0);
+        // Clear scroll target after rendering
+        if self.scroll_to_message_id.is_some() {
+            self.scroll_to_message_id = None;
         }
+    }
 
-        // Toolbar: Export and Search
-        let mut do_export = false;
-        let mut do_search = false;
-        let mut search_query_changed = false;
+    fn show_status_bar(&mut self, ui: &mut egui::Ui) {
+        let font_size = self.theme.text_size(self.status_bar_font_size);
 
-        ui.horizontal(|ui| {
-            ui.add_space(16.0);
+        // Check for async import progress
+        if let Some(rx) = &self.import_receiver {
+            if let Ok(progress) = rx.try_recv() {
+                match progress {
+                    ImportProgress::Started(total) => {
+                        self.import_progress = Some((0, total));
+                        self.set_status(&format!(
+                            "⏳ Starting import of {} conversations...",
+                            total
+                        ));
+                    }
+                    ImportProgress::Processing(current, total) => {
+                        self.import_progress = Some((current, total));
+                        // Request repaint to show progress
+                        ui.ctx().request_repaint();
+                    }
+                    ImportProgress::Paused(done, total) => {
+                        self.import_paused = true;
+                        self.set_status(&format!(
+                            "⏸ Import paused after {} of {} databases",
+                            done, total
+                        ));
+                    }
+                    ImportProgress::Resumed => {
+                        self.import_paused = false;
+                    }
+                    ImportProgress::Deduplicated(duplicates) => {
+                        self.import_duplicates = duplicates;
+                    }
+                    ImportProgress::Added(ids) => {
+                        self.import_new_ids = ids;
+                    }
+                    ImportProgress::Completed(imported, skipped) => {
+                        let duplicates = std::mem::take(&mut self.import_duplicates);
+                        let duplicate_note = match duplicates {
+                            0 => String::new(),
+                            1 => ", 1 duplicate skipped".to_string(),
+                            n => format!(", {} duplicates skipped", n),
+                        };
+                        self.import_progress = None;
+                        self.import_in_progress = false;
+                        self.import_receiver = None;
+                        self.refresh_all(); // Full refresh including bookmarks
 
-            // Export button
-            if ui
-                .small_button("📤 Export")
-                .on_hover_text("Export to Markdown")
-                .clicked()
-            {
-                do_export = true;
-            }
+                        let new_ids = std::mem::take(&mut self.import_new_ids);
+                        if imported > 0 {
+                            // The most recently active of the conversations just added
+                            let newest = new_ids
+                                .iter()
+                                .filter_map(|id| self.db.get_conversation(id).ok().flatten())
+                                .max_by(|a, b| a.updated_at.cmp(&b.updated_at))
+                                .map(|c| c.id);
+                            self.push_toast(
+                                format!(
+                                    "📥 {} new conversation{} imported",
+                                    imported,
+                                    if imported == 1 { "" } else { "s" }
+                                ),
+                                newest,
+                            );
+                        }
 
-            ui.add_space(8.0);
+                        // Reattach bookmarks and restore favorites if this was a clear & reimport
+                        if self.import_needs_bookmark_reattach {
+                            self.import_needs_bookmark_reattach = false;
+                            let mut reattached = 0;
+                            let mut failed = 0;
+                            for conv in self.all_conversations() {
+                                if let Ok(results) = self.db.reattach_bookmarks(&conv.id) {
+                                    for (_, success) in results {
+                                        if success {
+                                            reattached += 1;
+                                        } else {
+                                            failed += 1;
+                                        }
+                                    }
+                                }
+                            }
 
-            // Search box
-            ui.label(RichText::new("🔍").size(12.0));
-            let search_response = ui.add(
-                egui::TextEdit::singleline(&mut self.conv_search_query)
-                    .desired_width(150.0)
-                    .hint_text("Search in chat...")
-                    .font(egui::FontId::proportional(11.0)),
-            );
+                            // Restore favorites
+                            let favorites_restored = self.db.restore_favorites().unwrap_or(0);
+                            if favorites_restored > 0 {
+                                self.refresh_chats(); // Refresh to show restored favorites
+                            }
 
-            if search_response.changed() {
-                search_query_changed = true;
+                            self.set_status(&format!(
+                                "✓ Imported {} chats, {} favorites, {} bookmarks restored",
+                                imported, favorites_restored, reattached
+                            ));
+                            return;
+                        }
+
+                        if imported > 0 {
+                            self.set_status(&format!(
+                                "✓ Imported {} new chats ({} already existed{})",
+                                imported, skipped, duplicate_note
+                            ));
+                        } else if skipped > 0 || duplicates > 0 {
+                            self.set_status(&format!(
+                                "All {} chats already imported{}",
+                                skipped, duplicate_note
+                            ));
+                        } else {
+                            self.set_status("No new chats found to import");
+                        }
+                    }
+                    ImportProgress::Salvaged(report) => {
+                        let message = format!(
+                            "⚠️ Salvaged {} chats; {} chats and {} messages were unreadable",
+                            report.imported, report.lost_conversations, report.lost_messages
+                        );
+                        self.set_status(&message);
+                        self.push_toast(message, None);
+                    }
+                    ImportProgress::Locked(versions) => {
+                        let names: Vec<String> = versions
+                            .iter()
+                            .map(|v| Self::version_display_name(v))
+                            .collect();
+                        let message = format!(
+                            "⚠️ {} was open: close Cursor and import again for its newest chats",
+                            names.join(", ")
+                        );
+                        self.set_status(&message);
+                        self.push_toast(message, None);
+                    }
+                    ImportProgress::SourceFailed(version, e) => {
+                        let message = format!(
+                            "✗ Couldn't import {}: {}",
+                            Self::version_display_name(&version),
+                            e
+                        );
+                        self.last_import_error = Some(message.clone());
+                        self.set_status(&message);
+                        self.push_toast(message, None);
+                    }
+                    ImportProgress::Error(e) => {
+                        self.import_progress = None;
+                        self.import_in_progress = false;
+                        self.import_receiver = None;
+                        self.last_import_error = Some(e.clone());
+                        self.set_status(&format!("✗ Import error: {}", e));
+                    }
+                }
             }
+        }
 
-            // Enter to search
-            if search_response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-                do_search = true;
+        ui.horizontal(|ui| {
+            // Show import progress if active
+            let paused_at = self.import_progress.filter(|_| self.import_paused);
+            if let Some((current, total)) = paused_at {
+                ui.label(
+                    RichText::new(format!("⏸ Import paused at {}/{}", current, total))
+                        .color(Color32::from_rgb(100, 200, 255))
+                        .size(font_size),
+                );
+            } else if let Some((current, total)) = self.import_progress {
+                let pct = if total > 0 { (current as f32 / total as f32) * 100.0 } else { 0.0 };
+                ui.add(egui::Spinner::new().size(font_size));
+                ui.add_space(4.0);
+                ui.label(
+                    RichText::new(format!("Importing... {}/{} ({:.0}%)", current, total, pct))
+                        .color(Color32::from_rgb(100, 200, 255))
+                        .size(font_size),
+                );
+            } else if let Some(msg) = &self.status_message {
+                let status = ui
+                    .add(
+                        egui::Label::new(RichText::new(msg).color(Color32::WHITE).size(font_size))
+                            .sense(egui::Sense::click()),
+                    )
+                    .on_hover_text("Click for recent messages");
+                if status.clicked() {
+                    self.show_status_history = !self.show_status_history;
+                }
+            } else {
+                // Show detailed stats
+                if let Ok(stats) = self.db.get_detailed_stats() {
+                    let (total, _, _) = self
+                        .db
+                        .get_stats(!self.stats_exclude_archived)
+                        .unwrap_or((0, 0, 0));
+                    ui.label(
+                        RichText::new(format!(
+                            "📊 {} chats • 👤 {} user • 🤖 {} AI • 🔧 {} tools • 💭 {} thinking • 📝 {} code • 🔖 {} bookmarks",
+                            total,
+                            stats.user_messages,
+                            stats.assistant_messages,
+                            stats.tool_calls,
+                            stats.with_thinking,
+                            stats.with_code,
+                            stats.bookmarks,
+                        ))
+                        .color(Color32::WHITE)
+                        .size(font_size),
+                    );
+                } else {
+                    let (total, messages, _) = self
+                        .db
+                        .get_stats(!self.stats_exclude_archived)
+                        .unwrap_or((0, 0, 0));
+                    ui.label(
+                        RichText::new(format!("📊 {} chats • 💬 {} messages", total, messages))
+                            .color(Color32::WHITE)
+                            .size(font_size),
+                    );
+                }
             }
 
-            // Search nav buttons
-            if !self.conv_search_results.is_empty() {
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                 ui.label(
-                    RichText::new(format!(
-                        "{}/{}",
-                        self.conv_search_index + 1,
-                        self.conv_search_results.len()
-                    ))
-                    .size(10.0)
-                    .color(theme.fg_dim),
+                    RichText::new("Cursor Studio v0.2.0")
+                        .color(Color32::WHITE)
+                        .size(font_size),
                 );
 
-                if ui
-                    .small_button("◀")
-                    .on_hover_text("Previous result")
-                    .clicked()
-                {
-                    self.jump_to_prev_search_result();
+                if !self.status_history.is_empty() {
+                    ui.add_space(12.0);
+                    let history_btn = ui
+                        .add(
+                            egui::Label::new(
+                                RichText::new(format!("🕘 {}", self.status_history.len()))
+                                    .color(Color32::WHITE)
+                                    .size(font_size),
+                            )
+                            .sense(egui::Sense::click()),
+                        )
+                        .on_hover_text("Recent status messages");
+                    if history_btn.hovered() {
+                        ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+                    }
+                    if history_btn.clicked() {
+                        self.show_status_history = !self.show_status_history;
+                    }
                 }
-                if ui.small_button("▶").on_hover_text("Next result").clicked() {
-                    self.jump_to_next_search_result();
+
+                ui.add_space(12.0);
+                let logs_btn = ui
+                    .add(
+                        egui::Label::new(
+                            RichText::new("📜 Logs")
+                                .color(Color32::WHITE)
+                                .size(font_size),
+                        )
+                        .sense(egui::Sense::click()),
+                    )
+                    .on_hover_text("Show recent log messages");
+                if logs_btn.hovered() {
+                    ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+                }
+                if logs_btn.clicked() {
+                    self.log_viewer_open = !self.log_viewer_open;
+                }
+
+                if let Some(export) = self.last_export.clone() {
+                    self.show_last_export_links(ui, &export, font_size);
                 }
-            }
 
-            // Search button
-            if ui.small_button("Find").clicked() {
-                do_search = true;
-            }
+                if let Some((shown, absolute)) = self
+                    .last_auto_import
+                    .as_deref()
+                    .and_then(|ts| format_timestamp(ts, self.relative_timestamps))
+                {
+                    ui.add_space(12.0);
+                    ui.label(
+                        RichText::new(format!("⟳ auto-import {}", shown))
+                            .color(Color32::WHITE)
+                            .size(font_size),
+                    )
+                    .on_hover_text(format!("Last auto-import: {}", absolute));
+                }
+            });
         });
 
-        // Handle actions
-        if do_export {
-            self.export_conversation_to_markdown(conv_id);
-        }
-
-        if do_search || (search_query_changed && self.conv_search_query.len() >= 2) {
-            let query = self.conv_search_query.clone();
-            self.search_in_conversation(&query);
-        }
-
-        ui.add_space(8.0);
-
-        // Bookmark panel (if visible)
-        if self.show_bookmark_panel && !self.current_bookmarks.is_empty() {
-            egui::Frame::none()
-                .fill(theme.sidebar_bg)
-                .inner_margin(8.0)
-                .rounding(Rounding::same(4.0))
-                .show(ui, |ui| {
-                    ui.horizontal(|ui| {
-                        ui.label(
-                            RichText::new("📑 Bookmarks")
-                                .color(theme.fg)
-                                .strong()
-                                .size(12.0),
-                        );
-                    });
-                    ui.add_space(4.0);
+        self.status_message = None;
+    }
 
-                    let bookmarks = self.current_bookmarks.clone();
-                    let mut jump_to: Option<(String, String)> = None;
+    /// Open-folder/file/terminal links for the latest export (right-to-left layout)
+    fn show_last_export_links(&mut self, ui: &mut egui::Ui, export: &LastExport, font_size: f32) {
+        let link = |ui: &mut egui::Ui, text: &str, hover: String| {
+            let response = ui
+                .add(
+                    egui::Label::new(RichText::new(text).color(Color32::WHITE).size(font_size))
+                        .sense(egui::Sense::click()),
+                )
+                .on_hover_text(hover);
+            if response.hovered() {
+                ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+            }
+            response.clicked()
+        };
 
-                    for bookmark in &bookmarks {
-                        ui.horizontal(|ui| {
-                            // Bookmark color indicator
-                            let color = Color32::from_rgb(255, 215, 0);
-                            ui.painter().circle_filled(
-                                ui.cursor().min + Vec2::new(6.0, 8.0),
-                                4.0,
-                                color,
-                            );
-                            ui.add_space(12.0);
+        ui.add_space(12.0);
+        if link(ui, "✕", "Hide export links".to_string()) {
+            self.last_export = None;
+        }
+        let folder = export.folder.display();
+        if link(ui, "⌨ Terminal", format!("Open a terminal in {}", folder)) {
+            if let Err(e) = open_terminal_in(&export.folder) {
+                self.set_status(&format!("✗ Failed to open terminal: {}", e));
+            }
+        }
+        if let Some(file) = &export.file {
+            let hover = format!("Open {} in its default app", file.display());
+            if link(ui, "📄 Open file", hover) {
+                if let Err(e) = open_with_default_app(file) {
+                    self.set_status(&format!("✗ Failed to open: {}", e));
+                }
+            }
+        }
+        if link(ui, "📂 Open folder", folder.to_string()) {
+            if let Err(e) = open_with_default_app(&export.folder) {
+                self.set_status(&format!("✗ Failed to open: {}", e));
+            }
+        }
+        ui.label(
+            RichText::new("📤 Last export:")
+                .color(Color32::WHITE)
+                .size(font_size),
+        );
+    }
 
-                            // Label or default (create owned string first)
-                            let default_label = format!("Msg #{}", bookmark.message_sequence);
-                            let label = bookmark.label.as_deref().unwrap_or(&default_label);
-                            ui.label(RichText::new(label).color(theme.fg).size(11.0));
+    /// Recent log records, filtered by level, with a copy button for bug reports
+    fn show_log_viewer(&mut self, ui: &mut egui::Ui, theme: Theme) {
+        let entries = log_buffer::recent(self.log_level_filter);
 
-                            // Jump button
-                            if ui
-                                .small_button("→")
-                                .on_hover_text("Jump to message")
-                                .clicked()
-                            {
-                                jump_to = Some((
-                                    bookmark.conversation_id.clone(),
-                                    bookmark.message_id.clone(),
-                                ));
-                            }
-                        });
-                    }
+        ui.horizontal(|ui| {
+            ui.label(
+                RichText::new("LOGS")
+                    .size(theme.text_size(11.0))
+                    .color(theme.fg_dim)
+                    .strong(),
+            );
+            ui.add_space(8.0);
 
-                    // Process jump after UI iteration
-                    if let Some((cid, mid)) = jump_to {
-                        self.scroll_to_message_id = Some(mid);
+            egui::ComboBox::from_id_salt("log_level_filter")
+                .selected_text(self.log_level_filter.to_string())
+                .width(70.0)
+                .show_ui(ui, |ui| {
+                    for level in [
+                        log::LevelFilter::Error,
+                        log::LevelFilter::Warn,
+                        log::LevelFilter::Info,
+                    ] {
+                        ui.selectable_value(&mut self.log_level_filter, level, level.to_string());
                     }
                 });
-            ui.add_space(4.0);
-        }
 
-        ui.separator();
-
-        let msgs = self.current_messages.clone();
-        let bookmarks = self.current_bookmarks.clone();
-        let display_prefs = self.display_prefs.clone();
-        let conv_id = conv_id.to_string();
-        let mut bookmark_actions: Vec<BookmarkAction> = Vec::new();
-        let message_spacing = self.message_spacing;
-        let scroll_target = self.scroll_to_message_id.clone();
-
-        let scroll_area = egui::ScrollArea::vertical().auto_shrink([false; 2]);
+            ui.label(
+                RichText::new(format!("{} entries", entries.len()))
+                    .size(theme.text_size(10.0))
+                    .color(theme.fg_dim),
+            );
 
-        scroll_area.show(ui, |ui| {
-            ui.add_space(8.0);
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.small_button("✕").on_hover_text("Close").clicked() {
+                    self.log_viewer_open = false;
+                }
+                if ui.small_button("🗑 Clear").clicked() {
+                    log_buffer::clear();
+                }
+                if ui
+                    .small_button("📋 Copy logs")
+                    .on_hover_text("Copy the shown entries for a bug report")
+                    .clicked()
+                {
+                    let text = entries
+                        .iter()
+                        .map(|entry| entry.format_line())
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    ui.ctx().copy_text(text);
+                    self.set_status(&format!("✓ Copied {} log lines", entries.len()));
+                }
+            });
+        });
+        ui.add_space(4.0);
 
-            if msgs.is_empty() {
-                ui.vertical_centered(|ui| {
-                    ui.add_space(40.0);
+        egui::ScrollArea::vertical()
+            .auto_shrink([false; 2])
+            .stick_to_bottom(true)
+            .show(ui, |ui| {
+                if entries.is_empty() {
                     ui.label(
-                        RichText::new("No messages found")
+                        RichText::new("No log messages yet")
                             .color(theme.fg_dim)
-                            .italics(),
+                            .italics()
+                            .size(theme.text_size(11.0)),
                     );
-                });
-            }
-
-            for msg in &msgs {
-                ui.add_space(message_spacing);
-
-                // Check if this message is the scroll target
-                let is_scroll_target = scroll_target
-                    .as_ref()
-                    .map(|id| id == &msg.id)
-                    .unwrap_or(false);
-
-                // If this is the scroll target, scroll to it and highlight
-                if is_scroll_target {
-                    ui.scroll_to_cursor(Some(egui::Align::Center));
                 }
+                for entry in &entries {
+                    let color = match entry.level {
+                        log::Level::Error => theme.error,
+                        log::Level::Warn => theme.warning,
+                        _ => theme.fg_dim,
+                    };
+                    ui.label(
+                        RichText::new(entry.format_line())
+                            .color(color)
+                            .size(theme.text_size(10.0))
+                            .family(egui::FontFamily::Monospace),
+                    );
+                }
+            });
+    }
 
-                // Determine alignment based on role AND display preferences
-                let is_user = matches!(msg.role, MessageRole::User);
-                let max_width = ui.available_width() * 0.66; // Messages take 2/3 of tab width
-
-                // Get alignment from display preferences
-                let content_type_key = match msg.role {
-                    MessageRole::User => "user",
-                    MessageRole::Assistant => "assistant",
-                    MessageRole::ToolCall | MessageRole::ToolResult => "tool_call",
-                };
-                let alignment = display_prefs
-                    .iter()
-                    .find(|p| p.content_type == content_type_key)
-                    .map(|p| p.alignment.as_str())
-                    .unwrap_or(if is_user { "right" } else { "left" });
-
-                let use_right_align = alignment == "right";
-                let use_center_align = alignment == "center";
+    fn open_conversation(&mut self, conv_id: &str) {
+        // Ask before loading a huge conversation in one go
+        let already_open = self.tabs.contains(&Tab::Conversation(conv_id.to_string()));
+        if !already_open && !self.paged_conversations.contains_key(conv_id) {
+            let count = self.db.count_messages(conv_id).unwrap_or(0);
+            if count > LARGE_CONVERSATION_MESSAGES {
+                self.pending_large_conversation = Some((conv_id.to_string(), count));
+                return;
+            }
+        }
 
-                // Determine icon, label, and color based on role and tool call
-                let (icon, label, color) = if msg.tool_call.is_some() {
-                    let tc = msg.tool_call.as_ref().unwrap();
-                    let status_icon = match tc.status.as_str() {
-                        "completed" => "✓",
-                        "running" => "⏳",
-                        "error" => "✗",
-                        _ => "•",
-                    };
-                    (
-                        format!("🔧{}", status_icon),
-                        format!("TOOL: {}", tc.name),
-                        theme.warning,
-                    )
-                } else {
-                    match msg.role {
-                        MessageRole::User => ("👤".to_string(), "USER".to_string(), theme.accent),
-                        MessageRole::Assistant => {
-                            ("🤖".to_string(), "ASSISTANT".to_string(), theme.success)
-                        }
-                        MessageRole::ToolCall => {
-                            ("🔧".to_string(), "TOOL CALL".to_string(), theme.warning)
-                        }
-                        MessageRole::ToolResult => {
-                            ("📋".to_string(), "TOOL RESULT".to_string(), theme.fg_dim)
-                        }
-                    }
-                };
+        self.touch_recent_conversation(conv_id);
 
-                // Check if this message is bookmarked
-                let is_bookmarked = bookmarks.iter().any(|b| b.message_id == msg.id);
-                let msg_id = msg.id.clone();
-                let conv_id_clone = conv_id.to_string();
-                let msg_seq = msg.sequence;
+        for (i, tab) in self.tabs.iter().enumerate() {
+            if let Tab::Conversation(id) = tab {
+                if id == conv_id {
+                    self.active_tab = i;
+                    return;
+                }
+            }
+        }
 
-                // === RIGHT-ALIGNED MESSAGES (bubble style) ===
-                // Position box on right, but keep internal content left-to-right
-                if use_right_align {
-                    ui.horizontal(|ui| {
-                        // Spacer pushes content to the right
-                        let available = ui.available_width();
-                        let box_width = max_width.min(available * 0.75);
-                        ui.add_space(available - box_width - 16.0);
+        self.tabs.push(Tab::Conversation(conv_id.to_string()));
+        self.active_tab = self.tabs.len() - 1;
+        self.current_messages = self.load_messages(conv_id);
+    }
 
-                        // Frame with normal left-to-right layout inside
-                        egui::Frame::none()
-                            .fill(theme.accent.linear_multiply(0.15))
-                            .rounding(Rounding::same(12.0))
-                            .inner_margin(egui::Margin::symmetric(12.0, 8.0))
-                            .show(ui, |ui| {
-                                ui.set_max_width(box_width);
-                                // Force left-to-right layout inside the box
-                                ui.with_layout(egui::Layout::top_down(egui::Align::LEFT), |ui| {
-                                    // Header row with bookmark button
-                                    ui.horizontal(|ui| {
-                                        // Bookmark indicator/button
-                                        let bookmark_icon =
-                                            if is_bookmarked { "🔖" } else { "⭐" };
-                                        let bookmark_color = if is_bookmarked {
-                                            Color32::from_rgb(255, 215, 0)
-                                        } else {
-                                            Color32::from_rgb(100, 100, 100)
-                                        };
-                                        let bookmark_btn = ui
-                                            .add(
-                                                egui::Button::new(
-                                                    RichText::new(bookmark_icon)
-                                                        .color(bookmark_color)
-                                                        .size(14.0),
-                                                )
-                                                .frame(false)
-                                                .min_size(Vec2::new(20.0, 20.0)),
-                                            )
-                                            .on_hover_text(if is_bookmarked {
-                                                "Remove bookmark"
-                                            } else {
-                                                "Add bookmark"
-                                            });
+    /// Messages of a conversation; paged conversations only load their window
+    fn load_messages(&self, conv_id: &str) -> Vec<Message> {
+        match self.paged_conversations.get(conv_id) {
+            Some(&(loaded, _)) => self.db.get_messages_page(conv_id, 0, loaded),
+            None => self.db.get_messages(conv_id),
+        }
+        .unwrap_or_default()
+    }
 
-                                        if bookmark_btn.hovered() {
-                                            ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
-                                        }
+    /// Append the next page of a paged conversation
+    fn load_more_messages(&mut self, conv_id: &str) {
+        let Some((loaded, total)) = self.paged_conversations.get(conv_id).copied() else {
+            return;
+        };
+        let page = self
+            .db
+            .get_messages_page(conv_id, loaded, MESSAGE_PAGE_SIZE);
+        match page {
+            Ok(page) => {
+                let loaded = (loaded + page.len()).min(total);
+                self.current_messages.extend(page);
+                self.paged_conversations
+                    .insert(conv_id.to_string(), (loaded, total));
+            }
+            Err(e) => self.set_status(&format!("✗ Failed to load messages: {}", e)),
+        }
+    }
 
-                                        if bookmark_btn.clicked() {
-                                            if is_bookmarked {
-                                                if let Some(bm) = bookmarks
-                                                    .iter()
-                                                    .find(|b| b.message_id == msg_id)
-                                                {
-                                                    bookmark_actions.push(BookmarkAction::Remove(
-                                                        bm.id.clone(),
-                                                        conv_id_clone.clone(),
-                                                    ));
-                                                }
-                                            } else {
-                                                bookmark_actions.push(BookmarkAction::Add(
-                                                    conv_id_clone.clone(),
-                                                    msg_id.clone(),
-                                                    msg_seq,
-                                                ));
-                                            }
-                                        }
+    /// Confirmation before opening a conversation above `LARGE_CONVERSATION_MESSAGES`
+    fn show_large_conversation_prompt(&mut self, ctx: &egui::Context, theme: Theme) {
+        let Some((conv_id, count)) = self.pending_large_conversation.clone() else {
+            return;
+        };
 
-                                        ui.label(
-                                            RichText::new(format!("{} {}", icon, label))
-                                                .color(color)
-                                                .strong()
-                                                .size(11.0),
-                                        );
-                                    });
+        let mut open_paged = false;
+        let mut cancel = false;
+        egui::Area::new(egui::Id::new("large_conversation_prompt"))
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                warning_card_frame(theme, self.density).show(ui, |ui| {
+                    ui.set_width(380.0);
+                    ui.label(
+                        RichText::new(format!(
+                            "⚠ This conversation has {} messages — open anyway?",
+                            format_count(count)
+                        ))
+                        .color(theme.fg)
+                        .size(13.0),
+                    );
+                    ui.add_space(TIGHT_SPACING);
+                    ui.label(
+                        RichText::new(format!(
+                            "The first {} messages load now; more load on request.",
+                            MESSAGE_PAGE_SIZE
+                        ))
+                        .color(theme.fg_dim)
+                        .size(theme.text_size(11.0)),
+                    );
+                    ui.add_space(ELEMENT_SPACING);
+                    ui.horizontal(|ui| {
+                        let open = styled_button_accent(ui, "Open", Vec2::new(80.0, 28.0), theme);
+                        if open.clicked() {
+                            open_paged = true;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            cancel = true;
+                        }
+                    });
+                });
+            });
 
-                                    ui.add_space(4.0);
+        if open_paged {
+            self.pending_large_conversation = None;
+            self.paged_conversations
+                .insert(conv_id.clone(), (MESSAGE_PAGE_SIZE.min(count), count));
+            self.open_conversation(&conv_id);
+        } else if cancel || ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.pending_large_conversation = None;
+        }
+    }
 
-                                    // Render full message body (tool calls, thinking, content)
-                                    render_message_body(ui, msg, theme);
-                                });
-                            });
+    /// Move a conversation to the front of the recency list and persist it
+    fn touch_recent_conversation(&mut self, conv_id: &str) {
+        self.recent_conversations.retain(|id| id != conv_id);
+        self.recent_conversations.insert(0, conv_id.to_string());
+        self.recent_conversations.truncate(RECENT_CONVERSATIONS_MAX);
+        if let Ok(json) = serde_json::to_string(&self.recent_conversations) {
+            let _ = self.db.set_config("ui.recent_conversations", &json);
+        }
+    }
 
-                        ui.add_space(16.0);
-                    });
-                    continue; // Skip the rest for right-aligned messages
-                }
+    fn open_quick_switcher(&mut self) {
+        self.quick_switcher_items = self
+            .recent_conversations
+            .iter()
+            .filter_map(|id| match self.db.get_conversation(id) {
+                Ok(Some(conv)) => Some((conv.id, conv.title)),
+                _ => None,
+            })
+            .collect();
+        // Start on the previous conversation so a quick Ctrl+E, Enter toggles between two
+        self.quick_switcher_index = usize::from(self.quick_switcher_items.len() > 1);
+        self.show_quick_switcher = true;
+    }
 
-                // === CENTER-ALIGNED MESSAGES ===
-                if use_center_align {
-                    let box_width = max_width.min(ui.available_width() * 0.66);
+    /// Keyboard handling for the recent-conversations overlay
+    fn handle_quick_switcher_keys(&mut self, ctx: &egui::Context) {
+        let open_pressed = ctx.input_mut(|i| {
+            i.consume_key(egui::Modifiers::CTRL, egui::Key::E)
+                || i.consume_key(egui::Modifiers::CTRL, egui::Key::Tab)
+        });
 
-                    // Use vertical_centered for proper centering
-                    ui.vertical_centered(|ui| {
-                        egui::Frame::none()
-                            .fill(theme.sidebar_bg)
-                            .rounding(Rounding::same(8.0))
-                            .inner_margin(egui::Margin::symmetric(12.0, 8.0))
-                            .show(ui, |ui| {
-                                ui.set_max_width(box_width);
-                                // Force left-to-right layout inside
-                                ui.with_layout(egui::Layout::top_down(egui::Align::LEFT), |ui| {
-                                    // Header with bookmark
-                                    ui.horizontal(|ui| {
-                                        let bookmark_icon =
-                                            if is_bookmarked { "🔖" } else { "⭐" };
-                                        let bookmark_color = if is_bookmarked {
-                                            Color32::from_rgb(255, 215, 0)
-                                        } else {
-                                            Color32::from_rgb(100, 100, 100)
-                                        };
-                                        let bookmark_btn = ui.add(
-                                            egui::Button::new(
-                                                RichText::new(bookmark_icon)
-                                                    .color(bookmark_color)
-                                                    .size(14.0),
-                                            )
-                                            .frame(false)
-                                            .min_size(Vec2::new(20.0, 20.0)),
-                                        );
-                                        if bookmark_btn.clicked() {
-                                            if is_bookmarked {
-                                                if let Some(bm) = bookmarks
-                                                    .iter()
-                                                    .find(|b| b.message_id == msg_id)
-                                                {
-                                                    bookmark_actions.push(BookmarkAction::Remove(
-                                                        bm.id.clone(),
-                                                        conv_id_clone.clone(),
-                                                    ));
-                                                }
-                                            } else {
-                                                bookmark_actions.push(BookmarkAction::Add(
-                                                    conv_id_clone.clone(),
-                                                    msg_id.clone(),
-                                                    msg_seq,
-                                                ));
-                                            }
-                                        }
+        if !self.show_quick_switcher {
+            if open_pressed {
+                self.open_quick_switcher();
+            }
+            return;
+        }
 
-                                        ui.label(
-                                            RichText::new(format!("{} {}", icon, label))
-                                                .color(color)
-                                                .strong()
-                                                .size(12.0),
-                                        );
-                                    });
-                                    ui.add_space(4.0);
+        let len = self.quick_switcher_items.len();
+        let (down, up, enter, escape) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+            )
+        });
 
-                                    // Render full message body (tool calls, thinking, content)
-                                    render_message_body(ui, msg, theme);
-                                });
+        if len > 0 && (down || open_pressed) {
+            self.quick_switcher_index = (self.quick_switcher_index + 1) % len;
+        }
+        if len > 0 && up {
+            self.quick_switcher_index = (self.quick_switcher_index + len - 1) % len;
+        }
+        if escape {
+            self.show_quick_switcher = false;
+        }
+        if enter {
+            self.show_quick_switcher = false;
+            let selected = self.quick_switcher_items.get(self.quick_switcher_index);
+            if let Some((id, _)) = selected.cloned() {
+                self.open_conversation(&id);
+            }
+        }
+    }
+
+    fn show_quick_switcher(&mut self, ctx: &egui::Context, theme: Theme) {
+        let density = self.density;
+        if !self.show_quick_switcher {
+            return;
+        }
+
+        let mut to_open: Option<String> = None;
+        egui::Area::new(egui::Id::new("quick_switcher"))
+            .anchor(egui::Align2::CENTER_TOP, Vec2::new(0.0, 80.0))
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::none()
+                    .fill(theme.sidebar_bg)
+                    .stroke(Stroke::new(1.0, theme.border))
+                    .rounding(Rounding::same(CARD_ROUNDING))
+                    .inner_margin(egui::Margin::same(density.card_margin()))
+                    .show(ui, |ui| {
+                        ui.set_width(420.0);
+                        ui.label(
+                            RichText::new("RECENT CONVERSATIONS")
+                                .size(theme.text_size(11.0))
+                                .color(theme.fg_dim)
+                                .strong(),
+                        );
+                        ui.add_space(TIGHT_SPACING);
+
+                        if self.quick_switcher_items.is_empty() {
+                            ui.label(
+                                RichText::new("No recently opened conversations")
+                                    .color(theme.fg_dim)
+                                    .italics(),
+                            );
+                        }
+
+                        for (idx, (id, title)) in self.quick_switcher_items.iter().enumerate() {
+                            let selected = idx == self.quick_switcher_index;
+                            let text = RichText::new(title).size(12.0).color(if selected {
+                                theme.selected_fg
+                            } else {
+                                theme.fg
                             });
+                            let row = ui.add(
+                                egui::Button::new(text)
+                                    .fill(if selected {
+                                        theme.selected_bg
+                                    } else {
+                                        Color32::TRANSPARENT
+                                    })
+                                    .min_size(Vec2::new(ui.available_width(), 24.0)),
+                            );
+                            if row.clicked() {
+                                to_open = Some(id.clone());
+                            }
+                        }
+
+                        ui.add_space(TIGHT_SPACING);
+                        ui.label(
+                            RichText::new("↑↓ navigate • Enter open • Esc close")
+                                .size(theme.text_size(9.0))
+                                .color(theme.fg_dim),
+                        );
                     });
+            });
 
-                    ui.add_space(message_spacing);
-                    continue;
-                }
+        if let Some(id) = to_open {
+            self.show_quick_switcher = false;
+            self.open_conversation(&id);
+        }
+    }
 
-                // === LEFT-ALIGNED MESSAGES ===
-                // Same box-based approach as center/right, but positioned on left
-                ui.horizontal(|ui| {
-                    // Small left margin for left-aligned messages
-                    ui.add_space(16.0);
+    fn push_toast(&mut self, message: String, conversation_id: Option<String>) {
+        self.toasts.push(Toast {
+            message,
+            conversation_id,
+            shown_at: std::time::Instant::now(),
+        });
+    }
 
-                    let available = ui.available_width();
-                    let box_width = max_width.min(available * 0.66); // 2/3 width rule
+    /// First-run wizard: detect installs, import chats, pick a theme and default version
+    fn show_onboarding(&mut self, ctx: &egui::Context, theme: Theme) {
+        let Some(step) = self.onboarding_step else {
+            return;
+        };
 
-                    // Highlight for scroll target
-                    let box_fill = if is_scroll_target {
-                        theme.sidebar_bg.linear_multiply(1.2)
-                    } else {
-                        theme.sidebar_bg
-                    };
+        let mut next_step = Some(step);
+        let mut finish = false;
+        let mut rescan = false;
+        let mut import = false;
+        let mut theme_choice: Option<(String, Option<PathBuf>)> = None;
+        let mut version_choice: Option<String> = None;
 
-                    egui::Frame::none()
-                        .fill(box_fill)
-                        .rounding(Rounding::same(8.0))
-                        .inner_margin(egui::Margin::symmetric(12.0, 8.0))
-                        .stroke(if is_scroll_target {
-                            Stroke::new(2.0, theme.accent)
-                        } else {
-                            Stroke::NONE
-                        })
-                        .show(ui, |ui| {
-                            ui.set_max_width(box_width);
-                            // Force left-to-right layout inside
-                            ui.with_layout(egui::Layout::top_down(egui::Align::LEFT), |ui| {
-                                // Header with bookmark
-                                ui.horizontal(|ui| {
-                                    let bookmark_icon = if is_bookmarked { "🔖" } else { "⭐" };
-                                    let bookmark_color = if is_bookmarked {
-                                        Color32::from_rgb(255, 215, 0)
-                                    } else {
-                                        Color32::from_rgb(100, 100, 100)
-                                    };
-                                    let bookmark_btn = ui
-                                        .add(
-                                            egui::Button::new(
-                                                RichText::new(bookmark_icon)
-                                                    .color(bookmark_color)
-                                                    .size(14.0),
-                                            )
-                                            .frame(false)
-                                            .min_size(Vec2::new(20.0, 20.0)),
-                                        )
-                                        .on_hover_text(if is_bookmarked {
-                                            "Remove bookmark"
-                                        } else {
-                                            "Add bookmark"
-                                        });
+        egui::Area::new(egui::Id::new("onboarding"))
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::none()
+                    .fill(theme.sidebar_bg)
+                    .stroke(Stroke::new(1.0, theme.border))
+                    .rounding(Rounding::same(CARD_ROUNDING))
+                    .inner_margin(egui::Margin::same(16.0))
+                    .show(ui, |ui| {
+                        ui.set_width(480.0);
+                        ui.label(
+                            RichText::new("Welcome to Cursor Studio")
+                                .size(16.0)
+                                .color(theme.fg)
+                                .strong(),
+                        );
+                        ui.add_space(TIGHT_SPACING);
 
-                                    if bookmark_btn.hovered() {
-                                        ui.ctx().set_cursor_icon(CursorIcon::PointingHand);
+                        // Step indicator
+                        ui.horizontal(|ui| {
+                            for (i, s) in OnboardingStep::ALL.iter().enumerate() {
+                                let color = if *s == step {
+                                    theme.accent
+                                } else {
+                                    theme.fg_dim
+                                };
+                                ui.label(
+                                    RichText::new(format!("{}. {}", i + 1, s.title()))
+                                        .size(theme.text_size(11.0))
+                                        .color(color),
+                                );
+                                ui.add_space(8.0);
+                            }
+                        });
+                        ui.separator();
+                        ui.add_space(ELEMENT_SPACING);
+
+                        match step {
+                            OnboardingStep::Detect => {
+                                ui.label(
+                                    RichText::new("Cursor installations found on this machine:")
+                                        .color(theme.fg),
+                                );
+                                ui.add_space(TIGHT_SPACING);
+                                if self.versions.is_empty() {
+                                    ui.label(
+                                        RichText::new(
+                                            "⚠ None found. You can download a version from the \
+                                             Manager panel later.",
+                                        )
+                                        .color(theme.warning)
+                                        .size(theme.text_size(11.0)),
+                                    );
+                                }
+                                for v in &self.versions {
+                                    ui.horizontal(|ui| {
+                                        ui.label(
+                                            RichText::new(Self::version_display_name(&v.version))
+                                                .color(theme.fg)
+                                                .strong(),
+                                        );
+                                        ui.label(
+                                            RichText::new(v.path.display().to_string())
+                                                .color(theme.fg_dim)
+                                                .size(theme.text_size(10.0)),
+                                        );
+                                    });
+                                }
+                                ui.add_space(TIGHT_SPACING);
+                                if ui.small_button("↻ Rescan").clicked() {
+                                    rescan = true;
+                                }
+                            }
+                            OnboardingStep::Import => {
+                                ui.label(
+                                    RichText::new(
+                                        "Import your chat history from Cursor. Conversations \
+                                         already in the library are skipped.",
+                                    )
+                                    .color(theme.fg),
+                                );
+                                ui.add_space(ELEMENT_SPACING);
+                                if let Some((current, total)) = self.import_progress {
+                                    ui.horizontal(|ui| {
+                                        ui.spinner();
+                                        ui.label(
+                                            RichText::new(format!(
+                                                "Importing... {}/{}",
+                                                current, total
+                                            ))
+                                            .color(theme.fg_dim),
+                                        );
+                                    });
+                                } else if self.import_in_progress {
+                                    ui.spinner();
+                                } else if styled_button_accent(
+                                    ui,
+                                    "📥 Import chats",
+                                    Vec2::new(160.0, 32.0),
+                                    theme,
+                                )
+                                .clicked()
+                                {
+                                    import = true;
+                                }
+                                ui.add_space(TIGHT_SPACING);
+                                ui.label(
+                                    RichText::new(format!(
+                                        "{} conversations in library",
+                                        self.library_total
+                                    ))
+                                        .color(theme.fg_dim)
+                                        .size(theme.text_size(11.0)),
+                                );
+                            }
+                            OnboardingStep::Theme => {
+                                ui.label(RichText::new("Pick a theme:").color(theme.fg));
+                                ui.add_space(TIGHT_SPACING);
+                                egui::ScrollArea::vertical()
+                                    .max_height(200.0)
+                                    .show(ui, |ui| {
+                                        for (name, path) in &self.available_themes {
+                                            let selected = *name == self.current_theme_name;
+                                            if ui.selectable_label(selected, name).clicked() {
+                                                theme_choice = Some((name.clone(), path.clone()));
+                                            }
+                                        }
+                                    });
+                            }
+                            OnboardingStep::DefaultVersion => {
+                                ui.label(
+                                    RichText::new("Version launched by default:").color(theme.fg),
+                                );
+                                ui.add_space(TIGHT_SPACING);
+                                if self.versions.is_empty() {
+                                    ui.label(
+                                        RichText::new("No installed versions yet")
+                                            .color(theme.fg_dim)
+                                            .italics(),
+                                    );
+                                }
+                                for v in &self.versions {
+                                    let selected = v.version == self.default_version;
+                                    let label = Self::version_display_name(&v.version);
+                                    if ui.radio(selected, label).clicked() {
+                                        version_choice = Some(v.version.clone());
                                     }
+                                }
+                            }
+                        }
 
-                                    if bookmark_btn.clicked() {
-                                        if is_bookmarked {
-                                            if let Some(bm) =
-                                                bookmarks.iter().find(|b| b.message_id == msg_id)
+                        ui.add_space(SECTION_SPACING);
+                        ui.separator();
+                        ui.horizontal(|ui| {
+                            if ui.button("Skip setup").clicked() {
+                                finish = true;
+                            }
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    match step.next() {
+                                        Some(next) => {
+                                            if styled_button_accent(
+                                                ui,
+                                                "Next →",
+                                                Vec2::new(90.0, 28.0),
+                                                theme,
+                                            )
+                                            .clicked()
                                             {
-                                                bookmark_actions.push(BookmarkAction::Remove(
-                                                    bm.id.clone(),
-                                                    conv_id_clone.clone(),
-                                                ));
+                                                next_step = Some(next);
+                                            }
+                                        }
+                                        None => {
+                                            if styled_button_accent(
+                                                ui,
+                                                "✓ Finish",
+                                                Vec2::new(90.0, 28.0),
+                                                theme,
+                                            )
+                                            .clicked()
+                                            {
+                                                finish = true;
                                             }
-                                        } else {
-                                            bookmark_actions.push(BookmarkAction::Add(
-                                                conv_id_clone.clone(),
-                                                msg_id.clone(),
-                                                msg_seq,
-                                            ));
                                         }
                                     }
-
-                                    ui.label(
-                                        RichText::new(format!("{} {}", icon, label))
-                                            .color(color)
-                                            .strong()
-                                            .size(12.0),
-                                    );
-                                });
-
-                                ui.add_space(4.0);
-
-                                // Render full message body (tool calls, thinking, content)
-                                render_message_body(ui, msg, theme);
-                            });
+                                    if let Some(prev) = step.prev() {
+                                        if ui.button("← Back").clicked() {
+                                            next_step = Some(prev);
+                                        }
+                                    }
+                                },
+                            );
                         });
-                });
-
-                ui.add_space(message_spacing);
-            }
-
-            ui.add_space(16.0);
-        });
+                    });
+            });
 
-        // Process bookmark actions after UI rendering
-        for action in bookmark_actions {
-            match action {
-                BookmarkAction::Add(cid, mid, seq) => {
-                    self.add_bookmark(&cid, &mid, seq);
-                }
-                BookmarkAction::Remove(bid, cid) => {
-                    self.delete_bookmark(&bid, &cid);
-                }
-            }
+        if rescan {
+            self.versions = self.db.get_versions().unwrap_or_default();
+        }
+        if import {
+            self.do_import();
+        }
+        if let Some((name, path)) = theme_choice {
+            self.apply_theme(&name, path.as_ref());
+        }
+        if let Some(version) = version_choice {
+            self.set_default_version(&version);
         }
 
-        // Clear scroll target after rendering
-        if self.scroll_to_message_id.is_some() {
-            self.scroll_to_message_id = None;
+        if finish {
+            self.onboarding_step = None;
+            let _ = self.db.set_config("ui.onboarded", "true");
+            self.set_status("✓ Setup complete");
+        } else {
+            self.onboarding_step = next_step;
         }
     }
 
-    fn show_status_bar(&mut self, ui: &mut egui::Ui) {
-        let font_size = self.status_bar_font_size;
-
-        // Check for async import progress
-        if let Some(rx) = &self.import_receiver {
-            if let Ok(progress) = rx.try_recv() {
-                match progress {
-                    ImportProgress::Started(total) => {
-                        self.import_progress = Some((0, total));
-                        self.set_status(&format!(
-                            "⏳ Starting import of {} conversations...",
-                            total
-                        ));
-                    }
-                    ImportProgress::Processing(current, total) => {
-                        self.import_progress = Some((current, total));
-                        // Request repaint to show progress
-                        ui.ctx().request_repaint();
-                    }
-                    ImportProgress::Completed(imported, skipped) => {
-                        self.import_progress = None;
-                        self.import_in_progress = false;
-                        self.import_receiver = None;
-                        self.refresh_all(); // Full refresh including bookmarks
-
-                        // Reattach bookmarks and restore favorites if this was a clear & reimport
-                        if self.import_needs_bookmark_reattach {
-                            self.import_needs_bookmark_reattach = false;
-                            let mut reattached = 0;
-                            let mut failed = 0;
-                            for conv in &self.conversations {
-                                if let Ok(results) = self.db.reattach_bookmarks(&conv.id) {
-                                    for (_, success) in results {
-                                        if success {
-                                            reattached += 1;
-                                        } else {
-                                            failed += 1;
-                                        }
+    /// What "Detect installations" found, to tick and register
+    fn show_install_detection(&mut self, ctx: &egui::Context, theme: Theme) {
+        let Some(detection) = &mut self.install_detection else {
+            return;
+        };
+        let registered = self.db.registered_installs().unwrap_or_default();
+
+        let mut close = false;
+        let mut search = false;
+        let mut register = false;
+        let mut forget: Option<String> = None;
+        egui::Area::new(egui::Id::new("install_detection"))
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::none()
+                    .fill(theme.sidebar_bg)
+                    .stroke(Stroke::new(1.0, theme.border))
+                    .rounding(Rounding::same(CARD_ROUNDING))
+                    .inner_margin(egui::Margin::same(16.0))
+                    .show(ui, |ui| {
+                        ui.set_width(560.0);
+                        ui.horizontal(|ui| {
+                            ui.label(
+                                RichText::new("Detect Cursor Installations")
+                                    .size(16.0)
+                                    .color(theme.fg)
+                                    .strong(),
+                            );
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    if ui.small_button("✕").clicked() {
+                                        close = true;
                                     }
-                                }
-                            }
-
-                            // Restore favorites
-                            let favorites_restored = self.db.restore_favorites().unwrap_or(0);
-                            if favorites_restored > 0 {
-                                self.refresh_chats(); // Refresh to show restored favorites
+                                },
+                            );
+                        });
+                        ui.add_space(TIGHT_SPACING);
+                        ui.label(
+                            RichText::new(
+                                "Searched ~/.config/Cursor, ~/.cursor-*, Cursor Studio installs, \
+                                 the download cache, ~/Applications, ~/Downloads, ~/.local/bin, \
+                                 ~/bin and /opt.",
+                            )
+                            .color(theme.fg_dim)
+                            .size(theme.text_size(10.0)),
+                        );
+                        ui.horizontal(|ui| {
+                            ui.label(
+                                RichText::new("Also search")
+                                    .color(theme.fg)
+                                    .size(theme.text_size(11.0)),
+                            );
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.install_search_dirs)
+                                    .hint_text("/path/one:/path/two")
+                                    .desired_width(320.0),
+                            );
+                            if ui.small_button("↻ Search").clicked() {
+                                search = true;
                             }
+                        });
+                        ui.separator();
 
-                            self.set_status(&format!(
-                                "✓ Imported {} chats, {} favorites, {} bookmarks restored",
-                                imported, favorites_restored, reattached
-                            ));
-                            return;
-                        }
-
-                        if imported > 0 {
-                            self.set_status(&format!(
-                                "✓ Imported {} new chats ({} already existed)",
-                                imported, skipped
-                            ));
-                        } else if skipped > 0 {
-                            self.set_status(&format!("All {} chats already imported", skipped));
-                        } else {
-                            self.set_status("No Cursor chats found to import");
+                        if detection.found.is_empty() {
+                            ui.label(
+                                RichText::new("⚠ No Cursor installations found")
+                                    .color(theme.warning)
+                                    .size(theme.text_size(11.0)),
+                            );
                         }
-                    }
-                    ImportProgress::Error(e) => {
-                        self.import_progress = None;
-                        self.import_in_progress = false;
-                        self.import_receiver = None;
-                        self.last_import_error = Some(e.clone());
-                        self.set_status(&format!("✗ Import error: {}", e));
-                    }
-                }
-            }
-        }
+                        egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                            for (install, selected) in
+                                detection.found.iter().zip(detection.selected.iter_mut())
+                            {
+                                let listed =
+                                    self.versions.iter().any(|v| v.version == install.version);
+                                ui.horizontal(|ui| {
+                                    ui.add_enabled(!listed, egui::Checkbox::without_text(selected));
+                                    ui.label(
+                                        RichText::new(Self::version_display_name(&install.version))
+                                            .color(theme.fg)
+                                            .strong(),
+                                    );
+                                    if listed {
+                                        ui.label(
+                                            RichText::new("✓ listed")
+                                                .color(theme.success)
+                                                .size(theme.text_size(10.0)),
+                                        );
+                                    }
+                                    ui.label(
+                                        RichText::new(&install.source)
+                                            .color(theme.fg_dim)
+                                            .size(theme.text_size(10.0)),
+                                    );
+                                });
+                                ui.horizontal(|ui| {
+                                    ui.add_space(28.0);
+                                    ui.vertical(|ui| {
+                                        let appimage = install
+                                            .appimage
+                                            .as_ref()
+                                            .map(|p| format!("AppImage: {}", p.display()))
+                                            .unwrap_or_else(|| "No AppImage".to_string());
+                                        for line in [
+                                            appimage,
+                                            format!("Data: {}", install.data_dir.display()),
+                                        ] {
+                                            ui.label(
+                                                RichText::new(line)
+                                                    .color(theme.fg_dim)
+                                                    .size(theme.text_size(10.0))
+                                                    .monospace(),
+                                            );
+                                        }
+                                    });
+                                });
+                                ui.add_space(TIGHT_SPACING);
+                            }
 
-        ui.horizontal(|ui| {
-            // Show import progress if active
-            if let Some((current, total)) = self.import_progress {
-                let pct = if total > 0 { (current as f32 / total as f32) * 100.0 } else { 0.0 };
-                ui.add(egui::Spinner::new().size(font_size));
-                ui.add_space(4.0);
-                ui.label(
-                    RichText::new(format!("Importing... {}/{} ({:.0}%)", current, total, pct))
-                        .color(Color32::from_rgb(100, 200, 255))
-                        .size(font_size),
-                );
-            } else if let Some(msg) = &self.status_message {
-                ui.label(RichText::new(msg).color(Color32::WHITE).size(font_size));
-            } else {
-                // Show detailed stats
-                if let Ok(stats) = self.db.get_detailed_stats() {
-                    let (total, _, _) = self.db.get_stats().unwrap_or((0, 0, 0));
-                    ui.label(
-                        RichText::new(format!(
-                            "📊 {} chats • 👤 {} user • 🤖 {} AI • 🔧 {} tools • 💭 {} thinking • 📝 {} code • 🔖 {} bookmarks",
-                            total,
-                            stats.user_messages,
-                            stats.assistant_messages,
-                            stats.tool_calls,
-                            stats.with_thinking,
-                            stats.with_code,
-                            stats.bookmarks,
-                        ))
-                        .color(Color32::WHITE)
-                        .size(font_size),
-                    );
-                } else {
-                    let (total, messages, _) = self.db.get_stats().unwrap_or((0, 0, 0));
-                    ui.label(
-                        RichText::new(format!("📊 {} chats • 💬 {} messages", total, messages))
-                            .color(Color32::WHITE)
-                            .size(font_size),
-                    );
+                            if !registered.is_empty() {
+                                ui.separator();
+                                ui.label(
+                                    RichText::new("REGISTERED")
+                                        .size(theme.text_size(11.0))
+                                        .color(theme.fg_dim)
+                                        .strong(),
+                                );
+                                for install in &registered {
+                                    ui.horizontal(|ui| {
+                                        if ui
+                                            .small_button("✕")
+                                            .on_hover_text("Forget (files are kept)")
+                                            .clicked()
+                                        {
+                                            forget = Some(install.version.clone());
+                                        }
+                                        ui.label(
+                                            RichText::new(Self::version_display_name(
+                                                &install.version,
+                                            ))
+                                            .color(theme.fg),
+                                        );
+                                        ui.label(
+                                            RichText::new(install.data_dir.display().to_string())
+                                                .color(theme.fg_dim)
+                                                .size(theme.text_size(10.0)),
+                                        );
+                                    });
+                                }
+                            }
+                        });
+
+                        ui.separator();
+                        ui.horizontal(|ui| {
+                            let count = detection.selected.iter().filter(|s| **s).count();
+                            let clicked = ui
+                                .add_enabled_ui(count > 0, |ui| {
+                                    styled_button_accent(
+                                        ui,
+                                        &format!("Register {}", count),
+                                        Vec2::new(120.0, 28.0),
+                                        theme,
+                                    )
+                                })
+                                .inner
+                                .clicked();
+                            if clicked {
+                                register = true;
+                            }
+                            if styled_button(ui, "Close", Vec2::new(80.0, 28.0)).clicked() {
+                                close = true;
+                            }
+                        });
+                    });
+            });
+
+        if let Some(version) = forget {
+            match self.db.unregister_install(&version) {
+                Ok(_) => {
+                    self.versions = self.db.get_versions().unwrap_or_default();
+                    self.set_status(&format!(
+                        "✓ Forgot {}",
+                        Self::version_display_name(&version)
+                    ));
                 }
+                Err(e) => self.set_status(&format!("✗ Failed to forget: {}", e)),
             }
+        }
+        if search {
+            self.save_settings();
+            self.detect_installations();
+        } else if register {
+            self.register_detected_installs();
+        } else if close {
+            self.install_detection = None;
+        }
+    }
 
-            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                ui.label(
-                    RichText::new("Cursor Studio v0.2.0")
-                        .color(Color32::WHITE)
-                        .size(font_size),
-                );
+    /// Recent status messages above the status bar, newest first
+    fn show_status_history_popup(&mut self, ctx: &egui::Context, theme: Theme) {
+        if !self.show_status_history {
+            return;
+        }
+
+        let mut close = false;
+        egui::Area::new(egui::Id::new("status_history"))
+            .anchor(egui::Align2::LEFT_BOTTOM, Vec2::new(56.0, -32.0))
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::none()
+                    .fill(theme.sidebar_bg)
+                    .stroke(Stroke::new(1.0, theme.border))
+                    .rounding(Rounding::same(CARD_ROUNDING))
+                    .inner_margin(egui::Margin::same(self.density.card_margin()))
+                    .show(ui, |ui| {
+                        ui.set_width(420.0);
+                        ui.horizontal(|ui| {
+                            ui.label(
+                                RichText::new("RECENT MESSAGES")
+                                    .size(theme.text_size(11.0))
+                                    .color(theme.fg_dim)
+                                    .strong(),
+                            );
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    if ui.small_button("✕").clicked() {
+                                        close = true;
+                                    }
+                                },
+                            );
+                        });
+                        ui.add_space(TIGHT_SPACING);
+
+                        egui::ScrollArea::vertical()
+                            .max_height(300.0)
+                            .show(ui, |ui| {
+                                for entry in &self.status_history {
+                                    ui.horizontal(|ui| {
+                                        ui.label(
+                                            RichText::new(entry.at.format("%H:%M:%S").to_string())
+                                                .size(theme.text_size(10.0))
+                                                .color(theme.fg_dim)
+                                                .monospace(),
+                                        );
+                                        ui.label(
+                                            RichText::new(&entry.message)
+                                                .size(theme.text_size(11.0))
+                                                .color(entry.severity.color(theme)),
+                                        );
+                                    });
+                                }
+                            });
+                    });
             });
-        });
 
-        self.status_message = None;
+        if close || ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.show_status_history = false;
+        }
     }
 
-    fn open_conversation(&mut self, conv_id: &str) {
-        for (i, tab) in self.tabs.iter().enumerate() {
-            if let Tab::Conversation(id) = tab {
-                if id == conv_id {
-                    self.active_tab = i;
-                    return;
-                }
-            }
+    /// Draw toast notifications; clicking one opens its conversation
+    fn show_toasts(&mut self, ctx: &egui::Context, theme: Theme) {
+        let density = self.density;
+        self.toasts
+            .retain(|toast| toast.shown_at.elapsed() < TOAST_DURATION);
+        if self.toasts.is_empty() {
+            return;
         }
 
-        self.tabs.push(Tab::Conversation(conv_id.to_string()));
-        self.active_tab = self.tabs.len() - 1;
-        self.current_messages = self.db.get_messages(conv_id).unwrap_or_default();
+        let mut dismissed: Option<usize> = None;
+        let mut to_open: Option<String> = None;
+        egui::Area::new(egui::Id::new("toasts"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, Vec2::new(-16.0, -40.0))
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                for (idx, toast) in self.toasts.iter().enumerate() {
+                    let remaining = TOAST_DURATION.saturating_sub(toast.shown_at.elapsed());
+                    ui.set_opacity(remaining.as_secs_f32().min(1.0));
+
+                    egui::Frame::none()
+                        .fill(theme.sidebar_bg)
+                        .stroke(Stroke::new(1.0, theme.accent))
+                        .rounding(Rounding::same(CARD_ROUNDING))
+                        .inner_margin(egui::Margin::same(density.card_margin()))
+                        .show(ui, |ui| {
+                            ui.horizontal(|ui| {
+                                let message = ui.add(
+                                    egui::Label::new(
+                                        RichText::new(&toast.message).color(theme.fg).size(12.0),
+                                    )
+                                    .sense(egui::Sense::click()),
+                                );
+                                if toast.conversation_id.is_some()
+                                    && message
+                                        .on_hover_cursor(CursorIcon::PointingHand)
+                                        .on_hover_text("Open the newest conversation")
+                                        .clicked()
+                                {
+                                    to_open = toast.conversation_id.clone();
+                                    dismissed = Some(idx);
+                                }
+                                if ui
+                                    .add(
+                                        egui::Button::new(
+                                            RichText::new("✕")
+                                                .color(theme.fg_dim)
+                                                .size(theme.text_size(10.0)),
+                                        )
+                                        .frame(false),
+                                    )
+                                    .on_hover_text("Dismiss")
+                                    .clicked()
+                                {
+                                    dismissed = Some(idx);
+                                }
+                            });
+                        });
+                    ui.add_space(TIGHT_SPACING);
+                }
+            });
+
+        if let Some(idx) = dismissed {
+            self.toasts.remove(idx);
+        }
+        if let Some(id) = to_open {
+            self.open_conversation(&id);
+        }
+        // Keep animating the fade-out
+        ctx.request_repaint_after(std::time::Duration::from_millis(50));
     }
 
     fn close_tab(&mut self, index: usize) {
         if index < self.tabs.len() && !matches!(self.tabs[index], Tab::Dashboard) {
-            self.tabs.remove(index);
+            let tab = self.tabs.remove(index);
+            self.pinned_tabs.retain(|t| *t != tab);
             if self.active_tab >= self.tabs.len() {
                 self.active_tab = self.tabs.len().saturating_sub(1);
             }
         }
     }
 
+    /// Move a tab to `target`; the Dashboard stays first and pinned tabs stay
+    /// ahead of unpinned ones
+    fn move_tab(&mut self, from: usize, target: usize) {
+        if from >= self.tabs.len() || matches!(self.tabs[from], Tab::Dashboard) {
+            return;
+        }
+        let active = self.tabs.get(self.active_tab).cloned();
+        let tab = self.tabs.remove(from);
+        self.tabs.insert(target.min(self.tabs.len()), tab);
+        self.normalize_tab_order(active);
+    }
+
+    fn toggle_tab_pin(&mut self, index: usize) {
+        let Some(tab) = self.tabs.get(index).cloned() else {
+            return;
+        };
+        if matches!(tab, Tab::Dashboard) {
+            return;
+        }
+        if let Some(pos) = self.pinned_tabs.iter().position(|t| *t == tab) {
+            self.pinned_tabs.remove(pos);
+        } else {
+            self.pinned_tabs.push(tab);
+        }
+        let active = self.tabs.get(self.active_tab).cloned();
+        self.normalize_tab_order(active);
+    }
+
+    /// Stable-sort tabs into Dashboard, pinned, rest and keep `active` selected
+    fn normalize_tab_order(&mut self, active: Option<Tab>) {
+        let pinned = &self.pinned_tabs;
+        self.tabs.sort_by_key(|tab| match tab {
+            Tab::Dashboard => 0,
+            tab if pinned.contains(tab) => 1,
+            _ => 2,
+        });
+        if let Some(i) = active.and_then(|active| self.tabs.iter().position(|t| *t == active)) {
+            self.active_tab = i;
+        }
+    }
+
     /// Called when switching between tabs - refreshes relevant data
     fn on_tab_switch(&mut self) {
         if let Some(tab) = self.tabs.get(self.active_tab).cloned() {
             match tab {
                 Tab::Dashboard => {
                     // Refresh stats for dashboard
-                    self.conversations = self.db.get_conversations(50).unwrap_or_default();
+                    self.reload_conversations();
                 }
                 Tab::Conversation(ref id) => {
                     // Load messages and bookmarks for conversation
-                    self.current_messages = self.db.get_messages(id).unwrap_or_default();
+                    self.current_messages = self.load_messages(id);
                     self.current_bookmarks = self.db.get_bookmarks(id).unwrap_or_default();
                     // Clear search when switching conversations
                     self.conv_search_query.clear();
@@ -6835,8 +15179,41 @@ impl CursorStudio {
     }
 }
 
-/// Render a complete message body including tool calls, thinking, and content
-fn render_message_body(ui: &mut egui::Ui, msg: &Message, theme: Theme) {
+/// Look up the color overrides saved for a content type
+fn pref_colors(
+    prefs: &[DisplayPreference],
+    content_type: &str,
+) -> (Option<Color32>, Option<Color32>) {
+    let Some(pref) = prefs.iter().find(|p| p.content_type == content_type) else {
+        return (None, None);
+    };
+    (
+        pref.fg_color.as_deref().and_then(theme::parse_color),
+        pref.bg_color.as_deref().and_then(theme::parse_color),
+    )
+}
+
+/// Theme default accent for a content type (used when no override is set)
+fn role_color(content_type: &str, theme: Theme) -> Color32 {
+    match content_type {
+        "user" => theme.accent,
+        "assistant" => theme.success,
+        "tool_call" => theme.warning,
+        _ => theme.fg_dim,
+    }
+}
+
+/// Render a complete message body including tool calls, thinking, and content.
+/// `thinking_colors` are the (fg, bg) overrides for the thinking block.
+fn render_message_body(
+    ui: &mut egui::Ui,
+    msg: &Message,
+    tool_result: Option<&Message>,
+    theme: Theme,
+    code_wrap: bool,
+    thinking_colors: (Option<Color32>, Option<Color32>),
+) {
+    let (thinking_fg, thinking_bg) = thinking_colors;
     // Tool call info (if present)
     if let Some(tool_call) = &msg.tool_call {
         egui::Frame::none()
@@ -6863,7 +15240,7 @@ fn render_message_body(ui: &mut egui::Ui, msg: &Message, theme: Theme) {
                         RichText::new(&tool_call.name)
                             .color(theme.accent)
                             .strong()
-                            .size(11.0),
+                            .size(theme.text_size(11.0)),
                     );
 
                     // Show tool_id if present
@@ -6874,7 +15251,7 @@ fn render_message_body(ui: &mut egui::Ui, msg: &Message, theme: Theme) {
                                 &tool_call.tool_id.chars().take(8).collect::<String>()
                             ))
                             .color(theme.fg_dim)
-                            .size(9.0),
+                            .size(theme.text_size(9.0)),
                         );
                     }
                 });
@@ -6885,7 +15262,7 @@ fn render_message_body(ui: &mut egui::Ui, msg: &Message, theme: Theme) {
                     ui.label(
                         RichText::new(&tool_call.args_preview)
                             .color(theme.fg_dim)
-                            .size(10.0)
+                            .size(theme.text_size(10.0))
                             .family(egui::FontFamily::Monospace),
                     );
                 }
@@ -6904,8 +15281,12 @@ fn render_message_body(ui: &mut egui::Ui, msg: &Message, theme: Theme) {
                         "▶ Show full args"
                     };
                     let toggle = ui.add(
-                        egui::Button::new(RichText::new(toggle_text).color(theme.fg_dim).size(9.0))
-                            .frame(false),
+                        egui::Button::new(
+                            RichText::new(toggle_text)
+                                .color(theme.fg_dim)
+                                .size(theme.text_size(9.0)),
+                        )
+                        .frame(false),
                     );
                     if toggle.clicked() {
                         show_full = !show_full;
@@ -6931,12 +15312,23 @@ fn render_message_body(ui: &mut egui::Ui, msg: &Message, theme: Theme) {
                                 ui.label(
                                     RichText::new(&display_args)
                                         .color(theme.fg)
-                                        .size(10.0)
+                                        .size(theme.text_size(10.0))
                                         .family(egui::FontFamily::Monospace),
                                 );
                             });
                     }
                 }
+
+                // Output of the call, when a result was paired with it
+                if let Some(result) = tool_result {
+                    ui.add_space(4.0);
+                    ui.label(
+                        RichText::new("↳ Result")
+                            .color(theme.fg_dim)
+                            .size(theme.text_size(10.0)),
+                    );
+                    render_tool_result(ui, result, theme, code_wrap);
+                }
             });
         ui.add_space(4.0);
     }
@@ -6956,9 +15348,9 @@ fn render_message_body(ui: &mut egui::Ui, msg: &Message, theme: Theme) {
                 let response = ui.add(
                     egui::Button::new(
                         RichText::new(toggle_text)
-                            .color(theme.fg_dim)
+                            .color(thinking_fg.unwrap_or(theme.fg_dim))
                             .italics()
-                            .size(11.0),
+                            .size(theme.text_size(11.0)),
                     )
                     .fill(Color32::TRANSPARENT)
                     .stroke(Stroke::NONE),
@@ -6972,7 +15364,7 @@ fn render_message_body(ui: &mut egui::Ui, msg: &Message, theme: Theme) {
 
             if is_open {
                 egui::Frame::none()
-                    .fill(theme.sidebar_bg)
+                    .fill(thinking_bg.unwrap_or(theme.sidebar_bg))
                     .rounding(Rounding::same(4.0))
                     .inner_margin(egui::Margin::same(8.0))
                     .show(ui, |ui| {
@@ -6983,9 +15375,9 @@ fn render_message_body(ui: &mut egui::Ui, msg: &Message, theme: Theme) {
                         };
                         ui.label(
                             RichText::new(truncated)
-                                .color(theme.fg_dim)
+                                .color(thinking_fg.unwrap_or(theme.fg_dim))
                                 .italics()
-                                .size(11.0),
+                                .size(theme.text_size(11.0)),
                         );
                     });
             }
@@ -6995,12 +15387,155 @@ fn render_message_body(ui: &mut egui::Ui, msg: &Message, theme: Theme) {
 
     // Main content
     if !msg.content.is_empty() {
-        render_markdown_content(ui, &msg.content, theme);
+        if msg.role == MessageRole::ToolResult {
+            render_tool_result(ui, msg, theme, code_wrap);
+        } else {
+            render_markdown_content(ui, &msg.content, &msg.id, theme, code_wrap);
+        }
+    }
+}
+
+/// Shortened tool output, or `None` when the result is short enough to show whole
+fn tool_result_preview(content: &str) -> Option<String> {
+    let lines = content.lines().count();
+    if lines <= TOOL_RESULT_PREVIEW_LINES && content.chars().count() <= TOOL_RESULT_PREVIEW_CHARS {
+        return None;
+    }
+    let preview = content
+        .lines()
+        .take(TOOL_RESULT_PREVIEW_LINES)
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(preview.chars().take(TOOL_RESULT_PREVIEW_CHARS).collect())
+}
+
+/// Render tool output, collapsed to a preview with an expand toggle when long
+fn render_tool_result(ui: &mut egui::Ui, result: &Message, theme: Theme, code_wrap: bool) {
+    if let Some(files) = diff::parse_unified_diff(&result.content) {
+        render_diff(ui, &files, &result.id, theme);
+        return;
+    }
+
+    let Some(preview) = tool_result_preview(&result.content) else {
+        render_markdown_content(ui, &result.content, &result.id, theme, code_wrap);
+        return;
+    };
+
+    let expand_id = ui.make_persistent_id(format!("tool_result_{}", result.id));
+    let mut expanded = ui.data_mut(|d| d.get_temp::<bool>(expand_id).unwrap_or(false));
+    let shown = if expanded { &result.content } else { &preview };
+    render_markdown_content(ui, shown, &result.id, theme, code_wrap);
+
+    let toggle_text = if expanded {
+        "▼ Collapse output".to_string()
+    } else {
+        format!(
+            "▶ Show full output ({} lines)",
+            result.content.lines().count()
+        )
+    };
+    let toggle = ui.add(
+        egui::Button::new(
+            RichText::new(toggle_text)
+                .color(theme.fg_dim)
+                .size(theme.text_size(9.0)),
+        )
+        .frame(false),
+    );
+    if toggle.clicked() {
+        expanded = !expanded;
+        ui.data_mut(|d| d.insert_temp(expand_id, expanded));
+    }
+}
+
+/// Render a unified diff with colored lines, line numbers and collapsible hunks
+fn render_diff(ui: &mut egui::Ui, files: &[diff::DiffFile], id_salt: &str, theme: Theme) {
+    for (file_index, file) in files.iter().enumerate() {
+        if let Some(path) = &file.path {
+            ui.label(
+                RichText::new(format!("📄 {}", path))
+                    .color(theme.fg_bright)
+                    .size(theme.text_size(11.0))
+                    .family(egui::FontFamily::Monospace),
+            );
+        }
+
+        for (hunk_index, hunk) in file.hunks.iter().enumerate() {
+            let hunk_id = ui.make_persistent_id((id_salt, "diff_hunk", file_index, hunk_index));
+            let default_open = hunk.lines.len() <= DIFF_HUNK_OPEN_LINES;
+            let mut is_open = ui.data_mut(|d| d.get_temp::<bool>(hunk_id).unwrap_or(default_open));
+
+            let arrow = if is_open { "▼" } else { "▶" };
+            let toggle = ui.add(
+                egui::Button::new(
+                    RichText::new(format!("{} {}", arrow, hunk.header))
+                        .color(theme.accent)
+                        .size(theme.text_size(10.0))
+                        .family(egui::FontFamily::Monospace),
+                )
+                .frame(false),
+            );
+            if toggle.clicked() {
+                is_open = !is_open;
+                ui.data_mut(|d| d.insert_temp(hunk_id, is_open));
+            }
+            if !is_open {
+                continue;
+            }
+
+            egui::Frame::none()
+                .fill(theme.code_bg)
+                .rounding(Rounding::same(4.0))
+                .inner_margin(egui::Margin::same(6.0))
+                .show(ui, |ui| {
+                    egui::ScrollArea::horizontal()
+                        .id_salt(hunk_id)
+                        .auto_shrink([false, true])
+                        .show(ui, |ui| {
+                            ui.spacing_mut().item_spacing.y = 0.0;
+                            for line in &hunk.lines {
+                                ui.add(egui::Label::new(diff_line_text(line, theme)).extend());
+                            }
+                        });
+                });
+            ui.add_space(4.0);
+        }
     }
 }
 
+/// One diff line with old/new line numbers, tinted by whether it was added or removed
+fn diff_line_text(line: &diff::DiffLine, theme: Theme) -> RichText {
+    let number = |n: Option<usize>| n.map(|n| n.to_string()).unwrap_or_default();
+    let (sign, color, background) = match line.kind {
+        diff::DiffLineKind::Added => ("+", theme.success, theme.success.linear_multiply(0.15)),
+        diff::DiffLineKind::Removed => ("-", theme.error, theme.error.linear_multiply(0.15)),
+        diff::DiffLineKind::Context => (" ", theme.fg, Color32::TRANSPARENT),
+        diff::DiffLineKind::Note => ("", theme.fg_dim, Color32::TRANSPARENT),
+    };
+    RichText::new(format!(
+        "{:>4} {:>4} {}{}",
+        number(line.old_line),
+        number(line.new_line),
+        sign,
+        line.text
+    ))
+    .color(color)
+    .background_color(background)
+    .size(theme.text_size(11.0))
+    .family(egui::FontFamily::Monospace)
+}
+
 /// Render markdown-ish content with code block support
-fn render_markdown_content(ui: &mut egui::Ui, content: &str, theme: Theme) {
+///
+/// `id_salt` keeps per-block wrap overrides apart between messages.
+fn render_markdown_content(
+    ui: &mut egui::Ui,
+    content: &str,
+    id_salt: &str,
+    theme: Theme,
+    code_wrap: bool,
+) {
+    let mut block_index = 0;
     let mut in_code_block = false;
     let mut code_lang = String::new();
     let mut code_buffer = String::new();
@@ -7009,7 +15544,9 @@ fn render_markdown_content(ui: &mut egui::Ui, content: &str, theme: Theme) {
         if line.starts_with("```") {
             if in_code_block {
                 // End of code block - render it
-                render_code_block(ui, &code_buffer, &code_lang, theme);
+                let block_id = ui.make_persistent_id((id_salt, block_index));
+                render_code_block(ui, &code_buffer, &code_lang, block_id, theme, code_wrap);
+                block_index += 1;
                 code_buffer.clear();
                 code_lang.clear();
                 in_code_block = false;
@@ -7031,43 +15568,80 @@ fn render_markdown_content(ui: &mut egui::Ui, content: &str, theme: Theme) {
 
     // Handle unclosed code block
     if in_code_block && !code_buffer.is_empty() {
-        render_code_block(ui, &code_buffer, &code_lang, theme);
+        let block_id = ui.make_persistent_id((id_salt, block_index));
+        render_code_block(ui, &code_buffer, &code_lang, block_id, theme, code_wrap);
     }
 }
 
 /// Render a code block with syntax highlighting-ish styling
-fn render_code_block(ui: &mut egui::Ui, code: &str, lang: &str, theme: Theme) {
-    ui.add_space(4.0);
+///
+/// Long lines scroll horizontally by default so indentation stays intact;
+/// `default_wrap` comes from settings and the header button flips a single block.
+fn render_code_block(
+    ui: &mut egui::Ui,
+    code: &str,
+    lang: &str,
+    block_id: egui::Id,
+    theme: Theme,
+    default_wrap: bool,
+) {
+    let mut wrap = ui.data_mut(|d| d.get_temp::<bool>(block_id).unwrap_or(default_wrap));
 
+    ui.add_space(4.0);
     egui::Frame::none()
         .fill(theme.code_bg)
         .rounding(Rounding::same(4.0))
         .inner_margin(8.0)
         .stroke(Stroke::new(1.0, theme.border))
         .show(ui, |ui| {
-            // Language label
-            if !lang.is_empty() {
-                ui.label(
-                    RichText::new(lang)
-                        .color(theme.fg_dim)
-                        .size(10.0)
-                        .family(egui::FontFamily::Monospace),
-                );
-                ui.add_space(4.0);
-            }
+            ui.horizontal(|ui| {
+                if !lang.is_empty() {
+                    ui.label(
+                        RichText::new(lang)
+                            .color(theme.fg_dim)
+                            .size(theme.text_size(10.0))
+                            .family(egui::FontFamily::Monospace),
+                    );
+                }
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    let (toggle_text, tooltip) = if wrap {
+                        ("⇆ scroll", "Scroll long lines horizontally")
+                    } else {
+                        ("↩ wrap", "Wrap long lines")
+                    };
+                    let toggle = ui
+                        .add(
+                            egui::Button::new(
+                                RichText::new(toggle_text)
+                                    .color(theme.fg_dim)
+                                    .size(theme.text_size(9.0)),
+                            )
+                            .frame(false),
+                        )
+                        .on_hover_text(tooltip);
+                    if toggle.clicked() {
+                        wrap = !wrap;
+                        ui.data_mut(|d| d.insert_temp(block_id, wrap));
+                    }
+                });
+            });
+            ui.add_space(4.0);
 
-            // Code content
-            ui.add(
-                egui::Label::new(
-                    RichText::new(code)
-                        .color(theme.syntax_string)
-                        .size(12.0)
-                        .family(egui::FontFamily::Monospace),
-                )
-                .wrap(),
-            );
+            let text = RichText::new(code)
+                .color(theme.syntax_string)
+                .size(12.0)
+                .family(egui::FontFamily::Monospace);
+            if wrap {
+                ui.add(egui::Label::new(text).wrap());
+            } else {
+                egui::ScrollArea::horizontal()
+                    .id_salt(block_id)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        ui.add(egui::Label::new(text).extend());
+                    });
+            }
         });
-
     ui.add_space(4.0);
 }
 
@@ -7171,6 +15745,44 @@ fn render_text_line(ui: &mut egui::Ui, line: &str, theme: Theme) {
     }
 }
 
+/// A search snippet with its matched terms highlighted
+fn snippet_layout(snippet: &str, theme: Theme, width: f32) -> egui::text::LayoutJob {
+    use egui::text::{LayoutJob, TextFormat};
+    use egui::FontId;
+
+    let mut job = LayoutJob::default();
+    job.wrap.max_width = width;
+    job.wrap.max_rows = 3;
+    let normal = TextFormat {
+        font_id: FontId::proportional(theme.text_size(12.0)),
+        color: theme.fg,
+        ..Default::default()
+    };
+    let matched = TextFormat {
+        color: theme.fg_bright,
+        background: theme.accent.linear_multiply(0.35),
+        ..normal.clone()
+    };
+
+    // Text before a start mark is plain; after it, up to the end mark, matched
+    for (i, part) in snippet.split(database::SNIPPET_MATCH_START).enumerate() {
+        let (hit, rest) = match part.split_once(database::SNIPPET_MATCH_END) {
+            Some((hit, rest)) if i > 0 => (hit, rest),
+            _ => ("", part),
+        };
+        // Snippets can span lines; keep each to one flowing paragraph
+        let hit = hit.replace('\n', " ");
+        let rest = rest.replace('\n', " ");
+        if !hit.is_empty() {
+            job.append(&hit, 0.0, matched.clone());
+        }
+        if !rest.is_empty() {
+            job.append(&rest, 0.0, normal.clone());
+        }
+    }
+    job
+}
+
 /// Handle inline formatting (backticks, bold) using LayoutJob for proper text flow
 /// Supports: `code`, **bold**, and combinations like **`bold code`**
 fn render_inline_formatting(ui: &mut egui::Ui, text: &str, theme: Theme) {
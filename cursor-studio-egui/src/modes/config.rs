@@ -331,6 +331,34 @@ impl CustomMode {
         
         sections.join("\n")
     }
+
+    /// Check the fields serde can't, so a bad import fails before it reaches the registry
+    pub fn validate(&self) -> Result<(), String> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Err("Mode name is empty".to_string());
+        }
+        let reserved = name.eq_ignore_ascii_case("registry");
+        if name != self.name || name.contains(['/', '\\']) || reserved {
+            return Err(format!("Invalid mode name '{}'", self.name));
+        }
+        if self.model.primary.trim().is_empty() {
+            return Err("Primary model is empty".to_string());
+        }
+        if let Some(temperature) = self.model.temperature {
+            if !(0.0..=2.0).contains(&temperature) {
+                return Err(format!("Temperature {} is outside 0.0-2.0", temperature));
+            }
+        }
+        if self.model.max_tokens == Some(0) {
+            return Err("Max tokens must be greater than zero".to_string());
+        }
+        let mut tools = self.tools.allowed.iter().chain(&self.tools.blocked);
+        if tools.any(|tool| tool.trim().is_empty()) {
+            return Err("Tool names must not be empty".to_string());
+        }
+        Ok(())
+    }
 }
 
 impl ToolAccess {
@@ -425,7 +453,7 @@ pub struct ModeConfig {
     /// Directory for mode storage
     pub modes_dir: String,
     
-    /// Whether to auto-generate .cursorrules
+    /// Whether activating a mode writes .cursorrules into the chosen project
     pub auto_generate_cursorrules: bool,
     
     /// Path to generate .cursorrules to
@@ -439,7 +467,7 @@ impl Default for ModeConfig {
     fn default() -> Self {
         Self {
             modes_dir: "~/.config/cursor-studio/modes".to_string(),
-            auto_generate_cursorrules: true,
+            auto_generate_cursorrules: false,
             cursorrules_path: None,
             include_environment_in_rules: true,
         }
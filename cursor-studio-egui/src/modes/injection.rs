@@ -2,7 +2,15 @@
 
 use super::{CustomMode, ModeRegistry};
 use crate::ai_workspace::{EnvironmentState, AliasRegistry};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Where the files replaced by an injection are kept, relative to the project root
+const BACKUP_DIR: &str = ".cursor-studio/mode-backup";
+
+/// Replaced files keyed by path relative to the project root. `None` means the
+/// file didn't exist before injection, so restoring removes it.
+type BackupManifest = BTreeMap<String, Option<String>>;
 
 /// Where to inject mode configuration
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -84,11 +92,78 @@ impl ModeInjector {
         Ok(generated)
     }
     
+    /// Put back every file replaced since the last restore, removing the ones
+    /// injection created. Returns the paths that were touched.
+    pub fn restore_vanilla(&self) -> std::io::Result<Vec<PathBuf>> {
+        let backup_dir = self.backup_dir();
+        let mut restored = Vec::new();
+        
+        for (relative, backup) in self.load_manifest() {
+            let path = self.project_root.join(&relative);
+            match backup {
+                Some(name) => {
+                    std::fs::copy(backup_dir.join(name), &path)?;
+                }
+                None if path.exists() => std::fs::remove_file(&path)?,
+                None => continue,
+            }
+            restored.push(path);
+        }
+        
+        if backup_dir.exists() {
+            std::fs::remove_dir_all(&backup_dir)?;
+        }
+        Ok(restored)
+    }
+    
+    fn backup_dir(&self) -> PathBuf {
+        self.project_root.join(BACKUP_DIR)
+    }
+    
+    fn load_manifest(&self) -> BackupManifest {
+        std::fs::read_to_string(self.backup_dir().join("manifest.json"))
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+    
+    /// Write `content` to `path`, first saving whatever was there.
+    ///
+    /// Only the first write to a path is backed up, so switching between modes
+    /// still restores the file as it was before any mode was applied.
+    fn write_with_backup(&self, path: &Path, content: &str) -> std::io::Result<()> {
+        let relative = path
+            .strip_prefix(&self.project_root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .into_owned();
+        let mut manifest = self.load_manifest();
+        
+        if !manifest.contains_key(&relative) {
+            let backup_dir = self.backup_dir();
+            std::fs::create_dir_all(&backup_dir)?;
+            
+            let backup = if path.exists() {
+                let name = relative.replace(['/', '\\'], "__");
+                std::fs::copy(path, backup_dir.join(&name))?;
+                Some(name)
+            } else {
+                None
+            };
+            manifest.insert(relative, backup);
+            
+            let json = serde_json::to_string_pretty(&manifest)?;
+            std::fs::write(backup_dir.join("manifest.json"), json)?;
+        }
+        
+        std::fs::write(path, content)
+    }
+    
     /// Generate .cursorrules file
     fn inject_cursorrules(&self, mode: &CustomMode) -> std::io::Result<PathBuf> {
         let path = self.project_root.join(".cursorrules");
         let content = self.generate_full_rules(mode);
-        std::fs::write(&path, content)?;
+        self.write_with_backup(&path, &content)?;
         Ok(path)
     }
     
@@ -100,14 +175,16 @@ impl ModeInjector {
         let filename = format!("{}.mdc", mode.name.to_lowercase().replace(' ', "-"));
         let path = rules_dir.join(&filename);
         
+        // Cursor only applies .mdc rules with frontmatter; alwaysApply keeps the
+        // mode in every chat instead of waiting for a glob match
         let content = format!(
-            "# {}\n\n{}\n\n{}\n",
+            "---\ndescription: {}\nalwaysApply: true\n---\n\n# {}\n\n{}\n",
+            mode.description.replace('\n', " "),
             mode.name,
-            mode.description,
             mode.to_cursorrules()
         );
         
-        std::fs::write(&path, content)?;
+        self.write_with_backup(&path, &content)?;
         Ok(path)
     }
     
@@ -119,13 +196,13 @@ impl ModeInjector {
         // Update hints.md
         let hints_path = self.ai_workspace.join("hints.md");
         let hints_content = self.generate_hints(mode);
-        std::fs::write(&hints_path, hints_content)?;
+        self.write_with_backup(&hints_path, &hints_content)?;
         generated.push(hints_path);
         
         // Update relevant-tools.md
         let tools_path = self.ai_workspace.join("relevant-tools.md");
         let tools_content = self.generate_relevant_tools(mode);
-        std::fs::write(&tools_path, tools_content)?;
+        self.write_with_backup(&tools_path, &tools_content)?;
         generated.push(tools_path);
         
         // Update context/current.json
@@ -133,7 +210,7 @@ impl ModeInjector {
         std::fs::create_dir_all(&context_dir)?;
         let context_path = context_dir.join("current.json");
         let context_content = self.generate_context_json(mode);
-        std::fs::write(&context_path, context_content)?;
+        self.write_with_backup(&context_path, &context_content)?;
         generated.push(context_path);
         
         Ok(generated)
@@ -334,3 +411,34 @@ pub fn apply_mode(
     injector.inject(mode, target)
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inject_then_restore_vanilla() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        std::fs::write(root.join(".cursorrules"), "original rules").unwrap();
+        let injector = ModeInjector::new(root.clone());
+
+        let (review, planning) = (CustomMode::code_review(), CustomMode::planning());
+        injector.inject(&review, InjectionTarget::All).unwrap();
+        let rules_only = InjectionTarget::CursorRules;
+        injector.inject(&planning, rules_only).unwrap();
+        let rules = std::fs::read_to_string(root.join(".cursorrules")).unwrap();
+        assert!(rules.contains("Active Mode: Planning"));
+        let mdc = root.join(".cursor/rules/code-review.mdc");
+        assert!(std::fs::read_to_string(&mdc).unwrap().starts_with("---\n"));
+        assert!(root.join(BACKUP_DIR).join("manifest.json").exists());
+
+        let restored = injector.restore_vanilla().unwrap();
+        assert_eq!(restored.len(), 5);
+        let rules = std::fs::read_to_string(root.join(".cursorrules")).unwrap();
+        assert_eq!(rules, "original rules");
+        assert!(!mdc.exists());
+        assert!(!root.join(".ai-workspace/hints.md").exists());
+        assert!(!root.join(BACKUP_DIR).exists());
+    }
+}
@@ -22,7 +22,7 @@ pub use injection::{ModeInjector, InjectionTarget};
 pub use ui::{ModesPanel, ModesPanelEvent};
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use serde::{Deserialize, Serialize};
 
 /// Registry of all available custom modes
@@ -32,6 +32,7 @@ pub struct ModeRegistry {
     pub active_mode: Option<String>,
     
     /// All registered modes
+    #[serde(default)]
     pub modes: HashMap<String, CustomMode>,
     
     /// Path to modes directory
@@ -74,6 +75,9 @@ impl ModeRegistry {
     }
     
     /// Save registry to disk
+    ///
+    /// Mode files left over from deleted modes are removed so they don't come
+    /// back on the next load. Files that don't parse as a mode are left alone.
     pub fn save(&self) -> std::io::Result<()> {
         std::fs::create_dir_all(&self.modes_dir)?;
         
@@ -87,13 +91,43 @@ impl ModeRegistry {
         
         // Save individual mode files
         for (name, mode) in &self.modes {
-            let mode_path = self.modes_dir.join(format!("{}.json", name.to_lowercase().replace(' ', "-")));
+            let mode_path = self.modes_dir.join(mode_file_name(name));
             std::fs::write(&mode_path, serde_json::to_string_pretty(mode)?)?;
         }
         
+        for entry in std::fs::read_dir(&self.modes_dir)?.flatten() {
+            let path = entry.path();
+            if path.extension().map_or(true, |e| e != "json") {
+                continue;
+            }
+            let stale = std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|s| serde_json::from_str::<CustomMode>(&s).ok())
+                .map_or(false, |mode| !self.modes.contains_key(&mode.name));
+            if stale {
+                std::fs::remove_file(&path)?;
+            }
+        }
+        
         Ok(())
     }
     
+    /// Write a single mode to `dir` as a shareable JSON file
+    pub fn export_mode(&self, name: &str, dir: &Path) -> Result<PathBuf, String> {
+        let mode = self
+            .modes
+            .get(name)
+            .ok_or_else(|| format!("Mode '{}' not found", name))?;
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        
+        let path = dir.join(mode_file_name(name));
+        let json = serde_json::to_string_pretty(mode).map_err(|e| e.to_string())?;
+        std::fs::write(&path, json)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        Ok(path)
+    }
+    
     /// Get active mode
     pub fn active(&self) -> Option<&CustomMode> {
         self.active_mode.as_ref().and_then(|name| self.modes.get(name))
@@ -153,3 +187,84 @@ impl ModeRegistry {
     }
 }
 
+/// File name a mode is stored and exported under
+pub fn mode_file_name(name: &str) -> String {
+    format!("{}.json", name.to_lowercase().replace(' ', "-"))
+}
+
+/// Parse and validate a mode shared as JSON
+///
+/// Imported modes are never treated as built-in, so they can be edited and deleted.
+pub fn parse_mode(json: &str) -> Result<CustomMode, String> {
+    let mut mode: CustomMode =
+        serde_json::from_str(json).map_err(|e| format!("Not a valid mode file: {}", e))?;
+    mode.validate()?;
+    mode.builtin = false;
+    Ok(mode)
+}
+
+/// Read a mode file exported by [`ModeRegistry::export_mode`]
+pub fn read_mode_file(path: &Path) -> Result<CustomMode, String> {
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    parse_mode(&json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_load_and_remove_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut registry = ModeRegistry::load(dir.path().to_path_buf());
+        let mut mode = CustomMode::new("Docs Writer", "Writes docs");
+        mode.system_prompt = "Write clear docs".to_string();
+        registry.upsert(mode);
+        registry.set_active("Docs Writer");
+        registry.save().unwrap();
+
+        let mut loaded = ModeRegistry::load(dir.path().to_path_buf());
+        assert_eq!(loaded.active_mode.as_deref(), Some("Docs Writer"));
+        let prompt = &loaded.modes["Docs Writer"].system_prompt;
+        assert_eq!(prompt, "Write clear docs");
+
+        loaded.remove("Docs Writer");
+        loaded.save().unwrap();
+        let reloaded = ModeRegistry::load(dir.path().to_path_buf());
+        assert!(!reloaded.modes.contains_key("Docs Writer"));
+        assert!(reloaded.active_mode.is_none());
+        assert!(reloaded.modes.contains_key("Agent"));
+    }
+
+    #[test]
+    fn test_export_then_import() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = ModeRegistry::load(dir.path().join("modes"));
+        let export_dir = dir.path().join("export");
+        let path = registry.export_mode("Code Review", &export_dir).unwrap();
+        assert!(path.ends_with("code-review.json"));
+
+        let imported = read_mode_file(&path).unwrap();
+        assert_eq!(imported.name, "Code Review");
+        assert!(!imported.builtin);
+        assert!(registry.export_mode("Missing", dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_parse_mode_rejects_invalid() {
+        assert!(parse_mode("not json").is_err());
+        assert!(parse_mode(r#"{"name": "Partial"}"#).is_err());
+
+        let mut mode = CustomMode::new("Tuned", "");
+        mode.model.temperature = Some(3.5);
+        let json = serde_json::to_string(&mode).unwrap();
+        assert!(parse_mode(&json).unwrap_err().contains("Temperature"));
+
+        mode.model.temperature = Some(0.2);
+        mode.name = "../escape".to_string();
+        let json = serde_json::to_string(&mode).unwrap();
+        assert!(parse_mode(&json).is_err());
+    }
+}
+
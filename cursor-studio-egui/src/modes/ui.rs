@@ -11,6 +11,7 @@
 
 use egui::{self, Color32, CursorIcon, RichText, TextWrapMode};
 use super::{CustomMode, ModeRegistry, ModeConfig, ToolAccess, ModelConfig, ContextConfig};
+use super::read_mode_file;
 use super::config::AccessMode;
 use super::injection::{ModeInjector, InjectionTarget};
 use std::path::PathBuf;
@@ -29,9 +30,20 @@ pub enum ModesPanelEvent {
     /// Mode was deleted
     ModeDeleted(String),
     /// Mode was injected to Cursor
-    ModeInjected { mode: String, target: InjectionTarget },
-    /// Quick swap to vanilla
-    VanillaSwap { from_mode: String },
+    ModeInjected {
+        mode: String,
+        target: InjectionTarget,
+        paths: Vec<PathBuf>,
+    },
+    /// Quick swap to vanilla, with the files restored from backup
+    VanillaSwap {
+        from_mode: String,
+        restored: Vec<PathBuf>,
+    },
+    /// Mode was written to a shareable file
+    ModeExported { mode: String, path: PathBuf },
+    /// Mode was imported from a file
+    ModeImported(String),
 }
 
 /// UI state for the modes panel
@@ -48,8 +60,15 @@ pub struct ModesPanel {
     /// Config
     config: ModeConfig,
     
-    /// Project root for injection
-    project_root: PathBuf,
+    /// Project the user chose and confirmed for injection; nothing is
+    /// written until this is set
+    project_root: Option<PathBuf>,
+    
+    /// Project root text input
+    project_root_input: String,
+    
+    /// Typed project directory waiting for the user to confirm writes there
+    pending_project_root: Option<PathBuf>,
     
     /// Show mode creation dialog
     show_create_dialog: bool,
@@ -60,6 +79,18 @@ pub struct ModesPanel {
     /// New mode name input
     new_mode_name: String,
     
+    /// Show mode import dialog
+    show_import_dialog: bool,
+    
+    /// Path of the mode file to import
+    import_path: String,
+    
+    /// Imported mode waiting for overwrite confirmation
+    pending_import: Option<CustomMode>,
+    
+    /// Directory modes are exported to
+    export_dir: PathBuf,
+    
     /// Status message
     status_message: Option<(String, bool)>, // (message, is_error)
     
@@ -98,9 +129,15 @@ struct ModeEditor {
     is_dirty: bool,
 }
 
+impl Default for ModesPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ModesPanel {
     /// Create a new modes panel
-    pub fn new(project_root: PathBuf) -> Self {
+    pub fn new() -> Self {
         let modes_dir = dirs::config_dir()
             .unwrap_or_else(|| PathBuf::from(".config"))
             .join("cursor-studio")
@@ -113,10 +150,20 @@ impl ModesPanel {
             selected_mode: None,
             editor: ModeEditor::default(),
             config: ModeConfig::default(),
-            project_root,
+            project_root: None,
+            project_root_input: String::new(),
+            pending_project_root: None,
             show_create_dialog: false,
             show_delete_confirm: None,
             new_mode_name: String::new(),
+            show_import_dialog: false,
+            import_path: String::new(),
+            pending_import: None,
+            export_dir: dirs::document_dir()
+                .or_else(dirs::home_dir)
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("cursor-studio-exports")
+                .join("modes"),
             status_message: None,
             events: Vec::new(),
         }
@@ -170,6 +217,8 @@ impl ModesPanel {
         // Dialogs
         self.show_create_dialog(ui, theme);
         self.show_delete_dialog(ui, theme);
+        self.show_import_dialog(ui, theme);
+        self.show_overwrite_dialog(ui, theme);
     }
     
     fn show_header(&mut self, ui: &mut egui::Ui, theme: &Theme) {
@@ -182,13 +231,19 @@ impl ModesPanel {
                     self.show_create_dialog = true;
                     self.new_mode_name.clear();
                 }
+                let import = ui
+                    .add(egui::Button::new("📥 Import").small())
+                    .on_hover_text("Import a mode from a JSON file");
+                if import.clicked() {
+                    self.show_import_dialog = true;
+                }
             });
         });
         
         ui.add_space(4.0);
         ui.label(
             RichText::new("Custom modes for tool control, prompts & models")
-                .size(11.0)
+                .size(theme.text_size(11.0))
                 .color(theme.fg_dim),
         );
     }
@@ -229,21 +284,17 @@ impl ModesPanel {
                 
                 // Apply changes after ComboBox
                 if let Some(name) = new_active {
-                    self.registry.active_mode = Some(name.clone());
-                    self.events.push(ModesPanelEvent::ModeActivated(name));
+                    self.activate_mode(name);
                 }
                 if go_vanilla {
-                    if let Some(old) = self.registry.active_mode.take() {
-                        self.events.push(ModesPanelEvent::VanillaSwap { from_mode: old });
-                    }
+                    self.swap_to_vanilla();
                 }
                 
                 // Quick vanilla swap button
                 if self.registry.active_mode.is_some() {
-                    if ui.small_button("⟲ Vanilla").on_hover_text("Quick swap to vanilla Cursor").clicked() {
-                        if let Some(old) = self.registry.active_mode.take() {
-                            self.events.push(ModesPanelEvent::VanillaSwap { from_mode: old });
-                        }
+                    let hint = "Quick swap to vanilla Cursor, restoring the original rules files";
+                    if ui.small_button("⟲ Vanilla").on_hover_text(hint).clicked() {
+                        self.swap_to_vanilla();
                     }
                 }
             });
@@ -283,6 +334,7 @@ impl ModesPanel {
         // Actions to perform after iteration
         let mut edit_mode: Option<String> = None;
         let mut delete_mode: Option<String> = None;
+        let mut export_mode: Option<String> = None;
         let mut activate_mode: Option<String> = None;
         
         for info in &mode_infos {
@@ -309,13 +361,25 @@ impl ModesPanel {
                             ui.horizontal(|ui| {
                                 ui.label(RichText::new(&info.name).strong().color(theme.fg));
                                 if is_active {
-                                    ui.label(RichText::new("●").size(8.0).color(theme.accent));
+                                    ui.label(
+                                        RichText::new("●")
+                                            .size(theme.text_size(8.0))
+                                            .color(theme.accent),
+                                    );
                                 }
                                 if info.builtin {
-                                    ui.label(RichText::new("[built-in]").size(9.0).color(theme.fg_dim));
+                                    ui.label(
+                                        RichText::new("[built-in]")
+                                            .size(theme.text_size(9.0))
+                                            .color(theme.fg_dim),
+                                    );
                                 }
                             });
-                            ui.label(RichText::new(&info.description).size(10.0).color(theme.fg_dim));
+                            ui.label(
+                                RichText::new(&info.description)
+                                    .size(theme.text_size(10.0))
+                                    .color(theme.fg_dim),
+                            );
                             
                             // Tool/model summary
                             ui.horizontal(|ui| {
@@ -324,9 +388,21 @@ impl ModesPanel {
                                     AccessMode::Allowlist => format!("🔒 {} tools", info.tools_allowed_count),
                                     AccessMode::Blocklist => format!("🚫 {} blocked", info.tools_blocked_count),
                                 };
-                                ui.label(RichText::new(tool_badge).size(9.0).color(theme.fg_dim));
-                                ui.label(RichText::new("•").size(9.0).color(theme.fg_dim));
-                                ui.label(RichText::new(&info.model_primary).size(9.0).color(theme.fg_dim));
+                                ui.label(
+                                    RichText::new(tool_badge)
+                                        .size(theme.text_size(9.0))
+                                        .color(theme.fg_dim),
+                                );
+                                ui.label(
+                                    RichText::new("•")
+                                        .size(theme.text_size(9.0))
+                                        .color(theme.fg_dim),
+                                );
+                                ui.label(
+                                    RichText::new(&info.model_primary)
+                                        .size(theme.text_size(9.0))
+                                        .color(theme.fg_dim),
+                                );
                             });
                         });
                         
@@ -336,6 +412,12 @@ impl ModesPanel {
                                 edit_mode = Some(info.name.clone());
                             }
                             
+                            // Export button
+                            let export_hint = format!("Export to {}", self.export_dir.display());
+                            if ui.small_button("📤").on_hover_text(export_hint).clicked() {
+                                export_mode = Some(info.name.clone());
+                            }
+                            
                             // Delete button (not for built-ins)
                             if !info.builtin {
                                 if ui.small_button("🗑️").on_hover_text("Delete mode").clicked() {
@@ -364,9 +446,17 @@ impl ModesPanel {
         if let Some(name) = delete_mode {
             self.show_delete_confirm = Some(name);
         }
+        if let Some(name) = export_mode {
+            match self.registry.export_mode(&name, &self.export_dir) {
+                Ok(path) => {
+                    self.status_message = Some((format!("Exported to {}", path.display()), false));
+                    self.events.push(ModesPanelEvent::ModeExported { mode: name, path });
+                }
+                Err(e) => self.status_message = Some((e, true)),
+            }
+        }
         if let Some(name) = activate_mode {
-            self.registry.set_active(&name);
-            self.events.push(ModesPanelEvent::ModeActivated(name));
+            self.activate_mode(name);
         }
     }
     
@@ -454,7 +544,11 @@ impl ModesPanel {
     }
     
     fn show_prompt_editor(&mut self, ui: &mut egui::Ui, theme: &Theme) {
-        ui.label(RichText::new("System prompt injected at the start of every conversation:").size(10.0).color(theme.fg_dim));
+        ui.label(
+            RichText::new("System prompt injected at the start of every conversation:")
+                .size(theme.text_size(10.0))
+                .color(theme.fg_dim),
+        );
         ui.add_space(4.0);
         
         let text_edit = egui::TextEdit::multiline(&mut self.editor.system_prompt)
@@ -467,11 +561,19 @@ impl ModesPanel {
         }
         
         ui.add_space(4.0);
-        ui.label(RichText::new(format!("{} characters", self.editor.system_prompt.len())).size(9.0).color(theme.fg_dim));
+        ui.label(
+            RichText::new(format!("{} characters", self.editor.system_prompt.len()))
+                .size(theme.text_size(9.0))
+                .color(theme.fg_dim),
+        );
     }
     
     fn show_tools_editor(&mut self, ui: &mut egui::Ui, theme: &Theme) {
-        ui.label(RichText::new("Control which tools the AI can use:").size(10.0).color(theme.fg_dim));
+        ui.label(
+            RichText::new("Control which tools the AI can use:")
+                .size(theme.text_size(10.0))
+                .color(theme.fg_dim),
+        );
         ui.add_space(4.0);
         
         ui.horizontal(|ui| {
@@ -512,7 +614,13 @@ impl ModesPanel {
         }
         
         ui.add_space(8.0);
-        ui.label(RichText::new("Common tools: read_file, write, edit_file, delete_file, grep, run_terminal_cmd").size(9.0).color(theme.fg_dim));
+        ui.label(
+            RichText::new(
+                "Common tools: read_file, write, edit_file, delete_file, grep, run_terminal_cmd",
+            )
+            .size(theme.text_size(9.0))
+            .color(theme.fg_dim),
+        );
     }
     
     fn show_tool_list_editor(&mut self, ui: &mut egui::Ui, theme: &Theme, is_allowlist: bool) {
@@ -523,7 +631,7 @@ impl ModesPanel {
         ui.horizontal_wrapped(|ui| {
             for tool in &tools_vec {
                 ui.horizontal(|ui| {
-                    ui.label(RichText::new(format!("• {}", tool)).size(11.0));
+                    ui.label(RichText::new(format!("• {}", tool)).size(theme.text_size(11.0)));
                     if ui.small_button("×").clicked() {
                         tools.remove(tool);
                         self.editor.is_dirty = true;
@@ -544,7 +652,7 @@ impl ModesPanel {
         
         // Quick add buttons
         ui.horizontal_wrapped(|ui| {
-            ui.label(RichText::new("Quick add:").size(10.0).color(theme.fg_dim));
+            ui.label(RichText::new("Quick add:").size(theme.text_size(10.0)).color(theme.fg_dim));
             let common_tools = ["read_file", "write", "edit_file", "delete_file", "grep", "run_terminal_cmd", "mcp_memory_create_entities", "mcp_github_create_pull_request"];
             for tool in common_tools {
                 if !tools.contains(tool) {
@@ -558,7 +666,11 @@ impl ModesPanel {
     }
     
     fn show_model_editor(&mut self, ui: &mut egui::Ui, theme: &Theme) {
-        ui.label(RichText::new("Configure model preferences for this mode:").size(10.0).color(theme.fg_dim));
+        ui.label(
+            RichText::new("Configure model preferences for this mode:")
+                .size(theme.text_size(10.0))
+                .color(theme.fg_dim),
+        );
         ui.add_space(4.0);
         
         egui::Grid::new("model_config_grid")
@@ -599,7 +711,11 @@ impl ModesPanel {
                     if ui.text_edit_singleline(&mut self.editor.temperature).changed() {
                         self.editor.is_dirty = true;
                     }
-                    ui.label(RichText::new("(0.0-2.0, empty=default)").size(9.0).color(theme.fg_dim));
+                    ui.label(
+                        RichText::new("(0.0-2.0, empty=default)")
+                            .size(theme.text_size(9.0))
+                            .color(theme.fg_dim),
+                    );
                 });
                 ui.end_row();
                 
@@ -608,7 +724,11 @@ impl ModesPanel {
                     if ui.text_edit_singleline(&mut self.editor.max_tokens).changed() {
                         self.editor.is_dirty = true;
                     }
-                    ui.label(RichText::new("(empty=default)").size(9.0).color(theme.fg_dim));
+                    ui.label(
+                        RichText::new("(empty=default)")
+                            .size(theme.text_size(9.0))
+                            .color(theme.fg_dim),
+                    );
                 });
                 ui.end_row();
             });
@@ -617,43 +737,67 @@ impl ModesPanel {
         
         // Model override info
         ui.group(|ui| {
-            ui.label(RichText::new("💡 Model Override").size(11.0).color(theme.accent));
+            ui.label(
+                RichText::new("💡 Model Override")
+                    .size(theme.text_size(11.0))
+                    .color(theme.accent),
+            );
             ui.label(RichText::new(
                 "When this mode is active, Cursor Studio will inject model preferences into the context. \
                  The actual model used depends on your Cursor subscription and availability."
-            ).size(10.0).color(theme.fg_dim));
+            ).size(theme.text_size(10.0)).color(theme.fg_dim));
         });
     }
     
     fn show_context_editor(&mut self, ui: &mut egui::Ui, theme: &Theme) {
-        ui.label(RichText::new("Control what context is automatically injected:").size(10.0).color(theme.fg_dim));
+        ui.label(
+            RichText::new("Control what context is automatically injected:")
+                .size(theme.text_size(10.0))
+                .color(theme.fg_dim),
+        );
         ui.add_space(4.0);
         
         ui.horizontal(|ui| {
             if ui.checkbox(&mut self.editor.include_environment, "Include environment info").changed() {
                 self.editor.is_dirty = true;
             }
-            ui.label(RichText::new("(hostname, OS, user)").size(9.0).color(theme.fg_dim));
+            ui.label(
+                RichText::new("(hostname, OS, user)")
+                    .size(theme.text_size(9.0))
+                    .color(theme.fg_dim),
+            );
         });
         
         ui.horizontal(|ui| {
             if ui.checkbox(&mut self.editor.include_git, "Include git state").changed() {
                 self.editor.is_dirty = true;
             }
-            ui.label(RichText::new("(branch, uncommitted, status)").size(9.0).color(theme.fg_dim));
+            ui.label(
+                RichText::new("(branch, uncommitted, status)")
+                    .size(theme.text_size(9.0))
+                    .color(theme.fg_dim),
+            );
         });
         
         ui.horizontal(|ui| {
             if ui.checkbox(&mut self.editor.include_project, "Include project context").changed() {
                 self.editor.is_dirty = true;
             }
-            ui.label(RichText::new("(project-specific hints)").size(9.0).color(theme.fg_dim));
+            ui.label(
+                RichText::new("(project-specific hints)")
+                    .size(theme.text_size(9.0))
+                    .color(theme.fg_dim),
+            );
         });
         
         ui.add_space(8.0);
         
         ui.label("Additional files to include:");
-        ui.label(RichText::new("(one path per line, relative to project root)").size(9.0).color(theme.fg_dim));
+        ui.label(
+            RichText::new("(one path per line, relative to project root)")
+                .size(theme.text_size(9.0))
+                .color(theme.fg_dim),
+        );
         if ui.text_edit_multiline(&mut self.editor.include_files_input).changed() {
             self.editor.is_dirty = true;
         }
@@ -661,7 +805,11 @@ impl ModesPanel {
         ui.add_space(8.0);
         
         ui.label("Custom injection:");
-        ui.label(RichText::new("(raw text appended to context)").size(9.0).color(theme.fg_dim));
+        ui.label(
+            RichText::new("(raw text appended to context)")
+                .size(theme.text_size(9.0))
+                .color(theme.fg_dim),
+        );
         if ui.text_edit_multiline(&mut self.editor.custom_injection).changed() {
             self.editor.is_dirty = true;
         }
@@ -676,7 +824,43 @@ impl ModesPanel {
         ui.add_space(8.0);
         
         ui.label(RichText::new("🚀 Inject Active Mode").size(12.0).strong().color(theme.fg));
-        ui.label(RichText::new("Apply mode configuration to Cursor:").size(10.0).color(theme.fg_dim));
+        ui.label(
+            RichText::new("Apply mode configuration to Cursor:")
+                .size(theme.text_size(10.0))
+                .color(theme.fg_dim),
+        );
+        
+        ui.add_space(4.0);
+        
+        ui.horizontal(|ui| {
+            ui.label(RichText::new("Project:").size(theme.text_size(10.0)).color(theme.fg_dim));
+            let input = egui::TextEdit::singleline(&mut self.project_root_input)
+                .desired_width(f32::INFINITY);
+            let hint = "Rules are written into this project. Files they replace are backed up \
+                        and put back by ⟲ Vanilla.";
+            let response = ui.add(input.hint_text("choose a project directory")).on_hover_text(hint);
+            if response.lost_focus() {
+                self.set_project_root();
+            }
+        });
+        
+        if let Some(path) = self.pending_project_root.clone() {
+            ui.horizontal(|ui| {
+                ui.label(
+                    RichText::new(format!("Write mode files into {}?", path.display()))
+                        .size(theme.text_size(10.0))
+                        .color(theme.warning),
+                );
+                if ui.button("✓ Use this project").clicked() {
+                    self.project_root = Some(path);
+                    self.pending_project_root = None;
+                }
+                if ui.button("Cancel").clicked() {
+                    self.pending_project_root = None;
+                    self.project_root_input = self.project_root_display();
+                }
+            });
+        }
         
         ui.add_space(4.0);
         
@@ -741,7 +925,7 @@ impl ModesPanel {
             let name = self.new_mode_name.clone();
             let mode = CustomMode::new(&name, "New custom mode");
             self.registry.upsert(mode);
-            let _ = self.registry.save();
+            self.persist();
             self.events.push(ModesPanelEvent::ModeUpdated(name.clone()));
             self.selected_mode = Some(name.clone());
             self.load_mode_to_editor(&name);
@@ -769,7 +953,7 @@ impl ModesPanel {
                     
                     if ui.button(RichText::new("Delete").color(Color32::from_rgb(255, 100, 100))).clicked() {
                         self.registry.remove(&mode_name);
-                        let _ = self.registry.save();
+                        self.persist();
                         self.events.push(ModesPanelEvent::ModeDeleted(mode_name.clone()));
                         if self.selected_mode.as_ref() == Some(&mode_name) {
                             self.selected_mode = None;
@@ -780,6 +964,97 @@ impl ModesPanel {
             });
     }
     
+    fn show_import_dialog(&mut self, ui: &mut egui::Ui, theme: &Theme) {
+        if !self.show_import_dialog {
+            return;
+        }
+        
+        let mut should_import = false;
+        let mut should_cancel = false;
+        
+        egui::Window::new("Import Mode")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ui.ctx(), |ui| {
+                ui.label("Mode file (.json):");
+                let hint = self.export_dir.join("my-mode.json").display().to_string();
+                let input = egui::TextEdit::singleline(&mut self.import_path).hint_text(hint);
+                let response = ui.add(input);
+                if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    should_import = true;
+                }
+                ui.label(
+                    RichText::new("Files written by 📤 Export can be shared and imported here")
+                        .size(theme.text_size(10.0))
+                        .color(theme.fg_dim),
+                );
+                
+                ui.add_space(8.0);
+                
+                ui.horizontal(|ui| {
+                    if ui.button("Cancel").clicked() {
+                        should_cancel = true;
+                    }
+                    
+                    ui.add_enabled_ui(!self.import_path.trim().is_empty(), |ui| {
+                        if ui.button("Import").clicked() {
+                            should_import = true;
+                        }
+                    });
+                });
+            });
+        
+        if should_cancel {
+            self.show_import_dialog = false;
+        }
+        if should_import && !self.import_path.trim().is_empty() {
+            self.import_from_path();
+        }
+    }
+    
+    fn show_overwrite_dialog(&mut self, ui: &mut egui::Ui, _theme: &Theme) {
+        let Some(name) = self.pending_import.as_ref().map(|m| m.name.clone()) else { return };
+        
+        let mut confirmed = false;
+        let mut cancelled = false;
+        
+        egui::Window::new("Replace Mode?")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ui.ctx(), |ui| {
+                ui.label(format!("A mode named '{}' already exists.", name));
+                ui.label(
+                    RichText::new("Importing will replace its prompt, tools and model settings.")
+                        .color(Color32::from_rgb(255, 100, 100)),
+                );
+                
+                ui.add_space(8.0);
+                
+                ui.horizontal(|ui| {
+                    if ui.button("Cancel").clicked() {
+                        cancelled = true;
+                    }
+                    
+                    let replace = RichText::new("Replace").color(Color32::from_rgb(255, 100, 100));
+                    if ui.button(replace).clicked() {
+                        confirmed = true;
+                    }
+                });
+            });
+        
+        if cancelled {
+            self.pending_import = None;
+            self.status_message = Some((format!("Import of '{}' cancelled", name), false));
+        }
+        if confirmed {
+            if let Some(mode) = self.pending_import.take() {
+                self.apply_import(mode);
+            }
+        }
+    }
+    
     fn load_mode_to_editor(&mut self, name: &str) {
         let Some(mode) = self.registry.modes.get(name) else { return };
         
@@ -838,10 +1113,46 @@ impl ModesPanel {
         
         let name = mode.name.clone();
         self.registry.upsert(mode);
-        let _ = self.registry.save();
         self.events.push(ModesPanelEvent::ModeUpdated(name));
         self.editor.is_dirty = false;
-        self.status_message = Some(("Mode saved!".to_string(), false));
+        if self.persist() {
+            self.status_message = Some(("Mode saved!".to_string(), false));
+        }
+    }
+    
+    /// Write the registry to disk, reporting failures in the status line
+    fn persist(&mut self) -> bool {
+        match self.registry.save() {
+            Ok(()) => true,
+            Err(e) => {
+                self.status_message = Some((format!("Failed to save modes: {}", e), true));
+                false
+            }
+        }
+    }
+    
+    fn import_from_path(&mut self) {
+        let path = PathBuf::from(self.import_path.trim());
+        match read_mode_file(&path) {
+            Ok(mode) if self.registry.modes.contains_key(&mode.name) => {
+                self.pending_import = Some(mode);
+            }
+            Ok(mode) => self.apply_import(mode),
+            Err(e) => self.status_message = Some((e, true)),
+        }
+        self.show_import_dialog = false;
+    }
+    
+    fn apply_import(&mut self, mode: CustomMode) {
+        let name = mode.name.clone();
+        self.registry.upsert(mode);
+        if self.persist() {
+            self.status_message = Some((format!("Imported mode '{}'", name), false));
+        }
+        if self.selected_mode.as_ref() == Some(&name) {
+            self.load_mode_to_editor(&name);
+        }
+        self.events.push(ModesPanelEvent::ModeImported(name));
     }
     
     fn inject_mode(&mut self, target: InjectionTarget) {
@@ -855,13 +1166,19 @@ impl ModesPanel {
             return;
         };
         
-        let injector = ModeInjector::new(self.project_root.clone());
+        let Some(project_root) = self.project_root.clone() else {
+            self.status_message = Some(("Choose a project directory first".to_string(), true));
+            return;
+        };
+        
+        let injector = ModeInjector::new(project_root);
         match injector.inject(mode, target) {
             Ok(paths) => {
-                self.status_message = Some((format!("Injected to {} files", paths.len()), false));
+                self.status_message = Some((format!("Wrote {}", describe_paths(&paths)), false));
                 self.events.push(ModesPanelEvent::ModeInjected { 
                     mode: mode_name.clone(), 
-                    target 
+                    target,
+                    paths,
                 });
             }
             Err(e) => {
@@ -869,5 +1186,96 @@ impl ModesPanel {
             }
         }
     }
+    
+    /// Make `name` the active mode and, when auto-generation is on and a project
+    /// has been confirmed, write it into the project's .cursorrules
+    fn activate_mode(&mut self, name: String) {
+        if !self.registry.set_active(&name) {
+            return;
+        }
+        self.persist();
+        self.events.push(ModesPanelEvent::ModeActivated(name));
+        if self.config.auto_generate_cursorrules && self.project_root.is_some() {
+            self.inject_mode(InjectionTarget::CursorRules);
+        }
+    }
+    
+    /// Deactivate the current mode and put back the files injection replaced
+    fn swap_to_vanilla(&mut self) {
+        let Some(from_mode) = self.registry.active_mode.take() else { return };
+        self.persist();
+        
+        let Some(project_root) = self.project_root.clone() else {
+            self.status_message = Some(("Vanilla: no project chosen, nothing to restore".to_string(), false));
+            self.events.push(ModesPanelEvent::VanillaSwap { from_mode, restored: Vec::new() });
+            return;
+        };
+        
+        let injector = ModeInjector::new(project_root);
+        let restored = match injector.restore_vanilla() {
+            Ok(restored) if restored.is_empty() => {
+                let msg = "Vanilla: no injected files to restore".to_string();
+                self.status_message = Some((msg, false));
+                restored
+            }
+            Ok(restored) => {
+                let msg = format!("Vanilla: restored {}", describe_paths(&restored));
+                self.status_message = Some((msg, false));
+                restored
+            }
+            Err(e) => {
+                let msg = format!("Failed to restore original files: {}", e);
+                self.status_message = Some((msg, true));
+                Vec::new()
+            }
+        };
+        self.events.push(ModesPanelEvent::VanillaSwap {
+            from_mode,
+            restored,
+        });
+    }
+    
+    /// Stage the typed directory; it is used only once the user confirms it
+    fn set_project_root(&mut self) {
+        let input = self.project_root_input.trim();
+        if input.is_empty() {
+            return;
+        }
+        let path = PathBuf::from(input);
+        if self.project_root.as_ref() == Some(&path) {
+            self.pending_project_root = None;
+        } else if path.is_dir() {
+            self.pending_project_root = Some(path);
+        } else {
+            let msg = format!("Not a directory: {}", path.display());
+            self.status_message = Some((msg, true));
+            self.project_root_input = self.project_root_display();
+        }
+    }
+    
+    fn project_root_display(&self) -> String {
+        self.project_root.as_ref().map(|p| p.display().to_string()).unwrap_or_default()
+    }
 }
 
+/// Short, readable list of written files for the status line
+fn describe_paths(paths: &[PathBuf]) -> String {
+    match paths {
+        [path] => path.display().to_string(),
+        _ => {
+            let parent = common_parent(paths);
+            format!("{} files in {}", paths.len(), parent.display())
+        }
+    }
+}
+
+fn common_parent(paths: &[PathBuf]) -> PathBuf {
+    let first = paths.first().and_then(|p| p.parent());
+    let mut parent = first.map(PathBuf::from).unwrap_or_default();
+    while !paths.iter().all(|p| p.starts_with(&parent)) {
+        if !parent.pop() {
+            break;
+        }
+    }
+    parent
+}
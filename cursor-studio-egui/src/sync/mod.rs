@@ -35,5 +35,5 @@ pub use pipe_client::{
     AsyncPipeClient, ClientError, DaemonCommand, DaemonEvent, DaemonResponse, DaemonStatus,
     PipeClient,
 };
-pub use ui::{SyncStatusIndicator, SyncStatusPanel};
+pub use ui::{DaemonHealth, SyncStatusIndicator, SyncStatusPanel};
 pub use watcher::DatabaseWatcher;
@@ -21,6 +21,7 @@
 use serde::{Deserialize, Serialize};
 use std::fs::{File, OpenOptions};
 use std::io::{BufRead, BufReader, Write};
+use std::os::unix::fs::OpenOptionsExt;
 use std::path::PathBuf;
 use std::sync::mpsc::{channel, Receiver, Sender};
 use std::thread;
@@ -126,6 +127,7 @@ impl std::fmt::Display for ClientError {
 impl std::error::Error for ClientError {}
 
 /// Sync daemon IPC client
+#[derive(Debug, Clone)]
 pub struct PipeClient {
     cmd_pipe: PathBuf,
     resp_pipe: PathBuf,
@@ -181,11 +183,16 @@ impl PipeClient {
         let json =
             serde_json::to_string(&command).map_err(|e| ClientError::WriteError(e.to_string()))?;
 
-        // Write to command pipe (this will block until daemon reads)
+        // A blocking open would wait forever for a reader when the pipes
+        // outlived the daemon; non-blocking, a missing reader fails with ENXIO
         let mut cmd_file = OpenOptions::new()
             .write(true)
+            .custom_flags(libc::O_NONBLOCK)
             .open(&self.cmd_pipe)
-            .map_err(|e| ClientError::WriteError(e.to_string()))?;
+            .map_err(|e| match e.raw_os_error() {
+                Some(libc::ENXIO) => ClientError::WriteError("no daemon is reading".to_string()),
+                _ => ClientError::WriteError(e.to_string()),
+            })?;
 
         writeln!(cmd_file, "{}", json).map_err(|e| ClientError::WriteError(e.to_string()))?;
 
@@ -375,4 +382,19 @@ mod tests {
         assert_eq!(client.cmd_pipe, PathBuf::from(DEFAULT_CMD_PIPE));
         assert_eq!(client.resp_pipe, PathBuf::from(DEFAULT_RESP_PIPE));
     }
+
+    #[test]
+    fn test_pipe_without_reader_fails_fast() {
+        let dir = tempfile::tempdir().unwrap();
+        let cmd_pipe = dir.path().join("cmd.pipe");
+        let path = std::ffi::CString::new(cmd_pipe.to_str().unwrap()).unwrap();
+        assert_eq!(unsafe { libc::mkfifo(path.as_ptr(), 0o600) }, 0);
+
+        // Pipes left behind by a daemon that exited
+        let client = PipeClient::with_pipes(&cmd_pipe, dir.path().join("resp.pipe"));
+        match client.send_command(DaemonCommand::Status) {
+            Err(ClientError::WriteError(e)) => assert!(e.contains("no daemon")),
+            other => panic!("expected a write error, got {:?}", other),
+        }
+    }
 }
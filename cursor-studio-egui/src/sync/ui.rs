@@ -3,7 +3,7 @@
 //! egui panel showing real-time sync daemon status.
 
 use eframe::egui::{self, Color32, RichText, Ui, Vec2};
-use std::sync::mpsc::Receiver;
+use std::sync::mpsc::{Receiver, TryRecvError};
 use std::time::{Duration, Instant};
 
 use super::pipe_client::{
@@ -11,11 +11,59 @@ use super::pipe_client::{
     PipeClient, SyncStats,
 };
 
+/// Longest gap between health polls while the daemon is failing
+const MAX_POLL_BACKOFF: Duration = Duration::from_secs(60);
+/// How often a running probe is checked for its answer
+const PROBE_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Status and stats fetched on a worker thread; `None` when there are no pipes
+type Probe = Option<(Result<DaemonStatus, ClientError>, Result<SyncStats, ClientError>)>;
+
+/// Daemon connection health, from the last poll
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DaemonHealth {
+    /// Pipes are present and the daemon answered
+    Connected,
+    /// Pipes are present but the daemon failed to answer
+    Degraded,
+    /// No pipes, so no daemon
+    Down,
+}
+
+impl DaemonHealth {
+    fn classify(pipes_present: bool, status_ok: bool, stats_ok: bool) -> Self {
+        match (pipes_present, status_ok && stats_ok) {
+            (false, _) => DaemonHealth::Down,
+            (true, true) => DaemonHealth::Connected,
+            (true, false) => DaemonHealth::Degraded,
+        }
+    }
+
+    /// Indicator icon, color and label
+    fn indicator(self) -> (&'static str, Color32, &'static str) {
+        match self {
+            DaemonHealth::Connected => ("●", Color32::from_rgb(100, 200, 100), "Connected"),
+            DaemonHealth::Degraded => ("◐", Color32::YELLOW, "Degraded"),
+            DaemonHealth::Down => ("○", Color32::from_rgb(200, 100, 100), "Down"),
+        }
+    }
+}
+
+/// Delay before the next poll, doubling per consecutive failure
+fn poll_delay(interval: Duration, failures: u32) -> Duration {
+    interval
+        .saturating_mul(1 << failures.min(6))
+        .min(MAX_POLL_BACKOFF.max(interval))
+}
+
 /// Sync status panel state
 pub struct SyncStatusPanel {
-    /// Sync client (blocking)
+    /// Sync client (blocking, so probes run on a worker thread)
     client: PipeClient,
 
+    /// Answer of the probe that's running, if any
+    probe_rx: Option<Receiver<Probe>>,
+
     /// Async client for background operations
     async_client: Option<AsyncPipeClient>,
 
@@ -34,6 +82,15 @@ pub struct SyncStatusPanel {
     /// Whether daemon is connected
     daemon_connected: bool,
 
+    /// Health from the last poll
+    health: DaemonHealth,
+
+    /// Polls in a row that came back degraded or down
+    failed_polls: u32,
+
+    /// Last sync the daemon reported as successful, kept while it's unreachable
+    last_successful_sync: Option<String>,
+
     /// Last refresh time
     last_refresh: Instant,
 
@@ -61,12 +118,20 @@ impl SyncStatusPanel {
 
         Self {
             client,
+            probe_rx: None,
             async_client: None,
             event_rx: None,
             status: None,
             stats: None,
             last_error: None,
             daemon_connected,
+            health: if daemon_connected {
+                DaemonHealth::Connected
+            } else {
+                DaemonHealth::Down
+            },
+            failed_polls: 0,
+            last_successful_sync: None,
             last_refresh: Instant::now() - Duration::from_secs(60), // Force immediate refresh
             refresh_interval: Duration::from_secs(5),
             auto_refresh: true,
@@ -74,41 +139,109 @@ impl SyncStatusPanel {
         }
     }
 
-    /// Check for daemon and refresh status
+    /// Start probing the daemon in the background; `poll` picks up the answer
     pub fn refresh(&mut self) {
-        self.daemon_connected = self.client.is_daemon_running();
+        if self.probe_rx.is_some() {
+            return;
+        }
+        let client = self.client.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.probe_rx = Some(rx);
+        std::thread::spawn(move || {
+            let probe = client
+                .is_daemon_running()
+                .then(|| (client.status(), client.stats()));
+            let _ = tx.send(probe);
+        });
+    }
 
-        if !self.daemon_connected {
+    /// Take in the answer of a finished probe
+    fn apply_probe(&mut self, probe: Probe) {
+        self.last_refresh = Instant::now();
+        self.daemon_connected = probe.is_some();
+        let Some((status, stats)) = probe else {
             self.status = None;
             self.stats = None;
             self.last_error = Some("Daemon not running".to_string());
+            self.record_health(DaemonHealth::Down);
             return;
-        }
+        };
 
-        // Fetch status
-        match self.client.status() {
+        let status_ok = match status {
             Ok(status) => {
+                if status.last_sync.is_some() {
+                    self.last_successful_sync = status.last_sync.clone();
+                }
                 self.status = Some(status);
                 self.last_error = None;
+                true
             }
             Err(e) => {
                 self.last_error = Some(e.to_string());
+                false
             }
-        }
+        };
 
-        // Fetch stats
-        match self.client.stats() {
+        let stats_ok = match stats {
             Ok(stats) => {
                 self.stats = Some(stats);
+                true
             }
             Err(e) => {
                 if self.last_error.is_none() {
                     self.last_error = Some(e.to_string());
                 }
+                false
             }
+        };
+
+        self.record_health(DaemonHealth::classify(true, status_ok, stats_ok));
+    }
+
+    fn record_health(&mut self, health: DaemonHealth) {
+        self.health = health;
+        if health == DaemonHealth::Connected {
+            self.failed_polls = 0;
+        } else {
+            self.failed_polls = self.failed_polls.saturating_add(1);
         }
+    }
 
-        self.last_refresh = Instant::now();
+    /// Poll the daemon's health when the debounced interval has elapsed
+    ///
+    /// Meant to be called every frame; it never waits on the daemon, and
+    /// failing daemons are polled less often.
+    pub fn poll(&mut self, ctx: &egui::Context) {
+        if let Some(rx) = &self.probe_rx {
+            match rx.try_recv() {
+                Ok(probe) => {
+                    self.probe_rx = None;
+                    self.apply_probe(probe);
+                }
+                Err(TryRecvError::Empty) => {
+                    ctx.request_repaint_after(PROBE_CHECK_INTERVAL);
+                    return;
+                }
+                Err(TryRecvError::Disconnected) => {
+                    self.probe_rx = None;
+                    self.last_refresh = Instant::now();
+                    self.last_error = Some("Status probe failed".to_string());
+                    self.record_health(DaemonHealth::Degraded);
+                }
+            }
+        }
+
+        if !self.auto_refresh || self.sync_pending {
+            return;
+        }
+
+        let delay = poll_delay(self.refresh_interval, self.failed_polls);
+        let elapsed = self.last_refresh.elapsed();
+        if elapsed < delay {
+            ctx.request_repaint_after(delay - elapsed);
+            return;
+        }
+        self.refresh();
     }
 
     /// Trigger a sync operation
@@ -134,24 +267,15 @@ impl SyncStatusPanel {
 
     /// Render the sync status panel
     pub fn ui(&mut self, ui: &mut Ui) {
-        // Auto-refresh if enabled
-        if self.auto_refresh && self.last_refresh.elapsed() > self.refresh_interval {
-            self.refresh();
-        }
-
         ui.heading("🔄 Sync Daemon");
         ui.separator();
 
         // Connection status
         ui.horizontal(|ui| {
-            let (icon, color, text) = if self.daemon_connected {
-                ("●", Color32::from_rgb(100, 200, 100), "Connected")
-            } else {
-                ("○", Color32::from_rgb(200, 100, 100), "Disconnected")
-            };
+            let (icon, color, text) = self.health.indicator();
 
             ui.label(RichText::new(icon).color(color).size(16.0));
-            ui.label(text);
+            ui.label(RichText::new(text).color(color));
 
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                 if ui.button("⟳ Refresh").clicked() {
@@ -160,6 +284,15 @@ impl SyncStatusPanel {
             });
         });
 
+        ui.label(
+            RichText::new(format!(
+                "Last successful sync: {}",
+                self.last_successful_sync.as_deref().unwrap_or("Never")
+            ))
+            .small()
+            .color(Color32::GRAY),
+        );
+
         ui.add_space(8.0);
 
         // Status section
@@ -357,3 +490,47 @@ impl SyncStatusIndicator {
             .on_hover_text(tooltip)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_health_classification() {
+        let cases = [
+            ((false, true, true), DaemonHealth::Down),
+            ((true, true, true), DaemonHealth::Connected),
+            ((true, true, false), DaemonHealth::Degraded),
+            ((true, false, true), DaemonHealth::Degraded),
+        ];
+        for ((pipes, status, stats), expected) in cases {
+            assert_eq!(DaemonHealth::classify(pipes, status, stats), expected);
+        }
+    }
+
+    #[test]
+    fn test_poll_backoff() {
+        let interval = Duration::from_secs(5);
+        assert_eq!(poll_delay(interval, 0), interval);
+        assert_eq!(poll_delay(interval, 2), Duration::from_secs(20));
+        assert_eq!(poll_delay(interval, 30), MAX_POLL_BACKOFF);
+    }
+
+    #[test]
+    fn test_probe_runs_off_the_ui_thread() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut panel = SyncStatusPanel::new();
+        panel.client = PipeClient::with_pipes(dir.path().join("cmd"), dir.path().join("resp"));
+
+        let ctx = egui::Context::default();
+        panel.refresh();
+        assert!(panel.probe_rx.is_some());
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while panel.probe_rx.is_some() && Instant::now() < deadline {
+            panel.poll(&ctx);
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(panel.health, DaemonHealth::Down);
+        assert!(!panel.daemon_connected);
+    }
+}
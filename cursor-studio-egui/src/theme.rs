@@ -3,7 +3,12 @@
 use eframe::egui::Color32;
 use serde::Deserialize;
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Sizes the user's minimum can raise small text to; larger text is never clamped,
+/// so captions don't end up bigger than the body text around them
+pub const MIN_TEXT_SIZE_RANGE: std::ops::RangeInclusive<f32> = 8.0..=12.0;
 
 /// VS Code Dark+ inspired theme
 #[derive(Clone, Copy)]
@@ -48,6 +53,9 @@ pub struct Theme {
     pub syntax_variable: Color32,
     pub syntax_type: Color32,
     pub syntax_operator: Color32,
+
+    /// Smallest size small fixed-size labels are drawn at (see `text_size`)
+    pub min_text_size: f32,
 }
 
 impl Theme {
@@ -93,6 +101,8 @@ impl Theme {
             syntax_variable: Color32::from_rgb(156, 220, 254), // #9cdcfe - light blue
             syntax_type: Color32::from_rgb(78, 201, 176),    // #4ec9b0 - teal
             syntax_operator: Color32::from_rgb(212, 212, 212), // #d4d4d4 - light gray
+
+            min_text_size: 0.0,
         }
     }
 
@@ -138,17 +148,42 @@ impl Theme {
             syntax_variable: Color32::from_rgb(0, 16, 128), // #001080 - dark blue
             syntax_type: Color32::from_rgb(38, 127, 153), // #267f99 - teal
             syntax_operator: Color32::from_rgb(0, 0, 0),  // #000000 - black
+
+            min_text_size: 0.0,
         }
     }
 
+    /// This theme with small labels raised to `size` (clamped to
+    /// [`MIN_TEXT_SIZE_RANGE`])
+    pub fn with_min_text_size(mut self, size: f32) -> Self {
+        self.min_text_size = size.clamp(*MIN_TEXT_SIZE_RANGE.start(), *MIN_TEXT_SIZE_RANGE.end());
+        self
+    }
+
+    /// A fixed label size raised to the user's minimum text size
+    pub fn text_size(&self, size: f32) -> f32 {
+        size.max(self.min_text_size)
+    }
+
     /// Load a theme from a VS Code theme JSON file
     pub fn from_vscode_file(path: &Path) -> Option<Self> {
+        Self::from_vscode_file_detailed(path).map(|load| load.theme)
+    }
+
+    /// Load a theme file, reporting which colors fell back to defaults
+    pub fn from_vscode_file_detailed(path: &Path) -> Option<ThemeLoad> {
         let content = std::fs::read_to_string(path).ok()?;
-        Self::from_vscode_json(&content)
+        Self::from_vscode_json_detailed(&content)
     }
 
     /// Parse a VS Code theme from JSON content
     pub fn from_vscode_json(json_content: &str) -> Option<Self> {
+        Self::from_vscode_json_detailed(json_content).map(|load| load.theme)
+    }
+
+    /// Parse a VS Code theme, keeping every color that parsed and listing
+    /// the keys that were missing or unparseable
+    pub fn from_vscode_json_detailed(json_content: &str) -> Option<ThemeLoad> {
         // VS Code themes can have comments, so we need to strip them
         let cleaned = strip_json_comments(json_content);
 
@@ -175,165 +210,117 @@ impl Theme {
             Self::dark()
         };
 
-        // Track if we loaded any colors
-        let mut loaded_colors = false;
+        let empty = HashMap::new();
+        let mut lookup = ColorLookup {
+            colors: theme_json.colors.as_ref().unwrap_or(&empty),
+            missing: Vec::new(),
+            invalid: Vec::new(),
+        };
 
         // Parse colors from the theme
-        if let Some(colors) = &theme_json.colors {
-            loaded_colors = true;
-            
-            // Editor colors
-            if let Some(c) = colors.get("editor.background") {
-                if let Some(color) = parse_color(c) {
-                    theme.editor_bg = color;
-                    theme.bg = color;
-                    theme.tab_active_bg = color;
-                }
-            }
+        // Editor colors
+        if let Some(color) = lookup.required("editor.background") {
+            theme.editor_bg = color;
+            theme.bg = color;
+            theme.tab_active_bg = color;
+        }
 
-            if let Some(c) = colors.get("editor.foreground") {
-                if let Some(color) = parse_color(c) {
-                    theme.fg = color;
-                }
-            }
-            
-            // Additional fallback: try "foreground" if editor.foreground not found
-            if let Some(c) = colors.get("foreground") {
-                if let Some(color) = parse_color(c) {
-                    theme.fg_bright = color;
-                    // Only set fg if not already set
-                    if theme.fg == Theme::dark().fg {
-                        theme.fg = color;
-                    }
-                }
-            }
+        // Fall back to "foreground" if editor.foreground isn't defined
+        if let Some(color) = lookup.first_of(&["editor.foreground", "foreground"], |_| true) {
+            theme.fg = color;
+        }
 
-            // Sidebar
-            if let Some(c) = colors.get("sideBar.background") {
-                if let Some(color) = parse_color(c) {
-                    theme.sidebar_bg = color;
-                }
-            }
+        // Already reported above if unparseable
+        if let Some(color) = lookup.colors.get("foreground").and_then(|c| parse_color(c)) {
+            theme.fg_bright = color;
+        }
 
-            // Activity bar
-            if let Some(c) = colors.get("activityBar.background") {
-                if let Some(color) = parse_color(c) {
-                    theme.activitybar_bg = color;
-                }
-            }
+        // Sidebar
+        if let Some(color) = lookup.required("sideBar.background") {
+            theme.sidebar_bg = color;
+        }
 
-            // Status bar
-            if let Some(c) = colors.get("statusBar.background") {
-                if let Some(color) = parse_color(c) {
-                    theme.statusbar_bg = color;
-                }
-            }
+        // Activity bar
+        if let Some(color) = lookup.required("activityBar.background") {
+            theme.activitybar_bg = color;
+        }
 
-            // Tabs
-            if let Some(c) = colors.get("tab.inactiveBackground") {
-                if let Some(color) = parse_color(c) {
-                    theme.tab_bg = color;
-                }
-            }
+        // Status bar
+        if let Some(color) = lookup.required("statusBar.background") {
+            theme.statusbar_bg = color;
+        }
 
-            if let Some(c) = colors.get("tab.activeBackground") {
-                if let Some(color) = parse_color(c) {
-                    theme.tab_active_bg = color;
-                }
-            }
+        // Tabs
+        if let Some(color) = lookup.required("tab.inactiveBackground") {
+            theme.tab_bg = color;
+        }
 
-            // Input
-            if let Some(c) = colors.get("input.background") {
-                if let Some(color) = parse_color(c) {
-                    theme.input_bg = color;
-                }
-            }
+        if let Some(color) = lookup.required("tab.activeBackground") {
+            theme.tab_active_bg = color;
+        }
 
-            // Selection
-            if let Some(c) = colors.get("editor.selectionBackground") {
-                if let Some(color) = parse_color(c) {
-                    theme.selection = color;
-                }
-            }
+        // Input
+        if let Some(color) = lookup.required("input.background") {
+            theme.input_bg = color;
+        }
 
-            // Accent / focus - try multiple VS Code properties
-            // Priority: button.background > focusBorder > list.activeSelectionBackground
-            let accent_sources = [
-                "button.background",
-                "button.hoverBackground",
-                "focusBorder",
-                "list.activeSelectionBackground",
-                "activityBarBadge.background",
-            ];
-            for source in accent_sources {
-                if let Some(c) = colors.get(source) {
-                    if let Some(color) = parse_color(c) {
-                        // Only use if reasonably visible (not too dark or transparent)
-                        let brightness =
-                            (color.r() as u32 + color.g() as u32 + color.b() as u32) / 3;
-                        if brightness > 40 && color.a() > 128 {
-                            theme.accent = color;
-                            break;
-                        }
-                    }
-                }
-            }
+        // Selection
+        if let Some(color) = lookup.required("editor.selectionBackground") {
+            theme.selection = color;
+        }
 
-            // Selected state background - use list selection colors
-            let selection_sources = [
-                "list.activeSelectionBackground",
-                "list.inactiveSelectionBackground",
-                "button.background",
-                "editor.selectionBackground",
-            ];
-            for source in selection_sources {
-                if let Some(c) = colors.get(source) {
-                    if let Some(color) = parse_color(c) {
-                        let brightness =
-                            (color.r() as u32 + color.g() as u32 + color.b() as u32) / 3;
-                        if brightness > 30 && color.a() > 100 {
-                            theme.selected_bg = color;
-                            break;
-                        }
-                    }
-                }
-            }
+        // Accent / focus - try multiple VS Code properties
+        // Priority: button.background > focusBorder > list.activeSelectionBackground
+        let accent_sources = [
+            "button.background",
+            "button.hoverBackground",
+            "focusBorder",
+            "list.activeSelectionBackground",
+            "activityBarBadge.background",
+        ];
+        // Only use if reasonably visible (not too dark or transparent)
+        if let Some(color) =
+            lookup.first_of(&accent_sources, |c| brightness(c) > 40 && c.a() > 128)
+        {
+            theme.accent = color;
+        }
 
-            // Selected state foreground - use list selection foreground
-            let fg_selection_sources = [
-                "list.activeSelectionForeground",
-                "button.foreground",
-                "list.focusForeground",
-            ];
-            for source in fg_selection_sources {
-                if let Some(c) = colors.get(source) {
-                    if let Some(color) = parse_color(c) {
-                        theme.selected_fg = color;
-                        break;
-                    }
-                }
-            }
+        // Selected state background - use list selection colors
+        let selection_sources = [
+            "list.activeSelectionBackground",
+            "list.inactiveSelectionBackground",
+            "button.background",
+            "editor.selectionBackground",
+        ];
+        if let Some(color) =
+            lookup.first_of(&selection_sources, |c| brightness(c) > 30 && c.a() > 100)
+        {
+            theme.selected_bg = color;
+        }
 
-            // List hover
-            if let Some(c) = colors.get("list.hoverBackground") {
-                if let Some(color) = parse_color(c) {
-                    theme.list_hover = color;
-                }
-            }
+        // Selected state foreground - use list selection foreground
+        let fg_selection_sources = [
+            "list.activeSelectionForeground",
+            "button.foreground",
+            "list.focusForeground",
+        ];
+        if let Some(color) = lookup.first_of(&fg_selection_sources, |_| true) {
+            theme.selected_fg = color;
+        }
 
-            // Border
-            if let Some(c) = colors.get("panel.border") {
-                if let Some(color) = parse_color(c) {
-                    theme.border = color;
-                }
-            }
+        // List hover
+        if let Some(color) = lookup.required("list.hoverBackground") {
+            theme.list_hover = color;
+        }
 
-            // Foreground dim - try VS Code's descriptionForeground
-            if let Some(c) = colors.get("descriptionForeground") {
-                if let Some(color) = parse_color(c) {
-                    theme.fg_dim = color;
-                }
-            }
+        // Border
+        if let Some(color) = lookup.required("panel.border") {
+            theme.border = color;
+        }
+
+        // Foreground dim - try VS Code's descriptionForeground
+        if let Some(color) = lookup.required("descriptionForeground") {
+            theme.fg_dim = color;
         }
 
         // Compute selected state colors based on theme brightness
@@ -381,11 +368,15 @@ impl Theme {
         }
         
         // Log if no colors were found
-        if !loaded_colors {
+        if theme_json.colors.is_none() {
             log::info!("Theme has no 'colors' section, using defaults with token colors only");
         }
 
-        Some(theme)
+        Some(ThemeLoad {
+            theme,
+            missing: lookup.missing,
+            invalid: lookup.invalid,
+        })
     }
 
     /// Compute selected state colors based on theme brightness
@@ -445,7 +436,7 @@ enum VSCodeScope {
 }
 
 /// Parse a hex color string to Color32
-fn parse_color(color_str: &str) -> Option<Color32> {
+pub fn parse_color(color_str: &str) -> Option<Color32> {
     let hex = color_str.trim_start_matches('#');
 
     if hex.len() == 6 {
@@ -464,6 +455,61 @@ fn parse_color(color_str: &str) -> Option<Color32> {
     }
 }
 
+/// Format a color as `#rrggbb` (or `#rrggbbaa` when translucent)
+pub fn color_to_hex(color: Color32) -> String {
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    if a == 255 {
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    } else {
+        format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+    }
+}
+
+/// Read `workbench.colorTheme` from a Cursor/VS Code `settings.json`,
+/// which may contain comments and trailing commas
+pub fn color_theme_setting(settings_json: &str) -> Option<String> {
+    let cleaned = strip_trailing_commas(&strip_json_comments(settings_json));
+    let json: serde_json::Value = serde_json::from_str(&cleaned).ok()?;
+    json.get("workbench.colorTheme")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+}
+
+/// Drop commas directly before a closing `}` or `]` (allowed in settings.json)
+fn strip_trailing_commas(json: &str) -> String {
+    let chars: Vec<char> = json.chars().collect();
+    let mut result = String::with_capacity(json.len());
+    let mut in_string = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+        if in_string {
+            result.push(c);
+            if c == '\\' {
+                // Keep the escaped character as-is
+                if let Some(&next) = chars.get(i) {
+                    result.push(next);
+                    i += 1;
+                }
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else if c == ',' {
+            let next = chars[i..].iter().find(|nc| !nc.is_whitespace());
+            if !matches!(next, Some('}') | Some(']')) {
+                result.push(c);
+            }
+        } else {
+            in_string = c == '"';
+            result.push(c);
+        }
+    }
+
+    result
+}
+
 /// Strip C-style comments from JSON (VS Code themes often have comments)
 fn strip_json_comments(json: &str) -> String {
     let mut result = String::with_capacity(json.len());
@@ -513,6 +559,99 @@ fn strip_json_comments(json: &str) -> String {
     result
 }
 
+/// A parsed theme plus the color keys that fell back to defaults
+#[derive(Clone)]
+pub struct ThemeLoad {
+    pub theme: Theme,
+    /// Expected color keys the theme doesn't define
+    pub missing: Vec<String>,
+    /// Color keys whose values couldn't be parsed
+    pub invalid: Vec<String>,
+}
+
+impl ThemeLoad {
+    /// Number of colors that fell back to defaults
+    pub fn fallback_count(&self) -> usize {
+        self.missing.len() + self.invalid.len()
+    }
+}
+
+/// Looks up theme colors, recording keys that are absent or unparseable
+struct ColorLookup<'a> {
+    colors: &'a HashMap<String, String>,
+    missing: Vec<String>,
+    invalid: Vec<String>,
+}
+
+impl ColorLookup<'_> {
+    /// A color the theme is expected to define
+    fn required(&mut self, key: &str) -> Option<Color32> {
+        if !self.colors.contains_key(key) {
+            self.missing.push(key.to_string());
+        }
+        self.optional(key)
+    }
+
+    /// A fallback color; absence is fine but a bad value is still reported
+    fn optional(&mut self, key: &str) -> Option<Color32> {
+        let color = parse_color(self.colors.get(key)?);
+        if color.is_none() {
+            self.invalid.push(key.to_string());
+        }
+        color
+    }
+
+    /// First color among `keys` that passes `accept`; the group counts as
+    /// missing (under its first key) only if none of the keys is defined
+    fn first_of(&mut self, keys: &[&str], accept: impl Fn(Color32) -> bool) -> Option<Color32> {
+        for key in keys {
+            if let Some(color) = self.optional(key).filter(|c| accept(*c)) {
+                return Some(color);
+            }
+        }
+        if !keys.iter().any(|key| self.colors.contains_key(*key)) {
+            self.missing.push(keys[0].to_string());
+        }
+        None
+    }
+}
+
+/// Average of the RGB channels (0-255)
+fn brightness(color: Color32) -> u32 {
+    (color.r() as u32 + color.g() as u32 + color.b() as u32) / 3
+}
+
+/// Parsed VS Code themes keyed by file path; a file is only re-parsed when
+/// its modification time changes
+#[derive(Default)]
+pub struct ThemeCache {
+    entries: HashMap<PathBuf, (SystemTime, Option<ThemeLoad>)>,
+}
+
+impl ThemeCache {
+    /// Like `Theme::from_vscode_file_detailed`, but served from the cache when
+    /// the file is unchanged
+    pub fn load(&mut self, path: &Path) -> Option<ThemeLoad> {
+        let Ok(mtime) = std::fs::metadata(path).and_then(|m| m.modified()) else {
+            return Theme::from_vscode_file_detailed(path);
+        };
+        if let Some((cached_mtime, load)) = self.entries.get(path) {
+            if *cached_mtime == mtime {
+                return load.clone();
+            }
+        }
+
+        let load = Theme::from_vscode_file_detailed(path);
+        self.entries.insert(path.to_path_buf(), (mtime, load.clone()));
+        load
+    }
+
+    /// Forget every parsed theme (e.g. after rescanning extensions)
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -531,6 +670,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_min_text_size_floor() {
+        assert_eq!(Theme::dark().text_size(9.0), 9.0);
+        let theme = Theme::dark().with_min_text_size(11.0);
+        assert_eq!(theme.text_size(9.0), 11.0);
+        assert_eq!(theme.text_size(13.0), 13.0);
+        assert_eq!(Theme::light().with_min_text_size(40.0).text_size(9.0), 12.0);
+        assert_eq!(Theme::dark().with_min_text_size(0.0).text_size(9.0), 9.0);
+    }
+
+    #[test]
+    fn test_color_to_hex_roundtrip() {
+        assert_eq!(color_to_hex(Color32::from_rgb(255, 0, 16)), "#ff0010");
+        let teal = Color32::from_rgb(0, 128, 128);
+        assert_eq!(parse_color(&color_to_hex(teal)), Some(teal));
+    }
+
     #[test]
     fn test_strip_comments() {
         let with_comments = r#"{
@@ -543,5 +699,83 @@ mod tests {
         assert!(!stripped.contains("//"));
         assert!(!stripped.contains("/*"));
     }
+
+    #[test]
+    fn test_color_theme_setting() {
+        let settings = r#"{
+            // Picked in Cursor
+            "workbench.colorTheme": "Tokyo Night, Storm",
+            "editor.rulers": [80, 120,],
+            "files.exclude": { "**/.git": true, },
+        }"#;
+        assert_eq!(
+            color_theme_setting(settings).as_deref(),
+            Some("Tokyo Night, Storm")
+        );
+        assert_eq!(color_theme_setting(r#"{"editor.fontSize": 14}"#), None);
+        assert_eq!(color_theme_setting("not json"), None);
+    }
+
+    #[test]
+    fn test_partial_theme_report() {
+        let load = Theme::from_vscode_json_detailed(
+            r##"{
+                "colors": {
+                    "editor.background": "#1e1e1e",
+                    "sideBar.background": "not-a-color",
+                    "focusBorder": "#007acc"
+                }
+            }"##,
+        )
+        .unwrap();
+
+        // Parsed colors are still applied
+        assert_eq!(load.theme.bg, Color32::from_rgb(30, 30, 30));
+        assert_eq!(load.theme.accent, Color32::from_rgb(0, 122, 204));
+        assert_eq!(load.invalid, vec!["sideBar.background".to_string()]);
+        assert!(load.missing.contains(&"editor.foreground".to_string()));
+        assert!(!load.missing.contains(&"button.background".to_string()));
+        assert!(!load.missing.contains(&"editor.background".to_string()));
+        assert_eq!(load.fallback_count(), load.missing.len() + 1);
+    }
+
+    #[test]
+    fn test_fallback_key_not_reported_missing() {
+        let load = Theme::from_vscode_json_detailed(
+            r##"{"colors": {"foreground": "#cccccc"}}"##,
+        )
+        .unwrap();
+
+        assert_eq!(load.theme.fg, Color32::from_rgb(204, 204, 204));
+        assert!(!load.missing.contains(&"editor.foreground".to_string()));
+    }
+
+    #[test]
+    fn test_theme_cache_invalidates_on_mtime() {
+        let path =
+            std::env::temp_dir().join(format!("cursor-studio-theme-{}.json", std::process::id()));
+        std::fs::write(&path, r##"{"colors": {"editor.background": "#101010"}}"##).unwrap();
+
+        let mut cache = ThemeCache::default();
+        let first = cache.load(&path).unwrap().theme;
+        assert_eq!(first.bg, Color32::from_rgb(16, 16, 16));
+
+        // Same mtime: served from cache even though the content changed
+        let mtime = std::fs::metadata(&path).unwrap().modified().unwrap();
+        std::fs::write(&path, r##"{"colors": {"editor.background": "#202020"}}"##).unwrap();
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(mtime).unwrap();
+        assert_eq!(cache.load(&path).unwrap().theme.bg, first.bg);
+
+        // Newer mtime: re-parsed
+        file.set_modified(mtime + std::time::Duration::from_secs(5))
+            .unwrap();
+        assert_eq!(
+            cache.load(&path).unwrap().theme.bg,
+            Color32::from_rgb(32, 32, 32)
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
 }
 
@@ -695,10 +695,7 @@ impl ManualImport {
         let hash = compute_hash(source_path)?;
         
         // Create cache directory
-        let cache_dir = dirs::cache_dir()
-            .unwrap_or_else(|| PathBuf::from("/tmp"))
-            .join("cursor-studio")
-            .join("downloads");
+        let cache_dir = crate::versions::get_cache_dir();
         std::fs::create_dir_all(&cache_dir)?;
         
         // Copy to cache with correct filename
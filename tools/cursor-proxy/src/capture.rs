@@ -17,6 +17,31 @@ use tracing::{debug, error, info, warn};
 /// Maximum concurrent capture save operations
 const MAX_CONCURRENT_SAVES: usize = 10;
 
+/// Replacement for redacted header values and JSON fields
+pub const REDACTED: &str = "[redacted]";
+
+/// Headers carrying credentials (compared case-insensitively)
+const AUTH_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+    "x-api-key",
+    "x-client-key",
+    "x-cursor-checksum",
+];
+
+/// JSON fields carrying credentials (compared ignoring case and `_`)
+const AUTH_FIELDS: &[&str] = &[
+    "accesstoken",
+    "refreshtoken",
+    "authtoken",
+    "idtoken",
+    "apikey",
+    "password",
+    "secret",
+];
+
 /// Captured request/response pair
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CapturedExchange {
@@ -59,6 +84,88 @@ pub struct CapturedBody {
     pub truncated: bool,
 }
 
+/// Size-capped, display-ready copy of a body, kept with dashboard activity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BodyPreview {
+    /// Size of the whole body in bytes
+    pub size: usize,
+    /// Body text; JSON is pretty-printed, binary is summarized
+    pub text: String,
+    /// Whether the body parsed as JSON
+    pub is_json: bool,
+    /// Whether `text` was cut at the size cap
+    pub truncated: bool,
+}
+
+impl BodyPreview {
+    /// Preview `body`, keeping at most `max_bytes` of text
+    pub fn new(body: &[u8], content_type: Option<&str>, max_bytes: usize, redact: bool) -> Self {
+        let (text, is_json) = match serde_json::from_slice::<serde_json::Value>(body) {
+            Ok(mut json) => {
+                if redact {
+                    redact_json(&mut json);
+                }
+                let pretty = serde_json::to_string_pretty(&json)
+                    .unwrap_or_else(|_| String::from_utf8_lossy(body).into_owned());
+                (pretty, true)
+            }
+            Err(_) if is_binary_content(content_type, body) => {
+                (format!("<{} bytes of binary data>", body.len()), false)
+            }
+            Err(_) => (String::from_utf8_lossy(body).into_owned(), false),
+        };
+        
+        let truncated = text.len() > max_bytes;
+        let text = if truncated {
+            let mut end = max_bytes;
+            while !text.is_char_boundary(end) {
+                end -= 1;
+            }
+            text[..end].to_string()
+        } else {
+            text
+        };
+        
+        Self { size: body.len(), text, is_json, truncated }
+    }
+    
+    /// Text for display, marked when it was cut short
+    pub fn display(&self) -> String {
+        if self.truncated {
+            format!("{}\n… [truncated, {} bytes total]", self.text, self.size)
+        } else {
+            self.text.clone()
+        }
+    }
+}
+
+/// Replace credential header values with `REDACTED`
+pub fn redact_headers(headers: &mut [(String, String)]) {
+    for (name, value) in headers.iter_mut() {
+        if AUTH_HEADERS.iter().any(|h| name.eq_ignore_ascii_case(h)) {
+            *value = REDACTED.to_string();
+        }
+    }
+}
+
+/// Replace credential fields anywhere in a JSON document with `REDACTED`
+pub fn redact_json(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                let normalized = key.replace('_', "").to_lowercase();
+                if AUTH_FIELDS.contains(&normalized.as_str()) {
+                    *field = serde_json::Value::String(REDACTED.to_string());
+                } else {
+                    redact_json(field);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact_json),
+        _ => {}
+    }
+}
+
 /// Payload capturer
 #[derive(Clone)]
 pub struct PayloadCapturer {
@@ -74,6 +181,10 @@ pub struct PayloadCapturer {
     pending_saves: Arc<AtomicU64>,
     /// Retention period in days
     retention_days: u32,
+    /// Strip credentials from captured headers and JSON bodies
+    redact: bool,
+    /// Per-body cap for dashboard activity previews (0 = no previews)
+    preview_max: usize,
 }
 
 impl PayloadCapturer {
@@ -100,9 +211,33 @@ impl PayloadCapturer {
             save_semaphore: Arc::new(Semaphore::new(MAX_CONCURRENT_SAVES)),
             pending_saves: Arc::new(AtomicU64::new(0)),
             retention_days,
+            redact: true,
+            preview_max: 0,
         })
     }
     
+    /// Keep or strip credentials in captures (stripped by default)
+    pub fn with_redaction(mut self, redact: bool) -> Self {
+        self.redact = redact;
+        self
+    }
+    
+    /// Keep previews of up to `max_bytes` per body for dashboard activity
+    pub fn with_activity_previews(mut self, max_bytes: usize) -> Self {
+        self.preview_max = max_bytes;
+        self
+    }
+    
+    /// Whether captured headers and bodies have credentials stripped
+    pub fn redacts(&self) -> bool {
+        self.redact
+    }
+    
+    /// Maximum body size kept in a capture
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
+    
     /// Get number of pending save operations
     pub fn pending_saves(&self) -> u64 {
         self.pending_saves.load(Ordering::Relaxed)
@@ -113,6 +248,11 @@ impl PayloadCapturer {
         self.enabled
     }
     
+    /// Preview a body for dashboard activity; `None` when previews are off
+    pub fn preview_body(&self, body: &[u8], content_type: Option<&str>) -> Option<BodyPreview> {
+        (self.preview_max > 0).then(|| BodyPreview::new(body, content_type, self.preview_max, self.redact))
+    }
+    
     /// Start a new capture for a request
     pub fn start_capture(&self, conn_id: u64, method: &str, path: &str) -> Option<ExchangeBuilder> {
         if !self.enabled {
@@ -216,7 +356,16 @@ impl PayloadCapturer {
         let (data, is_base64) = if is_binary {
             (base64::Engine::encode(&base64::engine::general_purpose::STANDARD, data_to_capture), true)
         } else {
-            (String::from_utf8_lossy(data_to_capture).to_string(), false)
+            let redacted = if self.redact && !truncated {
+                serde_json::from_slice::<serde_json::Value>(data_to_capture).ok().map(|mut json| {
+                    redact_json(&mut json);
+                    json.to_string()
+                })
+            } else {
+                None
+            };
+            let text = redacted.unwrap_or_else(|| String::from_utf8_lossy(data_to_capture).to_string());
+            (text, false)
         };
         
         Some(CapturedBody {
@@ -386,6 +535,33 @@ mod tests {
         assert_eq!(extract_method("/aiserver.v1.AiService/CheckStatus"), "CheckStatus");
     }
     
+    #[test]
+    fn test_body_preview_redacts_and_truncates() {
+        let body = br#"{"model":"gpt-4","auth":{"access_token":"abc","refreshToken":"def"}}"#;
+        let preview = BodyPreview::new(body, Some("application/json"), 1024, true);
+        assert!(preview.is_json);
+        assert!(!preview.truncated);
+        assert!(preview.text.contains("\"model\": \"gpt-4\""));
+        assert!(!preview.text.contains("abc") && !preview.text.contains("def"));
+        assert_eq!(preview.text.matches(REDACTED).count(), 2);
+        
+        let preview = BodyPreview::new("héllo wörld".as_bytes(), Some("text/plain"), 2, true);
+        assert_eq!(preview.text, "h");
+        assert!(preview.truncated);
+        assert!(preview.display().ends_with("[truncated, 13 bytes total]"));
+    }
+    
+    #[test]
+    fn test_redact_headers() {
+        let mut headers = vec![
+            ("Authorization".to_string(), "Bearer abc".to_string()),
+            ("content-type".to_string(), "application/grpc".to_string()),
+        ];
+        redact_headers(&mut headers);
+        assert_eq!(headers[0].1, REDACTED);
+        assert_eq!(headers[1].1, "application/grpc");
+    }
+    
     #[test]
     fn test_sanitize_filename() {
         assert_eq!(sanitize_filename("ChatService/Method"), "ChatService_Method");
@@ -81,6 +81,10 @@ pub struct CaptureConfig {
     pub capture_responses: bool,
     /// Maximum payload size to capture (bytes)
     pub max_payload_size: usize,
+    /// Per-body cap for bodies kept with dashboard activity (bytes, 0 = off)
+    pub activity_body_max_bytes: usize,
+    /// Keep auth headers and token fields in captures instead of redacting them
+    pub keep_credentials: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -114,6 +118,8 @@ impl Default for Config {
                 capture_requests: true,
                 capture_responses: true,
                 max_payload_size: 10 * 1024 * 1024, // 10MB
+                activity_body_max_bytes: 16 * 1024,
+                keep_credentials: false,
             },
             iptables: IptablesConfig::default(),
             injection: InjectionConfigFile::default(),
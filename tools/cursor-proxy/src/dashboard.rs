@@ -3,9 +3,15 @@
 //! Uses ANSI 16-color palette for terminal theme compatibility.
 //! Colors adapt to light/dark terminal themes automatically.
 
+use crate::capture::BodyPreview;
+use crate::cert::CertificateAuthority;
+use crate::config::CaConfig;
+use crate::error::ProxyResult;
 use crate::events::{EventReceiver, ProxyEvent, ServiceCategory, AgentActivityType};
+use crate::iptables_guard::IptablesStatus;
 use std::collections::HashMap;
 use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -119,11 +125,14 @@ impl Led {
 #[derive(Debug, Clone)]
 struct ActivityEntry {
     timestamp: Instant,
+    request_id: u64,
     category: ServiceCategory,
     endpoint: String,
     status: Option<u16>,
     duration_ms: Option<u64>,
     is_streaming: bool,
+    request_body: Option<BodyPreview>,
+    response_body: Option<BodyPreview>,
 }
 
 /// In-flight request tracking
@@ -298,6 +307,8 @@ pub struct Dashboard {
     queue_by_category: HashMap<ServiceCategory, usize>,
     // Agent monitoring
     agent_state: AgentState,
+    // Bodies attached before their request completed, by request id
+    pending_bodies: HashMap<u64, (Option<BodyPreview>, Option<BodyPreview>)>,
     // Memory management
     last_cleanup: Instant,
 }
@@ -340,6 +351,7 @@ impl Dashboard {
             rate_tracker: RateTracker::new(),
             queue_by_category,
             agent_state: AgentState::default(),
+            pending_bodies: HashMap::new(),
             last_cleanup: Instant::now(),
         }
     }
@@ -378,6 +390,10 @@ impl Dashboard {
             tracing::debug!("Cleaned up {} stale in-flight requests", stale_ids.len());
         }
         
+        // Bodies for requests that never completed
+        let in_flight = &self.in_flight;
+        self.pending_bodies.retain(|id, _| in_flight.contains_key(id));
+        
         // Force trim in_flight if it's too large (prevent unbounded growth)
         if self.in_flight.len() > MAX_IN_FLIGHT_ENTRIES {
             // Remove oldest entries
@@ -469,13 +485,18 @@ impl Dashboard {
                     led.trigger(state);
                 }
                 
+                let (request_body, response_body) =
+                    self.pending_bodies.remove(request_id).unwrap_or_default();
                 self.activity_log.push(ActivityEntry {
                     timestamp: Instant::now(),
+                    request_id: *request_id,
                     category,
                     endpoint,
                     status: Some(*status),
                     duration_ms: Some(*duration_ms),
                     is_streaming,
+                    request_body,
+                    response_body,
                 });
                 
                 while self.activity_log.len() > self.max_activity {
@@ -518,6 +539,9 @@ impl Dashboard {
                 self.bytes_out += *size as u64;
                 self.capture_led.trigger(LedState::Success);
             }
+            ProxyEvent::BodiesCaptured { request_id, request, response } => {
+                self.attach_bodies(*request_id, request.clone(), response.clone());
+            }
             ProxyEvent::AgentActivity { activity, tool_name, .. } => {
                 match activity {
                     AgentActivityType::Thinking => {
@@ -544,6 +568,19 @@ impl Dashboard {
         }
     }
     
+    /// Keep request/response bodies with a request's activity entry.
+    ///
+    /// Bodies may arrive before or after the request completes. Previews are
+    /// already size-capped and redacted (see `BodyPreview::new`).
+    pub fn attach_bodies(&mut self, request_id: u64, request: Option<BodyPreview>, response: Option<BodyPreview>) {
+        if let Some(entry) = self.activity_log.iter_mut().find(|e| e.request_id == request_id) {
+            entry.request_body = request;
+            entry.response_body = response;
+        } else if self.in_flight.contains_key(&request_id) {
+            self.pending_bodies.insert(request_id, (request, response));
+        }
+    }
+    
     fn decay_leds(&mut self, decay_rate: f32) {
         for led in self.service_leds.values_mut() {
             led.decay(decay_rate);
@@ -828,6 +865,8 @@ impl Dashboard {
                 duration_ms: e.duration_ms,
                 is_streaming: e.is_streaming,
                 age_ms: e.timestamp.elapsed().as_millis() as u64,
+                request_body: e.request_body.clone(),
+                response_body: e.response_body.clone(),
             }
         }).collect();
         
@@ -862,6 +901,7 @@ impl Dashboard {
             agent_current_tool: self.agent_state.current_tool.clone(),
             agent_tool_calls: self.agent_state.tool_calls_total,
             agent_thinking_secs,
+            iptables: IptablesStatus::current(),
         }
     }
 }
@@ -913,6 +953,8 @@ pub struct DashboardState {
     pub agent_current_tool: Option<String>,
     pub agent_tool_calls: u64,
     pub agent_thinking_secs: Option<u64>,
+    // Transparent-mode firewall rules
+    pub iptables: IptablesStatus,
 }
 
 #[derive(Debug, Clone)]
@@ -931,6 +973,128 @@ pub struct ActivityRecord {
     pub duration_ms: Option<u64>,
     pub is_streaming: bool,
     pub age_ms: u64,
+    /// Captured bodies; show with `BodyPreview::display` (JSON is pretty-printed)
+    pub request_body: Option<BodyPreview>,
+    pub response_body: Option<BodyPreview>,
 }
 
 
+
+// ============================================================================
+// CA certificate management for the dashboard widgets
+// Without a trusted CA, HTTPS capture fails at the TLS handshake
+// ============================================================================
+
+/// State of the proxy's CA certificate, for display next to the dashboard
+#[derive(Debug, Clone)]
+pub struct CaStatus {
+    pub cert_path: PathBuf,
+    pub exists: bool,
+    /// SHA-256 fingerprint as colon-separated hex (None if it couldn't be read)
+    pub fingerprint: Option<String>,
+}
+
+/// Inspect the CA certificate configured in `ca`
+pub fn ca_status(ca: &CaConfig) -> CaStatus {
+    let exists = ca.cert_path.exists();
+    CaStatus {
+        cert_path: ca.cert_path.clone(),
+        exists,
+        fingerprint: if exists { ca_fingerprint(&ca.cert_path) } else { None },
+    }
+}
+
+/// Generate a fresh CA, replacing any existing one.
+///
+/// Copies trusted before this must be replaced with the new certificate.
+pub fn regenerate_ca(ca: &CaConfig) -> ProxyResult<CaStatus> {
+    if let Some(parent) = ca.cert_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let authority = CertificateAuthority::generate(ca)?;
+    authority.save(&ca.cert_path, &ca.key_path)?;
+    Ok(ca_status(ca))
+}
+
+/// Write the CA certificate (PEM) to `dest` for installing in a trust store
+pub fn export_ca(ca: &CaConfig, dest: &Path) -> ProxyResult<()> {
+    let authority = CertificateAuthority::load_or_generate(ca)?;
+    std::fs::write(dest, authority.ca_cert_pem())?;
+    Ok(())
+}
+
+/// Steps for trusting the CA on NixOS, or just for Cursor
+pub fn ca_trust_instructions(cert_path: &Path) -> Vec<String> {
+    vec![
+        "To trust system-wide (NixOS):".to_string(),
+        "  Add to configuration.nix:".to_string(),
+        format!("    security.pki.certificateFiles = [ \"{}\" ];", cert_path.display()),
+        "  Then run: sudo nixos-rebuild switch".to_string(),
+        String::new(),
+        "To trust for Cursor only:".to_string(),
+        format!("  Launch with: NODE_EXTRA_CA_CERTS=\"{}\" cursor", cert_path.display()),
+    ]
+}
+
+/// SHA-256 fingerprint via `openssl x509`, as shown by browsers and `trust list`
+fn ca_fingerprint(cert_path: &Path) -> Option<String> {
+    let output = std::process::Command::new("openssl")
+        .args(["x509", "-noout", "-fingerprint", "-sha256", "-in"])
+        .arg(cert_path)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    // "sha256 Fingerprint=AB:CD:..." (OpenSSL 1.1 prints "SHA256")
+    let text = String::from_utf8_lossy(&output.stdout);
+    text.trim().split_once('=').map(|(_, hex)| hex.to_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture::PayloadCapturer;
+    use chrono::Utc;
+    
+    #[test]
+    fn test_captured_bodies_land_on_activity_entry() {
+        // Same previews and event order as ProxyServer::handle_request
+        let capturer = PayloadCapturer::new("/nonexistent", 1024, false)
+            .unwrap()
+            .with_activity_previews(32);
+        let request = capturer.preview_body(br#"{"prompt":"hi","accessToken":"secret"}"#, Some("application/json"));
+        let response = capturer.preview_body(&[b'x'; 100], Some("text/plain"));
+        
+        let mut dashboard = Dashboard::new();
+        dashboard.handle_event(&ProxyEvent::RequestStarted {
+            conn_id: 1,
+            request_id: 7,
+            method: "POST".to_string(),
+            path: "/aiserver.v1.ChatService/GetChat".to_string(),
+            service: "aiserver.v1.ChatService".to_string(),
+            endpoint: "GetChat".to_string(),
+            timestamp: Utc::now(),
+        });
+        dashboard.handle_event(&ProxyEvent::BodiesCaptured { request_id: 7, request, response });
+        dashboard.handle_event(&ProxyEvent::RequestCompleted {
+            conn_id: 1,
+            request_id: 7,
+            status: 200,
+            duration_ms: 12,
+            request_size: 0,
+            response_size: None,
+            timestamp: Utc::now(),
+        });
+        
+        let entry = dashboard.activity_log.iter().find(|e| e.request_id == 7).unwrap();
+        let request = entry.request_body.as_ref().unwrap();
+        assert!(request.is_json && request.truncated);
+        assert!(!request.text.contains("secret"));
+        let response = entry.response_body.as_ref().unwrap();
+        assert_eq!((response.size, response.text.len()), (100, 32));
+        
+        let off = PayloadCapturer::new("/nonexistent", 1024, false).unwrap();
+        assert!(off.preview_body(b"{}", None).is_none());
+    }
+}
@@ -0,0 +1,262 @@
+//! Preview, approval and guaranteed revert for transparent-mode iptables rules
+//!
+//! The proxy's redirect rules live in their own `CURSOR_PROXY` chain in the
+//! NAT table, reached by a single jump from `OUTPUT`. Reverting deletes that
+//! jump and the chain and nothing else, so rules added by other software are
+//! never touched. A marker file records that the chain is in place; rules left
+//! behind by a crash are removed on the next start, stop or cleanup, so a user
+//! is never left with traffic redirected to a dead proxy.
+
+use crate::error::{ProxyError, ProxyResult};
+use std::io::{self, BufRead, Write};
+use std::net::{IpAddr, ToSocketAddrs};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use tracing::warn;
+
+/// Port the redirected Cursor API traffic is bound for
+const HTTPS_PORT: u16 = 443;
+
+/// NAT chain holding the proxy's redirect rules
+const CHAIN: &str = "CURSOR_PROXY";
+
+/// Whether the proxy's redirect rules are in place, for the dashboard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IptablesStatus {
+    /// No rules applied by the proxy
+    Inactive,
+    /// Rules applied; they will be removed on shutdown
+    Applied,
+    /// Removing the rules failed; the marker is kept for a retry
+    RevertFailed(String),
+}
+
+impl IptablesStatus {
+    /// Read the status from the marker and error files, so a separate
+    /// dashboard process sees the same state as the proxy
+    pub fn current() -> Self {
+        if let Ok(error) = std::fs::read_to_string(error_path()) {
+            return IptablesStatus::RevertFailed(error.trim().to_string());
+        }
+        if marker_path().exists() {
+            IptablesStatus::Applied
+        } else {
+            IptablesStatus::Inactive
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            IptablesStatus::Inactive => "inactive",
+            IptablesStatus::Applied => "applied",
+            IptablesStatus::RevertFailed(_) => "revert failed",
+        }
+    }
+}
+
+fn proxy_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".cursor-proxy")
+}
+
+/// Present while the proxy's chain may be installed
+pub fn marker_path() -> PathBuf {
+    proxy_dir().join("iptables-chain.active")
+}
+
+fn error_path() -> PathBuf {
+    proxy_dir().join("iptables-revert.error")
+}
+
+/// Current IPv4 addresses of a target; IPv6 isn't covered by the IPv4 NAT table
+fn resolve_ipv4(target: &str) -> io::Result<Vec<IpAddr>> {
+    let mut ips: Vec<IpAddr> = (target, HTTPS_PORT)
+        .to_socket_addrs()?
+        .map(|addr| addr.ip())
+        .filter(|ip| ip.is_ipv4())
+        .collect();
+    ips.sort();
+    ips.dedup();
+    Ok(ips)
+}
+
+/// Arguments for the rule sending one address's HTTPS traffic to the proxy
+fn redirect_args(ip: IpAddr, port: u16) -> Vec<String> {
+    format!(
+        "-t nat -A {} -p tcp -d {} --dport {} -j REDIRECT --to-ports {}",
+        CHAIN, ip, HTTPS_PORT, port
+    )
+    .split(' ')
+    .map(String::from)
+    .collect()
+}
+
+/// Arguments for the jump from `OUTPUT` into the proxy's chain, with the
+/// given action (`-A`, `-C` or `-D`)
+fn jump_args(action: &str) -> [&str; 6] {
+    ["-t", "nat", action, "OUTPUT", "-j", CHAIN]
+}
+
+/// The `iptables` commands transparent mode amounts to: each target's current
+/// IPv4 addresses redirected from port 443 to the proxy. This is a dry run;
+/// nothing is changed.
+pub fn preview_rules(port: u16, targets: &[String]) -> Vec<String> {
+    let mut rules = vec![format!("iptables -t nat -N {}", CHAIN)];
+    for target in targets {
+        let ips = match resolve_ipv4(target) {
+            Ok(ips) => ips,
+            Err(e) => {
+                rules.push(format!("# {}: could not resolve ({})", target, e));
+                continue;
+            }
+        };
+        rules.push(format!("# {}", target));
+        for ip in ips {
+            rules.push(format!("iptables {}", redirect_args(ip, port).join(" ")));
+        }
+    }
+    rules.push(format!("iptables {}", jump_args("-A").join(" ")));
+    rules
+}
+
+/// Show the rules and ask before applying them
+pub fn confirm_apply(rules: &[String]) -> bool {
+    println!("Transparent mode will apply these iptables rules:");
+    for rule in rules {
+        println!("  {}", rule);
+    }
+    println!("They are removed again when the proxy stops; other NAT rules are left alone.");
+    print!("Apply? [y/N] ");
+    let _ = io::stdout().flush();
+
+    let mut answer = String::new();
+    if io::stdin().lock().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+/// Run an iptables tool, through sudo unless already root
+fn run_tool(tool: &str, args: &[&str], input: Option<&str>) -> ProxyResult<String> {
+    let is_root = unsafe { libc::geteuid() } == 0;
+    let mut cmd = if is_root {
+        Command::new(tool)
+    } else {
+        let mut cmd = Command::new("sudo");
+        cmd.arg(tool);
+        cmd
+    };
+    cmd.args(args)
+        .stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let mut child = cmd.spawn()?;
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        stdin.write_all(input.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(ProxyError::Internal(format!(
+            "{} failed: {}",
+            tool,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Install the proxy's chain and redirect rules for the targets, returning
+/// the number of addresses redirected.
+///
+/// Rules left by a crashed run are removed first. The marker is written
+/// before anything is added, so a partial install can always be rolled back.
+pub fn apply_rules(port: u16, targets: &[String]) -> ProxyResult<usize> {
+    remove_rules()?;
+    std::fs::create_dir_all(proxy_dir())?;
+    std::fs::write(marker_path(), "")?;
+
+    run_tool("iptables", &["-t", "nat", "-N", CHAIN], None)?;
+    let mut added = 0;
+    for target in targets {
+        let ips = match resolve_ipv4(target) {
+            Ok(ips) => ips,
+            Err(e) => {
+                warn!("Could not resolve {}: {}", target, e);
+                continue;
+            }
+        };
+        for ip in ips {
+            let args = redirect_args(ip, port);
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            run_tool("iptables", &args, None)?;
+            added += 1;
+        }
+    }
+    run_tool("iptables", &jump_args("-A"), None)?;
+    Ok(added)
+}
+
+/// Remove the proxy's chain and the jump into it, if they were installed.
+///
+/// Returns whether anything was removed. On failure the marker is kept and
+/// the error recorded for the dashboard.
+pub fn remove_rules() -> ProxyResult<bool> {
+    if !marker_path().exists() {
+        return Ok(false);
+    }
+    if let Err(e) = delete_chain() {
+        let _ = std::fs::write(error_path(), e.to_string());
+        return Err(e);
+    }
+    std::fs::remove_file(marker_path())?;
+    let _ = std::fs::remove_file(error_path());
+    Ok(true)
+}
+
+fn delete_chain() -> ProxyResult<()> {
+    // The jump may be missing if a previous install stopped partway
+    while run_tool("iptables", &jump_args("-C"), None).is_ok() {
+        run_tool("iptables", &jump_args("-D"), None)?;
+    }
+    if run_tool("iptables", &["-t", "nat", "-L", CHAIN, "-n"], None).is_ok() {
+        run_tool("iptables", &["-t", "nat", "-F", CHAIN], None)?;
+        run_tool("iptables", &["-t", "nat", "-X", CHAIN], None)?;
+    }
+    Ok(())
+}
+
+/// Remove the rules if the process panics while they are applied
+pub fn remove_on_panic() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if let Err(e) = remove_rules() {
+            eprintln!("Failed to remove iptables rules after panic: {}", e);
+        }
+        previous(info);
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_rules() {
+        let targets = vec!["127.0.0.1".to_string(), "::1".to_string()];
+        let rules = preview_rules(8443, &targets);
+        assert_eq!(
+            rules,
+            vec![
+                "iptables -t nat -N CURSOR_PROXY".to_string(),
+                "# 127.0.0.1".to_string(),
+                "iptables -t nat -A CURSOR_PROXY -p tcp -d 127.0.0.1 --dport 443 -j REDIRECT --to-ports 8443"
+                    .to_string(),
+                // IPv6 addresses aren't redirected by the IPv4 NAT table
+                "# ::1".to_string(),
+                "iptables -t nat -A OUTPUT -j CURSOR_PROXY".to_string(),
+            ]
+        );
+    }
+}
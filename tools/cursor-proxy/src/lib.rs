@@ -41,6 +41,7 @@ mod config;
 mod dns;
 mod error;
 mod iptables;
+mod iptables_guard;
 mod pool;
 mod proxy;
 
@@ -52,9 +53,11 @@ pub mod injection;
 pub mod ipc;
 
 // Re-exports for convenience
-pub use dashboard::{Dashboard, DashboardState, ActivityRecord, ServiceState};
+pub use dashboard::{Dashboard, DashboardState, ActivityRecord, ServiceState, CaStatus};
 pub use events::{EventBroadcaster, EventReceiver, ProxyEvent, ServiceCategory};
 pub use ipc::{IpcClient, IpcEventStream};
+pub use capture::BodyPreview;
+pub use iptables_guard::IptablesStatus;
 
 #[cfg(feature = "egui")]
 pub use dashboard_egui::ProxyDashboardWidget;
@@ -13,6 +13,7 @@ pub mod events;
 pub mod injection;
 pub mod ipc;
 mod iptables;
+mod iptables_guard;
 mod pool;
 mod proxy;
 
@@ -71,6 +72,10 @@ enum Commands {
         /// Force start even if proxy is disabled in config
         #[arg(long)]
         force: bool,
+        
+        /// Apply transparent-mode iptables rules without asking
+        #[arg(long)]
+        yes: bool,
     },
     
     /// Enable proxy in configuration
@@ -94,6 +99,10 @@ enum Commands {
         /// Output path for CA certificate
         #[arg(long)]
         output: Option<PathBuf>,
+        
+        /// Replace the CA with a freshly generated one (must be trusted again)
+        #[arg(long)]
+        regenerate: bool,
     },
     
     /// Manage iptables rules
@@ -142,8 +151,16 @@ enum Commands {
 
 #[derive(Subcommand)]
 enum IptablesAction {
-    /// Add rules for Cursor API
-    Add,
+    /// Print the rules that would be added, without changing anything
+    Preview,
+    /// Add rules for Cursor API in the proxy's own chain (asks first)
+    Add {
+        /// Don't ask for confirmation
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Remove the proxy's chain and the jump into it
+    Revert,
     /// Remove all managed rules
     Remove,
     /// Show current rules
@@ -212,12 +229,12 @@ async fn main() {
 async fn run_command(cli: Cli) -> ProxyResult<()> {
     match cli.command {
         Commands::Init { force } => cmd_init(force).await,
-        Commands::Start { port, dns_mode, transparent, foreground, force } => {
-            cmd_start(port, dns_mode, transparent, foreground, force).await
+        Commands::Start { port, dns_mode, transparent, foreground, force, yes } => {
+            cmd_start(port, dns_mode, transparent, foreground, force, yes).await
         }
         Commands::Stop => cmd_stop().await,
         Commands::Status => cmd_status().await,
-        Commands::TrustCa { show, output } => cmd_trust_ca(show, output).await,
+        Commands::TrustCa { show, output, regenerate } => cmd_trust_ca(show, output, regenerate).await,
         Commands::Iptables { action } => cmd_iptables(action).await,
         Commands::Captures { pattern, recent } => cmd_captures(pattern, recent).await,
         Commands::Config { edit } => cmd_config(edit).await,
@@ -562,7 +579,7 @@ async fn cmd_init(force: bool) -> ProxyResult<()> {
 }
 
 /// Start the proxy server
-async fn cmd_start(port: Option<u16>, dns_mode: bool, transparent: bool, foreground: bool, force: bool) -> ProxyResult<()> {
+async fn cmd_start(port: Option<u16>, dns_mode: bool, transparent: bool, foreground: bool, force: bool, yes: bool) -> ProxyResult<()> {
     let mut config = Config::load()?;
     config.expand_paths();
     
@@ -619,12 +636,24 @@ async fn cmd_start(port: Option<u16>, dns_mode: bool, transparent: bool, foregro
     let mut server = ProxyServer::new(config.clone(), ca).await?;
     
     // Setup iptables transparent mode if requested (legacy)
+    let mut rules_applied = false;
     if transparent && !dns_mode {
         // Try to setup iptables - the commands use sudo internally
         // so we don't need to be root, just have sudo access
-        match server.setup_transparent() {
-            Ok(_) => {}
+        iptables_guard::remove_on_panic();
+        let mut declined = false;
+        let approve = |rules: &[String]| {
+            declined = !(yes || iptables_guard::confirm_apply(rules));
+            !declined
+        };
+        match server.setup_transparent(approve) {
+            Ok(applied) => rules_applied = applied,
+            Err(e) if declined => return Err(e),
             Err(e) => {
+                // Take back whatever part of the rules did get installed
+                if let Err(e) = iptables_guard::remove_rules() {
+                    error!("Failed to remove iptables rules: {}", e);
+                }
                 error!("Failed to setup transparent mode: {}", e);
                 error!("Consider using --dns-mode instead (more reliable)");
                 info!("Or launch Cursor with: cursor-studio --proxy");
@@ -651,6 +680,13 @@ async fn cmd_start(port: Option<u16>, dns_mode: bool, transparent: bool, foregro
     tokio::spawn(async move {
         tokio::signal::ctrl_c().await.ok();
         info!("Received Ctrl+C, shutting down...");
+        // Before stopping, since the process exits once the server returns
+        if rules_applied {
+            match iptables_guard::remove_rules() {
+                Ok(_) => info!("✓ Removed iptables rules"),
+                Err(e) => error!("Failed to remove iptables rules: {}", e),
+            }
+        }
         server_clone.stop();
         // Clean up socket
         let _ = std::fs::remove_file(&socket_path);
@@ -683,6 +719,13 @@ async fn cmd_stop() -> ProxyResult<()> {
         info!("No proxy running (no PID file found)");
     }
     
+    // Remove the proxy's own chain (also recovers from a crash)
+    match iptables_guard::remove_rules() {
+        Ok(true) => info!("✓ Removed the proxy's iptables chain"),
+        Ok(false) => {}
+        Err(e) => error!("Failed to remove iptables rules: {}", e),
+    }
+    
     // Clean up iptables rules
     if IptablesManager::is_available() && IptablesManager::has_root() {
         let config = Config::load()?;
@@ -723,8 +766,12 @@ async fn cmd_status() -> ProxyResult<()> {
     let mut expanded_config = config.clone();
     expanded_config.expand_paths();
     
-    if expanded_config.ca.cert_path.exists() {
-        println!("CA:      ✓ {:?}", expanded_config.ca.cert_path);
+    let ca = dashboard::ca_status(&expanded_config.ca);
+    if ca.exists {
+        println!("CA:      ✓ {:?}", ca.cert_path);
+        if let Some(fingerprint) = &ca.fingerprint {
+            println!("         SHA-256 {}", fingerprint);
+        }
     } else {
         println!("CA:      ✗ Not found (run 'cursor-proxy init')");
     }
@@ -744,36 +791,45 @@ async fn cmd_status() -> ProxyResult<()> {
 }
 
 /// Trust CA certificate
-async fn cmd_trust_ca(show: bool, output: Option<PathBuf>) -> ProxyResult<()> {
+async fn cmd_trust_ca(show: bool, output: Option<PathBuf>, regenerate: bool) -> ProxyResult<()> {
     let mut config = Config::load()?;
     config.expand_paths();
     
-    let ca = CertificateAuthority::load_or_generate(&config.ca)?;
-    let pem = ca.ca_cert_pem();
+    if regenerate {
+        let status = dashboard::regenerate_ca(&config.ca)?;
+        info!("✓ New CA certificate created at {:?}", status.cert_path);
+        if let Some(fingerprint) = status.fingerprint {
+            info!("  SHA-256 Fingerprint: {}", fingerprint);
+        }
+        info!("  Previously trusted copies must be replaced with this certificate");
+    }
     
     if show {
-        println!("{}", pem);
+        let ca = CertificateAuthority::load_or_generate(&config.ca)?;
+        println!("{}", ca.ca_cert_pem());
         return Ok(());
     }
     
     if let Some(path) = output {
-        std::fs::write(&path, &pem)?;
+        dashboard::export_ca(&config.ca, &path)?;
         info!("✓ CA certificate written to {:?}", path);
         return Ok(());
     }
     
     // Show instructions for trusting
     info!("CA Certificate Location: {:?}", config.ca.cert_path);
+    if let Some(fingerprint) = dashboard::ca_status(&config.ca).fingerprint {
+        info!("SHA-256 Fingerprint: {}", fingerprint);
+    }
     info!("");
-    info!("To trust system-wide (NixOS):");
-    info!("  Add to configuration.nix:");
-    info!("    security.pki.certificateFiles = [ \"{}\" ];", config.ca.cert_path.display());
-    info!("");
-    info!("To trust for Cursor only:");
-    info!("  Launch with: NODE_EXTRA_CA_CERTS=\"{}\" cursor", config.ca.cert_path.display());
+    for line in dashboard::ca_trust_instructions(&config.ca.cert_path) {
+        info!("{}", line);
+    }
     info!("");
     info!("Or copy the certificate:");
     info!("  cursor-proxy trust-ca --output /path/to/ca.pem");
+    info!("To replace a compromised or expired CA:");
+    info!("  cursor-proxy trust-ca --regenerate");
     
     Ok(())
 }
@@ -783,11 +839,27 @@ async fn cmd_iptables(action: IptablesAction) -> ProxyResult<()> {
     let config = Config::load()?;
     
     match action {
-        IptablesAction::Add => {
-            let manager = IptablesManager::new(config.proxy.port, false)?;
-            for target in &config.iptables.targets {
-                let added = manager.add_domain(target)?;
-                info!("Added {} IPs for {}", added.len(), target);
+        IptablesAction::Preview => {
+            for rule in iptables_guard::preview_rules(config.proxy.port, &config.iptables.targets) {
+                println!("{}", rule);
+            }
+        }
+        
+        IptablesAction::Add { yes } => {
+            let rules = iptables_guard::preview_rules(config.proxy.port, &config.iptables.targets);
+            if !yes && !iptables_guard::confirm_apply(&rules) {
+                info!("Not applied");
+                return Ok(());
+            }
+            let added = iptables_guard::apply_rules(config.proxy.port, &config.iptables.targets)?;
+            info!("Redirected {} IPs", added);
+        }
+        
+        IptablesAction::Revert => {
+            if iptables_guard::remove_rules()? {
+                info!("✓ Removed the proxy's iptables chain");
+            } else {
+                info!("No proxy rules to remove");
             }
         }
         
@@ -912,7 +984,7 @@ async fn cmd_cleanup(all: bool) -> ProxyResult<()> {
     // Stop proxy first
     cmd_stop().await?;
     
-    // Flush iptables
+    // Flush iptables (cmd_stop already removed the proxy's chain)
     if IptablesManager::is_available() && IptablesManager::has_root() {
         IptablesManager::flush_all()?;
         info!("✓ Flushed iptables rules");
@@ -2,13 +2,13 @@
 //!
 //! This is the core proxy implementation that intercepts Cursor traffic.
 
-use crate::capture::{PayloadCapturer, ExchangeBuilder};
+use crate::capture::{redact_headers, BodyPreview, PayloadCapturer, ExchangeBuilder};
 use crate::cert::CertificateAuthority;
 use crate::config::Config;
 use crate::dns::ExternalDnsResolver;
 use crate::error::{ProxyError, ProxyResult};
 use crate::injection::{InjectionEngine, InjectionConfig};
-use crate::iptables::IptablesManager;
+use crate::iptables_guard;
 use crate::pool::Http2Pool;
 
 use bytes::Bytes;
@@ -39,8 +39,8 @@ pub struct ProxyServer {
     capturer: Arc<PayloadCapturer>,
     /// Injection engine for request/response modification
     injector: Arc<InjectionEngine>,
-    /// iptables manager (if transparent mode - legacy)
-    iptables: Option<Arc<IptablesManager>>,
+    /// Whether transparent-mode iptables rules are applied (legacy)
+    transparent: bool,
     /// Connection counter
     conn_counter: AtomicU64,
     /// Request counter
@@ -72,7 +72,8 @@ impl ProxyServer {
             config.capture.max_payload_size,
             config.capture.enabled,
             config.capture.retention_days,
-        )?);
+        )?.with_redaction(!config.capture.keep_credentials)
+            .with_activity_previews(config.capture.activity_body_max_bytes));
         
         // Initialize injection engine with config
         let injection_config = InjectionConfig {
@@ -92,7 +93,7 @@ impl ProxyServer {
             pool,
             capturer,
             injector,
-            iptables: None,
+            transparent: false,
             conn_counter: AtomicU64::new(0),
             req_counter: AtomicU64::new(0),
             running: std::sync::atomic::AtomicBool::new(false),
@@ -105,34 +106,31 @@ impl ProxyServer {
         self.events.clone()
     }
     
-    /// Setup iptables for transparent mode
-    pub fn setup_transparent(&mut self) -> ProxyResult<()> {
+    /// Setup iptables for transparent mode, once `approve` accepts the
+    /// previewed rules. Returns whether rules were applied.
+    pub fn setup_transparent(
+        &mut self,
+        approve: impl FnOnce(&[String]) -> bool,
+    ) -> ProxyResult<bool> {
         if !self.config.iptables.auto_manage {
             info!("iptables auto-management disabled");
-            return Ok(());
+            return Ok(false);
         }
         
-        let manager = IptablesManager::new(
-            self.config.proxy.port,
-            self.config.iptables.cleanup_on_exit,
-        )?;
-        
-        // Add rules for configured targets
-        for target in &self.config.iptables.targets {
-            match manager.add_domain(target) {
-                Ok(ips) => {
-                    if ips.is_empty() {
-                        warn!("No IPs found for target: {}", target);
-                    }
-                }
-                Err(e) => {
-                    warn!("Failed to add iptables rules for {}: {}", target, e);
-                }
-            }
+        let port = self.config.proxy.port;
+        let targets = &self.config.iptables.targets;
+        if !approve(&iptables_guard::preview_rules(port, targets)) {
+            return Err(ProxyError::Internal("iptables rules not approved".into()));
         }
         
-        self.iptables = Some(Arc::new(manager));
-        Ok(())
+        // Rules for configured targets, in the proxy's own chain
+        let added = iptables_guard::apply_rules(port, targets)?;
+        if added == 0 {
+            warn!("No IPs found for the configured targets");
+        }
+        
+        self.transparent = true;
+        Ok(true)
     }
     
     /// Start the proxy server
@@ -419,10 +417,11 @@ impl ProxyServer {
             pool, 
             &capturer,
             capture_builder.flatten(),
+            should_capture,
             injector,
             &endpoint,
         ).await {
-            Ok(response) => {
+            Ok((response, request_body, response_body)) => {
                 let status = response.status();
                 let duration_ms = start_time.elapsed().as_millis() as u64;
                 
@@ -430,6 +429,15 @@ impl ProxyServer {
                     info!("[{}] ← {} {} ({}ms)", conn_id, status, path, duration_ms);
                 }
                 
+                // Bodies go out first so the dashboard can file them with the entry
+                if request_body.is_some() || response_body.is_some() {
+                    events.emit(ProxyEvent::BodiesCaptured {
+                        request_id,
+                        request: request_body,
+                        response: response_body,
+                    });
+                }
+                
                 // Emit RequestCompleted event
                 events.emit(ProxyEvent::RequestCompleted {
                     conn_id,
@@ -464,7 +472,10 @@ impl ProxyServer {
         }
     }
     
-    /// Forward request to upstream server with optional capture and injection
+    /// Forward request to upstream server with optional capture and injection.
+    ///
+    /// Also returns dashboard previews of the request and (buffered) response
+    /// bodies when `preview_bodies` is set and previews are enabled.
     async fn forward_to_upstream_with_capture(
         conn_id: u64,
         req: Request<Incoming>,
@@ -472,9 +483,10 @@ impl ProxyServer {
         pool: Arc<Http2Pool>,
         capturer: &PayloadCapturer,
         capture_builder: Option<ExchangeBuilder>,
+        preview_bodies: bool,
         injector: Arc<InjectionEngine>,
         endpoint: &str,
-    ) -> ProxyResult<Response<BoxBody<Bytes, hyper::Error>>> {
+    ) -> ProxyResult<(Response<BoxBody<Bytes, hyper::Error>>, Option<BodyPreview>, Option<BodyPreview>)> {
         // Buffer the request body (required for retries and HTTP/2 framing)
         let (mut parts, body) = req.into_parts();
         let mut body_bytes = body.collect().await
@@ -493,12 +505,23 @@ impl ProxyServer {
             }
         }
         
+        let request_preview = if preview_bodies {
+            let content_type = parts.headers.get("content-type")
+                .and_then(|v| v.to_str().ok());
+            capturer.preview_body(&body_bytes, content_type)
+        } else {
+            None
+        };
+        
         // Capture request if builder exists
         let builder = if let Some(b) = capture_builder {
             // Capture request headers
-            let headers: Vec<(String, String)> = parts.headers.iter()
+            let mut headers: Vec<(String, String)> = parts.headers.iter()
                 .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("<binary>").to_string()))
                 .collect();
+            if capturer.redacts() {
+                redact_headers(&mut headers);
+            }
             
             // Get content type for body capture
             let content_type = parts.headers.get("content-type")
@@ -542,19 +565,51 @@ impl ProxyServer {
         let (resp_parts, body) = response.into_parts();
         let status = resp_parts.status;
         
+        // Unary responses that fit the capture cap are buffered so the body can
+        // be captured; streams pass through untouched so tokens aren't delayed
+        let response_len = resp_parts.headers.get("content-length")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<usize>().ok());
+        let buffer_response = (builder.is_some() || request_preview.is_some())
+            && !endpoint.contains("Stream")
+            && response_len.is_some_and(|len| len <= capturer.max_size());
+        let (buffered, response_body) = if buffer_response {
+            let bytes = body.collect().await
+                .map_err(|e| ProxyError::Http(format!("Failed to read response body: {}", e)))?
+                .to_bytes();
+            (Some(bytes.clone()), Self::full_body(bytes))
+        } else {
+            // Convert the incoming body to a boxed stream body for proper streaming
+            (None, body.map_err(|e| e).boxed())
+        };
+        
+        let response_preview = match &buffered {
+            Some(bytes) if request_preview.is_some() => {
+                let content_type = resp_parts.headers.get("content-type")
+                    .and_then(|v| v.to_str().ok());
+                capturer.preview_body(bytes, content_type)
+            }
+            _ => None,
+        };
+        
         // Capture response if builder exists
         if let Some(mut b) = builder {
             // Capture response headers
-            let headers: Vec<(String, String)> = resp_parts.headers.iter()
+            let mut headers: Vec<(String, String)> = resp_parts.headers.iter()
                 .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("<binary>").to_string()))
                 .collect();
+            if capturer.redacts() {
+                redact_headers(&mut headers);
+            }
             
             b = b.response_status(status.as_u16())
                 .response_headers(headers);
             
-            // Note: We can't easily capture streaming response body without buffering
-            // For now, just save what we have. Full streaming capture would require
-            // wrapping the body stream.
+            if let Some(bytes) = &buffered {
+                let content_type = resp_parts.headers.get("content-type")
+                    .and_then(|v| v.to_str().ok());
+                b = b.response_body(capturer.capture_body(bytes, content_type));
+            }
             
             // Build and save the capture
             let exchange = b.build();
@@ -573,13 +628,10 @@ impl ProxyServer {
             response_builder = response_builder.header(name, value);
         }
         
-        // Convert the incoming body to a boxed stream body for proper streaming
-        let stream_body = body.map_err(|e| e).boxed();
-        
-        let response = response_builder.body(stream_body)
+        let response = response_builder.body(response_body)
             .map_err(|e| ProxyError::Http(e.to_string()))?;
         
-        Ok(response)
+        Ok((response, request_preview, response_preview))
     }
     
     /// Create a full body from bytes
@@ -637,7 +689,7 @@ impl ProxyServer {
         info!("║              Cursor Proxy v{} Ready                        ║", env!("CARGO_PKG_VERSION"));
         info!("╠══════════════════════════════════════════════════════════════╣");
         
-        if self.iptables.is_some() {
+        if self.transparent {
             info!("║ ✓ Transparent mode active (iptables configured)             ║");
         } else {
             info!("║ ⚠ Explicit proxy mode (set NODE_EXTRA_CA_CERTS)             ║");
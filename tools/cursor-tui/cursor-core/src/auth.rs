@@ -1,18 +1,35 @@
 use anyhow::{Result, Context};
+use serde::{Deserialize, Serialize};
 use sqlite::State;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, info};
 
-#[derive(Debug, Clone)]
+/// Treat tokens this close to expiry as expired, so requests don't race it
+const EXPIRY_SKEW_SECS: u64 = 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CursorAuth {
     pub access_token: String,
     pub refresh_token: String,
 }
 
+/// Cursor's stored login is gone or expired; the user has to sign in again
+#[derive(Debug, thiserror::Error)]
+#[error("Cursor login expired: sign in to Cursor again (re-login required)")]
+pub struct ReloginRequired;
+
 pub fn get_auth_db_path() -> PathBuf {
     let home = dirs::home_dir().expect("Could not find home directory");
     home.join(".config/Cursor/User/globalStorage/state.vscdb")
 }
 
+/// Where the last working token is kept between runs
+pub fn get_token_cache_path() -> Result<PathBuf> {
+    let config = dirs::config_dir().context("Could not find config directory")?;
+    Ok(config.join("cursor-tui/auth.json"))
+}
+
 pub fn extract_auth_from_db(db_path: &PathBuf) -> Result<CursorAuth> {
     let connection = sqlite::open(db_path).context("Failed to open state.vscdb")?;
 
@@ -27,7 +44,7 @@ pub fn extract_auth_from_db(db_path: &PathBuf) -> Result<CursorAuth> {
             return Err(anyhow::anyhow!("cursorAuth/accessToken not found"));
         }
     };
-    
+
     statement.reset()?;
 
     let refresh_token = {
@@ -44,3 +61,270 @@ pub fn extract_auth_from_db(db_path: &PathBuf) -> Result<CursorAuth> {
         refresh_token,
     })
 }
+
+/// Restore the cached token, or refresh it from Cursor's login when the
+/// cache is missing or expired. Fails with `ReloginRequired` if neither is usable.
+pub fn load_auth() -> Result<CursorAuth> {
+    match get_token_cache_path().and_then(|path| Ok((CursorAuth::load(&path)?, path))) {
+        Ok((auth, path)) if !auth.is_expired() => {
+            debug!("Using cached token from {:?}", path);
+            return Ok(auth);
+        }
+        Ok(_) => info!("Cached token expired, refreshing"),
+        Err(e) => debug!("No usable cached token: {:#}", e),
+    }
+
+    let mut auth = CursorAuth {
+        access_token: String::new(),
+        refresh_token: String::new(),
+    };
+    auth.refresh()?;
+    Ok(auth)
+}
+
+impl CursorAuth {
+    /// Expiry of the access token (JWT `exp`, seconds since the epoch)
+    pub fn expires_at(&self) -> Option<u64> {
+        let payload = self.access_token.split('.').nth(1)?;
+        let claims: serde_json::Value = serde_json::from_slice(&decode_base64url(payload)?).ok()?;
+        claims.get("exp")?.as_u64()
+    }
+
+    /// Whether the access token has expired (or is about to). Tokens without
+    /// a readable expiry are left for the server to judge.
+    pub fn is_expired(&self) -> bool {
+        let Some(exp) = self.expires_at() else {
+            return self.access_token.is_empty();
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        now + EXPIRY_SKEW_SECS >= exp
+    }
+
+    /// Refresh the access token from state.vscdb, where Cursor keeps its
+    /// login current, and cache it. No token exchange happens here:
+    /// `refresh_token` is only carried along, and when Cursor's own login has
+    /// lapsed this fails with `ReloginRequired`.
+    pub fn refresh(&mut self) -> Result<()> {
+        self.refresh_from(&get_auth_db_path())?;
+
+        if let Err(e) = get_token_cache_path().and_then(|path| self.save(&path)) {
+            // Still usable for this run
+            debug!("Failed to cache token: {:#}", e);
+        }
+        info!("Refreshed Cursor auth token");
+        Ok(())
+    }
+
+    /// Replace the token with the one stored in `db_path`, unless that one
+    /// is missing or expired too
+    fn refresh_from(&mut self, db_path: &PathBuf) -> Result<()> {
+        let fresh = extract_auth_from_db(db_path).map_err(|e| e.context(ReloginRequired))?;
+        if fresh.is_expired() {
+            return Err(ReloginRequired.into());
+        }
+        *self = fresh;
+        Ok(())
+    }
+
+    /// Write the token to `path`, readable only by the current user
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string(self)?;
+
+        #[cfg(unix)]
+        {
+            use std::io::Write;
+            use std::os::unix::fs::OpenOptionsExt;
+            let mut file = std::fs::OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .mode(0o600)
+                .open(path)
+                .with_context(|| format!("Failed to write {:?}", path))?;
+            file.write_all(json.as_bytes())?;
+        }
+        #[cfg(not(unix))]
+        std::fs::write(path, json).with_context(|| format!("Failed to write {:?}", path))?;
+
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {:?}", path))?;
+        Ok(serde_json::from_str(&json)?)
+    }
+}
+
+/// Decode unpadded base64url, as used for JWT segments
+fn decode_base64url(input: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(input.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for byte in input.trim_end_matches('=').bytes() {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'-' | b'+' => 62,
+            b'_' | b'/' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Unsigned JWT carrying just an `exp` claim
+    fn token_expiring_at(exp: u64) -> String {
+        let encode = |bytes: &[u8]| {
+            const ALPHABET: &[u8] =
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+            let mut out = String::new();
+            for chunk in bytes.chunks(3) {
+                let n = chunk.iter().fold(0u32, |n, b| (n << 8) | *b as u32);
+                let n = n << (8 * (3 - chunk.len()));
+                for i in 0..=chunk.len() {
+                    out.push(ALPHABET[(n >> (18 - 6 * i)) as usize & 63] as char);
+                }
+            }
+            out
+        };
+        format!(
+            "{}.{}.sig",
+            encode(br#"{"alg":"none"}"#),
+            encode(format!(r#"{{"exp":{}}}"#, exp).as_bytes())
+        )
+    }
+
+    fn now() -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+    }
+
+    #[test]
+    fn test_decode_base64url() {
+        assert_eq!(decode_base64url("aGVsbG8").unwrap(), b"hello");
+        assert_eq!(decode_base64url("aGVsbG8=").unwrap(), b"hello");
+        // URL-safe and standard alphabets both decode
+        assert_eq!(decode_base64url("-_8").unwrap(), [0xfb, 0xff]);
+        assert_eq!(decode_base64url("+/8").unwrap(), [0xfb, 0xff]);
+        assert_eq!(decode_base64url("").unwrap(), b"");
+        assert!(decode_base64url("not base64!").is_none());
+    }
+
+    #[test]
+    fn test_is_expired() {
+        let auth = |access_token: String| CursorAuth {
+            access_token,
+            refresh_token: String::new(),
+        };
+
+        let exp = now() + 3600;
+        let valid = auth(token_expiring_at(exp));
+        assert_eq!(valid.expires_at(), Some(exp));
+        assert!(!valid.is_expired());
+        assert!(auth(token_expiring_at(now() - 1)).is_expired());
+        // Inside the skew window counts as expired
+        assert!(auth(token_expiring_at(now() + EXPIRY_SKEW_SECS / 2)).is_expired());
+
+        // No readable expiry: only an empty token is treated as expired
+        assert!(!auth("opaque-token".to_string()).is_expired());
+        assert!(auth(String::new()).is_expired());
+    }
+
+    /// state.vscdb stand-in holding the given tokens
+    fn auth_db(dir: &Path, access_token: &str) -> PathBuf {
+        std::fs::create_dir_all(dir).unwrap();
+        let path = dir.join("state.vscdb");
+        let connection = sqlite::open(&path).unwrap();
+        connection
+            .execute("CREATE TABLE ItemTable (key TEXT PRIMARY KEY, value TEXT)")
+            .unwrap();
+        let mut insert = connection
+            .prepare("INSERT INTO ItemTable (key, value) VALUES (?, ?)")
+            .unwrap();
+        for (key, value) in [
+            ("cursorAuth/accessToken", access_token),
+            ("cursorAuth/refreshToken", "refresh"),
+        ] {
+            insert.reset().unwrap();
+            insert.bind((1, key)).unwrap();
+            insert.bind((2, value)).unwrap();
+            insert.next().unwrap();
+        }
+        path
+    }
+
+    #[test]
+    fn test_refresh_replaces_expired_token() {
+        let dir = std::env::temp_dir().join(format!("cursor-auth-refresh-{}", std::process::id()));
+        let fresh_token = token_expiring_at(now() + 3600);
+        let db = auth_db(&dir.join("fresh"), &fresh_token);
+
+        let mut auth = CursorAuth {
+            access_token: token_expiring_at(now() - 1),
+            refresh_token: String::new(),
+        };
+        assert!(auth.is_expired());
+        auth.refresh_from(&db).unwrap();
+        assert_eq!(auth.access_token, fresh_token);
+        assert_eq!(auth.refresh_token, "refresh");
+        assert!(!auth.is_expired());
+
+        // Cursor's own login has lapsed too: the user has to sign in again
+        let stale_token = token_expiring_at(now() - 1);
+        let stale_db = auth_db(&dir.join("stale"), &stale_token);
+        let mut auth = CursorAuth {
+            access_token: stale_token.clone(),
+            refresh_token: String::new(),
+        };
+        assert!(auth.refresh_from(&stale_db).unwrap_err().is::<ReloginRequired>());
+        assert_eq!(auth.access_token, stale_token);
+
+        // No login stored at all
+        let missing = dir.join("missing/state.vscdb");
+        assert!(auth.refresh_from(&missing).unwrap_err().is::<ReloginRequired>());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_save_load_round_trip() {
+        let dir = std::env::temp_dir().join(format!("cursor-auth-test-{}", std::process::id()));
+        let path = dir.join("nested/auth.json");
+        let auth = CursorAuth {
+            access_token: token_expiring_at(now() + 3600),
+            refresh_token: "refresh".to_string(),
+        };
+
+        auth.save(&path).unwrap();
+        let loaded = CursorAuth::load(&path).unwrap();
+        assert_eq!(loaded.access_token, auth.access_token);
+        assert_eq!(loaded.refresh_token, auth.refresh_token);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        assert!(CursorAuth::load(&dir.join("missing.json")).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,9 +1,116 @@
 use crate::auth::CursorAuth;
 use crate::aiserver::v1::chat_service_client::ChatServiceClient;
-use crate::aiserver::v1::{StreamUnifiedChatRequestWithTools, StreamUnifiedChatRequest, ConversationMessage};
+use crate::aiserver::v1::{
+    ClientChunk, ConversationMessage, StreamUnifiedChatRequest, StreamUnifiedChatRequestWithTools,
+    StreamUnifiedChatRequestWithToolsIdempotent, StreamUnifiedChatResponseWithTools,
+    StreamUnifiedChatResponseWithToolsIdempotent,
+};
+use crate::retry::{with_retry, RetryPolicy, Rpc};
 use anyhow::Result;
-use tonic::{transport::{Channel, ClientTlsConfig}, Request, metadata::MetadataValue};
-use tracing::{info, error};
+use std::io::Write;
+use tokio::sync::watch;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tonic::{service::{interceptor::InterceptedService, Interceptor}, transport::{Channel, ClientTlsConfig}, Code, Request, Status, Streaming, metadata::MetadataValue};
+use tracing::{debug, info, error};
+
+/// Why a chat stream ended early
+#[derive(Debug, thiserror::Error)]
+pub enum StreamError {
+    #[error("Chat stream cancelled")]
+    Cancelled,
+    #[error("Chat stream failed ({code:?}): {message}")]
+    Status { code: Code, message: String },
+}
+
+impl From<Status> for StreamError {
+    fn from(status: Status) -> Self {
+        StreamError::Status {
+            code: status.code(),
+            message: status.message().to_string(),
+        }
+    }
+}
+
+/// Cancels a `ChatStream` from another task, e.g. when the user hits Esc
+#[derive(Debug, Clone)]
+pub struct CancelHandle(watch::Sender<bool>);
+
+impl CancelHandle {
+    pub fn cancel(&self) {
+        let _ = self.0.send(true);
+    }
+}
+
+enum ChatSource {
+    Plain(Streaming<StreamUnifiedChatResponseWithTools>),
+    Idempotent(Streaming<StreamUnifiedChatResponseWithToolsIdempotent>),
+}
+
+impl ChatSource {
+    async fn message(&mut self) -> std::result::Result<Option<StreamUnifiedChatResponseWithTools>, Status> {
+        match self {
+            ChatSource::Plain(stream) => stream.message().await,
+            ChatSource::Idempotent(stream) => loop {
+                // Acks without a payload carry nothing to show
+                match stream.message().await? {
+                    Some(message) => match message.response {
+                        Some(response) => return Ok(Some(response)),
+                        None if message.is_final => return Ok(None),
+                        None => continue,
+                    },
+                    None => return Ok(None),
+                }
+            },
+        }
+    }
+}
+
+/// Response chunks of a chat, yielded as the server sends them
+pub struct ChatStream {
+    inner: Option<ChatSource>,
+    cancel_tx: watch::Sender<bool>,
+    cancel_rx: watch::Receiver<bool>,
+}
+
+impl ChatStream {
+    fn new(inner: ChatSource) -> Self {
+        let (cancel_tx, cancel_rx) = watch::channel(false);
+        Self {
+            inner: Some(inner),
+            cancel_tx,
+            cancel_rx,
+        }
+    }
+
+    pub fn cancel_handle(&self) -> CancelHandle {
+        CancelHandle(self.cancel_tx.clone())
+    }
+
+    /// Next chunk, or `None` once the stream has finished. Cancellation and
+    /// errors are reported once, after which the stream is closed.
+    pub async fn next(&mut self) -> Option<std::result::Result<StreamUnifiedChatResponseWithTools, StreamError>> {
+        let inner = self.inner.as_mut()?;
+        let result = tokio::select! {
+            message = inner.message() => message.map_err(StreamError::from).transpose(),
+            _ = self.cancel_rx.wait_for(|cancelled| *cancelled) => Some(Err(StreamError::Cancelled)),
+        };
+        if !matches!(result, Some(Ok(_))) {
+            // Dropping the response stream resets the call on the server
+            debug!("Closing chat stream");
+            self.inner = None;
+        }
+        result
+    }
+}
+
+/// Text carried by a response chunk, if any
+pub fn chunk_text(chunk: &StreamUnifiedChatResponseWithTools) -> Option<&str> {
+    chunk
+        .stream_unified_chat_response
+        .as_ref()
+        .map(|response| response.text.as_str())
+        .filter(|text| !text.is_empty())
+}
 
 pub struct Client {
     auth: CursorAuth,
@@ -25,10 +132,94 @@ impl Client {
         })
     }
 
+    /// Refresh the token first if it has expired
+    fn ensure_fresh_auth(&mut self) -> Result<()> {
+        if self.auth.is_expired() {
+            info!("Access token expired, refreshing");
+            self.auth.refresh()?;
+        }
+        Ok(())
+    }
+
+    /// Start a chat and return the response as it streams in.
+    ///
+    /// The request is retried once with a refreshed token if the server
+    /// rejects the current one.
+    pub async fn stream_chat(&mut self, conversation: Vec<ConversationMessage>) -> Result<ChatStream> {
+        self.ensure_fresh_auth()?;
+
+        let mut response = self.open_chat(conversation.clone()).await;
+        if matches!(&response, Err(status) if status.code() == Code::Unauthenticated) {
+            // The server rejected a token we thought was valid; refresh once and retry
+            info!("Token rejected, refreshing and retrying");
+            self.auth.refresh()?;
+            response = self.open_chat(conversation).await;
+        }
+
+        let response = response.map_err(StreamError::from)?;
+        info!("Response received: {:?}", response.metadata());
+        Ok(ChatStream::new(ChatSource::Plain(response.into_inner())))
+    }
+
+    /// Like `stream_chat`, but through the idempotent RPC, so the call can be
+    /// retried under `retry` if it fails before the response starts.
+    /// Failures after the first chunk end the stream as usual.
+    pub async fn stream_chat_idempotent(
+        &mut self,
+        conversation: Vec<ConversationMessage>,
+        retry: Option<&RetryPolicy>,
+    ) -> Result<ChatStream> {
+        self.ensure_fresh_auth()?;
+
+        // One key for every attempt, so the server can drop duplicates
+        let idempotency_key = new_idempotency_key();
+        let open = || self.open_chat_idempotent(conversation.clone(), idempotency_key.clone());
+        let mut response = with_retry(Rpc::StreamUnifiedChatWithToolsIdempotent, retry, open).await;
+        if matches!(&response, Err(status) if status.code() == Code::Unauthenticated) {
+            info!("Token rejected, refreshing and retrying");
+            self.auth.refresh()?;
+            let open = || self.open_chat_idempotent(conversation.clone(), idempotency_key.clone());
+            response = with_retry(Rpc::StreamUnifiedChatWithToolsIdempotent, retry, open).await;
+        }
+
+        let response = response.map_err(StreamError::from)?;
+        info!("Response received: {:?}", response.metadata());
+        Ok(ChatStream::new(ChatSource::Idempotent(response.into_inner())))
+    }
+
     pub async fn send_dummy_request(&mut self) -> Result<()> {
+        let conversation = vec![ConversationMessage {
+            text: "Hello, are you there?".to_string(),
+            ..Default::default()
+        }];
+
+        let mut stream = match self.stream_chat(conversation).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                error!("gRPC Error: {:?}", e);
+                println!("gRPC Error: {:?}", e);
+                return Ok(());
+            }
+        };
+
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            info!("Received message: {:?}", chunk);
+            if let Some(text) = chunk_text(&chunk) {
+                print!("{}", text);
+                let _ = std::io::stdout().flush();
+            }
+        }
+        println!();
+        info!("Stream finished");
+
+        Ok(())
+    }
+
+    fn grpc_client(&self) -> ChatServiceClient<InterceptedService<Channel, impl Interceptor>> {
         let token = self.auth.access_token.clone();
         
-        let mut grpc_client = ChatServiceClient::with_interceptor(self.channel.clone(), move |mut req: Request<()>| {
+        ChatServiceClient::with_interceptor(self.channel.clone(), move |mut req: Request<()>| {
             let token_val = MetadataValue::try_from(&format!("Bearer {}", token)).unwrap();
             req.metadata_mut().insert("authorization", token_val);
             
@@ -41,14 +232,21 @@ impl Client {
             // req.metadata_mut().insert("x-cursor-checksum", checksum_val);
 
             Ok(req)
-        });
+        })
+    }
+
+    async fn open_chat(
+        &self,
+        conversation: Vec<ConversationMessage>,
+    ) -> std::result::Result<
+        tonic::Response<tonic::Streaming<StreamUnifiedChatResponseWithTools>>,
+        tonic::Status,
+    > {
+        let mut grpc_client = self.grpc_client();
 
         let request_msg = StreamUnifiedChatRequestWithTools {
             stream_unified_chat_request: Some(StreamUnifiedChatRequest {
-                conversation: vec![ConversationMessage {
-                    text: "Hello, are you there?".to_string(),
-                    ..Default::default()
-                }],
+                conversation,
                 ..Default::default()
             }),
             ..Default::default()
@@ -58,25 +256,46 @@ impl Client {
         let request_stream = tokio_stream::iter(vec![request_msg]);
 
         info!("Sending request...");
-        let response = grpc_client.stream_unified_chat_with_tools(request_stream).await;
-
-        match response {
-            Ok(resp) => {
-                info!("Response received: {:?}", resp.metadata());
-                let mut stream = resp.into_inner();
-                
-                while let Some(msg) = stream.message().await? {
-                    info!("Received message: {:?}", msg);
-                    println!("Received message!");
-                }
-                info!("Stream finished");
-            },
-            Err(status) => {
-                error!("gRPC Error: {:?}", status);
-                println!("gRPC Error: {:?}", status);
-            }
-        }
+        grpc_client.stream_unified_chat_with_tools(request_stream).await
+    }
 
-        Ok(())
+    async fn open_chat_idempotent(
+        &self,
+        conversation: Vec<ConversationMessage>,
+        idempotency_key: String,
+    ) -> std::result::Result<
+        tonic::Response<tonic::Streaming<StreamUnifiedChatResponseWithToolsIdempotent>>,
+        tonic::Status,
+    > {
+        let mut grpc_client = self.grpc_client();
+
+        let request_msg = StreamUnifiedChatRequestWithToolsIdempotent {
+            client_chunk: Some(ClientChunk {
+                request: Some(StreamUnifiedChatRequestWithTools {
+                    stream_unified_chat_request: Some(StreamUnifiedChatRequest {
+                        conversation,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                chunk_index: 0,
+            }),
+            idempotency_key,
+            seqno: 0,
+            ..Default::default()
+        };
+
+        let request_stream = tokio_stream::iter(vec![request_msg]);
+
+        info!("Sending idempotent request...");
+        grpc_client.stream_unified_chat_with_tools_idempotent(request_stream).await
     }
 }
+
+fn new_idempotency_key() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    format!("cursor-tui-{}-{:x}", std::process::id(), nanos)
+}
@@ -1,5 +1,6 @@
 pub mod auth;
 pub mod client;
+pub mod retry;
 
 pub mod aiserver {
     pub mod v1 {
@@ -0,0 +1,268 @@
+use std::future::Future;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tonic::{Code, Status};
+use tracing::{info, warn};
+
+/// ChatService RPCs, so callers can check whether a call is safe to retry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rpc {
+    StreamUnifiedChatWithTools,
+    StreamUnifiedChatWithToolsSse,
+    StreamUnifiedChat,
+    StreamUnifiedChatWithToolsIdempotent,
+}
+
+impl Rpc {
+    /// Only the idempotent variant carries an idempotency key, so only it can
+    /// be resent without risking a second, billed completion
+    pub fn is_idempotent(self) -> bool {
+        matches!(self, Rpc::StreamUnifiedChatWithToolsIdempotent)
+    }
+}
+
+/// How often and how patiently to retry a call that failed transiently
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub multiplier: f64,
+    /// Fraction of each delay that is randomised, from 0.0 to 1.0
+    pub jitter: f64,
+    /// Source of the jitter, returning values in 0.0..1.0
+    pub random: fn() -> f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(8),
+            multiplier: 2.0,
+            jitter: 0.2,
+            random: random_unit,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Status codes that mean the call didn't go through and may succeed later
+    pub fn is_retryable(code: Code) -> bool {
+        matches!(
+            code,
+            Code::Unavailable | Code::DeadlineExceeded | Code::ResourceExhausted | Code::Aborted
+        )
+    }
+
+    /// Delay before retry number `retry` (starting at 1), with jitter applied
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let exponent = retry.saturating_sub(1).min(31) as i32;
+        let base = self.initial_backoff.as_secs_f64() * self.multiplier.powi(exponent);
+        let base = base.min(self.max_backoff.as_secs_f64());
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        // -1.0..1.0, good enough to keep clients from retrying in lockstep
+        let spread = (self.random)() * 2.0 - 1.0;
+        Duration::from_secs_f64((base * (1.0 + jitter * spread)).max(0.0))
+    }
+}
+
+/// What the server asked for, via `grpc-retry-pushback-ms` or `retry-after`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryHint {
+    After(Duration),
+    /// The server asked not to retry at all
+    DontRetry,
+}
+
+pub fn retry_hint(status: &Status) -> Option<RetryHint> {
+    let metadata = status.metadata();
+    if let Some(value) = metadata.get("grpc-retry-pushback-ms") {
+        // Per the gRPC spec, a negative or malformed pushback means stop
+        return Some(match value.to_str().ok().and_then(|v| v.trim().parse::<i64>().ok()) {
+            Some(ms) if ms >= 0 => RetryHint::After(Duration::from_millis(ms as u64)),
+            _ => RetryHint::DontRetry,
+        });
+    }
+    let seconds = metadata.get("retry-after")?.to_str().ok()?.trim().parse::<u64>().ok()?;
+    Some(RetryHint::After(Duration::from_secs(seconds)))
+}
+
+/// Run `call`, retrying transient failures under `policy`.
+///
+/// Retries are opt-in: with no policy, or for an RPC that isn't idempotent,
+/// the call is made exactly once.
+pub async fn with_retry<T, F, Fut>(rpc: Rpc, policy: Option<&RetryPolicy>, mut call: F) -> Result<T, Status>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, Status>>,
+{
+    let policy = match policy {
+        Some(policy) if rpc.is_idempotent() => policy,
+        Some(_) => {
+            warn!("{:?} is not idempotent, not retrying it", rpc);
+            return call().await;
+        }
+        None => return call().await,
+    };
+
+    let mut attempt = 1;
+    loop {
+        let status = match call().await {
+            Ok(value) => return Ok(value),
+            Err(status) => status,
+        };
+        if attempt >= policy.max_attempts || !RetryPolicy::is_retryable(status.code()) {
+            return Err(status);
+        }
+        let delay = match retry_hint(&status) {
+            Some(RetryHint::DontRetry) => return Err(status),
+            Some(RetryHint::After(delay)) => delay,
+            None => policy.backoff(attempt),
+        };
+        info!(
+            "{:?} failed ({:?}), retrying in {:?} (attempt {}/{})",
+            rpc,
+            status.code(),
+            delay,
+            attempt + 1,
+            policy.max_attempts
+        );
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// Cheap pseudo-random value in 0.0..1.0, seeded from the clock
+fn random_unit() -> f64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    // xorshift to spread the low-entropy nanosecond bits
+    let mut x = nanos as u64 | 1;
+    x ^= x << 13;
+    x ^= x >> 7;
+    x ^= x << 17;
+    (x % 1_000_000) as f64 / 1_000_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tonic::metadata::MetadataMap;
+
+    fn policy(random: fn() -> f64) -> RetryPolicy {
+        RetryPolicy {
+            random,
+            ..RetryPolicy::default()
+        }
+    }
+
+    fn status_with(key: &'static str, value: &str) -> Status {
+        let mut metadata = MetadataMap::new();
+        metadata.insert(key, value.parse().unwrap());
+        Status::with_metadata(Code::Unavailable, "busy", metadata)
+    }
+
+    #[test]
+    fn test_backoff_bounds() {
+        // Without jitter: 250ms doubling per retry
+        let none = RetryPolicy {
+            jitter: 0.0,
+            ..RetryPolicy::default()
+        };
+        assert_eq!(none.backoff(1), Duration::from_millis(250));
+        assert_eq!(none.backoff(2), Duration::from_millis(500));
+        assert_eq!(none.backoff(3), Duration::from_secs(1));
+
+        // Jitter spreads each delay by at most ±20%
+        let low = policy(|| 0.0);
+        let high = policy(|| 0.999_999);
+        assert_eq!(low.backoff(2), Duration::from_millis(400));
+        assert!(high.backoff(2) > Duration::from_millis(599));
+        assert!(high.backoff(2) <= Duration::from_millis(600));
+        assert_eq!(policy(|| 0.5).backoff(2), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_backoff_cap() {
+        let capped = policy(|| 0.5);
+        assert_eq!(capped.backoff(10), Duration::from_secs(8));
+        // Huge retry counts neither overflow nor exceed the cap
+        assert_eq!(capped.backoff(u32::MAX), Duration::from_secs(8));
+        assert!(policy(|| 0.999_999).backoff(100) <= Duration::from_secs_f64(8.0 * 1.2));
+    }
+
+    #[test]
+    fn test_retry_hint() {
+        assert_eq!(
+            retry_hint(&status_with("grpc-retry-pushback-ms", "1500")),
+            Some(RetryHint::After(Duration::from_millis(1500)))
+        );
+        assert_eq!(
+            retry_hint(&status_with("grpc-retry-pushback-ms", "-1")),
+            Some(RetryHint::DontRetry)
+        );
+        assert_eq!(
+            retry_hint(&status_with("grpc-retry-pushback-ms", "soon")),
+            Some(RetryHint::DontRetry)
+        );
+        assert_eq!(
+            retry_hint(&status_with("retry-after", " 3 ")),
+            Some(RetryHint::After(Duration::from_secs(3)))
+        );
+        // HTTP-date retry-after isn't understood and is ignored
+        assert_eq!(retry_hint(&status_with("retry-after", "Wed, 21 Oct 2026 07:28:00 GMT")), None);
+        assert_eq!(retry_hint(&Status::unavailable("busy")), None);
+    }
+
+    #[tokio::test]
+    async fn test_with_retry_stops_on_non_retryable() {
+        let fast = RetryPolicy {
+            initial_backoff: Duration::ZERO,
+            ..RetryPolicy::default()
+        };
+        let idempotent = Rpc::StreamUnifiedChatWithToolsIdempotent;
+
+        let mut calls = 0;
+        let result: Result<(), Status> = with_retry(idempotent, Some(&fast), || {
+            calls += 1;
+            async { Err(Status::invalid_argument("bad request")) }
+        })
+        .await;
+        assert_eq!(result.unwrap_err().code(), Code::InvalidArgument);
+        assert_eq!(calls, 1);
+
+        // Retryable code, but the RPC isn't idempotent
+        let mut calls = 0;
+        let result: Result<(), Status> = with_retry(Rpc::StreamUnifiedChat, Some(&fast), || {
+            calls += 1;
+            async { Err(Status::unavailable("busy")) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+
+        // The server asked not to retry
+        let mut calls = 0;
+        let result: Result<(), Status> = with_retry(idempotent, Some(&fast), || {
+            calls += 1;
+            async { Err(status_with("grpc-retry-pushback-ms", "-1")) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+
+        // Transient failures are retried up to max_attempts
+        let mut calls = 0;
+        let result: Result<(), Status> = with_retry(idempotent, Some(&fast), || {
+            calls += 1;
+            async { Err(Status::unavailable("busy")) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls, fast.max_attempts);
+    }
+}
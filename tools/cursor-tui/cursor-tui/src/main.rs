@@ -1,5 +1,5 @@
 use anyhow::Result;
-use cursor_core::auth::{extract_auth_from_db, get_auth_db_path};
+use cursor_core::auth::{get_auth_db_path, load_auth, ReloginRequired};
 use cursor_core::client::Client;
 use tracing::{info, error};
 
@@ -13,7 +13,7 @@ async fn main() -> Result<()> {
     let db_path = get_auth_db_path();
     println!("Reading auth from: {:?}", db_path);
 
-    match extract_auth_from_db(&db_path) {
+    match load_auth() {
         Ok(auth) => {
             let masked_token = if auth.access_token.len() > 10 {
                 format!("{}...", &auth.access_token[..10])
@@ -37,6 +37,10 @@ async fn main() -> Result<()> {
                 }
             }
         }
+        Err(e) if e.is::<ReloginRequired>() => {
+            println!("{:#}", e);
+            println!("Open Cursor, sign in, then run cursor-tui again.");
+        }
         Err(e) => {
             println!("Failed to extract auth: {:?}", e);
             if !db_path.exists() {